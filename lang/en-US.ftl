@@ -9,22 +9,129 @@ overall = Overall
 status = Status
 
 cli-unrecognized-games = No info for these games:
+cli-did-you-mean = Did you mean: {$games}?
+cli-ambiguous-store-id = {$id} matches multiple games:
+store-id = {$store ->
+    [steam] Steam ID
+    [gog] GOG ID
+    *[lutris] Lutris ID
+} {$id}
 cli-unable-to-request-confirmation = Unable to request confirmation.
     .winpty-workaround = If you are using a Bash emulator (like Git Bash), try running winpty.
 cli-backup-id-with-multiple-games = Cannot specify backup ID when restoring multiple games.
 cli-invalid-backup-id = Invalid backup ID.
+cli-available-backups = Available backups:
+cli-restore-needs-confirmation = Restoring {$total-games} games needs confirmation, but it could not be requested. To proceed anyway, add --yes.
+cli-unable-to-serve = Unable to listen on {$address}: {$why}
+cli-unable-to-start-daemon = Unable to start the daemon: {$why}
+cli-unable-to-watch = Unable to watch for changes: {$why}
+cli-unable-to-read-game-list = Unable to read the list of games from {$path}: {$message}
+cli-invalid-glob = Invalid glob pattern: {$why}
+cli-root-already-configured = This root is already configured: {$path}
+cli-root-does-not-exist = This folder does not exist: {$path}. Use --force to add it anyway.
+cli-unrecognized-root = No configured root matches: {$root}
+cli-custom-game-already-exists = There's already a custom game with this name: {$name}
+cli-custom-game-in-manifest = The manifest already has a game with this name: {$name}. Use --override to replace it with your custom game.
+cli-unknown-placeholders = Unknown placeholders in {$path}: {$placeholders}
+cli-unable-to-import-config = Unable to import {$path}: {$message}
+cli-watching = Watching {$locations} save {$locations ->
+    [one] location
+    *[other] locations
+} for {$games} {$games ->
+    [one] game
+    *[other] games
+}. Press Ctrl+C to stop.
+daemon-unavailable = Unable to communicate with the daemon: {$why}
+app-update-unavailable = Unable to check for a new version of Ludusavi: {$why}
 
 badge-failed = FAILED
 badge-duplicates = DUPLICATES
 badge-duplicated = DUPLICATED
 badge-ignored = IGNORED
+badge-forced = FORCED
+badge-deferred = DEFERRED
+badge-only-enabled-games = ONLY ENABLED GAMES
 badge-redirected-from = FROM: {$path}
 badge-redirecting-to = TO: {$path}
 
 some-entries-failed = Some entries failed to process; look for {badge-failed} in the output for details. Double check whether you can access those files or whether their paths are very long.
+changes-pending = The preview found changes that have not been applied yet.
+nothing-changed = No games have changed since their last backup.
 
 cli-game-line-item-redirected = Redirected from: {$path}
 cli-game-line-item-redirecting = Redirecting to: {$path}
+cli-game-line-item-redirect-rule = Redirect rule #{$number}: {$source} → {$target}
+cli-game-line-item-junk = Ignored by the built-in junk filter
+cli-game-line-item-stored-as = The backup location renamed this file to: {$path}
+cli-game-line-item-changed-during-backup = Changed while being backed up, so this backup may not be consistent
+cli-game-line-item-ambiguous-target = Skipped because it could belong to any of these accounts (use --prefer-account to choose): {$candidates}
+cli-game-line-item-unsafe-zip-entry = Rejected because the backup archive may be malicious: {$message}
+cli-game-line-item-os-constrained = Skipped because it was backed up on a different operating system (use a redirect to restore it here)
+cli-game-line-item-newer-local = The local file is newer than this backup, so restoring would overwrite it (use --no-clobber-newer to keep it)
+cli-game-line-item-kept-newer-local = Skipped because the local file is newer than this backup
+cli-game-line-item-migrated-from = Compared with the previous backup at its old location: {$path}
+cli-game-line-item-wine-user-variant = Matched with the same file in another Wine user folder: {$path}
+cli-game-line-item-folder-summary = {$path} ({$total} {$total ->
+    [one] file
+    *[other] files
+}, {$size})
+failure-changed-while-copying = The file kept changing while it was being copied
+failure-locked = Locked by another process: {$message}
+games-deferred = {$total} {$total ->
+    [one] game was
+    *[other] games were
+} deferred because {$total ->
+    [one] its files were
+    *[other] their files were
+} locked by another process. Try again after closing {$total ->
+    [one] it
+    *[other] them
+}.
+failure-missing-from-backup = The file is missing from the backup
+failure-missing-backup = The backup folder or archive is missing or could not be opened
+failure-hash-mismatch = The file's content does not match the hash that was recorded when it was backed up
+failure-unreadable-archive = The backup archive could not be read: {$message}
+failure-safety-snapshot = Not restored because the current files could not be backed up first
+safety-snapshot-comment = Safety snapshot before restoring
+safety-snapshot-unknown-game = The game is not in the manifest, so its current files could not be found
+safety-snapshot-io-timeout = Some of the game's save locations did not respond in time
+cli-backup-size = {$size}, {$total} {$total ->
+    [one] file
+    *[other] files
+}
+cli-game-merged-titles = Merged from: {$games}
+cli-game-took = (took {$seconds}s)
+cli-game-no-recent-activity = Skipped because its save locations have not changed recently
+cli-game-unchanged = Skipped because its saves have not changed since the last backup
+cli-game-inconsistent = Skipped because its existing backups do not match their mapping file
+cli-game-promoted-to-full = Made a full backup because the differential backups would hold {$size} (limit: {$limit})
+cli-game-backup-comment = Comment on the new backup: {$comment}
+cli-game-backup-locked = The new backup is locked
+cli-game-safety-snapshot = Backed up the current files before restoring: {$backup}
+cli-game-safety-snapshot-failed = Unable to back up the current files before restoring: {$message}
+cli-game-registry-skipped = Registry skipped because registry access is disabled for this game.
+cli-game-unresolved-base-dir = Install folder not found: {$path}
+cli-game-os-constrained = Skipped {$total} {$total ->
+    [one] file
+    *[other] files
+} from a different operating system.
+cli-game-io-timeout = Skipped because it did not respond in time: {$path}
+cli-no-failures = No games failed.
+cli-summary-ignored = Ignored
+cli-summary-ignored-entries = {$files} {$files ->
+    [one] file
+    *[other] files
+} ({$size}), {$keys} {$keys ->
+    [one] registry key
+    *[other] registry keys
+}
+cli-markdown-path = Path
+cli-markdown-value = Value
+cli-markdown-change = Change
+cli-markdown-status = Status
+cli-markdown-registry = Registry
+cli-cloud-header = Cloud
+cli-cloud-header-preview = Cloud (preview)
 
 button-backup = Back up
 button-preview = Preview
@@ -47,6 +154,8 @@ button-exit = Exit
 button-comment = Comment
 button-lock = Lock
 button-unlock = Unlock
+button-enable-registry = Enable registry
+button-disable-registry = Disable registry
 # This opens a download page.
 button-get-app = Get {$app}
 button-validate = Validate
@@ -54,17 +163,70 @@ button-validate = Validate
 no-roots-are-configured = Add some roots to back up even more data.
 
 config-is-invalid = Error: The config file is invalid.
+config-cannot-be-saved = Error: Unable to save the config file.
 manifest-is-invalid = Error: The manifest file is invalid.
 manifest-cannot-be-updated = Error: Unable to check for an update to the manifest file. Is your Internet connection down?
 cannot-prepare-backup-target = Error: Unable to prepare backup target (either creating or emptying the folder). If you have the folder open in your file browser, try closing it: {$path}
+unable-to-write-report = Error: Unable to write the report to {$path}, so it was printed instead: {$message}
 restoration-source-is-invalid = Error: The restoration source is invalid (either doesn't exist or isn't a directory). Please double check the location: {$path}
 registry-issue = Error: Some registry entries were skipped.
+registry-hive-unsupported = Error: Restoring into a registry hive file is only supported on Windows.
+registry-hive-needs-elevation = Error: Restoring into a registry hive file requires running Ludusavi as an administrator.
+unable-to-load-registry-hive = Error: Unable to load the registry hive file ({$why}): {$path}
 unable-to-browse-file-system = Error: Unable to browse on your system.
 unable-to-open-directory = Error: Unable to open directory:
 unable-to-open-url = Error: Unable to open URL:
 unable-to-configure-cloud = Unable to configure cloud.
 unable-to-synchronize-with-cloud = Unable to synchronize with cloud.
 cloud-synchronize-conflict = Your local and cloud backups are in conflict. Perform an upload or download to resolve this.
+cli-cloud-conflict-paths = {$total} {$total ->
+    [one] path differs:
+    *[other] paths differ:
+}
+cli-cloud-conflict-more = ...and {$total} more
+cloud-sync-deferred-on-metered = Skipped cloud synchronization because the network connection is metered. It will happen after a later backup.
+cli-cloud-sync-deferred-on-metered = Skipped cloud synchronization because the network connection is metered. It will happen after a later backup, or you can use --allow-metered.
+backup-inconsistent = Skipped {$game} because its backups don't match its mapping file. Use --repair to update the mapping.
+backup-inconsistent-repaired = Repaired the mapping file for {$game} because it didn't match its backups.
+unresolved-base-dirs = {$total-games} {$total-games ->
+    [one] game had
+    *[other] games had
+} an install folder that no longer exists, so their saves may not have been found.
+io-timeout = These locations did not respond in time, so they were skipped. Any games with saves there were not backed up:
+manifest-fallback = The manifest file could not be read, so an older copy was used instead. Recently added games may be missing. You can download it again with: ludusavi manifest update --repair
+manifest-fallback-age = The older copy was downloaded {$hours} hours ago.
+game-running = Skipped restoring {$game} because it seems to be running ({$process}). Use --force to restore anyway.
+game-running-for-backup = Skipped backing up {$game} because it appears to be running ({$process}). Try again after closing it.
+running-check-unavailable = Unable to list running processes on this system, so games were not checked for whether they were running.
+backup-missing-backup = Missing backup: {$path}
+backup-created-by-version = Created by Ludusavi v{$version}
+backup-manifest-hash = Manifest: {$hash}
+backup-newer-version = Created by a newer version of Ludusavi (v{$version}), so it may not restore correctly
+backup-missing-file = Missing file in backup {$name}: {$path}
+backup-unknown-file = Unknown file: {$path}
+
+unable-to-adopt-backup = Unable to adopt backup: {$path}
+adoption-source-missing = The path is neither a folder nor a zip file.
+adoption-destination-occupied = There is already a backup folder for this game: {$path}
+adoption-unreadable-mapping = The folder's mapping.yaml could not be read: {$message}
+adoption-no-backups = The folder's mapping.yaml does not list any backups.
+adoption-missing-backup = The folder's mapping.yaml lists a backup that is not in the folder: {$path}
+adoption-no-drive-folders = No backed up files were found. Expected at least one folder like drive-C or drive-0.
+adoption-unknown-drive-folder = Unable to tell which drive this folder belongs to without a mapping.yaml: {$path}
+adoption-unreadable-zip = The zip file could not be read: {$message}
+adoption-unable-to-move = Unable to move the backup into place: {$message}
+adoption-unsafe-zip-entry = The zip file may be malicious: {$message}
+unable-to-delete-backup = Unable to delete backup: {$path}
+deletion-not-found = There is no backup with that name or ID.
+deletion-has-differentials = This full backup has {$total} differential backup(s) that depend on it. Use --cascade to delete them too.
+deletion-locked = The backup is locked. Use --force to delete it anyway.
+unsafe-zip-entry-absolute-path = The entry has an absolute path: {$path}
+unsafe-zip-entry-escapes-root = The entry leads outside of the archive: {$path}
+unsafe-zip-entry-symlink = The entry is a link to somewhere outside of the archive: {$path} → {$target}
+unsafe-zip-entry-compression-ratio = The entry expands {$ratio} times its compressed size (limit: {$limit}): {$path}
+unsafe-zip-entry-total-compression-ratio = The archive expands {$ratio} times its compressed size by this entry (limit: {$limit}): {$path}
+unsafe-zip-entry-too-large = The archive expands to {$size} by this entry (limit: {$limit}): {$path}
+unsafe-zip-entry-oversized = The entry expands beyond its declared size of {$size}: {$path}
 
 command-unlaunched = Command did not launch: {$command}
 command-terminated = Command terminated abruptly: {$command}
@@ -102,6 +264,16 @@ field-backup-format = Format:
 field-backup-compression = Compression:
 # The compression level determines how much compresison we perform.
 field-backup-compression-level = Level:
+field-backup-mode = Mode:
+
+backup-mode-all-games-except-disabled = All games except disabled ones
+backup-mode-only-enabled-list = Only enabled games
+backup-mode-only-enabled-list-tooltip = Games are only scanned and backed up if you enable them.
+cli-summary-backup-mode = Mode: {$mode} ({$total} {$total ->
+    [one] game
+    *[other] games
+} skipped)
+button-enable-for-backup = Enable for backup
 
 label-manifest = Manifest
 # This shows the time when we checked for an update to the manifest.
@@ -140,6 +312,8 @@ label-password = Password
 # This is a specific website or service that provides some cloud functionality.
 # For example, Nextcloud and Owncloud are providers of WebDAV services.
 label-provider = Provider
+# Checkbox when confirming a backup. Locked backups are not deleted by the retention limits.
+label-lock-new-backups = Lock the new backups
 label-custom = Custom
 label-none = None
 label-change-count = Changes: {$total}
@@ -187,8 +361,13 @@ redirect-bidirectional = Bidirectional
 show-deselected-games = Show deselected games
 show-unchanged-games = Show unchanged games
 show-unscanned-games = Show unscanned games
+registry-enabled = Back up and restore the Windows registry
 override-max-threads = Override max threads
 synchronize-automatically = Synchronize automatically
+label-on-metered-connection = On a metered connection
+on-metered-proceed = Synchronize anyway
+on-metered-skip = Wait
+on-metered-ask = Ask
 
 explanation-for-exclude-store-screenshots =
     In backups, exclude store-specific screenshots
@@ -207,6 +386,22 @@ confirm-restore =
     Are you sure you want to proceed with the restoration?
     This will overwrite any current files with the backups from here:
 
+restore-many-games-summary = This will restore {$total-games} games ({$size}) without any games having been specified.
+
+confirm-remap-user-profile =
+    Some backups were made in a different user's profile folder.
+    Do you want to restore them into your current profile folder instead?
+
+choose-account-for-restore =
+    Some backups were made for a Steam account that isn't on this system,
+    so they were skipped because there are several accounts they could belong to.
+    Choose an account to use for future restores:
+
+confirm-metered-cloud-sync =
+    Your network connection is metered.
+    Do you want to synchronize with the cloud now?
+    Otherwise, it will happen after a later backup on an unmetered connection.
+
 confirm-cloud-upload =
     Do you want to replace your cloud files with your local files?
     Your cloud files ({$cloud-path}) will become an exact copy of your local files ({$local-path}).
@@ -223,6 +418,99 @@ loading = Loading...
 preparing-backup-target = Preparing backup directory...
 updating-manifest = Updating manifest...
 no-cloud-changes = No changes to synchronize
+cloud-upload-resumed-at = resumed at {$percent}%
+cloud-chunked-upload-failed = Unable to upload {$path}: {$message}. The next upload will resume from the last complete chunk.
+no-config-changes = The config file is already up to date
+no-redundant-roots = No redundant roots were found
+redundant-root-same = same location as: {$path}
+redundant-root-nested = inside of: {$path}
+redundant-roots-skipped = These roots were skipped because another root already covers them. You can remove them with: ludusavi roots dedupe --apply
+no-dangling-titles = All aliases and merged titles refer to known games
+dangling-custom-alias = {$title} (alias for custom game: {$name})
+dangling-merged-title = {$title} (merged into: {$name})
+dangling-titles-found = {$total} {$total ->
+    [one] alias or merged title no longer refers
+    *[other] aliases or merged titles no longer refer
+} to a known game. Run `ludusavi config validate` for details.
+no-cloud-history = No cloud syncs have been recorded
+no-custom-games = No custom games are configured
+custom-game-alias = alias for: {$name}
+custom-game-overrides-manifest = replaces the manifest entry
+no-imported-entries = The file doesn't contain any custom games, toggled paths or registry entries, or redirects
+import-section = {$section ->
+    [custom-game] Custom game
+    [backup-path] Backup path toggle
+    [backup-registry] Backup registry toggle
+    [restore-path] Restore path toggle
+    [restore-registry] Restore registry toggle
+    *[redirect] Redirect
+}
+import-skipped = kept the existing entry; use --replace to overwrite it
+import-summary = Added: {$added}, updated: {$updated}, unchanged: {$unchanged}, skipped: {$skipped}
+no-duplicate-content = No identical files were found across different games
+duplicate-content-group = {$hash} ({$size} each, {$savings} could be saved)
+duplicate-content-total = Total potential savings: {$savings}
+cli-deleted-backups = {$preview ->
+    [true] Would delete
+    *[false] Deleted
+} {$total} {$total ->
+    [one] backup
+    *[other] backups
+}, reclaiming {$size}
+no-size-history = No backups were found
+no-backup-differences = No differences were found
+storage-line = {$size} in {$full} full and {$differential} differential {$total ->
+    [one] backup
+    *[other] backups
+} (latest: {$latest-size} at {$when})
+storage-missing-files = {$total} {$total ->
+    [one] file is
+    *[other] files are
+} missing from disk
+storage-total = Total: {$size} in {$total} {$total ->
+    [one] backup
+    *[other] backups
+} for {$games} {$games ->
+    [one] game
+    *[other] games
+}
+backup-diff-header = Comparing {$old} to {$new}
+backup-diff-current = current data
+backup-diff-registry = Registry:
+fuzzy-match = score: {$score}{$source ->
+    [alias] , alias: {$name}
+    [custom-game] , custom game
+    [install-dir] , install folder: {$name}
+    *[title] {""}
+}
+size-history-line = {$when} ({$kind ->
+    [full] full
+    *[differential] differential
+}): {$size} in {$files} {$files ->
+    [one] file
+    *[other] files
+}
+no-run-history = No operations have been recorded
+run-history-line = {$when} {$operation ->
+    [backup] backup
+    [restore] restore
+    [cloudUpload] cloud upload
+    *[cloudDownload] cloud download
+}: {$total} {$unit ->
+    [games] {$total ->
+        [one] game
+        *[other] games
+    }
+    *[files] {$total ->
+        [one] file
+        *[other] files
+    }
+}, {$size}, {$failures} failed ({$seconds} s)
+cloud-history-total = Total
+cloud-history-line = {$period}: {$uploaded} uploaded, {$downloaded} downloaded, {$runs} {$runs ->
+    [one] sync
+    *[other] syncs
+} ({$failed} failed)
 backups-are-valid =
     Your backups are valid.
 backups-are-invalid =
@@ -0,0 +1,684 @@
+//! Entry points for embedding Ludusavi's scan/backup engine in another program.
+//!
+//! Everything defined or re-exported in this module follows semantic versioning.
+//! The bundled CLI and GUI are built on the same functions.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+pub use crate::{
+    cli::report::JsonOutput,
+    cloud::{Remote, WebDavProvider},
+    lang::Language,
+    path::StrictPath,
+    prelude::{CommandError, Error, Finality},
+    resource::{
+        config::{
+            App, Apps, BackupConfig, BackupFilter, BackupFormat, BackupFormats, BackupMode, ChunkedUpload, Cloud,
+            Compression, Config, CustomGame, History, ManifestConfig, MergedGame, OnMetered, PathMigrationConfig,
+            PreferWineUser, RedirectConfig, RedirectKind, RemapUserProfile, RestoreConfig, Retention, RootsConfig,
+            Runtime, Scan, SecondaryManifestConfig, SizeThreshold, Sort, SortKey, Theme, ToggledPaths, ToggledRegistry,
+            UnicodeNormalization, ZipCompression, ZipConfig,
+        },
+        manifest::{
+            Game, GameFileConstraint, GameFileEntry, GameInstallDirEntry, GameRegistryConstraint, GameRegistryEntry,
+            GameSource, GogMetadata, IdMetadata, Manifest, ManifestFallback, Os, PathMigration, SteamMetadata, Store,
+            StoreId, Tag,
+        },
+    },
+    scan::{
+        layout::{
+            AdoptionProblem, Backup, BackupKind, BackupLayout, DeletionProblem, DifferentialBackup, FullBackup,
+            GameLayout, IndividualMappingFile, IndividualMappingRegistry, PromotionReason,
+        },
+        registry_compat::RegistryItem,
+        running::{ProcessSnapshot, RunningProcess},
+        zip_safety::UnsafeZipEntry,
+        BackupId, BackupInfo, BackupLabel, DuplicateDetector, LocalState, OperationStatus, OperationStepDecision,
+        PathOverrides, RedirectRule, ScanChange, ScanChangeCount, ScanInfo, ScannedFile, ScannedRegistry,
+        ScannedRegistryValue, ScannedRegistryValues, Trigger, TriggerSource, UnknownGame,
+    },
+};
+use crate::{
+    cli::report::Reporter,
+    prelude::app_dir,
+    scan::{
+        game_has_recent_activity, game_save_locations, scan_game_for_backup, IoWatchdog, Launchers, OsConstraints,
+        SteamShortcuts, TitleFinder,
+    },
+};
+
+/// Loads the locally cached manifest and merges in the custom games and
+/// secondary manifests from the config. This does not download anything.
+/// The manifest's hash is recorded in the config for new backups.
+pub fn load_manifest(config: &mut Config) -> Result<Manifest, Error> {
+    let (mut manifest, hash, fallback) = Manifest::load_with_hash()?;
+    config.manifest.loaded_hash = hash;
+    config.manifest.loaded_fallback = fallback;
+    manifest.incorporate_extensions(config);
+    Ok(manifest)
+}
+
+/// Loads the config from Ludusavi's usual location.
+pub fn load_config() -> Result<Config, Error> {
+    Config::load()
+}
+
+/// The roots to scan, along with the launcher and shortcut data found in them.
+/// Building this is relatively expensive, so reuse it across games.
+#[derive(Clone, Debug)]
+pub struct Roots {
+    roots: Vec<RootsConfig>,
+    launchers: Launchers,
+    steam_shortcuts: SteamShortcuts,
+    wine_prefix: Option<StrictPath>,
+    watchdog: Arc<IoWatchdog>,
+}
+
+impl Roots {
+    /// Inspect the configured roots for the given games.
+    /// If `games` is empty, every game in the manifest is considered.
+    pub fn new(manifest: &Manifest, config: &Config, layout: &BackupLayout, games: &[String]) -> Self {
+        let roots = config.expanded_roots();
+        let watchdog = IoWatchdog::new(config.scan.io_timeout.map(std::time::Duration::from_secs));
+        // Unresponsive roots are still kept, so that the games inside of them can report the timeout.
+        let responsive_roots: Vec<_> = roots
+            .iter()
+            .filter(|root| watchdog.responds(&root.path, &root.path))
+            .cloned()
+            .collect();
+        let title_finder = TitleFinder::new(manifest, layout);
+        let subjects: Vec<_> = if games.is_empty() {
+            manifest.0.keys().cloned().collect()
+        } else {
+            games.to_vec()
+        };
+        let launchers = Launchers::scan(&responsive_roots, manifest, &subjects, &title_finder, None);
+
+        Self {
+            roots,
+            launchers,
+            steam_shortcuts: SteamShortcuts::scan(),
+            wine_prefix: None,
+            watchdog: Arc::new(watchdog),
+        }
+    }
+
+    /// Games that a launcher or store root reports as installed.
+    pub fn installed_games(&self) -> BTreeSet<String> {
+        self.launchers.installed_games()
+    }
+
+    /// Where a launcher or store root reports the game to be installed.
+    pub fn install_dirs(&self, game: &str) -> Vec<StrictPath> {
+        self.launchers.install_dirs(game)
+    }
+
+    /// Every location that didn't respond within `scan.ioTimeout` so far.
+    pub fn io_timed_out_paths(&self) -> Vec<StrictPath> {
+        self.watchdog.timed_out_paths()
+    }
+
+    /// Also scan this Wine prefix, in addition to the configured roots.
+    pub fn with_wine_prefix(mut self, wine_prefix: Option<StrictPath>) -> Self {
+        self.wine_prefix = wine_prefix;
+        self
+    }
+}
+
+/// Shared flag to stop an operation early.
+/// Clones refer to the same flag, so one can be handed to another thread.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clear the flag so that the token can be used for another operation.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Reported by `back_up_games` and `restore_games` as they go.
+/// Games are processed in parallel, so the events for different games may be interleaved.
+#[derive(Clone, Copy, Debug)]
+pub enum Progress<'a> {
+    /// Work on a game is starting. The `index` starts at 1, so it reaches `max` with the last game.
+    Started { game: &'a str, index: usize, max: usize },
+    /// Work on a game is done, and `current` of the `max` games have been handled so far.
+    Finished {
+        outcome: &'a Outcome,
+        current: usize,
+        max: usize,
+    },
+}
+
+/// Result of processing one game.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub game: String,
+    pub scan_info: ScanInfo,
+    /// This is only set if the game was actually processed (i.e., not in preview mode).
+    /// For a restore, it describes the files and registry data that were put back.
+    pub backup_info: Option<BackupInfo>,
+    pub decision: OperationStepDecision,
+    /// Why the game wasn't backed up, if it otherwise would have been.
+    /// This is never set by `restore_games`.
+    pub skipped: Option<Skip>,
+    /// This is not set if the game was skipped without a scan.
+    pub duration: Option<Duration>,
+}
+
+/// Why `back_up_games` left out a game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Skip {
+    /// There hasn't been any recent activity (see `BackupOptions::if_changed_since`),
+    /// so the game wasn't scanned.
+    Inactive,
+    /// Nothing changed since the latest backup (see `BackupOptions::if_changed`).
+    Unchanged,
+    /// The existing backups need to be repaired first (see `BackupOptions::inconsistent`).
+    Inconsistent,
+    /// The game seems to be running (see `BackupOptions::running`).
+    Running(RunningProcess),
+}
+
+/// Choices for `back_up_games` beyond what's in the config.
+/// The defaults back up the games that are enabled in the config.
+#[derive(Clone, Debug, Default)]
+pub struct BackupOptions {
+    /// Back up games even if they're disabled in the config, like when they're requested by name.
+    pub include_disabled: bool,
+    /// Always back up these games as a new full backup, even if they'd be skipped otherwise.
+    pub forced: HashSet<String>,
+    /// Use this instead of `backup.format` from the config.
+    pub format: Option<BackupFormats>,
+    /// Apply this to new backups instead of a label that only records `TriggerSource::Api`.
+    pub label: Option<BackupLabel>,
+    /// Leave out matching files, on top of the config.
+    pub overrides: PathOverrides,
+    /// Skip games whose saves haven't changed since their latest backup.
+    pub if_changed: bool,
+    /// Skip games without any activity since this time, checking this many levels deep (see `has_recent_activity`).
+    pub if_changed_since: Option<(chrono::DateTime<chrono::Utc>, usize)>,
+    /// Skip these games, such as when `GameLayout::check_consistency` found a problem with their backups.
+    pub inconsistent: HashSet<String>,
+    /// Skip games that seem to be running according to this snapshot.
+    pub running: Option<ProcessSnapshot>,
+}
+
+/// Choices for `restore_games` beyond what's in the config.
+/// The defaults restore the latest backup of each game that is enabled in the config.
+#[derive(Clone, Debug, Default)]
+pub struct RestoreOptions {
+    /// Restore games even if they're disabled in the config, like when they're requested by name.
+    pub include_disabled: bool,
+    /// Restore this backup instead of the latest one.
+    /// This only makes sense when restoring a single game.
+    pub backup: Option<BackupId>,
+    /// Leave out matching files, on top of the config.
+    pub overrides: PathOverrides,
+}
+
+/// Find the save data for a single game.
+/// Returns `None` if the game is not in the manifest.
+pub fn scan_game(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &Roots,
+    layout: &BackupLayout,
+    name: &str,
+) -> Option<ScanInfo> {
+    let mut game = manifest.0.get(name)?.clone();
+    let previous = layout.latest_backup(name, false, &config.redirects, &config.restore.toggled_paths);
+
+    let registry_skipped = !config.is_registry_enabled(name)
+        && cfg!(target_os = "windows")
+        && game.registry.as_ref().is_some_and(|x| !x.is_empty());
+    if !config.is_registry_enabled(name) {
+        game.registry = None;
+    }
+
+    let mut scan_info = scan_game_for_backup(
+        &game,
+        name,
+        &roots.roots,
+        &StrictPath::from_std_path_buf(&app_dir()),
+        &roots.launchers,
+        &config.backup.filter,
+        &roots.wine_prefix,
+        &config.backup.toggled_paths,
+        &config.backup.toggled_registry,
+        previous,
+        &config.redirects,
+        &roots.steam_shortcuts,
+        config.scan.unicode_normalization,
+        &roots.watchdog,
+    );
+    scan_info.registry_skipped = registry_skipped;
+    if config.scan.exclude_common_junk {
+        scan_info.apply_junk_filter(&config.backup.toggled_paths);
+    }
+    Some(scan_info)
+}
+
+/// Cheaply check whether a game's saves may have changed since `since`
+/// (or since its latest backup, if that's earlier), based on file modification times,
+/// looking at most `depth` levels into each save folder.
+/// Games without any backups yet, or with registry data to check, always count as active.
+pub fn has_recent_activity(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &Roots,
+    layout: &BackupLayout,
+    name: &str,
+    since: chrono::DateTime<chrono::Utc>,
+    depth: usize,
+) -> bool {
+    let Some(game) = manifest.0.get(name) else {
+        return true;
+    };
+    if cfg!(target_os = "windows")
+        && config.is_registry_enabled(name)
+        && game.registry.as_ref().is_some_and(|x| !x.is_empty())
+    {
+        return true;
+    }
+    let Some(latest) = layout.try_game_layout(name).and_then(|x| x.latest_backup_time()) else {
+        return true;
+    };
+
+    game_has_recent_activity(
+        game,
+        name,
+        &roots.roots,
+        &StrictPath::from_std_path_buf(&app_dir()),
+        &roots.launchers,
+        &config.backup.filter,
+        &roots.wine_prefix,
+        &roots.steam_shortcuts,
+        since.min(latest).into(),
+        depth,
+    )
+}
+
+/// The folders (or individual files) where a game's saves may be, without any glob patterns,
+/// so that they can be watched for changes.
+/// Some of them may not exist yet, and registry data is not included.
+pub fn save_locations(manifest: &Manifest, config: &Config, roots: &Roots, name: &str) -> BTreeSet<StrictPath> {
+    let Some(game) = manifest.0.get(name) else {
+        return BTreeSet::new();
+    };
+
+    game_save_locations(
+        game,
+        name,
+        &roots.roots,
+        &StrictPath::from_std_path_buf(&app_dir()),
+        &roots.launchers,
+        &config.backup.filter,
+        &roots.wine_prefix,
+        &roots.steam_shortcuts,
+    )
+}
+
+/// Copy the data found by `scan_game` into the backup folder.
+/// If a new backup is created, then `label` is applied to it.
+pub fn back_up(scan_info: &ScanInfo, layout: &BackupLayout, format: &BackupFormats, label: &BackupLabel) -> BackupInfo {
+    layout
+        .game_layout(&scan_info.game_name)
+        .back_up(scan_info, &chrono::Utc::now(), format, label)
+}
+
+/// Find the data that would be restored for a single game.
+/// The manifest is used to skip files that were backed up on a different OS.
+pub fn scan_for_restoration(
+    manifest: &Manifest,
+    config: &Config,
+    layout: &mut GameLayout,
+    name: &str,
+    backup_id: &BackupId,
+) -> ScanInfo {
+    let os_constraints = manifest.0.get(name).map(OsConstraints::from_game).unwrap_or_default();
+    layout.scan_for_restoration(
+        name,
+        backup_id,
+        &config.redirects,
+        &config.restore.toggled_paths,
+        &config.restore.toggled_registry,
+        config.restore.remap_user_profile.enabled(),
+        config.restore.prefer_account.as_deref(),
+        config.restore.prefer_wine_user,
+        config.is_registry_enabled(name),
+        &os_constraints,
+    )
+}
+
+/// Put the data found by `scan_for_restoration` back in its original location.
+pub fn restore(scan_info: &ScanInfo, layout: &GameLayout, config: &Config) -> BackupInfo {
+    layout.restore(scan_info, &config.restore.toggled_registry)
+}
+
+/// Scan and back up several games in parallel.
+/// If `games` is empty, every game in the manifest is processed.
+/// Games that are disabled in the config are scanned, but not backed up,
+/// unless `backup.mode` says to leave them out entirely.
+/// Once `cancel` is tripped, the remaining games are skipped.
+pub fn back_up_games(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &Roots,
+    layout: &BackupLayout,
+    games: &[String],
+    finality: Finality,
+    options: &BackupOptions,
+    on_progress: &(dyn Fn(Progress) + Sync),
+    cancel: &Cancellation,
+) -> Vec<Outcome> {
+    let subjects: Vec<_> = if games.is_empty() {
+        manifest
+            .processable_titles()
+            .filter(|name| !config.is_game_skipped_for_backup(name))
+            .cloned()
+            .collect()
+    } else {
+        games.to_vec()
+    };
+    let max = subjects.len();
+    let current = AtomicUsize::new(0);
+    let label = options.label.clone().unwrap_or_else(|| BackupLabel {
+        trigger: Trigger::new(TriggerSource::Api),
+        manifest_hash: config.manifest.loaded_hash.clone(),
+        ..Default::default()
+    });
+    let format = options.format.as_ref().unwrap_or(&config.backup.format);
+    let forced_layout = layout.with_retention(Retention {
+        force_new_full: true,
+        ..layout.retention().clone()
+    });
+
+    subjects
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, name)| {
+            if cancel.is_cancelled() || !manifest.0.contains_key(name) {
+                return None;
+            }
+
+            on_progress(Progress::Started {
+                game: name,
+                index: i + 1,
+                max,
+            });
+            let forced = options.forced.contains(name);
+            let outcome = back_up_game(
+                manifest,
+                config,
+                roots,
+                if forced { &forced_layout } else { layout },
+                name,
+                finality,
+                options,
+                forced,
+                format,
+                &label,
+            );
+            on_progress(Progress::Finished {
+                outcome: &outcome,
+                current: current.fetch_add(1, Ordering::Relaxed) + 1,
+                max,
+            });
+            Some(outcome)
+        })
+        .collect()
+}
+
+/// Scan and restore several games in parallel.
+/// If `games` is empty, every game with a backup is processed.
+/// Games without a backup are left out of the results.
+/// Games that are disabled in the config are scanned, but not restored.
+/// Once `cancel` is tripped, the remaining games are skipped.
+pub fn restore_games(
+    manifest: &Manifest,
+    config: &Config,
+    layout: &BackupLayout,
+    games: &[String],
+    finality: Finality,
+    options: &RestoreOptions,
+    on_progress: &(dyn Fn(Progress) + Sync),
+    cancel: &Cancellation,
+) -> Vec<Outcome> {
+    let subjects: Vec<_> = if games.is_empty() {
+        let mut names = layout.restorable_games();
+        names.sort();
+        names
+    } else {
+        games.to_vec()
+    };
+    let max = subjects.len();
+    let current = AtomicUsize::new(0);
+    let backup_id = options.backup.clone().unwrap_or_default();
+
+    subjects
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, name)| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let mut game_layout = layout.try_game_layout(name)?;
+
+            on_progress(Progress::Started {
+                game: name,
+                index: i + 1,
+                max,
+            });
+            let timer = Instant::now();
+            let mut scan_info = scan_for_restoration(manifest, config, &mut game_layout, name, &backup_id);
+            options.overrides.apply(&mut scan_info);
+
+            let decision = if !config.is_game_enabled_for_restore(name) && !options.include_disabled {
+                OperationStepDecision::Ignored
+            } else {
+                OperationStepDecision::Processed
+            };
+            let backup_info =
+                (!finality.preview() && decision == OperationStepDecision::Processed && scan_info.backup.is_some())
+                    .then(|| restore(&scan_info, &game_layout, config));
+
+            let outcome = Outcome {
+                game: name.to_string(),
+                scan_info,
+                backup_info,
+                decision,
+                skipped: None,
+                duration: Some(timer.elapsed()),
+            };
+            on_progress(Progress::Finished {
+                outcome: &outcome,
+                current: current.fetch_add(1, Ordering::Relaxed) + 1,
+                max,
+            });
+            Some(outcome)
+        })
+        .collect()
+}
+
+fn back_up_game(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &Roots,
+    layout: &BackupLayout,
+    name: &str,
+    finality: Finality,
+    options: &BackupOptions,
+    forced: bool,
+    format: &BackupFormats,
+    label: &BackupLabel,
+) -> Outcome {
+    let ignored = !config.is_game_enabled_for_backup(name) && !options.include_disabled && !forced;
+    let decision = if ignored {
+        OperationStepDecision::Ignored
+    } else {
+        OperationStepDecision::Processed
+    };
+
+    if let Some((since, depth)) = options.if_changed_since {
+        if !ignored && !forced && !has_recent_activity(manifest, config, roots, layout, name, since, depth) {
+            log::info!("[{name}] skipping because there has been no recent activity");
+            return Outcome {
+                game: name.to_string(),
+                scan_info: ScanInfo::default(),
+                backup_info: None,
+                decision,
+                skipped: Some(Skip::Inactive),
+                duration: None,
+            };
+        }
+    }
+
+    let timer = Instant::now();
+    let mut scan_info = scan_game(manifest, config, roots, layout, name).unwrap_or_default();
+    options.overrides.apply(&mut scan_info);
+
+    let running = || {
+        let snapshot = options.running.as_ref().filter(|_| scan_info.found_anything())?;
+        let save_files: Vec<_> = scan_info
+            .found_files
+            .iter()
+            .filter(|x| !x.ignored)
+            .map(|x| x.path.clone())
+            .collect();
+        snapshot.find(&roots.install_dirs(name), &save_files)
+    };
+    let skipped = if ignored {
+        None
+    } else if options.inconsistent.contains(name) {
+        Some(Skip::Inconsistent)
+    } else if let Some(process) = running() {
+        log::warn!("[{name}] skipping backup because it seems to be running: {process:?}");
+        Some(Skip::Running(process))
+    } else if options.if_changed
+        && !forced
+        && !matches!(
+            scan_info.count_changes().overall(),
+            ScanChange::New | ScanChange::Different
+        )
+    {
+        log::info!("[{name}] skipping because its saves have not changed since the last backup");
+        Some(Skip::Unchanged)
+    } else {
+        None
+    };
+
+    let decision = if skipped.is_some() {
+        OperationStepDecision::Ignored
+    } else {
+        decision
+    };
+    let backup_info = (!finality.preview() && decision == OperationStepDecision::Processed).then(|| {
+        if forced {
+            log::info!("[{name}] forcing a new full backup");
+        }
+        back_up(&scan_info, layout, format, label)
+    });
+    let decision = if backup_info.as_ref().is_some_and(|x| x.deferred) {
+        log::warn!("[{name}] deferring because its files are locked");
+        OperationStepDecision::Deferred
+    } else {
+        decision
+    };
+
+    Outcome {
+        game: name.to_string(),
+        scan_info,
+        backup_info,
+        decision,
+        skipped,
+        duration: Some(timer.elapsed()),
+    }
+}
+
+/// Build the same report that the CLI prints with `--api`.
+pub fn report(outcomes: &[Outcome]) -> JsonOutput {
+    let mut duplicate_detector = DuplicateDetector::default();
+    for outcome in outcomes {
+        if outcome.scan_info.can_report_game() {
+            duplicate_detector.add_game(&outcome.scan_info, outcome.decision == OperationStepDecision::Processed);
+        }
+    }
+
+    let mut reporter = Reporter::json();
+    for outcome in outcomes {
+        if outcome.skipped == Some(Skip::Inactive) {
+            reporter.add_inactive_game(&outcome.game);
+            continue;
+        }
+        reporter.add_game(
+            &outcome.game,
+            &outcome.scan_info,
+            &outcome.backup_info.clone().unwrap_or_default(),
+            &outcome.decision,
+            &duplicate_detector,
+            outcome.duration.map(|x| x.as_millis() as u64),
+        );
+    }
+    reporter.into_json_output().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cancellation_is_shared_between_clones() {
+        let cancel = Cancellation::default();
+        let clone = cancel.clone();
+        assert!(!clone.is_cancelled());
+
+        cancel.cancel();
+        assert!(clone.is_cancelled());
+
+        clone.reset();
+        assert!(!cancel.is_cancelled());
+    }
+
+    #[test]
+    fn report_is_empty_without_outcomes() {
+        let output = serde_json::to_string(&report(&[])).unwrap();
+        assert_eq!(
+            r#"{"apiVersion":2,"overall":{"totalGames":0,"totalBytes":0,"processedGames":0,"processedBytes":0,"changedGames":{"new":0,"different":0,"removed":0,"same":0},"ignoredFiles":0,"ignoredBytes":0,"ignoredRegistryKeys":0},"games":{}}"#,
+            output
+        );
+    }
+
+    #[test]
+    fn report_includes_inactive_games_without_a_scan() {
+        let outcome = Outcome {
+            game: "foo".to_string(),
+            scan_info: ScanInfo::default(),
+            backup_info: None,
+            decision: OperationStepDecision::Processed,
+            skipped: Some(Skip::Inactive),
+            duration: None,
+        };
+        let output = serde_json::to_value(report(&[outcome])).unwrap();
+        assert!(output["games"]["foo"].is_object());
+    }
+}
@@ -1,44 +1,64 @@
+pub mod operation;
 mod parse;
-mod report;
+pub mod progress;
+pub mod report;
+mod requests;
 mod ui;
+mod watch;
 
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Debug,
+    path::Path,
     process::Command,
+    time::Duration,
 };
 
-use clap::CommandFactory;
 use indicatif::{ParallelProgressIterator, ProgressBar};
-use rayon::{
-    iter::{IntoParallelRefIterator, ParallelIterator},
-    prelude::IndexedParallelIterator,
-};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
+    api,
     cli::{
-        parse::{Cli, CompletionShell, ManifestSubcommand, Subcommand},
-        report::{report_cloud_changes, Reporter},
+        parse::{parse_game_list, Cli, CliDuration, CliSort, ManifestSubcommand, Subcommand},
+        report::{
+            report_api_schema, report_backup_diff, report_cloud_changes, report_cloud_history, report_config_changes,
+            report_custom_games, report_dangling_titles, report_dedup, report_explanation, report_history,
+            report_import_changes, report_redundant_roots, report_removed_roots, report_roots, report_schema,
+            report_size_history, report_storage, write_report, ApiFormat, ReportOptions, Reporter,
+        },
     },
-    cloud::{CloudChange, Rclone, Remote},
+    cloud::{chunked, CloudChange, Rclone, Remote},
+    daemon, history,
     lang::TRANSLATOR,
     prelude::{
         app_dir, get_threads_from_env, initialize_rayon, register_sigint, unregister_sigint, Error, Finality,
         StrictPath, SyncDirection,
     },
-    resource::{cache::Cache, config::Config, manifest::Manifest, ResourceFile, SaveableResourceFile},
+    resource::{
+        cache::Cache,
+        config::{
+            Config, CustomGame, DanglingTitle, ImportMode, ImportOutcome, RedirectKind, RemapUserProfile, Retention,
+            RootsConfig, SharedConfig, Sort, SortKey,
+        },
+        manifest::{placeholder, Manifest, StoreId},
+        ResourceFile, SaveableResourceFile,
+    },
     scan::{
-        layout::BackupLayout, prepare_backup_target, scan_game_for_backup, BackupId, DuplicateDetector, Launchers,
-        OperationStepDecision, SteamShortcuts, TitleFinder,
+        layout::{Backup, BackupFilter, BackupLayout, DeletionProblem, PruneRules},
+        BackupDiff, BackupId, BackupLabel, DedupReport, DiffSide, DuplicateDetector, Explanation, Launchers,
+        OperationStepDecision, SafetySnapshot, SizeHistoryEntry, SteamShortcuts, StorageReport, StoreIdProblem,
+        TitleFinder, Trigger, TriggerSource, UnknownGame, DEFAULT_FUZZY_LIMIT, DEFAULT_FUZZY_THRESHOLD,
     },
+    serve::{LastOperationsFile, OperationKind, OperationRecord},
     wrap::{heroic::infer_game_from_heroic, infer_game_from_steam, WrapGameInfo},
 };
 
 #[derive(Clone, Debug, Default)]
-struct GameSubjects {
+pub(crate) struct GameSubjects {
     // TODO: Use BTreeSet
-    valid: Vec<String>,
-    invalid: Vec<String>,
+    pub valid: Vec<String>,
+    pub invalid: Vec<String>,
 }
 
 impl GameSubjects {
@@ -95,6 +115,15 @@ fn warn_backup_deprecations(merge: bool, no_merge: bool, update: bool, try_updat
     }
 }
 
+/// The daemon may be running in a different working directory.
+fn absolutize(path: &StrictPath) -> StrictPath {
+    if path.is_absolute() {
+        path.clone()
+    } else {
+        path.rendered()
+    }
+}
+
 fn negatable_flag(on: bool, off: bool, default: bool) -> bool {
     if on {
         true
@@ -105,23 +134,80 @@ fn negatable_flag(on: bool, off: bool, default: bool) -> bool {
     }
 }
 
+/// This also records the manifest's hash in the config for new backups (see `ManifestConfig::loaded_hash`).
 fn load_manifest(
-    config: &Config,
+    config: &mut Config,
     cache: &mut Cache,
     no_manifest_update: bool,
     try_manifest_update: bool,
+    ignore_invalid_manifest: bool,
 ) -> Result<Manifest, Error> {
-    if no_manifest_update {
-        Ok(Manifest::load().unwrap_or_default())
+    let loaded = if no_manifest_update {
+        Ok(Manifest::load_with_hash().unwrap_or_default())
     } else if try_manifest_update {
         if let Err(e) = Manifest::update_mut(config, cache, false) {
             eprintln!("{}", TRANSLATOR.handle_error(&e));
         }
-        Ok(Manifest::load().unwrap_or_default())
+        Ok(Manifest::load_with_hash().unwrap_or_default())
     } else {
         Manifest::update_mut(config, cache, false)?;
-        Manifest::load()
+        Manifest::load_with_hash()
+    };
+    let loaded = loaded.map(|(manifest, hash, fallback)| {
+        config.manifest.loaded_hash = hash;
+        config.manifest.loaded_fallback = fallback;
+        manifest
+    });
+
+    if !no_manifest_update {
+        if let Ok(manifest) = &loaded {
+            let dangling = config.dangling_titles(manifest, &Default::default());
+            if !dangling.is_empty() {
+                log::warn!(
+                    "Config has {} alias/merged title(s) that are not in the manifest: {:?}",
+                    dangling.len(),
+                    dangling
+                );
+            }
+        }
+    }
+
+    match loaded {
+        Err(e) if ignore_invalid_manifest => {
+            // Custom games are added later, so they can still be processed.
+            eprintln!("{}", TRANSLATOR.handle_error(&e));
+            Ok(Manifest::default())
+        }
+        loaded => loaded,
+    }
+}
+
+fn find_dangling_titles(
+    config: &mut Config,
+    cache: &mut Cache,
+    no_manifest_update: bool,
+    try_manifest_update: bool,
+    ignore_invalid_manifest: bool,
+) -> Result<Vec<DanglingTitle>, Error> {
+    let manifest = load_manifest(
+        config,
+        cache,
+        no_manifest_update,
+        try_manifest_update,
+        ignore_invalid_manifest,
+    )?;
+    let layout = BackupLayout::new(config.restore.path.clone(), config);
+    let backups = layout.restorable_games().into_iter().collect();
+    Ok(config.dangling_titles(&manifest, &backups))
+}
+
+/// `--reverse` flips whichever order applies, including one from the config file.
+fn choose_sort(sort: Option<CliSort>, reverse: bool, default: &Sort) -> Sort {
+    let mut sort = sort.map(Sort::from).unwrap_or_else(|| default.clone());
+    if reverse {
+        sort.reversed = !sort.reversed;
     }
+    sort
 }
 
 fn parse_games(games: Vec<String>) -> Vec<String> {
@@ -141,12 +227,233 @@ fn parse_games(games: Vec<String>) -> Vec<String> {
     }
 }
 
+/// Add the titles from `--games-from` to the ones given directly, skipping duplicates.
+/// This returns the titles that only came from the list.
+fn add_games_from(games: &mut Vec<String>, source: Option<&StrictPath>) -> Result<BTreeSet<String>, Error> {
+    let Some(source) = source else {
+        return Ok(BTreeSet::new());
+    };
+
+    let content = if source.raw() == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(source.as_std_path_buf())
+    }
+    .map_err(|e| Error::CliUnableToReadGameList {
+        path: source.clone(),
+        why: e.to_string(),
+    })?;
+
+    let mut listed = BTreeSet::new();
+    for game in parse_game_list(&content) {
+        if !games.contains(&game) {
+            games.push(game.clone());
+            listed.insert(game);
+        }
+    }
+    log::debug!("Games from {}: {:?}", source.raw(), &listed);
+    Ok(listed)
+}
+
+/// Collect `--steam-id`, `--gog-id`, and `--lutris-id`, in that order.
+fn collect_store_ids(steam_id: Option<u32>, gog_id: Option<u64>, lutris_id: Option<String>) -> Vec<StoreId> {
+    steam_id
+        .map(StoreId::Steam)
+        .into_iter()
+        .chain(gog_id.map(StoreId::Gog))
+        .chain(lutris_id.map(StoreId::Lutris))
+        .collect()
+}
+
+/// The games found by store ID, and the IDs that didn't match any game.
+type StoreIdResolution = (Vec<(String, StoreId)>, Vec<UnknownGame>);
+
+/// Resolve each store ID to a single game.
+/// An ambiguous ID is an error right away,
+/// while unknown IDs are returned separately with suggestions based on similar IDs.
+fn resolve_store_ids(
+    title_finder: &TitleFinder,
+    ids: &[StoreId],
+    backup: bool,
+    restore: bool,
+) -> Result<StoreIdResolution, Error> {
+    let mut found = vec![];
+    let mut unknown = vec![];
+
+    for id in ids {
+        match title_finder.find_by_store_id(id, backup, restore) {
+            Ok(name) => found.push((name, id.clone())),
+            Err(StoreIdProblem::Ambiguous { games }) => {
+                return Err(Error::CliAmbiguousStoreId { id: id.clone(), games });
+            }
+            Err(StoreIdProblem::Unknown { suggestions }) => unknown.push(UnknownGame {
+                name: id.value(),
+                suggestions,
+            }),
+        }
+    }
+
+    Ok((found, unknown))
+}
+
+/// For commands that operate on the games, every ID has to match.
+fn games_for_store_ids(
+    title_finder: &TitleFinder,
+    ids: &[StoreId],
+    backup: bool,
+    restore: bool,
+) -> Result<Vec<String>, Error> {
+    let (found, unknown) = resolve_store_ids(title_finder, ids, backup, restore)?;
+    if !unknown.is_empty() {
+        return Err(Error::CliUnrecognizedGames { games: unknown });
+    }
+    Ok(found.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Like `games_for_store_ids`, but using the manifest that's already on disk,
+/// for when the IDs need to be resolved before the manifest would otherwise be loaded
+/// (e.g., before handing the operation to the daemon).
+fn games_for_store_ids_from_cache(
+    config: &Config,
+    backup_dir: &StrictPath,
+    ids: &[StoreId],
+    backup: bool,
+    restore: bool,
+) -> Result<Vec<String>, Error> {
+    let mut manifest = Manifest::load().unwrap_or_default();
+    manifest.incorporate_extensions(config);
+    let layout = BackupLayout::new(backup_dir.clone(), config);
+    games_for_store_ids(&TitleFinder::new(&manifest, &layout), ids, backup, restore)
+}
+
+/// Back up a game's current files for `restore --backup-before-restore`.
+/// This is always a new, locked full backup with its own name,
+/// so it can't replace the backup that's about to be restored or be pruned by retention later.
+fn snapshot_before_restore(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &api::Roots,
+    layout: &BackupLayout,
+    name: &str,
+) -> SafetySnapshot {
+    let Some(scan_info) = api::scan_game(manifest, config, roots, layout, name) else {
+        return SafetySnapshot::Failed(TRANSLATOR.safety_snapshot_unknown_game());
+    };
+    if !scan_info.io_timeouts.is_empty() {
+        return SafetySnapshot::Failed(TRANSLATOR.safety_snapshot_io_timeout());
+    }
+    if !scan_info.found_anything() {
+        return SafetySnapshot::Unneeded;
+    }
+
+    let layout = layout.with_retention(Retention {
+        // With a limit of 1, the only full backup would be replaced in place.
+        full: u8::MAX,
+        differential: 0,
+        force_new_full: true,
+        promote_full_when_diff_bytes_exceed: None,
+    });
+    let label = BackupLabel {
+        comment: Some(TRANSLATOR.safety_snapshot_comment()),
+        locked: true,
+        trigger: Trigger::new(TriggerSource::Cli),
+        manifest_hash: config.manifest.loaded_hash.clone(),
+    };
+    let backup_info = api::back_up(&scan_info, &layout, &config.backup.format, &label);
+    if let Some(reason) = backup_info
+        .failed_files
+        .values()
+        .chain(backup_info.failed_registry.values())
+        .next()
+    {
+        return SafetySnapshot::Failed(reason.clone());
+    }
+
+    match layout
+        .game_layout(name)
+        .restorable_backups_flattened()
+        .into_iter()
+        .rfind(|x| x.full())
+    {
+        Some(backup) if backup_info.kind.is_some() => SafetySnapshot::Created(backup.name().to_string()),
+        _ => SafetySnapshot::Unneeded,
+    }
+}
+
 pub fn parse() -> Cli {
     use clap::Parser;
-    Cli::parse()
+    Cli::parse().validate().unwrap_or_else(|e| e.exit())
+}
+
+/// Keep track of the outcome for `ludusavi serve` and `ludusavi history`.
+pub(crate) fn record_last_operation(
+    config: &Config,
+    kind: OperationKind,
+    started: chrono::DateTime<chrono::Utc>,
+    reporter: &Reporter,
+    failed: bool,
+    failed_games: usize,
+) {
+    let record =
+        OperationRecord::new(Some(started), failed, reporter.overall().cloned()).with_errors(reporter.errors_json());
+    LastOperationsFile::default().record(kind, record);
+
+    let operation = match kind {
+        OperationKind::Backup => history::Operation::Backup,
+        OperationKind::Restore => history::Operation::Restore,
+    };
+    history::History::default().record(
+        &config.history,
+        &history::Record::for_games(operation, started, reporter.overall(), failed_games),
+    );
 }
 
-pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool) -> Result<(), Error> {
+/// Hand the operation to `ludusavi daemon` if one is running.
+/// Returns `None` if there isn't one, so that the caller can proceed as usual.
+fn run_via_daemon(
+    request: &daemon::Request,
+    confirmation: Option<String>,
+    output_file: Option<&StrictPath>,
+) -> Option<Result<(), Error>> {
+    let endpoint = daemon::Endpoint::default();
+    if !endpoint.running() {
+        log::info!("No daemon is running, so handling the operation directly");
+        return None;
+    }
+
+    if let Some(prompt) = confirmation {
+        match dialoguer::Confirm::new().with_prompt(prompt).interact() {
+            Ok(true) => (),
+            Ok(false) => return Some(Ok(())),
+            Err(_) => return Some(Err(Error::CliUnableToRequestConfirmation)),
+        }
+    }
+
+    let response = match endpoint.send(request) {
+        Ok(response) => response,
+        Err(e) => return Some(Err(e)),
+    };
+    if !response.output.is_empty() {
+        if let Err(e) = write_report(&response.output, output_file, false) {
+            return Some(Err(e));
+        }
+    }
+    Some(match response.error {
+        Some(message) => Err(Error::DaemonReported { message }),
+        None if response.failed => Err(Error::SomeEntriesFailed),
+        None if response.changes_pending => Err(Error::ChangesPending),
+        None if response.nothing_changed => Err(Error::NothingChanged),
+        None => Ok(()),
+    })
+}
+
+pub fn run(
+    sub: Subcommand,
+    no_manifest_update: bool,
+    try_manifest_update: bool,
+    ignore_invalid_manifest: bool,
+    report_options: ReportOptions,
+) -> Result<(), Error> {
     let mut config = Config::load()?;
     if let Some(threads) = get_threads_from_env().or(config.runtime.threads) {
         initialize_rayon(threads);
@@ -154,7 +461,9 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
     let mut cache = Cache::load().unwrap_or_default().migrate_config(&mut config);
     TRANSLATOR.set_language(config.language);
     let mut failed = false;
-    let mut duplicate_detector = DuplicateDetector::default();
+    let mut changes_pending = false;
+    let mut nothing_changed = false;
+    let duplicate_detector = DuplicateDetector::default();
 
     log::debug!("Config on startup: {config:?}");
     log::debug!("Invocation: {sub:?}");
@@ -171,6 +480,7 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
             wine_prefix,
             api,
             sort,
+            reverse,
             format,
             compression,
             compression_level,
@@ -178,20 +488,151 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
             differential_limit,
             cloud_sync,
             no_cloud_sync,
+            allow_metered,
+            repair,
+            retry_locked,
+            skip_running,
+            force_game,
+            if_changed,
+            if_changed_since,
+            if_changed_depth,
+            no_redirects,
+            redirect,
+            exclude,
+            include,
+            comment,
+            lock,
+            scheduled,
+            trigger,
+            verbose,
+            full_file_list,
+            only_changed,
+            only_failed,
+            fail_on_change,
+            output_file,
+            use_daemon,
+            steam_id,
+            gog_id,
+            lutris_id,
+            games_from,
+            strict,
             games,
         } => {
             warn_backup_deprecations(x_merge, x_no_merge, x_update, x_try_update);
-            let games = parse_games(games);
+            let store_ids = collect_store_ids(steam_id, gog_id, lutris_id);
+            // A launcher passing an ID may not close stdin, so only check it when there's nothing else.
+            let mut games = if store_ids.is_empty() && games_from.is_none() {
+                parse_games(games)
+            } else {
+                games
+            };
+            let listed = add_games_from(&mut games, games_from.as_ref())?;
+            let trigger = trigger.unwrap_or_else(|| {
+                Trigger::new(if scheduled {
+                    TriggerSource::Scheduler
+                } else {
+                    TriggerSource::Cli
+                })
+            });
+            let mut request = operation::BackupRequest {
+                preview,
+                path,
+                output: operation::ReportRequest {
+                    api,
+                    sort: Some(choose_sort(sort, reverse, &config.backup.sort)),
+                    verbose,
+                    full_file_list,
+                    only_changed,
+                    only_failed,
+                    report: report_options,
+                },
+                wine_prefix,
+                format,
+                compression,
+                compression_level,
+                full_limit,
+                differential_limit,
+                cloud_sync,
+                no_cloud_sync,
+                allow_metered,
+                repair,
+                retry_locked,
+                skip_running,
+                force_game,
+                if_changed,
+                if_changed_since: if_changed_since.map(|CliDuration(window)| chrono::Utc::now() - window),
+                if_changed_depth: Some(if_changed_depth),
+                no_redirects,
+                redirects: redirect.iter().map(|x| x.resolve(RedirectKind::Backup)).collect(),
+                exclude,
+                include,
+                comment,
+                lock,
+                trigger: Some(trigger),
+                fail_on_change,
+                games,
+                listed,
+                from_list: games_from.is_some(),
+                strict,
+            };
 
-            let mut reporter = if api { Reporter::json() } else { Reporter::standard() };
+            // The daemon can't stream JSON Lines back as each game finishes.
+            if use_daemon && !(api && report_options.api_format == ApiFormat::JsonLines) {
+                let backup_dir = request.path.clone().unwrap_or_else(|| config.backup.path.clone());
+                let mut request = request.clone();
+                if !store_ids.is_empty() {
+                    request.games.extend(games_for_store_ids_from_cache(
+                        &config,
+                        &backup_dir,
+                        &store_ids,
+                        true,
+                        false,
+                    )?);
+                }
+                request.path = request.path.as_ref().map(absolutize);
+                request.wine_prefix = request.wine_prefix.as_ref().map(absolutize);
+                for redirect in &mut request.redirects {
+                    redirect.source = absolutize(&redirect.source);
+                    redirect.target = absolutize(&redirect.target);
+                }
+                request.output.report.color = report_options.color.resolve(output_file.is_some());
+                let confirmation =
+                    (!preview && !force).then(|| TRANSLATOR.confirm_backup(&backup_dir, backup_dir.exists(), false));
+                if let Some(result) =
+                    run_via_daemon(&daemon::Request::Backup(request), confirmation, output_file.as_ref())
+                {
+                    return result;
+                }
+            }
 
-            let mut manifest = load_manifest(&config, &mut cache, no_manifest_update, try_manifest_update)?;
+            let mut reporter = request.output.reporter(&config);
+            reporter.set_output_file(output_file);
+
+            let mut manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            reporter.trip_manifest_fallback(config.manifest.loaded_fallback.clone());
+            manifest.incorporate_extensions(&config);
 
-            let backup_dir = match path {
-                None => config.backup.path.clone(),
-                Some(p) => p,
-            };
-            let roots = config.expanded_roots();
+            let backup_dir = request.path.clone().unwrap_or_else(|| config.backup.path.clone());
+
+            if !store_ids.is_empty() {
+                let layout = BackupLayout::new(backup_dir.clone(), &config);
+                match games_for_store_ids(&TitleFinder::new(&manifest, &layout), &store_ids, true, false) {
+                    Ok(found) => request.games.extend(found),
+                    Err(e) => {
+                        if let Error::CliUnrecognizedGames { games } = &e {
+                            reporter.trip_unknown_games(games.clone());
+                        }
+                        reporter.print_failure();
+                        return Err(e);
+                    }
+                }
+            }
 
             if !preview && !force {
                 match dialoguer::Confirm::new()
@@ -204,360 +645,540 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                 }
             }
 
-            if !preview {
-                prepare_backup_target(&backup_dir)?;
+            let summary = match operation::back_up(&manifest, &mut config, &mut cache, &request, &mut reporter) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    if let Error::CliUnrecognizedGames { .. } = &e {
+                        reporter.print_failure();
+                    }
+                    return Err(e);
+                }
+            };
+            let printed = reporter.print(&summary.dir);
+            summary.record(&config, &reporter);
+            printed?;
+            failed = summary.failed_games > 0;
+            changes_pending = summary.changes_pending;
+            nothing_changed = summary.nothing_changed;
+        }
+        Subcommand::Restore {
+            preview,
+            path,
+            force,
+            yes,
+            api,
+            sort,
+            reverse,
+            backup,
+            only_path,
+            exclude,
+            include,
+            no_clobber_newer,
+            backup_before_restore,
+            no_backup_before_restore,
+            cloud_sync,
+            no_cloud_sync,
+            no_redirects,
+            redirect,
+            migrate_paths,
+            prefer_account,
+            registry_into_hive,
+            with_files,
+            verbose,
+            full_file_list,
+            only_changed,
+            only_failed,
+            output_file,
+            use_daemon,
+            steam_id,
+            gog_id,
+            lutris_id,
+            games_from,
+            strict,
+            games,
+        } => {
+            #[cfg(not(target_os = "windows"))]
+            if registry_into_hive.is_some() {
+                return Err(Error::RegistryHiveUnsupported);
             }
+            // Load this early so that we fail before any prompts if it's not possible.
+            #[cfg(target_os = "windows")]
+            let offline_hive = match registry_into_hive.as_ref().filter(|_| !preview) {
+                Some(file) => Some(crate::scan::registry::OfflineHive::load(file)?),
+                None => None,
+            };
+            #[cfg(target_os = "windows")]
+            let offline_hive_key = offline_hive.as_ref().map(|x| x.subkey());
+            #[cfg(not(target_os = "windows"))]
+            let offline_hive_key: Option<&str> = None;
+            let hive = registry_into_hive.as_ref().map(|_| operation::RestoreHive {
+                key: offline_hive_key,
+                with_files,
+            });
 
-            manifest.incorporate_extensions(&config);
-
-            let games_specified = !games.is_empty();
-            let subjects = GameSubjects::new(manifest.0.keys().cloned().collect(), games, Some(&manifest.aliases()));
-            if !subjects.invalid.is_empty() {
-                reporter.trip_unknown_games(subjects.invalid.clone());
-                reporter.print_failure();
-                return Err(Error::CliUnrecognizedGames {
-                    games: subjects.invalid,
-                });
+            let store_ids = collect_store_ids(steam_id, gog_id, lutris_id);
+            let mut games = if store_ids.is_empty() && games_from.is_none() {
+                parse_games(games)
+            } else {
+                games
+            };
+            let listed = add_games_from(&mut games, games_from.as_ref())?;
+            if !store_ids.is_empty() {
+                let restore_dir = path.clone().unwrap_or_else(|| config.restore.path.clone());
+                games.extend(games_for_store_ids_from_cache(
+                    &config,
+                    &restore_dir,
+                    &store_ids,
+                    false,
+                    true,
+                )?);
+            }
+            let request = operation::RestoreRequest {
+                preview,
+                path,
+                force,
+                output: operation::ReportRequest {
+                    api,
+                    sort: Some(choose_sort(sort, reverse, &config.restore.sort)),
+                    verbose,
+                    full_file_list,
+                    only_changed,
+                    only_failed,
+                    report: report_options,
+                },
+                backup,
+                only_path,
+                exclude,
+                include,
+                no_clobber_newer,
+                backup_before_restore,
+                no_backup_before_restore,
+                cloud_sync,
+                no_cloud_sync,
+                no_redirects,
+                redirects: redirect.iter().map(|x| x.resolve(RedirectKind::Restore)).collect(),
+                migrate_paths,
+                prefer_account,
+                games,
+                listed,
+                from_list: games_from.is_some(),
+                strict,
+            };
+            let restore_dir = request.path.clone().unwrap_or_else(|| config.restore.path.clone());
+
+            let mut confirmed = force;
+            if !preview && request.games.is_empty() && games_from.is_none() {
+                match confirm_restoring_many_games(&config, &restore_dir, yes)? {
+                    Some(true) => confirmed = true,
+                    Some(false) => return Ok(()),
+                    None => {}
+                }
             }
 
-            let mut retention = config.backup.retention.clone();
-            if let Some(full_limit) = full_limit {
-                retention.full = full_limit;
+            // The daemon can't restore into a hive file or ask whether to remap user profiles,
+            // so those are always handled here.
+            // The daemon also can't stream JSON Lines back as each game finishes.
+            if use_daemon
+                && hive.is_none()
+                && !(config.restore.remap_user_profile == RemapUserProfile::Ask && !preview && !force)
+                && !(api && report_options.api_format == ApiFormat::JsonLines)
+            {
+                let mut request = request.clone();
+                request.path = request.path.as_ref().map(absolutize);
+                for redirect in &mut request.redirects {
+                    redirect.source = absolutize(&redirect.source);
+                    redirect.target = absolutize(&redirect.target);
+                }
+                request.output.report.color = report_options.color.resolve(output_file.is_some());
+                let confirmation = (!preview && !confirmed).then(|| TRANSLATOR.confirm_restore(&restore_dir, false));
+                if let Some(result) =
+                    run_via_daemon(&daemon::Request::Restore(request), confirmation, output_file.as_ref())
+                {
+                    return result;
+                }
             }
-            if let Some(differential_limit) = differential_limit {
-                retention.differential = differential_limit;
+
+            let mut reporter = request.output.reporter(&config);
+            reporter.set_output_file(output_file);
+
+            if !preview && !confirmed {
+                match dialoguer::Confirm::new()
+                    .with_prompt(TRANSLATOR.confirm_restore(&restore_dir, false))
+                    .interact()
+                {
+                    Ok(true) => (),
+                    Ok(false) => return Ok(()),
+                    Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+                }
             }
 
-            let layout = BackupLayout::new(backup_dir.clone(), retention);
-            let title_finder = TitleFinder::new(&manifest, &layout);
-            let launchers = Launchers::scan(&roots, &manifest, &subjects.valid, &title_finder, None);
-            let filter = config.backup.filter.clone();
-            let toggled_paths = config.backup.toggled_paths.clone();
-            let toggled_registry = config.backup.toggled_registry.clone();
-            let steam_shortcuts = SteamShortcuts::scan();
-
-            let cloud_sync = negatable_flag(
-                cloud_sync && !preview,
-                no_cloud_sync,
-                config.cloud.synchronize
-                    && !preview
-                    && crate::cloud::validate_cloud_config(&config, &config.cloud.path).is_ok(),
-            );
-            let mut should_sync_cloud_after = cloud_sync && !preview;
-            if cloud_sync {
-                let changes = sync_cloud(
-                    &config,
-                    &backup_dir,
-                    &config.cloud.path,
-                    SyncDirection::Upload,
-                    Finality::Preview,
-                    if games_specified { &subjects.valid } else { &[] },
-                );
-                match changes {
-                    Ok(changes) => {
-                        if !changes.is_empty() {
-                            should_sync_cloud_after = false;
-                            reporter.trip_cloud_conflict();
-                        }
-                    }
-                    Err(_) => {
-                        should_sync_cloud_after = false;
-                        reporter.trip_cloud_sync_failed();
+            // The restore itself doesn't need the manifest, but it can refine which paths make sense here.
+            let (mut manifest, manifest_hash, fallback) = Manifest::load_with_hash().unwrap_or_default();
+            config.manifest.loaded_hash = manifest_hash;
+            config.manifest.loaded_fallback = fallback;
+            manifest.incorporate_extensions(&config);
+
+            let summary = match operation::restore(
+                &manifest,
+                &mut config,
+                &mut cache,
+                &request,
+                &mut reporter,
+                hive,
+                &|remaps| ask(TRANSLATOR.confirm_remap_user_profile(remaps), Finality::Final, false),
+            ) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    if let Error::CliUnrecognizedGames { .. } = &e {
+                        reporter.print_failure();
                     }
+                    return Err(e);
                 }
+            };
+            let printed = reporter.print(&summary.dir);
+            summary.record(&config, &reporter);
+            printed?;
+            failed = summary.failed_games > 0;
+        }
+        Subcommand::Complete { shell, list_games } => {
+            if let Some(shell) = shell {
+                shell.generate(&mut std::io::stdout());
             }
+            if let Some(prefix) = list_games {
+                // This runs while the user is typing, so don't wait for a manifest update.
+                let mut manifest = Manifest::load().unwrap_or_default();
+                manifest.incorporate_extensions(&config);
+                for title in manifest.titles_starting_with(&prefix) {
+                    println!("{title}");
+                }
+            }
+        }
+        Subcommand::Backups {
+            sub:
+                Some(parse::BackupsSubcommand::Comment {
+                    path,
+                    api,
+                    backup,
+                    set,
+                    clear: _,
+                    game,
+                }),
+            ..
+        } => {
+            let mut reporter = Reporter::new(api, report_options);
+            reporter.suppress_overall();
 
-            log::info!("beginning backup with {} steps", subjects.valid.len());
-
-            let mut info: Vec<_> = subjects
-                .valid
-                .par_iter()
-                .enumerate()
-                .progress_with(scan_progress_bar(subjects.valid.len() as u64))
-                .map(|(i, name)| {
-                    log::trace!("step {i} / {}: {name}", subjects.valid.len());
-                    let game = &manifest.0[name];
+            let restore_dir = match path {
+                None => config.restore.path.clone(),
+                Some(p) => p,
+            };
 
-                    let previous = layout.latest_backup(name, false, &config.redirects, &config.restore.toggled_paths);
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
 
-                    let scan_info = scan_game_for_backup(
-                        game,
-                        name,
-                        &roots,
-                        &StrictPath::from_std_path_buf(&app_dir()),
-                        &launchers,
-                        &filter,
-                        &wine_prefix,
-                        &toggled_paths,
-                        &toggled_registry,
-                        previous,
-                        &config.redirects,
-                        &steam_shortcuts,
-                    );
-                    let ignored = !&config.is_game_enabled_for_backup(name) && !games_specified;
-                    let decision = if ignored {
-                        OperationStepDecision::Ignored
-                    } else {
-                        OperationStepDecision::Processed
-                    };
-                    let backup_info = if preview || ignored {
-                        crate::scan::BackupInfo::default()
-                    } else {
-                        let mut backup_format = config.backup.format.clone();
-                        if let Some(format) = format {
-                            backup_format.chosen = format;
-                        }
-                        if let Some(compression) = compression {
-                            backup_format.zip.compression = compression;
-                        }
-                        if let Some(level) = compression_level {
-                            backup_format
-                                .compression
-                                .set_level(&backup_format.zip.compression, level);
-                        }
+            let subjects = GameSubjects::new(layout.restorable_games(), vec![game], None);
+            if !subjects.invalid.is_empty() {
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
+                reporter.print_failure();
+                return Err(Error::CliUnrecognizedGames { games: unknown });
+            }
 
-                        layout
-                            .game_layout(name)
-                            .back_up(&scan_info, &chrono::Utc::now(), &backup_format)
-                    };
-                    log::trace!("step {i} completed");
-                    (name, scan_info, backup_info, decision)
-                })
-                .collect();
-            log::info!("completed backup");
+            for name in &subjects.valid {
+                let mut layout = layout.game_layout(name);
+                let backups = layout.get_backups();
+                let target = match &backup {
+                    Some(id) => backups.iter().find(|x| x.matches(id)),
+                    None => backups.last(),
+                };
+                let Some(target) = target else {
+                    return Err(Error::CliInvalidBackupId {
+                        available: layout.describe_backups(),
+                    });
+                };
 
-            if should_sync_cloud_after {
-                let sync_result = sync_cloud(
-                    &config,
-                    &backup_dir,
-                    &config.cloud.path,
-                    SyncDirection::Upload,
-                    Finality::Final,
-                    if games_specified { &subjects.valid } else { &[] },
-                );
-                if sync_result.is_err() {
-                    reporter.trip_cloud_sync_failed();
-                }
+                // An empty comment is the same as clearing it.
+                layout.set_backup_comment(target.name(), set.as_deref().unwrap_or_default());
+                layout.save();
+
+                let backups: Vec<_> = layout
+                    .get_backups()
+                    .into_iter()
+                    .map(|backup| {
+                        let bytes = layout.backup_bytes(&backup);
+                        (backup, bytes)
+                    })
+                    .collect();
+                reporter.add_backups(name, &backups);
             }
+            reporter.print(&restore_dir)?;
+        }
+        Subcommand::Backups {
+            sub:
+                Some(parse::BackupsSubcommand::Delete {
+                    path,
+                    api,
+                    backup,
+                    cascade,
+                    force,
+                    game,
+                }),
+            ..
+        } => {
+            let mut reporter = Reporter::new(api, report_options);
+            reporter.suppress_overall();
 
-            for (_, scan_info, _, _) in info.iter() {
-                if !scan_info.can_report_game() {
-                    continue;
-                }
-                duplicate_detector.add_game(
-                    scan_info,
-                    config.is_game_enabled_for_operation(&scan_info.game_name, false),
-                );
-            }
+            let restore_dir = match path {
+                None => config.restore.path.clone(),
+                Some(p) => p,
+            };
 
-            let sort = sort.map(From::from).unwrap_or_else(|| config.backup.sort.clone());
-            info.sort_by(|(_, scan_info1, backup_info1, ..), (_, scan_info2, backup_info2, ..)| {
-                crate::scan::compare_games(sort.key, scan_info1, Some(backup_info1), scan_info2, Some(backup_info2))
-            });
-            if sort.reversed {
-                info.reverse();
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
+
+            let subjects = GameSubjects::new(layout.restorable_games(), vec![game], None);
+            if !subjects.invalid.is_empty() {
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
+                reporter.print_failure();
+                return Err(Error::CliUnrecognizedGames { games: unknown });
             }
 
-            for (name, scan_info, backup_info, decision) in info {
-                if !reporter.add_game(name, &scan_info, &backup_info, &decision, &duplicate_detector) {
-                    failed = true;
+            for name in &subjects.valid {
+                let mut layout = layout.game_layout(name);
+                match layout.delete_backup(&backup, cascade, force) {
+                    Ok(deleted) => {
+                        reporter.add_deleted_backups(name, &deleted, false);
+                    }
+                    Err(DeletionProblem::NotFound) => {
+                        return Err(Error::CliInvalidBackupId {
+                            available: layout.describe_backups(),
+                        });
+                    }
+                    Err(problem) => {
+                        return Err(Error::UnableToDeleteBackup { backup, problem });
+                    }
                 }
             }
-            reporter.print(&backup_dir);
+            reporter.print(&restore_dir)?;
         }
-        Subcommand::Restore {
-            preview,
+        Subcommand::Backups {
             path,
-            force,
             api,
+            newer_than,
+            older_than,
+            trigger,
             sort,
-            backup,
-            cloud_sync,
-            no_cloud_sync,
+            reverse,
+            adopt,
+            adopt_as,
+            output_file,
             games,
+            sub: None,
         } => {
-            let games = parse_games(games);
+            let mut reporter = Reporter::new(api, report_options);
+            reporter.set_output_file(output_file);
+            reporter.suppress_overall();
 
-            let mut reporter = if api { Reporter::json() } else { Reporter::standard() };
+            let now = chrono::Utc::now();
+            let filter = BackupFilter {
+                newer_than: newer_than.map(|x| x.resolve(&now)),
+                older_than: older_than.map(|x| x.resolve(&now)),
+                trigger,
+            };
+            reporter.set_backup_filter(&filter);
 
             let restore_dir = match path {
                 None => config.restore.path.clone(),
                 Some(p) => p,
             };
 
-            if !preview && !force {
-                match dialoguer::Confirm::new()
-                    .with_prompt(TRANSLATOR.confirm_restore(&restore_dir, false))
-                    .interact()
-                {
-                    Ok(true) => (),
-                    Ok(false) => return Ok(()),
-                    Err(_) => return Err(Error::CliUnableToRequestConfirmation),
+            let mut layout = BackupLayout::new(restore_dir.clone(), &config);
+
+            let adoption = match adopt.zip(adopt_as) {
+                Some((source, name)) => {
+                    // The backup is moved under this name, so it should be one that backups would use too.
+                    let mut manifest = Manifest::load().unwrap_or_default();
+                    manifest.incorporate_extensions(&config);
+                    let subjects = GameSubjects::new(
+                        manifest.0.keys().cloned().collect(),
+                        vec![name],
+                        Some(&manifest.aliases()),
+                    );
+                    if !subjects.invalid.is_empty() {
+                        let unknown = TitleFinder::new(&manifest, &layout).unknown_games(subjects.invalid, true, false);
+                        reporter.trip_unknown_games(unknown.clone());
+                        reporter.print_failure();
+                        return Err(Error::CliUnrecognizedGames { games: unknown });
+                    }
+                    subjects.valid.into_iter().next().map(|name| (source, name))
                 }
-            }
+                None => None,
+            };
+            let games = match &adoption {
+                Some((_, name)) => vec![name.clone()],
+                None => parse_games(games),
+            };
 
-            let layout = BackupLayout::new(restore_dir.clone(), config.backup.retention.clone());
+            if let Some((source, name)) = adoption {
+                if let Err(problem) = layout.adopt(&source, &name, &now) {
+                    return Err(Error::UnableToAdoptBackup { path: source, problem });
+                }
+                layout = BackupLayout::new(restore_dir.clone(), &config);
+            }
 
             let restorable_names = layout.restorable_games();
 
-            if backup.is_some() && games.len() != 1 {
-                return Err(Error::CliBackupIdWithMultipleGames);
-            }
-            let backup_id = backup.as_ref().map(|x| BackupId::Named(x.clone()));
-
-            let games_specified = !games.is_empty();
             let subjects = GameSubjects::new(restorable_names, games, None);
             if !subjects.invalid.is_empty() {
-                reporter.trip_unknown_games(subjects.invalid.clone());
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
                 reporter.print_failure();
-                return Err(Error::CliUnrecognizedGames {
-                    games: subjects.invalid,
-                });
+                return Err(Error::CliUnrecognizedGames { games: unknown });
             }
 
-            let cloud_sync = negatable_flag(
-                cloud_sync && !preview,
-                no_cloud_sync,
-                config.cloud.synchronize
-                    && !preview
-                    && crate::cloud::validate_cloud_config(&config, &config.cloud.path).is_ok(),
-            );
-            if cloud_sync {
-                let changes = sync_cloud(
-                    &config,
-                    &restore_dir,
-                    &config.cloud.path,
-                    SyncDirection::Upload,
-                    Finality::Preview,
-                    if games_specified { &subjects.valid } else { &[] },
-                );
-                match changes {
-                    Ok(changes) => {
-                        if !changes.is_empty() {
-                            reporter.trip_cloud_conflict();
-                        }
-                    }
-                    Err(_) => {
-                        reporter.trip_cloud_sync_failed();
-                    }
-                }
-            }
-
-            log::info!("beginning restore with {} steps", subjects.valid.len());
-
             let mut info: Vec<_> = subjects
                 .valid
                 .par_iter()
-                .enumerate()
-                .progress_with(scan_progress_bar(subjects.valid.len() as u64))
-                .map(|(i, name)| {
-                    log::trace!("step {i} / {}: {name}", subjects.valid.len());
+                .progress_count(subjects.valid.len() as u64)
+                .map(|name| {
                     let mut layout = layout.game_layout(name);
-                    let scan_info = layout.scan_for_restoration(
-                        name,
-                        backup_id.as_ref().unwrap_or(&BackupId::Latest),
-                        &config.redirects,
-                        &config.restore.toggled_paths,
-                        &config.restore.toggled_registry,
-                    );
-                    let ignored = !&config.is_game_enabled_for_restore(name) && !games_specified;
-                    let decision = if ignored {
-                        OperationStepDecision::Ignored
-                    } else {
-                        OperationStepDecision::Processed
-                    };
-
-                    if let Some(backup) = &backup {
-                        if let Some(BackupId::Named(scanned_backup)) = scan_info.backup.as_ref().map(|x| x.id()) {
-                            if backup != &scanned_backup {
-                                log::trace!("step {i} completed (backup mismatch)");
-                                return (
-                                    name,
-                                    scan_info,
-                                    Default::default(),
-                                    decision,
-                                    Some(Err(Error::CliInvalidBackupId)),
-                                );
-                            }
-                        }
-                    }
-
-                    let restore_info = if scan_info.backup.is_none() || preview || ignored {
-                        crate::scan::BackupInfo::default()
-                    } else {
-                        layout.restore(&scan_info, &config.restore.toggled_registry)
-                    };
-                    log::trace!("step {i} completed");
-                    (name, scan_info, restore_info, decision, None)
+                    let backups: Vec<_> = layout
+                        .get_backups()
+                        .into_iter()
+                        .filter(|backup| filter.includes(backup))
+                        .map(|backup| {
+                            let bytes = layout.backup_bytes(&backup);
+                            (backup, bytes)
+                        })
+                        .collect();
+                    (name, backups)
                 })
                 .collect();
-            log::info!("completed restore");
 
-            for (_, scan_info, _, _, failure) in info.iter() {
-                if !scan_info.can_report_game() {
-                    continue;
-                }
-                if let Some(failure) = failure {
-                    return failure.clone();
-                }
-                duplicate_detector.add_game(
-                    scan_info,
-                    config.is_game_enabled_for_operation(&scan_info.game_name, true),
-                );
+            let sort = choose_sort(
+                sort,
+                reverse,
+                &Sort {
+                    key: SortKey::Name,
+                    reversed: false,
+                },
+            );
+            let total_bytes = |backups: &[(Backup, u64)]| backups.iter().map(|(_, bytes)| bytes).sum::<u64>();
+            info.sort_by(|(name1, backups1), (name2, backups2)| match sort.key {
+                SortKey::Size => total_bytes(backups1)
+                    .cmp(&total_bytes(backups2))
+                    .then_with(|| name1.cmp(name2)),
+                SortKey::Name | SortKey::Status => name1.cmp(name2),
+            });
+            if sort.reversed {
+                info.reverse();
             }
 
-            let sort = sort.map(From::from).unwrap_or_else(|| config.restore.sort.clone());
-            info.sort_by(|(_, scan_info1, backup_info1, ..), (_, scan_info2, backup_info2, ..)| {
-                crate::scan::compare_games(sort.key, scan_info1, Some(backup_info1), scan_info2, Some(backup_info2))
-            });
-            if sort.reversed {
-                info.reverse();
+            for (name, backups) in info {
+                reporter.add_backups(name, &backups);
+            }
+            reporter.print(&restore_dir)?;
+        }
+        Subcommand::Prune {
+            path,
+            api,
+            preview,
+            keep_full,
+            keep_differential,
+            newer_than,
+            older_than,
+            trigger,
+            allow_latest,
+            games,
+        } => {
+            let mut reporter = Reporter::new(api, report_options);
+            reporter.suppress_overall();
+
+            let now = chrono::Utc::now();
+            let rules = PruneRules {
+                keep_full,
+                keep_differential,
+                filter: BackupFilter {
+                    newer_than: newer_than.map(|x| x.resolve(&now)),
+                    older_than: older_than.map(|x| x.resolve(&now)),
+                    trigger,
+                },
+                allow_latest,
+            };
+
+            let restore_dir = match path {
+                None => config.restore.path.clone(),
+                Some(p) => p,
+            };
+
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
+
+            let subjects = GameSubjects::new(layout.restorable_games(), games, None);
+            if !subjects.invalid.is_empty() {
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
+                reporter.print_failure();
+                return Err(Error::CliUnrecognizedGames { games: unknown });
             }
 
-            for (name, scan_info, backup_info, decision, _) in info {
-                if !reporter.add_game(name, &scan_info, &backup_info, &decision, &duplicate_detector) {
-                    failed = true;
-                }
+            let info: Vec<_> = subjects
+                .valid
+                .par_iter()
+                .progress_count(subjects.valid.len() as u64)
+                .map(|name| {
+                    let mut layout = layout.game_layout(name);
+                    (name, layout.prune(&rules, preview))
+                })
+                .collect();
+
+            for (name, pruned) in info {
+                reporter.add_deleted_backups(name, &pruned, preview);
             }
-            reporter.print(&restore_dir);
+            reporter.print(&restore_dir)?;
         }
-        Subcommand::Complete { shell } => {
-            let clap_shell = match shell {
-                CompletionShell::Bash => clap_complete::Shell::Bash,
-                CompletionShell::Fish => clap_complete::Shell::Fish,
-                CompletionShell::Zsh => clap_complete::Shell::Zsh,
-                CompletionShell::PowerShell => clap_complete::Shell::PowerShell,
-                CompletionShell::Elvish => clap_complete::Shell::Elvish,
-            };
-            clap_complete::generate(
-                clap_shell,
-                &mut Cli::command(),
-                env!("CARGO_PKG_NAME"),
-                &mut std::io::stdout(),
-            )
-        }
-        Subcommand::Backups { path, api, games } => {
+        Subcommand::Verify {
+            path,
+            api,
+            backup,
+            games,
+        } => {
             let games = parse_games(games);
 
-            let mut reporter = if api { Reporter::json() } else { Reporter::standard() };
-            reporter.suppress_overall();
+            let mut reporter = Reporter::new(api, report_options);
 
             let restore_dir = match path {
                 None => config.restore.path.clone(),
                 Some(p) => p,
             };
 
-            let layout = BackupLayout::new(restore_dir.clone(), config.backup.retention.clone());
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
 
-            let restorable_names = layout.restorable_games();
+            if backup.is_some() && games.len() != 1 {
+                return Err(Error::CliBackupIdWithMultipleGames);
+            }
 
-            let subjects = GameSubjects::new(restorable_names, games, None);
+            let subjects = GameSubjects::new(layout.restorable_games(), games, None);
             if !subjects.invalid.is_empty() {
-                reporter.trip_unknown_games(subjects.invalid.clone());
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
                 reporter.print_failure();
-                return Err(Error::CliUnrecognizedGames {
-                    games: subjects.invalid,
-                });
+                return Err(Error::CliUnrecognizedGames { games: unknown });
+            }
+
+            if let Some(backup) = &backup {
+                let id = BackupId::Named(backup.clone());
+                for name in &subjects.valid {
+                    let layout = layout.game_layout(name);
+                    if layout.find_by_id(&id).is_none() {
+                        return Err(Error::CliInvalidBackupId {
+                            available: layout.describe_backups(),
+                        });
+                    }
+                }
             }
 
             let info: Vec<_> = subjects
@@ -565,16 +1186,141 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                 .par_iter()
                 .progress_count(subjects.valid.len() as u64)
                 .map(|name| {
-                    let mut layout = layout.game_layout(name);
-                    let backups = layout.get_backups();
-                    (name, backups)
+                    let (scan_info, backup_info) = layout.game_layout(name).verify(backup.as_deref());
+                    (name, scan_info, backup_info)
                 })
                 .collect();
 
-            for (name, backups) in info {
-                reporter.add_backups(name, &backups);
+            for (name, scan_info, backup_info) in info {
+                if !reporter.add_game(
+                    name,
+                    &scan_info,
+                    &backup_info,
+                    &OperationStepDecision::Processed,
+                    &duplicate_detector,
+                    None,
+                ) {
+                    failed = true;
+                }
+            }
+            reporter.print(&restore_dir)?;
+        }
+        Subcommand::Diff {
+            path,
+            api,
+            old,
+            new,
+            game,
+        } => {
+            let restore_dir = match path {
+                None => config.restore.path.clone(),
+                Some(p) => p,
+            };
+
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
+
+            let subjects = GameSubjects::new(layout.restorable_games(), vec![game], None);
+            if !subjects.invalid.is_empty() {
+                let mut reporter = Reporter::new(api, report_options);
+                let unknown =
+                    TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+                reporter.trip_unknown_games(unknown.clone());
+                reporter.print_failure();
+                return Err(Error::CliUnrecognizedGames { games: unknown });
+            }
+            let name = &subjects.valid[0];
+            let game_layout = layout.game_layout(name);
+
+            let Some(old_side) = DiffSide::from_backup(&game_layout, &old) else {
+                return Err(Error::CliInvalidBackupId {
+                    available: game_layout.describe_backups(),
+                });
+            };
+
+            let new_side = match &new {
+                Some(new) => {
+                    let Some(new_side) = DiffSide::from_backup(&game_layout, new) else {
+                        return Err(Error::CliInvalidBackupId {
+                            available: game_layout.describe_backups(),
+                        });
+                    };
+                    new_side
+                }
+                None => {
+                    let mut manifest = load_manifest(
+                        &mut config,
+                        &mut cache,
+                        no_manifest_update,
+                        try_manifest_update,
+                        ignore_invalid_manifest,
+                    )?;
+                    manifest.incorporate_extensions(&config);
+
+                    // Without a manifest entry, there's nothing to scan for.
+                    if !manifest.0.contains_key(name) {
+                        let mut reporter = Reporter::new(api, report_options);
+                        let unknown =
+                            TitleFinder::new(&manifest, &layout).unknown_games(vec![name.clone()], true, false);
+                        reporter.trip_unknown_games(unknown.clone());
+                        reporter.print_failure();
+                        return Err(Error::CliUnrecognizedGames { games: unknown });
+                    }
+
+                    let roots = api::Roots::new(&manifest, &config, &layout, std::slice::from_ref(name));
+                    let scan_info = api::scan_game(&manifest, &config, &roots, &layout, name).unwrap_or_default();
+                    DiffSide::from_scan(&scan_info)
+                }
+            };
+
+            let diff = BackupDiff {
+                game: name.clone(),
+                old,
+                new,
+                ..BackupDiff::compare(&old_side, &new_side)
+            };
+            report_backup_diff(&diff, api);
+        }
+        Subcommand::Stats {
+            path,
+            api,
+            dedup,
+            history,
+            all_games,
+            csv,
+            sort,
+            game,
+        } => {
+            let restore_dir = match path {
+                None => config.restore.path.clone(),
+                Some(p) => p,
+            };
+            let layout = BackupLayout::new(restore_dir, &config);
+
+            if !dedup && !history {
+                let mut report = StorageReport::analyze(&layout);
+                if sort == Some(parse::StatsSort::Size) {
+                    report.sort_by_size();
+                }
+                report_storage(&report, api);
+            }
+            if dedup {
+                report_dedup(&DedupReport::analyze(&layout), api);
+            }
+            if history {
+                // With `--all-games`, there's no specific game, so every game is valid.
+                let requested = if all_games { vec![] } else { game.into_iter().collect() };
+                let subjects = GameSubjects::new(layout.restorable_games(), requested, None);
+                if !subjects.invalid.is_empty() {
+                    return Err(Error::CliUnrecognizedGames {
+                        games: TitleFinder::new(&Manifest::default(), &layout).unknown_games(
+                            subjects.invalid,
+                            false,
+                            true,
+                        ),
+                    });
+                }
+                report_size_history(&SizeHistoryEntry::analyze(&layout, &subjects.valid), api, csv);
             }
-            reporter.print(&restore_dir);
         }
         Subcommand::Find {
             api,
@@ -583,46 +1329,248 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
             restore,
             steam_id,
             gog_id,
+            lutris_id,
             normalized,
+            fuzzy,
+            threshold,
+            limit,
             disabled,
             partial,
+            missing,
+            backup_missing,
+            games_from,
+            strict,
             names,
         } => {
-            let names = parse_games(names);
+            let store_ids = collect_store_ids(steam_id, gog_id, lutris_id);
+            let mut names = if store_ids.is_empty() && games_from.is_none() {
+                parse_games(names)
+            } else {
+                names
+            };
+            let listed = add_games_from(&mut names, games_from.as_ref())?;
 
-            let mut reporter = if api { Reporter::json() } else { Reporter::standard() };
+            let mut reporter = Reporter::new(api, report_options);
             reporter.suppress_overall();
 
-            let mut manifest = load_manifest(&config, &mut cache, no_manifest_update, try_manifest_update)?;
+            let mut manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            reporter.trip_manifest_fallback(config.manifest.loaded_fallback.clone());
 
             manifest.incorporate_extensions(&config);
 
             let restore_dir = match path {
+                None if missing => config.backup.path.clone(),
                 None => config.restore.path.clone(),
                 Some(p) => p,
             };
-            let layout = BackupLayout::new(restore_dir.clone(), config.backup.retention.clone());
+            let layout = BackupLayout::new(restore_dir.clone(), &config);
 
             let title_finder = TitleFinder::new(&manifest, &layout);
-            let found = title_finder.find(
-                &names, &config, &steam_id, &gog_id, normalized, backup, restore, disabled, partial,
-            );
-            reporter.add_found_titles(&found);
 
-            if found.is_empty() {
-                let mut invalid = names;
-                if let Some(steam_id) = steam_id {
-                    invalid.push(steam_id.to_string());
+            if fuzzy {
+                let matches = title_finder.fuzzy_find(
+                    &manifest,
+                    &names,
+                    backup,
+                    restore,
+                    threshold.map(|x| x.0).unwrap_or(DEFAULT_FUZZY_THRESHOLD),
+                    limit.unwrap_or(DEFAULT_FUZZY_LIMIT),
+                );
+                if matches.is_empty() {
+                    let unknown: Vec<_> = names.into_iter().map(UnknownGame::new).collect();
+                    reporter.trip_unknown_games(unknown.clone());
+                    reporter.print_failure();
+                    return Err(Error::CliUnrecognizedGames { games: unknown });
                 }
-                if let Some(gog_id) = gog_id {
-                    invalid.push(gog_id.to_string());
+
+                reporter.add_fuzzy_matches(&matches);
+                reporter.print(&restore_dir)?;
+                return Ok(());
+            }
+
+            // The first ID that matches wins, and the names are only checked if none do.
+            let (by_id, unknown_ids) = match resolve_store_ids(&title_finder, &store_ids, backup, restore) {
+                Ok(x) => x,
+                Err(e) => {
+                    reporter.print_failure();
+                    return Err(e);
+                }
+            };
+            let matched_id = by_id.into_iter().next();
+
+            let mut found = match &matched_id {
+                Some((name, _)) => BTreeSet::from([name.clone()]),
+                None if names.is_empty() && !unknown_ids.is_empty() => BTreeSet::new(),
+                None => title_finder.find(
+                    &names, &config, &None, &None, normalized, backup, restore, disabled, partial,
+                ),
+            };
+
+            // An empty result is fine here, since it just means that everything has been backed up.
+            if missing {
+                let roots = api::Roots::new(&manifest, &config, &layout, &[]);
+                let installed = roots.installed_games();
+                found.retain(|name| {
+                    installed.contains(name) && config.is_game_enabled_for_backup(name) && !layout.has_backups(name)
+                });
+
+                if backup_missing && !found.is_empty() {
+                    // This goes through the regular backup command so that it respects
+                    // the same config settings (filters, redirects, format, cloud sync, etc).
+                    return run(
+                        Subcommand::Backup {
+                            games: found.into_iter().collect(),
+                            force: true,
+                            path: Some(restore_dir),
+                            api,
+                            strict: true,
+                            preview: Default::default(),
+                            merge: Default::default(),
+                            no_merge: Default::default(),
+                            update: Default::default(),
+                            try_update: Default::default(),
+                            wine_prefix: Default::default(),
+                            sort: Default::default(),
+                            reverse: Default::default(),
+                            format: Default::default(),
+                            compression: Default::default(),
+                            compression_level: Default::default(),
+                            full_limit: Default::default(),
+                            differential_limit: Default::default(),
+                            cloud_sync: Default::default(),
+                            no_cloud_sync: Default::default(),
+                            allow_metered: Default::default(),
+                            repair: Default::default(),
+                            retry_locked: Default::default(),
+                            skip_running: Default::default(),
+                            force_game: Default::default(),
+                            if_changed: Default::default(),
+                            if_changed_since: Default::default(),
+                            if_changed_depth: Default::default(),
+                            no_redirects: Default::default(),
+                            redirect: Default::default(),
+                            comment: Default::default(),
+                            lock: Default::default(),
+                            scheduled: Default::default(),
+                            trigger: Default::default(),
+                            verbose: Default::default(),
+                            full_file_list: Default::default(),
+                            only_changed: Default::default(),
+                            only_failed: Default::default(),
+                            fail_on_change: Default::default(),
+                            output_file: Default::default(),
+                            use_daemon: Default::default(),
+                            exclude: Default::default(),
+                            include: Default::default(),
+                            steam_id: Default::default(),
+                            gog_id: Default::default(),
+                            lutris_id: Default::default(),
+                            games_from: Default::default(),
+                        },
+                        true,
+                        false,
+                        ignore_invalid_manifest,
+                        report_options,
+                    );
+                } else {
+                    reporter.add_found_titles(&found);
+                }
+            } else {
+                match &matched_id {
+                    Some((name, id)) => reporter.add_found_by_store_id(name, id),
+                    None => reporter.add_found_titles(&found),
+                }
+
+                if found.is_empty() {
+                    let mut unknown = title_finder.unknown_games(names, backup, restore);
+                    unknown.extend(unknown_ids);
+                    reporter.trip_unknown_games(unknown.clone());
+                    reporter.print_failure();
+                    return Err(Error::CliUnrecognizedGames { games: unknown });
+                }
+
+                // Other names don't need to match, since they're alternatives for the same lookup,
+                // but each title from `--games-from` is meant to be a separate game.
+                let unmatched: Vec<_> = listed
+                    .into_iter()
+                    .filter(|name| {
+                        matched_id.is_none()
+                            && title_finder
+                                .find(
+                                    std::slice::from_ref(name),
+                                    &config,
+                                    &None,
+                                    &None,
+                                    normalized,
+                                    backup,
+                                    restore,
+                                    disabled,
+                                    partial,
+                                )
+                                .is_empty()
+                    })
+                    .collect();
+                if !unmatched.is_empty() {
+                    let unknown = title_finder.unknown_games(unmatched, backup, restore);
+                    reporter.trip_unknown_games(unknown.clone());
+                    if strict {
+                        reporter.print_failure();
+                        return Err(Error::CliUnrecognizedGames { games: unknown });
+                    }
+                    // The standard output only lists the titles that were found, so that it's easy to parse.
+                    if !api {
+                        eprintln!(
+                            "{}",
+                            TRANSLATOR.prefix_warning(&TRANSLATOR.cli_unrecognized_games(&unknown))
+                        );
+                    }
                 }
-                reporter.trip_unknown_games(invalid.clone());
-                reporter.print_failure();
-                return Err(Error::CliUnrecognizedGames { games: invalid });
             }
 
-            reporter.print(&restore_dir);
+            reporter.print(&restore_dir)?;
+        }
+        Subcommand::Explain { api, game } => {
+            let mut manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            manifest.incorporate_extensions(&config);
+
+            let name = match manifest.0.get(&game).and_then(|x| x.alias.clone()) {
+                Some(target) if manifest.0.contains_key(&target) => target,
+                _ => game,
+            };
+            let layout = BackupLayout::new(config.restore.path.clone(), &config);
+            let Some(entry) = manifest.0.get(&name) else {
+                return Err(Error::CliUnrecognizedGames {
+                    games: TitleFinder::new(&manifest, &layout).unknown_games(vec![name], true, false),
+                });
+            };
+
+            let roots = config.expanded_roots();
+            let title_finder = TitleFinder::new(&manifest, &layout);
+            let launchers = Launchers::scan(&roots, &manifest, std::slice::from_ref(&name), &title_finder, None);
+
+            let explanation = Explanation::new(
+                &name,
+                entry,
+                &config,
+                &roots,
+                &StrictPath::from_std_path_buf(&app_dir()),
+                &launchers,
+                &None,
+                &SteamShortcuts::scan(),
+            );
+            report_explanation(&explanation, api);
         }
         Subcommand::Manifest { sub: manifest_sub } => match manifest_sub {
             ManifestSubcommand::Show { api } => {
@@ -635,8 +1583,196 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                     println!("{}", serde_yaml::to_string(&manifest).unwrap());
                 }
             }
-            ManifestSubcommand::Update { force } => {
-                Manifest::update_mut(&config, &mut cache, force)?;
+            ManifestSubcommand::Update { force, repair } => {
+                if repair {
+                    // Without the ETag, the server will send the full manifest again.
+                    for cached in cache.manifests.values_mut() {
+                        cached.etag = None;
+                    }
+                }
+                Manifest::update_mut(&config, &mut cache, force || repair)?;
+
+                let layout = BackupLayout::new(config.restore.path.clone(), &config);
+                let backups = layout.restorable_games().into_iter().collect();
+                let dangling = config.dangling_titles(&Manifest::load()?, &backups);
+                if !dangling.is_empty() {
+                    eprintln!("{}", TRANSLATOR.dangling_titles_found(dangling.len()));
+                }
+            }
+        },
+        Subcommand::Config { sub } => match sub {
+            parse::ConfigSubcommand::Migrate { preview, api } => {
+                let (migrated, changes) = Config::load_with_changes()?;
+                report_config_changes(&changes, api);
+                if !preview && !changes.is_empty() {
+                    migrated.save();
+                }
+            }
+            parse::ConfigSubcommand::Validate { api } => {
+                let dangling = find_dangling_titles(
+                    &mut config,
+                    &mut cache,
+                    no_manifest_update,
+                    try_manifest_update,
+                    ignore_invalid_manifest,
+                )?;
+                report_dangling_titles(&dangling, api);
+            }
+            parse::ConfigSubcommand::PruneAliases { apply, api } => {
+                let dangling = find_dangling_titles(
+                    &mut config,
+                    &mut cache,
+                    no_manifest_update,
+                    try_manifest_update,
+                    ignore_invalid_manifest,
+                )?;
+                report_dangling_titles(&dangling, api);
+                if apply && dangling.iter().any(|x| x.prunable()) {
+                    config.prune_dangling_titles(&dangling);
+                    config.save();
+                }
+            }
+            parse::ConfigSubcommand::ExportCustom { output } => {
+                let text = serde_yaml::to_string(&config.export_shared()).unwrap();
+                write_report(text.trim_end(), output.as_ref(), false)?;
+            }
+            parse::ConfigSubcommand::ImportCustom {
+                file,
+                merge: _,
+                replace,
+                api,
+            } => {
+                let shared = std::fs::read_to_string(file.as_std_path_buf())
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| SharedConfig::parse(&content))
+                    .map_err(|why| Error::CliUnableToImportConfig {
+                        path: file.clone(),
+                        why,
+                    })?;
+                let mode = if replace {
+                    ImportMode::Replace
+                } else {
+                    ImportMode::Merge
+                };
+
+                let changes = config.import_shared(shared, mode);
+                if changes
+                    .iter()
+                    .any(|x| matches!(x.outcome, ImportOutcome::Added | ImportOutcome::Updated))
+                {
+                    config
+                        .try_save()
+                        .map_err(|e| Error::ConfigCannotBeSaved { why: e.to_string() })?;
+                }
+                report_import_changes(&changes, api);
+            }
+        },
+        Subcommand::Roots { sub } => match sub {
+            parse::RootsSubcommand::List { api } => {
+                report_roots(&config.roots, &config.redundant_roots(), api);
+            }
+            parse::RootsSubcommand::Dedupe { apply, api } => {
+                let redundant = config.redundant_roots();
+                report_redundant_roots(&redundant, api);
+                if apply && !redundant.is_empty() {
+                    config.remove_redundant_roots(&redundant);
+                    config.save();
+                }
+            }
+            parse::RootsSubcommand::Add { store, path, force } => {
+                let root = RootsConfig { path, store };
+                if config.find_duplicate_root(&root).is_some() {
+                    return Err(Error::CliRootAlreadyConfigured { path: root.path });
+                }
+                if !force && root.glob().is_empty() {
+                    return Err(Error::CliRootDoesNotExist { path: root.path });
+                }
+                config.roots.push(root);
+                config
+                    .try_save()
+                    .map_err(|e| Error::ConfigCannotBeSaved { why: e.to_string() })?;
+            }
+            parse::RootsSubcommand::Remove { root } => {
+                let removed = config.remove_roots(&root);
+                if removed.is_empty() {
+                    return Err(Error::CliUnrecognizedRoot { root });
+                }
+                config
+                    .try_save()
+                    .map_err(|e| Error::ConfigCannotBeSaved { why: e.to_string() })?;
+                report_removed_roots(&removed);
+            }
+        },
+        Subcommand::CustomGames { sub } => {
+            // This is only needed to tell which custom games replace a manifest entry,
+            // so custom games themselves are not incorporated.
+            let manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+
+            match sub {
+                parse::CustomGamesSubcommand::List { api } => {
+                    report_custom_games(&config.custom_games, |name| manifest.0.contains_key(name), api);
+                }
+                parse::CustomGamesSubcommand::Add {
+                    files,
+                    registry,
+                    override_manifest,
+                    name,
+                } => {
+                    if config.is_game_customized(&name) {
+                        return Err(Error::CliCustomGameAlreadyExists { name });
+                    }
+                    if manifest.0.contains_key(&name) && !override_manifest {
+                        return Err(Error::CliCustomGameInManifest { name });
+                    }
+                    for path in &files {
+                        let placeholders = placeholder::find_unknown(path);
+                        if !placeholders.is_empty() {
+                            return Err(Error::CliUnknownPlaceholders {
+                                path: path.clone(),
+                                placeholders,
+                            });
+                        }
+                    }
+
+                    config.custom_games.push(CustomGame {
+                        name,
+                        ignore: false,
+                        alias: None,
+                        files,
+                        registry,
+                    });
+                    config
+                        .try_save()
+                        .map_err(|e| Error::ConfigCannotBeSaved { why: e.to_string() })?;
+                }
+                parse::CustomGamesSubcommand::Remove { name } => {
+                    if !config.is_game_customized(&name) {
+                        return Err(Error::CliUnrecognizedGames {
+                            games: vec![UnknownGame::new(name)],
+                        });
+                    }
+                    config.custom_games.retain(|x| x.name != name);
+                    config
+                        .try_save()
+                        .map_err(|e| Error::ConfigCannotBeSaved { why: e.to_string() })?;
+                }
+            }
+        }
+        Subcommand::Schema { sub } => match sub {
+            parse::SchemaSubcommand::Api => {
+                report_api_schema();
+            }
+            parse::SchemaSubcommand::Config => {
+                report_schema::<Config>();
+            }
+            parse::SchemaSubcommand::Manifest => {
+                report_schema::<Manifest>();
             }
         },
         Subcommand::Cloud { sub: cloud_sub } => match cloud_sub {
@@ -738,6 +1874,8 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                 force,
                 preview,
                 api,
+                merged,
+                fail_on_change,
                 games,
             } => {
                 let games = parse_games(games);
@@ -756,8 +1894,25 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                     return Ok(());
                 }
 
-                let changes = sync_cloud(&config, &local, &cloud, direction, finality, &games)?;
-                report_cloud_changes(&changes, api);
+                let changes = sync_cloud(
+                    &config,
+                    &mut cache,
+                    &local,
+                    &cloud,
+                    direction,
+                    finality,
+                    &games,
+                    report_options.progress,
+                )?;
+                if merged {
+                    let mut reporter = Reporter::new(api, report_options);
+                    reporter.suppress_overall();
+                    reporter.add_cloud_changes(&changes, finality.preview());
+                    reporter.print(&local)?;
+                } else {
+                    report_cloud_changes(&changes, api, report_options.api_version);
+                }
+                changes_pending = fail_on_change && !changes.is_empty();
             }
             parse::CloudSubcommand::Download {
                 local,
@@ -765,6 +1920,7 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                 force,
                 preview,
                 api,
+                merged,
                 games,
             } => {
                 let games = parse_games(games);
@@ -783,17 +1939,209 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                     return Ok(());
                 }
 
-                let changes = sync_cloud(&config, &local, &cloud, direction, finality, &games)?;
-                report_cloud_changes(&changes, api);
+                let changes = sync_cloud(
+                    &config,
+                    &mut cache,
+                    &local,
+                    &cloud,
+                    direction,
+                    finality,
+                    &games,
+                    report_options.progress,
+                )?;
+                if merged {
+                    let mut reporter = Reporter::new(api, report_options);
+                    reporter.suppress_overall();
+                    reporter.add_cloud_changes(&changes, finality.preview());
+                    reporter.print(&local)?;
+                } else {
+                    report_cloud_changes(&changes, api, report_options.api_version);
+                }
+            }
+            parse::CloudSubcommand::History { since, api } => {
+                let since = since.map(|x| x.resolve(&chrono::Utc::now()));
+                let records = crate::cloud::history::History::default().load();
+                report_cloud_history(&crate::cloud::history::Summary::new(&records, since), api);
             }
         },
+        Subcommand::History {
+            operation,
+            since,
+            until,
+            last,
+            api,
+        } => {
+            let now = chrono::Utc::now();
+            let filter = history::Filter {
+                operations: operation,
+                since: since.map(|x| x.resolve(&now)),
+                until: until.map(|x| x.resolve(&now)),
+                last,
+            };
+            report_history(&filter.apply(history::History::default().load()), api);
+        }
+        Subcommand::Serve { bind } => {
+            crate::serve::serve(&bind, LastOperationsFile::default())?;
+        }
+        Subcommand::Daemon => {
+            let manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            daemon::serve(
+                daemon::Endpoint::default(),
+                manifest,
+                config.manifest.loaded_hash.clone(),
+            )?;
+        }
+        Subcommand::Api => {
+            let mut manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            manifest.incorporate_extensions(&config);
+            requests::run(manifest, config)?;
+        }
+        Subcommand::Watch {
+            path,
+            debounce,
+            poll,
+            poll_interval,
+            api,
+            games,
+        } => {
+            let mut manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            manifest.incorporate_extensions(&config);
+
+            let backup_dir = path.clone().unwrap_or_else(|| config.backup.path.clone());
+            let layout = BackupLayout::new(backup_dir.clone(), &config);
+
+            let subjects = if games.is_empty() {
+                let mut known = layout.restorable_games();
+                known.retain(|name| manifest.0.contains_key(name) && config.is_game_enabled_for_backup(name));
+                GameSubjects::new(known, vec![], None)
+            } else {
+                GameSubjects::new(manifest.0.keys().cloned().collect(), games, Some(&manifest.aliases()))
+            };
+            if !subjects.invalid.is_empty() {
+                let mut reporter = Reporter::new(api, report_options);
+                let unknown = TitleFinder::new(&manifest, &layout).unknown_games(subjects.invalid, true, false);
+                reporter.trip_unknown_games(unknown.clone());
+                reporter.print_failure();
+                return Err(Error::CliUnrecognizedGames { games: unknown });
+            }
+
+            let roots = api::Roots::new(&manifest, &config, &layout, &subjects.valid);
+            let locations: BTreeMap<_, _> = subjects
+                .valid
+                .iter()
+                .map(|name| (name.clone(), api::save_locations(&manifest, &config, &roots, name)))
+                .filter(|(_, locations)| !locations.is_empty())
+                .collect();
+            if !api {
+                eprintln!(
+                    "{}",
+                    TRANSLATOR.cli_watching(locations.len(), locations.values().map(|x| x.len()).sum())
+                );
+            }
+
+            let options = watch::Options {
+                debounce: Duration::from_secs(debounce),
+                poll: poll.then(|| Duration::from_secs(poll_interval)),
+                backup_dir,
+            };
+            watch::run(&locations, &options, &mut |name| {
+                // Each backup reloads the config, but there's no need to check for manifest updates again.
+                let result = run(
+                    Subcommand::Backup {
+                        games: vec![name.to_string()],
+                        force: true,
+                        path: path.clone(),
+                        api,
+                        trigger: Some(Trigger::new(TriggerSource::Watch)),
+                        preview: Default::default(),
+                        merge: Default::default(),
+                        no_merge: Default::default(),
+                        update: Default::default(),
+                        try_update: Default::default(),
+                        wine_prefix: Default::default(),
+                        sort: Default::default(),
+                        reverse: Default::default(),
+                        format: Default::default(),
+                        compression: Default::default(),
+                        compression_level: Default::default(),
+                        full_limit: Default::default(),
+                        differential_limit: Default::default(),
+                        cloud_sync: Default::default(),
+                        no_cloud_sync: Default::default(),
+                        allow_metered: Default::default(),
+                        repair: Default::default(),
+                        retry_locked: Default::default(),
+                        skip_running: Default::default(),
+                        force_game: Default::default(),
+                        if_changed: Default::default(),
+                        if_changed_since: Default::default(),
+                        if_changed_depth: Default::default(),
+                        no_redirects: Default::default(),
+                        redirect: Default::default(),
+                        comment: Default::default(),
+                        lock: Default::default(),
+                        scheduled: Default::default(),
+                        verbose: Default::default(),
+                        full_file_list: Default::default(),
+                        only_changed: Default::default(),
+                        only_failed: Default::default(),
+                        fail_on_change: Default::default(),
+                        output_file: Default::default(),
+                        use_daemon: Default::default(),
+                        exclude: Default::default(),
+                        include: Default::default(),
+                        steam_id: Default::default(),
+                        gog_id: Default::default(),
+                        lutris_id: Default::default(),
+                        games_from: Default::default(),
+                        strict: Default::default(),
+                    },
+                    true,
+                    false,
+                    ignore_invalid_manifest,
+                    report_options,
+                );
+                match result {
+                    // The report already shows which files failed.
+                    Ok(()) | Err(Error::SomeEntriesFailed) => {}
+                    Err(e) => {
+                        log::error!("[{name}] watch backup failed: {e:?}");
+                        eprintln!("{}", TRANSLATOR.handle_error(&e));
+                    }
+                }
+            })?;
+        }
         Subcommand::Wrap {
             name_source,
             gui,
             commands,
         } => {
-            let manifest = load_manifest(&config, &mut cache, no_manifest_update, try_manifest_update)?;
-            let layout = BackupLayout::new(config.restore.path.clone(), config.backup.retention.clone());
+            let manifest = load_manifest(
+                &mut config,
+                &mut cache,
+                no_manifest_update,
+                try_manifest_update,
+                ignore_invalid_manifest,
+            )?;
+            let layout = BackupLayout::new(config.restore.path.clone(), &config);
             let title_finder = TitleFinder::new(&manifest, &layout);
 
             // Determine raw game identifiers
@@ -862,16 +2210,43 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                     Subcommand::Restore {
                         games: vec![game_name.clone()],
                         force: true,
+                        yes: Default::default(),
                         preview: Default::default(),
                         path: Default::default(),
                         api: Default::default(),
                         sort: Default::default(),
+                        reverse: Default::default(),
                         backup: Default::default(),
+                        only_path: Default::default(),
                         cloud_sync: Default::default(),
                         no_cloud_sync: Default::default(),
+                        no_redirects: Default::default(),
+                        redirect: Default::default(),
+                        migrate_paths: Default::default(),
+                        prefer_account: Default::default(),
+                        registry_into_hive: Default::default(),
+                        with_files: Default::default(),
+                        verbose: Default::default(),
+                        full_file_list: Default::default(),
+                        only_changed: Default::default(),
+                        only_failed: Default::default(),
+                        output_file: Default::default(),
+                        use_daemon: Default::default(),
+                        exclude: Default::default(),
+                        include: Default::default(),
+                        no_clobber_newer: Default::default(),
+                        backup_before_restore: Default::default(),
+                        no_backup_before_restore: Default::default(),
+                        steam_id: Default::default(),
+                        gog_id: Default::default(),
+                        lutris_id: Default::default(),
+                        games_from: Default::default(),
+                        strict: Default::default(),
                     },
                     no_manifest_update,
                     try_manifest_update,
+                    ignore_invalid_manifest,
+                    report_options,
                 ) {
                     log::error!("WRAP::restore: failed for game {:?} with: {:?}", wrap_game_info, err);
                     ui::alert_with_error(gui, &TRANSLATOR.restore_one_game_failed(game_name), &err)?;
@@ -918,6 +2293,7 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                         wine_prefix: Default::default(),
                         api: Default::default(),
                         sort: Default::default(),
+                        reverse: Default::default(),
                         format: Default::default(),
                         compression: Default::default(),
                         compression_level: Default::default(),
@@ -925,9 +2301,45 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
                         differential_limit: Default::default(),
                         cloud_sync: Default::default(),
                         no_cloud_sync: Default::default(),
+                        allow_metered: Default::default(),
+                        repair: Default::default(),
+                        retry_locked: Default::default(),
+                        skip_running: Default::default(),
+                        force_game: Default::default(),
+                        if_changed: Default::default(),
+                        if_changed_since: Default::default(),
+                        if_changed_depth: Default::default(),
+                        no_redirects: Default::default(),
+                        redirect: Default::default(),
+                        comment: Default::default(),
+                        lock: Default::default(),
+                        scheduled: Default::default(),
+                        trigger: Some(
+                            Trigger::new(TriggerSource::Wrap).with_executable(
+                                Path::new(&commands[0])
+                                    .file_name()
+                                    .map(|x| x.to_string_lossy().to_string()),
+                            ),
+                        ),
+                        verbose: Default::default(),
+                        full_file_list: Default::default(),
+                        only_changed: Default::default(),
+                        only_failed: Default::default(),
+                        fail_on_change: Default::default(),
+                        output_file: Default::default(),
+                        use_daemon: Default::default(),
+                        exclude: Default::default(),
+                        include: Default::default(),
+                        steam_id: Default::default(),
+                        gog_id: Default::default(),
+                        lutris_id: Default::default(),
+                        games_from: Default::default(),
+                        strict: Default::default(),
                     },
                     no_manifest_update,
                     try_manifest_update,
+                    ignore_invalid_manifest,
+                    report_options,
                 ) {
                     log::error!("WRAP::backup: failed with: {:#?}", err);
                     ui::alert_with_error(gui, &TRANSLATOR.back_up_one_game_failed(game_name), &err)?;
@@ -938,6 +2350,10 @@ pub fn run(sub: Subcommand, no_manifest_update: bool, try_manifest_update: bool)
     }
     if failed {
         Err(Error::SomeEntriesFailed)
+    } else if changes_pending {
+        Err(Error::ChangesPending)
+    } else if nothing_changed {
+        Err(Error::NothingChanged)
     } else {
         Ok(())
     }
@@ -968,7 +2384,56 @@ fn ask(question: String, finality: Finality, force: bool) -> Result<bool, Error>
     }
 }
 
-fn scan_progress_bar(length: u64) -> ProgressBar {
+/// Restoring without specifying any games covers everything in the backup folder,
+/// so show what that means and make sure it's intended.
+/// This returns `None` if there aren't enough games to need the extra confirmation.
+/// The summary and prompt go to stderr so that they don't interfere with `--api` output.
+fn confirm_restoring_many_games(config: &Config, restore_dir: &StrictPath, yes: bool) -> Result<Option<bool>, Error> {
+    use std::io::IsTerminal;
+
+    let layout = BackupLayout::new(restore_dir.clone(), config);
+    let games: Vec<_> = layout
+        .restorable_games()
+        .into_iter()
+        .filter(|name| config.is_game_enabled_for_restore(name))
+        .collect();
+    if games.len() <= config.restore.confirm_when_games_exceed {
+        return Ok(None);
+    }
+    if yes {
+        return Ok(Some(true));
+    }
+
+    let bytes: u64 = games
+        .iter()
+        .flat_map(|name| {
+            layout
+                .game_layout(name)
+                .latest_files()
+                .into_iter()
+                .map(|(_, file)| file.size)
+                .collect::<Vec<_>>()
+        })
+        .sum();
+    eprintln!("{}", TRANSLATOR.restore_many_games_summary(games.len(), bytes));
+
+    let needs_confirmation = Error::CliRestoreNeedsConfirmation { games: games.len() };
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(needs_confirmation);
+    }
+    dialoguer::Confirm::new()
+        .with_prompt(TRANSLATOR.confirm_restore(restore_dir, false))
+        .interact()
+        .map(Some)
+        .map_err(|_| needs_confirmation)
+}
+
+/// `progress` comes from `ReportOptions::progress`.
+fn scan_progress_bar(length: u64, progress: bool) -> ProgressBar {
+    // The bar would get mixed up with the progress events on stderr.
+    if progress {
+        return ProgressBar::hidden();
+    }
     let template = format!(
         "{} ({{elapsed_precise}}) {{wide_bar}} {}: {{pos}} / {{len}}",
         TRANSLATOR.scan_label(),
@@ -978,7 +2443,10 @@ fn scan_progress_bar(length: u64) -> ProgressBar {
     ProgressBar::new(length).with_style(style)
 }
 
-fn cloud_progress_bar() -> ProgressBar {
+fn cloud_progress_bar(progress: bool) -> ProgressBar {
+    if progress {
+        return ProgressBar::hidden();
+    }
     let template = format!(
         "{} ({{elapsed_precise}}) {{wide_bar}} {{msg}}",
         TRANSLATOR.cloud_label()
@@ -989,11 +2457,13 @@ fn cloud_progress_bar() -> ProgressBar {
 
 fn sync_cloud(
     config: &Config,
+    cache: &mut Cache,
     local: &StrictPath,
     cloud: &str,
     sync: SyncDirection,
     finality: Finality,
     games: &[String],
+    progress: bool,
 ) -> Result<Vec<CloudChange>, Error> {
     match finality {
         Finality::Preview => log::info!("checking cloud sync"),
@@ -1003,22 +2473,71 @@ fn sync_cloud(
     let remote = crate::cloud::validate_cloud_config(config, cloud)?;
 
     let games = if !games.is_empty() {
-        let layout = BackupLayout::new(local.clone(), config.backup.retention.clone());
+        let layout = BackupLayout::new(local.clone(), config);
         let games: Vec<_> = games.iter().filter_map(|x| layout.game_folder(x).leaf()).collect();
         games
     } else {
         vec![]
     };
 
-    let rclone = Rclone::new(config.apps.rclone.clone(), remote);
-    let mut process = match rclone.sync(local, cloud, sync, finality, &games) {
+    let rclone = Rclone::new(config.apps.rclone.clone(), remote)
+        .with_chunking(&config.cloud.chunked_upload)
+        .with_operation_history(&config.history);
+
+    // Large files are uploaded in resumable chunks, separately from the main sync.
+    let mut chunked_changes = vec![];
+    let mut chunked_error = None;
+    let mut skip = vec![];
+    if let (SyncDirection::Upload, Some(threshold)) = (sync, config.cloud.chunked_upload.threshold_bytes()) {
+        for file in chunked::find_large_files(local, &games, threshold) {
+            skip.push(file.path.clone());
+
+            let pending = match chunked::check(&rclone, cloud, &file, cache) {
+                Ok(Some(pending)) => pending,
+                Ok(None) => continue,
+                Err(e) => {
+                    chunked_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            if finality.preview() {
+                chunked_changes.push(pending.change);
+                continue;
+            }
+
+            log::info!("uploading large file in chunks: {}", &file.path);
+            let progress_bar = cloud_progress_bar(progress);
+            let uploaded = chunked::upload(&rclone, cloud, &pending, cache, |current, max| {
+                progress::emit(
+                    progress,
+                    progress::Event::Cloud {
+                        direction: sync,
+                        bytes: current,
+                        total_bytes: max,
+                    },
+                );
+                progress_bar.set_length(max);
+                progress_bar.set_position(current);
+                progress_bar.set_message(TRANSLATOR.cloud_progress(current, max))
+            });
+            progress_bar.finish_and_clear();
+            match uploaded {
+                Ok(()) => chunked_changes.push(pending.change),
+                Err(e) => {
+                    chunked_error.get_or_insert(e);
+                }
+            }
+        }
+    }
+
+    let mut process = match rclone.sync(local, cloud, sync, finality, &games, &skip) {
         Ok(p) => p,
         Err(e) => return Err(Error::UnableToSynchronizeCloud(e)),
     };
 
     let interrupted = register_sigint();
 
-    let progress_bar = cloud_progress_bar();
+    let progress_bar = cloud_progress_bar(progress);
     let mut changes = vec![];
     loop {
         if interrupted.load(std::sync::atomic::Ordering::Relaxed) {
@@ -1032,6 +2551,14 @@ fn sync_cloud(
         for event in events {
             match event {
                 crate::cloud::RcloneProcessEvent::Progress { current, max } => {
+                    progress::emit(
+                        progress,
+                        progress::Event::Cloud {
+                            direction: sync,
+                            bytes: current as u64,
+                            total_bytes: max as u64,
+                        },
+                    );
                     progress_bar.set_length(max as u64);
                     progress_bar.set_position(current as u64);
                     progress_bar.set_message(TRANSLATOR.cloud_progress(current as u64, max as u64))
@@ -1044,6 +2571,10 @@ fn sync_cloud(
         match process.succeeded() {
             Some(Ok(_)) => {
                 unregister_sigint();
+                if let Some(e) = chunked_error {
+                    return Err(e);
+                }
+                changes.extend(chunked_changes);
                 return Ok(changes);
             }
             Some(Err(e)) => {
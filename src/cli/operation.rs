@@ -0,0 +1,920 @@
+//! The `backup` and `restore` commands, after their arguments have been settled.
+//! `ludusavi daemon` and `ludusavi api` run these on behalf of their clients,
+//! so every option has a counterpart in the requests here.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    time::Instant,
+};
+
+use indicatif::ParallelProgressIterator;
+use rayon::{
+    iter::{IntoParallelRefIterator, ParallelIterator},
+    prelude::IndexedParallelIterator,
+};
+
+use crate::{
+    api,
+    cli::{
+        negatable_flag, progress, record_last_operation, report::ReportOptions, report::Reporter, scan_progress_bar,
+        snapshot_before_restore, sync_cloud, GameSubjects,
+    },
+    cloud::metered::{self, MeteredDecision},
+    lang::TRANSLATOR,
+    prelude::{Error, Finality, StrictPath, SyncDirection},
+    resource::{
+        cache::Cache,
+        config::{BackupFormat, BackupMode, Config, RedirectConfig, RemapUserProfile, Sort, ZipCompression},
+        manifest::Manifest,
+        SaveableResourceFile,
+    },
+    scan::{
+        layout::BackupLayout, prepare_backup_target, running::ProcessSnapshot, BackupId, BackupInfo, BackupLabel,
+        DuplicateDetector, OperationStepDecision, OsConstraints, PathMigrations, PathOverrides, PathSelection,
+        SafetySnapshot, TitleFinder, Trigger, TriggerSource,
+    },
+    serve::OperationKind,
+};
+
+/// User profile folders from a backup, along with where they would be restored, rendered for display.
+pub type Remaps = BTreeSet<(String, String)>;
+
+/// How deep `--if-changed-since` looks by default.
+pub const DEFAULT_IF_CHANGED_DEPTH: usize = 3;
+
+/// These mirror the CLI options of the same name, which only affect the report.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReportRequest {
+    pub api: bool,
+    /// This already accounts for `--reverse`. If unset, the configured sort is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<Sort>,
+    pub verbose: bool,
+    pub full_file_list: bool,
+    pub only_changed: bool,
+    pub only_failed: bool,
+    /// The client settles `--color` for its own terminal before sending it.
+    pub report: ReportOptions,
+}
+
+impl ReportRequest {
+    pub fn reporter(&self, config: &Config) -> Reporter {
+        let mut reporter = Reporter::new(self.api, self.report);
+        reporter.set_verbose(self.verbose);
+        reporter.set_file_list_limit(if self.full_file_list {
+            None
+        } else {
+            config.scan.file_list_limit
+        });
+        reporter.set_report_hashes(config.scan.report_file_hashes);
+        reporter.set_only_changed(self.only_changed);
+        reporter.set_only_failed(self.only_failed);
+        reporter
+    }
+}
+
+/// These mirror the `backup` command's options of the same name.
+/// Paths should be absolute, since the request may be handled in another working directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackupRequest {
+    pub preview: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<StrictPath>,
+    #[serde(flatten)]
+    pub output: ReportRequest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wine_prefix: Option<StrictPath>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<BackupFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<ZipCompression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub differential_limit: Option<u8>,
+    pub cloud_sync: bool,
+    pub no_cloud_sync: bool,
+    pub allow_metered: bool,
+    pub repair: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_locked: Option<u32>,
+    pub skip_running: bool,
+    pub force_game: Vec<String>,
+    pub if_changed: bool,
+    /// The cutoff for `--if-changed-since`, which the client settles from its own clock.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_changed_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Defaults to `DEFAULT_IF_CHANGED_DEPTH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_changed_depth: Option<usize>,
+    pub no_redirects: bool,
+    /// From `--redirect`, with the kind already settled.
+    pub redirects: Vec<RedirectConfig>,
+    pub exclude: Vec<String>,
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub lock: bool,
+    /// What asked for a backup. Requests without this are treated as coming from the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
+    pub fail_on_change: bool,
+    pub games: Vec<String>,
+    /// Games that came from `--games-from`.
+    /// Unless `strict` is set, these are only reported if they're unknown, rather than failing the request.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub listed: BTreeSet<String>,
+    /// Whether `--games-from` was used, in which case an empty list means no games rather than all of them.
+    pub from_list: bool,
+    pub strict: bool,
+}
+
+/// These mirror the `restore` command's options of the same name.
+/// Paths should be absolute, since the request may be handled in another working directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RestoreRequest {
+    pub preview: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<StrictPath>,
+    pub force: bool,
+    #[serde(flatten)]
+    pub output: ReportRequest,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+    pub only_path: Vec<String>,
+    pub exclude: Vec<String>,
+    pub include: Vec<String>,
+    pub no_clobber_newer: bool,
+    pub backup_before_restore: bool,
+    pub no_backup_before_restore: bool,
+    pub cloud_sync: bool,
+    pub no_cloud_sync: bool,
+    pub no_redirects: bool,
+    /// From `--redirect`, with the kind already settled.
+    pub redirects: Vec<RedirectConfig>,
+    pub migrate_paths: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefer_account: Option<String>,
+    pub games: Vec<String>,
+    /// Games that came from `--games-from`.
+    /// Unless `strict` is set, these are only reported if they're unknown, rather than failing the request.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub listed: BTreeSet<String>,
+    /// Whether `--games-from` was used, in which case an empty list means no games rather than all of them.
+    pub from_list: bool,
+    pub strict: bool,
+}
+
+/// For `restore --registry-into-hive`, which only the CLI itself supports.
+#[derive(Clone, Copy, Debug)]
+pub struct RestoreHive<'a> {
+    /// Where the hive was loaded, which is unset for a preview.
+    pub key: Option<&'a str>,
+    pub with_files: bool,
+}
+
+/// What's left for the caller once the games have been added to the report.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    pub dir: StrictPath,
+    started: chrono::DateTime<chrono::Utc>,
+    /// Unset for a preview or if there turned out to be nothing to do.
+    kind: Option<OperationKind>,
+    pub failed_games: usize,
+    /// For `--fail-on-change`.
+    pub changes_pending: bool,
+    /// For `--if-changed`.
+    pub nothing_changed: bool,
+}
+
+impl Summary {
+    fn new(dir: StrictPath, started: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            dir,
+            started,
+            kind: None,
+            failed_games: 0,
+            changes_pending: false,
+            nothing_changed: false,
+        }
+    }
+
+    /// Keep track of the outcome for `ludusavi serve` and `ludusavi history`.
+    pub fn record(&self, config: &Config, reporter: &Reporter) {
+        if let Some(kind) = self.kind {
+            record_last_operation(
+                config,
+                kind,
+                self.started,
+                reporter,
+                self.failed_games > 0,
+                self.failed_games,
+            );
+        }
+    }
+}
+
+fn invalid_glob(error: globset::Error) -> Error {
+    Error::CliInvalidGlob { why: error.to_string() }
+}
+
+/// Apply `--no-redirects`/`--redirect` for the current operation only.
+/// The config is never saved afterward.
+fn override_redirects(config: &mut Config, disable: bool, extra: &[RedirectConfig]) {
+    if disable {
+        config.redirects.clear();
+    }
+    config.redirects.extend(extra.iter().cloned());
+    if disable || !extra.is_empty() {
+        log::info!("Using redirects for this operation: {:?}", &config.redirects);
+    }
+}
+
+/// The manifest should already incorporate any extensions from the config.
+/// Unknown games are added to the report before returning `Error::CliUnrecognizedGames`.
+pub fn back_up(
+    manifest: &Manifest,
+    config: &mut Config,
+    cache: &mut Cache,
+    request: &BackupRequest,
+    reporter: &mut Reporter,
+) -> Result<Summary, Error> {
+    let started = chrono::Utc::now();
+    let overrides = PathOverrides::new(&request.exclude, &request.include).map_err(invalid_glob)?;
+    override_redirects(config, request.no_redirects, &request.redirects);
+    if let Some(retry_locked) = request.retry_locked {
+        config.backup.retry_locked = retry_locked;
+    }
+    reporter.trip_redundant_roots(&config.redundant_roots());
+
+    let backup_dir = request.path.clone().unwrap_or_else(|| config.backup.path.clone());
+    let mut summary = Summary::new(backup_dir.clone(), started);
+    if !request.preview {
+        prepare_backup_target(&backup_dir)?;
+    }
+
+    let mut games = request.games.clone();
+    let games_specified = !games.is_empty() || request.from_list;
+    let forced = if request.force_game.is_empty() {
+        GameSubjects::default()
+    } else {
+        GameSubjects::new(
+            manifest.0.keys().cloned().collect(),
+            request.force_game.clone(),
+            Some(&manifest.aliases()),
+        )
+    };
+    if games_specified {
+        games.extend(request.force_game.iter().cloned());
+    }
+    let mut subjects = if games.is_empty() && games_specified {
+        GameSubjects::default()
+    } else {
+        GameSubjects::new(manifest.0.keys().cloned().collect(), games, Some(&manifest.aliases()))
+    };
+    if !subjects.invalid.is_empty() || !forced.invalid.is_empty() {
+        let invalid: Vec<_> = subjects
+            .invalid
+            .into_iter()
+            .chain(forced.invalid)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let only_listed = !request.strict && invalid.iter().all(|x| request.listed.contains(x));
+        let layout = BackupLayout::new(backup_dir.clone(), config);
+        let unknown = TitleFinder::new(manifest, &layout).unknown_games(invalid, true, false);
+        reporter.trip_unknown_games(unknown.clone());
+        if !only_listed {
+            return Err(Error::CliUnrecognizedGames { games: unknown });
+        }
+    }
+    if games_specified && subjects.valid.is_empty() {
+        return Ok(summary);
+    }
+    let forced: HashSet<_> = forced.valid.into_iter().collect();
+
+    if config.backup.mode == BackupMode::OnlyEnabledList && !games_specified {
+        let total = subjects.valid.len();
+        subjects
+            .valid
+            .retain(|name| !config.is_game_skipped_for_backup(name) || forced.contains(name));
+        reporter.set_backup_mode(config.backup.mode, total - subjects.valid.len());
+    }
+
+    let mut retention = config.backup.retention.clone();
+    if let Some(full_limit) = request.full_limit {
+        retention.full = full_limit;
+    }
+    if let Some(differential_limit) = request.differential_limit {
+        retention.differential = differential_limit;
+    }
+
+    let layout = BackupLayout::new(backup_dir.clone(), config).with_retention(retention);
+    let roots =
+        api::Roots::new(manifest, config, &layout, &subjects.valid).with_wine_prefix(request.wine_prefix.clone());
+    // This needs to inspect every process, so only do it when it matters.
+    let running_snapshot =
+        ((request.skip_running || config.backup.skip_if_running) && !request.preview).then(ProcessSnapshot::take);
+    if running_snapshot.as_ref().is_some_and(|x| x.is_unsupported()) {
+        reporter.trip_running_check_unavailable();
+    }
+
+    let wants_cloud_sync = negatable_flag(
+        request.cloud_sync,
+        request.no_cloud_sync,
+        config.cloud.synchronize && crate::cloud::validate_cloud_config(config, &config.cloud.path).is_ok(),
+    );
+    let mut cloud_sync = wants_cloud_sync && !request.preview;
+    if cloud_sync {
+        match metered::decide(config.cloud.on_metered, request.allow_metered, metered::detect) {
+            MeteredDecision::Proceed => {}
+            MeteredDecision::Defer | MeteredDecision::Ask => {
+                cloud_sync = false;
+                reporter.trip_cloud_sync_deferred();
+                if !cache.cloud.deferred {
+                    cache.cloud.deferred = true;
+                    cache.save();
+                }
+            }
+        }
+    }
+    // After a deferred sync, there may be pending changes for games outside of this run.
+    let cloud_games: &[String] = if games_specified && !cache.cloud.deferred {
+        &subjects.valid
+    } else {
+        &[]
+    };
+    let mut should_sync_cloud_after = cloud_sync;
+    if cloud_sync {
+        let changes = sync_cloud(
+            config,
+            cache,
+            &backup_dir,
+            &config.cloud.path,
+            SyncDirection::Upload,
+            Finality::Preview,
+            cloud_games,
+            request.output.report.progress,
+        );
+        match changes {
+            Ok(changes) => {
+                // An interrupted chunked upload isn't a conflict, since we'll just resume it.
+                let conflicts: Vec<_> = changes.into_iter().filter(|x| x.resumed_at.is_none()).collect();
+                if !conflicts.is_empty() {
+                    should_sync_cloud_after = false;
+                    reporter.trip_cloud_conflict(&conflicts);
+                }
+            }
+            Err(_) => {
+                should_sync_cloud_after = false;
+                reporter.trip_cloud_sync_failed();
+            }
+        }
+    }
+
+    let mut inconsistent = HashSet::new();
+    if !request.preview {
+        for name in &subjects.valid {
+            if !config.is_game_enabled_for_backup(name) && !games_specified && !forced.contains(name) {
+                continue;
+            }
+            let Some(mut game_layout) = layout.try_game_layout(name) else {
+                continue;
+            };
+            let discrepancies = game_layout.check_consistency();
+            if discrepancies.is_empty() {
+                continue;
+            }
+
+            if request.repair {
+                game_layout.repair(&discrepancies);
+            } else {
+                log::warn!("[{name}] skipping backup because of inconsistencies: {discrepancies:?}");
+                inconsistent.insert(name.clone());
+            }
+            reporter.trip_inconsistent_backup(name, discrepancies, request.repair);
+        }
+    }
+
+    let mut backup_format = config.backup.format.clone();
+    if let Some(format) = request.format {
+        backup_format.chosen = format;
+    }
+    if let Some(compression) = request.compression {
+        backup_format.zip.compression = compression;
+    }
+    if let Some(level) = request.compression_level {
+        backup_format
+            .compression
+            .set_level(&backup_format.zip.compression, level);
+    }
+    let options = api::BackupOptions {
+        include_disabled: games_specified,
+        forced: forced.clone(),
+        format: Some(backup_format),
+        label: Some(BackupLabel {
+            comment: request.comment.clone().filter(|x| !x.is_empty()),
+            locked: request.lock,
+            trigger: request
+                .trigger
+                .clone()
+                .unwrap_or_else(|| Trigger::new(TriggerSource::Api)),
+            manifest_hash: config.manifest.loaded_hash.clone(),
+        }),
+        overrides,
+        if_changed: request.if_changed,
+        if_changed_since: request
+            .if_changed_since
+            .map(|cutoff| (cutoff, request.if_changed_depth.unwrap_or(DEFAULT_IF_CHANGED_DEPTH))),
+        inconsistent,
+        running: running_snapshot,
+    };
+
+    log::info!("beginning backup with {} steps", subjects.valid.len());
+
+    let timer = Instant::now();
+    let progress_bar = scan_progress_bar(subjects.valid.len() as u64, request.output.report.progress);
+    let on_progress = |event: api::Progress| match event {
+        api::Progress::Started { game, index, max } => {
+            log::trace!("step {index} / {max}: {game}");
+            progress::emit(
+                request.output.report.progress,
+                progress::Event::GameStart {
+                    name: game,
+                    index,
+                    total: max,
+                },
+            );
+        }
+        api::Progress::Finished { outcome, .. } => {
+            progress::emit_files(request.output.report.progress, &outcome.scan_info);
+            progress_bar.inc(1);
+            log::trace!("step completed: {}", outcome.game);
+        }
+    };
+    // An empty list would mean every game to the facade.
+    let mut info = if subjects.valid.is_empty() {
+        vec![]
+    } else {
+        api::back_up_games(
+            manifest,
+            config,
+            &roots,
+            &layout,
+            &subjects.valid,
+            if request.preview {
+                Finality::Preview
+            } else {
+                Finality::Final
+            },
+            &options,
+            &on_progress,
+            &api::Cancellation::default(),
+        )
+    };
+    log::info!("completed backup");
+    summary.nothing_changed = request.if_changed
+        && info.iter().all(|outcome| match &outcome.skipped {
+            Some(api::Skip::Inactive) => true,
+            Some(api::Skip::Inconsistent | api::Skip::Running(_)) => false,
+            _ => outcome.decision == OperationStepDecision::Ignored,
+        });
+    reporter.set_total_duration(timer.elapsed());
+
+    if should_sync_cloud_after {
+        let sync_result = sync_cloud(
+            config,
+            cache,
+            &backup_dir,
+            &config.cloud.path,
+            SyncDirection::Upload,
+            Finality::Final,
+            cloud_games,
+            request.output.report.progress,
+        );
+        if sync_result.is_err() {
+            reporter.trip_cloud_sync_failed();
+        } else if cache.cloud.deferred {
+            cache.cloud.deferred = false;
+            cache.save();
+        }
+    } else if request.preview && wants_cloud_sync {
+        let sync_result = sync_cloud(
+            config,
+            cache,
+            &backup_dir,
+            &config.cloud.path,
+            SyncDirection::Upload,
+            Finality::Preview,
+            if games_specified { &subjects.valid } else { &[] },
+            request.output.report.progress,
+        );
+        match sync_result {
+            Ok(changes) => reporter.add_cloud_changes(&changes, true),
+            Err(_) => reporter.trip_cloud_sync_failed(),
+        }
+    }
+
+    let mut duplicate_detector = DuplicateDetector::default();
+    for api::Outcome { scan_info, .. } in info.iter() {
+        if !scan_info.can_report_game() {
+            continue;
+        }
+        duplicate_detector.add_game(
+            scan_info,
+            config.is_game_enabled_for_operation(&scan_info.game_name, false),
+        );
+    }
+
+    let sort = request
+        .output
+        .sort
+        .clone()
+        .unwrap_or_else(|| config.backup.sort.clone());
+    info.sort_by(|x, y| {
+        crate::scan::compare_games(
+            sort.key,
+            &x.scan_info,
+            x.backup_info.as_ref(),
+            &y.scan_info,
+            y.backup_info.as_ref(),
+        )
+    });
+    if sort.reversed {
+        info.reverse();
+    }
+
+    let merged_titles = config.merged_titles();
+    for api::Outcome {
+        game: name,
+        scan_info,
+        backup_info,
+        decision,
+        skipped,
+        duration,
+    } in info
+    {
+        if skipped == Some(api::Skip::Inactive) {
+            reporter.add_inactive_game(&name);
+            continue;
+        }
+        let inconsistent = skipped == Some(api::Skip::Inconsistent);
+        if !reporter.add_game(
+            &name,
+            &scan_info,
+            &backup_info.unwrap_or_default(),
+            &decision,
+            &duplicate_detector,
+            duration.map(|x| x.as_millis() as u64),
+        ) || inconsistent
+        {
+            summary.failed_games += 1;
+        }
+        if inconsistent {
+            reporter.add_inconsistent_game(&name);
+        }
+        if forced.contains(&name) {
+            reporter.add_forced_game(&name);
+        }
+        match skipped {
+            Some(api::Skip::Unchanged) => reporter.add_unchanged_game(&name),
+            Some(api::Skip::Running(process)) => reporter.add_running_game(&name, process, false),
+            _ => {}
+        }
+        if request.output.verbose {
+            reporter.add_merged_titles(
+                &name,
+                merged_titles.get(&name).map(|x| x.as_slice()).unwrap_or_default(),
+            );
+        }
+    }
+    reporter.trip_io_timeouts(&roots.io_timed_out_paths());
+
+    if !request.preview {
+        summary.kind = Some(OperationKind::Backup);
+    }
+    if request.fail_on_change {
+        summary.changes_pending = reporter
+            .overall()
+            .is_some_and(|x| x.changed_games.new + x.changed_games.different > 0);
+    }
+    Ok(summary)
+}
+
+/// The manifest isn't needed for the restore itself, but it can refine which paths make sense here.
+/// It should already incorporate any extensions from the config.
+/// Unknown games are added to the report before returning `Error::CliUnrecognizedGames`.
+/// `confirm_remaps` is only called if user profiles need to be remapped and the request isn't forced.
+pub fn restore(
+    manifest: &Manifest,
+    config: &mut Config,
+    cache: &mut Cache,
+    request: &RestoreRequest,
+    reporter: &mut Reporter,
+    hive: Option<RestoreHive>,
+    confirm_remaps: &dyn Fn(&Remaps) -> Result<bool, Error>,
+) -> Result<Summary, Error> {
+    let started = chrono::Utc::now();
+    let selection = PathSelection::new(&request.only_path).map_err(invalid_glob)?;
+    let overrides = PathOverrides::new(&request.exclude, &request.include).map_err(invalid_glob)?;
+    let skip_files = hive.is_some_and(|x| !x.with_files);
+    let hive_key = hive.and_then(|x| x.key);
+    let backup_before_restore = negatable_flag(
+        request.backup_before_restore,
+        request.no_backup_before_restore,
+        config.restore.backup_before_restore,
+    ) && !request.preview;
+    override_redirects(config, request.no_redirects, &request.redirects);
+
+    let restore_dir = request.path.clone().unwrap_or_else(|| config.restore.path.clone());
+    let mut summary = Summary::new(restore_dir.clone(), started);
+    let layout = BackupLayout::new(restore_dir.clone(), config);
+
+    let restorable_names = layout.restorable_games();
+
+    if request.backup.is_some() && request.games.len() != 1 {
+        return Err(Error::CliBackupIdWithMultipleGames);
+    }
+    let backup_id = request.backup.as_ref().map(|x| BackupId::Named(x.clone()));
+
+    let games_specified = !request.games.is_empty() || request.from_list;
+    let subjects = if request.games.is_empty() && games_specified {
+        GameSubjects::default()
+    } else {
+        GameSubjects::new(restorable_names, request.games.clone(), None)
+    };
+    if !subjects.invalid.is_empty() {
+        let only_listed = !request.strict && subjects.invalid.iter().all(|x| request.listed.contains(x));
+        let unknown = TitleFinder::new(&Manifest::default(), &layout).unknown_games(subjects.invalid, false, true);
+        reporter.trip_unknown_games(unknown.clone());
+        if !only_listed {
+            return Err(Error::CliUnrecognizedGames { games: unknown });
+        }
+    }
+    if games_specified && subjects.valid.is_empty() {
+        return Ok(summary);
+    }
+
+    let cloud_sync = negatable_flag(
+        request.cloud_sync && !request.preview,
+        request.no_cloud_sync,
+        config.cloud.synchronize
+            && !request.preview
+            && crate::cloud::validate_cloud_config(config, &config.cloud.path).is_ok(),
+    );
+    if cloud_sync {
+        let changes = sync_cloud(
+            config,
+            cache,
+            &restore_dir,
+            &config.cloud.path,
+            SyncDirection::Upload,
+            Finality::Preview,
+            if games_specified { &subjects.valid } else { &[] },
+            request.output.report.progress,
+        );
+        match changes {
+            Ok(changes) => {
+                if !changes.is_empty() {
+                    reporter.trip_cloud_conflict(&changes);
+                }
+            }
+            Err(_) => {
+                reporter.trip_cloud_sync_failed();
+            }
+        }
+    }
+
+    let remap_user_profile = match config.restore.remap_user_profile {
+        RemapUserProfile::Auto => true,
+        RemapUserProfile::Off => false,
+        RemapUserProfile::Ask => {
+            let remaps: Remaps = subjects
+                .valid
+                .iter()
+                .filter_map(|name| {
+                    layout
+                        .game_layout(name)
+                        .user_profile_redirect(backup_id.as_ref().unwrap_or(&BackupId::Latest))
+                })
+                .map(|x| (x.source.render(), x.target.render()))
+                .collect();
+            remaps.is_empty() || request.preview || request.force || confirm_remaps(&remaps)?
+        }
+    };
+
+    // This needs to inspect every process, so only do it when it matters.
+    let running_check = (config.restore.block_if_running && !request.force && !request.preview).then(|| {
+        let roots = api::Roots::new(manifest, config, &layout, &subjects.valid);
+        (roots, ProcessSnapshot::take())
+    });
+    if running_check.as_ref().is_some_and(|(_, x)| x.is_unsupported()) {
+        reporter.trip_running_check_unavailable();
+    }
+
+    let snapshot_target =
+        backup_before_restore.then(|| (api::Roots::new(manifest, config, &layout, &subjects.valid), &layout));
+
+    let path_migrations: HashMap<_, _> = if request.migrate_paths {
+        subjects
+            .valid
+            .iter()
+            .filter_map(|name| Some((name.clone(), PathMigrations::from_game(manifest.0.get(name)?))))
+            .filter(|(_, migrations)| !migrations.is_empty())
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    log::info!("beginning restore with {} steps", subjects.valid.len());
+
+    let config = &*config;
+    let timer = Instant::now();
+    let mut info: Vec<_> = subjects
+        .valid
+        .par_iter()
+        .enumerate()
+        .progress_with(scan_progress_bar(
+            subjects.valid.len() as u64,
+            request.output.report.progress,
+        ))
+        .map(|(i, name)| {
+            log::trace!("step {i} / {}: {name}", subjects.valid.len());
+            progress::emit(
+                request.output.report.progress,
+                progress::Event::GameStart {
+                    name,
+                    index: i + 1,
+                    total: subjects.valid.len(),
+                },
+            );
+            let step_timer = Instant::now();
+            let mut layout = layout.game_layout(name);
+            let redirects = match path_migrations.get(name) {
+                Some(migrations) => migrations.with_redirects(&config.redirects),
+                None => config.redirects.clone(),
+            };
+            let mut scan_info = layout.scan_for_restoration(
+                name,
+                backup_id.as_ref().unwrap_or(&BackupId::Latest),
+                &redirects,
+                &config.restore.toggled_paths,
+                &config.restore.toggled_registry,
+                remap_user_profile,
+                request
+                    .prefer_account
+                    .as_deref()
+                    .or(config.restore.prefer_account.as_deref()),
+                config.restore.prefer_wine_user,
+                config.is_registry_enabled(name),
+                &manifest.0.get(name).map(OsConstraints::from_game).unwrap_or_default(),
+            );
+            if skip_files {
+                scan_info.found_files.clear();
+            }
+            selection.apply(&mut scan_info);
+            overrides.apply(&mut scan_info);
+            if request.no_clobber_newer {
+                scan_info.keep_newer_local_files();
+            }
+            let ignored = !config.is_game_enabled_for_restore(name) && !games_specified;
+
+            let running = match &running_check {
+                Some((roots, snapshot)) if scan_info.backup.is_some() && !ignored => {
+                    let save_files: Vec<_> = scan_info
+                        .found_files
+                        .iter()
+                        .filter(|x| !x.ignored)
+                        .map(|x| x.effective().clone())
+                        .collect();
+                    snapshot.find(&roots.install_dirs(name), &save_files)
+                }
+                _ => None,
+            };
+            if let Some(process) = &running {
+                log::warn!("Skipping restore of {name} because it seems to be running: {process:?}");
+            }
+
+            let ignored = ignored || running.is_some();
+            let decision = if ignored {
+                OperationStepDecision::Ignored
+            } else {
+                OperationStepDecision::Processed
+            };
+
+            if let Some(backup) = &request.backup {
+                if let Some(scanned_backup) = &scan_info.backup {
+                    if !scanned_backup.matches(backup) {
+                        log::trace!("step {i} completed (backup mismatch)");
+                        return (
+                            name,
+                            scan_info,
+                            Default::default(),
+                            decision,
+                            Some(Error::CliInvalidBackupId {
+                                available: layout.describe_backups(),
+                            }),
+                            running,
+                            None,
+                            None,
+                        );
+                    }
+                }
+            }
+
+            let restoring = scan_info.backup.is_some() && !request.preview && !ignored;
+            let snapshot = match &snapshot_target {
+                Some((roots, backup_layout)) if restoring && scan_info.found_anything_processable() => {
+                    let snapshot = snapshot_before_restore(manifest, config, roots, backup_layout, name);
+                    if let SafetySnapshot::Failed(reason) = &snapshot {
+                        log::warn!("[{name}] unable to back up current files before restoring: {reason}");
+                    }
+                    Some(snapshot)
+                }
+                _ => None,
+            };
+
+            let restore_info = if !restoring {
+                BackupInfo::default()
+            } else if snapshot.as_ref().is_some_and(|x| x.failed()) && !request.force {
+                BackupInfo::total_failure(&scan_info, &TRANSLATOR.failure_safety_snapshot())
+            } else {
+                layout.restore_into_hive(&scan_info, &config.restore.toggled_registry, hive_key)
+            };
+            progress::emit_files(request.output.report.progress, &scan_info);
+            log::trace!("step {i} completed");
+            let duration_ms = step_timer.elapsed().as_millis() as u64;
+            (
+                name,
+                scan_info,
+                restore_info,
+                decision,
+                None,
+                running,
+                Some(duration_ms),
+                snapshot,
+            )
+        })
+        .collect();
+    log::info!("completed restore");
+    reporter.set_total_duration(timer.elapsed());
+
+    let mut duplicate_detector = DuplicateDetector::default();
+    for (_, scan_info, _, _, failure, ..) in info.iter() {
+        if !scan_info.can_report_game() {
+            continue;
+        }
+        if let Some(failure) = failure {
+            return Err(failure.clone());
+        }
+        duplicate_detector.add_game(
+            scan_info,
+            config.is_game_enabled_for_operation(&scan_info.game_name, true),
+        );
+    }
+
+    let sort = request
+        .output
+        .sort
+        .clone()
+        .unwrap_or_else(|| config.restore.sort.clone());
+    info.sort_by(|(_, scan_info1, backup_info1, ..), (_, scan_info2, backup_info2, ..)| {
+        crate::scan::compare_games(sort.key, scan_info1, Some(backup_info1), scan_info2, Some(backup_info2))
+    });
+    if sort.reversed {
+        info.reverse();
+    }
+
+    let merged_titles = config.merged_titles();
+    for (name, scan_info, backup_info, decision, _, running, duration_ms, snapshot) in info {
+        if !reporter.add_game(
+            name,
+            &scan_info,
+            &backup_info,
+            &decision,
+            &duplicate_detector,
+            duration_ms,
+        ) {
+            summary.failed_games += 1;
+        }
+        if let Some(process) = running {
+            reporter.add_running_game(name, process, true);
+        }
+        if let Some(snapshot) = &snapshot {
+            reporter.add_safety_snapshot(name, snapshot);
+        }
+        if request.output.verbose {
+            reporter.add_merged_titles(name, merged_titles.get(name).map(|x| x.as_slice()).unwrap_or_default());
+        }
+    }
+
+    if !request.preview {
+        summary.kind = Some(OperationKind::Restore);
+    }
+    Ok(summary)
+}
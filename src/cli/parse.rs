@@ -1,12 +1,22 @@
 use std::path::PathBuf;
 
 use crate::{
+    cli::{
+        operation::DEFAULT_IF_CHANGED_DEPTH,
+        report::{ApiFormat, ApiVersion, ColorChoice},
+    },
     cloud::WebDavProvider,
+    history::Operation,
+    lang::Language,
     prelude::StrictPath,
-    resource::config::{BackupFormat, Sort, SortKey, ZipCompression},
+    resource::{
+        config::{BackupFormat, RedirectConfig, RedirectKind, Sort, SortKey, ZipCompression},
+        manifest::Store,
+    },
+    scan::{PathSelection, Trigger, TriggerSource},
 };
 
-use clap::{ArgGroup, Args, ValueEnum};
+use clap::{ArgGroup, Args, CommandFactory, ValueEnum};
 
 macro_rules! possible_values {
     ($t: ty, $options: ident) => {{
@@ -19,12 +29,196 @@ fn parse_strict_path(path: &str) -> Result<StrictPath, std::io::Error> {
     Ok(StrictPath::new(path.to_owned()))
 }
 
+/// The titles in a `--games-from` list, skipping blank lines and `#` comments.
+pub fn parse_game_list(content: &str) -> Vec<String> {
+    content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_glob(pattern: &str) -> Result<String, globset::Error> {
+    PathSelection::new(&[pattern.to_string()])?;
+    Ok(pattern.to_string())
+}
+
 fn parse_existing_strict_path(path: &str) -> Result<StrictPath, std::io::Error> {
     let sp = StrictPath::new(path.to_owned());
     std::fs::canonicalize(sp.interpret())?;
     Ok(sp)
 }
 
+/// A point in time, given either as a date/timestamp (`2024-01-01`, `2024-01-01T12:00:00Z`)
+/// or as an age relative to now (`12h`, `90d`, `2w`, `6m`, `1y`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CliTime {
+    At(chrono::DateTime<chrono::Utc>),
+    Ago(chrono::Duration),
+}
+
+impl CliTime {
+    pub fn resolve(&self, now: &chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            Self::At(time) => *time,
+            Self::Ago(duration) => *now - *duration,
+        }
+    }
+}
+
+impl std::str::FromStr for CliTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Ok(time) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok(Self::At(time.with_timezone(&chrono::Utc)));
+        }
+
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            use chrono::TimeZone;
+            return chrono::Local
+                .from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+                .earliest()
+                .map(|time| Self::At(time.with_timezone(&chrono::Utc)))
+                .ok_or_else(|| format!("invalid local date: {s}"));
+        }
+
+        let unit_start = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, unit) = s.split_at(unit_start);
+        let amount: i64 = amount
+            .parse()
+            .map_err(|_| format!("expected a date, timestamp, or duration like `90d`: {s}"))?;
+        let duration = match unit {
+            "h" => chrono::Duration::hours(amount),
+            "d" => chrono::Duration::days(amount),
+            "w" => chrono::Duration::weeks(amount),
+            "m" => chrono::Duration::days(amount * 30),
+            "y" => chrono::Duration::days(amount * 365),
+            _ => return Err(format!("unknown duration unit (expected h, d, w, m, or y): {s}")),
+        };
+        Ok(Self::Ago(duration))
+    }
+}
+
+/// A length of time, like `30m`, `2h`, or `1d`.
+/// Unlike `CliTime`, `m` means minutes here, since this is meant for short intervals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CliDuration(pub chrono::Duration);
+
+impl std::str::FromStr for CliDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let unit_start = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, unit) = s.split_at(unit_start);
+        let amount: i64 = amount
+            .parse()
+            .map_err(|_| format!("expected a duration like `30m`: {s}"))?;
+        let duration = match unit {
+            "s" => chrono::Duration::seconds(amount),
+            "m" => chrono::Duration::minutes(amount),
+            "h" => chrono::Duration::hours(amount),
+            "d" => chrono::Duration::days(amount),
+            "w" => chrono::Duration::weeks(amount),
+            _ => return Err(format!("unknown duration unit (expected s, m, h, d, or w): {s}")),
+        };
+        Ok(Self(duration))
+    }
+}
+
+/// A similarity cutoff from 0 to 1, like `0.75`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CliThreshold(pub f64);
+
+/// Values are validated to be in range, so `NaN` is never stored.
+impl Eq for CliThreshold {}
+
+impl std::str::FromStr for CliThreshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<f64>() {
+            Ok(value) if (0.0..=1.0).contains(&value) => Ok(Self(value)),
+            _ => Err(format!("expected a number from 0 to 1: {s}")),
+        }
+    }
+}
+
+/// A redirect for a single operation, given as `<source>=<target>[:<kind>]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CliRedirect {
+    pub source: StrictPath,
+    pub target: StrictPath,
+    /// When unset, this applies to the current operation.
+    pub kind: Option<RedirectKind>,
+}
+
+impl CliRedirect {
+    pub fn resolve(&self, default: RedirectKind) -> RedirectConfig {
+        RedirectConfig {
+            kind: self.kind.unwrap_or(default),
+            source: self.source.clone(),
+            target: self.target.clone(),
+        }
+    }
+}
+
+impl std::str::FromStr for CliRedirect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((source, rest)) = s.split_once('=') else {
+            return Err(format!("expected `<SOURCE>=<TARGET>[:<KIND>]`: {s}"));
+        };
+
+        // Paths may contain colons (`C:/...` or `/saves:old`),
+        // so the last segment is only a kind if it names one exactly.
+        let (target, kind) = match rest.rsplit_once(':') {
+            Some((target, "backup")) => (target, Some(RedirectKind::Backup)),
+            Some((target, "restore")) => (target, Some(RedirectKind::Restore)),
+            Some((target, "bidirectional")) => (target, Some(RedirectKind::Bidirectional)),
+            _ => (rest, None),
+        };
+
+        if source.trim().is_empty() {
+            return Err(format!("missing source path: {s}"));
+        }
+        if target.trim().is_empty() {
+            return Err(format!("missing target path: {s}"));
+        }
+
+        Ok(Self {
+            source: StrictPath::new(source.to_string()),
+            target: StrictPath::new(target.to_string()),
+            kind,
+        })
+    }
+}
+
+/// Accepts a language code (`de-DE`, or just `de`),
+/// or `none`/`C` for the untranslated English text.
+fn parse_language(code: &str) -> Result<Language, String> {
+    if code == "none" || code == "C" {
+        return Ok(Language::English);
+    }
+
+    let matches = |language: &&Language| {
+        let id = language.id();
+        id.to_string().eq_ignore_ascii_case(code) || id.language.as_str().eq_ignore_ascii_case(code)
+    };
+    Language::ALL
+        .iter()
+        .find(matches)
+        .copied()
+        .ok_or_else(|| format!("unknown language: {code}"))
+}
+
 fn styles() -> clap::builder::styling::Styles {
     use clap::builder::styling::{AnsiColor, Effects, Styles};
 
@@ -49,6 +243,20 @@ pub enum CompletionShell {
     Elvish,
 }
 
+impl CompletionShell {
+    /// Write the completion script, based on the CLI definition.
+    pub fn generate(&self, out: &mut dyn std::io::Write) {
+        let shell = match self {
+            Self::Bash => clap_complete::Shell::Bash,
+            Self::Fish => clap_complete::Shell::Fish,
+            Self::Zsh => clap_complete::Shell::Zsh,
+            Self::PowerShell => clap_complete::Shell::PowerShell,
+            Self::Elvish => clap_complete::Shell::Elvish,
+        };
+        clap_complete::generate(shell, &mut Cli::command(), env!("CARGO_PKG_NAME"), out)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum CliSort {
     #[default]
@@ -61,7 +269,16 @@ pub enum CliSort {
 }
 
 impl CliSort {
-    pub const ALL: &'static [&'static str] = &["name", "name-rev", "size", "size-rev", "status", "status-rev"];
+    pub const ALL: &'static [&'static str] = &[
+        "name",
+        "name-rev",
+        "size",
+        "size-rev",
+        "status",
+        "status-rev",
+        "change",
+        "change-rev",
+    ];
 }
 
 impl std::str::FromStr for CliSort {
@@ -73,8 +290,9 @@ impl std::str::FromStr for CliSort {
             "name-rev" => Ok(Self::NameReversed),
             "size" => Ok(Self::Size),
             "size-rev" => Ok(Self::SizeReversed),
-            "status" => Ok(Self::Status),
-            "status-rev" => Ok(Self::StatusReversed),
+            // The status is how the game's saves have changed, so this is just another name for it.
+            "status" | "change" => Ok(Self::Status),
+            "status-rev" | "change-rev" => Ok(Self::StatusReversed),
             _ => Err(format!("invalid sort key: {}", s)),
         }
     }
@@ -118,6 +336,14 @@ pub enum LauncherTypes {
     Steam,
 }
 
+/// Sort order for the `stats` storage summary.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum StatsSort {
+    Name,
+    /// Biggest first.
+    Size,
+}
+
 #[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
 pub enum Subcommand {
     /// Back up data
@@ -173,6 +399,10 @@ pub enum Subcommand {
         #[clap(long, value_parser = possible_values!(CliSort, ALL))]
         sort: Option<CliSort>,
 
+        /// Reverse the sort order (e.g., with `--sort size`, list the largest games first).
+        #[clap(long)]
+        reverse: bool,
+
         /// Format in which to store new backups.
         /// When not specified, this defers to the config file.
         #[clap(long, value_parser = possible_values!(BackupFormat, ALL_NAMES))]
@@ -204,7 +434,7 @@ pub enum Subcommand {
         /// Upload any changes to the cloud when the backup is complete.
         /// If the local and cloud backups are not in sync to begin with,
         /// then nothing will be uploaded.
-        /// This has no effect on previews.
+        /// In preview mode, this only reports what would be uploaded.
         /// When not specified, this defers to the config file.
         #[clap(long)]
         cloud_sync: bool,
@@ -214,6 +444,174 @@ pub enum Subcommand {
         #[clap(long, conflicts_with("cloud_sync"))]
         no_cloud_sync: bool,
 
+        /// Synchronize with the cloud even if the network connection is metered.
+        /// Otherwise, the config decides whether to wait for an unmetered connection.
+        #[clap(long, conflicts_with("no_cloud_sync"))]
+        allow_metered: bool,
+
+        /// If a game's existing backups don't match its mapping file
+        /// (e.g., because some backup files were deleted by hand),
+        /// update the mapping and continue with the backup.
+        /// Without this, such games are skipped.
+        #[clap(long)]
+        repair: bool,
+
+        /// If a file is locked by another process (e.g., a running game on Windows),
+        /// try this many more times after the rest of the game's files,
+        /// waiting a few seconds before each attempt.
+        /// If all of a game's files are still locked, the game is reported as deferred.
+        /// This overrides `backup.retryLocked` in the config.
+        #[clap(long, value_name = "COUNT")]
+        retry_locked: Option<u32>,
+
+        /// Skip games that appear to be running, based on the processes under their install folder,
+        /// so that they can be backed up later.
+        /// This enables `backup.skipIfRunning` in the config.
+        #[clap(long)]
+        skip_running: bool,
+
+        /// Create a new full backup of this game even if nothing has changed,
+        /// while other games are backed up incrementally as usual.
+        /// This is useful when change detection is confused for a specific game.
+        /// This also processes the game if it is disabled in the config.
+        /// With `--preview`, the report shows what the forced backup would write.
+        /// Can be repeated.
+        #[clap(long, value_name = "GAME")]
+        force_game: Vec<String>,
+
+        /// Only back up games whose saves are new or different since their last backup.
+        /// Unchanged games are reported as ignored, with the reason `unchanged`.
+        /// If no games changed, nothing is backed up and the command exits with code 3,
+        /// so that scripts can tell that there was nothing to do.
+        #[clap(long)]
+        if_changed: bool,
+
+        /// Skip games whose save folders haven't been modified within this long
+        /// (or since the game's latest backup, if that's earlier),
+        /// based on a quick check of modification times before the full scan.
+        /// This accepts a duration like `30m`, `2h`, or `1d` (here, `m` means minutes).
+        /// Games without any backups yet, or with registry data on Windows, are always scanned.
+        #[clap(long, value_name = "DURATION")]
+        if_changed_since: Option<CliDuration>,
+
+        /// How many folder levels deep to look for `--if-changed-since`.
+        /// If a save folder goes deeper than this, the game is always scanned.
+        #[clap(long, value_name = "LEVELS", default_value_t = DEFAULT_IF_CHANGED_DEPTH, requires("if_changed_since"))]
+        if_changed_depth: usize,
+
+        /// Ignore the redirects from the config file for this backup.
+        /// This can be combined with `--redirect` to replace them.
+        #[clap(long)]
+        no_redirects: bool,
+
+        /// Add a redirect for this backup only, as `<SOURCE>=<TARGET>`,
+        /// optionally followed by `:backup`, `:restore`, or `:bidirectional`.
+        /// When the kind is omitted, the redirect applies to backups.
+        /// Can be repeated.
+        #[clap(long, value_name = "SOURCE=TARGET[:KIND]")]
+        redirect: Vec<CliRedirect>,
+
+        /// Skip files matching this glob for this backup only,
+        /// on top of anything that the config file ignores.
+        /// These are still listed in the report, but marked as ignored.
+        /// `*` matches within a folder, `**` matches across folders,
+        /// and a folder also matches everything inside of it.
+        /// Can be repeated.
+        #[clap(long, value_name = "GLOB", value_parser = parse_glob)]
+        exclude: Vec<String>,
+
+        /// Keep files matching this glob even if they match `--exclude`,
+        /// as long as this pattern has at least as many folder levels (not counting `**`).
+        /// This does not override the config file.
+        /// Can be repeated.
+        #[clap(long, value_name = "GLOB", value_parser = parse_glob, requires("exclude"))]
+        include: Vec<String>,
+
+        /// Add a comment to the new backups created by this run.
+        /// Games without any changes don't get a new backup, so they are unaffected.
+        #[clap(long, value_name = "TEXT")]
+        comment: Option<String>,
+
+        /// Lock the new backups created by this run,
+        /// so that they are exempt from retention limits.
+        #[clap(long)]
+        lock: bool,
+
+        /// Record the new backups as coming from a scheduled task
+        /// rather than from a manual CLI run.
+        /// Use this when running Ludusavi from cron, Task Scheduler, or similar.
+        #[clap(long)]
+        scheduled: bool,
+
+        /// Set by `wrap` to record which game was launched.
+        #[clap(skip)]
+        trigger: Option<Trigger>,
+
+        /// Include extra details in the output,
+        /// such as the titles that were merged into each game,
+        /// which redirect applied to each file,
+        /// and which root each file was found under.
+        #[clap(long)]
+        verbose: bool,
+
+        /// List every file, even for games with more than `scan.fileListLimit` files.
+        #[clap(long)]
+        full_file_list: bool,
+
+        /// Leave out games and entries that haven't changed since the last backup.
+        /// They're still counted in the overall totals.
+        #[clap(long)]
+        only_changed: bool,
+
+        /// Only show the games and entries that failed.
+        /// Everything is still counted in the overall totals.
+        #[clap(long)]
+        only_failed: bool,
+
+        /// With `--preview`, exit with code 2 if any games are new or different,
+        /// so that scripts can check for pending changes without parsing the output.
+        /// The report is still printed as usual,
+        /// and code 1 is still used for errors.
+        #[clap(long, requires("preview"))]
+        fail_on_change: bool,
+
+        /// Write the report to this file instead of printing it to stdout.
+        /// Parent folders are created as needed.
+        /// If the file can't be written, the report is printed instead and the command fails.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        output_file: Option<StrictPath>,
+
+        /// Send this backup to `ludusavi daemon` if it is running,
+        /// rather than loading the manifest and scanning in this process.
+        /// Without a running daemon, the backup happens here as usual.
+        /// With `--api-format json-lines`, the backup always happens here.
+        /// This can't be combined with `--progress`.
+        #[clap(long)]
+        use_daemon: bool,
+
+        /// Also back up the game with this Steam ID.
+        #[clap(long)]
+        steam_id: Option<u32>,
+
+        /// Also back up the game with this GOG ID.
+        #[clap(long)]
+        gog_id: Option<u64>,
+
+        /// Also back up the game with this Lutris slug.
+        #[clap(long)]
+        lutris_id: Option<String>,
+
+        /// Also back up the games listed in this file (one title per line),
+        /// or `-` to read them from stdin.
+        /// Blank lines and lines starting with `#` are ignored.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        games_from: Option<StrictPath>,
+
+        /// Fail if any games from `--games-from` are unknown.
+        /// Otherwise, they're reported and the other games are still processed.
+        #[clap(long, requires("games_from"))]
+        strict: bool,
+
         /// Only back up these specific games.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
@@ -231,9 +629,18 @@ pub enum Subcommand {
         path: Option<StrictPath>,
 
         /// Don't ask for confirmation.
+        /// This also restores games that seem to be running,
+        /// even if the config file says to skip them.
+        /// It does not skip the extra confirmation for restoring many games at once (see `--yes`).
         #[clap(long)]
         force: bool,
 
+        /// When no games are specified and more than `restore.confirmWhenGamesExceed` games would be restored,
+        /// proceed without the summary and confirmation.
+        /// Without this, that confirmation is required, and the restore is aborted if it can't be requested.
+        #[clap(long)]
+        yes: bool,
+
         /// Print information to stdout in machine-readable JSON.
         /// This replaces the default, human-readable output.
         #[clap(long)]
@@ -244,11 +651,56 @@ pub enum Subcommand {
         #[clap(long, value_parser = possible_values!(CliSort, ALL))]
         sort: Option<CliSort>,
 
-        /// Restore a specific backup, using an ID returned by the `backups` command.
+        /// Reverse the sort order (e.g., with `--sort size`, list the largest games first).
+        #[clap(long)]
+        reverse: bool,
+
+        /// Restore a specific backup, using a name or short ID returned by the `backups` command.
         /// This is only valid when restoring a single game.
         #[clap(long)]
         backup: Option<String>,
 
+        /// Only restore files whose target path (after redirects) matches this glob.
+        /// `*` matches within a folder, `**` matches across folders,
+        /// and a folder also matches everything inside of it.
+        /// Everything else, including registry data, is reported as ignored.
+        /// This may be repeated to select more files.
+        #[clap(long, value_name = "GLOB", value_parser = parse_glob)]
+        only_path: Vec<String>,
+
+        /// Skip files whose target path (after redirects) matches this glob for this restore only,
+        /// on top of anything that the config file or `--only-path` leaves out.
+        /// These are still listed in the report, but marked as ignored.
+        /// Can be repeated.
+        #[clap(long, value_name = "GLOB", value_parser = parse_glob)]
+        exclude: Vec<String>,
+
+        /// Keep files matching this glob even if they match `--exclude`,
+        /// as long as this pattern has at least as many folder levels (not counting `**`).
+        /// This does not override the config file or `--only-path`.
+        /// Can be repeated.
+        #[clap(long, value_name = "GLOB", value_parser = parse_glob, requires("exclude"))]
+        include: Vec<String>,
+
+        /// Don't overwrite local files that are different from the backup
+        /// and were modified more recently than the backed up copy.
+        /// These are still listed in the report, but marked as ignored.
+        #[clap(long)]
+        no_clobber_newer: bool,
+
+        /// Before restoring each game, back up its current files into the same backup folder.
+        /// That backup gets a comment and is locked, so it doesn't count toward retention limits.
+        /// If it can't be made, the game isn't restored, unless `--force` is also specified.
+        /// This has no effect on previews.
+        /// When not specified, this defers to the config file.
+        #[clap(long)]
+        backup_before_restore: bool,
+
+        /// Don't back up the current files before restoring.
+        /// When not specified, this defers to the config file.
+        #[clap(long, conflicts_with("backup_before_restore"))]
+        no_backup_before_restore: bool,
+
         /// Warn if the local and cloud backups are out of sync.
         /// The restore will still proceed regardless.
         /// This has no effect on previews.
@@ -261,17 +713,119 @@ pub enum Subcommand {
         #[clap(long, conflicts_with("cloud_sync"))]
         no_cloud_sync: bool,
 
+        /// Ignore the redirects from the config file for this restore.
+        /// This can be combined with `--redirect` to replace them.
+        #[clap(long)]
+        no_redirects: bool,
+
+        /// Add a redirect for this restore only, as `<SOURCE>=<TARGET>`,
+        /// optionally followed by `:backup`, `:restore`, or `:bidirectional`.
+        /// When the kind is omitted, the redirect applies to restores.
+        /// Can be repeated.
+        #[clap(long, value_name = "SOURCE=TARGET[:KIND]")]
+        redirect: Vec<CliRedirect>,
+
+        /// Restore files from a game's old save locations into its new ones,
+        /// based on the path migration rules from the manifest and config file.
+        #[clap(long)]
+        migrate_paths: bool,
+
+        /// If a backup's Steam account folder doesn't exist on this system
+        /// and there are several other accounts, restore into this account ID.
+        /// Otherwise, those files are skipped.
+        /// This overrides the `restore.preferAccount` config setting.
+        #[clap(long, value_name = "ID")]
+        prefer_account: Option<String>,
+
+        /// Windows only: restore `HKEY_CURRENT_USER` registry data into this hive file
+        /// (e.g., another user's `NTUSER.DAT`) instead of your own registry.
+        /// The hive is loaded under a temporary key in `HKEY_USERS` and unloaded afterward.
+        /// Registry data from other hives is skipped.
+        /// This requires running as an administrator.
+        /// Files are not restored unless you also pass `--with-files`.
+        #[clap(long, value_name = "PATH", value_parser = parse_existing_strict_path)]
+        registry_into_hive: Option<StrictPath>,
+
+        /// Also restore files when using `--registry-into-hive`.
+        #[clap(long, requires("registry_into_hive"))]
+        with_files: bool,
+
+        /// Include extra details in the output,
+        /// such as the titles that were merged into each game
+        /// and which redirect applied to each file.
+        #[clap(long)]
+        verbose: bool,
+
+        /// List every file, even for games with more than `scan.fileListLimit` files.
+        #[clap(long)]
+        full_file_list: bool,
+
+        /// Leave out games and entries that haven't changed since the last backup.
+        /// They're still counted in the overall totals.
+        #[clap(long)]
+        only_changed: bool,
+
+        /// Only show the games and entries that failed.
+        /// Everything is still counted in the overall totals.
+        #[clap(long)]
+        only_failed: bool,
+
+        /// Write the report to this file instead of printing it to stdout.
+        /// Parent folders are created as needed.
+        /// If the file can't be written, the report is printed instead and the command fails.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        output_file: Option<StrictPath>,
+
+        /// Send this restore to `ludusavi daemon` if it is running,
+        /// rather than handling it in this process.
+        /// Without a running daemon, the restore happens here as usual.
+        /// With `--registry-into-hive`, `--api-format json-lines`,
+        /// or if `restore.remapUserProfile` would need to ask, the restore always happens here.
+        /// This can't be combined with `--progress`.
+        #[clap(long)]
+        use_daemon: bool,
+
+        /// Also restore the game with this Steam ID.
+        #[clap(long)]
+        steam_id: Option<u32>,
+
+        /// Also restore the game with this GOG ID.
+        #[clap(long)]
+        gog_id: Option<u64>,
+
+        /// Also restore the game with this Lutris slug.
+        #[clap(long)]
+        lutris_id: Option<String>,
+
+        /// Also restore the games listed in this file (one title per line),
+        /// or `-` to read them from stdin.
+        /// Blank lines and lines starting with `#` are ignored.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        games_from: Option<StrictPath>,
+
+        /// Fail if any games from `--games-from` are unknown.
+        /// Otherwise, they're reported and the other games are still processed.
+        #[clap(long, requires("games_from"))]
+        strict: bool,
+
         /// Only restore these specific games.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
         games: Vec<String>,
     },
     /// Generate shell completion scripts
+    #[clap(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
     Complete {
         #[clap(subcommand)]
-        shell: CompletionShell,
+        shell: Option<CompletionShell>,
+
+        /// List the game titles that start with this prefix, one per line.
+        /// This is meant for custom completion scripts that complete game names.
+        #[clap(long, hide = true, value_name = "PREFIX")]
+        list_games: Option<String>,
     },
     /// Show backups
+    #[clap(args_conflicts_with_subcommands = true)]
     Backups {
         /// Directory in which to find backups.
         /// When unset, this defaults to the restore path from the config file.
@@ -283,10 +837,211 @@ pub enum Subcommand {
         #[clap(long)]
         api: bool,
 
+        /// Only show backups made at or after this point.
+        /// This may be a date (`2024-01-01`), an RFC 3339 timestamp,
+        /// or an age like `12h`, `90d`, `2w`, `6m`, or `1y`.
+        #[clap(long)]
+        newer_than: Option<CliTime>,
+
+        /// Only show backups made before this point.
+        /// This accepts the same formats as `--newer-than`.
+        #[clap(long)]
+        older_than: Option<CliTime>,
+
+        /// Only show backups made by this kind of invocation.
+        /// Backups from before Ludusavi recorded this are `unknown`.
+        #[clap(long, value_parser = possible_values!(TriggerSource, ALL_NAMES))]
+        trigger: Option<TriggerSource>,
+
+        /// Sort the game list by different criteria.
+        /// `size` uses the total size of each game's backups,
+        /// and `status`/`change` sort by name since there's no scan to compare.
+        /// When not specified, games are sorted by name.
+        #[clap(long, value_parser = possible_values!(CliSort, ALL))]
+        sort: Option<CliSort>,
+
+        /// Reverse the sort order (e.g., with `--sort size`, list the largest games first).
+        #[clap(long)]
+        reverse: bool,
+
+        /// Adopt a backup folder (or zip file) that was renamed or migrated by hand.
+        /// It will be validated, given a mapping for the game named by `--as`,
+        /// and moved into that game's folder under the backup path.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path, requires("adopt_as"), conflicts_with("games"))]
+        adopt: Option<StrictPath>,
+
+        /// Game name to use with `--adopt`.
+        /// This must be a game from the manifest or your custom games.
+        #[clap(long = "as", value_name = "GAME", requires("adopt"))]
+        adopt_as: Option<String>,
+
+        /// Write the report to this file instead of printing it to stdout.
+        /// Parent folders are created as needed.
+        /// If the file can't be written, the report is printed instead and the command fails.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        output_file: Option<StrictPath>,
+
         /// Only report these specific games.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
         games: Vec<String>,
+
+        #[clap(subcommand)]
+        sub: Option<BackupsSubcommand>,
+    },
+    /// Check that your backups are intact,
+    /// by recomputing the hash of each stored file and comparing it with the hash
+    /// that was recorded when the file was backed up.
+    /// Missing files, mismatched hashes, and unreadable archives are reported as failures.
+    /// This does not modify any data.
+    Verify {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// Only verify a specific backup, using a name or short ID returned by the `backups` command.
+        /// This is only valid when verifying a single game.
+        #[clap(long)]
+        backup: Option<String>,
+
+        /// Only verify these specific games.
+        /// Alternatively supports stdin (one value per line).
+        #[clap()]
+        games: Vec<String>,
+    },
+    /// Show which files and registry values were added, removed, or modified
+    /// between two backups of the same game,
+    /// based on the hashes recorded in the game's `mapping.yaml`.
+    /// This does not modify any data.
+    Diff {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// The backup to compare from, using a name or short ID returned by the `backups` command.
+        #[clap(long)]
+        old: String,
+
+        /// The backup to compare to, using a name or short ID returned by the `backups` command.
+        /// When unset, this compares against the game's current data,
+        /// as found by a new backup scan.
+        #[clap(long)]
+        new: Option<String>,
+
+        /// The game whose backups to compare.
+        #[clap()]
+        game: String,
+    },
+    /// Delete old backups.
+    /// Locked backups are never deleted and don't count toward the limits,
+    /// and each game's newest backup is kept unless you pass `--allow-latest`.
+    /// Deleting a full backup also deletes its differential backups,
+    /// so a full backup is kept if any of its differential backups must be kept.
+    #[clap(group(ArgGroup::new("prune_rules").required(true).multiple(true).args(["keep_full", "keep_differential", "newer_than", "older_than", "trigger"])))]
+    Prune {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// List the backups that would be deleted, but don't actually delete them.
+        #[clap(long)]
+        preview: bool,
+
+        /// Keep this many of the newest full backups for each game.
+        #[clap(long, value_name = "N")]
+        keep_full: Option<usize>,
+
+        /// Keep this many of the newest differential backups for each full backup.
+        #[clap(long, value_name = "N")]
+        keep_differential: Option<usize>,
+
+        /// Only delete backups made at or after this point.
+        /// This may be a date (`2024-01-01`), an RFC 3339 timestamp,
+        /// or an age like `12h`, `90d`, `2w`, `6m`, or `1y`.
+        #[clap(long)]
+        newer_than: Option<CliTime>,
+
+        /// Only delete backups made before this point.
+        /// This accepts the same formats as `--newer-than`.
+        /// When there's no `--keep-*` rule, all unlocked backups that match
+        /// `--newer-than`, `--older-than`, and `--trigger` are deleted.
+        #[clap(long)]
+        older_than: Option<CliTime>,
+
+        /// Only delete backups made by this kind of invocation.
+        /// Backups from before Ludusavi recorded this are `unknown`.
+        #[clap(long, value_parser = possible_values!(TriggerSource, ALL_NAMES))]
+        trigger: Option<TriggerSource>,
+
+        /// Allow deleting each game's newest backup.
+        #[clap(long)]
+        allow_latest: bool,
+
+        /// Only prune this game.
+        /// This may be repeated for multiple games.
+        /// When unset, all games are pruned.
+        #[clap(long = "game", value_name = "GAME")]
+        games: Vec<String>,
+    },
+    /// Analyze your backups.
+    /// By default, this shows how much space each game's backups take up on disk.
+    /// This does not modify any data.
+    #[clap(group(ArgGroup::new("stats_report").args(["dedup", "history"])))]
+    #[clap(group(ArgGroup::new("stats_history_games").args(["game", "all_games"])))]
+    Stats {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// Find identical files (by their recorded hashes) in the latest backups of different games,
+        /// and report how much space could be saved by only storing one copy.
+        #[clap(long)]
+        dedup: bool,
+
+        /// Show how much data each backup of a game held,
+        /// with one row per backup and the change from the previous backup.
+        #[clap(long, requires("stats_history_games"))]
+        history: bool,
+
+        /// Show the history of every game instead of one specific game.
+        #[clap(long, requires("history"), conflicts_with("game"))]
+        all_games: bool,
+
+        /// Print the history as CSV, such as for loading it into a spreadsheet.
+        #[clap(long, requires("history"), conflicts_with("api"))]
+        csv: bool,
+
+        /// Sort the games in the storage summary.
+        #[clap(long, value_enum, conflicts_with("stats_report"))]
+        sort: Option<StatsSort>,
+
+        /// The game whose history to show.
+        #[clap(requires("history"))]
+        game: Option<String>,
     },
     /// Find game titles
     ///
@@ -327,12 +1082,33 @@ pub enum Subcommand {
         #[clap(long)]
         gog_id: Option<u64>,
 
+        /// Look up game by a Lutris slug.
+        #[clap(long)]
+        lutris_id: Option<String>,
+
         /// Look up game by an approximation of the title.
         /// Ignores capitalization, "edition" suffixes, year suffixes, and some special symbols.
         /// This may find multiple games for a single input.
         #[clap(long)]
         normalized: bool,
 
+        /// Rank games by how closely their titles resemble the input,
+        /// including aliases, custom games, and install folder names.
+        /// Ignores capitalization, punctuation, and trademark symbols.
+        /// Each result includes a similarity score from 0 to 1.
+        #[clap(long, conflicts_with_all = ["steam_id", "gog_id", "lutris_id", "normalized", "missing"])]
+        fuzzy: bool,
+
+        /// Leave out fuzzy matches with a lower score than this (0 to 1).
+        /// Default: 0.6.
+        #[clap(long, requires("fuzzy"))]
+        threshold: Option<CliThreshold>,
+
+        /// Show at most this many fuzzy matches.
+        /// Default: 10.
+        #[clap(long, requires("fuzzy"))]
+        limit: Option<usize>,
+
         /// Select games that are disabled.
         #[clap(long)]
         disabled: bool,
@@ -341,22 +1117,78 @@ pub enum Subcommand {
         #[clap(long)]
         partial: bool,
 
+        /// Select games that seem to be installed, but that don't have any backups yet.
+        /// Games that are disabled for backup are not included.
+        /// With this option, `--path` defaults to the backup path from the config file.
+        #[clap(long)]
+        missing: bool,
+
+        /// Back up the games found by `--missing`,
+        /// the same way as `ludusavi backup --force` would for those games.
+        /// The output is then the same as for the `backup` command.
+        #[clap(long, requires("missing"))]
+        backup_missing: bool,
+
+        /// Also look up the titles listed in this file (one per line),
+        /// or `-` to read them from stdin.
+        /// Blank lines and lines starting with `#` are ignored.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path, conflicts_with = "fuzzy")]
+        games_from: Option<StrictPath>,
+
+        /// Fail if any titles from `--games-from` are unknown.
+        /// Otherwise, they're reported along with the games that were found.
+        #[clap(long, requires("games_from"))]
+        strict: bool,
+
         /// Look up game by an exact title.
         /// With multiple values, they will be checked in the order given.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
         names: Vec<String>,
     },
+    /// Show what Ludusavi would consider when scanning a game, without actually scanning it.
+    /// This includes the combined manifest entry, the roots and expanded paths to check,
+    /// ignore rules, redirects, and the backup and cloud settings that apply.
+    Explain {
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// The game to explain. Aliases are resolved to the game that they refer to.
+        #[clap()]
+        game: String,
+    },
     /// Options for Ludusavi's data set.
     Manifest {
         #[clap(subcommand)]
         sub: ManifestSubcommand,
     },
+    /// Options for Ludusavi's config file.
+    Config {
+        #[clap(subcommand)]
+        sub: ConfigSubcommand,
+    },
+    /// Inspect and manage the configured roots.
+    Roots {
+        #[clap(subcommand)]
+        sub: RootsSubcommand,
+    },
+    /// Manage custom games in the config file.
+    CustomGames {
+        #[clap(subcommand)]
+        sub: CustomGamesSubcommand,
+    },
     /// Cloud sync.
     Cloud {
         #[clap(subcommand)]
         sub: CloudSubcommand,
     },
+    /// Print JSON Schemas for Ludusavi's files and machine-readable output.
+    Schema {
+        #[clap(subcommand)]
+        sub: SchemaSubcommand,
+    },
     /// Wrap restore/backup around game execution
     Wrap {
         #[clap(flatten)]
@@ -372,6 +1204,83 @@ pub enum Subcommand {
         #[clap()]
         commands: Vec<String>,
     },
+    /// Show recent backups, restores, and cloud syncs, oldest first.
+    /// These are only recorded while `history.enabled` is set in the config file.
+    History {
+        /// Only show these kinds of operations.
+        #[clap(long, value_parser = possible_values!(Operation, ALL_NAMES))]
+        operation: Vec<Operation>,
+
+        /// Only include operations that started at or after this point.
+        /// This may be a date (`2024-01-01`), an RFC 3339 timestamp,
+        /// or an age like `12h`, `90d`, `2w`, `6m`, or `1y`.
+        #[clap(long)]
+        since: Option<CliTime>,
+
+        /// Only include operations that started before this point.
+        /// This accepts the same formats as `--since`.
+        #[clap(long)]
+        until: Option<CliTime>,
+
+        /// Only show this many of the most recent operations.
+        #[clap(long)]
+        last: Option<usize>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Publish the results of recent backups and restores over HTTP (read-only).
+    /// This provides `/status` as JSON and `/metrics` in the Prometheus text format.
+    Serve {
+        /// Address on which to listen.
+        #[clap(long, default_value = "127.0.0.1:7575")]
+        bind: String,
+    },
+    /// Keep the manifest loaded and perform backups and restores requested with `--use-daemon`.
+    /// Requests are handled one at a time, in the order they arrive.
+    /// This listens on a local socket in the config folder until the process is stopped.
+    Daemon,
+    /// Handle requests from another program, like a game launcher.
+    /// Each line from stdin should be a JSON request,
+    /// and each one gets a single line of JSON in response on stdout.
+    /// The manifest and config stay loaded until stdin is closed.
+    Api,
+    /// Watch the save locations of some games and back up each one after its saves change.
+    /// This runs until interrupted, and each backup prints its own report.
+    /// Save folders that don't exist yet are picked up once the game creates them.
+    Watch {
+        /// Directory in which to store the backups.
+        /// When not specified, this defers to the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Wait until a game's saves have been left alone for this many seconds before backing it up.
+        #[clap(long, value_name = "SECONDS", default_value_t = 10)]
+        debounce: u64,
+
+        /// Check for changes periodically instead of relying on file system notifications,
+        /// which some file systems (like network shares) don't support.
+        #[clap(long)]
+        poll: bool,
+
+        /// With `--poll`, how many seconds to wait between checks.
+        #[clap(long, value_name = "SECONDS", default_value_t = 2, requires("poll"))]
+        poll_interval: u64,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        /// Use `--api-format json-lines` for one line per game and backup.
+        #[clap(long)]
+        api: bool,
+
+        /// Only watch these games.
+        /// When not specified, this watches the games that already have backups
+        /// (other than those disabled in the config).
+        #[clap()]
+        games: Vec<String>,
+    },
 }
 
 #[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
@@ -388,9 +1297,248 @@ pub enum ManifestSubcommand {
         /// Check again even if the most recent check was within the last 24 hours.
         #[clap(long)]
         force: bool,
+
+        /// Download the manifest again from scratch,
+        /// even if the server reports that it hasn't changed.
+        /// This is useful if your local copy has become unreadable.
+        #[clap(long)]
+        repair: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigSubcommand {
+    /// Bring the config file up to date with the current version of Ludusavi,
+    /// and list what changed (new settings with their default values,
+    /// settings that moved, and obsolete settings that were dropped).
+    /// The file is only rewritten if something actually changed.
+    Migrate {
+        /// List the changes, but don't actually rewrite the file.
+        #[clap(long)]
+        preview: bool,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// List custom game aliases and merged game titles
+    /// that no longer refer to a game in the manifest, a custom game, or a backup.
+    /// If the manifest seems to have renamed a title, its new name is suggested.
+    Validate {
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Remove custom game aliases and merged game titles that no longer refer to anything.
+    /// Titles with a suggested rename are left alone, so that you can update them instead.
+    PruneAliases {
+        /// Actually remove the entries.
+        /// Without this, the entries are only listed.
+        #[clap(long)]
+        apply: bool,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Export custom games, per-game ignored paths and registry entries, and redirects,
+    /// so that they can be imported on another machine.
+    ExportCustom {
+        /// Write the export to this file instead of printing it to stdout.
+        /// Parent folders are created as needed.
+        #[clap(long, value_name = "PATH", value_parser = parse_strict_path)]
+        output: Option<StrictPath>,
+    },
+    /// Import a file from `config export-custom`.
+    /// Entries that already exist with different settings are skipped by default.
+    ImportCustom {
+        /// The exported file.
+        #[clap(value_parser = parse_strict_path)]
+        file: StrictPath,
+
+        /// Keep existing entries when they conflict with the imported ones.
+        /// This is the default.
+        #[clap(long, conflicts_with("replace"))]
+        merge: bool,
+
+        /// Overwrite existing entries when they conflict with the imported ones.
+        #[clap(long)]
+        replace: bool,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum BackupsSubcommand {
+    /// Set or clear the comment on one of a game's backups,
+    /// then show that game's backups.
+    #[clap(group(ArgGroup::new("comment_action").required(true).args(["set", "clear"])))]
+    Comment {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// Which backup to update, using a name or short ID returned by the `backups` command.
+        /// When unset, this updates the latest backup.
+        #[clap(long)]
+        backup: Option<String>,
+
+        /// The new comment. This may span multiple lines.
+        #[clap(long, value_name = "TEXT")]
+        set: Option<String>,
+
+        /// Remove the comment.
+        #[clap(long)]
+        clear: bool,
+
+        /// The game whose backup to update.
+        #[clap()]
+        game: String,
+    },
+    /// Delete one of a game's backups, including its files.
+    Delete {
+        /// Directory in which to find backups.
+        /// When unset, this defaults to the restore path from the config file.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: Option<StrictPath>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+
+        /// Which backup to delete, using a name or short ID returned by the `backups` command.
+        #[clap(long)]
+        backup: String,
+
+        /// When deleting a full backup, also delete any differential backups that depend on it.
+        #[clap(long)]
+        cascade: bool,
+
+        /// Delete the backup even if it (or one of its differential backups) is locked.
+        #[clap(long)]
+        force: bool,
+
+        /// The game whose backup to delete.
+        #[clap()]
+        game: String,
+    },
+}
+
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum RootsSubcommand {
+    /// List the configured roots, numbered for `roots remove`,
+    /// marking any that lead to the same place as another root of the same store
+    /// or that are inside another root for the same launcher.
+    List {
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Remove redundant roots from the config file.
+    /// When several roots lead to the same place, the first one listed is kept.
+    /// When a launcher root is inside another root for the same launcher, the broadest one is kept.
+    Dedupe {
+        /// Actually remove the roots.
+        /// Without this, the redundant roots are only listed.
+        #[clap(long)]
+        apply: bool,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Add a root to the config file.
+    Add {
+        /// The root's store, using the same names as the config file.
+        #[clap(long = "type", value_name = "STORE", default_value = "other", value_parser = possible_values!(Store, ALL_CLI))]
+        store: Store,
+
+        /// The root's folder.
+        #[clap(long, value_parser = parse_strict_path)]
+        path: StrictPath,
+
+        /// Add the root even if the folder doesn't exist yet.
+        #[clap(long)]
+        force: bool,
+    },
+    /// Remove roots from the config file.
+    Remove {
+        /// Either the root's number from `roots list` or its path.
+        /// A path removes the roots of every store at that location.
+        #[clap()]
+        root: String,
+    },
+}
+
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum CustomGamesSubcommand {
+    /// List the custom games,
+    /// marking any that replace a manifest entry of the same name.
+    List {
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
+    /// Add a custom game.
+    Add {
+        /// A file or folder to back up.
+        /// This supports the same placeholders as the manifest (e.g., `<home>` or `<winDocuments>`)
+        /// and globs.
+        /// Can be repeated.
+        #[clap(long = "file", value_name = "PATH", required_unless_present = "registry")]
+        files: Vec<String>,
+
+        /// A registry key to back up.
+        /// Can be repeated.
+        #[clap(long = "registry", value_name = "KEY")]
+        registry: Vec<String>,
+
+        /// Allow the name to match a game from the manifest.
+        /// The custom game is then used instead of the manifest entry.
+        #[clap(long = "override")]
+        override_manifest: bool,
+
+        /// The game's name.
+        #[clap()]
+        name: String,
+    },
+    /// Remove a custom game.
+    Remove {
+        /// The game's name.
+        #[clap()]
+        name: String,
     },
 }
 
+#[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum SchemaSubcommand {
+    /// The report from `--api` mode (e.g., `backup --api` or `restore --api`).
+    /// This describes the latest API version.
+    #[clap(visible_alias = "general-output")]
+    Api,
+    /// The config file (`config.yaml`).
+    Config,
+    /// The manifest file (`manifest.yaml`), including secondary manifests.
+    Manifest,
+}
+
 #[derive(clap::Subcommand, Clone, Debug, PartialEq, Eq)]
 pub enum CloudSubcommand {
     /// Configure the cloud system to use.
@@ -423,11 +1571,35 @@ pub enum CloudSubcommand {
         #[clap(long)]
         api: bool,
 
+        /// Report the changes in the same shape as the `backup` and `restore` commands,
+        /// under a top-level `cloud` key.
+        #[clap(long)]
+        merged: bool,
+
+        /// With `--preview`, exit with code 2 if any files would be uploaded or removed.
+        /// The report is still printed as usual,
+        /// and code 1 is still used for errors.
+        #[clap(long, requires("preview"))]
+        fail_on_change: bool,
+
         /// Only sync these specific games.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
         games: Vec<String>,
     },
+    /// Show how much data was transferred by previous cloud syncs, per day and per month.
+    History {
+        /// Only include syncs at or after this point.
+        /// This may be a date (`2024-01-01`), an RFC 3339 timestamp,
+        /// or an age like `12h`, `90d`, `2w`, `6m`, or `1y`.
+        #[clap(long)]
+        since: Option<CliTime>,
+
+        /// Print information to stdout in machine-readable JSON.
+        /// This replaces the default, human-readable output.
+        #[clap(long)]
+        api: bool,
+    },
     /// Download your cloud backups, overwriting any existing local backups.
     Download {
         /// Local folder path for backups.
@@ -453,6 +1625,11 @@ pub enum CloudSubcommand {
         #[clap(long)]
         api: bool,
 
+        /// Report the changes in the same shape as the `backup` and `restore` commands,
+        /// under a top-level `cloud` key.
+        #[clap(long)]
+        merged: bool,
+
         /// Only sync these specific games.
         /// Alternatively supports stdin (one value per line).
         #[clap()]
@@ -558,11 +1735,67 @@ pub struct Cli {
     #[clap(long)]
     pub try_manifest_update: bool,
 
+    /// If the manifest can't be loaded and there's no usable older copy,
+    /// continue with only your custom games instead of failing.
+    #[clap(long)]
+    pub ignore_invalid_manifest: bool,
+
+    /// Display text in this language instead of the one from the config file.
+    /// Use `none` or `C` for the untranslated English text,
+    /// which is useful when parsing the output in scripts.
+    #[clap(long, value_name = "CODE", value_parser = parse_language)]
+    pub language: Option<Language>,
+
+    /// Render `--api` output in an older major version of the JSON schema.
+    /// This is meant for tools that haven't been updated for the latest schema yet.
+    /// Supported versions: 1 (Ludusavi v0.22.0), 2 (current, default).
+    #[clap(long, value_name = "N", global = true)]
+    pub api_version: Option<ApiVersion>,
+
+    /// Serialize `--api` output in this format.
+    /// JSON and YAML have the same structure.
+    /// JSON Lines prints one object per game as it's reported,
+    /// then a final object with everything else (e.g., `overall` and `errors`).
+    /// CSV prints one row per file and registry entry,
+    /// with everything else printed as JSON on stderr.
+    /// Markdown renders the report in tables for pasting into issue trackers and wikis.
+    #[clap(long, value_name = "FORMAT", value_enum, global = true)]
+    pub api_format: Option<ApiFormat>,
+
+    /// Highlight changes, failures, and duplicates in the human-readable output.
+    /// By default, this only happens when printing to a terminal.
+    #[clap(long, value_name = "WHEN", value_enum, global = true)]
+    pub color: Option<ColorChoice>,
+
+    /// Print progress events to stderr as JSON lines during backups, restores, and cloud operations,
+    /// for wrapper programs that want to show a progress bar.
+    /// The report is still printed at the end as usual.
+    #[clap(long, global = true)]
+    pub progress: bool,
+
     #[clap(subcommand)]
     pub sub: Option<Subcommand>,
 }
 
 impl Cli {
+    /// Clap doesn't detect conflicts with global options, so those are checked here.
+    pub fn validate(self) -> Result<Self, clap::Error> {
+        use clap::CommandFactory;
+
+        let use_daemon = matches!(
+            self.sub,
+            Some(Subcommand::Backup { use_daemon: true, .. } | Subcommand::Restore { use_daemon: true, .. })
+        );
+        if use_daemon && self.progress {
+            return Err(Self::command().error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "the argument '--use-daemon' cannot be used with '--progress'",
+            ));
+        }
+
+        Ok(self)
+    }
+
     pub fn relaunch_gui_args(&self) -> Vec<String> {
         let mut args = vec![];
 
@@ -577,6 +1810,10 @@ impl Cli {
         if self.try_manifest_update {
             args.push("--try-manifest-update".into());
         }
+        if let Some(language) = self.language {
+            args.push("--language".into());
+            args.push(language.id().to_string());
+        }
 
         args
     }
@@ -600,64 +1837,356 @@ mod tests {
     }
 
     #[test]
-    fn accepts_cli_without_arguments() {
+    fn accepts_cli_language_override() {
+        let cases = [
+            ("de-DE", Language::German),
+            ("de", Language::German),
+            ("PT-br", Language::PortugueseBrazilian),
+            ("en-US", Language::English),
+            ("none", Language::English),
+            ("C", Language::English),
+        ];
+        for (code, language) in cases {
+            check_args(
+                &["ludusavi", "--language", code],
+                Cli {
+                    config: None,
+                    no_manifest_update: false,
+                    try_manifest_update: false,
+                    ignore_invalid_manifest: false,
+                    language: Some(language),
+                    api_version: None,
+                    api_format: None,
+                    color: None,
+                    progress: false,
+                    sub: None,
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_cli_unknown_language() {
+        check_args_err(
+            &["ludusavi", "--language", "xx"],
+            clap::error::ErrorKind::ValueValidation,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_api_version() {
         check_args(
-            &["ludusavi"],
+            &["ludusavi", "--api-version", "1"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: Some(ApiVersion::V1),
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: None,
             },
         );
+        assert_eq!(
+            Some(ApiVersion::V1),
+            Cli::parse_from(["ludusavi", "find", "--api", "--api-version", "1"]).api_version
+        );
     }
 
     #[test]
-    fn accepts_cli_backup_with_minimal_arguments() {
+    fn accepts_cli_api_format() {
         check_args(
-            &["ludusavi", "backup"],
+            &["ludusavi", "--api-format", "yaml"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
-                sub: Some(Subcommand::Backup {
-                    preview: false,
-                    path: None,
-                    force: false,
-                    merge: false,
-                    no_merge: false,
-                    update: false,
-                    try_update: false,
-                    wine_prefix: None,
-                    api: false,
-                    sort: None,
-                    format: None,
-                    compression: None,
-                    compression_level: None,
-                    full_limit: None,
-                    differential_limit: None,
-                    cloud_sync: false,
-                    no_cloud_sync: false,
-                    games: vec![],
-                }),
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: Some(ApiFormat::Yaml),
+                color: None,
+                progress: false,
+                sub: None,
             },
         );
+        assert_eq!(
+            Some(ApiFormat::Yaml),
+            Cli::parse_from(["ludusavi", "backup", "--api", "--api-format", "yaml"]).api_format
+        );
+        assert_eq!(
+            Some(ApiFormat::JsonLines),
+            Cli::parse_from(["ludusavi", "backup", "--api", "--api-format", "json-lines"]).api_format
+        );
+        assert_eq!(
+            Some(ApiFormat::Csv),
+            Cli::parse_from(["ludusavi", "backup", "--api", "--api-format", "csv"]).api_format
+        );
+        assert_eq!(
+            Some(ApiFormat::Markdown),
+            Cli::parse_from(["ludusavi", "backup", "--api", "--api-format", "markdown"]).api_format
+        );
+        check_args_err(
+            &["ludusavi", "--api-format", "toml"],
+            clap::error::ErrorKind::InvalidValue,
+        );
     }
 
     #[test]
-    fn accepts_cli_backup_with_all_arguments() {
-        check_args(
-            &[
-                "ludusavi",
-                "backup",
-                "--preview",
-                "--path",
-                "tests/backup",
-                "--force",
-                "--merge",
-                "--update",
-                "--wine-prefix",
-                "tests/wine-prefix",
+    fn accepts_cli_color() {
+        assert_eq!(
+            Some(ColorChoice::Never),
+            Cli::parse_from(["ludusavi", "backup", "--color", "never"]).color
+        );
+        assert_eq!(
+            Some(ColorChoice::Always),
+            Cli::parse_from(["ludusavi", "--color", "always", "backup"]).color
+        );
+        assert_eq!(None, Cli::parse_from(["ludusavi", "backup"]).color);
+        check_args_err(
+            &["ludusavi", "--color", "rainbow"],
+            clap::error::ErrorKind::InvalidValue,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_progress() {
+        assert!(Cli::parse_from(["ludusavi", "backup", "--progress"]).progress);
+        assert!(Cli::parse_from(["ludusavi", "--progress", "cloud", "upload"]).progress);
+        assert!(!Cli::parse_from(["ludusavi", "restore"]).progress);
+    }
+
+    #[test]
+    fn rejects_cli_unknown_api_version() {
+        check_args_err(
+            &["ludusavi", "--api-version", "99"],
+            clap::error::ErrorKind::ValueValidation,
+        );
+        check_args_err(
+            &["ludusavi", "--api-version", "latest"],
+            clap::error::ErrorKind::ValueValidation,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_without_arguments() {
+        check_args(
+            &["ludusavi"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: None,
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_minimal_arguments() {
+        check_args(
+            &["ludusavi", "backup"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backup {
+                    preview: false,
+                    path: None,
+                    force: false,
+                    merge: false,
+                    no_merge: false,
+                    update: false,
+                    try_update: false,
+                    wine_prefix: None,
+                    api: false,
+                    sort: None,
+                    reverse: false,
+                    format: None,
+                    compression: None,
+                    compression_level: None,
+                    full_limit: None,
+                    differential_limit: None,
+                    cloud_sync: false,
+                    no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
+                    games: vec![],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_daemon() {
+        check_args(
+            &["ludusavi", "backup", "--use-daemon", "--preview", "--api", "foo"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backup {
+                    preview: true,
+                    path: None,
+                    force: false,
+                    merge: false,
+                    no_merge: false,
+                    update: false,
+                    try_update: false,
+                    wine_prefix: None,
+                    api: true,
+                    sort: None,
+                    reverse: false,
+                    format: None,
+                    compression: None,
+                    compression_level: None,
+                    full_limit: None,
+                    differential_limit: None,
+                    cloud_sync: false,
+                    no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: true,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
+                    games: vec![s("foo")],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_daemon_with_progress() {
+        for sub in ["backup", "restore"] {
+            assert!(Cli::try_parse_from(["ludusavi", sub, "--use-daemon", "--progress"])
+                .and_then(Cli::validate)
+                .is_err());
+            assert!(Cli::try_parse_from(["ludusavi", "--progress", sub, "--use-daemon"])
+                .and_then(Cli::validate)
+                .is_err());
+            assert!(Cli::try_parse_from(["ludusavi", sub, "--use-daemon"])
+                .and_then(Cli::validate)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_daemon_and_other_options() {
+        let cli = Cli::try_parse_from(["ludusavi", "backup", "--use-daemon", "--format", "zip"]).unwrap();
+        assert!(matches!(
+            cli.sub,
+            Some(Subcommand::Backup {
+                use_daemon: true,
+                format: Some(BackupFormat::Zip),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_cli_backup_with_include_but_not_exclude() {
+        check_args_err(
+            &["ludusavi", "backup", "--include", "/saves"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_daemon_and_store_id() {
+        let cli = Cli::try_parse_from(["ludusavi", "backup", "--use-daemon", "--steam-id", "101"]).unwrap();
+        assert!(matches!(
+            cli.sub,
+            Some(Subcommand::Backup {
+                use_daemon: true,
+                steam_id: Some(101),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_all_arguments() {
+        check_args(
+            &[
+                "ludusavi",
+                "backup",
+                "--preview",
+                "--path",
+                "tests/backup",
+                "--force",
+                "--merge",
+                "--update",
+                "--wine-prefix",
+                "tests/wine-prefix",
                 "--api",
                 "--sort",
                 "name",
@@ -672,6 +2201,27 @@ mod tests {
                 "--differential-limit",
                 "2",
                 "--cloud-sync",
+                "--repair",
+                "--force-game",
+                "game3",
+                "--force-game",
+                "game4",
+                "--comment",
+                "before respec",
+                "--lock",
+                "--scheduled",
+                "--verbose",
+                "--full-file-list",
+                "--exclude",
+                "**/autosave",
+                "--include",
+                "**/autosave/keep.sav",
+                "--steam-id",
+                "101",
+                "--gog-id",
+                "102",
+                "--lutris-id",
+                "game-slug",
                 "game1",
                 "game2",
             ],
@@ -679,6 +2229,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backup {
                     preview: true,
                     path: Some(StrictPath::new(s("tests/backup"))),
@@ -690,6 +2246,7 @@ mod tests {
                     wine_prefix: Some(StrictPath::new(s("tests/wine-prefix"))),
                     api: true,
                     sort: Some(CliSort::Name),
+                    reverse: false,
                     format: Some(BackupFormat::Zip),
                     compression: Some(ZipCompression::Bzip2),
                     compression_level: Some(5),
@@ -697,6 +2254,34 @@ mod tests {
                     differential_limit: Some(2),
                     cloud_sync: true,
                     no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: true,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![s("game3"), s("game4")],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![s("**/autosave")],
+                    include: vec![s("**/autosave/keep.sav")],
+                    comment: Some(s("before respec")),
+                    lock: true,
+                    scheduled: true,
+                    trigger: None,
+                    verbose: true,
+                    full_file_list: true,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: Some(101),
+                    gog_id: Some(102),
+                    lutris_id: Some(s("game-slug")),
+                    games_from: None,
+                    strict: false,
                     games: vec![s("game1"), s("game2")],
                 }),
             },
@@ -711,6 +2296,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backup {
                     preview: false,
                     path: Some(StrictPath::new(s("tests/fake"))),
@@ -722,6 +2313,7 @@ mod tests {
                     wine_prefix: None,
                     api: false,
                     sort: None,
+                    reverse: false,
                     format: None,
                     compression: None,
                     compression_level: None,
@@ -729,6 +2321,34 @@ mod tests {
                     differential_limit: None,
                     cloud_sync: false,
                     no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
                     games: vec![],
                 }),
             },
@@ -743,6 +2363,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backup {
                     preview: false,
                     path: None,
@@ -754,6 +2380,7 @@ mod tests {
                     wine_prefix: None,
                     api: false,
                     sort: None,
+                    reverse: false,
                     format: None,
                     compression: None,
                     compression_level: None,
@@ -761,6 +2388,34 @@ mod tests {
                     differential_limit: None,
                     cloud_sync: false,
                     no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
                     games: vec![],
                 }),
             },
@@ -775,6 +2430,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backup {
                     preview: false,
                     path: None,
@@ -786,6 +2447,7 @@ mod tests {
                     wine_prefix: None,
                     api: false,
                     sort: None,
+                    reverse: false,
                     format: None,
                     compression: None,
                     compression_level: None,
@@ -793,6 +2455,34 @@ mod tests {
                     differential_limit: None,
                     cloud_sync: false,
                     no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
                     games: vec![],
                 }),
             },
@@ -814,6 +2504,9 @@ mod tests {
             ("name-rev", CliSort::NameReversed),
             ("size", CliSort::Size),
             ("size-rev", CliSort::SizeReversed),
+            ("status", CliSort::Status),
+            ("change", CliSort::Status),
+            ("change-rev", CliSort::StatusReversed),
         ];
 
         for (value, sort) in cases {
@@ -823,6 +2516,12 @@ mod tests {
                     config: None,
                     no_manifest_update: false,
                     try_manifest_update: false,
+                    ignore_invalid_manifest: false,
+                    language: None,
+                    api_version: None,
+                    api_format: None,
+                    color: None,
+                    progress: false,
                     sub: Some(Subcommand::Backup {
                         preview: false,
                         path: None,
@@ -834,6 +2533,7 @@ mod tests {
                         wine_prefix: None,
                         api: false,
                         sort: Some(sort),
+                        reverse: false,
                         format: None,
                         compression: None,
                         compression_level: None,
@@ -841,6 +2541,34 @@ mod tests {
                         differential_limit: None,
                         cloud_sync: false,
                         no_cloud_sync: false,
+                        allow_metered: false,
+                        repair: false,
+                        retry_locked: None,
+                        skip_running: false,
+                        force_game: vec![],
+                        if_changed: false,
+                        if_changed_since: None,
+                        if_changed_depth: 3,
+                        no_redirects: false,
+                        redirect: vec![],
+                        exclude: vec![],
+                        include: vec![],
+                        comment: None,
+                        lock: false,
+                        scheduled: false,
+                        trigger: None,
+                        verbose: false,
+                        full_file_list: false,
+                        only_changed: false,
+                        only_failed: false,
+                        fail_on_change: false,
+                        output_file: None,
+                        use_daemon: false,
+                        steam_id: None,
+                        gog_id: None,
+                        lutris_id: None,
+                        games_from: None,
+                        strict: false,
                         games: vec![],
                     }),
                 },
@@ -848,6 +2576,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn accepts_cli_backup_with_if_changed_since() {
+        check_args(
+            &[
+                "ludusavi",
+                "backup",
+                "--if-changed-since",
+                "30m",
+                "--if-changed-depth",
+                "5",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backup {
+                    preview: false,
+                    path: None,
+                    force: false,
+                    merge: false,
+                    no_merge: false,
+                    update: false,
+                    try_update: false,
+                    wine_prefix: None,
+                    api: false,
+                    sort: None,
+                    reverse: false,
+                    format: None,
+                    compression: None,
+                    compression_level: None,
+                    full_limit: None,
+                    differential_limit: None,
+                    cloud_sync: false,
+                    no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: Some(CliDuration(chrono::Duration::minutes(30))),
+                    if_changed_depth: 5,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
+                    games: vec![],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_if_changed() {
+        let cli = Cli::try_parse_from(["ludusavi", "backup", "--if-changed", "--only-changed"]).unwrap();
+        assert!(matches!(
+            cli.sub,
+            Some(Subcommand::Backup {
+                if_changed: true,
+                only_changed: true,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["ludusavi", "backup", "--if-changed", "--use-daemon"]).is_ok());
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_retry_locked() {
+        let cli = Cli::try_parse_from(["ludusavi", "backup", "--retry-locked", "3"]).unwrap();
+        assert!(matches!(
+            cli.sub,
+            Some(Subcommand::Backup {
+                retry_locked: Some(3),
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["ludusavi", "backup", "--retry-locked", "-1"]).is_err());
+    }
+
+    #[test]
+    fn accepts_cli_backup_with_skip_running() {
+        let cli = Cli::try_parse_from(["ludusavi", "backup", "--skip-running"]).unwrap();
+        assert!(matches!(cli.sub, Some(Subcommand::Backup { skip_running: true, .. })));
+    }
+
+    #[test]
+    fn rejects_cli_backup_with_if_changed_depth_alone() {
+        check_args_err(
+            &["ludusavi", "backup", "--if-changed-depth", "5"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
     #[test]
     fn accepts_cli_backup_with_negative_compression_level() {
         check_args(
@@ -856,6 +2699,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backup {
                     preview: false,
                     path: None,
@@ -867,6 +2716,7 @@ mod tests {
                     wine_prefix: None,
                     api: false,
                     sort: None,
+                    reverse: false,
                     format: None,
                     compression: None,
                     compression_level: Some(-7),
@@ -874,6 +2724,34 @@ mod tests {
                     differential_limit: None,
                     cloud_sync: false,
                     no_cloud_sync: false,
+                    allow_metered: false,
+                    repair: false,
+                    retry_locked: None,
+                    skip_running: false,
+                    force_game: vec![],
+                    if_changed: false,
+                    if_changed_since: None,
+                    if_changed_depth: 3,
+                    no_redirects: false,
+                    redirect: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    comment: None,
+                    lock: false,
+                    scheduled: false,
+                    trigger: None,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    fail_on_change: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
                     games: vec![],
                 }),
             },
@@ -888,15 +2766,46 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Restore {
                     preview: false,
                     path: None,
                     force: false,
+                    yes: false,
                     api: false,
                     sort: None,
+                    reverse: false,
                     backup: None,
+                    only_path: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    no_clobber_newer: false,
+                    backup_before_restore: false,
+                    no_backup_before_restore: false,
                     cloud_sync: false,
                     no_cloud_sync: false,
+                    no_redirects: false,
+                    redirect: vec![],
+                    migrate_paths: false,
+                    prefer_account: None,
+                    registry_into_hive: None,
+                    with_files: false,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
                     games: vec![],
                 }),
             },
@@ -913,12 +2822,37 @@ mod tests {
                 "--path",
                 "tests/backup",
                 "--force",
+                "--yes",
                 "--api",
                 "--sort",
                 "name",
                 "--backup",
                 ".",
+                "--only-path",
+                "**/*.sav",
+                "--only-path",
+                "/saves",
                 "--cloud-sync",
+                "--migrate-paths",
+                "--prefer-account",
+                "12345678",
+                "--registry-into-hive",
+                "tests/backup",
+                "--with-files",
+                "--verbose",
+                "--full-file-list",
+                "--exclude",
+                "**/autosave",
+                "--include",
+                "**/autosave/keep.sav",
+                "--no-clobber-newer",
+                "--backup-before-restore",
+                "--steam-id",
+                "101",
+                "--gog-id",
+                "102",
+                "--lutris-id",
+                "game-slug",
                 "game1",
                 "game2",
             ],
@@ -926,21 +2860,169 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Restore {
                     preview: true,
                     path: Some(StrictPath::new(s("tests/backup"))),
                     force: true,
+                    yes: true,
                     api: true,
                     sort: Some(CliSort::Name),
+                    reverse: false,
                     backup: Some(s(".")),
+                    only_path: vec![s("**/*.sav"), s("/saves")],
+                    exclude: vec![s("**/autosave")],
+                    include: vec![s("**/autosave/keep.sav")],
+                    no_clobber_newer: true,
+                    backup_before_restore: true,
+                    no_backup_before_restore: false,
                     cloud_sync: true,
                     no_cloud_sync: false,
+                    no_redirects: false,
+                    redirect: vec![],
+                    migrate_paths: true,
+                    prefer_account: Some(s("12345678")),
+                    registry_into_hive: Some(StrictPath::new(s("tests/backup"))),
+                    with_files: true,
+                    verbose: true,
+                    full_file_list: true,
+                    only_changed: false,
+                    only_failed: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: Some(101),
+                    gog_id: Some(102),
+                    lutris_id: Some(s("game-slug")),
+                    games_from: None,
+                    strict: false,
                     games: vec![s("game1"), s("game2")],
                 }),
             },
         );
     }
 
+    #[test]
+    fn accepts_cli_restore_with_redirect_overrides() {
+        check_args(
+            &[
+                "ludusavi",
+                "restore",
+                "--no-redirects",
+                "--redirect",
+                "/old=/new",
+                "--redirect",
+                "C:/old=D:/new:bidirectional",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Restore {
+                    preview: false,
+                    path: None,
+                    force: false,
+                    yes: false,
+                    api: false,
+                    sort: None,
+                    reverse: false,
+                    backup: None,
+                    only_path: vec![],
+                    exclude: vec![],
+                    include: vec![],
+                    no_clobber_newer: false,
+                    backup_before_restore: false,
+                    no_backup_before_restore: false,
+                    cloud_sync: false,
+                    no_cloud_sync: false,
+                    no_redirects: true,
+                    redirect: vec![
+                        CliRedirect {
+                            source: StrictPath::new(s("/old")),
+                            target: StrictPath::new(s("/new")),
+                            kind: None,
+                        },
+                        CliRedirect {
+                            source: StrictPath::new(s("C:/old")),
+                            target: StrictPath::new(s("D:/new")),
+                            kind: Some(RedirectKind::Bidirectional),
+                        },
+                    ],
+                    migrate_paths: false,
+                    prefer_account: None,
+                    registry_into_hive: None,
+                    with_files: false,
+                    verbose: false,
+                    full_file_list: false,
+                    only_changed: false,
+                    only_failed: false,
+                    output_file: None,
+                    use_daemon: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    games_from: None,
+                    strict: false,
+                    games: vec![],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_restore_with_invalid_redirect() {
+        for value in ["/old", "=/new", "/old=", "/old=:restore"] {
+            check_args_err(
+                &["ludusavi", "restore", "--redirect", value],
+                clap::error::ErrorKind::ValueValidation,
+            );
+        }
+    }
+
+    #[test]
+    fn can_parse_cli_redirect() {
+        let parse = |input: &str| input.parse::<CliRedirect>();
+        let redirect = |source: &str, target: &str, kind: Option<RedirectKind>| CliRedirect {
+            source: StrictPath::new(s(source)),
+            target: StrictPath::new(s(target)),
+            kind,
+        };
+
+        assert_eq!(Ok(redirect("/a", "/b", None)), parse("/a=/b"));
+        assert_eq!(
+            Ok(redirect("/a", "/b", Some(RedirectKind::Backup))),
+            parse("/a=/b:backup")
+        );
+        assert_eq!(Ok(redirect("C:/a", "D:/b", None)), parse("C:/a=D:/b"));
+        assert_eq!(Ok(redirect("C:/a", "D:", None)), parse("C:/a=D:"));
+        assert_eq!(
+            Ok(redirect("C:/a", "D:/b", Some(RedirectKind::Restore))),
+            parse("C:/a=D:/b:restore")
+        );
+        assert_eq!(Ok(redirect("/a", "/b:sideways", None)), parse("/a=/b:sideways"));
+        assert_eq!(
+            Ok(redirect("/saves:old", "/mnt/saves:new", None)),
+            parse("/saves:old=/mnt/saves:new")
+        );
+        assert_eq!(
+            Ok(redirect("/a", "/mnt/saves:new", Some(RedirectKind::Bidirectional))),
+            parse("/a=/mnt/saves:new:bidirectional")
+        );
+        assert_eq!(Err(s("expected `<SOURCE>=<TARGET>[:<KIND>]`: /a")), parse("/a"));
+        assert_eq!(Err(s("missing source path: =/b")), parse("=/b"));
+        assert_eq!(Err(s("missing target path: /a=:backup")), parse("/a=:backup"));
+    }
+
     #[test]
     fn rejects_cli_restore_with_nonexistent_path() {
         check_args_err(
@@ -965,15 +3047,46 @@ mod tests {
                     config: None,
                     no_manifest_update: false,
                     try_manifest_update: false,
+                    ignore_invalid_manifest: false,
+                    language: None,
+                    api_version: None,
+                    api_format: None,
+                    color: None,
+                    progress: false,
                     sub: Some(Subcommand::Restore {
                         preview: false,
                         path: None,
                         force: false,
+                        yes: false,
                         api: false,
                         sort: Some(sort),
+                        reverse: false,
                         backup: None,
+                        only_path: vec![],
+                        exclude: vec![],
+                        include: vec![],
+                        no_clobber_newer: false,
+                        backup_before_restore: false,
+                        no_backup_before_restore: false,
                         cloud_sync: false,
                         no_cloud_sync: false,
+                        no_redirects: false,
+                        redirect: vec![],
+                        migrate_paths: false,
+                        prefer_account: None,
+                        registry_into_hive: None,
+                        with_files: false,
+                        verbose: false,
+                        full_file_list: false,
+                        only_changed: false,
+                        only_failed: false,
+                        output_file: None,
+                        use_daemon: false,
+                        steam_id: None,
+                        gog_id: None,
+                        lutris_id: None,
+                        games_from: None,
+                        strict: false,
                         games: vec![],
                     }),
                 },
@@ -989,115 +3102,1380 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Complete {
-                    shell: CompletionShell::Bash,
+                    shell: Some(CompletionShell::Bash),
+                    list_games: None,
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_complete_for_fish() {
+    fn accepts_cli_manifest_update_with_repair() {
         check_args(
-            &["ludusavi", "complete", "fish"],
+            &["ludusavi", "manifest", "update", "--repair"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
-                sub: Some(Subcommand::Complete {
-                    shell: CompletionShell::Fish,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Manifest {
+                    sub: ManifestSubcommand::Update {
+                        force: false,
+                        repair: true,
+                    },
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_complete_for_zsh() {
+    fn accepts_cli_config_migrate() {
         check_args(
-            &["ludusavi", "complete", "zsh"],
+            &["ludusavi", "config", "migrate", "--preview", "--api"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
-                sub: Some(Subcommand::Complete {
-                    shell: CompletionShell::Zsh,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Config {
+                    sub: ConfigSubcommand::Migrate {
+                        preview: true,
+                        api: true,
+                    },
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_complete_for_powershell() {
+    fn accepts_cli_roots_dedupe() {
         check_args(
-            &["ludusavi", "complete", "powershell"],
+            &["ludusavi", "roots", "dedupe", "--apply", "--api"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
-                sub: Some(Subcommand::Complete {
-                    shell: CompletionShell::PowerShell,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Roots {
+                    sub: RootsSubcommand::Dedupe { apply: true, api: true },
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_complete_for_elvish() {
+    fn accepts_cli_roots_add() {
         check_args(
-            &["ludusavi", "complete", "elvish"],
+            &[
+                "ludusavi",
+                "roots",
+                "add",
+                "--type",
+                "steam",
+                "--path",
+                "/mnt/steam",
+                "--force",
+            ],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Roots {
+                    sub: RootsSubcommand::Add {
+                        store: Store::Steam,
+                        path: StrictPath::new(s("/mnt/steam")),
+                        force: true,
+                    },
+                }),
+            },
+        );
+
+        assert!(matches!(
+            Cli::parse_from(["ludusavi", "roots", "add", "--path", "/games"]).sub,
+            Some(Subcommand::Roots {
+                sub: RootsSubcommand::Add {
+                    store: Store::Other,
+                    force: false,
+                    ..
+                }
+            })
+        ));
+        assert!(Cli::try_parse_from(["ludusavi", "roots", "add", "--type", "bogus", "--path", "/games"]).is_err());
+    }
+
+    #[test]
+    fn accepts_cli_roots_remove() {
+        check_args(
+            &["ludusavi", "roots", "remove", "2"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Roots {
+                    sub: RootsSubcommand::Remove { root: s("2") },
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_custom_games_add() {
+        check_args(
+            &[
+                "ludusavi",
+                "custom-games",
+                "add",
+                "--file",
+                "<home>/foo",
+                "--file",
+                "<winDocuments>/bar",
+                "--registry",
+                "HKEY_CURRENT_USER/Software/Foo",
+                "--override",
+                "Foo",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::CustomGames {
+                    sub: CustomGamesSubcommand::Add {
+                        files: vec![s("<home>/foo"), s("<winDocuments>/bar")],
+                        registry: vec![s("HKEY_CURRENT_USER/Software/Foo")],
+                        override_manifest: true,
+                        name: s("Foo"),
+                    },
+                }),
+            },
+        );
+
+        assert!(Cli::try_parse_from(["ludusavi", "custom-games", "add", "Foo"]).is_err());
+        assert!(Cli::try_parse_from(["ludusavi", "custom-games", "add", "--registry", "HKCU/Foo", "Foo"]).is_ok());
+    }
+
+    #[test]
+    fn accepts_cli_schema_api() {
+        check_args(
+            &["ludusavi", "schema", "api"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Schema {
+                    sub: SchemaSubcommand::Api,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_schema_general_output_alias() {
+        check_args(
+            &["ludusavi", "schema", "general-output"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Schema {
+                    sub: SchemaSubcommand::Api,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_schema_config() {
+        check_args(
+            &["ludusavi", "schema", "config"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Schema {
+                    sub: SchemaSubcommand::Config,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_config_prune_aliases() {
+        check_args(
+            &["ludusavi", "config", "prune-aliases", "--apply", "--api"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Config {
+                    sub: ConfigSubcommand::PruneAliases { apply: true, api: true },
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_config_export_custom() {
+        check_args(
+            &["ludusavi", "config", "export-custom", "--output", "custom.yaml"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Config {
+                    sub: ConfigSubcommand::ExportCustom {
+                        output: Some(StrictPath::new(s("custom.yaml"))),
+                    },
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_config_import_custom() {
+        check_args(
+            &[
+                "ludusavi",
+                "config",
+                "import-custom",
+                "--replace",
+                "--api",
+                "custom.yaml",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Config {
+                    sub: ConfigSubcommand::ImportCustom {
+                        file: StrictPath::new(s("custom.yaml")),
+                        merge: false,
+                        replace: true,
+                        api: true,
+                    },
+                }),
+            },
+        );
+        assert!(Cli::try_parse_from(["ludusavi", "config", "import-custom", "--merge", "--replace", "x"]).is_err());
+    }
+
+    #[test]
+    fn accepts_cli_explain() {
+        check_args(
+            &["ludusavi", "explain", "--api", "game1"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Explain {
+                    api: true,
+                    game: s("game1"),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_history_with_minimal_arguments() {
+        check_args(
+            &["ludusavi", "history"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::History {
+                    operation: vec![],
+                    since: None,
+                    until: None,
+                    last: None,
+                    api: false,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_history_with_all_arguments() {
+        check_args(
+            &[
+                "ludusavi",
+                "history",
+                "--operation",
+                "backup",
+                "--operation",
+                "cloud-upload",
+                "--since",
+                "7d",
+                "--until",
+                "2024-01-02T03:04:05Z",
+                "--last",
+                "5",
+                "--api",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::History {
+                    operation: vec![Operation::Backup, Operation::CloudUpload],
+                    since: Some(CliTime::Ago(chrono::Duration::days(7))),
+                    until: Some(CliTime::At(
+                        chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+                            .unwrap()
+                            .with_timezone(&chrono::Utc),
+                    )),
+                    last: Some(5),
+                    api: true,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_serve_with_minimal_arguments() {
+        check_args(
+            &["ludusavi", "serve"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Serve {
+                    bind: s("127.0.0.1:7575"),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_serve_with_all_arguments() {
+        check_args(
+            &["ludusavi", "serve", "--bind", "0.0.0.0:9000"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Serve {
+                    bind: s("0.0.0.0:9000"),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_daemon() {
+        check_args(
+            &["ludusavi", "daemon"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Daemon),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_api() {
+        check_args(
+            &["ludusavi", "api"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Api),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_watch() {
+        check_args(
+            &[
+                "ludusavi",
+                "watch",
+                "--path",
+                "tests/backup",
+                "--debounce",
+                "30",
+                "--poll",
+                "--api",
+                "game1",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Watch {
+                    path: Some(StrictPath::new(s("tests/backup"))),
+                    debounce: 30,
+                    poll: true,
+                    poll_interval: 2,
+                    api: true,
+                    games: vec![s("game1")],
+                }),
+            },
+        );
+
+        assert_eq!(
+            Some(Subcommand::Watch {
+                path: None,
+                debounce: 10,
+                poll: true,
+                poll_interval: 5,
+                api: false,
+                games: vec![],
+            }),
+            Cli::parse_from(["ludusavi", "watch", "--poll", "--poll-interval", "5"]).sub
+        );
+        assert!(Cli::try_parse_from(["ludusavi", "watch", "--poll-interval", "5"]).is_err());
+    }
+
+    #[test]
+    fn accepts_cli_complete_for_fish() {
+        check_args(
+            &["ludusavi", "complete", "fish"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Complete {
+                    shell: Some(CompletionShell::Fish),
+                    list_games: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_complete_for_zsh() {
+        check_args(
+            &["ludusavi", "complete", "zsh"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Complete {
+                    shell: Some(CompletionShell::Zsh),
+                    list_games: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_complete_for_powershell() {
+        check_args(
+            &["ludusavi", "complete", "powershell"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Complete {
+                    shell: Some(CompletionShell::PowerShell),
+                    list_games: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_complete_for_elvish() {
+        check_args(
+            &["ludusavi", "complete", "elvish"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Complete {
+                    shell: Some(CompletionShell::Elvish),
+                    list_games: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_complete_with_list_games() {
+        check_args(
+            &["ludusavi", "complete", "--list-games", "Foo"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Complete {
-                    shell: CompletionShell::Elvish,
+                    shell: None,
+                    list_games: Some(s("Foo")),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_complete_without_shell_or_list_games() {
+        check_args_err(
+            &["ludusavi", "complete"],
+            clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand,
+        );
+    }
+
+    #[test]
+    fn can_generate_completions_for_each_shell() {
+        for shell in [
+            CompletionShell::Bash,
+            CompletionShell::Fish,
+            CompletionShell::Zsh,
+            CompletionShell::PowerShell,
+            CompletionShell::Elvish,
+        ] {
+            let mut script = vec![];
+            shell.generate(&mut script);
+            let script = String::from_utf8(script).unwrap();
+
+            assert!(script.contains("backup"), "{shell:?}");
+            // Enum-valued flags should complete their values.
+            assert!(script.contains("zip"), "{shell:?}");
+        }
+    }
+
+    #[test]
+    fn accepts_cli_backups_with_minimal_arguments() {
+        check_args(
+            &["ludusavi", "backups"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backups {
+                    path: None,
+                    api: false,
+                    newer_than: None,
+                    older_than: None,
+                    trigger: None,
+                    sort: None,
+                    reverse: false,
+                    adopt: None,
+                    adopt_as: None,
+                    output_file: None,
+                    games: vec![],
+                    sub: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backups_with_all_arguments() {
+        check_args(
+            &[
+                "ludusavi",
+                "backups",
+                "--path",
+                "tests/backup",
+                "--api",
+                "--newer-than",
+                "2024-01-02T03:04:05Z",
+                "--older-than",
+                "90d",
+                "--trigger",
+                "wrap",
+                "--output-file",
+                "report.json",
+                "game1",
+                "game2",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backups {
+                    path: Some(StrictPath::new(s("tests/backup"))),
+                    api: true,
+                    newer_than: Some(CliTime::At(
+                        chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+                            .unwrap()
+                            .with_timezone(&chrono::Utc),
+                    )),
+                    older_than: Some(CliTime::Ago(chrono::Duration::days(90))),
+                    trigger: Some(TriggerSource::Wrap),
+                    sort: None,
+                    reverse: false,
+                    adopt: None,
+                    adopt_as: None,
+                    output_file: Some(StrictPath::new(s("report.json"))),
+                    games: vec![s("game1"), s("game2")],
+                    sub: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_only_changed_with_preview() {
+        for command in ["backup", "restore"] {
+            assert!(
+                matches!(
+                    Cli::parse_from(["ludusavi", command, "--preview", "--only-changed"]).sub,
+                    Some(
+                        Subcommand::Backup {
+                            preview: true,
+                            only_changed: true,
+                            only_failed: false,
+                            ..
+                        } | Subcommand::Restore {
+                            preview: true,
+                            only_changed: true,
+                            only_failed: false,
+                            ..
+                        }
+                    )
+                ),
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_cli_only_failed() {
+        for command in ["backup", "restore"] {
+            assert!(
+                matches!(
+                    Cli::parse_from(["ludusavi", command, "--only-failed"]).sub,
+                    Some(Subcommand::Backup { only_failed: true, .. } | Subcommand::Restore { only_failed: true, .. })
+                ),
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_cli_games_from() {
+        for command in ["backup", "restore", "find"] {
+            let sub = Cli::parse_from(["ludusavi", command, "--games-from", "games.txt", "--strict", "foo"]).sub;
+            assert!(
+                match sub {
+                    Some(
+                        Subcommand::Backup {
+                            games_from,
+                            strict,
+                            games,
+                            ..
+                        }
+                        | Subcommand::Restore {
+                            games_from,
+                            strict,
+                            games,
+                            ..
+                        }
+                        | Subcommand::Find {
+                            games_from,
+                            strict,
+                            names: games,
+                            ..
+                        },
+                    ) => games_from == Some(StrictPath::new(s("games.txt"))) && strict && games == vec![s("foo")],
+                    _ => false,
+                },
+                "{command}"
+            );
+
+            assert!(
+                Cli::try_parse_from(["ludusavi", command, "--strict"]).is_err(),
+                "{command}"
+            );
+        }
+
+        assert!(Cli::try_parse_from(["ludusavi", "find", "--games-from", "-", "--fuzzy"]).is_err());
+        assert!(Cli::try_parse_from(["ludusavi", "backup", "--games-from", "-", "--use-daemon"]).is_ok());
+    }
+
+    #[test]
+    fn can_parse_game_list() {
+        assert_eq!(
+            vec![s("foo"), s("bar baz"), s("qux #1")],
+            parse_game_list("\u{feff}# recently played\nfoo\n\n  bar baz  \r\n\t\n  # indented comment\nqux #1"),
+        );
+    }
+
+    #[test]
+    fn accepts_cli_fail_on_change_with_preview() {
+        assert!(matches!(
+            Cli::parse_from(["ludusavi", "backup", "--preview", "--fail-on-change"]).sub,
+            Some(Subcommand::Backup {
+                preview: true,
+                fail_on_change: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Cli::parse_from(["ludusavi", "cloud", "upload", "--preview", "--fail-on-change"]).sub,
+            Some(Subcommand::Cloud {
+                sub: CloudSubcommand::Upload {
+                    preview: true,
+                    fail_on_change: true,
+                    ..
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_cli_fail_on_change_without_preview() {
+        check_args_err(
+            &["ludusavi", "backup", "--fail-on-change"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+        check_args_err(
+            &["ludusavi", "cloud", "upload", "--fail-on-change"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_verify() {
+        check_args(
+            &[
+                "ludusavi",
+                "verify",
+                "--path",
+                "tests/backup",
+                "--api",
+                "--backup",
+                "foo",
+                "game1",
+            ],
+            Cli {
+                config: None,
+                language: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Verify {
+                    path: Some(StrictPath::new(s("tests/backup"))),
+                    api: true,
+                    backup: Some(s("foo")),
+                    games: vec![s("game1")],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_diff() {
+        check_args(
+            &["ludusavi", "diff", "--old", "1", "--new", "backup-2", "game1"],
+            Cli {
+                config: None,
+                language: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Diff {
+                    path: None,
+                    api: false,
+                    old: s("1"),
+                    new: Some(s("backup-2")),
+                    game: s("game1"),
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_backups_with_minimal_arguments() {
+    fn rejects_cli_diff_without_old() {
+        check_args_err(
+            &["ludusavi", "diff", "game1"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn rejects_cli_restore_with_invalid_only_path() {
+        check_args_err(
+            &["ludusavi", "restore", "--only-path", "/saves/["],
+            clap::error::ErrorKind::ValueValidation,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_prune() {
         check_args(
-            &["ludusavi", "backups"],
+            &[
+                "ludusavi",
+                "prune",
+                "--preview",
+                "--keep-full",
+                "2",
+                "--keep-differential",
+                "3",
+                "--older-than",
+                "90d",
+                "--trigger",
+                "scheduler",
+                "--allow-latest",
+                "--game",
+                "foo",
+                "--game",
+                "bar",
+            ],
+            Cli {
+                config: None,
+                language: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Prune {
+                    path: None,
+                    api: false,
+                    preview: true,
+                    keep_full: Some(2),
+                    keep_differential: Some(3),
+                    newer_than: None,
+                    older_than: Some(CliTime::Ago(chrono::Duration::days(90))),
+                    trigger: Some(TriggerSource::Scheduler),
+                    allow_latest: true,
+                    games: vec![s("foo"), s("bar")],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_prune_without_rules() {
+        check_args_err(
+            &["ludusavi", "prune", "--game", "foo"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backups_with_sort() {
+        check_args(
+            &["ludusavi", "backups", "--sort", "size", "--reverse"],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backups {
                     path: None,
                     api: false,
+                    newer_than: None,
+                    older_than: None,
+                    trigger: None,
+                    sort: Some(CliSort::Size),
+                    reverse: true,
+                    adopt: None,
+                    adopt_as: None,
+                    output_file: None,
                     games: vec![],
+                    sub: None,
                 }),
             },
         );
     }
 
     #[test]
-    fn accepts_cli_backups_with_all_arguments() {
+    fn accepts_cli_backups_comment() {
         check_args(
             &[
                 "ludusavi",
                 "backups",
-                "--path",
-                "tests/backup",
-                "--api",
+                "comment",
+                "--backup",
+                "2",
+                "--set",
+                "before 1.5 patch\nslot 2",
+                "game1",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backups {
+                    path: None,
+                    api: false,
+                    newer_than: None,
+                    older_than: None,
+                    trigger: None,
+                    sort: None,
+                    reverse: false,
+                    adopt: None,
+                    adopt_as: None,
+                    output_file: None,
+                    games: vec![],
+                    sub: Some(BackupsSubcommand::Comment {
+                        path: None,
+                        api: false,
+                        backup: Some(s("2")),
+                        set: Some(s("before 1.5 patch\nslot 2")),
+                        clear: false,
+                        game: s("game1"),
+                    }),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_backups_comment_without_exactly_one_action() {
+        check_args_err(
+            &["ludusavi", "backups", "comment", "game1"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+        check_args_err(
+            &["ludusavi", "backups", "comment", "--set", "foo", "--clear", "game1"],
+            clap::error::ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backups_delete() {
+        check_args(
+            &[
+                "ludusavi",
+                "backups",
+                "delete",
+                "--backup",
+                "2",
+                "--cascade",
+                "--force",
                 "game1",
-                "game2",
             ],
             Cli {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Backups {
+                    path: None,
+                    api: false,
+                    newer_than: None,
+                    older_than: None,
+                    trigger: None,
+                    sort: None,
+                    reverse: false,
+                    adopt: None,
+                    adopt_as: None,
+                    output_file: None,
+                    games: vec![],
+                    sub: Some(BackupsSubcommand::Delete {
+                        path: None,
+                        api: false,
+                        backup: s("2"),
+                        cascade: true,
+                        force: true,
+                        game: s("game1"),
+                    }),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_backups_delete_without_backup() {
+        check_args_err(
+            &["ludusavi", "backups", "delete", "game1"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_backups_with_adopt() {
+        check_args(
+            &["ludusavi", "backups", "--adopt", "old/game-saves", "--as", "game1"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Backups {
+                    path: None,
+                    api: false,
+                    newer_than: None,
+                    older_than: None,
+                    trigger: None,
+                    sort: None,
+                    reverse: false,
+                    adopt: Some(StrictPath::new(s("old/game-saves"))),
+                    adopt_as: Some(s("game1")),
+                    output_file: None,
+                    games: vec![],
+                    sub: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_stats_dedup() {
+        check_args(
+            &["ludusavi", "stats", "--dedup", "--path", "tests/backup", "--api"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Stats {
                     path: Some(StrictPath::new(s("tests/backup"))),
                     api: true,
-                    games: vec![s("game1"), s("game2")],
+                    dedup: true,
+                    history: false,
+                    all_games: false,
+                    csv: false,
+                    sort: None,
+                    game: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_stats_history_for_one_game() {
+        check_args(
+            &["ludusavi", "stats", "--history", "--api", "game1"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Stats {
+                    path: None,
+                    api: true,
+                    dedup: false,
+                    history: true,
+                    all_games: false,
+                    csv: false,
+                    sort: None,
+                    game: Some(s("game1")),
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn accepts_cli_stats_history_for_all_games_as_csv() {
+        check_args(
+            &["ludusavi", "stats", "--history", "--all-games", "--csv"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Stats {
+                    path: None,
+                    api: false,
+                    dedup: false,
+                    history: true,
+                    all_games: true,
+                    csv: true,
+                    sort: None,
+                    game: None,
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_stats_history_without_game() {
+        check_args_err(
+            &["ludusavi", "stats", "--history"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_stats_storage_sorted_by_size() {
+        check_args(
+            &["ludusavi", "stats", "--sort", "size"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Stats {
+                    path: None,
+                    api: false,
+                    dedup: false,
+                    history: false,
+                    all_games: false,
+                    csv: false,
+                    sort: Some(StatsSort::Size),
+                    game: None,
                 }),
             },
         );
     }
 
+    #[test]
+    fn rejects_cli_stats_sort_with_other_report() {
+        check_args_err(
+            &["ludusavi", "stats", "--dedup", "--sort", "size"],
+            clap::error::ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn can_parse_cli_time() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let ago = |input: &str| input.parse::<CliTime>().unwrap().resolve(&now).to_rfc3339();
+
+        assert_eq!("2024-05-31T12:00:00+00:00", ago("12h"));
+        assert_eq!("2024-03-03T00:00:00+00:00", ago("90d"));
+        assert_eq!("2024-05-18T00:00:00+00:00", ago("2w"));
+        assert_eq!("2023-12-04T00:00:00+00:00", ago("6m"));
+        assert_eq!("2023-06-02T00:00:00+00:00", ago("1y"));
+        assert_eq!("2024-01-02T03:04:05+00:00", ago("2024-01-02T05:04:05+02:00"));
+        assert!(matches!("2024-01-02".parse::<CliTime>(), Ok(CliTime::At(_))));
+
+        assert!("90".parse::<CliTime>().is_err());
+        assert!("90x".parse::<CliTime>().is_err());
+        assert!("d".parse::<CliTime>().is_err());
+        assert!("yesterday".parse::<CliTime>().is_err());
+    }
+
+    #[test]
+    fn can_parse_cli_duration() {
+        let parse = |input: &str| input.parse::<CliDuration>().map(|x| x.0);
+
+        assert_eq!(Ok(chrono::Duration::seconds(45)), parse("45s"));
+        assert_eq!(Ok(chrono::Duration::minutes(30)), parse("30m"));
+        assert_eq!(Ok(chrono::Duration::hours(2)), parse("2h"));
+        assert_eq!(Ok(chrono::Duration::days(1)), parse("1d"));
+        assert_eq!(Ok(chrono::Duration::weeks(1)), parse("1w"));
+
+        assert!(parse("30").is_err());
+        assert!(parse("30y").is_err());
+        assert!(parse("m").is_err());
+    }
+
+    #[test]
+    fn rejects_cli_backups_with_invalid_time() {
+        check_args_err(
+            &["ludusavi", "backups", "--older-than", "soon"],
+            clap::error::ErrorKind::ValueValidation,
+        );
+    }
+
     #[test]
     fn accepts_cli_find_with_minimal_arguments() {
         check_args(
@@ -1106,6 +4484,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Find {
                     api: false,
                     path: None,
@@ -1113,9 +4497,17 @@ mod tests {
                     restore: false,
                     steam_id: None,
                     gog_id: None,
+                    lutris_id: None,
                     normalized: false,
+                    fuzzy: false,
+                    threshold: None,
+                    limit: None,
                     disabled: false,
                     partial: false,
+                    missing: false,
+                    backup_missing: false,
+                    games_from: None,
+                    strict: false,
                     names: vec![],
                 }),
             },
@@ -1137,9 +4529,13 @@ mod tests {
                 "101",
                 "--gog-id",
                 "102",
+                "--lutris-id",
+                "game-slug",
                 "--normalized",
                 "--disabled",
                 "--partial",
+                "--missing",
+                "--backup-missing",
                 "game1",
                 "game2",
             ],
@@ -1147,6 +4543,12 @@ mod tests {
                 config: None,
                 no_manifest_update: false,
                 try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
                 sub: Some(Subcommand::Find {
                     api: true,
                     path: Some(StrictPath::new(s("tests/backup"))),
@@ -1154,12 +4556,123 @@ mod tests {
                     restore: true,
                     steam_id: Some(101),
                     gog_id: Some(102),
+                    lutris_id: Some(s("game-slug")),
                     normalized: true,
+                    fuzzy: false,
+                    threshold: None,
+                    limit: None,
                     disabled: true,
                     partial: true,
+                    missing: true,
+                    backup_missing: true,
+                    games_from: None,
+                    strict: false,
                     names: vec![s("game1"), s("game2")],
                 }),
             },
         );
     }
+
+    #[test]
+    fn rejects_cli_find_with_backup_missing_but_not_missing() {
+        check_args_err(
+            &["ludusavi", "find", "--backup-missing"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_find_fuzzy() {
+        check_args(
+            &[
+                "ludusavi",
+                "find",
+                "--fuzzy",
+                "--threshold",
+                "0.75",
+                "--limit",
+                "5",
+                "hollow nigt",
+            ],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Find {
+                    api: false,
+                    path: None,
+                    backup: false,
+                    restore: false,
+                    steam_id: None,
+                    gog_id: None,
+                    lutris_id: None,
+                    normalized: false,
+                    fuzzy: true,
+                    threshold: Some(CliThreshold(0.75)),
+                    limit: Some(5),
+                    disabled: false,
+                    partial: false,
+                    missing: false,
+                    backup_missing: false,
+                    games_from: None,
+                    strict: false,
+                    names: vec![s("hollow nigt")],
+                }),
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_cli_find_fuzzy_with_normalized() {
+        check_args_err(
+            &["ludusavi", "find", "--fuzzy", "--normalized", "foo"],
+            clap::error::ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn rejects_cli_find_threshold_out_of_range() {
+        check_args_err(
+            &["ludusavi", "find", "--fuzzy", "--threshold", "1.5", "foo"],
+            clap::error::ErrorKind::ValueValidation,
+        );
+    }
+
+    #[test]
+    fn rejects_cli_find_limit_without_fuzzy() {
+        check_args_err(
+            &["ludusavi", "find", "--limit", "5", "foo"],
+            clap::error::ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn accepts_cli_cloud_history_with_all_arguments() {
+        check_args(
+            &["ludusavi", "cloud", "history", "--since", "30d", "--api"],
+            Cli {
+                config: None,
+                no_manifest_update: false,
+                try_manifest_update: false,
+                ignore_invalid_manifest: false,
+                language: None,
+                api_version: None,
+                api_format: None,
+                color: None,
+                progress: false,
+                sub: Some(Subcommand::Cloud {
+                    sub: CloudSubcommand::History {
+                        since: Some(CliTime::Ago(chrono::Duration::days(30))),
+                        api: true,
+                    },
+                }),
+            },
+        );
+    }
 }
@@ -0,0 +1,97 @@
+//! Machine-readable progress for wrapper UIs (see `--progress`).
+//! Each event is a single line of JSON on stderr, while the report is still printed at the end.
+
+use std::io::Write;
+
+use crate::{prelude::SyncDirection, scan::ScanInfo};
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event<'a> {
+    /// The index starts at 1, so it reaches the total with the last game.
+    GameStart {
+        name: &'a str,
+        index: usize,
+        total: usize,
+    },
+    File {
+        game: &'a str,
+        path: String,
+        bytes: u64,
+    },
+    Cloud {
+        direction: SyncDirection,
+        bytes: u64,
+        #[serde(rename = "totalBytes")]
+        total_bytes: u64,
+    },
+}
+
+impl Event<'_> {
+    fn line(&self) -> Option<String> {
+        serde_json::to_string(self).ok()
+    }
+}
+
+/// `enabled` comes from `ReportOptions::progress`.
+pub fn emit(enabled: bool, event: Event) {
+    if !enabled {
+        return;
+    }
+    let Some(line) = event.line() else {
+        return;
+    };
+    // Games are scanned in parallel, so the lock is held until the whole line has been written.
+    let mut stderr = std::io::stderr().lock();
+    let _ = writeln!(stderr, "{line}");
+}
+
+/// Report each file that was found for a game, except for ignored ones.
+pub fn emit_files(enabled: bool, scan_info: &ScanInfo) {
+    if !enabled {
+        return;
+    }
+    let restoring = scan_info.restoring();
+    for file in itertools::sorted(&scan_info.found_files) {
+        if file.ignored {
+            continue;
+        }
+        emit(
+            enabled,
+            Event::File {
+                game: &scan_info.game_name,
+                path: file.readable(restoring),
+                bytes: file.size,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn renders_events_as_json_lines() {
+        assert_eq!(
+            Some(r#"{"event":"game-start","name":"foo","index":12,"total":800}"#.to_string()),
+            Event::GameStart {
+                name: "foo",
+                index: 12,
+                total: 800,
+            }
+            .line()
+        );
+        assert_eq!(
+            Some(r#"{"event":"cloud","direction":"upload","bytes":10,"totalBytes":20}"#.to_string()),
+            Event::Cloud {
+                direction: SyncDirection::Upload,
+                bytes: 10,
+                total_bytes: 20,
+            }
+            .line()
+        );
+    }
+}
@@ -1,28 +1,78 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+mod color;
+mod csv;
+mod markdown;
+mod schema;
 
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    io::Write,
+    time::Duration,
+};
+
+use indexmap::IndexMap;
 use itertools::Itertools;
 
 use crate::{
-    cloud::CloudChange,
-    lang::TRANSLATOR,
-    prelude::StrictPath,
-    resource::manifest::Os,
+    cloud::{history::Summary, CloudChange},
+    history,
+    lang::{ADD_SYMBOL, CHANGE_SYMBOL, REMOVAL_SYMBOL, TRANSLATOR, WARNING_SYMBOL},
+    prelude::{is_newer_version, Error, StrictPath},
+    resource::{
+        config::{
+            BackupMode, ConfigChange, CustomGame, DanglingTitle, ImportChange, ImportOutcome, RedundantRoot,
+            RootsConfig,
+        },
+        manifest::{GameSource, ManifestFallback, Os, StoreId},
+    },
     scan::{
-        layout::Backup, BackupInfo, DuplicateDetector, OperationStatus, OperationStepDecision, ScanChange, ScanInfo,
+        is_wine_user_variant,
+        layout::{Backup, BackupFilter, BackupKind, Discrepancy, PromotionReason},
+        running::RunningProcess,
+        zip_safety::UnsafeZipEntry,
+        BackupDiff, BackupInfo, DedupReport, DuplicateDetector, Explanation, FuzzyMatch, FuzzySource, LocalState,
+        OperationStatus, OperationStepDecision, SafetySnapshot, ScanChange, ScanInfo, SizeHistoryEntry, StorageReport,
+        Trigger, UnknownGame,
     },
 };
 
-#[derive(Debug, Default, serde::Serialize)]
+pub use self::{
+    color::{ColorChoice, Palette},
+    schema::{ApiFormat, ApiVersion},
+};
+
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiErrors {
     #[serde(skip_serializing_if = "Option::is_none")]
     some_games_failed: Option<bool>,
+    /// How many games were deferred because their files were locked.
     #[serde(skip_serializing_if = "Option::is_none")]
-    unknown_games: Option<Vec<String>>,
+    deferred_games: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unknown_games: Option<Vec<UnknownGame>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     cloud_conflict: Option<concern::CloudConflict>,
     #[serde(skip_serializing_if = "Option::is_none")]
     cloud_sync_failed: Option<concern::CloudSyncFailed>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cloud_sync_deferred: Option<concern::CloudSyncDeferred>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inconsistent_backups: Option<BTreeMap<String, concern::InconsistentBackup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unresolved_base_dirs: Option<concern::UnresolvedBaseDirs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    io_timeout: Option<concern::IoTimeout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game_running: Option<BTreeMap<String, RunningProcess>>,
+    /// Whether `game_running` is about a backup rather than a restore.
+    #[serde(skip)]
+    game_running_in_backup: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    running_check_unavailable: Option<concern::RunningCheckUnavailable>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manifest_fallback: Option<concern::ManifestFallback>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redundant_roots: Option<concern::RedundantRoots>,
 }
 
 impl ApiErrors {
@@ -30,50 +80,264 @@ impl ApiErrors {
     pub fn messages(&self) -> Vec<String> {
         let mut out = vec![];
 
-        if self.cloud_conflict.is_some() {
-            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.cloud_synchronize_conflict()));
+        if let Some(games) = &self.unknown_games {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.cli_unrecognized_games(games)));
+        }
+
+        if let Some(games) = self.deferred_games {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.games_deferred(games)));
+        }
+
+        if let Some(conflict) = &self.cloud_conflict {
+            let mut message = TRANSLATOR.prefix_warning(&TRANSLATOR.cloud_synchronize_conflict());
+            if !conflict.changes.is_empty() {
+                message.push_str(&format!(
+                    "\n  {}",
+                    TRANSLATOR.cli_cloud_conflict_paths(conflict.changes.len())
+                ));
+                for change in conflict.changes.iter().take(CLOUD_CONFLICT_EXAMPLES) {
+                    message.push_str(&format!("\n  - [{}] {}", change.change.symbol(), change.path));
+                }
+                if conflict.changes.len() > CLOUD_CONFLICT_EXAMPLES {
+                    message.push_str(&format!(
+                        "\n  - {}",
+                        TRANSLATOR.cli_cloud_conflict_more(conflict.changes.len() - CLOUD_CONFLICT_EXAMPLES)
+                    ));
+                }
+            }
+            out.push(message);
         }
 
         if self.cloud_sync_failed.is_some() {
             out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.unable_to_synchronize_with_cloud()));
         }
 
+        if self.cloud_sync_deferred.is_some() {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.cli_cloud_sync_deferred_on_metered()));
+        }
+
+        if let Some(inconsistent) = &self.inconsistent_backups {
+            for (game, inconsistency) in inconsistent {
+                let mut message =
+                    TRANSLATOR.prefix_warning(&TRANSLATOR.backup_inconsistent(game, inconsistency.repaired));
+                for problem in &inconsistency.problems {
+                    message.push_str(&format!("\n  - {}", TRANSLATOR.backup_discrepancy(problem)));
+                }
+                out.push(message);
+            }
+        }
+
+        if let Some(unresolved) = &self.unresolved_base_dirs {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.unresolved_base_dirs(unresolved.games)));
+        }
+
+        if let Some(timeout) = &self.io_timeout {
+            let mut message = TRANSLATOR.prefix_warning(&TRANSLATOR.io_timeout());
+            for path in &timeout.paths {
+                message.push_str(&format!("\n  - {path}"));
+            }
+            out.push(message);
+        }
+
+        if let Some(running) = &self.game_running {
+            for (game, process) in running {
+                let message = if self.game_running_in_backup {
+                    TRANSLATOR.game_running_for_backup(game, &process.label())
+                } else {
+                    TRANSLATOR.game_running(game, &process.label())
+                };
+                out.push(TRANSLATOR.prefix_warning(&message));
+            }
+        }
+
+        if self.running_check_unavailable.is_some() {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.running_check_unavailable()));
+        }
+
+        if let Some(fallback) = &self.manifest_fallback {
+            out.push(TRANSLATOR.prefix_warning(&TRANSLATOR.manifest_fallback(&fallback.why, fallback.age_hours)));
+        }
+
+        if let Some(redundant) = &self.redundant_roots {
+            let mut message = TRANSLATOR.prefix_warning(&TRANSLATOR.redundant_roots_skipped());
+            for path in &redundant.paths {
+                message.push_str(&format!("\n  - {path}"));
+            }
+            out.push(message);
+        }
+
         out
     }
 }
 
+/// The standard reporter only lists this many of the conflicting cloud paths.
+const CLOUD_CONFLICT_EXAMPLES: usize = 5;
+
 pub mod concern {
-    #[derive(Debug, Default, serde::Serialize)]
-    pub struct CloudConflict {}
+    use crate::scan::ScanChange;
+
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct CloudConflict {
+        /// The paths that differ between the local and cloud backups, sorted by path.
+        pub changes: Vec<CloudConflictChange>,
+    }
+
+    #[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+    pub struct CloudConflictChange {
+        pub path: String,
+        /// From the local side: `New` only exists locally, and `Removed` only exists in the cloud.
+        pub change: ScanChange,
+    }
 
-    #[derive(Debug, Default, serde::Serialize)]
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
     pub struct CloudSyncFailed {}
+
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct CloudSyncDeferred {}
+
+    /// Processes couldn't be listed on this platform,
+    /// so games were backed up or restored without checking whether they were running.
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct RunningCheckUnavailable {}
+
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct InconsistentBackup {
+        pub repaired: bool,
+        pub problems: Vec<crate::scan::layout::Discrepancy>,
+    }
+
+    /// This does not count as a failure, since the games may simply not be installed anymore.
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct UnresolvedBaseDirs {
+        pub games: usize,
+    }
+
+    /// Locations that stopped responding, along with anything inside of them.
+    /// This does not count as a failure, but the affected games are not backed up.
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct IoTimeout {
+        pub paths: Vec<String>,
+    }
+
+    /// Roots that were skipped because another root already covers them.
+    /// This does not count as a failure.
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    pub struct RedundantRoots {
+        pub paths: Vec<String>,
+    }
+
+    /// The manifest couldn't be parsed, so an older copy was used instead.
+    /// This does not count as a failure, but recently added games may be missing.
+    #[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ManifestFallback {
+        pub why: String,
+        /// How long ago the older copy was downloaded, if known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub age_hours: Option<i64>,
+    }
 }
 
-#[derive(Debug, Default, serde::Serialize)]
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
 struct ApiFile {
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     failed: bool,
+    /// Why the file failed to process.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     ignored: bool,
+    #[serde(rename = "ignoredBy", skip_serializing_if = "Option::is_none")]
+    ignored_by: Option<IgnoreSource>,
     change: ScanChange,
     bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
     #[serde(rename = "originalPath", skip_serializing_if = "Option::is_none")]
     original_path: Option<String>,
     #[serde(rename = "redirectedPath", skip_serializing_if = "Option::is_none")]
     redirected_path: Option<String>,
+    /// Set when a redirect produced the `redirectedPath` or `originalPath`.
+    #[serde(rename = "redirectRule", skip_serializing_if = "Option::is_none")]
+    redirect_rule: Option<ApiRedirectRule>,
+    /// The configured root that the file was found under, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root: Option<String>,
     #[serde(
         rename = "duplicatedBy",
         serialize_with = "crate::serialization::ordered_set",
         skip_serializing_if = "crate::serialization::is_empty_set"
     )]
     duplicated_by: HashSet<String>,
+    #[serde(rename = "sqliteSafeCopy", skip_serializing_if = "crate::serialization::is_false")]
+    sqlite_safe_copy: bool,
+    /// Set when the file changed after the scan, so the backup may not be consistent with the game's other files.
+    #[serde(
+        rename = "changedDuringBackup",
+        skip_serializing_if = "crate::serialization::is_false"
+    )]
+    changed_during_backup: bool,
+    /// Set when the backup location stored the file under a different name than requested.
+    #[serde(rename = "storedAs", skip_serializing_if = "Option::is_none")]
+    stored_as: Option<String>,
+    /// Set when the file was compared with the previous backup at its old location.
+    #[serde(rename = "migratedFrom", skip_serializing_if = "Option::is_none")]
+    migrated_from: Option<String>,
+    /// Set when the file was matched with the same file in another user folder of its Wine prefix.
+    /// This is the path in that other folder.
+    #[serde(rename = "wineUserVariant", skip_serializing_if = "Option::is_none")]
+    wine_user_variant: Option<String>,
+    #[serde(rename = "skipReason", skip_serializing_if = "Option::is_none")]
+    skip_reason: Option<SkipReason>,
+    /// Where the file could be restored instead, when its target is ambiguous.
+    #[serde(rename = "candidateTargets", skip_serializing_if = "Vec::is_empty")]
+    candidate_targets: Vec<String>,
+    /// Set when the file wasn't restored because its zip entry looked malicious.
+    #[serde(rename = "unsafeZipEntry", skip_serializing_if = "Option::is_none")]
+    unsafe_zip_entry: Option<UnsafeZipEntry>,
+    /// When restoring, how the local file at the target compares to the backup.
+    #[serde(rename = "localState", skip_serializing_if = "Option::is_none")]
+    local_state: Option<LocalState>,
+}
+
+impl ApiFile {
+    /// Whether the file should still be listed when the rest are summarized.
+    fn needs_attention(&self) -> bool {
+        self.failed
+            || self.changed_during_backup
+            || self.skip_reason.is_some()
+            || self.unsafe_zip_entry.is_some()
+            || self.local_state == Some(LocalState::NewerLocal)
+    }
+}
+
+/// Count the files and bytes in each folder, for games with too many files to list.
+fn summarize_by_folder(files: &[(String, ApiFile)]) -> BTreeMap<String, (usize, u64)> {
+    let mut folders = BTreeMap::<String, (usize, u64)>::new();
+    for (path, file) in files {
+        let folder = path.rsplit_once(['/', '\\']).map(|(folder, _)| folder).unwrap_or(path);
+        let entry = folders.entry(folder.to_string()).or_default();
+        entry.0 += 1;
+        entry.1 += file.bytes;
+    }
+    folders
+}
+
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+struct ApiRedirectRule {
+    /// Zero-based position in the list of redirects.
+    index: usize,
+    source: String,
+    target: String,
 }
 
-#[derive(Debug, Default, serde::Serialize)]
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
 struct ApiRegistry {
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     failed: bool,
+    /// Why the registry key failed to process.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     ignored: bool,
     change: ScanChange,
@@ -87,7 +351,7 @@ struct ApiRegistry {
     values: BTreeMap<String, ApiRegistryValue>,
 }
 
-#[derive(Debug, Default, serde::Serialize)]
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
 struct ApiRegistryValue {
     #[serde(skip_serializing_if = "crate::serialization::is_false")]
     ignored: bool,
@@ -100,548 +364,5535 @@ struct ApiRegistryValue {
     duplicated_by: HashSet<String>,
 }
 
-#[derive(Debug, serde::Serialize)]
-#[serde(untagged)]
-enum ApiGame {
-    Operative {
-        decision: OperationStepDecision,
-        change: ScanChange,
-        #[serde(serialize_with = "crate::serialization::ordered_map")]
-        files: HashMap<String, ApiFile>,
-        #[serde(serialize_with = "crate::serialization::ordered_map")]
-        registry: HashMap<String, ApiRegistry>,
-    },
-    Stored {
-        backups: Vec<ApiBackup>,
-    },
-    Found {},
-}
-
-#[derive(Debug, serde::Serialize)]
-struct ApiBackup {
-    name: String,
-    when: chrono::DateTime<chrono::Utc>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    os: Option<Os>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    comment: Option<String>,
-    pub locked: bool,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+enum IgnoreSource {
+    /// The built-in list from `scan.excludeCommonJunk`.
+    JunkFilter,
 }
 
-#[derive(Debug, Default, serde::Serialize)]
-pub struct JsonOutput {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    errors: Option<ApiErrors>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    overall: Option<OperationStatus>,
-    #[serde(serialize_with = "crate::serialization::ordered_map")]
-    games: HashMap<String, ApiGame>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+enum SkipReason {
+    GameRunning,
+    /// The game's save locations haven't been modified recently (see `backup --if-changed-since`),
+    /// so it wasn't fully scanned.
+    NoRecentActivity,
+    /// The file belongs to a Steam account folder that doesn't exist on this system,
+    /// and there are several other accounts that it could be restored into.
+    AmbiguousTarget,
+    /// The file was backed up on a different OS, and no redirect applies to it.
+    OsConstrained,
+    /// The local file is newer than the backup, and `restore --no-clobber-newer` was set.
+    NewerLocal,
+    /// The game's saves haven't changed since its last backup (see `backup --if-changed`).
+    Unchanged,
+    /// The game's existing backups don't match its mapping file (see `backup --repair`).
+    InconsistentBackup,
 }
 
-#[derive(Debug)]
-pub enum Reporter {
-    Standard {
-        parts: Vec<String>,
-        status: Option<OperationStatus>,
-        errors: ApiErrors,
-    },
-    Json {
-        output: JsonOutput,
-    },
+/// Sizes and counts for one game's entries.
+/// Across all games, these add up to the `overall` totals.
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiGameTotals {
+    /// Every file that was found, including ignored and failed ones.
+    total_bytes: u64,
+    /// This is 0 unless the game was processed.
+    processed_bytes: u64,
+    failed_bytes: u64,
+    ignored_bytes: u64,
+    file_count: usize,
+    registry_count: usize,
 }
 
-impl Reporter {
-    pub fn standard() -> Self {
-        Self::Standard {
-            parts: vec![],
-            status: Some(Default::default()),
-            errors: Default::default(),
-        }
-    }
-
-    pub fn json() -> Self {
-        Self::Json {
-            output: JsonOutput {
-                errors: Default::default(),
-                overall: Some(Default::default()),
-                games: Default::default(),
+impl ApiGameTotals {
+    fn new(scan_info: &ScanInfo, backup_info: &BackupInfo, processed: bool) -> Self {
+        Self {
+            total_bytes: scan_info.total_possible_bytes(),
+            processed_bytes: if processed {
+                scan_info.sum_bytes(Some(backup_info))
+            } else {
+                0
             },
+            failed_bytes: backup_info.failed_files.keys().map(|x| x.size).sum(),
+            ignored_bytes: scan_info.found_files.iter().filter(|x| x.ignored).map(|x| x.size).sum(),
+            file_count: scan_info.found_files.len(),
+            registry_count: scan_info.found_registry_keys.len(),
         }
     }
+}
 
-    fn set_errors(&mut self, f: impl FnOnce(&mut ApiErrors)) {
-        match self {
-            Reporter::Standard { errors, .. } => f(errors),
-            Reporter::Json { output } => {
-                if let Some(errors) = &mut output.errors.as_mut() {
-                    f(errors)
-                } else {
-                    let mut errors = ApiErrors::default();
-                    f(&mut errors);
-                    output.errors = Some(errors);
-                }
-            }
-        }
-    }
-
-    fn trip_some_games_failed(&mut self) {
-        self.set_errors(|e| {
-            e.some_games_failed = Some(true);
-        });
-    }
-
-    pub fn trip_unknown_games(&mut self, games: Vec<String>) {
-        self.set_errors(|e| {
-            e.unknown_games = Some(games);
-        });
-    }
-
-    pub fn trip_cloud_conflict(&mut self) {
-        self.set_errors(|e| {
-            e.cloud_conflict = Some(concern::CloudConflict {});
-        });
-    }
+/// The standard reporter only mentions how long a game took when it was at least this slow.
+const SLOW_GAME_MS: u64 = 1000;
 
-    pub fn trip_cloud_sync_failed(&mut self) {
-        self.set_errors(|e| {
-            e.cloud_sync_failed = Some(concern::CloudSyncFailed {});
-        });
-    }
+/// The details of a game that was scanned for a backup or restore.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+struct ApiOperativeGame {
+    decision: OperationStepDecision,
+    change: ScanChange,
+    /// Which manifest provided the game's entry. This is omitted when restoring.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<GameSource>,
+    /// Which backup was restored (or would be). This is omitted when backing up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup: Option<ApiRestoredBackup>,
+    #[serde(flatten)]
+    totals: ApiGameTotals,
+    #[serde(serialize_with = "crate::serialization::ordered_pairs")]
+    #[schemars(with = "BTreeMap<String, ApiFile>")]
+    files: Vec<(String, ApiFile)>,
+    /// Set when `files` only lists some of the game's files (see `scan.fileListLimit`).
+    #[serde(rename = "filesTruncated", skip_serializing_if = "crate::serialization::is_false")]
+    files_truncated: bool,
+    /// This is omitted when registry access is disabled for the game.
+    #[serde(
+        serialize_with = "crate::serialization::ordered_optional_pairs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(with = "Option<BTreeMap<String, ApiRegistry>>")]
+    registry: Option<Vec<(String, ApiRegistry)>>,
+    #[serde(rename = "registrySkipped", skip_serializing_if = "crate::serialization::is_false")]
+    registry_skipped: bool,
+    #[serde(rename = "mergedTitles", skip_serializing_if = "Vec::is_empty")]
+    merged_titles: Vec<String>,
+    #[serde(rename = "unresolvedBaseDirs", skip_serializing_if = "Vec::is_empty")]
+    unresolved_base_dirs: Vec<String>,
+    /// Save locations that were skipped because they didn't respond in time.
+    #[serde(rename = "ioTimeouts", skip_serializing_if = "Vec::is_empty")]
+    io_timeouts: Vec<String>,
+    #[serde(rename = "skipReason", skip_serializing_if = "Option::is_none")]
+    skip_reason: Option<SkipReason>,
+    /// A new full backup was made (or would be made) regardless of changes.
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    forced: bool,
+    /// The kind of backup that was created, if any.
+    #[serde(rename = "backupKind", skip_serializing_if = "Option::is_none")]
+    backup_kind: Option<BackupKind>,
+    /// Set when a differential backup was made as a full backup instead.
+    #[serde(rename = "promotedBecause", skip_serializing_if = "Option::is_none")]
+    promoted_because: Option<PromotionReason>,
+    /// The comment that was added to the new backup (see `backup --comment`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    /// The new backup was locked (see `backup --lock`).
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    locked: bool,
+    /// The backup of the current files that was made before restoring over them.
+    #[serde(rename = "safetySnapshot", skip_serializing_if = "Option::is_none")]
+    safety_snapshot: Option<ApiSafetySnapshot>,
+    /// How long it took to scan the game and back it up or restore it.
+    #[serde(rename = "durationMs", skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// Only shown by the standard reporter.
+    #[serde(skip)]
+    bytes: u64,
+    /// Only shown by the standard reporter.
+    #[serde(skip)]
+    duplicated: bool,
+}
 
-    pub fn suppress_overall(&mut self) {
-        match self {
-            Self::Standard { status, .. } => {
-                *status = None;
-            }
-            Self::Json { output, .. } => {
-                output.overall = None;
-            }
-        }
-    }
+/// The data collected for each game.
+/// The JSON reporter serializes this directly, while the standard reporter renders it as text,
+/// so the entries are kept in the order in which they were found.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(untagged)]
+enum ApiGame {
+    Operative(Box<ApiOperativeGame>),
+    /// The game wasn't found, but probably would have been if its install folder still existed
+    /// or if its save locations had responded in time.
+    Unresolved {
+        #[serde(rename = "unresolvedBaseDirs", skip_serializing_if = "Vec::is_empty")]
+        unresolved_base_dirs: Vec<String>,
+        #[serde(rename = "ioTimeouts", skip_serializing_if = "Vec::is_empty")]
+        io_timeouts: Vec<String>,
+    },
+    Stored {
+        backups: Vec<ApiBackup>,
+    },
+    Found {
+        /// Only set for fuzzy searches.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        score: Option<f64>,
+        /// Only set for fuzzy searches.
+        #[serde(rename = "matchedVia", skip_serializing_if = "Option::is_none")]
+        matched_via: Option<FuzzySource>,
+        /// Only set for fuzzy searches.
+        #[serde(rename = "matchedName", skip_serializing_if = "Option::is_none")]
+        matched_name: Option<String>,
+        /// Only set when looking up a game by a store ID.
+        #[serde(rename = "matchedId", skip_serializing_if = "Option::is_none")]
+        matched_id: Option<StoreId>,
+    },
+}
 
-    pub fn add_game(
-        &mut self,
+impl ApiGame {
+    fn render(
+        &self,
         name: &str,
-        scan_info: &ScanInfo,
-        backup_info: &BackupInfo,
-        decision: &OperationStepDecision,
-        duplicate_detector: &DuplicateDetector,
-    ) -> bool {
-        if !scan_info.can_report_game() {
-            return true;
-        }
+        verbose: bool,
+        file_list_limit: Option<usize>,
+        palette: Palette,
+        restore_hints: bool,
+        parts: &mut Vec<String>,
+    ) {
+        match self {
+            Self::Operative(game) => {
+                let ApiOperativeGame {
+                    decision,
+                    change,
+                    source: _,
+                    backup,
+                    totals: _,
+                    files,
+                    files_truncated: _,
+                    registry,
+                    registry_skipped,
+                    merged_titles,
+                    unresolved_base_dirs,
+                    io_timeouts,
+                    skip_reason,
+                    forced,
+                    backup_kind: _,
+                    promoted_because,
+                    comment,
+                    locked,
+                    safety_snapshot,
+                    duration_ms,
+                    bytes,
+                    duplicated,
+                } = game.as_ref();
 
-        let mut successful = true;
-        let restoring = scan_info.restoring();
+                if *skip_reason == Some(SkipReason::NoRecentActivity) {
+                    parts.push(format!("{name}:"));
+                    parts.push(TRANSLATOR.cli_game_no_recent_activity());
+                    parts.push("".to_string());
+                    return;
+                }
 
-        match self {
-            Self::Standard { parts, status, .. } => {
                 parts.push(TRANSLATOR.cli_game_header(
                     name,
-                    scan_info.sum_bytes(Some(backup_info)),
+                    *bytes,
                     decision,
-                    !duplicate_detector.is_game_duplicated(&scan_info.game_name).resolved(),
-                    scan_info.overall_change(),
+                    *duplicated,
+                    *forced,
+                    *change,
+                    duration_ms.filter(|x| *x >= SLOW_GAME_MS),
+                    palette,
                 ));
-                for entry in itertools::sorted(&scan_info.found_files) {
-                    let entry_successful = !backup_info.failed_files.contains(entry);
-                    if !entry_successful {
-                        successful = false;
+
+                if *skip_reason == Some(SkipReason::Unchanged) {
+                    parts.push(TRANSLATOR.cli_game_unchanged());
+                    parts.push("".to_string());
+                    return;
+                }
+                if *skip_reason == Some(SkipReason::InconsistentBackup) {
+                    parts.push(TRANSLATOR.cli_game_inconsistent());
+                    parts.push("".to_string());
+                    return;
+                }
+
+                // With too many files to read through, summarize them by folder,
+                // but still list any files that need attention.
+                let collapsed = file_list_limit.is_some_and(|limit| files.len() > limit);
+                if collapsed {
+                    for (folder, (total, bytes)) in summarize_by_folder(files) {
+                        parts.push(TRANSLATOR.cli_game_line_item_folder_summary(&folder, total, bytes));
+                    }
+                }
+
+                for (path, file) in files {
+                    if collapsed && !file.needs_attention() {
+                        continue;
                     }
+                    let item = match file.root.as_ref().filter(|_| verbose) {
+                        Some(root) => format!("{path} ({root})"),
+                        None => path.clone(),
+                    };
                     parts.push(TRANSLATOR.cli_game_line_item(
-                        &entry.readable(restoring),
-                        entry_successful,
-                        entry.ignored,
-                        !duplicate_detector.is_file_duplicated(entry).resolved(),
-                        entry.change(),
+                        &item,
+                        !file.failed,
+                        file.error.as_deref(),
+                        file.ignored,
+                        !file.duplicated_by.is_empty(),
+                        file.change,
                         false,
+                        palette,
                     ));
 
-                    if let Some(alt) = entry.alt_readable(restoring) {
-                        if restoring {
-                            parts.push(TRANSLATOR.cli_game_line_item_redirected(&alt));
-                        } else {
-                            parts.push(TRANSLATOR.cli_game_line_item_redirecting(&alt));
-                        }
+                    if let Some(alt) = &file.original_path {
+                        parts.push(TRANSLATOR.cli_game_line_item_redirected(alt));
+                    } else if let Some(alt) = &file.redirected_path {
+                        parts.push(TRANSLATOR.cli_game_line_item_redirecting(alt));
                     }
-                }
-                for entry in itertools::sorted(&scan_info.found_registry_keys) {
-                    let entry_successful = !backup_info.failed_registry.contains(&entry.path);
-                    if !entry_successful {
-                        successful = false;
+                    if let Some(rule) = file.redirect_rule.as_ref().filter(|_| verbose) {
+                        parts.push(TRANSLATOR.cli_game_line_item_redirect_rule(rule.index, &rule.source, &rule.target));
+                    }
+                    if file.ignored_by == Some(IgnoreSource::JunkFilter) {
+                        parts.push(TRANSLATOR.cli_game_line_item_junk());
+                    }
+                    if let Some(stored) = &file.stored_as {
+                        parts.push(TRANSLATOR.cli_game_line_item_stored_as(stored));
+                    }
+                    if file.changed_during_backup {
+                        parts.push(TRANSLATOR.cli_game_line_item_changed_during_backup());
+                    }
+                    if let Some(other) = &file.wine_user_variant {
+                        parts.push(TRANSLATOR.cli_game_line_item_wine_user_variant(other));
+                    } else if let Some(old) = &file.migrated_from {
+                        parts.push(TRANSLATOR.cli_game_line_item_migrated_from(old));
+                    }
+                    if file.skip_reason == Some(SkipReason::AmbiguousTarget) {
+                        parts.push(TRANSLATOR.cli_game_line_item_ambiguous_target(&file.candidate_targets));
+                    }
+                    if file.skip_reason == Some(SkipReason::OsConstrained) {
+                        parts.push(TRANSLATOR.cli_game_line_item_os_constrained());
+                    }
+                    if file.skip_reason == Some(SkipReason::NewerLocal) {
+                        parts.push(TRANSLATOR.cli_game_line_item_kept_newer_local());
+                    } else if file.local_state == Some(LocalState::NewerLocal) {
+                        parts.push(TRANSLATOR.cli_game_line_item_newer_local(palette));
+                    }
+                    if let Some(problem) = &file.unsafe_zip_entry {
+                        parts.push(TRANSLATOR.cli_game_line_item_unsafe_zip_entry(problem));
                     }
+                }
+                for (path, entry) in registry.iter().flatten() {
                     parts.push(TRANSLATOR.cli_game_line_item(
-                        &entry.path.render(),
-                        entry_successful,
+                        path,
+                        !entry.failed,
+                        entry.error.as_deref(),
                         entry.ignored,
-                        !duplicate_detector.is_registry_duplicated(&entry.path).resolved(),
-                        entry.change(scan_info.restoring()),
+                        !entry.duplicated_by.is_empty(),
+                        entry.change,
                         false,
+                        palette,
                     ));
-                    for (value_name, value) in itertools::sorted(&entry.values) {
-                        parts.push(
-                            TRANSLATOR.cli_game_line_item(
-                                value_name,
-                                true,
-                                value.ignored,
-                                !duplicate_detector
-                                    .is_registry_value_duplicated(&entry.path, value_name)
-                                    .resolved(),
-                                value.change(scan_info.restoring()),
-                                true,
-                            ),
-                        );
+                    for (value_name, value) in &entry.values {
+                        parts.push(TRANSLATOR.cli_game_line_item(
+                            value_name,
+                            true,
+                            None,
+                            value.ignored,
+                            !value.duplicated_by.is_empty(),
+                            value.change,
+                            true,
+                            palette,
+                        ));
                     }
                 }
-
-                // Blank line between games.
-                parts.push("".to_string());
-
-                if let Some(status) = status.as_mut() {
-                    status.add_game(
-                        scan_info,
-                        &Some(backup_info.clone()),
-                        decision == &OperationStepDecision::Processed,
-                    );
+                let os_constrained = files
+                    .iter()
+                    .filter(|(_, file)| file.skip_reason == Some(SkipReason::OsConstrained))
+                    .count();
+                if os_constrained > 0 {
+                    parts.push(TRANSLATOR.cli_game_os_constrained(os_constrained));
                 }
-            }
-            Self::Json { output } => {
-                let decision = decision.clone();
-                let mut files = HashMap::new();
-                let mut registry = HashMap::new();
-
-                for entry in itertools::sorted(&scan_info.found_files) {
-                    let mut api_file = ApiFile {
-                        bytes: entry.size,
-                        failed: backup_info.failed_files.contains(entry),
-                        ignored: entry.ignored,
-                        change: entry.change(),
-                        ..Default::default()
-                    };
-                    if !duplicate_detector.is_file_duplicated(entry).resolved() {
-                        let mut duplicated_by: HashSet<_> = duplicate_detector.file(entry).into_keys().collect();
-                        duplicated_by.remove(&scan_info.game_name);
-                        api_file.duplicated_by = duplicated_by;
-                    }
-
-                    if let Some(alt) = entry.alt_readable(restoring) {
-                        if restoring {
-                            api_file.original_path = Some(alt);
-                        } else {
-                            api_file.redirected_path = Some(alt);
-                        }
-                    }
-                    if api_file.failed {
-                        successful = false;
-                    }
-
-                    files.insert(entry.readable(restoring), api_file);
+                if *registry_skipped {
+                    parts.push(TRANSLATOR.cli_game_registry_skipped());
                 }
-                for entry in itertools::sorted(&scan_info.found_registry_keys) {
-                    let mut api_registry = ApiRegistry {
-                        failed: backup_info.failed_registry.contains(&entry.path),
-                        ignored: entry.ignored,
-                        change: entry.change(scan_info.restoring()),
-                        values: entry
-                            .values
-                            .iter()
-                            .map(|(k, v)| {
-                                (
-                                    k.clone(),
-                                    ApiRegistryValue {
-                                        change: v.change(scan_info.restoring()),
-                                        ignored: v.ignored,
-                                        duplicated_by: {
-                                            if !duplicate_detector
-                                                .is_registry_value_duplicated(&entry.path, k)
-                                                .resolved()
-                                            {
-                                                let mut duplicated_by: HashSet<_> = duplicate_detector
-                                                    .registry_value(&entry.path, k)
-                                                    .into_keys()
-                                                    .collect();
-                                                duplicated_by.remove(&scan_info.game_name);
-                                                duplicated_by
-                                            } else {
-                                                HashSet::new()
-                                            }
-                                        },
-                                    },
-                                )
-                            })
-                            .collect(),
-                        ..Default::default()
-                    };
-                    if !duplicate_detector.is_registry_duplicated(&entry.path).resolved() {
-                        let mut duplicated_by: HashSet<_> =
-                            duplicate_detector.registry(&entry.path).into_keys().collect();
-                        duplicated_by.remove(&scan_info.game_name);
-                        api_registry.duplicated_by = duplicated_by;
-                    }
-
-                    if api_registry.failed {
-                        successful = false;
-                    }
-
-                    registry.insert(entry.path.render(), api_registry);
+                for dir in unresolved_base_dirs {
+                    parts.push(TRANSLATOR.cli_game_unresolved_base_dir(dir));
                 }
-
-                if let Some(overall) = output.overall.as_mut() {
-                    overall.add_game(
-                        scan_info,
-                        &Some(backup_info.clone()),
-                        decision == OperationStepDecision::Processed,
-                    );
+                for path in io_timeouts {
+                    parts.push(TRANSLATOR.cli_game_io_timeout(path));
                 }
-                output.games.insert(
-                    name.to_string(),
-                    ApiGame::Operative {
-                        decision,
-                        change: scan_info.overall_change(),
-                        files,
-                        registry,
-                    },
-                );
-            }
-        }
-
-        if !successful {
-            self.trip_some_games_failed();
-        }
-        successful
-    }
-
-    pub fn add_backups(&mut self, name: &str, available_backups: &[Backup]) {
-        match self {
-            Self::Standard { parts, .. } => {
-                if available_backups.is_empty() {
-                    return;
+                if !merged_titles.is_empty() {
+                    parts.push(TRANSLATOR.cli_game_merged_titles(merged_titles));
                 }
-
-                parts.push(format!("{}:", name));
-                for backup in available_backups {
-                    let mut line = format!(
-                        "  - \"{}\" ({})",
-                        backup.name(),
-                        backup.when_local().format("%Y-%m-%dT%H:%M:%S"),
-                    );
-                    if let Some(os) = backup.os() {
-                        line += &format!(" [{os:?}]");
-                    }
-                    if backup.locked() {
-                        line += " [🔒]";
-                    }
-                    if let Some(comment) = backup.comment() {
-                        line += &format!(" - {comment}");
-                    }
+                if let Some(reason) = promoted_because {
+                    parts.push(TRANSLATOR.cli_game_promoted_to_full(reason));
+                }
+                if let Some(comment) = comment {
+                    parts.push(TRANSLATOR.cli_game_backup_comment(comment));
+                }
+                if *locked {
+                    parts.push(TRANSLATOR.cli_game_backup_locked());
+                }
+                if let Some(version) = backup
+                    .as_ref()
+                    .filter(|x| x.newer_version)
+                    .and_then(|x| x.app_version.as_ref())
+                {
+                    parts.push(TRANSLATOR.cli_game_backup_newer_version(version));
+                }
+                if let Some(line) = safety_snapshot.as_ref().and_then(ApiSafetySnapshot::describe) {
                     parts.push(line);
                 }
 
                 // Blank line between games.
                 parts.push("".to_string());
             }
-            Self::Json { output } => {
-                if available_backups.is_empty() {
-                    return;
+            Self::Unresolved {
+                unresolved_base_dirs,
+                io_timeouts,
+            } => {
+                parts.push(name.to_string());
+                for dir in unresolved_base_dirs {
+                    parts.push(TRANSLATOR.cli_game_unresolved_base_dir(dir));
                 }
-
-                let mut backups = vec![];
-                for backup in available_backups {
-                    backups.push(ApiBackup {
-                        name: backup.name().to_string(),
-                        when: *backup.when(),
-                        os: backup.os(),
-                        comment: backup.comment().to_owned(),
-                        locked: backup.locked(),
-                    });
+                for path in io_timeouts {
+                    parts.push(TRANSLATOR.cli_game_io_timeout(path));
                 }
-
-                output.games.insert(name.to_string(), ApiGame::Stored { backups });
+                parts.push("".to_string());
             }
-        }
-    }
-
-    pub fn add_found_titles(&mut self, names: &BTreeSet<String>) {
-        match self {
-            Self::Standard { parts, .. } => {
-                for name in names {
-                    parts.push(name.to_owned());
+            Self::Stored { backups } => {
+                parts.push(format!("{}:", name));
+                for backup in backups {
+                    parts.push(format!("  - {}", backup.describe()));
+                    if !restore_hints {
+                        continue;
+                    }
+                    parts.push(format!(
+                        "    ludusavi restore --backup {} {:?}",
+                        backup
+                            .id
+                            .map(|x| x.to_string())
+                            .unwrap_or_else(|| format!("{:?}", backup.name)),
+                        name
+                    ));
                 }
+
+                // Blank line between games.
+                parts.push("".to_string());
             }
-            Self::Json { output } => {
-                for name in names {
-                    output.games.insert(name.to_owned(), ApiGame::Found {});
+            Self::Found {
+                score,
+                matched_via,
+                matched_name,
+                matched_id,
+            } => match (score, matched_via, matched_name, matched_id) {
+                (Some(score), Some(matched_via), Some(matched_name), _) => {
+                    parts.push(format!(
+                        "{} ({})",
+                        name,
+                        TRANSLATOR.fuzzy_match(*score, *matched_via, matched_name)
+                    ));
                 }
-            }
+                (_, _, _, Some(matched_id)) => {
+                    parts.push(format!("{} ({})", name, TRANSLATOR.store_id(matched_id)));
+                }
+                _ => {
+                    parts.push(name.to_string());
+                }
+            },
         }
     }
+}
 
-    fn render(&self, path: &StrictPath) -> String {
-        match self {
-            Self::Standard { parts, status, errors } => match status {
-                Some(status) => {
-                    let mut out = parts.join("\n") + "\n" + &TRANSLATOR.cli_summary(status, path);
-                    for message in errors.messages() {
-                        out += &format!("\n\n{message}");
-                    }
-                    out
-                }
-                None => parts.join("\n"),
-            },
-            Self::Json { output } => serde_json::to_string_pretty(&output).unwrap(),
+/// Multi-line comments would break up the list, so only show where they start.
+fn first_line(text: &str) -> String {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default();
+    if lines.next().is_some() {
+        format!("{first}…")
+    } else {
+        first.to_string()
+    }
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+struct ApiRestoredBackup {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    when: chrono::DateTime<chrono::Utc>,
+    /// The version of Ludusavi that made the backup, if it was recorded.
+    #[serde(rename = "appVersion", skip_serializing_if = "Option::is_none")]
+    app_version: Option<String>,
+    /// The backup was made by a newer version of Ludusavi, so it may not restore correctly.
+    #[serde(rename = "newerVersion", skip_serializing_if = "crate::serialization::is_false")]
+    newer_version: bool,
+}
+
+impl ApiRestoredBackup {
+    fn new(backup: &Backup) -> Self {
+        Self {
+            name: backup.name().to_string(),
+            id: backup.short_id(),
+            when: *backup.when(),
+            app_version: backup.app_version().map(|x| x.to_string()),
+            newer_version: backup.app_version().is_some_and(is_newer_version),
         }
     }
+}
 
-    pub fn print_failure(&self) {
-        // The standard reporter doesn't need to print on failure because
-        // that's handled generically in main.
-        if let Self::Json { .. } = self {
-            self.print(&StrictPath::new("".to_string()));
+/// See `restore --backup-before-restore`.
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+struct ApiSafetySnapshot {
+    /// The name of the new backup.
+    /// This is omitted if there were no current files to back up or if the backup failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup: Option<String>,
+    #[serde(skip_serializing_if = "crate::serialization::is_false")]
+    failed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ApiSafetySnapshot {
+    fn new(snapshot: &SafetySnapshot) -> Self {
+        match snapshot {
+            SafetySnapshot::Unneeded => Self {
+                backup: None,
+                failed: false,
+                error: None,
+            },
+            SafetySnapshot::Created(backup) => Self {
+                backup: Some(backup.clone()),
+                failed: false,
+                error: None,
+            },
+            SafetySnapshot::Failed(error) => Self {
+                backup: None,
+                failed: true,
+                error: Some(error.clone()),
+            },
         }
     }
 
-    pub fn print(&self, path: &StrictPath) {
-        println!("{}", self.render(path));
+    /// A note for the human-readable reporters, if there's anything to say.
+    fn describe(&self) -> Option<String> {
+        match (&self.backup, &self.error) {
+            (Some(backup), _) => Some(TRANSLATOR.cli_game_safety_snapshot(backup)),
+            (None, Some(error)) => Some(TRANSLATOR.cli_game_safety_snapshot_failed(error)),
+            (None, None) => None,
+        }
     }
 }
 
-pub fn report_cloud_changes(changes: &[CloudChange], api: bool) {
-    if api {
-        #[derive(serde::Serialize)]
-        struct Output {
-            cloud: BTreeMap<String, Entry>,
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+struct ApiBackup {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    when: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    os: Option<Os>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    pub locked: bool,
+    #[serde(rename = "appVersion", skip_serializing_if = "Option::is_none")]
+    app_version: Option<String>,
+    #[serde(rename = "manifestHash", skip_serializing_if = "Option::is_none")]
+    manifest_hash: Option<String>,
+    trigger: Trigger,
+    bytes: u64,
+    files: usize,
+}
+
+impl ApiBackup {
+    /// A one-line description for the human-readable reporters.
+    fn describe(&self) -> String {
+        let mut line = "".to_string();
+        if let Some(id) = self.id {
+            line += &format!("[{id}] ");
+        }
+        line += &format!(
+            "\"{}\" ({})",
+            self.name,
+            self.when.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S"),
+        );
+        if let Some(os) = self.os {
+            line += &format!(" [{os:?}]");
+        }
+        if self.locked {
+            line += " [🔒]";
         }
+        line += &format!(" [{}]", self.trigger.describe());
+        line += &format!(" ({})", TRANSLATOR.cli_backup_size(self.bytes, self.files));
+        if let Some(comment) = &self.comment {
+            line += &format!(" - {}", first_line(comment));
+        }
+        line
+    }
+}
 
-        #[derive(serde::Serialize)]
-        struct Entry {
-            change: ScanChange,
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+pub struct JsonOutput {
+    /// Which schema this report follows (see `--api-version`).
+    /// This is bumped whenever the structure changes incompatibly.
+    #[serde(rename = "apiVersion")]
+    #[schemars(with = "u32")]
+    api_version: ApiVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<ApiErrors>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overall: Option<OperationStatus>,
+    /// Set when a backup only covered the games selected by `backup.mode`.
+    #[serde(rename = "backupMode", skip_serializing_if = "Option::is_none")]
+    backup_mode: Option<ApiBackupMode>,
+    /// The resolved time bounds used to select backups, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<BackupFilter>,
+    /// Kept in the order that games were added, for the standard reporter.
+    #[serde(serialize_with = "crate::serialization::ordered_index_map")]
+    #[schemars(with = "BTreeMap<String, ApiGame>")]
+    games: IndexMap<String, ApiGame>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cloud: Option<ApiCloud>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted: Option<ApiDeletion>,
+    /// Only used by the standard reporter, since the JSON always includes everything.
+    #[serde(skip)]
+    verbose: bool,
+    /// Games with more files than this are summarized (see `scan.fileListLimit`).
+    #[serde(skip)]
+    file_list_limit: Option<usize>,
+    /// Leave out file hashes (see `scan.reportFileHashes`).
+    #[serde(skip)]
+    omit_hashes: bool,
+    /// Where to write the report instead of stdout (see `--output-file`).
+    #[serde(skip)]
+    output_file: Option<StrictPath>,
+    /// Only used by the standard reporter (see `--color`).
+    #[serde(skip)]
+    color: ColorChoice,
+    /// Leave out unchanged games and entries (see `--only-changed`).
+    #[serde(skip)]
+    only_changed: bool,
+    /// Only include games and entries that failed (see `--only-failed`).
+    #[serde(skip)]
+    only_failed: bool,
+    /// Set if the JSON Lines reporter couldn't write a game to the output file,
+    /// in which case the rest of the report goes to stdout.
+    #[serde(skip)]
+    output_file_error: Option<Error>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiBackupMode {
+    mode: BackupMode,
+    /// Games that were left out without being scanned.
+    skipped_games: usize,
+}
+
+#[derive(serde::Serialize)]
+struct JsonLinesGame<'a> {
+    game: &'a str,
+    #[serde(flatten)]
+    details: &'a ApiGame,
+}
+
+/// The last line of the JSON Lines output, which has everything except the games.
+/// The CSV reporter also prints this on stderr.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLinesSummary<'a> {
+    api_version: ApiVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<&'a ApiErrors>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overall: Option<&'a OperationStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup_mode: Option<&'a ApiBackupMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<&'a BackupFilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cloud: Option<&'a ApiCloud>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted: Option<&'a ApiDeletion>,
+}
+
+impl<'a> JsonLinesSummary<'a> {
+    fn new(output: &'a JsonOutput) -> Self {
+        Self {
+            api_version: output.api_version,
+            errors: output.errors.as_ref(),
+            overall: output.overall.as_ref(),
+            backup_mode: output.backup_mode.as_ref(),
+            filter: output.filter.as_ref(),
+            cloud: output.cloud.as_ref(),
+            deleted: output.deleted.as_ref(),
         }
+    }
+}
 
-        let changes = Output {
-            cloud: changes
-                .iter()
-                .map(|x| (x.path.clone(), Entry { change: x.change }))
-                .collect(),
+/// Backups that the `prune` or `backups delete` command deleted, or would delete in preview mode.
+/// The backups themselves are listed under each game.
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+struct ApiDeletion {
+    preview: bool,
+    backups: usize,
+    bytes: u64,
+}
+
+/// Changes that a cloud sync made, or would make in preview mode.
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+struct ApiCloud {
+    changes: BTreeMap<String, ApiCloudChange>,
+    counts: ApiCloudCounts,
+    /// Only shown by the standard reporter.
+    #[serde(skip)]
+    preview: bool,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ApiCloudChange {
+    change: ScanChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resumed_at: Option<u8>,
+}
+
+#[derive(Debug, Default, serde::Serialize, schemars::JsonSchema)]
+struct ApiCloudCounts {
+    new: usize,
+    different: usize,
+    removed: usize,
+}
+
+impl ApiCloud {
+    fn new(changes: &[CloudChange], preview: bool) -> Self {
+        let mut cloud = Self {
+            preview,
+            ..Default::default()
         };
-        eprintln!("{}", serde_json::to_string_pretty(&changes).unwrap());
-        return;
+
+        for CloudChange {
+            path,
+            change,
+            resumed_at,
+        } in changes
+        {
+            match change {
+                ScanChange::New => cloud.counts.new += 1,
+                ScanChange::Different => cloud.counts.different += 1,
+                ScanChange::Removed => cloud.counts.removed += 1,
+                ScanChange::Same | ScanChange::Unknown => (),
+            }
+            cloud.changes.insert(
+                path.clone(),
+                ApiCloudChange {
+                    change: *change,
+                    resumed_at: *resumed_at,
+                },
+            );
+        }
+
+        cloud
     }
 
-    if changes.is_empty() {
-        eprintln!("{}", TRANSLATOR.no_cloud_changes());
-    } else {
-        for CloudChange { path, change } in changes.iter().sorted() {
-            println!("[{}] {}", change.symbol(), path);
+    fn render(&self, palette: Palette, parts: &mut Vec<String>) {
+        parts.push(TRANSLATOR.cli_cloud_header(self.preview));
+        if self.changes.is_empty() {
+            parts.push(format!("  {}", TRANSLATOR.no_cloud_changes()));
+        }
+        for (path, ApiCloudChange { change, resumed_at }) in &self.changes {
+            let symbol = format!("[{}]", change.symbol());
+            let symbol = match change {
+                ScanChange::New => palette.added(symbol),
+                ScanChange::Different => palette.changed(symbol),
+                ScanChange::Removed | ScanChange::Same | ScanChange::Unknown => symbol,
+            };
+            parts.push(format!("  {} {}{}", symbol, path, render_resumed(*resumed_at)));
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use maplit::hashset;
-    use pretty_assertions::assert_eq;
+impl JsonOutput {
+    fn insert_game(&mut self, name: &str, game: ApiGame) {
+        self.games.insert(name.to_string(), game);
+    }
 
-    use super::*;
-    use crate::{
-        scan::{registry_compat::RegistryItem, ScannedFile, ScannedRegistry},
-        testing::s,
-    };
+    fn write(&self, text: &str, append: bool) -> Result<(), Error> {
+        write_report(text, self.output_file.as_ref(), append)
+    }
 
-    fn drive() -> String {
-        if cfg!(target_os = "windows") {
-            StrictPath::new(s("foo")).render()[..2].to_string()
+    /// One line per game, starting from the given index, for the JSON Lines reporter.
+    fn game_lines(&self, start: usize) -> impl Iterator<Item = String> + '_ {
+        self.games.iter().skip(start).map(|(name, game)| {
+            ApiFormat::JsonLines.serialize(&JsonLinesGame {
+                game: name,
+                details: game,
+            })
+        })
+    }
+
+    fn game_mut(&mut self, name: &str) -> Option<&mut ApiGame> {
+        self.games.get_mut(name)
+    }
+}
+
+/// All variants collect the same data, and only differ in how they render it.
+#[derive(Debug)]
+pub enum Reporter {
+    Standard {
+        output: JsonOutput,
+    },
+    Json {
+        output: JsonOutput,
+    },
+    Yaml {
+        output: JsonOutput,
+    },
+    /// Prints each game as soon as the next one is added,
+    /// since a game can still be annotated (e.g., as forced) right after it's added.
+    JsonLines {
+        output: JsonOutput,
+        streamed: usize,
+    },
+    /// Prints one row per file and registry entry,
+    /// and everything else on stderr so that the rows can be loaded as-is.
+    Csv {
+        output: JsonOutput,
+    },
+    /// Like the standard reporter, but laid out in tables for pasting into issue trackers and wikis.
+    Markdown {
+        output: JsonOutput,
+    },
+}
+
+/// How the CLI arguments asked for reports to be rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReportOptions {
+    /// See `--color`.
+    pub color: ColorChoice,
+    /// See `--api-version`.
+    pub api_version: ApiVersion,
+    /// See `--api-format`.
+    pub api_format: ApiFormat,
+    /// See `--progress`.
+    pub progress: bool,
+}
+
+/// Output is left plain unless asked otherwise, so that tests and library users don't depend on the terminal.
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            color: ColorChoice::Never,
+            api_version: ApiVersion::LATEST,
+            api_format: ApiFormat::Json,
+            progress: false,
+        }
+    }
+}
+
+impl Reporter {
+    /// The reporter for a command, depending on whether `--api` was set.
+    pub fn new(api: bool, options: ReportOptions) -> Self {
+        if api {
+            Self::api(options)
         } else {
-            s("")
+            let mut reporter = Self::standard();
+            reporter.output_mut().color = options.color;
+            reporter
         }
     }
 
-    #[test]
-    fn can_render_in_standard_mode_with_minimal_input() {
-        let mut reporter = Reporter::standard();
-        reporter.add_game(
-            "foo",
-            &ScanInfo::default(),
-            &BackupInfo::default(),
-            &OperationStepDecision::Processed,
-            &DuplicateDetector::default(),
-        );
-        assert_eq!(
-            format!(
-                r#"
-Overall:
-  Games: 0
-  Size: 0 B
-  Location: {}/dev/null
-            "#,
-                &drive()
-            )
-            .trim_end(),
-            reporter.render(&StrictPath::new(s("/dev/null")))
-        )
+    pub fn standard() -> Self {
+        Self::Standard {
+            output: JsonOutput {
+                overall: Some(Default::default()),
+                ..Default::default()
+            },
+        }
     }
 
-    #[test]
-    fn can_render_in_standard_mode_with_one_game_in_backup_mode() {
-        let mut reporter = Reporter::standard();
+    pub fn json() -> Self {
+        Self::Json {
+            output: Self::structured_output(ApiVersion::LATEST),
+        }
+    }
+
+    pub fn yaml() -> Self {
+        Self::Yaml {
+            output: Self::structured_output(ApiVersion::LATEST),
+        }
+    }
+
+    /// This always uses the latest schema, since older versions predate it.
+    pub fn json_lines() -> Self {
+        Self::JsonLines {
+            output: Self::structured_output(ApiVersion::LATEST),
+            streamed: 0,
+        }
+    }
+
+    pub fn csv() -> Self {
+        Self::Csv {
+            output: Self::structured_output(ApiVersion::LATEST),
+        }
+    }
+
+    pub fn markdown() -> Self {
+        Self::Markdown {
+            output: Self::structured_output(ApiVersion::LATEST),
+        }
+    }
+
+    /// The reporter for `--api`, in the format chosen by `--api-format`.
+    fn api(options: ReportOptions) -> Self {
+        let mut reporter = match options.api_format {
+            ApiFormat::Json => Self::json(),
+            ApiFormat::Yaml => Self::yaml(),
+            ApiFormat::JsonLines => return Self::json_lines(),
+            ApiFormat::Csv => Self::csv(),
+            ApiFormat::Markdown => Self::markdown(),
+        };
+        reporter.output_mut().api_version = options.api_version;
+        reporter
+    }
+
+    fn structured_output(api_version: ApiVersion) -> JsonOutput {
+        JsonOutput {
+            overall: Some(Default::default()),
+            api_version,
+            ..Default::default()
+        }
+    }
+
+    fn output_mut(&mut self) -> &mut JsonOutput {
+        match self {
+            Self::Standard { output }
+            | Self::Json { output }
+            | Self::Yaml { output }
+            | Self::JsonLines { output, .. }
+            | Self::Csv { output }
+            | Self::Markdown { output } => output,
+        }
+    }
+
+    fn output(&self) -> &JsonOutput {
+        match self {
+            Self::Standard { output }
+            | Self::Json { output }
+            | Self::Yaml { output }
+            | Self::JsonLines { output, .. }
+            | Self::Csv { output }
+            | Self::Markdown { output } => output,
+        }
+    }
+
+    fn insert_game(&mut self, name: &str, game: ApiGame) {
+        if let Self::JsonLines { output, streamed } = self {
+            let lines: Vec<_> = output.game_lines(*streamed).collect();
+            if !lines.is_empty() {
+                if let Err(e) = output.write(&lines.join("\n"), *streamed > 0) {
+                    output.output_file = None;
+                    output.output_file_error = Some(e);
+                }
+            }
+            *streamed = output.games.len();
+        }
+        self.output_mut().insert_game(name, game);
+    }
+
+    /// Write the report to this file instead of stdout.
+    pub fn set_output_file(&mut self, file: Option<StrictPath>) {
+        self.output_mut().output_file = file;
+    }
+
+    /// Show extra details that the JSON output always includes.
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.output_mut().verbose = verbose;
+    }
+
+    pub fn set_backup_mode(&mut self, mode: BackupMode, skipped_games: usize) {
+        self.output_mut().backup_mode = Some(ApiBackupMode { mode, skipped_games });
+    }
+
+    /// For games with more files than this, the standard reporter summarizes them by folder,
+    /// and the JSON reporter only lists some of them.
+    pub fn set_file_list_limit(&mut self, limit: Option<usize>) {
+        self.output_mut().file_list_limit = limit;
+    }
+
+    /// File hashes are included unless this is turned off.
+    pub fn set_report_hashes(&mut self, report: bool) {
+        self.output_mut().omit_hashes = !report;
+    }
+
+    /// Unchanged games and entries are left out, but still counted in the overall totals.
+    /// Entries that failed are always included.
+    pub fn set_only_changed(&mut self, only_changed: bool) {
+        self.output_mut().only_changed = only_changed;
+    }
+
+    /// Games and entries that didn't fail are left out, but still counted in the overall totals.
+    pub fn set_only_failed(&mut self, only_failed: bool) {
+        self.output_mut().only_failed = only_failed;
+    }
+
+    fn set_errors(&mut self, f: impl FnOnce(&mut ApiErrors)) {
+        f(self.output_mut().errors.get_or_insert_with(Default::default));
+    }
+
+    fn trip_some_games_failed(&mut self) {
+        self.set_errors(|e| {
+            e.some_games_failed = Some(true);
+        });
+    }
+
+    fn trip_deferred_game(&mut self) {
+        self.set_errors(|e| {
+            *e.deferred_games.get_or_insert(0) += 1;
+        });
+    }
+
+    pub fn trip_unknown_games(&mut self, games: Vec<UnknownGame>) {
+        self.set_errors(|e| {
+            e.unknown_games = Some(games);
+        });
+    }
+
+    pub fn trip_cloud_conflict(&mut self, changes: &[CloudChange]) {
+        let changes = changes
+            .iter()
+            .sorted()
+            .map(|x| concern::CloudConflictChange {
+                path: x.path.clone(),
+                change: x.change,
+            })
+            .collect();
+        self.set_errors(|e| {
+            e.cloud_conflict = Some(concern::CloudConflict { changes });
+        });
+    }
+
+    pub fn trip_cloud_sync_failed(&mut self) {
+        self.set_errors(|e| {
+            e.cloud_sync_failed = Some(concern::CloudSyncFailed {});
+        });
+    }
+
+    pub fn trip_cloud_sync_deferred(&mut self) {
+        self.set_errors(|e| {
+            e.cloud_sync_deferred = Some(concern::CloudSyncDeferred {});
+        });
+    }
+
+    pub fn trip_running_check_unavailable(&mut self) {
+        self.set_errors(|e| {
+            e.running_check_unavailable = Some(concern::RunningCheckUnavailable {});
+        });
+    }
+
+    pub fn trip_inconsistent_backup(&mut self, game: &str, problems: Vec<Discrepancy>, repaired: bool) {
+        self.set_errors(|e| {
+            e.inconsistent_backups
+                .get_or_insert_with(Default::default)
+                .insert(game.to_string(), concern::InconsistentBackup { repaired, problems });
+        });
+    }
+
+    fn trip_unresolved_base_dirs(&mut self) {
+        self.set_errors(|e| {
+            e.unresolved_base_dirs.get_or_insert_with(Default::default).games += 1;
+        });
+    }
+
+    /// Record every location that timed out during the scan.
+    pub fn trip_io_timeouts(&mut self, paths: &[StrictPath]) {
+        if paths.is_empty() {
+            return;
+        }
+        self.set_errors(|e| {
+            e.io_timeout = Some(concern::IoTimeout {
+                paths: paths.iter().map(|x| x.render()).collect(),
+            });
+        });
+    }
+
+    pub fn trip_redundant_roots(&mut self, redundant: &[RedundantRoot]) {
+        if redundant.is_empty() {
+            return;
+        }
+        self.set_errors(|e| {
+            e.redundant_roots = Some(concern::RedundantRoots {
+                paths: redundant.iter().map(|x| x.root.path.raw()).collect(),
+            });
+        });
+    }
+
+    pub fn trip_manifest_fallback(&mut self, fallback: Option<ManifestFallback>) {
+        let Some(fallback) = fallback else {
+            return;
+        };
+        self.set_errors(|e| {
+            e.manifest_fallback = Some(concern::ManifestFallback {
+                why: fallback.why,
+                age_hours: fallback
+                    .updated
+                    .map(|updated| chrono::Utc::now().signed_duration_since(updated).num_hours()),
+            });
+        });
+    }
+
+    pub fn overall(&self) -> Option<&OperationStatus> {
+        match self {
+            Self::Standard { output }
+            | Self::Json { output }
+            | Self::Yaml { output }
+            | Self::JsonLines { output, .. }
+            | Self::Csv { output }
+            | Self::Markdown { output } => output.overall.as_ref(),
+        }
+    }
+
+    /// This is the same as the `errors` field in the JSON output.
+    pub fn errors_json(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::Standard { output }
+            | Self::Json { output }
+            | Self::Yaml { output }
+            | Self::JsonLines { output, .. }
+            | Self::Csv { output }
+            | Self::Markdown { output } => output.errors.as_ref().and_then(|x| serde_json::to_value(x).ok()),
+        }
+    }
+
+    pub fn suppress_overall(&mut self) {
+        self.output_mut().overall = None;
+    }
+
+    pub fn set_total_duration(&mut self, duration: Duration) {
+        if let Some(overall) = self.output_mut().overall.as_mut() {
+            overall.total_duration_ms = Some(duration.as_millis() as u64);
+        }
+    }
+
+    pub fn set_backup_filter(&mut self, filter: &BackupFilter) {
+        if filter.is_empty() {
+            return;
+        }
+
+        self.output_mut().filter = Some(filter.clone());
+    }
+
+    pub fn add_game(
+        &mut self,
+        name: &str,
+        scan_info: &ScanInfo,
+        backup_info: &BackupInfo,
+        decision: &OperationStepDecision,
+        duplicate_detector: &DuplicateDetector,
+        duration_ms: Option<u64>,
+    ) -> bool {
+        let unresolved_base_dirs: Vec<_> = if decision == &OperationStepDecision::Ignored {
+            vec![]
+        } else {
+            scan_info.unresolved_base_dirs.iter().map(|x| x.render()).collect()
+        };
+        if !unresolved_base_dirs.is_empty() {
+            self.trip_unresolved_base_dirs();
+        }
+        let io_timeouts: Vec<_> = scan_info.io_timeouts.iter().map(|x| x.render()).collect();
+
+        if !scan_info.can_report_game_or_removal() {
+            let only_failed = self.output_mut().only_failed;
+            if (!unresolved_base_dirs.is_empty() || !io_timeouts.is_empty()) && !only_failed {
+                self.insert_game(
+                    name,
+                    ApiGame::Unresolved {
+                        unresolved_base_dirs,
+                        io_timeouts,
+                    },
+                );
+            }
+            return true;
+        }
+
+        let mut successful = true;
+        let restoring = scan_info.restoring();
+
+        let omit_hashes = self.output_mut().omit_hashes;
+        let only_changed = self.output_mut().only_changed;
+        let only_failed = self.output_mut().only_failed;
+        let mut files = vec![];
+        for entry in itertools::sorted(&scan_info.found_files) {
+            let mut api_file = ApiFile {
+                bytes: entry.size,
+                hash: (!omit_hashes && !entry.hash.is_empty()).then(|| entry.hash.clone()),
+                failed: backup_info.failed_files.contains_key(entry),
+                error: backup_info.failed_files.get(entry).cloned(),
+                ignored: entry.ignored,
+                ignored_by: (entry.ignored && entry.junk).then_some(IgnoreSource::JunkFilter),
+                change: entry.change(),
+                sqlite_safe_copy: entry.sqlite_safe_copy,
+                changed_during_backup: backup_info.changed_during_backup.contains_key(entry),
+                stored_as: backup_info.altered_names.get(entry).map(|x| x.render()),
+                migrated_from: entry.migrated_from.as_ref().map(|x| x.render()),
+                unsafe_zip_entry: backup_info.unsafe_zip_entries.get(entry).cloned(),
+                redirect_rule: entry.redirect_rule.as_ref().map(|x| ApiRedirectRule {
+                    index: x.index,
+                    source: x.source.render(),
+                    target: x.target.render(),
+                }),
+                root: scan_info.roots.get(&entry.path).map(|x| x.render()),
+                local_state: scan_info.local_state(entry),
+                ..Default::default()
+            };
+            let wine_user_variant = if restoring {
+                entry.redirected.is_some().then(|| entry.original_path())
+            } else {
+                entry.migrated_from.as_ref()
+            };
+            api_file.wine_user_variant = wine_user_variant
+                .filter(|other| is_wine_user_variant(other, entry.effective()))
+                .map(|x| x.render());
+            if let Some(candidates) = scan_info.ambiguous_target(entry) {
+                api_file.skip_reason = Some(SkipReason::AmbiguousTarget);
+                api_file.candidate_targets = candidates.iter().map(|x| x.render()).collect();
+            } else if scan_info.os_constrained(entry) {
+                api_file.skip_reason = Some(SkipReason::OsConstrained);
+            } else if scan_info.kept_newer_local(entry) {
+                api_file.skip_reason = Some(SkipReason::NewerLocal);
+            }
+            if !duplicate_detector.is_file_duplicated(entry).resolved() {
+                let mut duplicated_by: HashSet<_> = duplicate_detector.file(entry).into_keys().collect();
+                duplicated_by.remove(&scan_info.game_name);
+                api_file.duplicated_by = duplicated_by;
+            }
+
+            if let Some(alt) = entry.alt_readable(restoring) {
+                if restoring {
+                    api_file.original_path = Some(alt);
+                } else {
+                    api_file.redirected_path = Some(alt);
+                }
+            }
+            if api_file.failed {
+                successful = false;
+            } else if only_failed || (only_changed && api_file.change == ScanChange::Same) {
+                continue;
+            }
+
+            files.push((entry.readable(restoring), api_file));
+        }
+
+        // The standard reporter summarizes long lists when rendering instead.
+        let mut files_truncated = false;
+        if let Self::Json { output } | Self::Yaml { output } | Self::JsonLines { output, .. } = self {
+            if let Some(limit) = output
+                .file_list_limit
+                .filter(|limit| files.len() > *limit && output.api_version.can_truncate_files())
+            {
+                let mut kept = 0;
+                files.retain(|(_, file)| {
+                    kept += 1;
+                    kept <= limit || file.needs_attention()
+                });
+                files_truncated = true;
+            }
+        }
+
+        let mut registry = vec![];
+        for entry in itertools::sorted(&scan_info.found_registry_keys) {
+            let mut api_registry = ApiRegistry {
+                failed: backup_info.failed_registry.contains_key(&entry.path),
+                error: backup_info.failed_registry.get(&entry.path).cloned(),
+                ignored: entry.ignored,
+                change: entry.change(scan_info.restoring()),
+                values: entry
+                    .values
+                    .iter()
+                    .map(|(k, v)| {
+                        (
+                            k.clone(),
+                            ApiRegistryValue {
+                                change: v.change(scan_info.restoring()),
+                                ignored: v.ignored,
+                                duplicated_by: {
+                                    if !duplicate_detector
+                                        .is_registry_value_duplicated(&entry.path, k)
+                                        .resolved()
+                                    {
+                                        let mut duplicated_by: HashSet<_> =
+                                            duplicate_detector.registry_value(&entry.path, k).into_keys().collect();
+                                        duplicated_by.remove(&scan_info.game_name);
+                                        duplicated_by
+                                    } else {
+                                        HashSet::new()
+                                    }
+                                },
+                            },
+                        )
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            if !duplicate_detector.is_registry_duplicated(&entry.path).resolved() {
+                let mut duplicated_by: HashSet<_> = duplicate_detector.registry(&entry.path).into_keys().collect();
+                duplicated_by.remove(&scan_info.game_name);
+                api_registry.duplicated_by = duplicated_by;
+            }
+
+            if only_changed {
+                api_registry.values.retain(|_, value| value.change != ScanChange::Same);
+            }
+            if api_registry.failed {
+                successful = false;
+            } else if only_failed
+                || (only_changed && api_registry.change == ScanChange::Same && api_registry.values.is_empty())
+            {
+                continue;
+            }
+
+            registry.push((entry.path.render(), api_registry));
+        }
+
+        if let Some(overall) = self.output_mut().overall.as_mut() {
+            overall.add_game(
+                scan_info,
+                &Some(backup_info.clone()),
+                decision == &OperationStepDecision::Processed,
+            );
+        }
+        if successful && (only_failed || (only_changed && scan_info.overall_change() == ScanChange::Same)) {
+            return successful;
+        }
+        self.insert_game(
+            name,
+            ApiGame::Operative(Box::new(ApiOperativeGame {
+                decision: decision.clone(),
+                change: scan_info.overall_change(),
+                source: scan_info.source,
+                backup: scan_info.backup.as_ref().map(ApiRestoredBackup::new),
+                totals: ApiGameTotals::new(scan_info, backup_info, decision == &OperationStepDecision::Processed),
+                files,
+                files_truncated,
+                registry: (!scan_info.registry_skipped).then_some(registry),
+                registry_skipped: scan_info.registry_skipped,
+                merged_titles: vec![],
+                unresolved_base_dirs,
+                io_timeouts,
+                skip_reason: None,
+                forced: false,
+                backup_kind: backup_info.kind.clone(),
+                promoted_because: backup_info.promoted_because.clone(),
+                comment: backup_info.label.comment.clone(),
+                locked: backup_info.label.locked,
+                safety_snapshot: None,
+                duration_ms,
+                bytes: scan_info.sum_bytes(Some(backup_info)),
+                duplicated: !duplicate_detector.is_game_duplicated(&scan_info.game_name).resolved(),
+            })),
+        );
+
+        if decision == &OperationStepDecision::Deferred {
+            self.trip_deferred_game();
+        } else if !successful {
+            self.trip_some_games_failed();
+        }
+        successful
+    }
+
+    /// List the titles that were merged into a game that was just added.
+    pub fn add_merged_titles(&mut self, name: &str, titles: &[String]) {
+        if titles.is_empty() {
+            return;
+        }
+
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.merged_titles = titles.to_vec();
+        }
+    }
+
+    /// Add a game that was skipped without a full scan
+    /// because its save locations haven't been modified recently.
+    /// Since it wasn't scanned, this is left out when only showing changes or failures.
+    pub fn add_inactive_game(&mut self, name: &str) {
+        let output = self.output_mut();
+        if output.only_changed || output.only_failed {
+            return;
+        }
+
+        self.insert_game(
+            name,
+            ApiGame::Operative(Box::new(ApiOperativeGame {
+                decision: OperationStepDecision::Processed,
+                change: ScanChange::Same,
+                source: None,
+                backup: None,
+                totals: Default::default(),
+                files: vec![],
+                files_truncated: false,
+                registry: None,
+                registry_skipped: false,
+                merged_titles: vec![],
+                unresolved_base_dirs: vec![],
+                io_timeouts: vec![],
+                skip_reason: Some(SkipReason::NoRecentActivity),
+                forced: false,
+                backup_kind: None,
+                promoted_because: None,
+                comment: None,
+                locked: false,
+                safety_snapshot: None,
+                duration_ms: None,
+                bytes: 0,
+                duplicated: false,
+            })),
+        );
+    }
+
+    /// Note that a game that was just added was skipped because it seems to be running.
+    pub fn add_running_game(&mut self, name: &str, process: RunningProcess, restoring: bool) {
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.skip_reason = Some(SkipReason::GameRunning);
+        }
+
+        self.set_errors(|e| {
+            e.game_running
+                .get_or_insert_with(Default::default)
+                .insert(name.to_string(), process);
+            e.game_running_in_backup = !restoring;
+        });
+    }
+
+    /// Note the backup that was made before restoring a game that was just added.
+    pub fn add_safety_snapshot(&mut self, name: &str, snapshot: &SafetySnapshot) {
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.safety_snapshot = Some(ApiSafetySnapshot::new(snapshot));
+        }
+    }
+
+    /// Note that a game that was just added was skipped because its saves haven't changed.
+    pub fn add_unchanged_game(&mut self, name: &str) {
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.skip_reason = Some(SkipReason::Unchanged);
+        }
+    }
+
+    /// Note that a game that was just added was skipped because of `trip_inconsistent_backup`.
+    pub fn add_inconsistent_game(&mut self, name: &str) {
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.skip_reason = Some(SkipReason::InconsistentBackup);
+        }
+    }
+
+    pub fn add_forced_game(&mut self, name: &str) {
+        if let Some(ApiGame::Operative(game)) = self.output_mut().game_mut(name) {
+            game.forced = true;
+        }
+    }
+
+    /// Each backup is paired with its size on disk (see `GameLayout::backup_bytes`).
+    pub fn add_backups(&mut self, name: &str, available_backups: &[(Backup, u64)]) {
+        if available_backups.is_empty() {
+            return;
+        }
+
+        let mut backups = vec![];
+        for (backup, bytes) in available_backups {
+            backups.push(ApiBackup {
+                name: backup.name().to_string(),
+                id: backup.short_id(),
+                when: *backup.when(),
+                os: backup.os(),
+                comment: backup.comment().to_owned(),
+                locked: backup.locked(),
+                app_version: backup.app_version().map(|x| x.to_string()),
+                manifest_hash: backup.manifest_hash().map(|x| x.to_string()),
+                trigger: backup.trigger(),
+                bytes: *bytes,
+                files: backup.file_count(),
+            });
+        }
+
+        self.insert_game(name, ApiGame::Stored { backups });
+    }
+
+    pub fn add_deleted_backups(&mut self, name: &str, deleted: &[(Backup, u64)], preview: bool) {
+        let deletion = self.output_mut().deleted.get_or_insert_with(|| ApiDeletion {
+            preview,
+            ..Default::default()
+        });
+        deletion.backups += deleted.len();
+        deletion.bytes += deleted.iter().map(|(_, bytes)| bytes).sum::<u64>();
+
+        self.add_backups(name, deleted);
+    }
+
+    pub fn add_cloud_changes(&mut self, changes: &[CloudChange], preview: bool) {
+        self.output_mut().cloud = Some(ApiCloud::new(changes, preview));
+    }
+
+    pub fn add_found_titles(&mut self, names: &BTreeSet<String>) {
+        for name in names {
+            self.insert_game(
+                name,
+                ApiGame::Found {
+                    score: None,
+                    matched_via: None,
+                    matched_name: None,
+                    matched_id: None,
+                },
+            );
+        }
+    }
+
+    /// These are listed in the given order, which should be best first.
+    pub fn add_fuzzy_matches(&mut self, matches: &[FuzzyMatch]) {
+        for found in matches {
+            self.insert_game(
+                &found.title,
+                ApiGame::Found {
+                    score: Some(found.score),
+                    matched_via: Some(found.matched_via),
+                    matched_name: Some(found.matched_name.clone()),
+                    matched_id: None,
+                },
+            );
+        }
+    }
+
+    pub fn add_found_by_store_id(&mut self, name: &str, id: &StoreId) {
+        self.insert_game(
+            name,
+            ApiGame::Found {
+                score: None,
+                matched_via: None,
+                matched_name: None,
+                matched_id: Some(id.clone()),
+            },
+        );
+    }
+
+    pub fn render(&self, path: &StrictPath) -> String {
+        match self {
+            Self::Standard { output } => {
+                let palette = output.color.palette(output.output_file.is_some());
+                let mut parts = vec![];
+                for (name, game) in &output.games {
+                    // Deleted backups are gone, so there's nothing to restore.
+                    let restore_hints = output.deleted.is_none();
+                    game.render(
+                        name,
+                        output.verbose,
+                        output.file_list_limit,
+                        palette,
+                        restore_hints,
+                        &mut parts,
+                    );
+                }
+                if output.only_failed && output.games.is_empty() {
+                    parts.push(TRANSLATOR.cli_no_failures());
+                    parts.push("".to_string());
+                }
+
+                let mut out = match &output.overall {
+                    Some(status) => {
+                        let mut out = parts.join("\n") + "\n" + &TRANSLATOR.cli_summary(status, path);
+                        if let Some(ApiBackupMode { mode, skipped_games }) = &output.backup_mode {
+                            out += &format!("\n  {}", TRANSLATOR.cli_summary_backup_mode(mode, *skipped_games));
+                        }
+                        for message in output.errors.as_ref().map(|x| x.messages()).unwrap_or_default() {
+                            out += &format!("\n\n{message}");
+                        }
+                        out
+                    }
+                    None => parts.join("\n"),
+                };
+
+                if let Some(ApiDeletion {
+                    preview,
+                    backups,
+                    bytes,
+                }) = &output.deleted
+                {
+                    out += &TRANSLATOR.cli_deleted_backups(*preview, *backups, *bytes);
+                }
+
+                if let Some(cloud) = &output.cloud {
+                    let mut parts = vec![];
+                    cloud.render(palette, &mut parts);
+                    if !out.is_empty() {
+                        out += "\n\n";
+                    }
+                    out += &parts.join("\n");
+                }
+
+                out
+            }
+            Self::Json { output } => schema::render(output, ApiFormat::Json),
+            Self::Yaml { output } => schema::render(output, ApiFormat::Yaml),
+            Self::JsonLines { output, streamed } => output
+                .game_lines(*streamed)
+                .chain([ApiFormat::JsonLines.serialize(&JsonLinesSummary::new(output))])
+                .join("\n"),
+            Self::Csv { output } => csv::render(output),
+            Self::Markdown { output } => markdown::render(output, path),
+        }
+    }
+
+    /// What the CSV reporter prints on stderr, since it doesn't fit in the rows.
+    fn render_csv_summary(&self) -> Option<String> {
+        match self {
+            Self::Csv { output } => Some(ApiFormat::Json.serialize(&JsonLinesSummary::new(output))),
+            Self::Standard { .. }
+            | Self::Json { .. }
+            | Self::Yaml { .. }
+            | Self::JsonLines { .. }
+            | Self::Markdown { .. } => None,
+        }
+    }
+
+    pub fn print_failure(&self) {
+        if let Some(summary) = self.render_csv_summary() {
+            eprintln!("{summary}");
+        }
+        if let Some(text) = self.render_failure() {
+            // The operation has already failed, and the text is printed if the file can't be written.
+            let _ = self.output().write(&text, self.appends_to_output());
+        }
+    }
+
+    pub fn render_failure(&self) -> Option<String> {
+        // The standard reporter doesn't need to print on failure because
+        // that's handled generically in main.
+        match self {
+            Self::Standard { .. } => None,
+            Self::Json { .. }
+            | Self::Yaml { .. }
+            | Self::JsonLines { .. }
+            | Self::Csv { .. }
+            | Self::Markdown { .. } => Some(self.render(&StrictPath::new("".to_string()))),
+        }
+    }
+
+    /// If there's an output file and it can't be written, the report is printed instead.
+    pub fn print(&self, path: &StrictPath) -> Result<(), Error> {
+        let output = self.output();
+        if let Some(summary) = self.render_csv_summary() {
+            eprintln!("{summary}");
+        }
+        let written = output.write(&self.render(path), self.appends_to_output());
+        match &output.output_file_error {
+            Some(e) => Err(e.clone()),
+            None => written,
+        }
+    }
+
+    /// The JSON Lines reporter may have already written some games to the output file.
+    fn appends_to_output(&self) -> bool {
+        matches!(self, Self::JsonLines { streamed, .. } if *streamed > 0)
+    }
+
+    /// Only the standard reporter lacks structured output.
+    pub fn into_json_output(self) -> Option<JsonOutput> {
+        match self {
+            Self::Standard { .. } => None,
+            Self::Json { output }
+            | Self::Yaml { output }
+            | Self::JsonLines { output, .. }
+            | Self::Csv { output }
+            | Self::Markdown { output } => Some(output),
+        }
+    }
+}
+
+/// Write to the output file if there is one, or else print to stdout.
+/// If the file can't be written, the text is printed instead, so that the report isn't lost.
+pub fn write_report(text: &str, file: Option<&StrictPath>, append: bool) -> Result<(), Error> {
+    let Some(file) = file else {
+        println!("{text}");
+        return Ok(());
+    };
+
+    let written = file.create_parent_dir().and_then(|_| {
+        let mut handle = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(file.as_std_path_buf())?;
+        writeln!(handle, "{text}")
+    });
+
+    written.map_err(|e| {
+        log::error!("Unable to write report to {}: {e:?}", file.render());
+        println!("{text}");
+        Error::UnableToWriteReport {
+            path: file.clone(),
+            why: e.to_string(),
+        }
+    })
+}
+
+fn render_resumed(resumed_at: Option<u8>) -> String {
+    match resumed_at {
+        Some(percent) => format!(" ({})", TRANSLATOR.cloud_upload_resumed_at(percent)),
+        None => "".to_string(),
+    }
+}
+
+/// JSON Schema for the `--api` report, so that other tools can validate it.
+pub fn report_api_schema() {
+    report_schema::<JsonOutput>();
+}
+
+/// JSON Schema for a file or output format, generated from the type that Ludusavi reads or writes.
+pub fn report_schema<T: schemars::JsonSchema>() {
+    let schema = schemars::schema_for!(T);
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
+
+pub fn report_cloud_changes(changes: &[CloudChange], api: bool, api_version: ApiVersion) {
+    if api {
+        #[derive(serde::Serialize)]
+        struct Output {
+            /// This was added in API version 2, so older versions leave it out.
+            #[serde(rename = "apiVersion", skip_serializing_if = "Option::is_none")]
+            api_version: Option<ApiVersion>,
+            cloud: BTreeMap<String, Entry>,
+        }
+
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Entry {
+            change: ScanChange,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            resumed_at: Option<u8>,
+        }
+
+        let changes = Output {
+            api_version: Some(api_version).filter(|x| *x >= ApiVersion::V2),
+            cloud: changes
+                .iter()
+                .map(|x| {
+                    (
+                        x.path.clone(),
+                        Entry {
+                            change: x.change,
+                            resumed_at: x.resumed_at,
+                        },
+                    )
+                })
+                .collect(),
+        };
+        eprintln!("{}", serde_json::to_string_pretty(&changes).unwrap());
+        return;
+    }
+
+    if changes.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_cloud_changes());
+    } else {
+        for CloudChange {
+            path,
+            change,
+            resumed_at,
+        } in changes.iter().sorted()
+        {
+            println!("[{}] {}{}", change.symbol(), path, render_resumed(*resumed_at));
+        }
+    }
+}
+
+pub fn report_config_changes(changes: &[ConfigChange], api: bool) {
+    if api {
+        #[derive(serde::Serialize)]
+        struct Output<'a> {
+            changes: &'a [ConfigChange],
+        }
+
+        println!("{}", serde_json::to_string_pretty(&Output { changes }).unwrap());
+        return;
+    }
+
+    if changes.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_config_changes());
+        return;
+    }
+
+    let value = |x: &serde_yaml::Value| serde_json::to_string(x).unwrap_or_default();
+    for change in changes {
+        let line = match change {
+            ConfigChange::Added { key, value: new } => format!("[{ADD_SYMBOL}] {key} = {}", value(new)),
+            ConfigChange::Renamed { from, to } => format!("[{CHANGE_SYMBOL}] {from} -> {to}"),
+            ConfigChange::Removed { key, .. } => format!("[{REMOVAL_SYMBOL}] {key}"),
+            ConfigChange::Changed { key, old, new } => {
+                format!("[{CHANGE_SYMBOL}] {key} = {} -> {}", value(old), value(new))
+            }
+        };
+        println!("{line}");
+    }
+}
+
+pub fn report_import_changes(changes: &[ImportChange], api: bool) {
+    if api {
+        #[derive(serde::Serialize)]
+        struct Output<'a> {
+            changes: &'a [ImportChange],
+        }
+
+        println!("{}", serde_json::to_string_pretty(&Output { changes }).unwrap());
+        return;
+    }
+
+    if changes.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_imported_entries());
+        return;
+    }
+
+    let count = |outcome| changes.iter().filter(|x| x.outcome == outcome).count();
+    for change in changes {
+        let entry = match &change.game {
+            Some(game) => format!(
+                "{} ({game}): {}",
+                TRANSLATOR.import_section(change.section),
+                change.entry
+            ),
+            None => format!("{}: {}", TRANSLATOR.import_section(change.section), change.entry),
+        };
+        let line = match change.outcome {
+            ImportOutcome::Added => format!("[{ADD_SYMBOL}] {entry}"),
+            ImportOutcome::Updated => format!("[{CHANGE_SYMBOL}] {entry}"),
+            ImportOutcome::Skipped => format!("[{WARNING_SYMBOL}] {entry} ({})", TRANSLATOR.import_skipped()),
+            ImportOutcome::Unchanged => continue,
+        };
+        println!("{line}");
+    }
+    println!(
+        "{}",
+        TRANSLATOR.import_summary(
+            count(ImportOutcome::Added),
+            count(ImportOutcome::Updated),
+            count(ImportOutcome::Unchanged),
+            count(ImportOutcome::Skipped),
+        )
+    );
+}
+
+pub fn report_dangling_titles(dangling: &[DanglingTitle], api: bool) {
+    if api {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output<'a> {
+            dangling_titles: &'a [DanglingTitle],
+        }
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Output {
+                dangling_titles: dangling
+            })
+            .unwrap()
+        );
+        return;
+    }
+
+    if dangling.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_dangling_titles());
+        return;
+    }
+
+    for entry in dangling {
+        let line = match &entry.suggestion {
+            Some(suggestion) => format!("[{CHANGE_SYMBOL}] {} -> {suggestion}", TRANSLATOR.dangling_title(entry)),
+            None => format!("[{REMOVAL_SYMBOL}] {}", TRANSLATOR.dangling_title(entry)),
+        };
+        println!("{line}");
+    }
+}
+
+/// `in_manifest` tells whether the manifest has a game with the same name,
+/// which the custom game replaces.
+pub fn report_custom_games(games: &[CustomGame], in_manifest: impl Fn(&str) -> bool, api: bool) {
+    if api {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Game<'a> {
+            #[serde(flatten)]
+            game: &'a CustomGame,
+            overrides_manifest: bool,
+        }
+
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output<'a> {
+            custom_games: Vec<Game<'a>>,
+        }
+
+        let custom_games = games
+            .iter()
+            .map(|game| Game {
+                game,
+                overrides_manifest: in_manifest(&game.name),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&Output { custom_games }).unwrap());
+        return;
+    }
+
+    if games.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_custom_games());
+        return;
+    }
+
+    for game in games {
+        let mut notes = vec![];
+        if game.ignore {
+            notes.push(TRANSLATOR.badge_ignored());
+        }
+        if let Some(alias) = &game.alias {
+            notes.push(TRANSLATOR.custom_game_alias(alias));
+        }
+        if in_manifest(&game.name) {
+            notes.push(TRANSLATOR.custom_game_overrides_manifest());
+        }
+
+        if notes.is_empty() {
+            println!("{}", game.name);
+        } else {
+            println!("{} ({})", game.name, notes.join(", "));
+        }
+        for item in game.files.iter().chain(&game.registry) {
+            println!("  - {item}");
+        }
+    }
+}
+
+pub fn report_roots(roots: &[RootsConfig], redundant: &[RedundantRoot], api: bool) {
+    let redundancy = |root: &RootsConfig| redundant.iter().find(|x| x.root == *root);
+
+    if api {
+        #[derive(serde::Serialize)]
+        struct Root<'a> {
+            #[serde(flatten)]
+            root: &'a RootsConfig,
+            /// Whether the folder currently exists (or, for a glob, whether anything matches it).
+            exists: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            redundant: Option<&'a RedundantRoot>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Output<'a> {
+            roots: Vec<Root<'a>>,
+        }
+
+        let roots = roots
+            .iter()
+            .map(|root| Root {
+                root,
+                exists: !root.glob().is_empty(),
+                redundant: redundancy(root),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&Output { roots }).unwrap());
+        return;
+    }
+
+    if roots.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_roots_are_configured());
+        return;
+    }
+
+    for (i, root) in roots.iter().enumerate() {
+        match redundancy(root) {
+            Some(entry) => println!(
+                "{}. {} ({})",
+                i + 1,
+                render_root(root),
+                TRANSLATOR.redundant_root(entry)
+            ),
+            None => println!("{}. {}", i + 1, render_root(root)),
+        }
+    }
+}
+
+fn render_root(root: &RootsConfig) -> String {
+    format!("{} [{}]", root.path.raw(), TRANSLATOR.store(&root.store))
+}
+
+pub fn report_removed_roots(removed: &[RootsConfig]) {
+    for root in removed {
+        println!("[{REMOVAL_SYMBOL}] {}", render_root(root));
+    }
+}
+
+pub fn report_redundant_roots(redundant: &[RedundantRoot], api: bool) {
+    if api {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Output<'a> {
+            redundant_roots: &'a [RedundantRoot],
+        }
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Output {
+                redundant_roots: redundant
+            })
+            .unwrap()
+        );
+        return;
+    }
+
+    if redundant.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_redundant_roots());
+        return;
+    }
+
+    for entry in redundant {
+        println!(
+            "[{REMOVAL_SYMBOL}] {} [{}] ({})",
+            entry.root.path.raw(),
+            TRANSLATOR.store(&entry.root.store),
+            TRANSLATOR.redundant_root(entry)
+        );
+    }
+}
+
+pub fn report_dedup(report: &DedupReport, api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    if report.groups.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_duplicate_content());
+        return;
+    }
+
+    for group in &report.groups {
+        println!(
+            "{}:",
+            TRANSLATOR.duplicate_content_group(&group.hash, group.size, group.savings)
+        );
+        for file in &group.files {
+            println!("  - {}: {}", file.game, file.path);
+        }
+    }
+    println!();
+    println!("{}", TRANSLATOR.duplicate_content_total(report.total_savings));
+}
+
+pub fn report_backup_diff(diff: &BackupDiff, api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(diff).unwrap());
+        return;
+    }
+
+    println!(
+        "{}",
+        TRANSLATOR.backup_diff_header(&diff.game, &diff.old, diff.new.as_deref())
+    );
+
+    let registry = diff.registry.as_deref().unwrap_or_default();
+    if diff.files.is_empty() && registry.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_backup_differences());
+        return;
+    }
+
+    let size = |old: Option<u64>, new: Option<u64>| match (old, new) {
+        (Some(old), Some(new)) => format!("{} -> {}", TRANSLATOR.adjusted_size(old), TRANSLATOR.adjusted_size(new)),
+        (Some(size), None) | (None, Some(size)) => TRANSLATOR.adjusted_size(size),
+        (None, None) => String::new(),
+    };
+
+    for file in &diff.files {
+        println!(
+            "  {} {} ({})",
+            file.change.symbol(),
+            file.path,
+            size(file.old_size, file.new_size)
+        );
+    }
+
+    if !registry.is_empty() {
+        println!("{}", TRANSLATOR.backup_diff_registry());
+    }
+    for entry in registry {
+        let data = |data: &Option<serde_json::Value>| data.as_ref().map(|x| x.to_string());
+        match (&entry.value, data(&entry.old_data), data(&entry.new_data)) {
+            (None, _, _) => println!("  {} {}", entry.change.symbol(), entry.key),
+            (Some(value), Some(old), Some(new)) => {
+                println!(
+                    "  {} {}: {} ({} -> {})",
+                    entry.change.symbol(),
+                    entry.key,
+                    value,
+                    old,
+                    new
+                )
+            }
+            (Some(value), old, new) => println!(
+                "  {} {}: {} ({})",
+                entry.change.symbol(),
+                entry.key,
+                value,
+                old.or(new).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+pub fn report_storage(report: &StorageReport, api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    if report.games.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_size_history());
+        return;
+    }
+
+    for game in &report.games {
+        println!("{}: {}", game.game, TRANSLATOR.storage_line(game));
+        if game.missing_files > 0 {
+            println!("  - {}", TRANSLATOR.storage_missing_files(game.missing_files));
+        }
+    }
+    println!();
+    println!("{}", TRANSLATOR.storage_total(&report.total));
+    if report.total.missing_files > 0 {
+        println!("{}", TRANSLATOR.storage_missing_files(report.total.missing_files));
+    }
+}
+
+pub fn report_size_history(entries: &[SizeHistoryEntry], api: bool, csv: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(entries).unwrap());
+        return;
+    }
+
+    if csv {
+        println!("{}", SizeHistoryEntry::csv_header());
+        for entry in entries {
+            println!("{}", entry.to_csv_row());
+        }
+        return;
+    }
+
+    if entries.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_size_history());
+        return;
+    }
+
+    let mut game = None;
+    for entry in entries {
+        if game != Some(&entry.game) {
+            if game.is_some() {
+                println!();
+            }
+            println!("{}:", entry.game);
+            game = Some(&entry.game);
+        }
+        println!("  - {}", TRANSLATOR.size_history_line(entry));
+    }
+}
+
+pub fn report_cloud_history(summary: &Summary, api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(summary).unwrap());
+        return;
+    }
+
+    if summary.total.runs == 0 {
+        eprintln!("{}", TRANSLATOR.no_cloud_history());
+        return;
+    }
+
+    for (month, totals) in &summary.months {
+        println!("{}", TRANSLATOR.cloud_history_line(month, totals));
+        for (day, totals) in summary.days.iter().filter(|(day, _)| day.starts_with(month)) {
+            println!("  {}", TRANSLATOR.cloud_history_line(day, totals));
+        }
+    }
+    println!();
+    println!(
+        "{}",
+        TRANSLATOR.cloud_history_line(&TRANSLATOR.cloud_history_total(), &summary.total)
+    );
+}
+
+pub fn report_history(records: &[history::Record], api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(records).unwrap());
+        return;
+    }
+
+    if records.is_empty() {
+        eprintln!("{}", TRANSLATOR.no_run_history());
+        return;
+    }
+
+    for record in records {
+        println!("{}", TRANSLATOR.run_history_line(record));
+    }
+}
+
+pub fn report_explanation(explanation: &Explanation, api: bool) {
+    if api {
+        println!("{}", serde_json::to_string_pretty(explanation).unwrap());
+    } else {
+        println!("{}", serde_yaml::to_string(explanation).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::{btreemap, btreeset, hashmap, hashset};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        scan::{
+            layout::{DifferentialBackup, FullBackup, IndividualMappingFile},
+            registry_compat::RegistryItem,
+            BackupLabel, RedirectRule, ScannedFile, ScannedRegistry, TriggerSource,
+        },
+        testing::{repo, s},
+    };
+
+    fn drive() -> String {
+        if cfg!(target_os = "windows") {
+            StrictPath::new(s("foo")).render()[..2].to_string()
+        } else {
+            s("")
+        }
+    }
+
+    /// YAML quotes strings that contain a colon, like a Windows drive letter.
+    fn yaml_quote() -> &'static str {
+        if cfg!(target_os = "windows") {
+            "\""
+        } else {
+            ""
+        }
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_minimal_input() {
+        let mut reporter = Reporter::standard();
+        reporter.add_game(
+            "foo",
+            &ScanInfo::default(),
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            format!(
+                r#"
+Overall:
+  Games: 0
+  Size: 0 B
+  Location: {}/dev/null
+            "#,
+                &drive()
+            )
+            .trim_end(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        )
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_one_game_in_backup_mode() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(s("/file1")),
+                        size: 102_400,
+                        hash: "1".to_string(),
+                        original_path: None,
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                    ScannedFile {
+                        path: StrictPath::new(s("/file2")),
+                        size: 51_200,
+                        hash: "2".to_string(),
+                        original_path: None,
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key3").with_value_same("Value1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file2", 51_200, "2") => s("Permission denied (os error 13)"),
+                },
+                failed_registry: hashmap! {
+                    RegistryItem::new(s("HKEY_CURRENT_USER/Key1")) => s("Access is denied. (os error 5)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+  - [FAILED: Permission denied (os error 13)] <drive>/file2
+  - [FAILED: Access is denied. (os error 5)] HKEY_CURRENT_USER/Key1
+  - HKEY_CURRENT_USER/Key2
+  - HKEY_CURRENT_USER/Key3
+    - Value1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB / 150.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_removed_files() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file1", 100, "1", ScanChange::Same),
+                    ScannedFile::with_change("/file2", 0, "", ScanChange::Removed),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file3", 0, "", ScanChange::Removed),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100 B] [Δ]:
+  - <drive>/file1
+  - [x] <drive>/file2
+
+bar [0 B] [x]:
+  - [x] <drive>/file3
+
+Overall:
+  Games: 2 [Δ1] [x1]
+  Size: 100 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_multiple_games_in_backup_mode() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(s("/file1")),
+                        size: 1,
+                        hash: "1".to_string(),
+                        original_path: None,
+                        ignored: false,
+                        change: ScanChange::Same,
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {},
+                failed_registry: hashmap! {},
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(s("/file2")),
+                        size: 3,
+                        hash: "2".to_string(),
+                        original_path: None,
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {},
+                failed_registry: hashmap! {},
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [1 B]:
+  - <drive>/file1
+
+bar [3 B]:
+  - <drive>/file2
+
+Overall:
+  Games: 2
+  Size: 4 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_one_game_in_restore_mode() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file1", drive())),
+                        size: 102_400,
+                        hash: "1".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file2", drive())),
+                        size: 51_200,
+                        hash: "2".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [150.00 KiB]:
+  - <drive>/original/file1
+  - <drive>/original/file2
+
+Overall:
+  Games: 1
+  Size: 150.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_os_constrained_files() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file1", drive())),
+                        size: 102_400,
+                        hash: "1".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                        ..Default::default()
+                    },
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file2", drive())),
+                        size: 51_200,
+                        hash: "2".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
+                        ..Default::default()
+                    },
+                },
+                os_constrained: btreeset! {
+                    StrictPath::new(format!("{}/original/file2", drive())),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [150.00 KiB]:
+  - <drive>/original/file1
+  - <drive>/original/file2
+    - Skipped because it was backed up on a different operating system (use a redirect to restore it here)
+  Skipped 1 file from a different operating system.
+
+Overall:
+  Games: 1
+  Size: 150.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_newer_local_files() {
+        let file = |name: &str, ignored| ScannedFile {
+            path: StrictPath::new(format!("{}/backup/{name}", drive())),
+            size: 100,
+            hash: name.to_string(),
+            original_path: Some(StrictPath::new(format!("{}/original/{name}", drive()))),
+            ignored,
+            change: ScanChange::Different,
+            ..Default::default()
+        };
+        let scan_info = ScanInfo {
+            game_name: s("foo"),
+            found_files: hashset! { file("file1", false), file("file2", true), file("file3", false) },
+            local_states: btreemap! {
+                StrictPath::new(format!("{}/original/file1", drive())) => LocalState::NewerLocal,
+                StrictPath::new(format!("{}/original/file2", drive())) => LocalState::NewerLocal,
+                StrictPath::new(format!("{}/original/file3", drive())) => LocalState::OlderLocal,
+            },
+            kept_newer_local: btreeset! {
+                StrictPath::new(format!("{}/original/file2", drive())),
+            },
+            ..Default::default()
+        };
+
+        let mut reporter = Reporter::standard();
+        reporter.add_game(
+            "foo",
+            &scan_info,
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [200 B] [Δ]:
+  - [Δ] <drive>/original/file1
+    - [!] The local file is newer than this backup, so restoring would overwrite it (use --no-clobber-newer to keep it)
+  - [IGNORED] <drive>/original/file2
+    - Skipped because the local file is newer than this backup
+  - [Δ] <drive>/original/file3
+
+Overall:
+  Games: 1 [Δ1]
+  Size: 200 B / 300 B
+  Location: <drive>/dev/null
+  Ignored: 1 file (100 B), 0 registry keys
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+
+        let mut reporter = Reporter::json();
+        reporter.add_game(
+            "foo",
+            &scan_info,
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        let output: serde_json::Value =
+            serde_json::from_str(&reporter.render(&StrictPath::new(s("/dev/null")))).unwrap();
+        let files = &output["games"]["foo"]["files"];
+        let file = |name: &str| &files[format!("{}/original/{name}", drive())];
+        assert_eq!("newerLocal", file("file1")["localState"]);
+        assert!(file("file1").get("skipReason").is_none());
+        assert_eq!("newerLocal", file("file2")["localState"]);
+        assert_eq!("newerLocal", file("file2")["skipReason"]);
+        assert_eq!(true, file("file2")["ignored"]);
+        assert_eq!("olderLocal", file("file3")["localState"]);
+    }
+
+    #[test]
+    fn can_render_safety_snapshots() {
+        let scan_info = |name: &str| ScanInfo {
+            game_name: s(name),
+            found_files: hashset! {
+                ScannedFile {
+                    path: StrictPath::new(format!("{}/backup/{name}.sav", drive())),
+                    size: 100,
+                    hash: name.to_string(),
+                    original_path: Some(StrictPath::new(format!("{}/original/{name}.sav", drive()))),
+                    change: ScanChange::Different,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        };
+        let add = |reporter: &mut Reporter| {
+            for (name, snapshot) in [
+                ("bar", SafetySnapshot::Failed(s("oops"))),
+                ("foo", SafetySnapshot::Created(s("backup-20240101T000000Z"))),
+            ] {
+                reporter.add_game(
+                    name,
+                    &scan_info(name),
+                    &BackupInfo::default(),
+                    &OperationStepDecision::Processed,
+                    &DuplicateDetector::default(),
+                    None,
+                );
+                reporter.add_safety_snapshot(name, &snapshot);
+            }
+        };
+
+        let mut reporter = Reporter::standard();
+        add(&mut reporter);
+        assert_eq!(
+            r#"
+bar [100 B] [Δ]:
+  - [Δ] <drive>/original/bar.sav
+  Unable to back up the current files before restoring: oops
+
+foo [100 B] [Δ]:
+  - [Δ] <drive>/original/foo.sav
+  Backed up the current files before restoring: backup-20240101T000000Z
+
+Overall:
+  Games: 2 [Δ2]
+  Size: 200 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+
+        let mut reporter = Reporter::json();
+        add(&mut reporter);
+        let output: serde_json::Value =
+            serde_json::from_str(&reporter.render(&StrictPath::new(s("/dev/null")))).unwrap();
+        assert_eq!(
+            serde_json::json!({"failed": true, "error": "oops"}),
+            output["games"]["bar"]["safetySnapshot"]
+        );
+        assert_eq!(
+            serde_json::json!({"backup": "backup-20240101T000000Z"}),
+            output["games"]["foo"]["safetySnapshot"]
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_unsafe_zip_entries() {
+        let mut reporter = Reporter::standard();
+
+        let file = ScannedFile {
+            path: StrictPath::new(s("drive-0/../../evil.sav")),
+            size: 1024,
+            hash: "1".to_string(),
+            original_path: Some(StrictPath::new(format!("{}/original/evil.sav", drive()))),
+            ..Default::default()
+        };
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! { file.clone() => s("invalid zip entry") },
+                unsafe_zip_entries: hashmap! {
+                    file => UnsafeZipEntry::EscapesRoot { entry: s("drive-0/../../evil.sav") },
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [0 B]:
+  - [FAILED: invalid zip entry] <drive>/original/evil.sav
+    - Rejected because the backup archive may be malicious: The entry leads outside of the archive: drive-0/../../evil.sav
+
+Overall:
+  Games: 1
+  Size: 0 B / 1024 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_color() {
+        let mut reporter = Reporter::Standard {
+            output: JsonOutput {
+                overall: Some(Default::default()),
+                color: ColorChoice::Always,
+                ..Default::default()
+            },
+        };
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1").change_as(ScanChange::New),
+                    ScannedFile::new("/file2", 1, "2").change_as(ScanChange::Different),
+                    ScannedFile::new("/file3", 1, "3").change_as(ScanChange::New).ignored(),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file2", 1, "2").change_as(ScanChange::Different) => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(), None,
+        );
+        assert_eq!(
+            r#"
+foo [1 B] \x1b[33m[Δ]\x1b[0m:
+  - \x1b[32m[+]\x1b[0m <drive>/file1
+  - \x1b[33m[Δ]\x1b[0m \x1b[31m[FAILED: Permission denied (os error 13)]\x1b[0m <drive>/file2
+  - \x1b[2m[IGNORED] <drive>/file3\x1b[0m
+
+Overall:
+  Games: 1 [Δ1]
+  Size: 1 B / 3 B
+  Location: <drive>/dev/null
+  Ignored: 1 file (1 B), 0 registry keys
+            "#
+            .trim()
+            .replace("\\x1b", "\x1b")
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_only_changed() {
+        let mut reporter = Reporter::standard();
+        reporter.set_only_changed(true);
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::new("/file2", 2, "2").change_as(ScanChange::Same),
+                    ScannedFile::new("/file3", 4, "3").change_as(ScanChange::New),
+                    ScannedFile::new("/file4", 8, "4").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file4", 8, "4").change_as(ScanChange::Same) => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(), None,
+        );
+        reporter.add_game(
+            "baz",
+            &ScanInfo {
+                game_name: s("baz"),
+                found_files: hashset! {
+                    ScannedFile::new("/file5", 16, "5").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+bar [6 B] [Δ]:
+  - [+] <drive>/file3
+  - [FAILED: Permission denied (os error 13)] <drive>/file4
+
+Overall:
+  Games: 3 [Δ1]
+  Size: 23 B / 31 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_only_failed() {
+        let mut reporter = Reporter::standard();
+        reporter.set_only_failed(true);
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1").change_as(ScanChange::New),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::new("/file2", 2, "2").change_as(ScanChange::New),
+                    ScannedFile::new("/file3", 4, "3").change_as(ScanChange::New),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1").change_as(ScanChange::New),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file3", 4, "3").change_as(ScanChange::New) => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(), None,
+        );
+        assert_eq!(
+            r#"
+bar [2 B] [+]:
+  - [+] [FAILED: Permission denied (os error 13)] <drive>/file3
+
+Overall:
+  Games: 2 [+2]
+  Size: 3 B / 7 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_only_failed_and_no_failures() {
+        let mut reporter = Reporter::standard();
+        reporter.set_only_failed(true);
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1").change_as(ScanChange::New),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+No games failed.
+
+Overall:
+  Games: 1 [+1]
+  Size: 1 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_duplicated_entries() {
+        let mut reporter = Reporter::standard();
+
+        let mut duplicate_detector = DuplicateDetector::default();
+        for name in &["foo", "bar"] {
+            duplicate_detector.add_game(
+                &ScanInfo {
+                    game_name: s(name),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::New),
+                    },
+                    found_registry_keys: hashset! {
+                        ScannedRegistry::new("HKEY_CURRENT_USER/Key1").change_as(ScanChange::New),
+                    },
+                    ..Default::default()
+                },
+                true,
+            );
+        }
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1"),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &duplicate_detector,
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB] [DUPLICATES]:
+  - [DUPLICATED] <drive>/file1
+  - [DUPLICATED] HKEY_CURRENT_USER/Key1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_different_file_changes() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new(s("/new"), 1, "1".to_string()).change_as(ScanChange::New),
+                    ScannedFile::new(s("/different"), 1, "1".to_string()).change_as(ScanChange::Different),
+                    ScannedFile::new(s("/same"), 1, "1".to_string()).change_as(ScanChange::Same),
+                    ScannedFile::new(s("/unknown"), 1, "1".to_string()).change_as(ScanChange::Unknown),
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {},
+                failed_registry: hashmap! {},
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::new(s("/brand-new"), 1, "1".to_string()).change_as(ScanChange::New),
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {},
+                failed_registry: hashmap! {},
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [4 B] [Δ]:
+  - [Δ] <drive>/different
+  - [+] <drive>/new
+  - <drive>/same
+  - <drive>/unknown
+
+bar [1 B] [+]:
+  - [+] <drive>/brand-new
+
+Overall:
+  Games: 2 [+1] [Δ1]
+  Size: 5 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_unresolved_base_dir() {
+        let mut reporter = Reporter::standard();
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                unresolved_base_dirs: btreeset! { StrictPath::new(s("/games/foo")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo
+  Install folder not found: <drive>/games/foo
+
+Overall:
+  Games: 0
+  Size: 0 B
+  Location: <drive>/dev/null
+
+Warning: 1 game had an install folder that no longer exists, so their saves may not have been found.
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_io_timeout() {
+        let mut reporter = Reporter::standard();
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                io_timeouts: btreeset! { StrictPath::new(s("/mnt/nas")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.trip_io_timeouts(&[StrictPath::new(s("/mnt/nas"))]);
+        assert_eq!(
+            r#"
+foo
+  Skipped because it did not respond in time: <drive>/mnt/nas
+
+Overall:
+  Games: 0
+  Size: 0 B
+  Location: <drive>/dev/null
+
+Warning: These locations did not respond in time, so they were skipped. Any games with saves there were not backed up:
+  - <drive>/mnt/nas
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_details_added_after_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1"),
+                },
+                unresolved_base_dirs: btreeset! { StrictPath::new(s("/games/foo")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_merged_titles("foo", &[s("foo (disc 2)")]);
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+  Install folder not found: <drive>/games/foo
+  Merged from: foo (disc 2)
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+
+Warning: 1 game had an install folder that no longer exists, so their saves may not have been found.
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_forced_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_forced_game("foo");
+        assert_eq!(
+            r#"
+foo [100.00 KiB] [FORCED]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_cloud_conflict() {
+        let mut reporter = Reporter::standard();
+
+        let changes: Vec<_> = (1..=7)
+            .map(|i| CloudChange {
+                path: format!("game/file{i}.txt"),
+                change: if i == 1 { ScanChange::New } else { ScanChange::Different },
+                resumed_at: None,
+            })
+            .rev()
+            .collect();
+        reporter.trip_cloud_conflict(&changes);
+        assert_eq!(
+            r#"
+Overall:
+  Games: 0
+  Size: 0 B
+  Location: <drive>/dev/null
+
+Warning: Your local and cloud backups are in conflict. Perform an upload or download to resolve this.
+  7 paths differ:
+  - [+] game/file1.txt
+  - [Δ] game/file2.txt
+  - [Δ] game/file3.txt
+  - [Δ] game/file4.txt
+  - [Δ] game/file5.txt
+  - ...and 2 more
+            "#
+            .trim_end()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_backup_mode() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.set_backup_mode(BackupMode::OnlyEnabledList, 42);
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+  Mode: Only enabled games (42 games skipped)
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_promoted_backup() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                kind: Some(BackupKind::Full),
+                promoted_because: Some(PromotionReason::DiffBytesExceeded {
+                    diff_bytes: 61_440,
+                    limit_bytes: 51_200,
+                }),
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+  Made a full backup because the differential backups would hold 60.00 KiB (limit: 50.00 KiB)
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_junk_file() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1"),
+                    ScannedFile {
+                        junk: true,
+                        ..ScannedFile::new("/game.log", 1_024, "2").ignored()
+                    },
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+  - [IGNORED] <drive>/game.log
+    - Ignored by the built-in junk filter
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB / 101.00 KiB
+  Location: <drive>/dev/null
+  Ignored: 1 file (1024 B), 0 registry keys
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_ignored_entries() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1"),
+                    ScannedFile::new("/file2", 2, "2").ignored(),
+                    ScannedFile::new("/file3", 4, "3").ignored(),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1").ignored(),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [1 B]:
+  - <drive>/file1
+  - [IGNORED] <drive>/file2
+  - [IGNORED] <drive>/file3
+  - [IGNORED] HKEY_CURRENT_USER/Key1
+
+Overall:
+  Games: 1
+  Size: 1 B / 7 B
+  Location: <drive>/dev/null
+  Ignored: 2 files (6 B), 1 registry key
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_files_over_limit() {
+        let mut reporter = Reporter::standard();
+        reporter.set_file_list_limit(Some(2));
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/saves/a/1", 1_024, "1"),
+                    ScannedFile::new("/saves/a/2", 1_024, "2"),
+                    ScannedFile::new("/saves/b/3", 1_024, "3"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/saves/b/3", 1_024, "3") => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [2.00 KiB]:
+  - <drive>/saves/a (2 files, 2.00 KiB)
+  - <drive>/saves/b (1 file, 1024 B)
+  - [FAILED: Permission denied (os error 13)] <drive>/saves/b/3
+
+Overall:
+  Games: 1
+  Size: 2.00 KiB / 3.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_altered_file_name() {
+        let mut reporter = Reporter::standard();
+
+        let file = ScannedFile::new("/caf\u{e9}.sav", 102_400, "1").change_as(ScanChange::Same);
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            },
+            &BackupInfo {
+                altered_names: hashmap! {
+                    file => StrictPath::new(s("/backup/foo/drive-0/cafe\u{301}.sav")),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/<nfc>.sav
+    - The backup location renamed this file to: <drive>/backup/foo/drive-0/<nfd>.sav
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive())
+            .replace("<nfc>", "caf\u{e9}")
+            .replace("<nfd>", "cafe\u{301}"),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_migrated_file() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        migrated_from: Some(StrictPath::new(s("/old/file1"))),
+                        ..ScannedFile::new("/new/file1", 102_400, "1").change_as(ScanChange::Same)
+                    },
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/new/file1
+    - Compared with the previous backup at its old location: <drive>/old/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_wine_user_variant() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        migrated_from: Some(StrictPath::new(s("/pfx/drive_c/users/alex/file1"))),
+                        ..ScannedFile::new("/pfx/drive_c/users/steamuser/file1", 102_400, "1").change_as(ScanChange::Same)
+                    },
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(), None,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/pfx/drive_c/users/steamuser/file1
+    - Matched with the same file in another Wine user folder: <drive>/pfx/drive_c/users/alex/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_redirect_rule_when_verbose() {
+        let file = ScannedFile {
+            redirected: Some(StrictPath::new(s("/new/file1"))),
+            redirect_rule: Some(RedirectRule {
+                index: 1,
+                source: StrictPath::new(s("/old")),
+                target: StrictPath::new(s("/new")),
+            }),
+            ..ScannedFile::new("/old/file1", 102_400, "1").change_as(ScanChange::Same)
+        };
+
+        for (verbose, rule_line) in [
+            (false, ""),
+            (true, "\n    - Redirect rule #2: <drive>/old → <drive>/new"),
+        ] {
+            let mut reporter = Reporter::standard();
+            reporter.set_verbose(verbose);
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! { file.clone() },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &DuplicateDetector::default(),
+                None,
+            );
+            assert_eq!(
+                format!(
+                    r#"
+foo [100.00 KiB]:
+  - <drive>/old/file1
+    - Redirecting to: <drive>/new/file1{rule_line}
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+                )
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_root_when_verbose() {
+        for (verbose, root) in [(false, ""), (true, " (<drive>/root)")] {
+            let mut reporter = Reporter::standard();
+            reporter.set_verbose(verbose);
+            reporter.add_game(
+                "foo",
+                &ScanInfo {
+                    game_name: s("foo"),
+                    found_files: hashset! {
+                        ScannedFile::new("/root/file1", 102_400, "1").change_as(ScanChange::Same),
+                    },
+                    source: Some(GameSource::Primary),
+                    roots: btreemap! {
+                        StrictPath::new(s("/root/file1")) => StrictPath::new(s("/root")),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &DuplicateDetector::default(),
+                None,
+            );
+            assert_eq!(
+                format!(
+                    r#"
+foo [100.00 KiB]:
+  - <drive>/root/file1{root}
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+            "#
+                )
+                .trim()
+                .replace("<drive>", &drive()),
+                reporter.render(&StrictPath::new(s("/dev/null")))
+            );
+        }
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_cloud_preview() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_cloud_changes(
+            &[
+                CloudChange {
+                    path: s("foo/mapping.yaml"),
+                    change: ScanChange::Different,
+                    resumed_at: None,
+                },
+                CloudChange {
+                    path: s("bar/mapping.yaml"),
+                    change: ScanChange::New,
+                    resumed_at: None,
+                },
+                CloudChange {
+                    path: s("foo/backup.zip"),
+                    change: ScanChange::Different,
+                    resumed_at: Some(63),
+                },
+            ],
+            true,
+        );
+        assert_eq!(
+            r#"
+foo [100.00 KiB]:
+  - <drive>/file1
+
+Overall:
+  Games: 1
+  Size: 100.00 KiB
+  Location: <drive>/dev/null
+
+Cloud (preview):
+  [+] bar/mapping.yaml
+  [Δ] foo/backup.zip (resumed at 63%)
+  [Δ] foo/mapping.yaml
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_no_cloud_changes() {
+        let mut reporter = Reporter::standard();
+        reporter.suppress_overall();
+        reporter.add_cloud_changes(&[], false);
+        assert_eq!(
+            r#"
+Cloud:
+  No changes to synchronize
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_minimal_input() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo::default(),
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {}
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_backup_mode() {
+        let mut reporter = Reporter::json();
+
+        reporter.set_backup_mode(BackupMode::OnlyEnabledList, 1);
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "backupMode": {
+    "mode": "onlyEnabledList",
+    "skippedGames": 1
+  },
+  "games": {}
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_removed_files() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file1", 100, "1", ScanChange::Same),
+                    ScannedFile::with_change("/file2", 0, "", ScanChange::Removed),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file3", 0, "", ScanChange::Removed),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 2,
+    "totalBytes": 100,
+    "processedGames": 2,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 1,
+      "removed": 1,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "bar": {
+      "decision": "Processed",
+      "change": "Removed",
+      "totalBytes": 0,
+      "processedBytes": 0,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file3": {
+          "change": "Removed",
+          "bytes": 0
+        }
+      },
+      "registry": {}
+    },
+    "foo": {
+      "decision": "Processed",
+      "change": "Different",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 2,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        },
+        "<drive>/file2": {
+          "change": "Removed",
+          "bytes": 0
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_one_game_in_backup_mode() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1"),
+                    ScannedFile::new("/file2", 50, "2"),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key3").with_value_same("Value1")
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file2", 50, "2") => s("Permission denied (os error 13)"),
+                },
+                failed_registry: hashmap! {
+                    RegistryItem::new(s("HKEY_CURRENT_USER/Key1")) => s("Access is denied. (os error 5)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "someGamesFailed": true
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 150,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 150,
+      "processedBytes": 100,
+      "failedBytes": 50,
+      "ignoredBytes": 0,
+      "fileCount": 2,
+      "registryCount": 3,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        },
+        "<drive>/file2": {
+          "failed": true,
+          "error": "Permission denied (os error 13)",
+          "change": "Unknown",
+          "bytes": 50,
+          "hash": "2"
+        }
+      },
+      "registry": {
+        "HKEY_CURRENT_USER/Key1": {
+          "failed": true,
+          "error": "Access is denied. (os error 5)",
+          "change": "Unknown"
+        },
+        "HKEY_CURRENT_USER/Key2": {
+          "change": "Unknown"
+        },
+        "HKEY_CURRENT_USER/Key3": {
+          "change": "Unknown",
+          "values": {
+            "Value1": {
+              "change": "Same"
+            }
+          }
+        }
+      }
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_one_game_in_restore_mode() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file1", drive())),
+                        size: 100,
+                        hash: "1".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file2", drive())),
+                        size: 50,
+                        hash: "2".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+  {
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 150,
+    "processedGames": 1,
+    "processedBytes": 150,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 150,
+      "processedBytes": 150,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 2,
+      "registryCount": 0,
+      "files": {
+        "<drive>/original/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        },
+        "<drive>/original/file2": {
+          "change": "Unknown",
+          "bytes": 50,
+          "hash": "2"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_duplicated_entries() {
+        let mut reporter = Reporter::json();
+
+        let mut duplicate_detector = DuplicateDetector::default();
+        for name in &["foo", "bar"] {
+            duplicate_detector.add_game(
+                &ScanInfo {
+                    game_name: s(name),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400, "1"),
+                    },
+                    found_registry_keys: hashset! {
+                        ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                    },
+                    ..Default::default()
+                },
+                true,
+            );
+        }
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "2"),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &duplicate_detector,
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 1,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "2",
+          "duplicatedBy": [
+            "bar"
+          ]
+        }
+      },
+      "registry": {
+        "HKEY_CURRENT_USER/Key1": {
+          "change": "Unknown",
+          "duplicatedBy": [
+            "bar"
+          ]
+        }
+      }
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_same_hash_for_duplicated_file() {
+        let mut reporter = Reporter::json();
+
+        let scan_info = |name: &str| ScanInfo {
+            game_name: s(name),
+            found_files: hashset! {
+                ScannedFile::new("/file1", 100, "abc"),
+            },
+            ..Default::default()
+        };
+
+        let mut duplicate_detector = DuplicateDetector::default();
+        for name in ["foo", "bar"] {
+            duplicate_detector.add_game(&scan_info(name), true);
+        }
+        for name in ["foo", "bar"] {
+            reporter.add_game(
+                name,
+                &scan_info(name),
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &duplicate_detector,
+                None,
+            );
+        }
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 2,
+    "totalBytes": 200,
+    "processedGames": 2,
+    "processedBytes": 200,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 2
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "bar": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "abc",
+          "duplicatedBy": [
+            "foo"
+          ]
+        }
+      },
+      "registry": {}
+    },
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "abc",
+          "duplicatedBy": [
+            "bar"
+          ]
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_without_hashes() {
+        let mut reporter = Reporter::json();
+        reporter.set_report_hashes(false);
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_different_file_changes() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/new", 1, "1").change_as(ScanChange::New),
+                    ScannedFile::new("/different", 1, "2").change_as(ScanChange::Different),
+                    ScannedFile::new("/same", 1, "2").change_as(ScanChange::Same),
+                    ScannedFile::new("/unknown", 1, "2").change_as(ScanChange::Unknown),
+                },
+                found_registry_keys: hashset! {},
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {},
+                failed_registry: hashmap! {},
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 4,
+    "processedGames": 1,
+    "processedBytes": 4,
+    "changedGames": {
+      "new": 0,
+      "different": 1,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Different",
+      "totalBytes": 4,
+      "processedBytes": 4,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 4,
+      "registryCount": 0,
+      "files": {
+        "<drive>/different": {
+          "change": "Different",
+          "bytes": 1,
+          "hash": "2"
+        },
+        "<drive>/new": {
+          "change": "New",
+          "bytes": 1,
+          "hash": "1"
+        },
+        "<drive>/same": {
+          "change": "Same",
+          "bytes": 1,
+          "hash": "2"
+        },
+        "<drive>/unknown": {
+          "change": "Unknown",
+          "bytes": 1,
+          "hash": "2"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_io_timeout() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1"),
+                },
+                io_timeouts: btreeset! { StrictPath::new(s("/mnt/nas/foo")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.trip_io_timeouts(&[StrictPath::new(s("/mnt/nas"))]);
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "ioTimeout": {
+      "paths": [
+        "<drive>/mnt/nas"
+      ]
+    }
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "ioTimeouts": [
+        "<drive>/mnt/nas/foo"
+      ]
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_manifest_fallback() {
+        let mut reporter = Reporter::json();
+
+        reporter.trip_manifest_fallback(Some(ManifestFallback {
+            why: s("bad yaml"),
+            updated: Some(chrono::Utc::now() - chrono::Duration::hours(3)),
+        }));
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "manifestFallback": {
+      "why": "bad yaml",
+      "ageHours": 3
+    }
+  },
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {}
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_unresolved_base_dir() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                unresolved_base_dirs: btreeset! { StrictPath::new(s("/games/foo")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                unresolved_base_dirs: btreeset! { StrictPath::new(s("/games/bar")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Ignored,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "unresolvedBaseDirs": {
+      "games": 1
+    }
+  },
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "unresolvedBaseDirs": [
+        "<drive>/games/foo"
+      ]
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_running_game() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::new(format!("{}/backup/file1", drive())),
+                        size: 100,
+                        hash: "1".to_string(),
+                        original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
+                        ignored: false,
+                        change: Default::default(),
+                        container: None,
+                        redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
+                    },
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Ignored,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_running_game(
+            "foo",
+            RunningProcess {
+                pid: Some(123),
+                name: Some(s("foo.exe")),
+                path: format!("{}/original/file1", drive()),
+            },
+            true,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "gameRunning": {
+      "foo": {
+        "pid": 123,
+        "name": "foo.exe",
+        "path": "<drive>/original/file1"
+      }
+    }
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Ignored",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 0,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/original/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "skipReason": "gameRunning"
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_forced_game() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_forced_game("foo");
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "forced": true
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_slow_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 1, "1").change_as(ScanChange::New),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            Some(3210),
+        );
+        reporter.add_game(
+            "bar",
+            &ScanInfo {
+                game_name: s("bar"),
+                found_files: hashset! {
+                    ScannedFile::new("/file2", 2, "2").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            Some(20),
+        );
+        assert_eq!(
+            r#"
+foo [1 B] [+] (took 3.2s):
+  - [+] <drive>/file1
+
+bar [2 B]:
+  - <drive>/file2
+
+Overall:
+  Games: 2 [+1]
+  Size: 3 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_durations() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            Some(25),
+        );
+        reporter.set_total_duration(std::time::Duration::from_millis(40));
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0,
+    "totalDurationMs": 40
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "durationMs": 25
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_source_and_root() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/root/file1", 100, "1").change_as(ScanChange::Same),
+                    ScannedFile::new("/other/file2", 50, "2").change_as(ScanChange::Same),
+                },
+                source: Some(GameSource::Secondary),
+                roots: btreemap! {
+                    StrictPath::new(s("/root/file1")) => StrictPath::new(s("/root")),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 150,
+    "processedGames": 1,
+    "processedBytes": 150,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "source": "secondary",
+      "totalBytes": 150,
+      "processedBytes": 150,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 2,
+      "registryCount": 0,
+      "files": {
+        "<drive>/other/file2": {
+          "change": "Same",
+          "bytes": 50,
+          "hash": "2"
+        },
+        "<drive>/root/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1",
+          "root": "<drive>/root"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_restored_backup() {
+        use chrono::TimeZone;
+
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        original_path: Some(StrictPath::new(s("/original/file1"))),
+                        ..ScannedFile::new("/backup/file1", 100, "1").change_as(ScanChange::Same)
+                    },
+                },
+                backup: Some(Backup::Differential(DifferentialBackup {
+                    name: s("backup-2"),
+                    id: Some(2),
+                    when: chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 4, 5).unwrap(),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.suppress_overall();
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "backup": {
+        "name": "backup-2",
+        "id": 2,
+        "when": "2000-01-02T03:04:05Z"
+      },
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/original/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_backup_from_newer_version() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        original_path: Some(StrictPath::new(s("/original/file1"))),
+                        ..ScannedFile::new("/backup/file1", 100, "1").change_as(ScanChange::Same)
+                    },
+                },
+                backup: Some(Backup::Full(FullBackup {
+                    name: s("backup-1"),
+                    app_version: Some(s("999.0.0")),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+foo [100 B]:
+  - <drive>/original/file1
+  Created by a newer version of Ludusavi (v999.0.0), so it may not restore correctly
+
+Overall:
+  Games: 1
+  Size: 100 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_inactive_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_inactive_game("foo");
+        assert_eq!(
+            r#"
+foo:
+  Skipped because its save locations have not changed recently
+
+Overall:
+  Games: 0
+  Size: 0 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_unchanged_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file1", 100, "1", ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Ignored,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_unchanged_game("foo");
+        assert_eq!(
+            r#"
+foo [100 B] [IGNORED]:
+  Skipped because its saves have not changed since the last backup
+
+Overall:
+  Games: 0 / 1
+  Size: 0 B / 100 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_inconsistent_game() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file1", 100, "1", ScanChange::Different),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Ignored,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_inconsistent_game("foo");
+        assert_eq!(
+            r#"
+foo [100 B] [Δ] [IGNORED]:
+  Skipped because its existing backups do not match their mapping file
+
+Overall:
+  Games: 0 / 1 [Δ1]
+  Size: 0 B / 100 B
+  Location: <drive>/dev/null
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_running_game_during_backup() {
+        let mut reporter = Reporter::standard();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::with_change("/file1", 100, "1", ScanChange::Different),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Ignored,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_running_game(
+            "foo",
+            RunningProcess {
+                pid: Some(123),
+                name: Some(s("foo.exe")),
+                path: s("/games/foo/foo.exe"),
+            },
+            false,
+        );
+        assert_eq!(
+            r#"
+foo [100 B] [Δ] [IGNORED]:
+  - [Δ] <drive>/file1
+
+Overall:
+  Games: 0 / 1 [Δ1]
+  Size: 0 B / 100 B
+  Location: <drive>/dev/null
+
+Warning: Skipped backing up foo because it appears to be running (foo.exe, PID 123). Try again after closing it.
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_deferred_game() {
+        let mut reporter = Reporter::json();
+
+        let file = ScannedFile::with_change("/file1", 100, "1", ScanChange::Different);
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    file.clone() => TRANSLATOR.failure_locked("in use"),
+                },
+                locked_files: hashset! { file },
+                deferred: true,
+                ..Default::default()
+            },
+            &OperationStepDecision::Deferred,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "deferredGames": 1
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 1,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Deferred",
+      "change": "Different",
+      "totalBytes": 100,
+      "processedBytes": 0,
+      "failedBytes": 100,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "failed": true,
+          "error": "Locked by another process: in use",
+          "change": "Different",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_inactive_game() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_inactive_game("foo");
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 0,
+      "processedBytes": 0,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 0,
+      "registryCount": 0,
+      "files": {},
+      "skipReason": "noRecentActivity"
+    }
+  }
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_promoted_backup() {
+        let mut reporter = Reporter::json();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                kind: Some(BackupKind::Full),
+                promoted_because: Some(PromotionReason::DiffBytesExceeded {
+                    diff_bytes: 60,
+                    limit_bytes: 50,
+                }),
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "backupKind": "full",
+      "promotedBecause": {
+        "diffBytesExceeded": {
+          "diffBytes": 60,
+          "limitBytes": 50
+        }
+      }
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_labeled_backup() {
+        let mut reporter = Reporter::json();
 
         reporter.add_game(
             "foo",
             &ScanInfo {
                 game_name: s("foo"),
                 found_files: hashset! {
-                    ScannedFile {
-                        path: StrictPath::new(s("/file1")),
-                        size: 102_400,
-                        hash: "1".to_string(),
-                        original_path: None,
-                        ignored: false,
-                        change: Default::default(),
-                        container: None,
-                        redirected: None,
-                    },
-                    ScannedFile {
-                        path: StrictPath::new(s("/file2")),
-                        size: 51_200,
-                        hash: "2".to_string(),
-                        original_path: None,
-                        ignored: false,
-                        change: Default::default(),
-                        container: None,
-                        redirected: None,
-                    },
-                },
-                found_registry_keys: hashset! {
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key3").with_value_same("Value1"),
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::New),
                 },
                 ..Default::default()
             },
             &BackupInfo {
-                failed_files: hashset! {
-                    ScannedFile::new("/file2", 51_200, "2"),
-                },
-                failed_registry: hashset! {
-                    RegistryItem::new(s("HKEY_CURRENT_USER/Key1"))
+                kind: Some(BackupKind::Full),
+                label: BackupLabel {
+                    comment: Some(s("before respec")),
+                    locked: true,
+                    ..Default::default()
                 },
+                ..Default::default()
             },
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
-foo [100.00 KiB]:
-  - <drive>/file1
-  - [FAILED] <drive>/file2
-  - [FAILED] HKEY_CURRENT_USER/Key1
-  - HKEY_CURRENT_USER/Key2
-  - HKEY_CURRENT_USER/Key3
-    - Value1
-
-Overall:
-  Games: 1
-  Size: 100.00 KiB / 150.00 KiB
-  Location: <drive>/dev/null
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 1,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "New",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "New",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {},
+      "backupKind": "full",
+      "comment": "before respec",
+      "locked": true
+    }
+  }
+}
             "#
             .trim()
             .replace("<drive>", &drive()),
@@ -650,74 +5901,195 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_standard_mode_with_multiple_games_in_backup_mode() {
-        let mut reporter = Reporter::standard();
+    fn can_render_in_json_mode_with_cloud_preview() {
+        let mut reporter = Reporter::json();
 
         reporter.add_game(
             "foo",
             &ScanInfo {
                 game_name: s("foo"),
                 found_files: hashset! {
-                    ScannedFile {
-                        path: StrictPath::new(s("/file1")),
-                        size: 1,
-                        hash: "1".to_string(),
-                        original_path: None,
-                        ignored: false,
-                        change: ScanChange::Same,
-                        container: None,
-                        redirected: None,
-                    },
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::Same),
                 },
-                found_registry_keys: hashset! {},
                 ..Default::default()
             },
-            &BackupInfo {
-                failed_files: hashset! {},
-                failed_registry: hashset! {},
-            },
+            &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
-        reporter.add_game(
-            "bar",
-            &ScanInfo {
-                game_name: s("bar"),
-                found_files: hashset! {
-                    ScannedFile {
-                        path: StrictPath::new(s("/file2")),
-                        size: 3,
-                        hash: "2".to_string(),
-                        original_path: None,
-                        ignored: false,
-                        change: Default::default(),
-                        container: None,
-                        redirected: None,
-                    },
+        reporter.add_cloud_changes(
+            &[
+                CloudChange {
+                    path: s("foo/mapping.yaml"),
+                    change: ScanChange::Different,
+                    resumed_at: None,
                 },
-                found_registry_keys: hashset! {},
-                ..Default::default()
-            },
-            &BackupInfo {
-                failed_files: hashset! {},
-                failed_registry: hashset! {},
-            },
-            &OperationStepDecision::Processed,
-            &DuplicateDetector::default(),
+                CloudChange {
+                    path: s("bar/mapping.yaml"),
+                    change: ScanChange::New,
+                    resumed_at: None,
+                },
+                CloudChange {
+                    path: s("baz/mapping.yaml"),
+                    change: ScanChange::Removed,
+                    resumed_at: None,
+                },
+                CloudChange {
+                    path: s("foo/backup.zip"),
+                    change: ScanChange::Different,
+                    resumed_at: Some(63),
+                },
+            ],
+            true,
         );
         assert_eq!(
             r#"
-foo [1 B]:
-  - <drive>/file1
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Same",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {}
+    }
+  },
+  "cloud": {
+    "changes": {
+      "bar/mapping.yaml": {
+        "change": "New"
+      },
+      "baz/mapping.yaml": {
+        "change": "Removed"
+      },
+      "foo/backup.zip": {
+        "change": "Different",
+        "resumedAt": 63
+      },
+      "foo/mapping.yaml": {
+        "change": "Different"
+      }
+    },
+    "counts": {
+      "new": 1,
+      "different": 2,
+      "removed": 1
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
 
-bar [3 B]:
-  - <drive>/file2
+    #[test]
+    fn can_render_in_json_mode_with_only_cloud_changes() {
+        let mut reporter = Reporter::json();
+        reporter.suppress_overall();
+        reporter.add_cloud_changes(&[], false);
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "games": {},
+  "cloud": {
+    "changes": {},
+    "counts": {
+      "new": 0,
+      "different": 0,
+      "removed": 0
+    }
+  }
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_standard_mode_with_backups() {
+        use chrono::TimeZone;
+
+        let mut reporter = Reporter::standard();
+        let when = chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 4, 5).unwrap();
+
+        reporter.add_backups(
+            "foo",
+            &[
+                (
+                    Backup::Full(FullBackup {
+                        name: s("backup-1"),
+                        id: Some(1),
+                        when,
+                        files: btreemap! {
+                            s("/file1.txt") => IndividualMappingFile { hash: s("123"), size: 1536 },
+                        },
+                        ..Default::default()
+                    }),
+                    1536,
+                ),
+                (
+                    Backup::Differential(DifferentialBackup {
+                        name: s("backup-2"),
+                        id: Some(2),
+                        when,
+                        files: btreemap! {
+                            s("/file1.txt") => Some(IndividualMappingFile { hash: s("456"), size: 10 }),
+                            s("/file2.txt") => None,
+                        },
+                        ..Default::default()
+                    }),
+                    10,
+                ),
+            ],
+        );
+        assert_eq!(
+            format!(
+                r#"
+foo:
+  - [1] "backup-1" ({when}) [unknown] (1.50 KiB, 1 file)
+    ludusavi restore --backup 1 "foo"
+  - [2] "backup-2" ({when}) [unknown] (10 B, 1 file)
+    ludusavi restore --backup 2 "foo"
 
 Overall:
-  Games: 2
-  Size: 4 B
+  Games: 0
+  Size: 0 B
   Location: <drive>/dev/null
-            "#
+                "#,
+                when = when.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S"),
+            )
             .trim()
             .replace("<drive>", &drive()),
             reporter.render(&StrictPath::new(s("/dev/null")))
@@ -725,52 +6097,224 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_standard_mode_with_one_game_in_restore_mode() {
+    fn can_render_in_standard_mode_with_multi_line_backup_comment() {
+        use chrono::TimeZone;
+
         let mut reporter = Reporter::standard();
+        reporter.suppress_overall();
+        let when = chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 4, 5).unwrap();
+
+        reporter.add_backups(
+            "foo",
+            &[
+                (
+                    Backup::Full(FullBackup {
+                        name: s("backup-1"),
+                        when,
+                        comment: Some(s("before 1.5 patch\nslot 2 is broken")),
+                        ..Default::default()
+                    }),
+                    0,
+                ),
+                (
+                    Backup::Full(FullBackup {
+                        name: s("backup-2"),
+                        when,
+                        comment: Some(s("one line")),
+                        ..Default::default()
+                    }),
+                    0,
+                ),
+            ],
+        );
+        assert_eq!(
+            format!(
+                r#"
+foo:
+  - "backup-1" ({when}) [unknown] (0 B, 0 files) - before 1.5 patch…
+    ludusavi restore --backup "backup-1" "foo"
+  - "backup-2" ({when}) [unknown] (0 B, 0 files) - one line
+    ludusavi restore --backup "backup-2" "foo"
+                "#,
+                when = when.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S"),
+            )
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null"))).trim()
+        );
+    }
+
+    #[test]
+    fn api_schema_describes_json_output() {
+        let schema = serde_json::to_value(schemars::schema_for!(JsonOutput)).unwrap();
+        let properties = &schema["properties"];
+
+        assert_eq!("integer", properties["apiVersion"]["type"]);
+        assert_eq!("object", properties["games"]["type"]);
+        assert!(properties["games"]["additionalProperties"].is_object());
+        assert!(properties.get("verbose").is_none());
+        assert!(properties.get("outputFile").is_none());
+        assert_eq!(serde_json::json!(["apiVersion", "games"]), schema["required"]);
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_backups() {
+        use chrono::TimeZone;
+
+        let mut reporter = Reporter::json();
+
+        reporter.add_backups(
+            "foo",
+            &[
+                (
+                    Backup::Full(FullBackup {
+                        name: s("backup-1"),
+                        when: chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 4, 5).unwrap(),
+                        ..Default::default()
+                    }),
+                    0,
+                ),
+                (
+                    Backup::Full(FullBackup {
+                        name: s("backup-2.zip"),
+                        files: btreemap! {
+                            s("/file1.txt") => IndividualMappingFile { hash: s("123"), size: 100 },
+                            s("/file2.txt") => IndividualMappingFile { hash: s("456"), size: 200 },
+                        },
+                        id: Some(2),
+                        when: chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 5, 5).unwrap(),
+                        app_version: Some(s("0.22.0")),
+                        manifest_hash: Some(s("abc")),
+                        trigger: Some(Trigger {
+                            source: TriggerSource::Wrap,
+                            host: Some(s("desktop")),
+                            executable: Some(s("game.exe")),
+                        }),
+                        ..Default::default()
+                    }),
+                    150,
+                ),
+            ],
+        );
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 0,
+    "totalBytes": 0,
+    "processedGames": 0,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "backups": [
+        {
+          "name": "backup-1",
+          "when": "2000-01-02T03:04:05Z",
+          "locked": false,
+          "trigger": {
+            "source": "unknown"
+          },
+          "bytes": 0,
+          "files": 0
+        },
+        {
+          "name": "backup-2.zip",
+          "id": 2,
+          "when": "2000-01-02T03:05:05Z",
+          "locked": false,
+          "appVersion": "0.22.0",
+          "manifestHash": "abc",
+          "trigger": {
+            "source": "wrap",
+            "host": "desktop",
+            "executable": "game.exe"
+          },
+          "bytes": 150,
+          "files": 2
+        }
+      ]
+    }
+  }
+}
+            "#
+            .trim(),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_json_mode_with_junk_file() {
+        let mut reporter = Reporter::json();
 
         reporter.add_game(
             "foo",
             &ScanInfo {
-                game_name: s("foo"),
-                found_files: hashset! {
-                    ScannedFile {
-                        path: StrictPath::new(format!("{}/backup/file1", drive())),
-                        size: 102_400,
-                        hash: "1".to_string(),
-                        original_path: Some(StrictPath::new(format!("{}/original/file1", drive()))),
-                        ignored: false,
-                        change: Default::default(),
-                        container: None,
-                        redirected: None,
-                    },
-                    ScannedFile {
-                        path: StrictPath::new(format!("{}/backup/file2", drive())),
-                        size: 51_200,
-                        hash: "2".to_string(),
-                        original_path: Some(StrictPath::new(format!("{}/original/file2", drive()))),
-                        ignored: false,
-                        change: Default::default(),
-                        container: None,
-                        redirected: None,
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile {
+                        junk: true,
+                        ..ScannedFile::new("/game.log", 100, "1").ignored()
                     },
                 },
-                found_registry_keys: hashset! {},
                 ..Default::default()
             },
             &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
-foo [150.00 KiB]:
-  - <drive>/original/file1
-  - <drive>/original/file2
-
-Overall:
-  Games: 1
-  Size: 150.00 KiB
-  Location: <drive>/dev/null
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 0,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 1,
+    "ignoredBytes": 100,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 100,
+      "processedBytes": 0,
+      "failedBytes": 0,
+      "ignoredBytes": 100,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/game.log": {
+          "ignored": true,
+          "ignoredBy": "junkFilter",
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        }
+      },
+      "registry": {}
+    }
+  }
+}
             "#
             .trim()
             .replace("<drive>", &drive()),
@@ -779,52 +6323,68 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_standard_mode_with_duplicated_entries() {
-        let mut reporter = Reporter::standard();
-
-        let mut duplicate_detector = DuplicateDetector::default();
-        for name in &["foo", "bar"] {
-            duplicate_detector.add_game(
-                &ScanInfo {
-                    game_name: s(name),
-                    found_files: hashset! {
-                        ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::New),
-                    },
-                    found_registry_keys: hashset! {
-                        ScannedRegistry::new("HKEY_CURRENT_USER/Key1").change_as(ScanChange::New),
-                    },
-                    ..Default::default()
-                },
-                true,
-            );
-        }
+    fn can_render_in_json_mode_with_file_changed_during_backup() {
+        let mut reporter = Reporter::json();
 
+        let file = ScannedFile::new("/file1", 100, "1").change_as(ScanChange::New);
         reporter.add_game(
             "foo",
             &ScanInfo {
                 game_name: s("foo"),
-                found_files: hashset! {
-                    ScannedFile::new("/file1", 102_400, "1"),
-                },
-                found_registry_keys: hashset! {
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            },
+            &BackupInfo {
+                changed_during_backup: hashmap! {
+                    file => IndividualMappingFile { hash: s("2"), size: 101 },
                 },
                 ..Default::default()
             },
-            &BackupInfo::default(),
             &OperationStepDecision::Processed,
-            &duplicate_detector,
+            &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
-foo [100.00 KiB] [DUPLICATES]:
-  - [DUPLICATED] <drive>/file1
-  - [DUPLICATED] HKEY_CURRENT_USER/Key1
-
-Overall:
-  Games: 1
-  Size: 100.00 KiB
-  Location: <drive>/dev/null
+{
+  "apiVersion": 2,
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
+    "changedGames": {
+      "new": 1,
+      "different": 0,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "New",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "New",
+          "bytes": 100,
+          "hash": "1",
+          "changedDuringBackup": true
+        }
+      },
+      "registry": {}
+    }
+  }
+}
             "#
             .trim()
             .replace("<drive>", &drive()),
@@ -833,61 +6393,82 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_standard_mode_with_different_file_changes() {
-        let mut reporter = Reporter::standard();
+    fn can_render_in_json_mode_with_files_over_limit() {
+        let mut reporter = Reporter::json();
+        reporter.set_file_list_limit(Some(1));
 
         reporter.add_game(
             "foo",
             &ScanInfo {
                 game_name: s("foo"),
                 found_files: hashset! {
-                    ScannedFile::new(s("/new"), 1, "1".to_string()).change_as(ScanChange::New),
-                    ScannedFile::new(s("/different"), 1, "1".to_string()).change_as(ScanChange::Different),
-                    ScannedFile::new(s("/same"), 1, "1".to_string()).change_as(ScanChange::Same),
-                    ScannedFile::new(s("/unknown"), 1, "1".to_string()).change_as(ScanChange::Unknown),
+                    ScannedFile::new("/file1", 100, "1"),
+                    ScannedFile::new("/file2", 100, "2"),
+                    ScannedFile::new("/file3", 100, "3"),
                 },
-                found_registry_keys: hashset! {},
                 ..Default::default()
             },
             &BackupInfo {
-                failed_files: hashset! {},
-                failed_registry: hashset! {},
-            },
-            &OperationStepDecision::Processed,
-            &DuplicateDetector::default(),
-        );
-        reporter.add_game(
-            "bar",
-            &ScanInfo {
-                game_name: s("bar"),
-                found_files: hashset! {
-                    ScannedFile::new(s("/brand-new"), 1, "1".to_string()).change_as(ScanChange::New),
+                failed_files: hashmap! {
+                    ScannedFile::new("/file3", 100, "3") => s("Permission denied (os error 13)"),
                 },
-                found_registry_keys: hashset! {},
                 ..Default::default()
             },
-            &BackupInfo {
-                failed_files: hashset! {},
-                failed_registry: hashset! {},
-            },
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
-foo [4 B] [Δ]:
-  - [Δ] <drive>/different
-  - [+] <drive>/new
-  - <drive>/same
-  - <drive>/unknown
-
-bar [1 B] [+]:
-  - [+] <drive>/brand-new
-
-Overall:
-  Games: 2 [+1] [Δ1]
-  Size: 5 B
-  Location: <drive>/dev/null
+{
+  "apiVersion": 2,
+  "errors": {
+    "someGamesFailed": true
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 300,
+    "processedGames": 1,
+    "processedBytes": 200,
+    "changedGames": {
+      "new": 0,
+      "different": 0,
+      "removed": 0,
+      "same": 1
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 300,
+      "processedBytes": 200,
+      "failedBytes": 100,
+      "ignoredBytes": 0,
+      "fileCount": 3,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "1"
+        },
+        "<drive>/file3": {
+          "failed": true,
+          "error": "Permission denied (os error 13)",
+          "change": "Unknown",
+          "bytes": 100,
+          "hash": "3"
+        }
+      },
+      "filesTruncated": true,
+      "registry": {}
+    }
+  }
+}
             "#
             .trim()
             .replace("<drive>", &drive()),
@@ -896,40 +6477,79 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_json_mode_with_minimal_input() {
+    fn can_render_in_json_mode_with_altered_file_name() {
         let mut reporter = Reporter::json();
 
+        let file = ScannedFile::new("/caf\u{e9}.sav", 100, "1").change_as(ScanChange::New);
         reporter.add_game(
             "foo",
-            &ScanInfo::default(),
-            &BackupInfo::default(),
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            },
+            &BackupInfo {
+                altered_names: hashmap! {
+                    file => StrictPath::new(s("/backup/foo/drive-0/cafe\u{301}.sav")),
+                },
+                ..Default::default()
+            },
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
 {
+  "apiVersion": 2,
   "overall": {
-    "totalGames": 0,
-    "totalBytes": 0,
-    "processedGames": 0,
-    "processedBytes": 0,
+    "totalGames": 1,
+    "totalBytes": 100,
+    "processedGames": 1,
+    "processedBytes": 100,
     "changedGames": {
-      "new": 0,
+      "new": 1,
       "different": 0,
+      "removed": 0,
       "same": 0
-    }
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
   },
-  "games": {}
+  "games": {
+    "foo": {
+      "decision": "Processed",
+      "change": "New",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
+      "files": {
+        "<drive>/<nfc>.sav": {
+          "change": "New",
+          "bytes": 100,
+          "hash": "1",
+          "storedAs": "<drive>/backup/foo/drive-0/<nfd>.sav"
+        }
+      },
+      "registry": {}
+    }
+  }
 }
             "#
-            .trim(),
+            .trim()
+            .replace("<drive>", &drive())
+            .replace("<nfc>", "caf\u{e9}")
+            .replace("<nfd>", "cafe\u{301}"),
             reporter.render(&StrictPath::new(s("/dev/null")))
         );
     }
 
     #[test]
-    fn can_render_in_json_mode_with_one_game_in_backup_mode() {
+    fn can_render_in_json_mode_with_redirect_rule() {
         let mut reporter = Reporter::json();
 
         reporter.add_game(
@@ -937,76 +6557,66 @@ Overall:
             &ScanInfo {
                 game_name: s("foo"),
                 found_files: hashset! {
-                    ScannedFile::new("/file1", 100, "1"),
-                    ScannedFile::new("/file2", 50, "2"),
-                },
-                found_registry_keys: hashset! {
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Key3").with_value_same("Value1")
+                    ScannedFile {
+                        redirected: Some(StrictPath::new(s("/new/file1"))),
+                        redirect_rule: Some(RedirectRule {
+                            index: 0,
+                            source: StrictPath::new(s("/old")),
+                            target: StrictPath::new(s("/new")),
+                        }),
+                        ..ScannedFile::new("/old/file1", 100, "1").change_as(ScanChange::New)
+                    },
                 },
                 ..Default::default()
             },
-            &BackupInfo {
-                failed_files: hashset! {
-                    ScannedFile::new("/file2", 50, "2"),
-                },
-                failed_registry: hashset! {
-                    RegistryItem::new(s("HKEY_CURRENT_USER/Key1"))
-                },
-            },
+            &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
 {
-  "errors": {
-    "someGamesFailed": true
-  },
+  "apiVersion": 2,
   "overall": {
     "totalGames": 1,
-    "totalBytes": 150,
+    "totalBytes": 100,
     "processedGames": 1,
     "processedBytes": 100,
     "changedGames": {
-      "new": 0,
+      "new": 1,
       "different": 0,
-      "same": 1
-    }
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
   },
   "games": {
     "foo": {
       "decision": "Processed",
-      "change": "Same",
+      "change": "New",
+      "totalBytes": 100,
+      "processedBytes": 100,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 1,
+      "registryCount": 0,
       "files": {
-        "<drive>/file1": {
-          "change": "Unknown",
-          "bytes": 100
-        },
-        "<drive>/file2": {
-          "failed": true,
-          "change": "Unknown",
-          "bytes": 50
-        }
-      },
-      "registry": {
-        "HKEY_CURRENT_USER/Key1": {
-          "failed": true,
-          "change": "Unknown"
-        },
-        "HKEY_CURRENT_USER/Key2": {
-          "change": "Unknown"
-        },
-        "HKEY_CURRENT_USER/Key3": {
-          "change": "Unknown",
-          "values": {
-            "Value1": {
-              "change": "Same"
-            }
+        "<drive>/old/file1": {
+          "change": "New",
+          "bytes": 100,
+          "hash": "1",
+          "redirectedPath": "<drive>/new/file1",
+          "redirectRule": {
+            "index": 0,
+            "source": "<drive>/old",
+            "target": "<drive>/new"
           }
         }
-      }
+      },
+      "registry": {}
     }
   }
 }
@@ -1018,8 +6628,100 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_json_mode_with_one_game_in_restore_mode() {
-        let mut reporter = Reporter::json();
+    fn can_render_in_yaml_mode_with_one_game_in_backup_mode() {
+        let mut reporter = Reporter::yaml();
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 100, "1"),
+                    ScannedFile::new("/file2", 50, "2"),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key2"),
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key3").with_value_same("Value1")
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file2", 50, "2") => s("Permission denied (os error 13)"),
+                },
+                failed_registry: hashmap! {
+                    RegistryItem::new(s("HKEY_CURRENT_USER/Key1")) => s("Access is denied. (os error 5)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        assert_eq!(
+            r#"
+---
+apiVersion: 2
+errors:
+  someGamesFailed: true
+overall:
+  totalGames: 1
+  totalBytes: 150
+  processedGames: 1
+  processedBytes: 100
+  changedGames:
+    new: 0
+    different: 0
+    removed: 0
+    same: 1
+  ignoredFiles: 0
+  ignoredBytes: 0
+  ignoredRegistryKeys: 0
+games:
+  foo:
+    decision: Processed
+    change: Same
+    totalBytes: 150
+    processedBytes: 100
+    failedBytes: 50
+    ignoredBytes: 0
+    fileCount: 2
+    registryCount: 3
+    files:
+      <q><drive>/file1<q>:
+        change: Unknown
+        bytes: 100
+        hash: "1"
+      <q><drive>/file2<q>:
+        failed: true
+        error: Permission denied (os error 13)
+        change: Unknown
+        bytes: 50
+        hash: "2"
+    registry:
+      HKEY_CURRENT_USER/Key1:
+        failed: true
+        error: Access is denied. (os error 5)
+        change: Unknown
+      HKEY_CURRENT_USER/Key2:
+        change: Unknown
+      HKEY_CURRENT_USER/Key3:
+        change: Unknown
+        values:
+          Value1:
+            change: Same
+            "#
+            .trim()
+            .replace("<q>", yaml_quote())
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+    }
+
+    #[test]
+    fn can_render_in_yaml_mode_with_one_game_in_restore_mode() {
+        let mut reporter = Reporter::yaml();
 
         reporter.add_game(
             "foo",
@@ -1035,6 +6737,10 @@ Overall:
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: StrictPath::new(format!("{}/backup/file2", drive())),
@@ -1045,6 +6751,10 @@ Overall:
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                 },
                 found_registry_keys: hashset! {},
@@ -1053,49 +6763,56 @@ Overall:
             &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
         );
         assert_eq!(
             r#"
-  {
-  "overall": {
-    "totalGames": 1,
-    "totalBytes": 150,
-    "processedGames": 1,
-    "processedBytes": 150,
-    "changedGames": {
-      "new": 0,
-      "different": 0,
-      "same": 1
-    }
-  },
-  "games": {
-    "foo": {
-      "decision": "Processed",
-      "change": "Same",
-      "files": {
-        "<drive>/original/file1": {
-          "change": "Unknown",
-          "bytes": 100
-        },
-        "<drive>/original/file2": {
-          "change": "Unknown",
-          "bytes": 50
-        }
-      },
-      "registry": {}
-    }
-  }
-}
+---
+apiVersion: 2
+overall:
+  totalGames: 1
+  totalBytes: 150
+  processedGames: 1
+  processedBytes: 150
+  changedGames:
+    new: 0
+    different: 0
+    removed: 0
+    same: 1
+  ignoredFiles: 0
+  ignoredBytes: 0
+  ignoredRegistryKeys: 0
+games:
+  foo:
+    decision: Processed
+    change: Same
+    totalBytes: 150
+    processedBytes: 150
+    failedBytes: 0
+    ignoredBytes: 0
+    fileCount: 2
+    registryCount: 0
+    files:
+      <q><drive>/original/file1<q>:
+        change: Unknown
+        bytes: 100
+        hash: "1"
+      <q><drive>/original/file2<q>:
+        change: Unknown
+        bytes: 50
+        hash: "2"
+    registry: {}
             "#
             .trim()
+            .replace("<q>", yaml_quote())
             .replace("<drive>", &drive()),
             reporter.render(&StrictPath::new(s("/dev/null")))
         );
     }
 
     #[test]
-    fn can_render_in_json_mode_with_duplicated_entries() {
-        let mut reporter = Reporter::json();
+    fn can_render_in_yaml_mode_with_duplicated_entries() {
+        let mut reporter = Reporter::yaml();
 
         let mut duplicate_detector = DuplicateDetector::default();
         for name in &["foo", "bar"] {
@@ -1129,45 +6846,82 @@ Overall:
             &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &duplicate_detector,
+            None,
         );
         assert_eq!(
             r#"
-{
-  "overall": {
-    "totalGames": 1,
-    "totalBytes": 100,
-    "processedGames": 1,
-    "processedBytes": 100,
-    "changedGames": {
-      "new": 0,
-      "different": 0,
-      "same": 1
+---
+apiVersion: 2
+overall:
+  totalGames: 1
+  totalBytes: 100
+  processedGames: 1
+  processedBytes: 100
+  changedGames:
+    new: 0
+    different: 0
+    removed: 0
+    same: 1
+  ignoredFiles: 0
+  ignoredBytes: 0
+  ignoredRegistryKeys: 0
+games:
+  foo:
+    decision: Processed
+    change: Same
+    totalBytes: 100
+    processedBytes: 100
+    failedBytes: 0
+    ignoredBytes: 0
+    fileCount: 1
+    registryCount: 1
+    files:
+      <q><drive>/file1<q>:
+        change: Unknown
+        bytes: 100
+        hash: "2"
+        duplicatedBy:
+          - bar
+    registry:
+      HKEY_CURRENT_USER/Key1:
+        change: Unknown
+        duplicatedBy:
+          - bar
+            "#
+            .trim()
+            .replace("<q>", yaml_quote())
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
     }
-  },
-  "games": {
-    "foo": {
-      "decision": "Processed",
-      "change": "Same",
-      "files": {
-        "<drive>/file1": {
-          "change": "Unknown",
-          "bytes": 100,
-          "duplicatedBy": [
-            "bar"
-          ]
-        }
-      },
-      "registry": {
-        "HKEY_CURRENT_USER/Key1": {
-          "change": "Unknown",
-          "duplicatedBy": [
-            "bar"
-          ]
+
+    #[test]
+    fn can_render_in_json_lines_mode() {
+        let mut reporter = Reporter::json_lines();
+
+        for name in ["foo", "bar"] {
+            reporter.add_game(
+                name,
+                &ScanInfo {
+                    game_name: s(name),
+                    found_files: hashset! {
+                        ScannedFile::new(format!("/{name}"), 100, "1").change_as(ScanChange::New),
+                    },
+                    ..Default::default()
+                },
+                &BackupInfo::default(),
+                &OperationStepDecision::Processed,
+                &DuplicateDetector::default(),
+                None,
+            );
+            reporter.add_forced_game(name);
         }
-      }
-    }
-  }
-}
+
+        // The first game was already printed when the second one was added.
+        assert_eq!(
+            r#"
+{"game":"bar","decision":"Processed","change":"New","totalBytes":100,"processedBytes":100,"failedBytes":0,"ignoredBytes":0,"fileCount":1,"registryCount":0,"files":{"<drive>/bar":{"change":"New","bytes":100,"hash":"1"}},"registry":{},"forced":true}
+{"apiVersion":2,"overall":{"totalGames":2,"totalBytes":200,"processedGames":2,"processedBytes":200,"changedGames":{"new":2,"different":0,"removed":0,"same":0},"ignoredFiles":0,"ignoredBytes":0,"ignoredRegistryKeys":0}}
             "#
             .trim()
             .replace("<drive>", &drive()),
@@ -1176,73 +6930,242 @@ Overall:
     }
 
     #[test]
-    fn can_render_in_json_mode_with_different_file_changes() {
-        let mut reporter = Reporter::json();
+    fn can_render_failure_in_json_lines_mode() {
+        let mut reporter = Reporter::json_lines();
 
         reporter.add_game(
             "foo",
             &ScanInfo {
                 game_name: s("foo"),
                 found_files: hashset! {
-                    ScannedFile::new("/new", 1, "1").change_as(ScanChange::New),
-                    ScannedFile::new("/different", 1, "2").change_as(ScanChange::Different),
-                    ScannedFile::new("/same", 1, "2").change_as(ScanChange::Same),
-                    ScannedFile::new("/unknown", 1, "2").change_as(ScanChange::Unknown),
+                    ScannedFile::new("/file1", 100, "1").change_as(ScanChange::Same),
                 },
-                found_registry_keys: hashset! {},
                 ..Default::default()
             },
-            &BackupInfo {
-                failed_files: hashset! {},
-                failed_registry: hashset! {},
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.trip_unknown_games(vec![UnknownGame {
+            name: s("bar"),
+            suggestions: vec![s("baz")],
+        }]);
+        assert_eq!(
+            Some(
+                r#"
+{"game":"foo","decision":"Processed","change":"Same","totalBytes":100,"processedBytes":100,"failedBytes":0,"ignoredBytes":0,"fileCount":1,"registryCount":0,"files":{"<drive>/file1":{"change":"Same","bytes":100,"hash":"1"}},"registry":{}}
+{"apiVersion":2,"errors":{"unknownGames":[{"name":"bar","suggestions":["baz"]}]},"overall":{"totalGames":1,"totalBytes":100,"processedGames":1,"processedBytes":100,"changedGames":{"new":0,"different":0,"removed":0,"same":1},"ignoredFiles":0,"ignoredBytes":0,"ignoredRegistryKeys":0}}
+                "#
+                .trim()
+                .replace("<drive>", &drive())
+            ),
+            reporter.render_failure()
+        );
+    }
+
+    fn add_one_game(reporter: &mut Reporter, name: &str) {
+        reporter.add_game(
+            name,
+            &ScanInfo {
+                game_name: s(name),
+                found_files: hashset! {
+                    ScannedFile::new(format!("/{name}"), 100, "1").change_as(ScanChange::New),
+                },
+                ..Default::default()
             },
+            &BackupInfo::default(),
             &OperationStepDecision::Processed,
             &DuplicateDetector::default(),
+            None,
+        );
+    }
+
+    #[test]
+    fn can_render_in_csv_mode() {
+        let mut reporter = Reporter::csv();
+
+        reporter.add_game(
+            "foo, \"the game\"",
+            &ScanInfo {
+                game_name: s("foo, \"the game\""),
+                found_files: hashset! {
+                    ScannedFile::new("/saves, \"old\"/file1", 100, "1").change_as(ScanChange::New),
+                    ScannedFile::new("/file2", 50, "2").change_as(ScanChange::Same),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1").with_value_same("Value1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file2", 50, "2").change_as(ScanChange::Same) => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(), None,
         );
+
         assert_eq!(
             r#"
+game,type,path,value,bytes,change,ignored,failed,duplicatedBy,redirectedPath,originalPath
+"foo, ""the game""",file,<drive>/file2,,50,Same,false,true,,,
+"foo, ""the game""",file,"<drive>/saves, ""old""/file1",,100,New,false,false,,,
+"foo, ""the game""",registry,HKEY_CURRENT_USER/Key1,,,Unknown,false,false,,,
+"foo, ""the game""",registry-value,HKEY_CURRENT_USER/Key1,Value1,,Same,false,,,,
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            reporter.render(&StrictPath::new(s("/dev/null")))
+        );
+        assert_eq!(
+            Some(
+                r#"
 {
+  "apiVersion": 2,
+  "errors": {
+    "someGamesFailed": true
+  },
   "overall": {
     "totalGames": 1,
-    "totalBytes": 4,
+    "totalBytes": 150,
     "processedGames": 1,
-    "processedBytes": 4,
+    "processedBytes": 100,
     "changedGames": {
       "new": 0,
       "different": 1,
+      "removed": 0,
       "same": 0
-    }
-  },
-  "games": {
-    "foo": {
-      "decision": "Processed",
-      "change": "Different",
-      "files": {
-        "<drive>/different": {
-          "change": "Different",
-          "bytes": 1
-        },
-        "<drive>/new": {
-          "change": "New",
-          "bytes": 1
-        },
-        "<drive>/same": {
-          "change": "Same",
-          "bytes": 1
-        },
-        "<drive>/unknown": {
-          "change": "Unknown",
-          "bytes": 1
-        }
-      },
-      "registry": {}
-    }
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
   }
 }
+                "#
+                .trim()
+                .to_string()
+            ),
+            reporter.render_csv_summary()
+        );
+    }
+
+    #[test]
+    fn can_render_in_markdown_mode() {
+        let mut reporter = Reporter::markdown();
+
+        let mut duplicate_detector = DuplicateDetector::default();
+        for name in &["foo", "bar"] {
+            duplicate_detector.add_game(
+                &ScanInfo {
+                    game_name: s(name),
+                    found_files: hashset! {
+                        ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::New),
+                    },
+                    ..Default::default()
+                },
+                true,
+            );
+        }
+
+        reporter.add_game(
+            "foo",
+            &ScanInfo {
+                game_name: s("foo"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 102_400, "1").change_as(ScanChange::New),
+                    ScannedFile::new("/file|2", 51_200, "2").change_as(ScanChange::Same),
+                },
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Key1").with_value_same("Value1"),
+                },
+                ..Default::default()
+            },
+            &BackupInfo {
+                failed_files: hashmap! {
+                    ScannedFile::new("/file|2", 51_200, "2").change_as(ScanChange::Same) => s("Permission denied (os error 13)"),
+                },
+                ..Default::default()
+            },
+            &OperationStepDecision::Processed,
+            &duplicate_detector, None,
+        );
+        assert_eq!(
+            r#"
+## foo
+
+| Path | Size | Change | Status |
+| --- | --- | --- | --- |
+| `<drive>/file1` | 100.00 KiB | New | [DUPLICATED] `bar` |
+| `<drive>/file\|2` | 50.00 KiB | Unchanged | [FAILED: Permission denied (os error 13)] |
+
+<details>
+<summary>Registry</summary>
+
+| Path | Value | Change | Status |
+| --- | --- | --- | --- |
+| `HKEY_CURRENT_USER/Key1` |  |  |  |
+| `HKEY_CURRENT_USER/Key1` | `Value1` | Unchanged |  |
+
+</details>
+
+## Overall
+
+| Games | Size | Location |
+| --- | --- | --- |
+| 1 [Δ1] | 100.00 KiB / 150.00 KiB | `<drive>/dev/null` |
             "#
             .trim()
             .replace("<drive>", &drive()),
             reporter.render(&StrictPath::new(s("/dev/null")))
         );
     }
+
+    #[test]
+    fn can_write_report_to_output_file() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-report-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+
+        for (kind, mut reporter) in [
+            ("standard", Reporter::standard()),
+            ("json", Reporter::json()),
+            ("json-lines", Reporter::json_lines()),
+            ("csv", Reporter::csv()),
+            ("markdown", Reporter::markdown()),
+        ] {
+            let file = StrictPath::from(dir.join(kind).join("report.txt"));
+            reporter.set_output_file(Some(file.clone()));
+            add_one_game(&mut reporter, "foo");
+            add_one_game(&mut reporter, "bar");
+
+            let rendered = reporter.render(&StrictPath::new(s("/dev/null")));
+            let expected = match &reporter {
+                // The first game was already written when the second one was added.
+                Reporter::JsonLines { output, streamed } => {
+                    format!("{}\n{rendered}\n", output.game_lines(0).take(*streamed).join("\n"))
+                }
+                _ => format!("{rendered}\n"),
+            };
+
+            assert_eq!(Ok(()), reporter.print(&StrictPath::new(s("/dev/null"))), "{kind}");
+            assert_eq!(Some(expected), file.read(), "{kind}");
+        }
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn falls_back_to_stdout_when_output_file_cannot_be_written() {
+        let file = StrictPath::new(format!("{}/README.md/report.json", repo()));
+        let mut reporter = Reporter::json();
+        reporter.set_output_file(Some(file.clone()));
+        add_one_game(&mut reporter, "foo");
+
+        assert!(matches!(
+            reporter.print(&StrictPath::new(s("/dev/null"))),
+            Err(Error::UnableToWriteReport { path, .. }) if path == file
+        ));
+    }
 }
@@ -0,0 +1,103 @@
+use std::io::IsTerminal;
+
+/// Whether the standard reporter should highlight its output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorChoice {
+    /// Only when printing to a terminal, and unless `NO_COLOR` is set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// The report is only printed to the terminal when there's no output file.
+    pub fn palette(&self, to_file: bool) -> Palette {
+        Palette {
+            enabled: self.resolve(to_file) == Self::Always,
+        }
+    }
+
+    /// Settle `Auto` for the current terminal,
+    /// so that the choice still holds when the report is rendered by another process (e.g., the daemon).
+    pub fn resolve(&self, to_file: bool) -> Self {
+        match self {
+            Self::Auto => {
+                if !to_file && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal() {
+                    Self::Always
+                } else {
+                    Self::Never
+                }
+            }
+            Self::Always | Self::Never => *self,
+        }
+    }
+}
+
+/// Highlights parts of the standard report with ANSI escape codes.
+/// When disabled, text passes through unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    fn paint(&self, code: &str, text: String) -> String {
+        if self.enabled {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text
+        }
+    }
+
+    pub fn added(&self, text: String) -> String {
+        self.paint("32", text)
+    }
+
+    pub fn changed(&self, text: String) -> String {
+        self.paint("33", text)
+    }
+
+    pub fn failed(&self, text: String) -> String {
+        self.paint("31", text)
+    }
+
+    pub fn ignored(&self, text: String) -> String {
+        self.paint("2", text)
+    }
+
+    pub fn duplicated(&self, text: String) -> String {
+        self.paint("36", text)
+    }
+
+    pub fn warning(&self, text: String) -> String {
+        self.paint("1;33", text)
+    }
+
+    /// Ignored entries are dimmed as a whole,
+    /// since the resets from any labels inside would end the dimming early.
+    pub fn plain(&self) -> Self {
+        Self { enabled: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn only_paints_when_enabled() {
+        assert_eq!("[+]", Palette { enabled: false }.added("[+]".to_string()));
+        assert_eq!("\x1b[32m[+]\x1b[0m", Palette { enabled: true }.added("[+]".to_string()));
+    }
+
+    #[test]
+    fn respects_explicit_choice_regardless_of_output() {
+        assert_eq!(Palette { enabled: true }, ColorChoice::Always.palette(true));
+        assert_eq!(Palette { enabled: false }, ColorChoice::Never.palette(false));
+        assert_eq!(Palette { enabled: false }, ColorChoice::Auto.palette(true));
+    }
+}
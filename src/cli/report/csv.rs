@@ -0,0 +1,107 @@
+//! Flattens the report into one row per file and registry entry, for use in spreadsheets.
+//! Only the game entries fit in this shape, so the rest of the report is printed separately.
+
+use itertools::Itertools;
+
+use crate::{
+    cli::report::{ApiGame, ApiOperativeGame, JsonOutput},
+    scan::{csv_field, ScanChange},
+};
+
+const HEADER: &[&str] = &[
+    "game",
+    "type",
+    "path",
+    "value",
+    "bytes",
+    "change",
+    "ignored",
+    "failed",
+    "duplicatedBy",
+    "redirectedPath",
+    "originalPath",
+];
+
+pub fn render(output: &JsonOutput) -> String {
+    let mut lines = vec![row(HEADER.iter().copied())];
+
+    for (name, game) in &output.games {
+        let ApiGame::Operative(game) = game else {
+            continue;
+        };
+        let ApiOperativeGame { files, registry, .. } = game.as_ref();
+
+        for (path, file) in files {
+            lines.push(row([
+                name.as_str(),
+                "file",
+                path,
+                "",
+                &file.bytes.to_string(),
+                change(file.change),
+                flag(file.ignored),
+                flag(file.failed),
+                &file.duplicated_by.iter().sorted().join(";"),
+                file.redirected_path.as_deref().unwrap_or_default(),
+                file.original_path.as_deref().unwrap_or_default(),
+            ]));
+        }
+
+        for (path, key) in registry.iter().flatten() {
+            lines.push(row([
+                name.as_str(),
+                "registry",
+                path,
+                "",
+                "",
+                change(key.change),
+                flag(key.ignored),
+                flag(key.failed),
+                &key.duplicated_by.iter().sorted().join(";"),
+                "",
+                "",
+            ]));
+
+            for (value_name, value) in &key.values {
+                lines.push(row([
+                    name.as_str(),
+                    "registry-value",
+                    path,
+                    value_name,
+                    "",
+                    change(value.change),
+                    flag(value.ignored),
+                    "",
+                    &value.duplicated_by.iter().sorted().join(";"),
+                    "",
+                    "",
+                ]));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn row<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    fields.into_iter().map(csv_field).join(",")
+}
+
+/// Same as the `change` field in the JSON output.
+fn change(change: ScanChange) -> &'static str {
+    match change {
+        ScanChange::New => "New",
+        ScanChange::Different => "Different",
+        ScanChange::Removed => "Removed",
+        ScanChange::Same => "Same",
+        ScanChange::Unknown => "Unknown",
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
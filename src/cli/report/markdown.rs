@@ -0,0 +1,301 @@
+//! Renders the report as Markdown, for pasting into issue trackers and wikis.
+//! This shows the same data as the standard reporter, but laid out in tables.
+
+use itertools::Itertools;
+
+use crate::{
+    cli::report::{ApiBackupMode, ApiDeletion, ApiGame, ApiOperativeGame, JsonOutput, SkipReason},
+    lang::TRANSLATOR,
+    prelude::StrictPath,
+};
+
+pub fn render(output: &JsonOutput, path: &StrictPath) -> String {
+    let mut parts = vec![];
+
+    for (name, game) in &output.games {
+        parts.push(format!("## {name}"));
+        parts.push("".to_string());
+        render_game(game, &mut parts);
+    }
+
+    if let Some(status) = &output.overall {
+        parts.push(format!("## {}", TRANSLATOR.overall()));
+        parts.push("".to_string());
+        parts.push(row(&[
+            TRANSLATOR.total_games(),
+            TRANSLATOR.file_size(),
+            TRANSLATOR.file_location(),
+        ]));
+        parts.push(divider(3));
+        parts.push(row(&[
+            TRANSLATOR.cli_summary_games(status),
+            TRANSLATOR.cli_summary_size(status),
+            code(&path.render()),
+        ]));
+        parts.push("".to_string());
+
+        if let Some(ApiBackupMode { mode, skipped_games }) = &output.backup_mode {
+            parts.push(TRANSLATOR.cli_summary_backup_mode(mode, *skipped_games));
+            parts.push("".to_string());
+        }
+        for message in output.errors.as_ref().map(|x| x.messages()).unwrap_or_default() {
+            parts.push(message);
+            parts.push("".to_string());
+        }
+    }
+
+    if let Some(ApiDeletion {
+        preview,
+        backups,
+        bytes,
+    }) = &output.deleted
+    {
+        parts.push(TRANSLATOR.cli_deleted_backups(*preview, *backups, *bytes));
+        parts.push("".to_string());
+    }
+
+    if let Some(cloud) = &output.cloud {
+        parts.push(format!("## {}", TRANSLATOR.cli_cloud_header(cloud.preview)));
+        parts.push("".to_string());
+        if cloud.changes.is_empty() {
+            parts.push(TRANSLATOR.no_cloud_changes());
+        }
+        for (path, change) in &cloud.changes {
+            parts.push(format!(
+                "- [{}] {}{}",
+                change.change.symbol(),
+                code(path),
+                super::render_resumed(change.resumed_at)
+            ));
+        }
+        parts.push("".to_string());
+    }
+
+    parts.join("\n").trim_end().to_string()
+}
+
+fn render_game(game: &ApiGame, parts: &mut Vec<String>) {
+    match game {
+        ApiGame::Operative(game) => {
+            let ApiOperativeGame {
+                files,
+                registry,
+                registry_skipped,
+                merged_titles,
+                unresolved_base_dirs,
+                io_timeouts,
+                skip_reason,
+                promoted_because,
+                comment,
+                locked,
+                safety_snapshot,
+                ..
+            } = game.as_ref();
+
+            if *skip_reason == Some(SkipReason::NoRecentActivity) {
+                parts.push(TRANSLATOR.cli_game_no_recent_activity());
+                parts.push("".to_string());
+                return;
+            }
+
+            if !files.is_empty() {
+                parts.push(row(&[
+                    TRANSLATOR.cli_markdown_path(),
+                    TRANSLATOR.file_size(),
+                    TRANSLATOR.cli_markdown_change(),
+                    TRANSLATOR.cli_markdown_status(),
+                ]));
+                parts.push(divider(4));
+                for (path, file) in files {
+                    parts.push(row(&[
+                        code(path),
+                        TRANSLATOR.adjusted_size(file.bytes),
+                        TRANSLATOR.cli_markdown_scan_change(file.change),
+                        status(
+                            file.failed,
+                            file.error.as_deref(),
+                            file.ignored,
+                            file.duplicated_by.iter(),
+                        ),
+                    ]));
+                }
+                parts.push("".to_string());
+            }
+
+            let registry: Vec<_> = registry.iter().flatten().collect();
+            if !registry.is_empty() {
+                parts.push("<details>".to_string());
+                parts.push(format!("<summary>{}</summary>", TRANSLATOR.cli_markdown_registry()));
+                parts.push("".to_string());
+                parts.push(row(&[
+                    TRANSLATOR.cli_markdown_path(),
+                    TRANSLATOR.cli_markdown_value(),
+                    TRANSLATOR.cli_markdown_change(),
+                    TRANSLATOR.cli_markdown_status(),
+                ]));
+                parts.push(divider(4));
+                for (path, entry) in registry {
+                    parts.push(row(&[
+                        code(path),
+                        "".to_string(),
+                        TRANSLATOR.cli_markdown_scan_change(entry.change),
+                        status(
+                            entry.failed,
+                            entry.error.as_deref(),
+                            entry.ignored,
+                            entry.duplicated_by.iter(),
+                        ),
+                    ]));
+                    for (value_name, value) in &entry.values {
+                        parts.push(row(&[
+                            code(path),
+                            code(value_name),
+                            TRANSLATOR.cli_markdown_scan_change(value.change),
+                            status(false, None, value.ignored, value.duplicated_by.iter()),
+                        ]));
+                    }
+                }
+                parts.push("".to_string());
+                parts.push("</details>".to_string());
+                parts.push("".to_string());
+            }
+
+            let mut notes = vec![];
+            if *registry_skipped {
+                notes.push(TRANSLATOR.cli_game_registry_skipped());
+            }
+            for dir in unresolved_base_dirs {
+                notes.push(TRANSLATOR.cli_game_unresolved_base_dir(dir));
+            }
+            for path in io_timeouts {
+                notes.push(TRANSLATOR.cli_game_io_timeout(path));
+            }
+            if !merged_titles.is_empty() {
+                notes.push(TRANSLATOR.cli_game_merged_titles(merged_titles));
+            }
+            if let Some(reason) = promoted_because {
+                notes.push(TRANSLATOR.cli_game_promoted_to_full(reason));
+            }
+            if let Some(comment) = comment {
+                notes.push(TRANSLATOR.cli_game_backup_comment(comment));
+            }
+            if *locked {
+                notes.push(TRANSLATOR.cli_game_backup_locked());
+            }
+            notes.extend(safety_snapshot.as_ref().and_then(|x| x.describe()));
+            render_notes(notes, parts);
+        }
+        ApiGame::Unresolved {
+            unresolved_base_dirs,
+            io_timeouts,
+        } => {
+            let notes = unresolved_base_dirs
+                .iter()
+                .map(|dir| TRANSLATOR.cli_game_unresolved_base_dir(dir))
+                .chain(io_timeouts.iter().map(|path| TRANSLATOR.cli_game_io_timeout(path)))
+                .collect();
+            render_notes(notes, parts);
+        }
+        ApiGame::Stored { backups } => {
+            let notes = backups.iter().map(|backup| backup.describe()).collect();
+            render_notes(notes, parts);
+        }
+        ApiGame::Found {
+            score: Some(score),
+            matched_via: Some(matched_via),
+            matched_name: Some(matched_name),
+            ..
+        } => {
+            render_notes(vec![TRANSLATOR.fuzzy_match(*score, *matched_via, matched_name)], parts);
+        }
+        ApiGame::Found {
+            matched_id: Some(matched_id),
+            ..
+        } => {
+            render_notes(vec![TRANSLATOR.store_id(matched_id)], parts);
+        }
+        ApiGame::Found { .. } => {}
+    }
+}
+
+fn render_notes(notes: Vec<String>, parts: &mut Vec<String>) {
+    if notes.is_empty() {
+        return;
+    }
+    for note in notes {
+        parts.push(format!("- {note}"));
+    }
+    parts.push("".to_string());
+}
+
+/// Badges like the standard reporter's, with the other games spelled out for duplicates.
+fn status<'a>(
+    failed: bool,
+    error: Option<&str>,
+    ignored: bool,
+    duplicated_by: impl Iterator<Item = &'a String>,
+) -> String {
+    let mut badges = vec![];
+    if failed {
+        badges.push(match error {
+            Some(reason) => TRANSLATOR.label_failed_because(reason),
+            None => TRANSLATOR.label_failed(),
+        });
+    }
+    if ignored {
+        badges.push(TRANSLATOR.label_ignored());
+    }
+    let duplicated_by: Vec<_> = duplicated_by.sorted().map(|x| code(x)).collect();
+    if !duplicated_by.is_empty() {
+        badges.push(format!(
+            "{} {}",
+            TRANSLATOR.label_duplicated(),
+            duplicated_by.join(", ")
+        ));
+    }
+    badges.join(" ")
+}
+
+fn row(cells: &[String]) -> String {
+    format!("| {} |", cells.iter().map(|x| cell(x)).join(" | "))
+}
+
+fn divider(columns: usize) -> String {
+    format!("|{}", " --- |".repeat(columns))
+}
+
+/// Pipes would end the cell early, even inside of a code span.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// The fence needs to be longer than any run of backticks in the text.
+fn code(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(|x| x.len()).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    if longest > 0 {
+        format!("{fence} {text} {fence}")
+    } else {
+        format!("{fence}{text}{fence}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn can_escape_table_cells() {
+        assert_eq!("a \\| b", cell("a | b"));
+        assert_eq!("a<br>b", cell("a\nb"));
+    }
+
+    #[test]
+    fn can_format_code_spans() {
+        assert_eq!("`plain`", code("plain"));
+        assert_eq!("`` a`b ``", code("a`b"));
+        assert_eq!("``` a``b ```", code("a``b"));
+    }
+}
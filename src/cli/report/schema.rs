@@ -0,0 +1,602 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    cli::report::{concern, ApiBackup, ApiCloudChange, ApiErrors, ApiFile, ApiGame, ApiRegistry, JsonOutput},
+    resource::manifest::Os,
+    scan::{OperationStatus, OperationStepDecision, ScanChange},
+};
+
+/// How structured output is serialized.
+/// Every format renders the same schema, so they can't drift apart,
+/// except that CSV only has room for the files and registry entries
+/// and Markdown is meant for people rather than tools.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiFormat {
+    #[default]
+    Json,
+    Yaml,
+    /// One JSON object per line, printed as each game is reported.
+    JsonLines,
+    /// One row per file and registry entry.
+    Csv,
+    /// Tables for pasting into issue trackers and wikis.
+    Markdown,
+}
+
+impl ApiFormat {
+    pub fn serialize(&self, value: &impl serde::Serialize) -> String {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value).unwrap(),
+            Self::Yaml => serde_yaml::to_string(value).unwrap().trim_end().to_string(),
+            // Anything that doesn't fit in the CSV rows is printed as JSON instead.
+            Self::JsonLines | Self::Csv => serde_json::to_string(value).unwrap(),
+            // The report itself is rendered directly, so this only applies to other values.
+            Self::Markdown => serde_json::to_string_pretty(value).unwrap(),
+        }
+    }
+}
+
+/// A major version of the JSON report schema.
+/// When the schema changes incompatibly, add a new version here and keep an adapter for the old one,
+/// so that tools pinned to the old schema keep working.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    /// The schema from Ludusavi v0.22.0.
+    V1,
+    #[default]
+    V2,
+}
+
+impl ApiVersion {
+    pub const LATEST: Self = Self::V2;
+    pub const ALL: &'static [Self] = &[Self::V1, Self::V2];
+
+    pub fn number(&self) -> u32 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+        }
+    }
+
+    /// Whether the schema can tell the caller that a game's file list is incomplete (`filesTruncated`).
+    pub fn can_truncate_files(&self) -> bool {
+        *self >= Self::V2
+    }
+}
+
+/// Reports include the version as a plain number.
+impl serde::Serialize for ApiVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.number())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ApiVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let number = u32::deserialize(deserializer)?;
+        number.to_string().parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl std::str::FromStr for ApiVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let supported = || {
+            Self::ALL
+                .iter()
+                .map(|x| x.number().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let number: u32 = s
+            .trim()
+            .parse()
+            .map_err(|_| format!("expected a number (supported: {}): {s}", supported()))?;
+
+        Self::ALL
+            .iter()
+            .find(|x| x.number() == number)
+            .copied()
+            .ok_or_else(|| format!("unsupported API version (supported: {}): {s}", supported()))
+    }
+}
+
+pub fn render(output: &JsonOutput, format: ApiFormat) -> String {
+    match output.api_version {
+        ApiVersion::V1 => format.serialize(&v1::JsonOutput::new(output)),
+        ApiVersion::V2 => format.serialize(output),
+    }
+}
+
+/// The schema from Ludusavi v0.22.0.
+///
+/// * Games and files are sorted by name rather than listed in the order they were found.
+/// * Game-level details added later (merged titles, skip reasons, backup kinds, etc.) are dropped,
+///   and games that were only reported because their save locations couldn't be resolved are omitted.
+/// * `registry` is always present, even when registry access is disabled for the game.
+/// * File lists are never truncated, since there's no way to indicate that.
+/// * Errors only include the kinds that existed at the time.
+/// * `cloud` changes are listed without counts, like the old `cloud upload/download` output.
+/// * `changedGames` has no `removed` count, so games whose saves were all removed count as the same.
+mod v1 {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    pub struct JsonOutput<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        errors: Option<ApiErrors<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        overall: Option<ApiOperationStatus>,
+        games: BTreeMap<&'a str, ApiGame<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cloud: Option<BTreeMap<&'a str, ApiCloudChange<'a>>>,
+    }
+
+    impl<'a> JsonOutput<'a> {
+        pub fn new(output: &'a super::JsonOutput) -> Self {
+            Self {
+                errors: output.errors.as_ref().map(ApiErrors::new).filter(|x| !x.is_empty()),
+                overall: output.overall.as_ref().map(ApiOperationStatus::new),
+                games: output
+                    .games
+                    .iter()
+                    .filter_map(|(name, game)| Some((name.as_str(), ApiGame::new(game)?)))
+                    .collect(),
+                cloud: output.cloud.as_ref().map(|x| {
+                    x.changes
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), ApiCloudChange::new(v)))
+                        .collect()
+                }),
+            }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ApiOperationStatus {
+        total_games: usize,
+        total_bytes: u64,
+        processed_games: usize,
+        processed_bytes: u64,
+        changed_games: ApiChangedGames,
+    }
+
+    #[derive(serde::Serialize)]
+    struct ApiChangedGames {
+        new: usize,
+        different: usize,
+        same: usize,
+    }
+
+    impl ApiOperationStatus {
+        fn new(status: &OperationStatus) -> Self {
+            let changed = &status.changed_games;
+            Self {
+                total_games: status.total_games,
+                total_bytes: status.total_bytes,
+                processed_games: status.processed_games,
+                processed_bytes: status.processed_bytes,
+                changed_games: ApiChangedGames {
+                    new: changed.new,
+                    different: changed.different,
+                    same: changed.same + changed.removed,
+                },
+            }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct ApiCloudChange<'a> {
+        change: &'a ScanChange,
+    }
+
+    impl<'a> ApiCloudChange<'a> {
+        fn new(change: &'a super::ApiCloudChange) -> Self {
+            Self { change: &change.change }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ApiErrors<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        some_games_failed: Option<bool>,
+        /// Version 1 only listed the names, without suggestions.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unknown_games: Option<Vec<&'a str>>,
+        /// Version 1 didn't list the conflicting paths.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cloud_conflict: Option<ApiCloudConflict>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cloud_sync_failed: Option<&'a concern::CloudSyncFailed>,
+    }
+
+    impl<'a> ApiErrors<'a> {
+        fn new(errors: &'a super::ApiErrors) -> Self {
+            Self {
+                some_games_failed: errors.some_games_failed,
+                unknown_games: errors
+                    .unknown_games
+                    .as_ref()
+                    .map(|games| games.iter().map(|x| x.name.as_str()).collect()),
+                cloud_conflict: errors.cloud_conflict.as_ref().map(|_| ApiCloudConflict {}),
+                cloud_sync_failed: errors.cloud_sync_failed.as_ref(),
+            }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.some_games_failed.is_none()
+                && self.unknown_games.is_none()
+                && self.cloud_conflict.is_none()
+                && self.cloud_sync_failed.is_none()
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct ApiCloudConflict {}
+
+    #[derive(serde::Serialize)]
+    #[serde(untagged)]
+    enum ApiGame<'a> {
+        Operative {
+            decision: OperationStepDecision,
+            change: ScanChange,
+            files: BTreeMap<&'a str, ApiFile<'a>>,
+            registry: BTreeMap<&'a str, &'a ApiRegistry>,
+        },
+        Stored {
+            backups: Vec<ApiBackup<'a>>,
+        },
+        Found {},
+    }
+
+    impl<'a> ApiGame<'a> {
+        fn new(game: &'a super::ApiGame) -> Option<Self> {
+            match game {
+                super::ApiGame::Operative(game) => Some(Self::Operative {
+                    decision: game.decision.clone(),
+                    change: game.change,
+                    files: game.files.iter().map(|(k, v)| (k.as_str(), ApiFile::new(v))).collect(),
+                    registry: game.registry.iter().flatten().map(|(k, v)| (k.as_str(), v)).collect(),
+                }),
+                super::ApiGame::Unresolved { .. } => None,
+                super::ApiGame::Stored { backups } => Some(Self::Stored {
+                    backups: backups.iter().map(ApiBackup::new).collect(),
+                }),
+                super::ApiGame::Found { .. } => Some(Self::Found {}),
+            }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct ApiFile<'a> {
+        #[serde(skip_serializing_if = "crate::serialization::is_false")]
+        failed: bool,
+        #[serde(skip_serializing_if = "crate::serialization::is_false")]
+        ignored: bool,
+        change: ScanChange,
+        bytes: u64,
+        #[serde(rename = "originalPath", skip_serializing_if = "Option::is_none")]
+        original_path: Option<&'a str>,
+        #[serde(rename = "redirectedPath", skip_serializing_if = "Option::is_none")]
+        redirected_path: Option<&'a str>,
+        #[serde(rename = "duplicatedBy", skip_serializing_if = "BTreeSet::is_empty")]
+        duplicated_by: BTreeSet<&'a str>,
+    }
+
+    impl<'a> ApiFile<'a> {
+        fn new(file: &'a super::ApiFile) -> Self {
+            Self {
+                failed: file.failed,
+                ignored: file.ignored,
+                change: file.change,
+                bytes: file.bytes,
+                original_path: file.original_path.as_deref(),
+                redirected_path: file.redirected_path.as_deref(),
+                duplicated_by: file.duplicated_by.iter().map(|x| x.as_str()).collect(),
+            }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct ApiBackup<'a> {
+        name: &'a str,
+        when: chrono::DateTime<chrono::Utc>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        os: Option<Os>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<&'a str>,
+        locked: bool,
+    }
+
+    impl<'a> ApiBackup<'a> {
+        fn new(backup: &'a super::ApiBackup) -> Self {
+            Self {
+                name: &backup.name,
+                when: backup.when,
+                os: backup.os,
+                comment: backup.comment.as_deref(),
+                locked: backup.locked,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use maplit::{btreeset, hashset};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        cli::report::Reporter,
+        cloud::CloudChange,
+        prelude::StrictPath,
+        scan::{
+            layout::{Backup, FullBackup},
+            BackupInfo, DuplicateDetector, ScanInfo, ScannedFile, Trigger, TriggerSource,
+        },
+        testing::s,
+    };
+
+    fn drive() -> String {
+        if cfg!(target_os = "windows") {
+            StrictPath::new(s("foo")).render()[..2].to_string()
+        } else {
+            s("")
+        }
+    }
+
+    fn render(version: ApiVersion) -> String {
+        let mut reporter = Reporter::Json {
+            output: JsonOutput {
+                overall: Some(Default::default()),
+                api_version: version,
+                ..Default::default()
+            },
+        };
+        reporter.set_file_list_limit(Some(1));
+
+        reporter.add_game(
+            "zeta",
+            &ScanInfo {
+                game_name: s("zeta"),
+                found_files: hashset! {
+                    ScannedFile::new("/file1", 10, "1").change_as(ScanChange::New),
+                    ScannedFile::new("/file2", 20, "2").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_merged_titles("zeta", &[s("Zeta Classic")]);
+        reporter.add_inactive_game("alpha");
+        reporter.add_game(
+            "unresolved",
+            &ScanInfo {
+                game_name: s("unresolved"),
+                unresolved_base_dirs: btreeset! { StrictPath::new(s("/missing")) },
+                ..Default::default()
+            },
+            &BackupInfo::default(),
+            &OperationStepDecision::Processed,
+            &DuplicateDetector::default(),
+            None,
+        );
+        reporter.add_backups(
+            "stored",
+            &[(
+                Backup::Full(FullBackup {
+                    name: s("backup-1"),
+                    id: Some(1),
+                    when: chrono::Utc.with_ymd_and_hms(2000, 1, 2, 3, 4, 5).unwrap(),
+                    comment: Some(s("note")),
+                    app_version: Some(s("0.22.0")),
+                    trigger: Some(Trigger {
+                        source: TriggerSource::Cli,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                100,
+            )],
+        );
+        reporter.trip_cloud_conflict(&[CloudChange {
+            path: s("game/file.txt"),
+            change: ScanChange::Different,
+            resumed_at: None,
+        }]);
+
+        reporter.render(&StrictPath::new(s("/dev/null")))
+    }
+
+    #[test]
+    fn can_parse_supported_versions() {
+        for version in ApiVersion::ALL {
+            assert_eq!(Ok(*version), version.number().to_string().parse());
+        }
+        assert_eq!(
+            Err(s("unsupported API version (supported: 1, 2): 0")),
+            "0".parse::<ApiVersion>()
+        );
+        assert_eq!(ApiVersion::LATEST, ApiVersion::default());
+        assert_eq!(Some(&ApiVersion::LATEST), ApiVersion::ALL.last());
+    }
+
+    #[test]
+    fn can_round_trip_versions_through_serde() {
+        for version in ApiVersion::ALL {
+            let serialized = serde_json::to_string(version).unwrap();
+            assert_eq!(*version, serde_json::from_str::<ApiVersion>(&serialized).unwrap());
+        }
+        assert!(serde_json::from_str::<ApiVersion>("0").is_err());
+    }
+
+    #[test]
+    fn can_render_v1() {
+        assert_eq!(
+            r#"
+{
+  "errors": {
+    "cloudConflict": {}
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 30,
+    "processedGames": 1,
+    "processedBytes": 30,
+    "changedGames": {
+      "new": 0,
+      "different": 1,
+      "same": 0
+    }
+  },
+  "games": {
+    "alpha": {
+      "decision": "Processed",
+      "change": "Same",
+      "files": {},
+      "registry": {}
+    },
+    "stored": {
+      "backups": [
+        {
+          "name": "backup-1",
+          "when": "2000-01-02T03:04:05Z",
+          "comment": "note",
+          "locked": false
+        }
+      ]
+    },
+    "zeta": {
+      "decision": "Processed",
+      "change": "Different",
+      "files": {
+        "<drive>/file1": {
+          "change": "New",
+          "bytes": 10
+        },
+        "<drive>/file2": {
+          "change": "Same",
+          "bytes": 20
+        }
+      },
+      "registry": {}
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            render(ApiVersion::V1)
+        );
+    }
+
+    #[test]
+    fn can_render_v2() {
+        assert_eq!(
+            r#"
+{
+  "apiVersion": 2,
+  "errors": {
+    "cloudConflict": {
+      "changes": [
+        {
+          "path": "game/file.txt",
+          "change": "Different"
+        }
+      ]
+    },
+    "unresolvedBaseDirs": {
+      "games": 1
+    }
+  },
+  "overall": {
+    "totalGames": 1,
+    "totalBytes": 30,
+    "processedGames": 1,
+    "processedBytes": 30,
+    "changedGames": {
+      "new": 0,
+      "different": 1,
+      "removed": 0,
+      "same": 0
+    },
+    "ignoredFiles": 0,
+    "ignoredBytes": 0,
+    "ignoredRegistryKeys": 0
+  },
+  "games": {
+    "alpha": {
+      "decision": "Processed",
+      "change": "Same",
+      "totalBytes": 0,
+      "processedBytes": 0,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 0,
+      "registryCount": 0,
+      "files": {},
+      "skipReason": "noRecentActivity"
+    },
+    "stored": {
+      "backups": [
+        {
+          "name": "backup-1",
+          "id": 1,
+          "when": "2000-01-02T03:04:05Z",
+          "comment": "note",
+          "locked": false,
+          "appVersion": "0.22.0",
+          "trigger": {
+            "source": "cli"
+          },
+          "bytes": 100,
+          "files": 0
+        }
+      ]
+    },
+    "unresolved": {
+      "unresolvedBaseDirs": [
+        "<drive>/missing"
+      ]
+    },
+    "zeta": {
+      "decision": "Processed",
+      "change": "Different",
+      "totalBytes": 30,
+      "processedBytes": 30,
+      "failedBytes": 0,
+      "ignoredBytes": 0,
+      "fileCount": 2,
+      "registryCount": 0,
+      "files": {
+        "<drive>/file1": {
+          "change": "New",
+          "bytes": 10,
+          "hash": "1"
+        }
+      },
+      "filesTruncated": true,
+      "registry": {},
+      "mergedTitles": [
+        "Zeta Classic"
+      ]
+    }
+  }
+}
+            "#
+            .trim()
+            .replace("<drive>", &drive()),
+            render(ApiVersion::V2)
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! `ludusavi api`: a long-running process for launchers and other tools,
+//! which reads one JSON request per line from stdin and writes one JSON response per line to stdout.
+//! Unlike `ludusavi daemon`, this talks to a single client over its standard streams,
+//! and it keeps the config loaded as well as the manifest.
+
+use std::io::{BufRead, Write};
+
+use crate::{
+    cli::{
+        collect_store_ids,
+        operation::{BackupRequest, ReportRequest, RestoreRequest},
+        report::{ApiFormat, ReportOptions, Reporter},
+        resolve_store_ids,
+    },
+    daemon,
+    prelude::{Error, StrictPath, VERSION},
+    resource::{config::Config, manifest::Manifest},
+    scan::{layout::BackupLayout, TitleFinder},
+};
+
+const RELEASE_URL: &str = "https://api.github.com/repos/mtkennerly/ludusavi/releases/latest";
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum Request {
+    /// Look up a game like the `find` command.
+    FindTitle(FindTitleRequest),
+    /// Check whether there's a newer release of Ludusavi.
+    CheckAppUpdate {},
+    Backup(BackupRequest),
+    Restore(RestoreRequest),
+}
+
+/// These mirror the `find` command's options of the same name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct FindTitleRequest {
+    pub names: Vec<String>,
+    pub steam_id: Option<u32>,
+    pub gog_id: Option<u64>,
+    pub lutris_id: Option<String>,
+    pub normalized: bool,
+    pub backup: bool,
+    pub restore: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Response {
+    /// The same as `find --api`.
+    FindTitle(serde_json::Value),
+    CheckAppUpdate(AppUpdate),
+    /// The same as `backup --api`.
+    Backup(serde_json::Value),
+    /// The same as `restore --api`.
+    Restore(serde_json::Value),
+    Error(ResponseError),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdate {
+    pub current: String,
+    /// Only set if the latest release is newer than the current version.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseError {
+    pub code: ErrorCode,
+    /// A translated description, like the CLI would print.
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    /// The line wasn't a valid request.
+    InvalidRequest,
+    UnknownGames,
+    AmbiguousStoreId,
+    AppUpdateUnavailable,
+    /// Any other reason that the request couldn't be handled.
+    OperationFailed,
+}
+
+impl ResponseError {
+    fn new(error: &Error) -> Self {
+        let code = match error {
+            Error::CliUnrecognizedGames { .. } => ErrorCode::UnknownGames,
+            Error::CliAmbiguousStoreId { .. } => ErrorCode::AmbiguousStoreId,
+            Error::AppUpdateUnavailable { .. } => ErrorCode::AppUpdateUnavailable,
+            _ => ErrorCode::OperationFailed,
+        };
+        Self {
+            code,
+            message: crate::lang::TRANSLATOR.handle_error(error),
+        }
+    }
+}
+
+/// Parse `x.y.z`, with an optional `v` prefix and ignoring anything after a `-` or `+`.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|x| x.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+fn check_app_update() -> Result<AppUpdate, Error> {
+    #[derive(serde::Deserialize)]
+    struct Release {
+        tag_name: String,
+        html_url: String,
+    }
+
+    let unavailable = |e: &dyn std::fmt::Display| Error::AppUpdateUnavailable { why: e.to_string() };
+
+    let body = reqwest::blocking::Client::new()
+        .get(RELEASE_URL)
+        .header(reqwest::header::USER_AGENT, format!("ludusavi/{}", *VERSION))
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .and_then(|res| res.error_for_status())
+        .and_then(|res| res.text())
+        .map_err(|e| unavailable(&e))?;
+    let release: Release = serde_json::from_str(&body).map_err(|e| unavailable(&e))?;
+
+    let newer = is_newer(&release.tag_name, &VERSION);
+    Ok(AppUpdate {
+        current: VERSION.to_string(),
+        latest: newer.then(|| release.tag_name.trim_start_matches('v').to_string()),
+        url: newer.then_some(release.html_url),
+    })
+}
+
+/// Everything that's kept between requests.
+struct State {
+    manifest: Manifest,
+    config: Config,
+    /// Built for the first lookup and kept until a backup could have added new games.
+    title_finder: Option<(StrictPath, TitleFinder)>,
+}
+
+impl State {
+    fn title_finder(&mut self, path: &StrictPath) -> &TitleFinder {
+        if self.title_finder.as_ref().is_none_or(|(cached, _)| cached != path) {
+            let layout = BackupLayout::new(path.clone(), &self.config);
+            self.title_finder = Some((path.clone(), TitleFinder::new(&self.manifest, &layout)));
+        }
+        &self.title_finder.as_ref().unwrap().1
+    }
+
+    fn find_title(&mut self, request: FindTitleRequest) -> Result<serde_json::Value, Error> {
+        let restore_dir = self.config.restore.path.clone();
+        let store_ids = collect_store_ids(request.steam_id, request.gog_id, request.lutris_id);
+        let config = self.config.clone();
+        let title_finder = self.title_finder(&restore_dir);
+
+        let mut reporter = Reporter::json();
+        reporter.suppress_overall();
+
+        let (by_id, unknown_ids) = resolve_store_ids(title_finder, &store_ids, request.backup, request.restore)?;
+        if let Some((name, id)) = by_id.first() {
+            reporter.add_found_by_store_id(name, id);
+        } else {
+            let found = if request.names.is_empty() && !unknown_ids.is_empty() {
+                Default::default()
+            } else {
+                title_finder.find(
+                    &request.names,
+                    &config,
+                    &None,
+                    &None,
+                    request.normalized,
+                    request.backup,
+                    request.restore,
+                    false,
+                    false,
+                )
+            };
+            if found.is_empty() {
+                let mut unknown = title_finder.unknown_games(request.names, request.backup, request.restore);
+                unknown.extend(unknown_ids);
+                return Err(Error::CliUnrecognizedGames { games: unknown });
+            }
+            reporter.add_found_titles(&found);
+        }
+
+        render(reporter.render(&restore_dir))
+    }
+
+    fn handle(&mut self, request: Request) -> Result<Response, Error> {
+        match request {
+            Request::FindTitle(request) => Ok(Response::FindTitle(self.find_title(request)?)),
+            Request::CheckAppUpdate {} => Ok(Response::CheckAppUpdate(check_app_update()?)),
+            Request::Backup(request) => {
+                let request = BackupRequest {
+                    output: json_output(request.output),
+                    ..request
+                };
+                let response = daemon::back_up(&self.manifest, &self.config, &request)?;
+                if !request.preview {
+                    self.title_finder = None;
+                }
+                Ok(Response::Backup(render(response.output)?))
+            }
+            Request::Restore(request) => {
+                let request = RestoreRequest {
+                    output: json_output(request.output),
+                    ..request
+                };
+                let response = daemon::restore(&self.manifest, &self.config, &request)?;
+                Ok(Response::Restore(render(response.output)?))
+            }
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) -> Response {
+        let request = match serde_json::from_str::<Request>(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return Response::Error(ResponseError {
+                    code: ErrorCode::InvalidRequest,
+                    message: e.to_string(),
+                })
+            }
+        };
+        log::debug!("API request: {request:?}");
+
+        match self.handle(request) {
+            Ok(response) => response,
+            Err(e) => Response::Error(ResponseError::new(&e)),
+        }
+    }
+}
+
+/// The response is parsed back as JSON.
+fn json_output(output: ReportRequest) -> ReportRequest {
+    ReportRequest {
+        api: true,
+        report: ReportOptions {
+            api_format: ApiFormat::Json,
+            ..output.report
+        },
+        ..output
+    }
+}
+
+/// The reports are rendered as text, but each response should fit on one line.
+fn render(output: String) -> Result<serde_json::Value, Error> {
+    serde_json::from_str(&output).map_err(|e| Error::DaemonReported { message: e.to_string() })
+}
+
+/// Handle requests until stdin is closed.
+/// The manifest should already incorporate any extensions from the config.
+pub fn run(manifest: Manifest, config: Config) -> Result<(), Error> {
+    let mut state = State {
+        manifest,
+        config,
+        title_finder: None,
+    };
+    serve(&mut state, std::io::stdin().lock(), std::io::stdout().lock());
+    Ok(())
+}
+
+fn serve(state: &mut State, input: impl BufRead, mut output: impl Write) {
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Unable to read API request: {e:?}");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = state.handle_line(&line);
+        let body = serde_json::to_string(&response).unwrap();
+        if writeln!(output, "{body}").and_then(|_| output.flush()).is_err() {
+            // Nobody is listening anymore.
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        resource::ResourceFile,
+        testing::{repo, s},
+    };
+
+    fn state() -> State {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game1:
+              steam:
+                id: 101
+            game 2:
+              steam:
+                id: 102
+            "#,
+        )
+        .unwrap();
+        let mut config = Config::default();
+        config.restore.path = StrictPath::new(format!("{}/tests/backup", repo()));
+
+        State {
+            manifest,
+            config,
+            title_finder: None,
+        }
+    }
+
+    fn respond(state: &mut State, lines: &[&str]) -> Vec<serde_json::Value> {
+        let mut output = vec![];
+        serve(state, lines.join("\n").as_bytes(), &mut output);
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn can_parse_requests() {
+        assert_eq!(
+            Request::FindTitle(FindTitleRequest {
+                steam_id: Some(123),
+                ..Default::default()
+            }),
+            serde_json::from_str(r#"{"findTitle": {"steamId": 123}}"#).unwrap(),
+        );
+        assert_eq!(
+            Request::CheckAppUpdate {},
+            serde_json::from_str(r#"{"checkAppUpdate": {}}"#).unwrap(),
+        );
+        assert_eq!(
+            Request::Backup(BackupRequest {
+                games: vec![s("foo")],
+                preview: true,
+                ..Default::default()
+            }),
+            serde_json::from_str(r#"{"backup": {"games": ["foo"], "preview": true}}"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn can_compare_versions() {
+        assert!(is_newer("v0.26.0", "0.25.1"));
+        assert!(is_newer("v1.0.0", "0.99.99"));
+        assert!(!is_newer("v0.25.0", "0.25.0"));
+        assert!(!is_newer("v0.25.0-beta", "0.25.0"));
+        assert!(!is_newer("nightly", "0.25.0"));
+    }
+
+    #[test]
+    fn can_handle_several_requests_until_eof() {
+        let mut state = state();
+
+        let responses = respond(
+            &mut state,
+            &[
+                r#"{"findTitle": {"steamId": 101}}"#,
+                "",
+                r#"{"findTitle": {"names": ["game1"], "restore": true}}"#,
+                r#"{"findTitle": {"names": ["gaem1"]}}"#,
+                r#"{"unknown": {}}"#,
+            ],
+        );
+
+        assert_eq!(4, responses.len());
+        assert_eq!(
+            serde_json::json!({"matchedId": {"steam": 101}}),
+            responses[0]["findTitle"]["games"]["game1"],
+        );
+        assert_eq!(serde_json::json!({}), responses[1]["findTitle"]["games"]["game1"]);
+        assert_eq!("unknownGames", responses[2]["error"]["code"]);
+        assert_eq!("invalidRequest", responses[3]["error"]["code"]);
+        assert!(state.title_finder.is_some());
+    }
+}
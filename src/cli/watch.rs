@@ -0,0 +1,345 @@
+//! Back up games as soon as their saves change (`ludusavi watch`).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, mpsc},
+    time::{Duration, Instant},
+};
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::prelude::{register_sigint, unregister_sigint, Error, StrictPath};
+
+/// How often to check for interruptions and games that are ready to back up.
+const TICK: Duration = Duration::from_millis(500);
+
+/// Even without any events, look for save locations that appeared this often,
+/// in case the notification for their creation was missed.
+const RESYNC: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// How long a game's saves need to be left alone before backing it up.
+    pub debounce: Duration,
+    /// Check for changes at this interval instead of relying on file system notifications.
+    pub poll: Option<Duration>,
+    /// Changes in here are ignored, since they come from our own backups.
+    pub backup_dir: StrictPath,
+}
+
+/// Which games each save location belongs to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Locations(BTreeMap<PathBuf, BTreeSet<String>>);
+
+impl Locations {
+    fn new(games: &BTreeMap<String, BTreeSet<StrictPath>>) -> Self {
+        let mut locations = BTreeMap::<PathBuf, BTreeSet<String>>::new();
+        for (game, paths) in games {
+            for path in paths {
+                locations
+                    .entry(path.as_std_path_buf())
+                    .or_default()
+                    .insert(game.clone());
+            }
+        }
+        Self(locations)
+    }
+
+    /// The games whose saves may have been affected by a change to `path`.
+    /// Besides changes inside of a save location, this includes a folder being created or removed
+    /// above a save location, since that's how a game sets up its save folder on the first run.
+    fn games_for(&self, path: &Path, structural: bool) -> BTreeSet<String> {
+        self.0
+            .iter()
+            .filter(|(location, _)| path.starts_with(location) || (structural && location.starts_with(path)))
+            .flat_map(|(_, games)| games.iter().cloned())
+            .collect()
+    }
+
+    /// What to watch so that we notice changes to each location, even if it doesn't exist yet.
+    /// A missing location is covered by watching its closest existing parent folder,
+    /// and anything already covered by a recursive watch on a parent folder is left out.
+    fn targets(&self) -> BTreeMap<PathBuf, RecursiveMode> {
+        let mut targets = BTreeMap::new();
+        for location in self.0.keys() {
+            if location.exists() {
+                targets.insert(location.clone(), RecursiveMode::Recursive);
+            } else if let Some(parent) = location.ancestors().skip(1).find(|x| x.exists()) {
+                targets
+                    .entry(parent.to_path_buf())
+                    .or_insert(RecursiveMode::NonRecursive);
+            }
+        }
+
+        let recursive: Vec<_> = targets
+            .iter()
+            .filter(|(_, mode)| **mode == RecursiveMode::Recursive)
+            .map(|(path, _)| path.clone())
+            .collect();
+        targets.retain(|path, _| !recursive.iter().any(|x| path != x && path.starts_with(x)));
+        targets
+    }
+
+    /// The games with a location that exists now, but not in `before`.
+    fn appeared(&self, before: &BTreeSet<PathBuf>) -> BTreeSet<String> {
+        self.0
+            .iter()
+            .filter(|(location, _)| !before.contains(*location) && location.exists())
+            .flat_map(|(_, games)| games.iter().cloned())
+            .collect()
+    }
+
+    fn existing(&self) -> BTreeSet<PathBuf> {
+        self.0.keys().filter(|x| x.exists()).cloned().collect()
+    }
+}
+
+/// Waits until a game's saves have been left alone for a while before backing it up,
+/// since games often write several files (or the same file several times) in a row.
+#[derive(Clone, Debug)]
+struct Debouncer {
+    delay: Duration,
+    pending: BTreeMap<String, Instant>,
+}
+
+impl Debouncer {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn touch(&mut self, game: String, now: Instant) {
+        self.pending.insert(game, now);
+    }
+
+    /// Remove and return the games that haven't been touched within the delay.
+    fn take_ready(&mut self, now: Instant) -> Vec<String> {
+        let ready: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, touched)| now.saturating_duration_since(**touched) >= self.delay)
+            .map(|(game, _)| game.clone())
+            .collect();
+        for game in &ready {
+            self.pending.remove(game);
+        }
+        ready
+    }
+}
+
+/// Keeps the watcher's paths in line with `Locations::targets`.
+struct WatchSet {
+    watcher: Box<dyn Watcher>,
+    targets: BTreeMap<PathBuf, RecursiveMode>,
+    existing: BTreeSet<PathBuf>,
+}
+
+impl WatchSet {
+    fn new(watcher: Box<dyn Watcher>, locations: &Locations) -> Self {
+        let mut watch_set = Self {
+            watcher,
+            targets: BTreeMap::new(),
+            existing: locations.existing(),
+        };
+        watch_set.sync(locations);
+        watch_set
+    }
+
+    /// Update the watched paths, returning any games whose save locations have appeared since the last sync.
+    fn sync(&mut self, locations: &Locations) -> BTreeSet<String> {
+        let targets = locations.targets();
+
+        for (path, mode) in &self.targets {
+            if targets.get(path) != Some(mode) {
+                if let Err(e) = self.watcher.unwatch(path) {
+                    log::debug!("Unable to stop watching {path:?}: {e:?}");
+                }
+            }
+        }
+        for (path, mode) in &targets {
+            if self.targets.get(path) != Some(mode) {
+                log::debug!("Watching {path:?} ({mode:?})");
+                if let Err(e) = self.watcher.watch(path, *mode) {
+                    log::warn!("Unable to watch {path:?}: {e:?}");
+                }
+            }
+        }
+        self.targets = targets;
+
+        let appeared = locations.appeared(&self.existing);
+        self.existing = locations.existing();
+        appeared
+    }
+}
+
+/// Watch each game's save locations until interrupted,
+/// calling `back_up` for a game once its saves have changed and then settled down.
+pub fn run(
+    games: &BTreeMap<String, BTreeSet<StrictPath>>,
+    options: &Options,
+    back_up: &mut dyn FnMut(&str),
+) -> Result<(), Error> {
+    let locations = Locations::new(games);
+    let backup_dir = options.backup_dir.as_std_path_buf();
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher: Box<dyn Watcher> = match options.poll {
+        Some(interval) => Box::new(
+            notify::PollWatcher::new(sender, notify::Config::default().with_poll_interval(interval))
+                .map_err(|e| Error::CliUnableToWatch { why: e.to_string() })?,
+        ),
+        None => {
+            Box::new(notify::recommended_watcher(sender).map_err(|e| Error::CliUnableToWatch { why: e.to_string() })?)
+        }
+    };
+    let mut watch_set = WatchSet::new(watcher, &locations);
+    log::info!(
+        "Watching {} locations for {} games with {} targets",
+        locations.0.len(),
+        games.len(),
+        watch_set.targets.len()
+    );
+
+    let interrupted = register_sigint();
+    let mut debouncer = Debouncer::new(options.debounce);
+    let mut last_sync = Instant::now();
+
+    while !interrupted.load(Ordering::Relaxed) {
+        let mut resync = last_sync.elapsed() >= RESYNC;
+
+        match receiver.recv_timeout(TICK) {
+            Ok(event) => {
+                for event in std::iter::once(event).chain(receiver.try_iter()) {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(e) => {
+                            log::warn!("Error while watching for changes: {e:?}");
+                            continue;
+                        }
+                    };
+                    if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+                        continue;
+                    }
+                    let structural = event.kind.is_create() || event.kind.is_remove();
+                    resync = resync || structural;
+
+                    for path in &event.paths {
+                        if path.starts_with(&backup_dir) {
+                            continue;
+                        }
+                        for game in locations.games_for(path, structural) {
+                            log::trace!("[{game}] change detected: {path:?}");
+                            debouncer.touch(game, Instant::now());
+                        }
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if resync {
+            for game in watch_set.sync(&locations) {
+                log::debug!("[{game}] save location appeared");
+                debouncer.touch(game, Instant::now());
+            }
+            last_sync = Instant::now();
+        }
+
+        for game in debouncer.take_ready(Instant::now()) {
+            log::info!("[{game}] backing up after changes");
+            back_up(&game);
+        }
+    }
+
+    unregister_sigint();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::{btreemap, btreeset};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::{repo, s};
+
+    fn locations() -> Locations {
+        Locations::new(&btreemap! {
+            s("game1") => btreeset! {
+                StrictPath::new(format!("{}/tests/root1/game1", repo())),
+                StrictPath::new(format!("{}/tests/root1/game1/subdir", repo())),
+            },
+            s("game2") => btreeset! {
+                StrictPath::new(format!("{}/tests/root2/game2", repo())),
+            },
+            s("new") => btreeset! {
+                StrictPath::new(format!("{}/tests/root2/new/saves", repo())),
+            },
+        })
+    }
+
+    fn path(relative: &str) -> PathBuf {
+        StrictPath::new(format!("{}/{relative}", repo())).as_std_path_buf()
+    }
+
+    #[test]
+    fn can_find_games_for_changed_path() {
+        let locations = locations();
+
+        assert_eq!(
+            btreeset! { s("game1") },
+            locations.games_for(&path("tests/root1/game1/subdir/file2.txt"), false)
+        );
+        assert_eq!(btreeset! {}, locations.games_for(&path("tests/root2/new"), false));
+        assert_eq!(
+            btreeset! { s("new") },
+            locations.games_for(&path("tests/root2/new"), true)
+        );
+        assert_eq!(
+            btreeset! { s("game2"), s("new") },
+            locations.games_for(&path("tests/root2"), true)
+        );
+        assert_eq!(btreeset! {}, locations.games_for(&path("tests/root3"), true));
+    }
+
+    #[test]
+    fn can_plan_watch_targets() {
+        assert_eq!(
+            btreemap! {
+                path("tests/root1/game1") => RecursiveMode::Recursive,
+                path("tests/root2") => RecursiveMode::NonRecursive,
+                path("tests/root2/game2") => RecursiveMode::Recursive,
+            },
+            locations().targets()
+        );
+    }
+
+    #[test]
+    fn can_debounce_changes() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_secs(10));
+
+        debouncer.touch(s("game1"), start);
+        debouncer.touch(s("game2"), start);
+        debouncer.touch(s("game2"), start + Duration::from_secs(5));
+
+        assert_eq!(
+            Vec::<String>::new(),
+            debouncer.take_ready(start + Duration::from_secs(9))
+        );
+        assert_eq!(vec![s("game1")], debouncer.take_ready(start + Duration::from_secs(10)));
+        assert_eq!(
+            Vec::<String>::new(),
+            debouncer.take_ready(start + Duration::from_secs(14))
+        );
+        assert_eq!(vec![s("game2")], debouncer.take_ready(start + Duration::from_secs(15)));
+        assert_eq!(
+            Vec::<String>::new(),
+            debouncer.take_ready(start + Duration::from_secs(60))
+        );
+    }
+}
@@ -1,9 +1,13 @@
+pub mod chunked;
+pub mod history;
+pub mod metered;
+
 use std::io::{BufRead, BufReader};
 
 use crate::{
     lang::TRANSLATOR,
     prelude::{run_command, CommandError, CommandOutput, Error, Finality, Privacy, StrictPath, SyncDirection},
-    resource::config::{App, Config},
+    resource::config::{self, App, ChunkedUpload, Config},
     scan::ScanChange,
 };
 
@@ -30,6 +34,9 @@ pub fn validate_cloud_path(path: &str) -> Result<(), Error> {
 pub struct CloudChange {
     pub path: String,
     pub change: ScanChange,
+    /// For a chunked upload that was interrupted before,
+    /// this is how much of the file had already been uploaded, as a percentage.
+    pub resumed_at: Option<u8>,
 }
 
 #[derive(Clone, Debug)]
@@ -44,6 +51,10 @@ pub struct RcloneProcess {
     args: Vec<String>,
     child: std::process::Child,
     stderr: Option<BufReader<std::process::ChildStderr>>,
+    /// This is only set for real (non-preview) syncs, which get recorded in the history.
+    history: Option<Box<history::Pending>>,
+    /// Whether to also record the sync in the general operation history.
+    operation_history: config::History,
 }
 
 impl RcloneProcess {
@@ -78,9 +89,26 @@ impl RcloneProcess {
             args,
             child,
             stderr,
+            history: None,
+            operation_history: config::History::default(),
         })
     }
 
+    fn with_history(mut self, pending: history::Pending, operation_history: &config::History) -> Self {
+        self.history = Some(Box::new(pending));
+        self.operation_history = operation_history.clone();
+        self
+    }
+
+    fn finish_history(&mut self, succeeded: bool) {
+        if let Some(pending) = self.history.take() {
+            let record = pending.finish(succeeded);
+            history::History::default().record(&record);
+            crate::history::History::default()
+                .record(&self.operation_history, &crate::history::Record::for_cloud(&record));
+        }
+    }
+
     pub fn events(&mut self) -> Vec<RcloneProcessEvent> {
         let mut events = vec![];
 
@@ -106,27 +134,42 @@ impl RcloneProcess {
                         "copy" => events.push(RcloneProcessEvent::Change(CloudChange {
                             path: object,
                             change: ScanChange::Different,
+                            resumed_at: None,
                         })),
                         "delete" => events.push(RcloneProcessEvent::Change(CloudChange {
                             path: object,
                             change: ScanChange::Removed,
+                            resumed_at: None,
                         })),
                         raw => {
                             log::trace!("Unhandled Rclone 'skipped': {raw}");
                         }
                     },
                     Ok(Log::Change { msg, object }) => match msg.as_str() {
-                        "Copied (new)" => events.push(RcloneProcessEvent::Change(CloudChange {
-                            path: object,
-                            change: ScanChange::New,
-                        })),
-                        "Copied (replaced existing)" => events.push(RcloneProcessEvent::Change(CloudChange {
-                            path: object,
-                            change: ScanChange::Different,
-                        })),
+                        "Copied (new)" => {
+                            if let Some(pending) = self.history.as_mut() {
+                                pending.add_file();
+                            }
+                            events.push(RcloneProcessEvent::Change(CloudChange {
+                                path: object,
+                                change: ScanChange::New,
+                                resumed_at: None,
+                            }))
+                        }
+                        "Copied (replaced existing)" => {
+                            if let Some(pending) = self.history.as_mut() {
+                                pending.add_file();
+                            }
+                            events.push(RcloneProcessEvent::Change(CloudChange {
+                                path: object,
+                                change: ScanChange::Different,
+                                resumed_at: None,
+                            }))
+                        }
                         "Deleted" => events.push(RcloneProcessEvent::Change(CloudChange {
                             path: object,
                             change: ScanChange::Removed,
+                            resumed_at: None,
                         })),
                         raw => {
                             log::trace!("Unhandled Rclone 'msg': {raw}");
@@ -135,6 +178,9 @@ impl RcloneProcess {
                     Ok(Log::Stats {
                         stats: Stats { bytes, total_bytes },
                     }) => {
+                        if let Some(pending) = self.history.as_mut() {
+                            pending.set_bytes(bytes as u64);
+                        }
                         if total_bytes > 0.0 {
                             events.push(RcloneProcessEvent::Progress {
                                 current: bytes,
@@ -195,11 +241,15 @@ impl RcloneProcess {
         if let Some(Err(e)) = &res {
             log::error!("Rclone failed: {e:?}");
         }
+        if let Some(outcome) = &res {
+            self.finish_history(outcome.is_ok());
+        }
 
         res
     }
 
     pub fn kill(&mut self) -> Result<(), std::io::Error> {
+        self.finish_history(false);
         let res = self.child.kill();
         if let Err(e) = &res {
             log::error!("Unable to kill child process for Rclone: {e:?}");
@@ -252,7 +302,7 @@ impl ToString for RemoteChoice {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename = "camelCase")]
 pub enum Remote {
     Custom {
@@ -469,7 +519,7 @@ impl TryFrom<RemoteChoice> for Remote {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum WebDavProvider {
     #[default]
     Other,
@@ -541,17 +591,74 @@ impl std::str::FromStr for WebDavProvider {
     }
 }
 
+/// A file as reported by `rclone lsjson`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RemoteFile {
+    pub name: String,
+    /// This is -1 for folders and for files whose size is unknown.
+    pub size: i64,
+    #[serde(default)]
+    pub is_dir: bool,
+    #[serde(default)]
+    pub hashes: std::collections::BTreeMap<String, String>,
+}
+
+impl RemoteFile {
+    pub fn sha1(&self) -> Option<&str> {
+        self.hashes.get("sha1").map(|x| x.as_str())
+    }
+}
+
 pub struct Rclone {
     app: App,
     remote: Remote,
+    chunking: Option<ChunkedUpload>,
+    operation_history: config::History,
 }
 
 impl Rclone {
     pub fn new(app: App, remote: Remote) -> Self {
-        Self { app, remote }
+        Self {
+            app,
+            remote,
+            chunking: None,
+            operation_history: config::History::default(),
+        }
+    }
+
+    /// Record syncs in the operation history, if it's enabled.
+    pub fn with_operation_history(mut self, history: &config::History) -> Self {
+        self.operation_history = history.clone();
+        self
+    }
+
+    /// Access the remote through Rclone's chunker backend, if enabled.
+    /// Files that were stored in chunks then look like normal files.
+    pub fn with_chunking(mut self, chunking: &ChunkedUpload) -> Self {
+        self.chunking = chunking.enabled().then(|| chunking.clone());
+        self
+    }
+
+    /// In bytes.
+    pub fn chunk_size(&self) -> u64 {
+        self.chunking.clone().unwrap_or_default().chunk_size_bytes()
     }
 
     fn path(&self, path: &str) -> String {
+        match &self.chunking {
+            Some(chunking) => format!(
+                ":chunker,remote=\"{}:\",chunk_size={}M,hash_type=sha1:{}",
+                self.remote.id(),
+                chunking.chunk_size.max(1),
+                path.replace('\\', "/")
+            ),
+            None => self.raw_path(path),
+        }
+    }
+
+    /// This bypasses the chunker backend, so individual chunks can be accessed.
+    fn raw_path(&self, path: &str) -> String {
         // Rclone doesn't allow backslashes in non-local paths:
         // https://rclone.org/docs/#syntax-of-remote-paths
         format!("{}:{}", self.remote.id(), path.replace('\\', "/"))
@@ -635,6 +742,61 @@ impl Rclone {
         Ok(())
     }
 
+    fn list(&self, target: String) -> Result<Vec<RemoteFile>, CommandError> {
+        let args = vec![
+            "lsjson".to_string(),
+            "--hash".to_string(),
+            "--hash-type=sha1".to_string(),
+            "--no-mimetype".to_string(),
+            target,
+        ];
+        // Exit code 3 means that the folder doesn't exist.
+        let output = self.run(&args, &[0, 3], Privacy::Public)?;
+        if output.code == 3 {
+            return Ok(vec![]);
+        }
+        Ok(serde_json::from_str(&output.stdout).unwrap_or_else(|e| {
+            log::warn!("Unable to parse Rclone listing: {e:?}");
+            vec![]
+        }))
+    }
+
+    /// Look up a single file, or return `None` if it doesn't exist.
+    pub fn stat(&self, remote_path: &str) -> Result<Option<RemoteFile>, CommandError> {
+        Ok(self.list(self.path(remote_path))?.into_iter().find(|x| !x.is_dir))
+    }
+
+    /// List a folder without going through the chunker backend.
+    pub fn list_raw(&self, remote_dir: &str) -> Result<Vec<RemoteFile>, CommandError> {
+        Ok(self
+            .list(self.raw_path(remote_dir))?
+            .into_iter()
+            .filter(|x| !x.is_dir)
+            .collect())
+    }
+
+    /// Upload a single file without going through the chunker backend.
+    pub fn copy_to_raw(&self, local: &StrictPath, remote_path: &str) -> Result<(), CommandError> {
+        let args = vec!["copyto".to_string(), local.render(), self.raw_path(remote_path)];
+        self.run(&args, &[0], Privacy::Public)?;
+        Ok(())
+    }
+
+    pub fn move_to(&self, from: &str, to: &str) -> Result<(), CommandError> {
+        let args = vec!["moveto".to_string(), self.path(from), self.path(to)];
+        self.run(&args, &[0], Privacy::Public)?;
+        Ok(())
+    }
+
+    /// Delete a folder and its contents without going through the chunker backend.
+    pub fn purge_raw(&self, remote_dir: &str) -> Result<(), CommandError> {
+        let args = vec!["purge".to_string(), self.raw_path(remote_dir)];
+        self.run(&args, &[0], Privacy::Public)?;
+        Ok(())
+    }
+
+    /// Files in `skip` (relative to `local`) are left alone,
+    /// which is used for large files that get uploaded in chunks separately.
     pub fn sync(
         &self,
         local: &StrictPath,
@@ -642,6 +804,7 @@ impl Rclone {
         direction: SyncDirection,
         finality: Finality,
         game_dirs: &[String],
+        skip: &[String],
     ) -> Result<RcloneProcess, CommandError> {
         if direction == SyncDirection::Upload && !local.exists() {
             // Rclone will fail with exit code 3 if the local folder does not exist.
@@ -659,9 +822,23 @@ impl Rclone {
             args.push("--dry-run".to_string());
         }
 
-        for game_dir in game_dirs {
-            // Inclusion rules are file-based, so we have to add `**`.
-            args.push(format!("--include=/{game_dir}/**"));
+        if skip.is_empty() {
+            for game_dir in game_dirs {
+                // Inclusion rules are file-based, so we have to add `**`.
+                args.push(format!("--include=/{game_dir}/**"));
+            }
+        } else {
+            // Rclone doesn't define the order of mixed `--include` and `--exclude` rules,
+            // so we need to use `--filter` for both.
+            for file in skip {
+                args.push(format!("--filter=- /{}", escape_filter(file)));
+            }
+            for game_dir in game_dirs {
+                args.push(format!("--filter=+ /{}/**", escape_filter(game_dir)));
+            }
+            if !game_dirs.is_empty() {
+                args.push("--filter=- **".to_string());
+            }
         }
 
         match direction {
@@ -675,8 +852,28 @@ impl Rclone {
             }
         }
 
-        RcloneProcess::launch(self.app.path.raw(), self.args(&args))
+        let process = RcloneProcess::launch(self.app.path.raw(), self.args(&args))?;
+        if finality.preview() {
+            Ok(process)
+        } else {
+            Ok(process.with_history(
+                history::Pending::new(direction, self.remote.id().to_string()),
+                &self.operation_history,
+            ))
+        }
+    }
+}
+
+/// Rclone filter rules are glob patterns, so special characters must be escaped to match literally.
+fn escape_filter(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '\\' | '*' | '?' | '[' | ']' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
     }
+    escaped
 }
 
 pub mod rclone_monitor {
@@ -749,7 +946,7 @@ pub mod rclone_monitor {
                     match input {
                         Input::Process(new_process) => {
                             if let Some(proc) = process.as_mut() {
-                                let _ = proc.child.kill();
+                                let _ = proc.kill();
                             }
                             process = Some(new_process);
                         }
@@ -0,0 +1,383 @@
+//! Resumable uploads of large files.
+//!
+//! Each chunk is uploaded on its own into a staging folder next to the cloud folder,
+//! using the same layout as Rclone's chunker backend
+//! (`data.rclone_chunk.001`, `data.rclone_chunk.002`, and so on, plus a small metadata file called `data`).
+//! The uploaded chunks are recorded in the cache as we go,
+//! so an interrupted upload can pick up where it left off on the next run.
+//! Once every chunk is there and has been verified,
+//! the assembled file is moved into place through the chunker backend.
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use crate::{
+    cloud::{CloudChange, Rclone},
+    prelude::{filter_map_walkdir, CommandError, Error, StrictPath},
+    resource::{cache::Cache, SaveableResourceFile},
+    scan::ScanChange,
+};
+
+/// The staging folder is a sibling of the cloud folder, so that it isn't affected by normal syncs.
+const STAGING_SUFFIX: &str = ".ludusavi-partial";
+const DATA_NAME: &str = "data";
+
+/// Progress of a chunked upload, stored in the cache by the file's SHA-1 hash.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PartialUpload {
+    /// Relative to the cloud folder.
+    pub path: String,
+    pub size: u64,
+    pub chunk_size: u64,
+    /// SHA-1 hash of each chunk that has been uploaded, by index (starting from 0).
+    #[serde(default)]
+    pub chunks: BTreeMap<u64, String>,
+}
+
+impl PartialUpload {
+    fn new(path: String, size: u64, chunk_size: u64) -> Self {
+        Self {
+            path,
+            size,
+            chunk_size,
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    pub fn chunk_count(&self) -> u64 {
+        self.size.div_ceil(self.chunk_size).max(1)
+    }
+
+    fn chunk_len(&self, index: u64) -> u64 {
+        self.chunk_size.min(self.size.saturating_sub(index * self.chunk_size))
+    }
+
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.chunks.keys().map(|x| self.chunk_len(*x)).sum()
+    }
+
+    /// How much of the file has been uploaded, as a percentage.
+    pub fn percent(&self) -> u8 {
+        if self.size == 0 {
+            return 0;
+        }
+        (self.uploaded_bytes() * 100 / self.size).min(100) as u8
+    }
+
+    /// Chunker's metadata format, version 1.
+    fn metadata(&self, hash: &str) -> String {
+        serde_json::json!({
+            "ver": 1,
+            "size": self.size,
+            "nchunks": self.chunk_count(),
+            "sha1": hash,
+        })
+        .to_string()
+    }
+}
+
+/// A local file that's big enough to be uploaded in chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LargeFile {
+    /// Relative to the local backup folder, with `/` separators.
+    pub path: String,
+    pub local: StrictPath,
+    pub size: u64,
+}
+
+/// Find files of at least `threshold` bytes, limited to `game_dirs` if specified.
+pub fn find_large_files(local: &StrictPath, game_dirs: &[String], threshold: u64) -> Vec<LargeFile> {
+    let base = local.as_std_path_buf();
+    let roots: Vec<_> = if game_dirs.is_empty() {
+        vec![base.clone()]
+    } else {
+        game_dirs.iter().map(|x| base.join(x)).collect()
+    };
+
+    let mut files = vec![];
+    for root in roots {
+        for entry in walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(filter_map_walkdir)
+            .filter(|x| x.file_type().is_file())
+        {
+            let Ok(size) = entry.metadata().map(|x| x.len()) else {
+                continue;
+            };
+            if size < threshold {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&base) else {
+                continue;
+            };
+            let path = relative
+                .components()
+                .map(|x| x.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(LargeFile {
+                path,
+                local: StrictPath::from(&entry),
+                size,
+            });
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn join(folder: &str, path: &str) -> String {
+    format!("{}/{}", folder.trim_end_matches(['/', '\\']), path)
+}
+
+fn staging_dir(cloud: &str, hash: &str) -> String {
+    format!("{}{}/{}", cloud.trim_end_matches(['/', '\\']), STAGING_SUFFIX, hash)
+}
+
+fn chunk_name(index: u64) -> String {
+    format!("{DATA_NAME}.rclone_chunk.{:03}", index + 1)
+}
+
+/// A large file that differs from the cloud.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingUpload {
+    pub file: LargeFile,
+    pub hash: String,
+    pub change: CloudChange,
+}
+
+/// Compare a large file with the cloud.
+/// Returns `None` if the cloud already has the same content.
+pub fn check(rclone: &Rclone, cloud: &str, file: &LargeFile, cache: &Cache) -> Result<Option<PendingUpload>, Error> {
+    let hash = file.local.try_sha1().map_err(|e| Error::CloudChunkedUploadFailed {
+        path: file.path.clone(),
+        why: e.to_string(),
+    })?;
+
+    let remote = rclone
+        .stat(&join(cloud, &file.path))
+        .map_err(Error::UnableToSynchronizeCloud)?;
+    let change = match remote {
+        None => ScanChange::New,
+        Some(remote) if remote.size == file.size as i64 && remote.sha1() == Some(hash.as_str()) => return Ok(None),
+        Some(_) => ScanChange::Different,
+    };
+
+    let resumed_at = resumable(cache, &hash, file, rclone.chunk_size()).map(|x| x.percent());
+
+    Ok(Some(PendingUpload {
+        change: CloudChange {
+            path: file.path.clone(),
+            change,
+            resumed_at,
+        },
+        file: file.clone(),
+        hash,
+    }))
+}
+
+/// Progress is only reusable if it's for the same content and the same chunk size.
+fn resumable<'a>(cache: &'a Cache, hash: &str, file: &LargeFile, chunk_size: u64) -> Option<&'a PartialUpload> {
+    cache
+        .cloud
+        .uploads
+        .get(hash)
+        .filter(|x| x.size == file.size && x.chunk_size == chunk_size)
+}
+
+/// Upload the missing chunks of a file, verify them, and then move the assembled file into place.
+/// The cache is saved after each chunk, so that progress isn't lost if we're interrupted.
+pub fn upload(
+    rclone: &Rclone,
+    cloud: &str,
+    pending: &PendingUpload,
+    cache: &mut Cache,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(), Error> {
+    let PendingUpload { file, hash, .. } = pending;
+    let chunk_size = rclone.chunk_size();
+    let staging = staging_dir(cloud, hash);
+    let failed = |why: String| Error::CloudChunkedUploadFailed {
+        path: file.path.clone(),
+        why,
+    };
+
+    let mut state = match resumable(cache, hash, file, chunk_size) {
+        Some(state) => {
+            log::info!(
+                "Resuming upload of {} at {}% ({} of {} chunks)",
+                &file.path,
+                state.percent(),
+                state.chunks.len(),
+                state.chunk_count()
+            );
+            state.clone()
+        }
+        None => PartialUpload::new(file.path.clone(), file.size, chunk_size),
+    };
+
+    let temp = StrictPath::from_std_path_buf(&std::env::temp_dir().join(format!(
+        "ludusavi-chunk-{}-{}",
+        std::process::id(),
+        &hash[..hash.len().min(12)]
+    )));
+    let mut source = std::fs::File::open(file.local.as_std_path_buf()).map_err(|e| failed(e.to_string()))?;
+
+    on_progress(state.uploaded_bytes(), file.size);
+    for index in 0..state.chunk_count() {
+        if state.chunks.contains_key(&index) {
+            continue;
+        }
+
+        let chunk_hash = write_chunk(&mut source, &temp, index * chunk_size, state.chunk_len(index))
+            .map_err(|e| failed(e.to_string()))?;
+        let uploaded = rclone.copy_to_raw(&temp, &join(&staging, &chunk_name(index)));
+        _ = temp.remove();
+        uploaded.map_err(Error::UnableToSynchronizeCloud)?;
+
+        state.chunks.insert(index, chunk_hash);
+        cache.cloud.uploads.insert(hash.clone(), state.clone());
+        cache.save();
+        on_progress(state.uploaded_bytes(), file.size);
+    }
+
+    let bad = verify(rclone, &staging, &state).map_err(Error::UnableToSynchronizeCloud)?;
+    if !bad.is_empty() {
+        // Forget the bad chunks so that they'll be uploaded again next time.
+        for index in &bad {
+            state.chunks.remove(index);
+        }
+        cache.cloud.uploads.insert(hash.clone(), state);
+        cache.save();
+        return Err(failed(format!("chunks did not match after upload: {bad:?}")));
+    }
+
+    std::fs::write(temp.as_std_path_buf(), state.metadata(hash)).map_err(|e| failed(e.to_string()))?;
+    let uploaded = rclone.copy_to_raw(&temp, &join(&staging, DATA_NAME));
+    _ = temp.remove();
+    uploaded.map_err(Error::UnableToSynchronizeCloud)?;
+
+    // Make sure that the chunker backend sees the complete file before we replace the old one.
+    match rclone
+        .stat(&join(&staging, DATA_NAME))
+        .map_err(Error::UnableToSynchronizeCloud)?
+    {
+        Some(remote) if remote.size == file.size as i64 => {}
+        remote => {
+            return Err(failed(format!(
+                "expected {} bytes, but found {:?}",
+                file.size,
+                remote.map(|x| x.size)
+            )))
+        }
+    }
+
+    rclone
+        .move_to(&join(&staging, DATA_NAME), &join(cloud, &file.path))
+        .map_err(Error::UnableToSynchronizeCloud)?;
+
+    cache.cloud.uploads.remove(hash);
+    cache.save();
+    if let Err(e) = rclone.purge_raw(&staging) {
+        log::warn!("Unable to clean up staging folder for {}: {e:?}", &file.path);
+    }
+
+    Ok(())
+}
+
+fn write_chunk(source: &mut std::fs::File, target: &StrictPath, offset: u64, len: u64) -> std::io::Result<String> {
+    let mut buffer = vec![];
+    source.seek(SeekFrom::Start(offset))?;
+    source.take(len).read_to_end(&mut buffer)?;
+    if buffer.len() as u64 != len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "file changed while uploading",
+        ));
+    }
+
+    let mut file = std::fs::File::create(target.as_std_path_buf())?;
+    file.write_all(&buffer)?;
+
+    use sha1::Digest;
+    Ok(format!("{:x}", sha1::Sha1::digest(&buffer)))
+}
+
+/// Check every chunk by size, and by hash if the cloud provides one.
+/// Returns the indices of any chunks that are missing or don't match.
+fn verify(rclone: &Rclone, staging: &str, state: &PartialUpload) -> Result<Vec<u64>, CommandError> {
+    let remote: BTreeMap<_, _> = rclone
+        .list_raw(staging)?
+        .into_iter()
+        .map(|x| (x.name.clone(), x))
+        .collect();
+
+    Ok((0..state.chunk_count())
+        .filter(|index| {
+            let Some(remote) = remote.get(&chunk_name(*index)) else {
+                return true;
+            };
+            if remote.size != state.chunk_len(*index) as i64 {
+                return true;
+            }
+            match (remote.sha1(), state.chunks.get(index)) {
+                (Some(remote), Some(local)) => !remote.eq_ignore_ascii_case(local),
+                _ => false,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn state(size: u64, chunk_size: u64, chunks: &[u64]) -> PartialUpload {
+        PartialUpload {
+            path: s("game/backup.zip"),
+            size,
+            chunk_size,
+            chunks: chunks.iter().map(|x| (*x, s("hash"))).collect(),
+        }
+    }
+
+    #[test]
+    fn counts_chunks_including_partial_last_one() {
+        assert_eq!(1, state(10, 10, &[]).chunk_count());
+        assert_eq!(2, state(11, 10, &[]).chunk_count());
+        assert_eq!(10, state(11, 10, &[]).chunk_len(0));
+        assert_eq!(1, state(11, 10, &[]).chunk_len(1));
+    }
+
+    #[test]
+    fn reports_progress_by_uploaded_bytes() {
+        assert_eq!(0, state(100, 30, &[]).percent());
+        assert_eq!(60, state(100, 30, &[0, 2]).percent());
+        assert_eq!(10, state(100, 30, &[3]).percent());
+        assert_eq!(100, state(100, 30, &[0, 1, 2, 3]).percent());
+    }
+
+    #[test]
+    fn names_chunks_like_rclone_chunker() {
+        assert_eq!("data.rclone_chunk.001", chunk_name(0));
+        assert_eq!("data.rclone_chunk.1000", chunk_name(999));
+        assert_eq!(
+            "ludusavi-backup.ludusavi-partial/abc",
+            staging_dir("ludusavi-backup/", "abc")
+        );
+    }
+
+    #[test]
+    fn writes_chunker_metadata() {
+        assert_eq!(
+            r#"{"nchunks":4,"sha1":"abc","size":100,"ver":1}"#,
+            state(100, 30, &[]).metadata("abc")
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! A small log of cloud sync runs, so that users can see how much data they transfer.
+//! Each run is one line of JSON in `cloud-history.jsonl` next to the config file.
+
+use std::{collections::BTreeMap, io::Write, path::PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::prelude::{app_dir, FileLock, SyncDirection};
+
+const FILE_NAME: &str = "cloud-history.jsonl";
+const ROTATED_FILE_NAME: &str = "cloud-history.1.jsonl";
+
+/// Once the file grows past this size, it's moved aside and a new one is started.
+/// We keep one rotated file, so the history takes at most about twice this much space.
+const MAX_BYTES: u64 = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    pub when: DateTime<Utc>,
+    pub direction: SyncDirection,
+    pub files: usize,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    pub remote: String,
+    pub succeeded: bool,
+}
+
+/// Accounting for a sync that is still running.
+#[derive(Clone, Debug)]
+pub struct Pending {
+    when: DateTime<Utc>,
+    started: std::time::Instant,
+    direction: SyncDirection,
+    remote: String,
+    files: usize,
+    bytes: u64,
+}
+
+impl Pending {
+    pub fn new(direction: SyncDirection, remote: String) -> Self {
+        Self {
+            when: Utc::now(),
+            started: std::time::Instant::now(),
+            direction,
+            remote,
+            files: 0,
+            bytes: 0,
+        }
+    }
+
+    pub fn add_file(&mut self) {
+        self.files += 1;
+    }
+
+    /// Rclone reports the running total, so this replaces the previous value.
+    pub fn set_bytes(&mut self, bytes: u64) {
+        self.bytes = bytes;
+    }
+
+    pub fn finish(self, succeeded: bool) -> Record {
+        Record {
+            when: self.when,
+            direction: self.direction,
+            files: self.files,
+            bytes: self.bytes,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            remote: self.remote,
+            succeeded,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Totals {
+    pub runs: usize,
+    pub failed_runs: usize,
+    pub files: usize,
+    pub uploaded_bytes: u64,
+    pub downloaded_bytes: u64,
+    pub duration_ms: u64,
+}
+
+impl Totals {
+    fn add(&mut self, record: &Record) {
+        self.runs += 1;
+        if !record.succeeded {
+            self.failed_runs += 1;
+        }
+        self.files += record.files;
+        match record.direction {
+            SyncDirection::Upload => self.uploaded_bytes += record.bytes,
+            SyncDirection::Download => self.downloaded_bytes += record.bytes,
+        }
+        self.duration_ms += record.duration_ms;
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    pub total: Totals,
+    /// Keyed by local month, like `2024-01`.
+    pub months: BTreeMap<String, Totals>,
+    /// Keyed by local date, like `2024-01-31`.
+    pub days: BTreeMap<String, Totals>,
+}
+
+impl Summary {
+    pub fn new(records: &[Record], since: Option<DateTime<Utc>>) -> Self {
+        let mut summary = Self {
+            since,
+            ..Default::default()
+        };
+
+        for record in records {
+            if since.is_some_and(|since| record.when < since) {
+                continue;
+            }
+
+            let local = record.when.with_timezone(&Local);
+            summary.total.add(record);
+            summary
+                .months
+                .entry(local.format("%Y-%m").to_string())
+                .or_default()
+                .add(record);
+            summary
+                .days
+                .entry(local.format("%Y-%m-%d").to_string())
+                .or_default()
+                .add(record);
+        }
+
+        summary
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(app_dir())
+    }
+}
+
+impl History {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file(&self) -> PathBuf {
+        self.dir.join(FILE_NAME)
+    }
+
+    fn rotated_file(&self) -> PathBuf {
+        self.dir.join(ROTATED_FILE_NAME)
+    }
+
+    fn lock_file(&self) -> PathBuf {
+        self.dir.join(format!("{FILE_NAME}.lock"))
+    }
+
+    /// Append a record. Failures are only logged, since this shouldn't interrupt a sync.
+    pub fn record(&self, record: &Record) {
+        if let Err(e) = self.try_record(record) {
+            log::warn!("Unable to record cloud history: {e:?}");
+        }
+    }
+
+    fn try_record(&self, record: &Record) -> Result<(), std::io::Error> {
+        let mut line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+        line.push('\n');
+
+        std::fs::create_dir_all(&self.dir)?;
+        // The GUI and CLI may both be running, so only one of them can touch the files at a time.
+        let _lock = FileLock::acquire(&self.lock_file())?;
+
+        let file = self.file();
+        if file.metadata().is_ok_and(|x| x.len() + line.len() as u64 > MAX_BYTES) {
+            std::fs::rename(&file, self.rotated_file())?;
+        }
+
+        let mut handle = std::fs::OpenOptions::new().create(true).append(true).open(&file)?;
+        handle.write_all(line.as_bytes())?;
+        handle.flush()
+    }
+
+    /// Load all records, oldest first. Malformed lines are skipped.
+    pub fn load(&self) -> Vec<Record> {
+        let mut records = vec![];
+
+        for file in [self.rotated_file(), self.file()] {
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            for line in content.lines().filter(|x| !x.trim().is_empty()) {
+                match serde_json::from_str::<Record>(line) {
+                    Ok(record) => records.push(record),
+                    Err(e) => log::warn!("Ignoring invalid cloud history line in {file:?}: {e:?}"),
+                }
+            }
+        }
+
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn record(when: DateTime<Utc>, direction: SyncDirection, bytes: u64, succeeded: bool) -> Record {
+        Record {
+            when,
+            direction,
+            files: 1,
+            bytes,
+            duration_ms: 10,
+            remote: s("remote"),
+            succeeded,
+        }
+    }
+
+    fn local(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn can_summarize_records_by_day_and_month() {
+        let records = vec![
+            record(local(2024, 1, 1), SyncDirection::Upload, 100, true),
+            record(local(2024, 1, 1), SyncDirection::Download, 20, true),
+            record(local(2024, 1, 31), SyncDirection::Upload, 3, false),
+            record(local(2024, 2, 1), SyncDirection::Upload, 4000, true),
+        ];
+
+        let summary = Summary::new(&records, Some(local(2024, 1, 1)));
+
+        assert_eq!(
+            Totals {
+                runs: 4,
+                failed_runs: 1,
+                files: 4,
+                uploaded_bytes: 4103,
+                downloaded_bytes: 20,
+                duration_ms: 40,
+            },
+            summary.total
+        );
+        assert_eq!(vec!["2024-01", "2024-02"], summary.months.keys().collect::<Vec<_>>());
+        assert_eq!(103, summary.months["2024-01"].uploaded_bytes);
+        assert_eq!(
+            vec!["2024-01-01", "2024-01-31", "2024-02-01"],
+            summary.days.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(2, summary.days["2024-01-01"].runs);
+
+        let summary = Summary::new(&records, Some(local(2024, 1, 2)));
+        assert_eq!(2, summary.total.runs);
+    }
+
+    #[test]
+    fn can_record_and_load_history() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-cloud-history-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        let history = History::new(dir.clone());
+
+        let first = record(local(2024, 1, 1), SyncDirection::Upload, 100, true);
+        let second = record(local(2024, 1, 2), SyncDirection::Download, 200, false);
+        history.record(&first);
+        history.record(&second);
+
+        // A partially written line shouldn't prevent reading the rest.
+        let mut handle = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.join(FILE_NAME))
+            .unwrap();
+        handle.write_all(b"{\"when\":").unwrap();
+        drop(handle);
+
+        assert_eq!(vec![first.clone(), second.clone()], history.load());
+        assert!(!dir.join(format!("{FILE_NAME}.lock")).exists());
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotates_history_when_too_large() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-cloud-history-rotate-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let history = History::new(dir.clone());
+
+        let old = record(local(2024, 1, 1), SyncDirection::Upload, 100, true);
+        let filler = format!("{}\n", serde_json::to_string(&old).unwrap()).repeat(MAX_BYTES as usize / 100);
+        std::fs::write(dir.join(FILE_NAME), filler).unwrap();
+
+        let new = record(local(2024, 1, 2), SyncDirection::Upload, 200, true);
+        history.record(&new);
+
+        assert!(dir.join(ROTATED_FILE_NAME).exists());
+        assert_eq!(
+            vec![new.clone()],
+            History::new(dir.clone()).load()[MAX_BYTES as usize / 100..]
+        );
+
+        // Only one rotated file is kept.
+        std::fs::write(dir.join(FILE_NAME), "x".repeat(MAX_BYTES as usize)).unwrap();
+        history.record(&new);
+        assert_eq!(vec![new.clone()], history.load());
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,161 @@
+//! Best-effort detection of metered network connections, so that automatic cloud sync can wait for a cheaper one.
+//! If the platform doesn't tell us, we assume the connection is not metered,
+//! so that machines without this information (like servers) keep syncing as usual.
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use crate::prelude::{run_command, Privacy};
+use crate::resource::config::OnMetered;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metered {
+    Yes,
+    No,
+    Unknown,
+}
+
+/// What to do about automatic cloud sync, given the user's policy and the current connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeteredDecision {
+    Proceed,
+    /// Don't sync for now. It should happen on the next run with an unmetered connection.
+    Defer,
+    /// Let the user choose whether to sync or defer.
+    Ask,
+}
+
+/// The detector is only called when the policy could actually make a difference,
+/// since some platforms need to launch a process to find out.
+pub fn decide(policy: OnMetered, allow_metered: bool, detect: impl FnOnce() -> Metered) -> MeteredDecision {
+    if allow_metered || policy == OnMetered::Proceed {
+        return MeteredDecision::Proceed;
+    }
+
+    match detect() {
+        Metered::Yes => match policy {
+            OnMetered::Proceed => MeteredDecision::Proceed,
+            OnMetered::Skip => MeteredDecision::Defer,
+            OnMetered::Ask => MeteredDecision::Ask,
+        },
+        Metered::No | Metered::Unknown => MeteredDecision::Proceed,
+    }
+}
+
+pub fn detect() -> Metered {
+    let metered = detect_for_platform();
+    log::debug!("Metered connection: {metered:?}");
+    metered
+}
+
+/// NetworkManager reports one of these for its primary connection:
+/// 0 = unknown, 1 = yes, 2 = no, 3 = guessed yes, 4 = guessed no.
+#[cfg(target_os = "linux")]
+fn detect_for_platform() -> Metered {
+    match run_command(
+        "busctl",
+        &[
+            "--system",
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ],
+        &[0],
+        Privacy::Public,
+    ) {
+        Ok(output) => parse_network_manager(&output.stdout),
+        Err(_) => Metered::Unknown,
+    }
+}
+
+/// The output looks like `u 4`.
+#[cfg(target_os = "linux")]
+fn parse_network_manager(output: &str) -> Metered {
+    match output.trim().strip_prefix("u ").map(|x| x.trim()) {
+        Some("1" | "3") => Metered::Yes,
+        Some("2" | "4") => Metered::No,
+        _ => Metered::Unknown,
+    }
+}
+
+/// This uses the connection cost API, which is only available through WinRT,
+/// so we go through PowerShell rather than binding to it directly.
+#[cfg(target_os = "windows")]
+fn detect_for_platform() -> Metered {
+    const SCRIPT: &str = "[void][Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]; \
+        $profile = [Windows.Networking.Connectivity.NetworkInformation]::GetInternetConnectionProfile(); \
+        if ($profile) { $profile.GetConnectionCost().NetworkCostType }";
+
+    match run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", SCRIPT],
+        &[0],
+        Privacy::Public,
+    ) {
+        Ok(output) => parse_windows_cost(&output.stdout),
+        Err(_) => Metered::Unknown,
+    }
+}
+
+/// The output is a `NetworkCostType`, or nothing if there's no internet connection.
+#[cfg(target_os = "windows")]
+fn parse_windows_cost(output: &str) -> Metered {
+    match output.trim() {
+        "Fixed" | "Variable" => Metered::Yes,
+        "Unrestricted" => Metered::No,
+        _ => Metered::Unknown,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn detect_for_platform() -> Metered {
+    Metered::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn only_defers_or_asks_when_known_to_be_metered() {
+        for (policy, metered, expected) in [
+            (OnMetered::Skip, Metered::Yes, MeteredDecision::Defer),
+            (OnMetered::Skip, Metered::No, MeteredDecision::Proceed),
+            (OnMetered::Skip, Metered::Unknown, MeteredDecision::Proceed),
+            (OnMetered::Ask, Metered::Yes, MeteredDecision::Ask),
+            (OnMetered::Ask, Metered::No, MeteredDecision::Proceed),
+            (OnMetered::Ask, Metered::Unknown, MeteredDecision::Proceed),
+        ] {
+            assert_eq!(expected, decide(policy, false, || metered), "{policy:?} / {metered:?}");
+        }
+    }
+
+    #[test]
+    fn does_not_detect_when_policy_or_override_allows_syncing() {
+        let detect = || -> Metered { panic!("should not detect") };
+        assert_eq!(MeteredDecision::Proceed, decide(OnMetered::Proceed, false, detect));
+        assert_eq!(MeteredDecision::Proceed, decide(OnMetered::Skip, true, detect));
+        assert_eq!(MeteredDecision::Proceed, decide(OnMetered::Ask, true, detect));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn can_parse_network_manager_output() {
+        assert_eq!(Metered::Yes, parse_network_manager("u 1"));
+        assert_eq!(Metered::Yes, parse_network_manager("u 3\n"));
+        assert_eq!(Metered::No, parse_network_manager("u 4"));
+        assert_eq!(Metered::Unknown, parse_network_manager("u 0"));
+        assert_eq!(Metered::Unknown, parse_network_manager(""));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn can_parse_windows_cost() {
+        assert_eq!(Metered::Yes, parse_windows_cost("Fixed"));
+        assert_eq!(Metered::Yes, parse_windows_cost("Variable\r\n"));
+        assert_eq!(Metered::No, parse_windows_cost("Unrestricted"));
+        assert_eq!(Metered::Unknown, parse_windows_cost(""));
+    }
+}
@@ -0,0 +1,1029 @@
+//! An optional long-running process that performs backups and restores on behalf of the CLI and GUI,
+//! so that several operations (e.g., a scheduled backup while the GUI is scanning)
+//! share one loaded manifest and run one at a time instead of scanning concurrently.
+//!
+//! The protocol is one line of JSON per request and response over a local socket:
+//! a Unix socket in a private `daemon` folder inside the config folder,
+//! or a loopback TCP port (recorded in that folder) on Windows.
+//! Each request must include the `token` that the daemon wrote to the same folder,
+//! so that only the user who started the daemon (or an administrator) can use it.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    api::Roots,
+    cli::{
+        operation::{self, BackupRequest, ReportRequest, RestoreRequest, Summary},
+        report::Reporter,
+    },
+    lang::TRANSLATOR,
+    prelude::{app_dir, Error},
+    resource::{cache::Cache, config::Config, manifest::Manifest, ResourceFile},
+    scan::{
+        layout::{BackupLayout, GameLayout},
+        BackupId, BackupInfo, BackupLabel, OperationStepDecision, OsConstraints, ScanInfo, UnknownGame,
+    },
+};
+
+const DIR_NAME: &str = "daemon";
+#[cfg(unix)]
+const FILE_NAME: &str = "daemon.sock";
+#[cfg(not(unix))]
+const FILE_NAME: &str = "daemon.addr";
+const TOKEN_FILE_NAME: &str = "daemon.token";
+
+/// A GUI operation gives up its turn if it goes this long without any requests.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[cfg(unix)]
+type Listener = std::os::unix::net::UnixListener;
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+type Listener = std::net::TcpListener;
+#[cfg(not(unix))]
+type Stream = std::net::TcpStream;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum Request {
+    Backup(BackupRequest),
+    Restore(RestoreRequest),
+    /// The GUI sends this once per operation, followed by a `Game` request for each game
+    /// and then `Finish`. Other operations wait until then.
+    Prepare(PrepareRequest),
+    Game(GameRequest),
+    Finish {
+        session: u64,
+    },
+}
+
+/// What's sent over the connection, so that the token isn't part of `Request`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Envelope {
+    token: String,
+    #[serde(flatten)]
+    request: Request,
+}
+
+/// The state that a GUI operation shares across its games.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrepareRequest {
+    pub restoring: bool,
+    pub config: Box<Config>,
+    /// The operation is refused if the daemon loaded a different manifest.
+    pub manifest_hash: Option<String>,
+    /// Which games to inspect the roots for, when backing up.
+    pub games: Vec<String>,
+    pub force_new_full: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameRequest {
+    /// From the `Prepare` response.
+    pub session: u64,
+    pub name: String,
+    pub preview: bool,
+    /// Whether this is part of a scan of all games, rather than only some of them.
+    pub full: bool,
+    /// For backups.
+    pub label: BackupLabel,
+    /// For restores.
+    pub backup: BackupId,
+}
+
+/// The result of a `Game` request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameOutcome {
+    pub scan_info: Option<ScanInfo>,
+    pub backup_info: Option<BackupInfo>,
+    pub decision: OperationStepDecision,
+}
+
+impl GameOutcome {
+    fn ignored(scan_info: Option<ScanInfo>) -> Self {
+        Self {
+            scan_info,
+            backup_info: None,
+            decision: OperationStepDecision::Ignored,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// What the CLI would have printed, which is the `--api` JSON if requested.
+    #[serde(default)]
+    pub output: String,
+    /// Whether any games failed.
+    #[serde(default)]
+    pub failed: bool,
+    /// For `backup --fail-on-change`.
+    #[serde(default)]
+    pub changes_pending: bool,
+    /// For `backup --if-changed`.
+    #[serde(default)]
+    pub nothing_changed: bool,
+    /// Why the operation couldn't run at all, already translated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// For `Prepare` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<u64>,
+    /// For `Game` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game: Option<GameOutcome>,
+}
+
+impl Response {
+    fn error(error: &Error) -> Self {
+        Self {
+            failed: true,
+            error: Some(TRANSLATOR.handle_error(error)),
+            ..Default::default()
+        }
+    }
+
+    fn unrecognized_games(mut reporter: Reporter, games: Vec<UnknownGame>) -> Self {
+        reporter.trip_unknown_games(games.clone());
+        Self {
+            output: reporter.render_failure().unwrap_or_default(),
+            ..Self::error(&Error::CliUnrecognizedGames { games })
+        }
+    }
+
+    fn finish(config: &Config, reporter: &Reporter, summary: &Summary) -> Self {
+        summary.record(config, reporter);
+        Self {
+            output: reporter.render(&summary.dir),
+            failed: summary.failed_games > 0,
+            changes_pending: summary.changes_pending,
+            nothing_changed: summary.nothing_changed,
+            ..Default::default()
+        }
+    }
+}
+
+/// Where the daemon listens.
+#[derive(Clone, Debug)]
+pub struct Endpoint {
+    dir: PathBuf,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::new(app_dir().join(DIR_NAME))
+    }
+}
+
+impl Endpoint {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file(&self) -> PathBuf {
+        self.dir.join(FILE_NAME)
+    }
+
+    fn token_file(&self) -> PathBuf {
+        self.dir.join(TOKEN_FILE_NAME)
+    }
+
+    /// Whether a daemon is currently accepting requests.
+    pub fn running(&self) -> bool {
+        self.connect().is_ok()
+    }
+
+    #[cfg(unix)]
+    fn connect(&self) -> Result<Stream, std::io::Error> {
+        Stream::connect(self.file())
+    }
+
+    #[cfg(not(unix))]
+    fn connect(&self) -> Result<Stream, std::io::Error> {
+        let address = std::fs::read_to_string(self.file())?;
+        Stream::connect(address.trim())
+    }
+
+    /// Start listening, along with the token that requests must include.
+    fn bind(&self) -> Result<(Listener, String), std::io::Error> {
+        if self.running() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "another daemon is already running",
+            ));
+        }
+        self.create_private_dir()?;
+
+        let token = new_token()?;
+        write_private_file(&self.token_file(), &token)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let file = self.file();
+            if file.exists() {
+                // Left over from a daemon that didn't shut down cleanly.
+                std::fs::remove_file(&file)?;
+            }
+            // Nobody else can reach the socket inside of the private folder, even before this.
+            let listener = Listener::bind(&file)?;
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o600))?;
+            Ok((listener, token))
+        }
+
+        #[cfg(not(unix))]
+        {
+            let listener = Listener::bind("127.0.0.1:0")?;
+            std::fs::write(self.file(), listener.local_addr()?.to_string())?;
+            Ok((listener, token))
+        }
+    }
+
+    /// On Windows, the config folder is already only accessible to the user
+    /// (and administrators), which the new folder inherits.
+    fn create_private_dir(&self) -> Result<(), std::io::Error> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(&self.dir)?;
+            // In case it already existed with looser permissions.
+            std::fs::set_permissions(&self.dir, std::fs::Permissions::from_mode(0o700))
+        }
+
+        #[cfg(not(unix))]
+        {
+            std::fs::create_dir_all(&self.dir)
+        }
+    }
+
+    /// Send a request and wait for the operation to finish.
+    pub fn send(&self, request: &Request) -> Result<Response, Error> {
+        let unavailable = |e: &dyn std::fmt::Display| Error::DaemonUnavailable { why: e.to_string() };
+
+        let token = std::fs::read_to_string(self.token_file()).map_err(|e| unavailable(&e))?;
+        let stream = self.connect().map_err(|e| unavailable(&e))?;
+        let envelope = Envelope {
+            token: token.trim().to_string(),
+            request: request.clone(),
+        };
+        let body = serde_json::to_string(&envelope).map_err(|e| unavailable(&e))?;
+        let mut writer = &stream;
+        writeln!(writer, "{body}").map_err(|e| unavailable(&e))?;
+        writer.flush().map_err(|e| unavailable(&e))?;
+
+        let mut line = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut line)
+            .map_err(|e| unavailable(&e))?;
+        serde_json::from_str(&line).map_err(|e| unavailable(&e))
+    }
+
+    /// Start a GUI operation, once any other operation is done, and get its session.
+    /// If this fails, then the GUI should do the work itself.
+    pub fn prepare(&self, request: PrepareRequest) -> Result<u64, Error> {
+        let response = self.send(&Request::Prepare(request))?;
+        match (response.session, response.error) {
+            (Some(session), None) => Ok(session),
+            (_, why) => Err(Error::DaemonUnavailable {
+                why: why.unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Process one game of a GUI operation that was started with `prepare`.
+    pub fn process_game(&self, request: GameRequest) -> Result<GameOutcome, Error> {
+        let response = self.send(&Request::Game(request))?;
+        match (response.game, response.error) {
+            (Some(game), None) => Ok(game),
+            (_, why) => Err(Error::DaemonUnavailable {
+                why: why.unwrap_or_default(),
+            }),
+        }
+    }
+
+    /// Let other operations run after a GUI operation.
+    pub fn finish(&self, session: u64) -> Result<(), Error> {
+        match self.send(&Request::Finish { session })?.error {
+            Some(why) => Err(Error::DaemonUnavailable { why }),
+            None => Ok(()),
+        }
+    }
+}
+
+fn new_token() -> Result<String, std::io::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(std::io::Error::other)?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+fn write_private_file(file: &std::path::Path, content: &str) -> Result<(), std::io::Error> {
+    if file.exists() {
+        std::fs::remove_file(file)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(file)?.write_all(content.as_bytes())
+}
+
+/// Decides which operation has the daemon.
+/// A CLI operation has it for its one request,
+/// while a GUI operation has it from `Prepare` until `Finish`, so nothing else runs in between.
+#[derive(Default)]
+struct Queue {
+    state: Mutex<QueueState>,
+    turn: Condvar,
+}
+
+#[derive(Default)]
+struct QueueState {
+    owner: Option<Owner>,
+    last_session: u64,
+}
+
+enum Owner {
+    Cli,
+    Session {
+        id: u64,
+        /// Not set until `Prepare` is done.
+        prepared: Option<Arc<Session>>,
+        /// Requests in progress.
+        active: usize,
+        last_used: Instant,
+    },
+}
+
+impl QueueState {
+    /// Release a GUI operation that stopped sending requests (e.g., because the GUI closed).
+    fn expire(&mut self) {
+        if let Some(Owner::Session {
+            id, active, last_used, ..
+        }) = &self.owner
+        {
+            if *active == 0 && last_used.elapsed() >= SESSION_TIMEOUT {
+                log::warn!("Daemon session {id} timed out");
+                self.owner = None;
+            }
+        }
+    }
+}
+
+impl Queue {
+    fn wait_for_turn(&self) -> MutexGuard<'_, QueueState> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            state.expire();
+            if state.owner.is_none() {
+                return state;
+            }
+            state = self.turn.wait_timeout(state, SESSION_TIMEOUT).unwrap().0;
+        }
+    }
+
+    fn enter_cli(&self) -> CliTurn<'_> {
+        self.wait_for_turn().owner = Some(Owner::Cli);
+        CliTurn(self)
+    }
+
+    /// Wait for a turn for a new GUI operation, which is then ready once `ready` is called.
+    fn start_session(&self) -> u64 {
+        let mut state = self.wait_for_turn();
+        state.last_session += 1;
+        let id = state.last_session;
+        state.owner = Some(Owner::Session {
+            id,
+            prepared: None,
+            active: 1,
+            last_used: Instant::now(),
+        });
+        id
+    }
+
+    fn ready(&self, id: u64, session: Session) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(Owner::Session {
+            id: current,
+            prepared,
+            active,
+            last_used,
+        }) = &mut state.owner
+        {
+            if *current == id {
+                *prepared = Some(Arc::new(session));
+                *active -= 1;
+                *last_used = Instant::now();
+            }
+        }
+    }
+
+    /// Run a request for the GUI operation that has the daemon.
+    fn join(&self, id: u64) -> Result<SessionTurn<'_>, Error> {
+        let mut state = self.state.lock().unwrap();
+        state.expire();
+        match &mut state.owner {
+            Some(Owner::Session {
+                id: current,
+                prepared: Some(prepared),
+                active,
+                last_used,
+            }) if *current == id => {
+                *active += 1;
+                *last_used = Instant::now();
+                Ok(SessionTurn {
+                    queue: self,
+                    session: prepared.clone(),
+                })
+            }
+            _ => Err(Error::DaemonUnavailable {
+                why: format!("unknown session: {id}"),
+            }),
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(&state.owner, Some(Owner::Session { id: current, .. }) if *current == id) {
+            state.owner = None;
+            self.turn.notify_all();
+        }
+    }
+}
+
+/// The CLI operation may run until this is dropped.
+struct CliTurn<'a>(&'a Queue);
+
+impl Drop for CliTurn<'_> {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().owner = None;
+        self.0.turn.notify_all();
+    }
+}
+
+/// One request of a GUI operation may run until this is dropped.
+struct SessionTurn<'a> {
+    queue: &'a Queue,
+    session: Arc<Session>,
+}
+
+impl Drop for SessionTurn<'_> {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock().unwrap();
+        if let Some(Owner::Session { active, last_used, .. }) = &mut state.owner {
+            *active = active.saturating_sub(1);
+            *last_used = Instant::now();
+        }
+    }
+}
+
+/// What a GUI operation prepared with `PrepareRequest`.
+struct Session {
+    config: Config,
+    manifest: Manifest,
+    layout: BackupLayout,
+    /// Only for backups.
+    roots: Option<Roots>,
+}
+
+/// Reads one request from the connection and writes back one response.
+fn handle(stream: Stream, token: &str, handler: &dyn Fn(Request) -> Response) -> Result<(), std::io::Error> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut line = String::new();
+    if BufReader::new(&stream).read_line(&mut line)? == 0 {
+        // The client was only checking whether we're running.
+        return Ok(());
+    }
+
+    let response = match serde_json::from_str::<Envelope>(&line) {
+        Ok(envelope) if envelope.token != token => {
+            log::warn!("Rejected daemon request with the wrong token");
+            Response {
+                failed: true,
+                error: Some("invalid token".to_string()),
+                ..Default::default()
+            }
+        }
+        Ok(Envelope { request, .. }) => {
+            log::debug!("Daemon request: {request:?}");
+            handler(request)
+        }
+        Err(e) => Response {
+            failed: true,
+            error: Some(format!("invalid request: {e}")),
+            ..Default::default()
+        },
+    };
+
+    let body = serde_json::to_string(&response).map_err(std::io::Error::other)?;
+    let mut writer = &stream;
+    writeln!(writer, "{body}")?;
+    writer.flush()
+}
+
+/// Each connection gets its own thread, so the handler decides which requests wait for their turn.
+fn listen(listener: Listener, token: &str, handler: &(dyn Fn(Request) -> Response + Sync)) {
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        if let Err(e) = handle(stream, token, handler) {
+                            log::warn!("Unable to handle daemon request: {e:?}");
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Unable to accept daemon connection: {e:?}"),
+            }
+        }
+    });
+}
+
+/// Handle requests until the process is stopped.
+/// The manifest is kept for the whole time, while the config is reloaded for each CLI request.
+/// `manifest_hash` is what `Manifest::load_with_hash` returned for it.
+pub fn serve(endpoint: Endpoint, manifest: Manifest, manifest_hash: Option<String>) -> Result<(), Error> {
+    let (listener, token) = endpoint
+        .bind()
+        .map_err(|e| Error::CliUnableToStartDaemon { why: e.to_string() })?;
+    log::info!("Daemon listening on {:?}", endpoint.file());
+    eprintln!("{}", endpoint.file().display());
+
+    let daemon = Daemon {
+        manifest,
+        manifest_hash,
+        queue: Queue::default(),
+    };
+    listen(listener, &token, &|request| daemon.handle_request(request));
+    Ok(())
+}
+
+struct Daemon {
+    manifest: Manifest,
+    manifest_hash: Option<String>,
+    queue: Queue,
+}
+
+impl Daemon {
+    fn handle_request(&self, request: Request) -> Response {
+        let result = match request {
+            Request::Backup(mut request) => {
+                // Progress events would only reach the daemon's own stderr.
+                request.output.report.progress = false;
+                let _turn = self.queue.enter_cli();
+                return self.run_for_cli(&request.output, |manifest, config| back_up(manifest, config, &request));
+            }
+            Request::Restore(mut request) => {
+                request.output.report.progress = false;
+                let _turn = self.queue.enter_cli();
+                return self.run_for_cli(&request.output, |manifest, config| restore(manifest, config, &request));
+            }
+            Request::Prepare(request) => {
+                let id = self.queue.start_session();
+                match self.prepare(request) {
+                    Ok(session) => {
+                        self.queue.ready(id, session);
+                        Ok(Response {
+                            session: Some(id),
+                            ..Default::default()
+                        })
+                    }
+                    Err(e) => {
+                        self.queue.finish(id);
+                        Err(e)
+                    }
+                }
+            }
+            Request::Game(request) => self.queue.join(request.session).map(|turn| Response {
+                game: Some(process_game(&turn.session, &request)),
+                ..Default::default()
+            }),
+            Request::Finish { session } => {
+                self.queue.finish(session);
+                Ok(Response::default())
+            }
+        };
+        result.unwrap_or_else(|e| Response::error(&e))
+    }
+
+    fn run_for_cli(
+        &self,
+        output: &ReportRequest,
+        run: impl FnOnce(&Manifest, &Config) -> Result<Response, Error>,
+    ) -> Response {
+        let mut config = match Config::load() {
+            Ok(config) => config,
+            Err(e) => return Response::error(&e),
+        };
+        config.manifest.loaded_hash = self.manifest_hash.clone();
+        TRANSLATOR.set_language(config.language);
+
+        // Custom games and secondary manifests may have changed since the last request.
+        let mut manifest = self.manifest.clone();
+        manifest.incorporate_extensions(&config);
+
+        match run(&manifest, &config) {
+            Ok(response) => response,
+            Err(Error::CliUnrecognizedGames { games }) => Response::unrecognized_games(output.reporter(&config), games),
+            Err(e) => Response::error(&e),
+        }
+    }
+
+    fn prepare(&self, request: PrepareRequest) -> Result<Session, Error> {
+        if request.manifest_hash != self.manifest_hash {
+            return Err(Error::DaemonUnavailable {
+                why: "the daemon loaded a different manifest".to_string(),
+            });
+        }
+
+        let mut config = *request.config;
+        config.manifest.loaded_hash = self.manifest_hash.clone();
+        let mut manifest = self.manifest.clone();
+        manifest.incorporate_extensions(&config);
+
+        let (layout, roots) = if request.restoring {
+            (BackupLayout::new(config.restore.path.clone(), &config), None)
+        } else {
+            let mut retention = config.backup.retention.clone();
+            retention.force_new_full = request.force_new_full;
+            let layout = BackupLayout::new(config.backup.path.clone(), &config).with_retention(retention);
+            let roots = Roots::new(&manifest, &config, &layout, &request.games);
+            (layout, Some(roots))
+        };
+
+        Ok(Session {
+            config,
+            manifest,
+            layout,
+            roots,
+        })
+    }
+}
+
+fn process_game(session: &Session, request: &GameRequest) -> GameOutcome {
+    match &session.roots {
+        Some(roots) => back_up_game(
+            &session.manifest,
+            &session.config,
+            roots,
+            &session.layout,
+            &request.label,
+            &request.name,
+            request.preview,
+            request.full,
+        ),
+        None => {
+            let os_constraints = session
+                .manifest
+                .0
+                .get(&request.name)
+                .map(OsConstraints::from_game)
+                .unwrap_or_default();
+            restore_game(
+                &session.config,
+                &mut session.layout.game_layout(&request.name),
+                &request.name,
+                &request.backup,
+                &os_constraints,
+                request.preview,
+                request.full,
+            )
+        }
+    }
+}
+
+/// One game's part of a GUI backup, which the GUI also uses when there's no daemon.
+pub(crate) fn back_up_game(
+    manifest: &Manifest,
+    config: &Config,
+    roots: &Roots,
+    layout: &BackupLayout,
+    label: &BackupLabel,
+    name: &str,
+    preview: bool,
+    full: bool,
+) -> GameOutcome {
+    if name.trim().is_empty() || (full && config.is_game_skipped_for_backup(name)) {
+        return GameOutcome::ignored(None);
+    }
+
+    let Some(scan_info) = crate::api::scan_game(manifest, config, roots, layout, name) else {
+        return GameOutcome::ignored(None);
+    };
+    if !config.is_game_enabled_for_backup(name) && full {
+        return GameOutcome::ignored(Some(scan_info));
+    }
+
+    let backup_info = (!preview).then(|| crate::api::back_up(&scan_info, layout, &config.backup.format, label));
+    GameOutcome {
+        scan_info: Some(scan_info),
+        backup_info,
+        decision: OperationStepDecision::Processed,
+    }
+}
+
+/// One game's part of a GUI restore, which the GUI also uses when there's no daemon.
+pub(crate) fn restore_game(
+    config: &Config,
+    layout: &mut GameLayout,
+    name: &str,
+    backup_id: &BackupId,
+    os_constraints: &OsConstraints,
+    preview: bool,
+    full: bool,
+) -> GameOutcome {
+    let scan_info = layout.scan_for_restoration(
+        name,
+        backup_id,
+        &config.redirects,
+        &config.restore.toggled_paths,
+        &config.restore.toggled_registry,
+        config.restore.remap_user_profile.enabled(),
+        config.restore.prefer_account.as_deref(),
+        config.restore.prefer_wine_user,
+        config.is_registry_enabled(name),
+        os_constraints,
+    );
+    if !config.is_game_enabled_for_restore(name) && full {
+        return GameOutcome::ignored(Some(scan_info));
+    }
+
+    let backup_info =
+        (scan_info.backup.is_some() && !preview).then(|| layout.restore(&scan_info, &config.restore.toggled_registry));
+    GameOutcome {
+        scan_info: Some(scan_info),
+        backup_info,
+        decision: OperationStepDecision::Processed,
+    }
+}
+
+/// This is also used by `ludusavi api`.
+/// The manifest should already incorporate any extensions from the config.
+pub(crate) fn back_up(manifest: &Manifest, config: &Config, request: &BackupRequest) -> Result<Response, Error> {
+    let mut config = config.clone();
+    let mut cache = Cache::load().unwrap_or_default();
+    let mut reporter = request.output.reporter(&config);
+    let summary = operation::back_up(manifest, &mut config, &mut cache, request, &mut reporter)?;
+    Ok(Response::finish(&config, &reporter, &summary))
+}
+
+/// This is also used by `ludusavi api`.
+/// There's nobody to ask about remapping user profiles, so the request must be forced for that.
+pub(crate) fn restore(manifest: &Manifest, config: &Config, request: &RestoreRequest) -> Result<Response, Error> {
+    let mut config = config.clone();
+    let mut cache = Cache::load().unwrap_or_default();
+    let mut reporter = request.output.reporter(&config);
+    let summary = operation::restore(manifest, &mut config, &mut cache, request, &mut reporter, None, &|_| {
+        Err(Error::CliUnableToRequestConfirmation)
+    })?;
+    Ok(Response::finish(&config, &reporter, &summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn can_parse_minimal_request() {
+        assert_eq!(
+            Request::Backup(BackupRequest {
+                games: vec!["foo".to_string()],
+                ..Default::default()
+            }),
+            serde_json::from_str(r#"{"command": "backup", "games": ["foo"]}"#).unwrap(),
+        );
+        assert_eq!(
+            Request::Restore(RestoreRequest {
+                preview: true,
+                ..Default::default()
+            }),
+            serde_json::from_str(r#"{"command": "restore", "preview": true}"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn can_parse_request_with_token() {
+        let envelope: Envelope =
+            serde_json::from_str(r#"{"token": "abc", "command": "finish", "session": 3}"#).unwrap();
+        assert_eq!("abc", envelope.token);
+        assert_eq!(Request::Finish { session: 3 }, envelope.request);
+
+        let envelope = Envelope {
+            token: "abc".to_string(),
+            request: Request::Prepare(PrepareRequest {
+                restoring: true,
+                games: vec!["foo".to_string()],
+                ..Default::default()
+            }),
+        };
+        let serialized = serde_json::to_string(&envelope).unwrap();
+        assert_eq!(
+            envelope.request,
+            serde_json::from_str::<Envelope>(&serialized).unwrap().request
+        );
+    }
+
+    #[test]
+    fn can_round_trip_full_request() {
+        let request = Request::Restore(RestoreRequest {
+            force: true,
+            output: operation::ReportRequest {
+                api: true,
+                verbose: true,
+                ..Default::default()
+            },
+            backup: Some("backup-1".to_string()),
+            only_path: vec!["~/saves/**".to_string()],
+            no_clobber_newer: true,
+            prefer_account: Some("123".to_string()),
+            games: vec!["foo".to_string()],
+            from_list: true,
+            ..Default::default()
+        });
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(request, serde_json::from_str(&serialized).unwrap());
+        assert_eq!(
+            Request::Backup(BackupRequest {
+                output: operation::ReportRequest {
+                    api: true,
+                    ..Default::default()
+                },
+                retry_locked: Some(2),
+                force_game: vec!["foo".to_string()],
+                if_changed_depth: Some(1),
+                ..Default::default()
+            }),
+            serde_json::from_str(
+                r#"{"command": "backup", "api": true, "retryLocked": 2, "forceGame": ["foo"], "ifChangedDepth": 1}"#
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn can_round_trip_game_outcome() {
+        let file = crate::scan::ScannedFile {
+            path: crate::prelude::StrictPath::relative("./drive-0/save.txt".to_string(), Some("/backup".to_string())),
+            ..crate::scan::ScannedFile::new("/save.txt", 1, "abc")
+        };
+        let outcome = GameOutcome {
+            scan_info: Some(ScanInfo {
+                game_name: "foo".to_string(),
+                found_files: [file.clone()].into(),
+                ..Default::default()
+            }),
+            backup_info: Some(BackupInfo {
+                failed_files: [(file, "denied".to_string())].into(),
+                ..Default::default()
+            }),
+            decision: OperationStepDecision::Processed,
+        };
+        let serialized = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(outcome, serde_json::from_str(&serialized).unwrap());
+    }
+
+    fn session() -> Session {
+        Session {
+            config: Config::default(),
+            manifest: Manifest::default(),
+            layout: BackupLayout::new(
+                crate::prelude::StrictPath::new("/backups".to_string()),
+                &Config::default(),
+            ),
+            roots: None,
+        }
+    }
+
+    fn owner(queue: &Queue) -> Option<u64> {
+        match &queue.state.lock().unwrap().owner {
+            None => None,
+            Some(Owner::Cli) => Some(0),
+            Some(Owner::Session { id, .. }) => Some(*id),
+        }
+    }
+
+    #[test]
+    fn queue_keeps_the_turn_for_a_whole_session() {
+        let queue = Queue::default();
+        let id = queue.start_session();
+        queue.ready(id, session());
+
+        std::thread::scope(|scope| {
+            let cli = scope.spawn(|| {
+                let _turn = queue.enter_cli();
+                owner(&queue)
+            });
+
+            // Between the session's requests, the CLI operation still has to wait.
+            drop(queue.join(id).unwrap());
+            std::thread::sleep(Duration::from_millis(50));
+            drop(queue.join(id).unwrap());
+            assert_eq!(Some(id), owner(&queue));
+            assert!(queue.join(id + 1).is_err());
+
+            queue.finish(id);
+            assert_eq!(Some(0), cli.join().unwrap());
+        });
+        assert_eq!(None, owner(&queue));
+        assert!(queue.join(id).is_err());
+    }
+
+    #[test]
+    fn queue_gives_each_session_its_own_id() {
+        let queue = Queue::default();
+        let first = queue.start_session();
+        queue.ready(first, session());
+        queue.finish(first);
+
+        let second = queue.start_session();
+        queue.ready(second, session());
+        assert_ne!(first, second);
+        assert!(queue.join(first).is_err());
+        assert!(queue.join(second).is_ok());
+    }
+
+    #[test]
+    fn queue_releases_an_abandoned_session() {
+        let queue = Queue::default();
+        let id = queue.start_session();
+        queue.ready(id, session());
+        if let Some(Owner::Session { last_used, .. }) = &mut queue.state.lock().unwrap().owner {
+            *last_used -= SESSION_TIMEOUT;
+        }
+
+        drop(queue.enter_cli());
+        assert!(queue.join(id).is_err());
+    }
+
+    #[test]
+    fn can_send_requests_to_a_listener() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-daemon-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        let endpoint = Endpoint::new(dir.clone());
+        assert!(!endpoint.running());
+
+        let (listener, token) = endpoint.bind().unwrap();
+        assert!(endpoint.bind().is_err());
+        std::thread::spawn(move || {
+            listen(listener, &token, &|request| Response {
+                output: format!("{request:?}"),
+                ..Default::default()
+            })
+        });
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(0o700, mode(&dir));
+            assert_eq!(0o600, mode(&endpoint.token_file()));
+        }
+
+        // Checking whether it's running shouldn't get in the way of later requests.
+        assert!(endpoint.running());
+        let request = Request::Backup(BackupRequest {
+            preview: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            Response {
+                output: format!("{request:?}"),
+                ..Default::default()
+            },
+            endpoint.send(&request).unwrap(),
+        );
+
+        std::fs::write(endpoint.token_file(), "wrong").unwrap();
+        assert_eq!(
+            Response {
+                failed: true,
+                error: Some("invalid token".to_string()),
+                ..Default::default()
+            },
+            endpoint.send(&request).unwrap(),
+        );
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
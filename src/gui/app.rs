@@ -1,9 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use iced::{keyboard, widget::scrollable, Alignment, Application, Command, Subscription};
 
 use crate::{
-    cloud::{rclone_monitor, Rclone, Remote},
+    api,
+    cloud::{
+        metered::{self, MeteredDecision},
+        rclone_monitor, Rclone, Remote,
+    },
+    daemon,
     gui::{
         button,
         common::*,
@@ -14,8 +19,9 @@ use crate::{
         style,
         widget::{id, Column, Container, Element, IcedParentExt, Progress, Row},
     },
+    history,
     lang::TRANSLATOR,
-    prelude::{app_dir, get_threads_from_env, initialize_rayon, Error, Finality, StrictPath, SyncDirection},
+    prelude::{get_threads_from_env, initialize_rayon, Error, Finality, StrictPath, SyncDirection},
     resource::{
         cache::Cache,
         config::{Config, CustomGame, CustomGameKind, RootsConfig},
@@ -23,9 +29,10 @@ use crate::{
         ResourceFile, SaveableResourceFile,
     },
     scan::{
-        layout::BackupLayout, prepare_backup_target, registry_compat::RegistryItem, scan_game_for_backup, BackupId,
-        Launchers, OperationStepDecision, SteamShortcuts, TitleFinder,
+        layout::BackupLayout, prepare_backup_target, registry_compat::RegistryItem, BackupId, BackupLabel,
+        OperationStepDecision, OsConstraints, Trigger, TriggerSource,
     },
+    serve::{LastOperationsFile, OperationKind, OperationRecord},
 };
 
 pub struct Executor(tokio::runtime::Runtime);
@@ -64,10 +71,12 @@ pub struct App {
     modal: Option<Modal>,
     backup_screen: screen::Backup,
     restore_screen: screen::Restore,
-    operation_should_cancel: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    operation_should_cancel: api::Cancellation,
     operation_steps: Vec<Command<Message>>,
     operation_steps_active: usize,
     progress: Progress,
+    /// The daemon's session for the current operation, if it's handling the games.
+    daemon_session: Option<u64>,
     backups_to_restore: HashMap<String, BackupId>,
     updating_manifest: bool,
     notify_on_single_game_scanned: Option<(String, Screen)>,
@@ -81,6 +90,12 @@ pub struct App {
 
 impl App {
     fn go_idle(&mut self) {
+        if let Some(session) = self.daemon_session.take() {
+            if let Err(e) = daemon::Endpoint::default().finish(session) {
+                log::warn!("Unable to finish daemon session {session}: {e:?}");
+            }
+        }
+
         if self.exiting {
             std::process::exit(0);
         }
@@ -90,8 +105,7 @@ impl App {
         self.operation_steps_active = 0;
         self.modal = None;
         self.progress.reset();
-        self.operation_should_cancel
-            .swap(false, std::sync::atomic::Ordering::Relaxed);
+        self.operation_should_cancel.reset();
         self.notify_on_single_game_scanned = None;
     }
 
@@ -182,15 +196,17 @@ impl App {
 
         let games = match games {
             Some(games) => {
-                let layout = BackupLayout::new(local.clone(), self.config.backup.retention.clone());
+                let layout = BackupLayout::new(local.clone(), &self.config);
                 let games: Vec<_> = games.iter().filter_map(|x| layout.game_folder(x).leaf()).collect();
                 games
             }
             None => vec![],
         };
 
-        let rclone = Rclone::new(self.config.apps.rclone.clone(), remote);
-        match rclone.sync(local, &self.config.cloud.path, direction, finality, &games) {
+        let rclone = Rclone::new(self.config.apps.rclone.clone(), remote)
+            .with_chunking(&self.config.cloud.chunked_upload)
+            .with_operation_history(&self.config.history);
+        match rclone.sync(local, &self.config.cloud.path, direction, finality, &games, &[]) {
             Ok(process) => {
                 if let Some(sender) = self.rclone_monitor_sender.as_mut() {
                     if standalone {
@@ -212,8 +228,16 @@ impl App {
 
     fn handle_backup(&mut self, phase: BackupPhase) -> Command<Message> {
         match phase {
-            BackupPhase::Confirm { games } => self.show_modal(Modal::ConfirmBackup { games }),
-            BackupPhase::Start { preview, repair, games } => {
+            BackupPhase::Confirm { games } => {
+                self.text_histories.clear_modal_fields();
+                self.show_modal(Modal::ConfirmBackup { games, locked: false })
+            }
+            BackupPhase::Start {
+                preview,
+                repair,
+                games,
+                label,
+            } => {
                 if !self.operation.idle() {
                     return Command::none();
                 }
@@ -229,6 +253,7 @@ impl App {
                 self.operation =
                     Operation::new_backup(if preview { Finality::Preview } else { Finality::Final }, games);
                 self.operation.set_force_new_full_backups(repair);
+                self.operation.set_backup_label(label);
 
                 if !preview {
                     if let Err(e) = prepare_backup_target(&self.config.backup.path) {
@@ -255,19 +280,27 @@ impl App {
                     return self.handle_backup(BackupPhase::Load);
                 }
 
-                let local = self.config.backup.path.clone();
-                let games = self.operation.games();
+                match metered::decide(self.config.cloud.on_metered, false, metered::detect) {
+                    MeteredDecision::Proceed => self.check_cloud_before_backup(),
+                    MeteredDecision::Defer => self.handle_backup(BackupPhase::MeteredCloudChoice { sync: false }),
+                    MeteredDecision::Ask => self.show_modal(Modal::ConfirmMeteredCloudSync),
+                }
+            }
+            BackupPhase::MeteredCloudChoice { sync } => {
+                let close = self.close_modal();
 
-                match self.start_sync_cloud(&local, SyncDirection::Upload, Finality::Preview, games.as_ref(), false) {
-                    Ok(_) => {
-                        // deferring to `transition_from_cloud_step`
-                        Command::none()
-                    }
-                    Err(e) => {
-                        self.operation.push_error(e);
-                        self.handle_backup(BackupPhase::Load)
+                let next = if sync {
+                    self.check_cloud_before_backup()
+                } else {
+                    self.operation.push_error(Error::CloudSyncDeferred);
+                    if !self.cache.cloud.deferred {
+                        self.cache.cloud.deferred = true;
+                        self.cache.save();
                     }
-                }
+                    self.handle_backup(BackupPhase::Load)
+                };
+
+                Command::batch([close, next])
             }
             BackupPhase::Load => {
                 self.invalidate_path_caches();
@@ -311,21 +344,33 @@ impl App {
                         let mut retention = config.backup.retention.clone();
                         retention.force_new_full = should_force_new_full_backups;
 
-                        let roots = config.expanded_roots();
-                        let layout = BackupLayout::new(config.backup.path.clone(), retention);
-                        let title_finder = TitleFinder::new(&manifest, &layout);
-                        let steam = SteamShortcuts::scan();
-                        let launchers = Launchers::scan(&roots, &manifest, &subjects, &title_finder, None);
+                        let layout = BackupLayout::new(config.backup.path.clone(), &config).with_retention(retention);
+
+                        // When a daemon is running, it inspects the roots instead.
+                        let prepared = daemon::Endpoint::default().prepare(daemon::PrepareRequest {
+                            restoring: false,
+                            config: Box::new(config.clone()),
+                            manifest_hash: config.manifest.loaded_hash.clone(),
+                            games: subjects.clone(),
+                            force_new_full: should_force_new_full_backups,
+                        });
+                        let (session, roots) = match prepared {
+                            Ok(session) => (Some(session), None),
+                            Err(e) => {
+                                log::debug!("Backing up without the daemon: {e:?}");
+                                (None, Some(api::Roots::new(&manifest, &config, &layout, &subjects)))
+                            }
+                        };
 
-                        (subjects, manifest, layout, steam, launchers)
+                        (subjects, manifest, layout, roots, session)
                     },
-                    move |(subjects, manifest, layout, steam, heroic)| {
+                    move |(subjects, manifest, layout, roots, session)| {
                         Message::Backup(BackupPhase::RegisterCommands {
                             subjects,
                             manifest,
                             layout: Box::new(layout),
-                            steam,
-                            launchers: heroic,
+                            roots: roots.map(Box::new),
+                            session,
                         })
                     },
                 )
@@ -334,14 +379,15 @@ impl App {
                 subjects,
                 manifest,
                 layout,
-                steam,
-                launchers,
+                roots,
+                session,
             } => {
                 log::info!("beginning backup with {} steps", subjects.len());
+                self.daemon_session = session;
                 let preview = self.operation.preview();
                 let full = self.operation.full();
 
-                if self.operation_should_cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                if self.operation_should_cancel.is_cancelled() {
                     self.go_idle();
                     return Command::none();
                 }
@@ -369,66 +415,63 @@ impl App {
                 self.register_notify_on_single_game_scanned();
 
                 let config = std::sync::Arc::new(self.config.clone());
-                let roots = std::sync::Arc::new(config.expanded_roots());
+                let manifest = std::sync::Arc::new(manifest);
+                // Without a daemon, or if it stops responding, the roots are inspected here.
+                let roots_cell = std::sync::Arc::new(std::sync::OnceLock::new());
+                if let Some(roots) = roots {
+                    _ = roots_cell.set(*roots);
+                }
+                let all_subjects = std::sync::Arc::new(subjects.clone());
                 let layout = std::sync::Arc::new(*layout);
-                let launchers = std::sync::Arc::new(launchers);
-                let filter = std::sync::Arc::new(self.config.backup.filter.clone());
-                let steam_shortcuts = std::sync::Arc::new(steam);
+                let label = std::sync::Arc::new(BackupLabel {
+                    trigger: Trigger::new(TriggerSource::Gui),
+                    manifest_hash: self.config.manifest.loaded_hash.clone(),
+                    ..self.operation.backup_label()
+                });
 
                 for key in subjects {
-                    let game = manifest.0[&key].clone();
                     let config = config.clone();
-                    let roots = roots.clone();
-                    let launchers = launchers.clone();
+                    let manifest = manifest.clone();
+                    let roots = roots_cell.clone();
+                    let all_subjects = all_subjects.clone();
                     let layout = layout.clone();
-                    let filter = filter.clone();
-                    let steam_shortcuts = steam_shortcuts.clone();
+                    let label = label.clone();
                     let cancel_flag = self.operation_should_cancel.clone();
                     self.operation_steps.push(Command::perform(
                         async move {
-                            if key.trim().is_empty() {
-                                return (None, None, OperationStepDecision::Ignored);
-                            }
-                            if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                            if cancel_flag.is_cancelled() {
                                 // TODO: https://github.com/hecrj/iced/issues/436
                                 std::thread::sleep(std::time::Duration::from_millis(1));
-                                return (None, None, OperationStepDecision::Cancelled);
+                                return daemon::GameOutcome {
+                                    decision: OperationStepDecision::Cancelled,
+                                    ..Default::default()
+                                };
                             }
 
-                            let previous =
-                                layout.latest_backup(&key, false, &config.redirects, &config.restore.toggled_paths);
-
-                            let scan_info = scan_game_for_backup(
-                                &game,
-                                &key,
-                                &roots,
-                                &StrictPath::from_std_path_buf(&app_dir()),
-                                &launchers,
-                                &filter,
-                                &None,
-                                // &ranking,
-                                &config.backup.toggled_paths,
-                                &config.backup.toggled_registry,
-                                previous,
-                                &config.redirects,
-                                &steam_shortcuts,
-                            );
-                            if !config.is_game_enabled_for_backup(&key) && full {
-                                return (Some(scan_info), None, OperationStepDecision::Ignored);
+                            if let Some(session) = session {
+                                let request = daemon::GameRequest {
+                                    session,
+                                    name: key.clone(),
+                                    preview,
+                                    full,
+                                    label: (*label).clone(),
+                                    ..Default::default()
+                                };
+                                match daemon::Endpoint::default().process_game(request) {
+                                    Ok(outcome) => return outcome,
+                                    Err(e) => log::warn!("Unable to back up {key} through the daemon: {e:?}"),
+                                }
                             }
 
-                            let backup_info = if !preview {
-                                Some(layout.game_layout(&key).back_up(
-                                    &scan_info,
-                                    &chrono::Utc::now(),
-                                    &config.backup.format,
-                                ))
-                            } else {
-                                None
-                            };
-                            (Some(scan_info), backup_info, OperationStepDecision::Processed)
+                            let roots =
+                                roots.get_or_init(|| api::Roots::new(&manifest, &config, &layout, &all_subjects));
+                            daemon::back_up_game(&manifest, &config, roots, &layout, &label, &key, preview, full)
                         },
-                        move |(scan_info, backup_info, decision)| {
+                        move |daemon::GameOutcome {
+                                  scan_info,
+                                  backup_info,
+                                  decision,
+                              }| {
                             Message::Backup(BackupPhase::GameScanned {
                                 scan_info,
                                 backup_info,
@@ -512,7 +555,7 @@ impl App {
                 }
 
                 let local = self.config.backup.path.clone();
-                let games = self.operation.games();
+                let games = self.backup_cloud_games();
 
                 match self.start_sync_cloud(&local, SyncDirection::Upload, Finality::Final, games.as_ref(), false) {
                     Ok(_) => {
@@ -556,6 +599,10 @@ impl App {
                     self.operation.push_error(Error::SomeEntriesFailed);
                 }
 
+                if !preview {
+                    self.record_last_operation(OperationKind::Backup);
+                }
+
                 let errors = self.operation.errors().cloned();
                 self.go_idle();
 
@@ -636,25 +683,46 @@ impl App {
 
                 Command::perform(
                     async move {
-                        let layout = BackupLayout::new(restore_path, config.backup.retention.clone());
+                        let layout = BackupLayout::new(restore_path, &config);
                         let restorables = layout.restorable_games();
-                        (layout, restorables)
+
+                        let prepared = daemon::Endpoint::default().prepare(daemon::PrepareRequest {
+                            restoring: true,
+                            config: Box::new((*config).clone()),
+                            manifest_hash: config.manifest.loaded_hash.clone(),
+                            ..Default::default()
+                        });
+                        let session = match prepared {
+                            Ok(session) => Some(session),
+                            Err(e) => {
+                                log::debug!("Restoring without the daemon: {e:?}");
+                                None
+                            }
+                        };
+
+                        (layout, restorables, session)
                     },
-                    move |(layout, restorables)| {
-                        Message::Restore(RestorePhase::RegisterCommands { layout, restorables })
+                    move |(layout, restorables, session)| {
+                        Message::Restore(RestorePhase::RegisterCommands {
+                            layout,
+                            restorables,
+                            session,
+                        })
                     },
                 )
             }
             RestorePhase::RegisterCommands {
                 mut restorables,
                 layout,
+                session,
             } => {
                 log::info!("beginning restore with {} steps", restorables.len());
+                self.daemon_session = session;
                 let preview = self.operation.preview();
                 let full = self.operation.full();
                 let games = self.operation.games();
 
-                if self.operation_should_cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                if self.operation_should_cancel.is_cancelled() {
                     self.go_idle();
                     return Command::none();
                 }
@@ -695,35 +763,60 @@ impl App {
                     let layout = layout.clone();
                     let cancel_flag = self.operation_should_cancel.clone();
                     let backup_id = self.backups_to_restore.get(&name).cloned().unwrap_or(BackupId::Latest);
+                    let os_constraints = self
+                        .manifest
+                        .0
+                        .get(&name)
+                        .map(OsConstraints::from_game)
+                        .unwrap_or_default();
                     self.operation_steps.push(Command::perform(
                         async move {
-                            let mut layout = layout.game_layout(&name);
-
-                            if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                            if cancel_flag.is_cancelled() {
                                 // TODO: https://github.com/hecrj/iced/issues/436
                                 std::thread::sleep(std::time::Duration::from_millis(1));
-                                return (None, None, OperationStepDecision::Cancelled, layout);
+                                let outcome = daemon::GameOutcome {
+                                    decision: OperationStepDecision::Cancelled,
+                                    ..Default::default()
+                                };
+                                return (outcome, layout.game_layout(&name));
                             }
 
-                            let scan_info = layout.scan_for_restoration(
+                            if let Some(session) = session {
+                                let request = daemon::GameRequest {
+                                    session,
+                                    name: name.clone(),
+                                    preview,
+                                    full,
+                                    backup: backup_id.clone(),
+                                    ..Default::default()
+                                };
+                                match daemon::Endpoint::default().process_game(request) {
+                                    // This comes after the request in case the daemon migrated a legacy backup.
+                                    Ok(outcome) => return (outcome, layout.game_layout(&name)),
+                                    Err(e) => log::warn!("Unable to restore {name} through the daemon: {e:?}"),
+                                }
+                            }
+
+                            let mut game_layout = layout.game_layout(&name);
+                            let outcome = daemon::restore_game(
+                                &config,
+                                &mut game_layout,
                                 &name,
                                 &backup_id,
-                                &config.redirects,
-                                &config.restore.toggled_paths,
-                                &config.restore.toggled_registry,
+                                &os_constraints,
+                                preview,
+                                full,
                             );
-                            if !config.is_game_enabled_for_restore(&name) && full {
-                                return (Some(scan_info), None, OperationStepDecision::Ignored, layout);
-                            }
-
-                            let backup_info = if scan_info.backup.is_some() && !preview {
-                                Some(layout.restore(&scan_info, &config.restore.toggled_registry))
-                            } else {
-                                None
-                            };
-                            (Some(scan_info), backup_info, OperationStepDecision::Processed, layout)
+                            (outcome, game_layout)
                         },
-                        move |(scan_info, backup_info, decision, game_layout)| {
+                        move |(
+                            daemon::GameOutcome {
+                                scan_info,
+                                backup_info,
+                                decision,
+                            },
+                            game_layout,
+                        )| {
                             Message::Restore(RestorePhase::GameScanned {
                                 scan_info,
                                 backup_info,
@@ -805,6 +898,7 @@ impl App {
             RestorePhase::Done => {
                 log::info!("completed restore");
                 let mut failed = false;
+                let preview = self.operation.preview();
                 let full = self.operation.full();
 
                 self.handle_notify_on_single_game_scanned();
@@ -831,6 +925,10 @@ impl App {
                     self.operation.push_error(Error::SomeEntriesFailed);
                 }
 
+                if !preview {
+                    self.record_last_operation(OperationKind::Restore);
+                }
+
                 let errors = self.operation.errors().cloned();
                 self.go_idle();
 
@@ -840,11 +938,54 @@ impl App {
                     }
                 }
 
+                let accounts: BTreeSet<_> = self
+                    .restore_screen
+                    .log
+                    .entries
+                    .iter()
+                    .flat_map(|entry| entry.scan_info.ambiguous_targets.values().flatten())
+                    .filter_map(|x| x.leaf())
+                    .collect();
+                if !accounts.is_empty() {
+                    return self.show_modal(Modal::ChooseAccount {
+                        accounts: accounts.into_iter().collect(),
+                    });
+                }
+
                 Command::none()
             }
         }
     }
 
+    /// Keep track of the outcome for `ludusavi serve` and `ludusavi history`.
+    fn record_last_operation(&self, kind: OperationKind) {
+        let (log, restoring, operation) = match kind {
+            OperationKind::Backup => (&self.backup_screen.log, false, history::Operation::Backup),
+            OperationKind::Restore => (&self.restore_screen.log, true, history::Operation::Restore),
+        };
+        let failed = self.operation.errors().is_some_and(|x| !x.is_empty());
+        let status = log.compute_operation_status(&self.config, restoring);
+        let failed_games = log
+            .entries
+            .iter()
+            .filter(|x| x.backup_info.as_ref().is_some_and(|x| !x.successful()))
+            .count();
+
+        history::History::default().record(
+            &self.config.history,
+            &history::Record::for_games(
+                operation,
+                self.progress.start_time().unwrap_or_else(chrono::Utc::now),
+                Some(&status),
+                failed_games,
+            ),
+        );
+        LastOperationsFile::default().record(
+            kind,
+            OperationRecord::new(self.progress.start_time(), failed, Some(status)),
+        );
+    }
+
     fn handle_validation(&mut self, phase: ValidatePhase) -> Command<Message> {
         match phase {
             ValidatePhase::Start => {
@@ -875,9 +1016,9 @@ impl App {
 
                 Command::perform(
                     async move {
-                        let layout = BackupLayout::new(restore_path, config.backup.retention.clone());
+                        let layout = BackupLayout::new(restore_path, &config);
                         let subjects = layout.restorable_games();
-                        (layout, subjects)
+                        (Box::new(layout), subjects)
                     },
                     move |(layout, subjects)| {
                         Message::ValidateBackups(ValidatePhase::RegisterCommands { layout, subjects })
@@ -887,7 +1028,7 @@ impl App {
             ValidatePhase::RegisterCommands { subjects, layout } => {
                 log::info!("beginning validation with {} steps", subjects.len());
 
-                if self.operation_should_cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                if self.operation_should_cancel.is_cancelled() {
                     self.go_idle();
                     return Command::none();
                 }
@@ -899,7 +1040,7 @@ impl App {
 
                 self.progress.set_max(subjects.len() as f32);
 
-                let layout = std::sync::Arc::new(layout);
+                let layout = std::sync::Arc::new(*layout);
 
                 for name in subjects {
                     let layout = layout.clone();
@@ -907,7 +1048,7 @@ impl App {
                     let backup_id = self.backups_to_restore.get(&name).cloned().unwrap_or(BackupId::Latest);
                     self.operation_steps.push(Command::perform(
                         async move {
-                            if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                            if cancel_flag.is_cancelled() {
                                 // TODO: https://github.com/hecrj/iced/issues/436
                                 std::thread::sleep(std::time::Duration::from_millis(1));
                                 return (name, true);
@@ -962,6 +1103,31 @@ impl App {
         }
     }
 
+    fn check_cloud_before_backup(&mut self) -> Command<Message> {
+        let local = self.config.backup.path.clone();
+        let games = self.backup_cloud_games();
+
+        match self.start_sync_cloud(&local, SyncDirection::Upload, Finality::Preview, games.as_ref(), false) {
+            Ok(_) => {
+                // deferring to `transition_from_cloud_step`
+                Command::none()
+            }
+            Err(e) => {
+                self.operation.push_error(e);
+                self.handle_backup(BackupPhase::Load)
+            }
+        }
+    }
+
+    /// After a deferred sync, there may be pending changes for games outside of this backup.
+    fn backup_cloud_games(&self) -> Option<Vec<String>> {
+        if self.cache.cloud.deferred {
+            None
+        } else {
+            self.operation.games()
+        }
+    }
+
     fn transition_from_cloud_step(&mut self) -> Option<Command<Message>> {
         let synced = self.operation.cloud_changes() == 0;
 
@@ -988,8 +1154,7 @@ impl App {
     }
 
     fn cancel_operation(&mut self) -> Command<Message> {
-        self.operation_should_cancel
-            .swap(true, std::sync::atomic::Ordering::Relaxed);
+        self.operation_should_cancel.cancel();
         self.operation_steps.clear();
         self.operation.flag_cancel();
         if self.operation.is_cloud_active() {
@@ -1120,8 +1285,12 @@ impl Application for App {
         let mut cache = Cache::load().unwrap_or_default().migrate_config(&mut config);
         TRANSLATOR.set_language(config.language);
         let manifest = if Manifest::path().exists() {
-            match Manifest::load() {
-                Ok(y) => y,
+            match Manifest::load_with_hash() {
+                Ok((y, hash, fallback)) => {
+                    config.manifest.loaded_hash = hash;
+                    config.manifest.loaded_fallback = fallback;
+                    y
+                }
                 Err(e) => {
                     errors.push(e);
                     Manifest::default()
@@ -1253,9 +1422,11 @@ impl Application for App {
 
                 self.cache.save();
 
-                match Manifest::load() {
-                    Ok(x) => {
+                match Manifest::load_with_hash() {
+                    Ok((x, hash, fallback)) => {
                         self.manifest = x;
+                        self.config.manifest.loaded_hash = hash;
+                        self.config.manifest.loaded_fallback = fallback;
                     }
                     Err(e) => {
                         errors.push(e);
@@ -1294,6 +1465,11 @@ impl Application for App {
                     self.show_modal(Modal::ConfirmAddMissingRoots(missing))
                 }
             }
+            Message::PreferAccount(account) => {
+                self.config.restore.prefer_account = Some(account);
+                self.config.save();
+                self.close_modal()
+            }
             Message::ConfirmAddMissingRoots(missing) => {
                 for root in missing {
                     self.text_histories.roots.push(TextHistory::raw(&root.path.render()));
@@ -1675,7 +1851,8 @@ impl Application for App {
                         restoring,
                     );
                 } else {
-                    self.config.backup.toggled_paths.toggle(&name, &path);
+                    let junk = self.config.scan.exclude_common_junk && crate::scan::junk::is_junk(&path);
+                    self.config.backup.toggled_paths.toggle_with_default(&name, &path, junk);
                     self.backup_screen.log.refresh_game_tree(
                         &name,
                         &self.config,
@@ -2059,6 +2236,7 @@ impl Application for App {
                             ModalInputKind::Port => self.text_histories.modal.port.apply(shortcut),
                             ModalInputKind::Username => self.text_histories.modal.username.apply(shortcut),
                             ModalInputKind::Password => self.text_histories.modal.password.apply(shortcut),
+                            ModalInputKind::Comment => self.text_histories.modal.comment.apply(shortcut),
                         }
                         return Command::none();
                     }
@@ -2099,6 +2277,11 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
+            Message::SelectedBackupMode(mode) => {
+                self.config.backup.mode = mode;
+                self.config.save();
+                Command::none()
+            }
             Message::SelectedBackupCompression(compression) => {
                 self.config.backup.format.zip.compression = compression;
                 self.config.save();
@@ -2118,11 +2301,17 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
+            Message::SelectedOnMetered(policy) => {
+                self.config.cloud.on_metered = policy;
+                self.config.save();
+                Command::none()
+            }
             Message::GameAction { action, game } => match action {
                 GameAction::PreviewBackup => self.handle_backup(BackupPhase::Start {
                     preview: true,
                     repair: false,
                     games: Some(vec![game]),
+                    label: Default::default(),
                 }),
                 GameAction::Backup { confirm } => {
                     if confirm {
@@ -2134,6 +2323,7 @@ impl Application for App {
                             preview: false,
                             repair: false,
                             games: Some(vec![game]),
+                            label: Default::default(),
                         })
                     }
                 }
@@ -2160,6 +2350,17 @@ impl Application for App {
                     self.restore_screen.log.toggle_locked(&game);
                     Command::none()
                 }
+                GameAction::EnableRegistry | GameAction::DisableRegistry => {
+                    self.config
+                        .set_registry_enabled(&game, action == GameAction::EnableRegistry);
+                    self.config.save();
+                    Command::none()
+                }
+                GameAction::EnableForBackup => self.update(Message::ToggleGameListEntryEnabled {
+                    name: game,
+                    enabled: true,
+                    restoring: false,
+                }),
             },
             Message::Scrolled { subject, position } => {
                 self.scroll_offsets.insert(subject, position);
@@ -2188,6 +2389,11 @@ impl Application for App {
                 self.config.save();
                 Command::none()
             }
+            Message::SetRegistryEnabled(value) => {
+                self.config.scan.registry = value;
+                self.config.save();
+                Command::none()
+            }
             Message::FilterDuplicates { restoring, game } => {
                 let log = if restoring {
                     &mut self.restore_screen.log
@@ -2362,6 +2568,14 @@ impl Application for App {
                         }
                     }
                     rclone_monitor::Event::Succeeded => {
+                        if self.cache.cloud.deferred
+                            && matches!(self.operation, Operation::Backup { .. })
+                            && self.operation.integrated_syncing_cloud()
+                        {
+                            self.cache.cloud.deferred = false;
+                            self.cache.save();
+                        }
+
                         if let Some(cmd) = self.transition_from_cloud_step() {
                             return cmd;
                         }
@@ -2411,6 +2625,14 @@ impl Application for App {
                             *provider = new;
                         }
                     }
+                    ModalField::Comment(new) => {
+                        self.text_histories.modal.comment.push(&new);
+                    }
+                    ModalField::Locked(new) => {
+                        if let Some(Modal::ConfirmBackup { locked, .. }) = self.modal.as_mut() {
+                            *locked = new;
+                        }
+                    }
                 }
                 Command::none()
             }
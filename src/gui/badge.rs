@@ -80,6 +80,11 @@ impl Badge {
         }
     }
 
+    pub fn tooltip(mut self, tooltip: String) -> Self {
+        self.tooltip = Some(tooltip);
+        self
+    }
+
     pub fn on_press(mut self, message: Message) -> Self {
         self.on_press = Some(message);
         self
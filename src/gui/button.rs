@@ -350,6 +350,7 @@ pub fn backup_preview<'a>(ongoing: &Operation) -> Element<'a> {
                 preview: true,
                 repair: false,
                 games: None,
+                label: Default::default(),
             })),
             Operation::Backup {
                 finality: Finality::Preview,
@@ -3,6 +3,7 @@ use std::collections::BTreeSet;
 use iced::Length;
 
 use crate::{
+    api,
     cloud::{rclone_monitor, Remote, RemoteChoice},
     gui::{
         icon::Icon,
@@ -12,8 +13,8 @@ use crate::{
     prelude::{CommandError, Error, Finality, Privacy, StrictPath, SyncDirection},
     resource::{
         config::{
-            BackupFormat, CustomGameKind, RedirectKind, RootsConfig, SecondaryManifestConfigKind, SortKey, Theme,
-            ZipCompression,
+            BackupFormat, BackupMode, CustomGameKind, OnMetered, RedirectKind, RootsConfig,
+            SecondaryManifestConfigKind, SortKey, Theme, ZipCompression,
         },
         manifest::{Manifest, ManifestUpdate, Store},
     },
@@ -21,7 +22,7 @@ use crate::{
         game_filter,
         layout::{Backup, BackupLayout, GameLayout},
         registry_compat::RegistryItem,
-        BackupInfo, Launchers, OperationStepDecision, ScanInfo, SteamShortcuts,
+        BackupInfo, BackupLabel, OperationStepDecision, ScanInfo,
     },
 };
 
@@ -40,15 +41,22 @@ pub enum BackupPhase {
         /// Was this backup triggered by a validation check?
         repair: bool,
         games: Option<Vec<String>>,
+        label: BackupLabel,
     },
     CloudCheck,
+    /// Whether to synchronize with the cloud despite a metered connection.
+    MeteredCloudChoice {
+        sync: bool,
+    },
     Load,
     RegisterCommands {
         subjects: Vec<String>,
         manifest: Manifest,
         layout: Box<BackupLayout>,
-        steam: SteamShortcuts,
-        launchers: Launchers,
+        /// Not inspected yet when a daemon is handling the games.
+        roots: Option<Box<api::Roots>>,
+        /// For the daemon, if one is handling the games.
+        session: Option<u64>,
     },
     GameScanned {
         scan_info: Option<ScanInfo>,
@@ -73,6 +81,8 @@ pub enum RestorePhase {
     RegisterCommands {
         restorables: Vec<String>,
         layout: BackupLayout,
+        /// For the daemon, if one is handling the games.
+        session: Option<u64>,
     },
     GameScanned {
         scan_info: Option<ScanInfo>,
@@ -89,7 +99,7 @@ pub enum ValidatePhase {
     Load,
     RegisterCommands {
         subjects: Vec<String>,
-        layout: BackupLayout,
+        layout: Box<BackupLayout>,
     },
     GameScanned {
         game: String,
@@ -117,6 +127,7 @@ pub enum Message {
     EditedRestoreSource(String),
     FindRoots,
     ConfirmAddMissingRoots(Vec<RootsConfig>),
+    PreferAccount(String),
     EditedRoot(EditAction),
     EditedSecondaryManifest(EditAction),
     SelectedRootStore(usize, Store),
@@ -211,10 +222,12 @@ pub enum Message {
     SelectedLanguage(Language),
     SelectedTheme(Theme),
     SelectedBackupFormat(BackupFormat),
+    SelectedBackupMode(BackupMode),
     SelectedBackupCompression(ZipCompression),
     EditedCompressionLevel(i32),
     ToggleBackupSettings,
     ToggleCloudSynchronize,
+    SelectedOnMetered(OnMetered),
     GameAction {
         action: GameAction,
         game: String,
@@ -235,6 +248,7 @@ pub enum Message {
     SetShowDeselectedGames(bool),
     SetShowUnchangedGames(bool),
     SetShowUnscannedGames(bool),
+    SetRegistryEnabled(bool),
     FilterDuplicates {
         restoring: bool,
         game: Option<String>,
@@ -279,6 +293,7 @@ pub enum Operation {
         errors: Vec<Error>,
         cloud_changes: i64,
         force_new_full_backup: bool,
+        label: BackupLabel,
     },
     Restore {
         finality: Finality,
@@ -317,6 +332,7 @@ impl Operation {
             errors: vec![],
             cloud_changes: 0,
             force_new_full_backup: false,
+            label: BackupLabel::default(),
         }
     }
 
@@ -549,6 +565,26 @@ impl Operation {
             Operation::Cloud { .. } => (),
         }
     }
+
+    pub fn backup_label(&self) -> BackupLabel {
+        match self {
+            Operation::Backup { label, .. } => label.clone(),
+            Operation::Idle
+            | Operation::Restore { .. }
+            | Operation::ValidateBackups { .. }
+            | Operation::Cloud { .. } => BackupLabel::default(),
+        }
+    }
+
+    pub fn set_backup_label(&mut self, value: BackupLabel) {
+        match self {
+            Operation::Backup { label, .. } => *label = value,
+            Operation::Idle
+            | Operation::Restore { .. }
+            | Operation::ValidateBackups { .. }
+            | Operation::Cloud { .. } => (),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -661,9 +697,11 @@ impl UndoSubject {
             | UndoSubject::CloudRemoteId
             | UndoSubject::CloudPath => Privacy::Public,
             UndoSubject::ModalField(field) => match field {
-                ModalInputKind::Url | ModalInputKind::Host | ModalInputKind::Port | ModalInputKind::Username => {
-                    Privacy::Public
-                }
+                ModalInputKind::Url
+                | ModalInputKind::Host
+                | ModalInputKind::Port
+                | ModalInputKind::Username
+                | ModalInputKind::Comment => Privacy::Public,
                 ModalInputKind::Password => Privacy::Private,
             },
         }
@@ -735,6 +773,9 @@ pub enum GameAction {
     Comment,
     Lock,
     Unlock,
+    EnableRegistry,
+    DisableRegistry,
+    EnableForBackup,
 }
 
 impl GameAction {
@@ -745,6 +786,8 @@ impl GameAction {
         invented: bool,
         has_backups: bool,
         locked: bool,
+        registry_enabled: bool,
+        can_enable_for_backup: bool,
     ) -> Vec<Self> {
         let mut options = vec![];
 
@@ -758,6 +801,10 @@ impl GameAction {
             }
         }
 
+        if !restoring && can_enable_for_backup {
+            options.push(Self::EnableForBackup);
+        }
+
         if !restoring && !customized {
             options.push(Self::Customize);
         }
@@ -772,6 +819,14 @@ impl GameAction {
             }
         }
 
+        if cfg!(target_os = "windows") {
+            if registry_enabled {
+                options.push(Self::DisableRegistry);
+            } else {
+                options.push(Self::EnableRegistry);
+            }
+        }
+
         if !invented {
             options.push(Self::Wiki);
         }
@@ -794,6 +849,9 @@ impl GameAction {
             GameAction::Comment => Icon::Comment,
             GameAction::Lock => Icon::Lock,
             GameAction::Unlock => Icon::LockOpen,
+            GameAction::EnableRegistry => Icon::Add,
+            GameAction::EnableForBackup => Icon::Add,
+            GameAction::DisableRegistry => Icon::VisibilityOff,
         }
     }
 }
@@ -821,6 +879,9 @@ impl ToString for GameAction {
             Self::Comment => TRANSLATOR.comment_button(),
             Self::Lock => TRANSLATOR.lock_button(),
             Self::Unlock => TRANSLATOR.unlock_button(),
+            Self::EnableRegistry => TRANSLATOR.enable_registry_button(),
+            Self::DisableRegistry => TRANSLATOR.disable_registry_button(),
+            Self::EnableForBackup => TRANSLATOR.enable_for_backup_button(),
         }
     }
 }
@@ -250,6 +250,7 @@ pub fn custom_games<'a>(
                                                 games: Some(vec![config.custom_games[i].name.clone()]),
                                                 preview: true,
                                                 repair: false,
+                                                label: Default::default(),
                                             }),
                                             operating,
                                             config.is_custom_game_individually_scannable(i),
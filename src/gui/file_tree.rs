@@ -435,7 +435,7 @@ impl FileTree {
         for item in scan_info.found_files.iter() {
             let mut successful = true;
             if let Some(backup_info) = &backup_info {
-                if backup_info.failed_files.contains(item) {
+                if backup_info.failed_files.contains_key(item) {
                     successful = false;
                 }
             }
@@ -479,7 +479,7 @@ impl FileTree {
         for item in scan_info.found_registry_keys.iter() {
             let mut successful = true;
             if let Some(backup_info) = &backup_info {
-                if backup_info.failed_registry.contains(&item.path) {
+                if backup_info.failed_registry.contains_key(&item.path) {
                     successful = false;
                 }
             }
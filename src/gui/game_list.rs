@@ -17,9 +17,10 @@ use crate::{
         },
     },
     lang::TRANSLATOR,
+    prelude::VERSION,
     resource::{
         cache::Cache,
-        config::{Config, Sort},
+        config::{BackupMode, Config, Sort},
         manifest::{Manifest, Os},
     },
     scan::{layout::GameLayout, BackupInfo, DuplicateDetector, OperationStatus, ScanChange, ScanInfo},
@@ -107,6 +108,7 @@ impl GameListEntry {
                                         preview: true,
                                         repair: false,
                                         games: Some(vec![self.scan_info.game_name.clone()]),
+                                        label: Default::default(),
                                     }))
                                 }
                             } else {
@@ -187,6 +189,20 @@ impl GameListEntry {
                                     (os != Os::HOST && os != Os::Other).then(|| Badge::new(&format!("{os:?}")).view())
                                 })
                         })
+                        .push_some(|| {
+                            let backup = self.scan_info.backup.as_ref()?;
+                            let version = backup.app_version()?;
+                            (version != *VERSION).then(|| {
+                                Tooltip::new(
+                                    Badge::new(&format!("v{version}")).view(),
+                                    TRANSLATOR.backup_provenance(version, backup.manifest_hash()),
+                                    tooltip::Position::Top,
+                                )
+                                .size(16)
+                                .gap(5)
+                                .style(style::Container::Tooltip)
+                            })
+                        })
                         .push_some(|| {
                             self.scan_info
                                 .backup
@@ -299,6 +315,9 @@ impl GameListEntry {
                                                 .as_ref()
                                                 .map(|backup| backup.locked())
                                                 .unwrap_or_default(),
+                                            config.is_registry_enabled(&self.scan_info.game_name),
+                                            config.backup.mode == BackupMode::OnlyEnabledList
+                                                && !config.is_game_enabled_for_backup(&self.scan_info.game_name),
                                         );
                                         let game_name = self.scan_info.game_name.clone();
 
@@ -11,11 +11,12 @@ use crate::{
         common::{BackupPhase, Message, RestorePhase, ScrollSubject, UndoSubject},
         shortcuts::TextHistories,
         style,
-        widget::{pick_list, text, Column, Container, Element, IcedParentExt, Row, Space},
+        widget::{checkbox, pick_list, text, Column, Container, Element, IcedParentExt, Row, Space},
     },
     lang::TRANSLATOR,
     prelude::{Error, Finality, SyncDirection},
     resource::config::{Config, RootsConfig},
+    scan::BackupLabel,
 };
 
 const CHANGES_PER_PAGE: usize = 500;
@@ -33,6 +34,7 @@ pub enum ModalInputKind {
     Port,
     Username,
     Password,
+    Comment,
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +45,8 @@ pub enum ModalField {
     Username(String),
     Password(String),
     WebDavProvider(WebDavProvider),
+    Comment(String),
+    Locked(bool),
 }
 
 impl ModalField {
@@ -53,6 +57,7 @@ impl ModalField {
             ModalInputKind::Port => TRANSLATOR.port_label(),
             ModalInputKind::Username => TRANSLATOR.username_label(),
             ModalInputKind::Password => TRANSLATOR.password_label(),
+            ModalInputKind::Comment => TRANSLATOR.comment_field(),
         };
 
         Row::new()
@@ -88,6 +93,8 @@ pub enum Modal {
     Exiting,
     ConfirmBackup {
         games: Option<Vec<String>>,
+        /// Whether to lock the new backups.
+        locked: bool,
     },
     ConfirmRestore {
         games: Option<Vec<String>>,
@@ -98,6 +105,8 @@ pub enum Modal {
         games: BTreeSet<String>,
     },
     UpdatingManifest,
+    /// A backup is waiting to know whether it should synchronize with the cloud.
+    ConfirmMeteredCloudSync,
     ConfirmCloudSync {
         local: String,
         cloud: String,
@@ -113,16 +122,24 @@ pub enum Modal {
     ConfigureWebDavRemote {
         provider: WebDavProvider,
     },
+    /// Some files belong to a Steam account that doesn't exist on this system,
+    /// and there are several others that they could be restored into.
+    ChooseAccount {
+        accounts: Vec<String>,
+    },
 }
 
 impl Modal {
     pub fn variant(&self) -> ModalVariant {
         match self {
             Self::Exiting | Self::UpdatingManifest => ModalVariant::Loading,
-            Self::Error { .. } | Self::Errors { .. } | Self::NoMissingRoots => ModalVariant::Info,
+            Self::Error { .. } | Self::Errors { .. } | Self::NoMissingRoots | Self::ChooseAccount { .. } => {
+                ModalVariant::Info
+            }
             Self::ConfirmBackup { .. }
             | Self::ConfirmRestore { .. }
             | Self::ConfirmAddMissingRoots(..)
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
             | Self::ConfigureWebDavRemote { .. } => ModalVariant::Confirm,
@@ -158,6 +175,7 @@ impl Modal {
             Self::NoMissingRoots => TRANSLATOR.no_missing_roots(),
             Self::ConfirmAddMissingRoots(missing) => TRANSLATOR.confirm_add_missing_roots(missing),
             Self::UpdatingManifest => TRANSLATOR.updating_manifest(),
+            Self::ConfirmMeteredCloudSync => TRANSLATOR.confirm_metered_cloud_sync(),
             Self::BackupValidation { games } => {
                 if games.is_empty() {
                     TRANSLATOR.backups_are_valid()
@@ -183,19 +201,27 @@ impl Modal {
             Self::ConfigureFtpRemote { .. } => RemoteChoice::Ftp.to_string(),
             Self::ConfigureSmbRemote { .. } => RemoteChoice::Smb.to_string(),
             Self::ConfigureWebDavRemote { .. } => RemoteChoice::WebDav.to_string(),
+            Self::ChooseAccount { .. } => TRANSLATOR.choose_account_for_restore(),
         }
     }
 
     pub fn message(&self, histories: &TextHistories) -> Option<Message> {
         match self {
-            Self::Error { .. } | Self::Errors { .. } | Self::NoMissingRoots | Self::BackupValidation { .. } => {
-                Some(Message::CloseModal)
-            }
+            Self::Error { .. }
+            | Self::Errors { .. }
+            | Self::NoMissingRoots
+            | Self::BackupValidation { .. }
+            | Self::ChooseAccount { .. } => Some(Message::CloseModal),
             Self::Exiting => None,
-            Self::ConfirmBackup { games } => Some(Message::Backup(BackupPhase::Start {
+            Self::ConfirmBackup { games, locked } => Some(Message::Backup(BackupPhase::Start {
                 preview: false,
                 repair: false,
                 games: games.clone(),
+                label: BackupLabel {
+                    comment: Some(histories.modal.comment.current()).filter(|x| !x.is_empty()),
+                    locked: *locked,
+                    ..Default::default()
+                },
             })),
             Self::ConfirmRestore { games } => Some(Message::Restore(RestorePhase::Start {
                 preview: false,
@@ -203,6 +229,7 @@ impl Modal {
             })),
             Self::ConfirmAddMissingRoots(missing) => Some(Message::ConfirmAddMissingRoots(missing.clone())),
             Self::UpdatingManifest => None,
+            Self::ConfirmMeteredCloudSync => Some(Message::Backup(BackupPhase::MeteredCloudChoice { sync: true })),
             modal @ Self::ConfirmCloudSync {
                 direction,
                 syncing,
@@ -276,6 +303,27 @@ impl Modal {
         }
     }
 
+    /// Most modals can simply be dismissed, but some are holding up an operation.
+    fn negative_message(&self) -> Message {
+        match self {
+            Self::ConfirmMeteredCloudSync => Message::Backup(BackupPhase::MeteredCloudChoice { sync: false }),
+            Self::Error { .. }
+            | Self::Errors { .. }
+            | Self::Exiting
+            | Self::ConfirmBackup { .. }
+            | Self::ConfirmRestore { .. }
+            | Self::NoMissingRoots
+            | Self::ConfirmAddMissingRoots(_)
+            | Self::BackupValidation { .. }
+            | Self::UpdatingManifest
+            | Self::ConfirmCloudSync { .. }
+            | Self::ConfigureFtpRemote { .. }
+            | Self::ConfigureSmbRemote { .. }
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => Message::CloseModal,
+        }
+    }
+
     fn extra_controls(&self) -> Vec<Element> {
         match self {
             modal @ Self::ConfirmCloudSync {
@@ -307,6 +355,7 @@ impl Modal {
                             preview: false,
                             repair: true,
                             games: Some(games.iter().cloned().collect()),
+                            label: Default::default(),
                         })),
                     )]
                 }
@@ -319,9 +368,11 @@ impl Modal {
             | Self::NoMissingRoots
             | Self::ConfirmAddMissingRoots(_)
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => vec![],
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => vec![],
         }
     }
 
@@ -337,11 +388,27 @@ impl Modal {
             Self::Error { .. }
             | Self::Errors { .. }
             | Self::Exiting
-            | Self::ConfirmBackup { .. }
             | Self::ConfirmRestore { .. }
             | Self::NoMissingRoots
             | Self::ConfirmAddMissingRoots(_)
-            | Self::UpdatingManifest => (),
+            | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync => (),
+            Self::ConfirmBackup { locked, .. } => {
+                col = col
+                    .width(500)
+                    .push(ModalField::view(ModalInputKind::Comment, histories))
+                    .push(checkbox(TRANSLATOR.lock_new_backups_label(), *locked, |x| {
+                        Message::EditedModalField(ModalField::Locked(x))
+                    }));
+            }
+            Self::ChooseAccount { accounts } => {
+                for account in accounts {
+                    col = col.push(button::primary(
+                        account.clone(),
+                        Some(Message::PreferAccount(account.clone())),
+                    ));
+                }
+            }
             Self::BackupValidation { games } => {
                 for game in games.iter().sorted() {
                     col = col.push(text(game))
@@ -381,7 +448,7 @@ impl Modal {
                                 .take(CHANGES_PER_PAGE)
                                 .fold(
                                     Column::new().width(Length::Fill).align_items(Alignment::Start),
-                                    |parent, CloudChange { change, path }| {
+                                    |parent, CloudChange { change, path, .. }| {
                                         parent.push(
                                             Row::new()
                                                 .spacing(20)
@@ -435,9 +502,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => (),
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => (),
         }
     }
 
@@ -455,9 +524,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => (),
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => (),
         }
     }
 
@@ -475,9 +546,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => (),
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => (),
         }
     }
 
@@ -493,9 +566,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => false,
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => false,
         }
     }
 
@@ -513,9 +588,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => false,
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => false,
         }
     }
 
@@ -531,9 +608,11 @@ impl Modal {
             | Self::ConfirmAddMissingRoots(_)
             | Self::BackupValidation { .. }
             | Self::UpdatingManifest
+            | Self::ConfirmMeteredCloudSync
             | Self::ConfigureFtpRemote { .. }
             | Self::ConfigureSmbRemote { .. }
-            | Self::ConfigureWebDavRemote { .. } => 2,
+            | Self::ConfigureWebDavRemote { .. }
+            | Self::ChooseAccount { .. } => 2,
         }
     }
 
@@ -547,7 +626,7 @@ impl Modal {
             self.message(histories),
         );
 
-        let negative_button = button::negative(TRANSLATOR.cancel_button(), Some(Message::CloseModal));
+        let negative_button = button::negative(TRANSLATOR.cancel_button(), Some(self.negative_message()));
 
         Container::new(
             Column::new()
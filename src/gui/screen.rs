@@ -19,7 +19,7 @@ use crate::{
     prelude::{AVAILABLE_PARALELLISM, STEAM_DECK},
     resource::{
         cache::Cache,
-        config::{BackupFormat, Config, SortKey, Theme, ZipCompression},
+        config::{BackupFormat, BackupMode, Config, OnMetered, SortKey, Theme, ZipCompression},
         manifest::Manifest,
     },
     scan::{DuplicateDetector, Duplication, OperationStatus},
@@ -99,7 +99,16 @@ impl Backup {
                         self.log.all_entries_selected(config, false),
                     ))
                     .push(button::filter(Screen::Backup, self.log.search.show))
-                    .push(button::settings(self.show_settings)),
+                    .push(button::settings(self.show_settings))
+                    .push_if(
+                        || config.backup.mode == BackupMode::OnlyEnabledList,
+                        || {
+                            Badge::new(&TRANSLATOR.badge_only_enabled_games())
+                                .tooltip(TRANSLATOR.backup_mode_only_enabled_list_tooltip())
+                                .on_press(Message::ToggleBackupSettings)
+                                .view()
+                        },
+                    ),
             )
             .push(make_status_row(
                 &self.log.compute_operation_status(config, false),
@@ -157,6 +166,16 @@ impl Backup {
                         .padding([0, 20, 0, 20])
                         .spacing(20)
                         .align_items(Alignment::Center)
+                        .push(
+                            Row::new()
+                                .spacing(5)
+                                .align_items(Alignment::Center)
+                                .push(text(TRANSLATOR.backup_mode_field()))
+                                .push(
+                                    pick_list(BackupMode::ALL, Some(config.backup.mode), Message::SelectedBackupMode)
+                                        .style(style::PickList::Primary),
+                                ),
+                        )
                         .push(
                             Row::new()
                                 .spacing(5)
@@ -414,7 +433,17 @@ pub fn other<'a>(
                                     TRANSLATOR.show_unscanned_games(),
                                     config.scan.show_unscanned_games,
                                     Message::SetShowUnscannedGames,
-                                )),
+                                ))
+                                .push_if(
+                                    || cfg!(target_os = "windows"),
+                                    || {
+                                        checkbox(
+                                            TRANSLATOR.registry_enabled(),
+                                            config.scan.registry,
+                                            Message::SetRegistryEnabled,
+                                        )
+                                    },
+                                ),
                         )
                         .style(style::Container::GameListEntry),
                     ),
@@ -527,6 +556,20 @@ pub fn other<'a>(
                                                         config.cloud.synchronize,
                                                         |_| Message::ToggleCloudSynchronize,
                                                     ))
+                                                    .push_if(
+                                                        || config.cloud.synchronize,
+                                                        || {
+                                                            Row::new()
+                                                                .spacing(10)
+                                                                .align_items(Alignment::Center)
+                                                                .push(text(TRANSLATOR.on_metered_label()))
+                                                                .push(pick_list(
+                                                                    OnMetered::ALL,
+                                                                    Some(config.cloud.on_metered),
+                                                                    Message::SelectedOnMetered,
+                                                                ))
+                                                        },
+                                                    )
                                             },
                                         )
                                         .push_if(|| !is_cloud_configured, || text(TRANSLATOR.cloud_not_configured()))
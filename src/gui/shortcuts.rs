@@ -189,6 +189,7 @@ pub struct ModalHistory {
     pub port: TextHistory,
     pub username: TextHistory,
     pub password: TextHistory,
+    pub comment: TextHistory,
 }
 
 #[derive(Default)]
@@ -274,6 +275,7 @@ impl TextHistories {
         self.modal.port.clear();
         self.modal.username.clear();
         self.modal.password.clear();
+        self.modal.comment.clear();
     }
 
     pub fn input<'a>(&self, subject: UndoSubject) -> Element<'a> {
@@ -320,6 +322,7 @@ impl TextHistories {
                 ModalInputKind::Port => self.modal.port.current(),
                 ModalInputKind::Username => self.modal.username.current(),
                 ModalInputKind::Password => self.modal.password.current(),
+                ModalInputKind::Comment => self.modal.comment.current(),
             },
         };
 
@@ -371,6 +374,7 @@ impl TextHistories {
                     ModalInputKind::Port => ModalField::Port(value),
                     ModalInputKind::Username => ModalField::Username(value),
                     ModalInputKind::Password => ModalField::Password(value),
+                    ModalInputKind::Comment => ModalField::Comment(value),
                 })
             }),
         };
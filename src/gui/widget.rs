@@ -147,6 +147,10 @@ impl Progress {
         self.start_time = Some(chrono::Utc::now());
     }
 
+    pub fn start_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.start_time
+    }
+
     pub fn step(&mut self) {
         self.current += 1.0;
     }
@@ -0,0 +1,286 @@
+//! An opt-in log of recent operations, so that users can review what Ludusavi did and how it went.
+//! Each backup, restore, or cloud sync adds one line of JSON to `history.jsonl` next to the config file.
+//! This is only stored locally and is never sent anywhere.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    prelude::{app_dir, FileLock, SyncDirection},
+    resource::config,
+    scan::OperationStatus,
+};
+
+const FILE_NAME: &str = "history.jsonl";
+
+pub const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    Backup,
+    Restore,
+    CloudUpload,
+    CloudDownload,
+}
+
+impl Operation {
+    pub const BACKUP: &'static str = "backup";
+    pub const RESTORE: &'static str = "restore";
+    pub const CLOUD_UPLOAD: &'static str = "cloud-upload";
+    pub const CLOUD_DOWNLOAD: &'static str = "cloud-download";
+    pub const ALL_NAMES: &'static [&'static str] =
+        &[Self::BACKUP, Self::RESTORE, Self::CLOUD_UPLOAD, Self::CLOUD_DOWNLOAD];
+}
+
+impl std::str::FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::BACKUP => Ok(Self::Backup),
+            Self::RESTORE => Ok(Self::Restore),
+            Self::CLOUD_UPLOAD => Ok(Self::CloudUpload),
+            Self::CLOUD_DOWNLOAD => Ok(Self::CloudDownload),
+            _ => Err(format!("invalid operation: {}", s)),
+        }
+    }
+}
+
+impl From<SyncDirection> for Operation {
+    fn from(value: SyncDirection) -> Self {
+        match value {
+            SyncDirection::Upload => Self::CloudUpload,
+            SyncDirection::Download => Self::CloudDownload,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    /// When the operation started.
+    pub when: DateTime<Utc>,
+    pub operation: Operation,
+    /// Games processed by a backup or restore.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub games: Option<usize>,
+    /// Files transferred by a cloud sync.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<usize>,
+    pub bytes: u64,
+    /// Games that failed during a backup or restore, or 1 if a cloud sync failed.
+    pub failures: usize,
+    pub duration_ms: u64,
+}
+
+impl Record {
+    pub fn for_games(
+        operation: Operation,
+        started: DateTime<Utc>,
+        status: Option<&OperationStatus>,
+        failures: usize,
+    ) -> Self {
+        Self {
+            when: started,
+            operation,
+            games: Some(status.map(|x| x.processed_games).unwrap_or_default()),
+            files: None,
+            bytes: status.map(|x| x.processed_bytes).unwrap_or_default(),
+            failures,
+            duration_ms: (Utc::now() - started).num_milliseconds().max(0) as u64,
+        }
+    }
+
+    pub fn for_cloud(record: &crate::cloud::history::Record) -> Self {
+        Self {
+            when: record.when,
+            operation: record.direction.into(),
+            games: None,
+            files: Some(record.files),
+            bytes: record.bytes,
+            failures: if record.succeeded { 0 } else { 1 },
+            duration_ms: record.duration_ms,
+        }
+    }
+}
+
+/// Which records to show.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// When empty, all operations are included.
+    pub operations: Vec<Operation>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only keep this many of the most recent matching records.
+    pub last: Option<usize>,
+}
+
+impl Filter {
+    fn includes(&self, record: &Record) -> bool {
+        (self.operations.is_empty() || self.operations.contains(&record.operation))
+            && self.since.map(|bound| record.when >= bound).unwrap_or(true)
+            && self.until.map(|bound| record.when < bound).unwrap_or(true)
+    }
+
+    /// Apply the filter to records in the order that they were loaded (oldest first).
+    pub fn apply(&self, records: Vec<Record>) -> Vec<Record> {
+        let mut records: Vec<_> = records.into_iter().filter(|x| self.includes(x)).collect();
+        if let Some(last) = self.last {
+            records.drain(..records.len().saturating_sub(last));
+        }
+        records
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(app_dir())
+    }
+}
+
+impl History {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file(&self) -> PathBuf {
+        self.dir.join(FILE_NAME)
+    }
+
+    fn lock_file(&self) -> PathBuf {
+        self.dir.join(format!("{FILE_NAME}.lock"))
+    }
+
+    /// Add a record if the history is enabled (see `history.enabled` and `history.limit`).
+    /// Failures are only logged, since this shouldn't affect the operation itself.
+    pub fn record(&self, config: &config::History, record: &Record) {
+        if !config.enabled {
+            return;
+        }
+        if let Err(e) = self.try_record(record, config.limit) {
+            log::warn!("Unable to record history: {e:?}");
+        }
+    }
+
+    fn try_record(&self, record: &Record, limit: usize) -> Result<(), std::io::Error> {
+        let line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+
+        std::fs::create_dir_all(&self.dir)?;
+        // The GUI and CLI may both be running, so only one of them can touch the file at a time.
+        let _lock = FileLock::acquire(&self.lock_file())?;
+
+        let file = self.file();
+        let existing = std::fs::read_to_string(&file).unwrap_or_default();
+        let mut lines: Vec<_> = existing.lines().filter(|x| !x.trim().is_empty()).collect();
+        lines.push(&line);
+        lines.drain(..lines.len().saturating_sub(limit.max(1)));
+
+        let mut content = lines.join("\n");
+        content.push('\n');
+
+        // Write to a temporary file first so that readers never see a partial file.
+        let temp = self.dir.join(format!("{FILE_NAME}.tmp"));
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, file)
+    }
+
+    /// Load all records, oldest first. Malformed lines are skipped.
+    pub fn load(&self) -> Vec<Record> {
+        let file = self.file();
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            return vec![];
+        };
+
+        content
+            .lines()
+            .filter(|x| !x.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<Record>(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    log::warn!("Ignoring invalid history line in {file:?}: {e:?}");
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn record(day: u32, operation: Operation) -> Record {
+        Record {
+            when: Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap(),
+            operation,
+            games: Some(1),
+            files: None,
+            bytes: 100,
+            failures: 0,
+            duration_ms: 10,
+        }
+    }
+
+    #[test]
+    fn can_record_and_load_history_up_to_the_limit() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-history-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        let history = History::new(dir.clone());
+
+        let records: Vec<_> = (1..=4).map(|day| record(day, Operation::Backup)).collect();
+        history.record(&config::History::default(), &records[0]);
+        assert_eq!(Vec::<Record>::new(), history.load());
+
+        let config = config::History {
+            enabled: true,
+            limit: 3,
+        };
+        for record in &records {
+            history.record(&config, record);
+        }
+
+        assert_eq!(records[1..].to_vec(), history.load());
+        assert!(!dir.join(format!("{FILE_NAME}.lock")).exists());
+        assert!(!dir.join(format!("{FILE_NAME}.tmp")).exists());
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn can_filter_by_operation_and_time() {
+        let records = vec![
+            record(1, Operation::Backup),
+            record(2, Operation::Restore),
+            record(3, Operation::Backup),
+            record(4, Operation::CloudUpload),
+            record(5, Operation::Backup),
+        ];
+
+        let filter = Filter {
+            operations: vec![Operation::Backup, Operation::CloudUpload],
+            since: Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()),
+            until: Some(Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap()),
+            last: None,
+        };
+        assert_eq!(
+            vec![records[2].clone(), records[3].clone()],
+            filter.apply(records.clone())
+        );
+
+        let filter = Filter {
+            last: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(records[3..].to_vec(), filter.apply(records.clone()));
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Mutex;
+use std::{collections::BTreeSet, sync::Mutex};
 
 use byte_unit::Byte;
 use fluent::{bundle::FluentBundle, FluentArgs, FluentResource};
@@ -8,12 +8,22 @@ use regex::Regex;
 use unic_langid::LanguageIdentifier;
 
 use crate::{
-    prelude::{CommandError, Error, StrictPath, VARIANT, VERSION},
+    cli::report::Palette,
+    prelude::{is_newer_version, CommandError, Error, StrictPath, VARIANT, VERSION},
     resource::{
-        config::{BackupFormat, CustomGameKind, RedirectKind, RootsConfig, SortKey, Theme, ZipCompression},
-        manifest::Store,
+        config::{
+            BackupFormat, BackupMode, CustomGameKind, DanglingTitle, ImportSection, OnMetered, RedirectKind,
+            RedundantRoot, RootRedundancy, RootsConfig, SortKey, Theme, TitleReference, ZipCompression,
+        },
+        manifest::{Store, StoreId},
+    },
+    scan::{
+        game_filter,
+        layout::{AdoptionProblem, BackupKind, DeletionProblem, Discrepancy, PromotionReason},
+        zip_safety::UnsafeZipEntry,
+        FuzzySource, GameStorage, OperationStatus, OperationStepDecision, ScanChange, SizeHistoryEntry, StorageTotals,
+        UnknownGame,
     },
-    scan::{game_filter, OperationStatus, OperationStepDecision, ScanChange},
 };
 
 const PATH: &str = "path";
@@ -30,11 +40,13 @@ const CODE: &str = "code";
 const MESSAGE: &str = "message";
 const APP: &str = "app";
 const GAME: &str = "game";
+const PERCENT: &str = "percent";
 
 pub const TRANSLATOR: Translator = Translator {};
 pub const ADD_SYMBOL: &str = "+";
 pub const CHANGE_SYMBOL: &str = "Δ";
 pub const REMOVAL_SYMBOL: &str = "x";
+pub const WARNING_SYMBOL: &str = "!";
 
 fn title_case(text: &str) -> String {
     let lowercase = text.to_lowercase();
@@ -46,7 +58,7 @@ fn title_case(text: &str) -> String {
 }
 
 // TODO: Some are blocked by https://github.com/mtkennerly/ludusavi/issues/9.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum Language {
     #[serde(rename = "ar-SA")]
     Arabic,
@@ -165,24 +177,15 @@ pub struct Translator {}
 
 static LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
 
-static BUNDLE: Lazy<Mutex<FluentBundle<FluentResource, IntlLangMemoizer>>> = Lazy::new(|| {
-    let ftl = include_str!("../lang/en-US.ftl").to_owned();
-    let res = FluentResource::try_new(ftl).expect("Failed to parse Fluent file content.");
-
-    let mut bundle = FluentBundle::new_concurrent(vec![Language::English.id()]);
-    bundle.set_use_isolating(false);
-
-    bundle
-        .add_resource(res)
-        .expect("Failed to add Fluent resources to the bundle.");
+/// Set from the command line, taking precedence over the config.
+static LANGUAGE_OVERRIDE: Mutex<Option<Language>> = Mutex::new(None);
 
-    Mutex::new(bundle)
-});
+type Bundle = FluentBundle<FluentResource, IntlLangMemoizer>;
 
-fn set_language(language: Language) {
-    let mut bundle = BUNDLE.lock().unwrap();
+static BUNDLE: Lazy<Mutex<Bundle>> = Lazy::new(|| Mutex::new(load_bundle(Language::English)));
 
-    let ftl = match language {
+fn ftl(language: Language) -> &'static str {
+    match language {
         Language::Arabic => include_str!("../lang/ar-SA.ftl"),
         Language::ChineseSimplified => include_str!("../lang/zh-CN.ftl"),
         Language::Czech => include_str!("../lang/cs-CZ.ftl"),
@@ -202,17 +205,40 @@ fn set_language(language: Language) {
         Language::Thai => include_str!("../lang/th-TH.ftl"),
         Language::Ukrainian => include_str!("../lang/uk-UA.ftl"),
     }
-    .to_owned();
+}
 
-    let res = FluentResource::try_new(ftl).expect("Failed to parse Fluent file content.");
-    bundle.locales = vec![language.id()];
+/// English is always loaded first, so that it can fill in any untranslated messages.
+fn load_bundle(language: Language) -> Bundle {
+    let mut bundle = FluentBundle::new_concurrent(vec![Language::English.id()]);
+    bundle.set_use_isolating(false);
 
-    bundle.add_resource_overriding(res);
+    let res = FluentResource::try_new(ftl(Language::English).to_owned()).expect("Failed to parse Fluent file content.");
+    bundle
+        .add_resource(res)
+        .expect("Failed to add Fluent resources to the bundle.");
+
+    if language != Language::English {
+        let res = FluentResource::try_new(ftl(language).to_owned()).expect("Failed to parse Fluent file content.");
+        bundle.locales = vec![language.id()];
+        bundle.add_resource_overriding(res);
+    }
+
+    bundle
+}
+
+fn set_language(language: Language) {
+    let bundle = load_bundle(language);
+    *BUNDLE.lock().unwrap() = bundle;
 
     let mut last_language = LANGUAGE.lock().unwrap();
     *last_language = language;
 }
 
+/// The command line's choice wins over the config's.
+fn choose_language(configured: Language, overridden: Option<Language>) -> Language {
+    overridden.unwrap_or(configured)
+}
+
 static RE_EXTRA_SPACES: Lazy<Regex> = Lazy::new(|| Regex::new(r"([^\r\n ]) {2,}").unwrap());
 static RE_EXTRA_LINES: Lazy<Regex> = Lazy::new(|| Regex::new(r"([^\r\n ])[\r\n]([^\r\n ])").unwrap());
 static RE_EXTRA_PARAGRAPHS: Lazy<Regex> = Lazy::new(|| Regex::new(r"([^\r\n ])[\r\n]{2,}([^\r\n ])").unwrap());
@@ -226,7 +252,10 @@ fn translate_args(id: &str, args: &FluentArgs) -> String {
         Ok(x) => x,
         Err(_) => return "fluent-cannot-lock".to_string(),
     };
+    format_message(&bundle, id, args)
+}
 
+fn format_message(bundle: &Bundle, id: &str, args: &FluentArgs) -> String {
     let parts: Vec<&str> = id.splitn(2, '.').collect();
     let (name, attr) = if parts.len() < 2 {
         (id, None)
@@ -262,9 +291,15 @@ fn translate_args(id: &str, args: &FluentArgs) -> String {
 
 impl Translator {
     pub fn set_language(&self, language: Language) {
-        set_language(Language::English);
-        if language != Language::English {
-            set_language(language);
+        let language = choose_language(language, *LANGUAGE_OVERRIDE.lock().unwrap());
+        set_language(language);
+    }
+
+    /// Use this language regardless of later calls to `set_language`.
+    pub fn set_language_override(&self, language: Option<Language>) {
+        *LANGUAGE_OVERRIDE.lock().unwrap() = language;
+        if let Some(language) = language {
+            self.set_language(language);
         }
     }
 
@@ -299,16 +334,49 @@ impl Translator {
     pub fn handle_error(&self, error: &Error) -> String {
         match error {
             Error::ConfigInvalid { why } => self.config_is_invalid(why),
+            Error::ConfigCannotBeSaved { why } => self.config_cannot_be_saved(why),
             Error::ManifestInvalid { why, identifier } => self.manifest_is_invalid(why, identifier.as_deref()),
             Error::ManifestCannotBeUpdated { identifier } => self.manifest_cannot_be_updated(identifier.as_deref()),
             Error::CliUnrecognizedGames { games } => self.cli_unrecognized_games(games),
+            Error::CliAmbiguousStoreId { id, games } => self.cli_ambiguous_store_id(id, games),
             Error::CliUnableToRequestConfirmation => self.cli_unable_to_request_confirmation(),
             Error::CliBackupIdWithMultipleGames => self.cli_backup_id_with_multiple_games(),
-            Error::CliInvalidBackupId => self.cli_invalid_backup_id(),
+            Error::CliInvalidBackupId { available } => self.cli_invalid_backup_id(available),
+            Error::CliRestoreNeedsConfirmation { games } => self.cli_restore_needs_confirmation(*games),
+            Error::CliUnableToServe { address, why } => self.cli_unable_to_serve(address, why),
+            Error::CliUnableToStartDaemon { why } => self.cli_unable_to_start_daemon(why),
+            Error::CliUnableToWatch { why } => self.cli_unable_to_watch(why),
+            Error::CliUnableToReadGameList { path, why } => self.cli_unable_to_read_game_list(path, why),
+            Error::CliInvalidGlob { why } => self.cli_invalid_glob(why),
+            Error::CliRootAlreadyConfigured { path } => self.cli_root_already_configured(path),
+            Error::CliRootDoesNotExist { path } => self.cli_root_does_not_exist(path),
+            Error::CliUnrecognizedRoot { root } => self.cli_unrecognized_root(root),
+            Error::CliCustomGameAlreadyExists { name } => self.cli_custom_game_already_exists(name),
+            Error::CliCustomGameInManifest { name } => self.cli_custom_game_in_manifest(name),
+            Error::CliUnknownPlaceholders { path, placeholders } => self.cli_unknown_placeholders(path, placeholders),
+            Error::CliUnableToImportConfig { path, why } => self.cli_unable_to_import_config(path, why),
+            Error::DaemonUnavailable { why } => self.daemon_unavailable(why),
+            Error::AppUpdateUnavailable { why } => self.app_update_unavailable(why),
+            Error::DaemonReported { message } => message.clone(),
             Error::SomeEntriesFailed => self.some_entries_failed(),
+            Error::ChangesPending => self.changes_pending(),
+            Error::NothingChanged => self.nothing_changed(),
             Error::CannotPrepareBackupTarget { path } => self.cannot_prepare_backup_target(path),
             Error::RestorationSourceInvalid { path } => self.restoration_source_is_invalid(path),
+            Error::UnableToAdoptBackup { path, problem } => format!(
+                "{}\n\n{}",
+                self.prefix_error(&self.unable_to_adopt_backup(path)),
+                self.adoption_problem(problem)
+            ),
+            Error::UnableToDeleteBackup { backup, problem } => format!(
+                "{}\n\n{}",
+                self.prefix_error(&self.unable_to_delete_backup(backup)),
+                self.deletion_problem(problem)
+            ),
             Error::RegistryIssue => self.registry_issue(),
+            Error::RegistryHiveUnsupported => self.registry_hive_unsupported(),
+            Error::RegistryHiveNeedsElevation => self.registry_hive_needs_elevation(),
+            Error::UnableToLoadRegistryHive { path, why } => self.unable_to_load_registry_hive(path, why),
             Error::UnableToBrowseFileSystem => self.unable_to_browse_file_system(),
             Error::UnableToOpenDir(path) => self.unable_to_open_dir(path),
             Error::UnableToOpenUrl(url) => self.unable_to_open_url(url),
@@ -330,7 +398,15 @@ impl Translator {
                 )
             }
             Error::CloudConflict => TRANSLATOR.prefix_error(&TRANSLATOR.cloud_synchronize_conflict()),
+            Error::CloudSyncDeferred => TRANSLATOR.prefix_warning(&TRANSLATOR.cloud_sync_deferred_on_metered()),
+            Error::CloudChunkedUploadFailed { path, why } => {
+                let mut args = FluentArgs::new();
+                args.set(PATH, path.as_str());
+                args.set(MESSAGE, why.as_str());
+                self.prefix_error(&translate_args("cloud-chunked-upload-failed", &args))
+            }
             Error::GameDidNotLaunch { why } => format!("{}\n\n{}", self.game_did_not_launch(), self.prefix_error(why)),
+            Error::UnableToWriteReport { path, why } => self.unable_to_write_report(path, why),
         }
     }
 
@@ -363,12 +439,46 @@ impl Translator {
         }
     }
 
-    pub fn cli_unrecognized_games(&self, games: &[String]) -> String {
+    pub fn cli_unrecognized_games(&self, games: &[UnknownGame]) -> String {
         let prefix = translate("cli-unrecognized-games");
-        let lines: Vec<_> = games.iter().map(|x| format!("  - {}", x)).collect();
+        let mut lines = vec![];
+        for game in games {
+            lines.push(format!("  - {}", game.name));
+            if !game.suggestions.is_empty() {
+                lines.push(format!("    {}", self.cli_did_you_mean(&game.suggestions)));
+            }
+        }
+        format!("{}\n{}", prefix, lines.join("\n"))
+    }
+
+    pub fn cli_ambiguous_store_id(&self, id: &StoreId, games: &[String]) -> String {
+        let mut args = FluentArgs::new();
+        args.set("id", self.store_id(id));
+        let prefix = translate_args("cli-ambiguous-store-id", &args);
+        let lines: Vec<_> = games.iter().map(|game| format!("  - {game}")).collect();
         format!("{}\n{}", prefix, lines.join("\n"))
     }
 
+    pub fn store_id(&self, id: &StoreId) -> String {
+        let mut args = FluentArgs::new();
+        args.set(
+            "store",
+            match id {
+                StoreId::Steam(_) => "steam",
+                StoreId::Gog(_) => "gog",
+                StoreId::Lutris(_) => "lutris",
+            },
+        );
+        args.set("id", id.value());
+        translate_args("store-id", &args)
+    }
+
+    pub fn cli_did_you_mean(&self, suggestions: &[String]) -> String {
+        let mut args = FluentArgs::new();
+        args.set("games", suggestions.join(", "));
+        translate_args("cli-did-you-mean", &args)
+    }
+
     pub fn cli_unable_to_request_confirmation(&self) -> String {
         #[cfg(target_os = "windows")]
         let extra_note = translate("cli-unable-to-request-confirmation.winpty-workaround");
@@ -383,8 +493,119 @@ impl Translator {
         translate("cli-backup-id-with-multiple-games")
     }
 
-    pub fn cli_invalid_backup_id(&self) -> String {
-        translate("cli-invalid-backup-id")
+    pub fn cli_invalid_backup_id(&self, available: &[String]) -> String {
+        let prefix = translate("cli-invalid-backup-id");
+        if available.is_empty() {
+            return prefix;
+        }
+        let lines: Vec<_> = available.iter().map(|backup| format!("  - {backup}")).collect();
+        format!(
+            "{}\n{}\n{}",
+            prefix,
+            translate("cli-available-backups"),
+            lines.join("\n")
+        )
+    }
+
+    pub fn cli_restore_needs_confirmation(&self, games: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL_GAMES, games);
+        translate_args("cli-restore-needs-confirmation", &args)
+    }
+
+    pub fn cli_unable_to_serve(&self, address: &str, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("address", address);
+        args.set("why", why);
+        translate_args("cli-unable-to-serve", &args)
+    }
+
+    pub fn cli_unable_to_start_daemon(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("why", why);
+        translate_args("cli-unable-to-start-daemon", &args)
+    }
+
+    pub fn cli_unable_to_watch(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("why", why);
+        translate_args("cli-unable-to-watch", &args)
+    }
+
+    pub fn cli_unable_to_read_game_list(&self, path: &StrictPath, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        args.set(MESSAGE, why);
+        translate_args("cli-unable-to-read-game-list", &args)
+    }
+
+    pub fn cli_invalid_glob(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("why", why);
+        translate_args("cli-invalid-glob", &args)
+    }
+
+    pub fn cli_root_already_configured(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-root-already-configured", &args)
+    }
+
+    pub fn cli_root_does_not_exist(&self, path: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        translate_args("cli-root-does-not-exist", &args)
+    }
+
+    pub fn cli_unrecognized_root(&self, root: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("root", root);
+        translate_args("cli-unrecognized-root", &args)
+    }
+
+    pub fn cli_custom_game_already_exists(&self, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("name", name);
+        translate_args("cli-custom-game-already-exists", &args)
+    }
+
+    pub fn cli_custom_game_in_manifest(&self, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("name", name);
+        translate_args("cli-custom-game-in-manifest", &args)
+    }
+
+    pub fn cli_unknown_placeholders(&self, path: &str, placeholders: &[String]) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        args.set("placeholders", placeholders.join(", "));
+        translate_args("cli-unknown-placeholders", &args)
+    }
+
+    pub fn cli_unable_to_import_config(&self, path: &StrictPath, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        args.set(MESSAGE, why);
+        translate_args("cli-unable-to-import-config", &args)
+    }
+
+    pub fn cli_watching(&self, games: usize, locations: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set("games", games);
+        args.set("locations", locations);
+        translate_args("cli-watching", &args)
+    }
+
+    pub fn daemon_unavailable(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("why", why);
+        translate_args("daemon-unavailable", &args)
+    }
+
+    pub fn app_update_unavailable(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("why", why);
+        translate_args("app-update-unavailable", &args)
     }
 
     pub fn cloud_not_configured(&self) -> String {
@@ -399,6 +620,14 @@ impl Translator {
         translate("some-entries-failed")
     }
 
+    pub fn changes_pending(&self) -> String {
+        translate("changes-pending")
+    }
+
+    pub fn nothing_changed(&self) -> String {
+        translate("nothing-changed")
+    }
+
     fn label(&self, text: &str) -> String {
         format!("[{}]", text)
     }
@@ -407,6 +636,10 @@ impl Translator {
         self.label(&self.badge_failed())
     }
 
+    pub fn label_failed_because(&self, reason: &str) -> String {
+        self.label(&format!("{}: {}", self.badge_failed(), reason))
+    }
+
     pub fn label_duplicates(&self) -> String {
         self.label(&self.badge_duplicates())
     }
@@ -419,6 +652,14 @@ impl Translator {
         self.label(&self.badge_ignored())
     }
 
+    pub fn label_forced(&self) -> String {
+        self.label(&self.badge_forced())
+    }
+
+    pub fn label_deferred(&self) -> String {
+        self.label(&self.badge_deferred())
+    }
+
     pub fn field(&self, text: &str) -> String {
         let language = LANGUAGE.lock().unwrap();
         match *language {
@@ -447,10 +688,22 @@ impl Translator {
         translate("badge-duplicated")
     }
 
+    pub fn badge_only_enabled_games(&self) -> String {
+        translate("badge-only-enabled-games")
+    }
+
     pub fn badge_ignored(&self) -> String {
         translate("badge-ignored")
     }
 
+    pub fn badge_forced(&self) -> String {
+        translate("badge-forced")
+    }
+
+    pub fn badge_deferred(&self) -> String {
+        translate("badge-deferred")
+    }
+
     pub fn badge_redirected_from(&self, original: &StrictPath) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, original.render());
@@ -469,29 +722,57 @@ impl Translator {
         bytes: u64,
         decision: &OperationStepDecision,
         duplicated: bool,
+        forced: bool,
         change: ScanChange,
+        duration_ms: Option<u64>,
+        palette: Palette,
     ) -> String {
+        let ignored = *decision == OperationStepDecision::Ignored;
+        // Ignored entries are dimmed as a whole instead of highlighting their labels.
+        let (palette, outer) = if ignored {
+            (palette.plain(), palette)
+        } else {
+            (palette, palette.plain())
+        };
+
         let mut labels = vec![];
         match change {
             ScanChange::New => {
-                labels.push(format!("[{}]", crate::lang::ADD_SYMBOL));
+                labels.push(palette.added(format!("[{}]", crate::lang::ADD_SYMBOL)));
             }
             ScanChange::Different => {
-                labels.push(format!("[{}]", crate::lang::CHANGE_SYMBOL));
+                labels.push(palette.changed(format!("[{}]", crate::lang::CHANGE_SYMBOL)));
             }
-            ScanChange::Removed | ScanChange::Same | ScanChange::Unknown => (),
+            ScanChange::Removed => {
+                labels.push(format!("[{}]", crate::lang::REMOVAL_SYMBOL));
+            }
+            ScanChange::Same | ScanChange::Unknown => (),
         }
-        if *decision == OperationStepDecision::Ignored {
+        if ignored {
             labels.push(self.label_ignored());
         }
+        if *decision == OperationStepDecision::Deferred {
+            labels.push(palette.failed(self.label_deferred()));
+        }
         if duplicated {
-            labels.push(self.label_duplicates());
+            labels.push(palette.duplicated(self.label_duplicates()));
+        }
+        if forced {
+            labels.push(self.label_forced());
+        }
+        if let Some(duration_ms) = duration_ms {
+            labels.push(self.cli_game_took(duration_ms));
         }
 
         if labels.is_empty() {
-            format!("{} [{}]:", name, self.adjusted_size(bytes))
+            outer.ignored(format!("{} [{}]:", name, self.adjusted_size(bytes)))
         } else {
-            format!("{} [{}] {}:", name, self.adjusted_size(bytes), labels.join(" "))
+            outer.ignored(format!(
+                "{} [{}] {}:",
+                name,
+                self.adjusted_size(bytes),
+                labels.join(" ")
+            ))
         }
     }
 
@@ -499,34 +780,225 @@ impl Translator {
         &self,
         item: &str,
         successful: bool,
+        error: Option<&str>,
         ignored: bool,
         duplicated: bool,
         change: ScanChange,
         nested: bool,
+        palette: Palette,
     ) -> String {
+        // Ignored entries are dimmed as a whole instead of highlighting their labels.
+        let (palette, outer) = if ignored {
+            (palette.plain(), palette)
+        } else {
+            (palette, palette.plain())
+        };
+
         let mut parts = vec![];
         match change {
             ScanChange::Same | ScanChange::Unknown => (),
-            ScanChange::New => parts.push(format!("[{}]", ADD_SYMBOL)),
-            ScanChange::Different => parts.push(format!("[{}]", CHANGE_SYMBOL)),
+            ScanChange::New => parts.push(palette.added(format!("[{}]", ADD_SYMBOL))),
+            ScanChange::Different => parts.push(palette.changed(format!("[{}]", CHANGE_SYMBOL))),
             ScanChange::Removed => parts.push(format!("[{}]", REMOVAL_SYMBOL)),
         }
         if !successful {
-            parts.push(self.label_failed());
+            parts.push(palette.failed(match error {
+                Some(error) => self.label_failed_because(error),
+                None => self.label_failed(),
+            }));
         }
         if ignored {
             parts.push(self.label_ignored());
         }
         if duplicated {
-            parts.push(self.label_duplicated());
+            parts.push(palette.duplicated(self.label_duplicated()));
         }
         parts.push(item.to_string());
 
         if nested {
-            format!("    - {}", parts.join(" "))
+            format!("    - {}", outer.ignored(parts.join(" ")))
         } else {
-            format!("  - {}", parts.join(" "))
+            format!("  - {}", outer.ignored(parts.join(" ")))
+        }
+    }
+
+    pub fn games_deferred(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        translate_args("games-deferred", &args)
+    }
+
+    pub fn failure_changed_while_copying(&self) -> String {
+        translate("failure-changed-while-copying")
+    }
+
+    pub fn failure_locked(&self, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(MESSAGE, why);
+        translate_args("failure-locked", &args)
+    }
+
+    pub fn failure_missing_from_backup(&self) -> String {
+        translate("failure-missing-from-backup")
+    }
+
+    pub fn failure_missing_backup(&self) -> String {
+        translate("failure-missing-backup")
+    }
+
+    pub fn failure_hash_mismatch(&self) -> String {
+        translate("failure-hash-mismatch")
+    }
+
+    pub fn failure_unreadable_archive(&self, message: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(MESSAGE, message);
+        translate_args("failure-unreadable-archive", &args)
+    }
+
+    pub fn failure_safety_snapshot(&self) -> String {
+        translate("failure-safety-snapshot")
+    }
+
+    pub fn safety_snapshot_comment(&self) -> String {
+        translate("safety-snapshot-comment")
+    }
+
+    pub fn safety_snapshot_unknown_game(&self) -> String {
+        translate("safety-snapshot-unknown-game")
+    }
+
+    pub fn safety_snapshot_io_timeout(&self) -> String {
+        translate("safety-snapshot-io-timeout")
+    }
+
+    pub fn cli_backup_size(&self, bytes: u64, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        args.set("size", self.adjusted_size(bytes));
+        translate_args("cli-backup-size", &args)
+    }
+
+    pub fn cli_game_line_item_folder_summary(&self, folder: &str, total: usize, bytes: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, folder);
+        args.set(TOTAL, total);
+        args.set("size", self.adjusted_size(bytes));
+        format!("  - {}", translate_args("cli-game-line-item-folder-summary", &args))
+    }
+
+    pub fn cli_game_registry_skipped(&self) -> String {
+        format!("  {}", translate("cli-game-registry-skipped"))
+    }
+
+    pub fn cli_game_unresolved_base_dir(&self, path: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        format!("  {}", translate_args("cli-game-unresolved-base-dir", &args))
+    }
+
+    pub fn cli_game_line_item_ambiguous_target(&self, candidates: &[String]) -> String {
+        let mut args = FluentArgs::new();
+        args.set("candidates", candidates.join(", "));
+        format!("    - {}", translate_args("cli-game-line-item-ambiguous-target", &args))
+    }
+
+    pub fn cli_game_line_item_unsafe_zip_entry(&self, problem: &UnsafeZipEntry) -> String {
+        let mut args = FluentArgs::new();
+        args.set(MESSAGE, self.unsafe_zip_entry(problem));
+        format!("    - {}", translate_args("cli-game-line-item-unsafe-zip-entry", &args))
+    }
+
+    pub fn cli_game_line_item_os_constrained(&self) -> String {
+        format!("    - {}", translate("cli-game-line-item-os-constrained"))
+    }
+
+    pub fn cli_game_line_item_newer_local(&self, palette: Palette) -> String {
+        format!(
+            "    - {} {}",
+            palette.warning(format!("[{}]", WARNING_SYMBOL)),
+            translate("cli-game-line-item-newer-local")
+        )
+    }
+
+    pub fn cli_game_line_item_kept_newer_local(&self) -> String {
+        format!("    - {}", translate("cli-game-line-item-kept-newer-local"))
+    }
+
+    pub fn cli_game_os_constrained(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        format!("  {}", translate_args("cli-game-os-constrained", &args))
+    }
+
+    pub fn cli_game_io_timeout(&self, path: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        format!("  {}", translate_args("cli-game-io-timeout", &args))
+    }
+
+    pub fn cli_game_merged_titles(&self, titles: &[String]) -> String {
+        let mut args = FluentArgs::new();
+        args.set("games", titles.join(", "));
+        format!("  {}", translate_args("cli-game-merged-titles", &args))
+    }
+
+    pub fn cli_game_took(&self, duration_ms: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set("seconds", format!("{:.1}", duration_ms as f64 / 1000.0));
+        translate_args("cli-game-took", &args)
+    }
+
+    pub fn cli_game_no_recent_activity(&self) -> String {
+        format!("  {}", translate("cli-game-no-recent-activity"))
+    }
+
+    pub fn cli_game_unchanged(&self) -> String {
+        format!("  {}", translate("cli-game-unchanged"))
+    }
+
+    pub fn cli_game_inconsistent(&self) -> String {
+        format!("  {}", translate("cli-game-inconsistent"))
+    }
+
+    pub fn cli_game_promoted_to_full(&self, reason: &PromotionReason) -> String {
+        let mut args = FluentArgs::new();
+        match reason {
+            PromotionReason::DiffBytesExceeded {
+                diff_bytes,
+                limit_bytes,
+            } => {
+                args.set("size", self.adjusted_size(*diff_bytes));
+                args.set("limit", self.adjusted_size(*limit_bytes));
+            }
         }
+        format!("  {}", translate_args("cli-game-promoted-to-full", &args))
+    }
+
+    pub fn cli_game_backup_comment(&self, comment: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("comment", comment);
+        format!("  {}", translate_args("cli-game-backup-comment", &args))
+    }
+
+    pub fn cli_game_backup_locked(&self) -> String {
+        format!("  {}", translate("cli-game-backup-locked"))
+    }
+
+    pub fn cli_game_backup_newer_version(&self, version: &str) -> String {
+        format!("  {}", self.backup_newer_version(version))
+    }
+
+    pub fn cli_game_safety_snapshot(&self, backup: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("backup", backup);
+        format!("  {}", translate_args("cli-game-safety-snapshot", &args))
+    }
+
+    pub fn cli_game_safety_snapshot_failed(&self, message: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(MESSAGE, message);
+        format!("  {}", translate_args("cli-game-safety-snapshot-failed", &args))
     }
 
     pub fn cli_game_line_item_redirected(&self, item: &str) -> String {
@@ -541,7 +1013,75 @@ impl Translator {
         format!("    - {}", translate_args("cli-game-line-item-redirecting", &args),)
     }
 
+    pub fn cli_game_line_item_redirect_rule(&self, index: usize, source: &str, target: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("number", index + 1);
+        args.set("source", source);
+        args.set("target", target);
+        format!("    - {}", translate_args("cli-game-line-item-redirect-rule", &args))
+    }
+
+    pub fn cli_game_line_item_changed_during_backup(&self) -> String {
+        format!("    - {}", translate("cli-game-line-item-changed-during-backup"))
+    }
+
+    pub fn cli_game_line_item_junk(&self) -> String {
+        format!("    - {}", translate("cli-game-line-item-junk"))
+    }
+
+    pub fn cli_game_line_item_stored_as(&self, path: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        format!("    - {}", translate_args("cli-game-line-item-stored-as", &args))
+    }
+
+    pub fn cli_game_line_item_migrated_from(&self, path: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        format!("    - {}", translate_args("cli-game-line-item-migrated-from", &args))
+    }
+
+    pub fn cli_game_line_item_wine_user_variant(&self, path: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path);
+        format!(
+            "    - {}",
+            translate_args("cli-game-line-item-wine-user-variant", &args)
+        )
+    }
+
     pub fn cli_summary(&self, status: &OperationStatus, location: &StrictPath) -> String {
+        let mut out = format!(
+            "{}:\n  {}: {}\n  {}: {}\n  {}: {}",
+            translate("overall"),
+            translate("total-games"),
+            self.cli_summary_games(status),
+            translate("file-size"),
+            self.cli_summary_size(status),
+            translate("file-location"),
+            location.render(),
+        );
+        if status.ignored_anything() {
+            out.push_str(&format!(
+                "\n  {}: {}",
+                translate("cli-summary-ignored"),
+                self.cli_summary_ignored(status)
+            ));
+        }
+        out
+    }
+
+    /// How much was left out by ignore rules.
+    pub fn cli_summary_ignored(&self, status: &OperationStatus) -> String {
+        let mut args = FluentArgs::new();
+        args.set("files", status.ignored_files);
+        args.set("size", self.adjusted_size(status.ignored_bytes));
+        args.set("keys", status.ignored_registry_keys);
+        translate_args("cli-summary-ignored-entries", &args)
+    }
+
+    /// How many games were processed, with counts of the ones that changed.
+    pub fn cli_summary_games(&self, status: &OperationStatus) -> String {
         let new_games = if status.changed_games.new > 0 {
             format!(" [{}{}]", crate::lang::ADD_SYMBOL, status.changed_games.new)
         } else {
@@ -552,11 +1092,14 @@ impl Translator {
         } else {
             "".to_string()
         };
+        let removed_games = if status.changed_games.removed > 0 {
+            format!(" [{}{}]", crate::lang::REMOVAL_SYMBOL, status.changed_games.removed)
+        } else {
+            "".to_string()
+        };
 
         format!(
-            "{}:\n  {}: {}{}{}\n  {}: {}\n  {}: {}",
-            translate("overall"),
-            translate("total-games"),
+            "{}{}{}{}",
             if status.processed_all_games() {
                 status.processed_games.to_string()
             } else {
@@ -564,21 +1107,69 @@ impl Translator {
             },
             new_games,
             changed_games,
-            translate("file-size"),
-            if status.processed_all_bytes() {
-                self.adjusted_size(status.processed_bytes)
-            } else {
-                format!(
-                    "{} / {}",
-                    self.adjusted_size(status.processed_bytes),
-                    self.adjusted_size(status.total_bytes)
-                )
-            },
-            translate("file-location"),
-            location.render(),
+            removed_games,
         )
     }
 
+    pub fn cli_summary_size(&self, status: &OperationStatus) -> String {
+        if status.processed_all_bytes() {
+            self.adjusted_size(status.processed_bytes)
+        } else {
+            format!(
+                "{} / {}",
+                self.adjusted_size(status.processed_bytes),
+                self.adjusted_size(status.total_bytes)
+            )
+        }
+    }
+
+    pub fn cli_no_failures(&self) -> String {
+        translate("cli-no-failures")
+    }
+
+    pub fn cli_markdown_path(&self) -> String {
+        translate("cli-markdown-path")
+    }
+
+    pub fn cli_markdown_value(&self) -> String {
+        translate("cli-markdown-value")
+    }
+
+    pub fn cli_markdown_change(&self) -> String {
+        translate("cli-markdown-change")
+    }
+
+    pub fn cli_markdown_status(&self) -> String {
+        translate("cli-markdown-status")
+    }
+
+    pub fn cli_markdown_registry(&self) -> String {
+        translate("cli-markdown-registry")
+    }
+
+    /// A file's change, spelled out. Unknown changes are left blank.
+    pub fn cli_markdown_scan_change(&self, change: ScanChange) -> String {
+        match change {
+            ScanChange::New => translate("label-new"),
+            ScanChange::Different => translate("label-updated"),
+            ScanChange::Removed => translate("label-removed"),
+            ScanChange::Same => translate("label-unchanged"),
+            ScanChange::Unknown => "".to_string(),
+        }
+    }
+
+    pub fn overall(&self) -> String {
+        translate("overall")
+    }
+
+    pub fn file_size(&self) -> String {
+        translate("file-size")
+    }
+
+    pub fn file_location(&self) -> String {
+        translate("file-location")
+    }
+
     pub fn backup_button(&self) -> String {
         translate("button-backup")
     }
@@ -712,6 +1303,10 @@ impl Translator {
         format!("{}\n{}", translate("config-is-invalid"), why)
     }
 
+    pub fn config_cannot_be_saved(&self, why: &str) -> String {
+        format!("{}\n{}", translate("config-cannot-be-saved"), why)
+    }
+
     pub fn manifest_is_invalid(&self, why: &str, identifier: Option<&str>) -> String {
         let message = translate("manifest-is-invalid");
         let identifier = identifier.map(|x| format!(" ({})", x)).unwrap_or("".to_string());
@@ -730,16 +1325,141 @@ impl Translator {
         translate_args("cannot-prepare-backup-target", &args)
     }
 
+    pub fn unable_to_write_report(&self, path: &StrictPath, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        args.set(MESSAGE, why);
+        translate_args("unable-to-write-report", &args)
+    }
+
     pub fn restoration_source_is_invalid(&self, source: &StrictPath) -> String {
         let mut args = FluentArgs::new();
         args.set(PATH, source.render());
         translate_args("restoration-source-is-invalid", &args)
     }
 
+    pub fn unable_to_adopt_backup(&self, source: &StrictPath) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, source.render());
+        translate_args("unable-to-adopt-backup", &args)
+    }
+
+    pub fn adoption_problem(&self, problem: &AdoptionProblem) -> String {
+        let mut args = FluentArgs::new();
+        match problem {
+            AdoptionProblem::SourceMissing => translate("adoption-source-missing"),
+            AdoptionProblem::DestinationOccupied { path } => {
+                args.set(PATH, path.render());
+                translate_args("adoption-destination-occupied", &args)
+            }
+            AdoptionProblem::UnreadableMapping { why } => {
+                args.set(MESSAGE, why.as_str());
+                translate_args("adoption-unreadable-mapping", &args)
+            }
+            AdoptionProblem::NoBackups => translate("adoption-no-backups"),
+            AdoptionProblem::MissingBackup { backup } => {
+                args.set(PATH, backup.as_str());
+                translate_args("adoption-missing-backup", &args)
+            }
+            AdoptionProblem::NoDriveFolders => translate("adoption-no-drive-folders"),
+            AdoptionProblem::UnknownDriveFolder { folder } => {
+                args.set(PATH, folder.as_str());
+                translate_args("adoption-unknown-drive-folder", &args)
+            }
+            AdoptionProblem::UnreadableZip { why } => {
+                args.set(MESSAGE, why.as_str());
+                translate_args("adoption-unreadable-zip", &args)
+            }
+            AdoptionProblem::UnableToMove { why } => {
+                args.set(MESSAGE, why.as_str());
+                translate_args("adoption-unable-to-move", &args)
+            }
+            AdoptionProblem::UnsafeZipEntry { problem } => {
+                args.set(MESSAGE, self.unsafe_zip_entry(problem));
+                translate_args("adoption-unsafe-zip-entry", &args)
+            }
+        }
+    }
+
+    pub fn unable_to_delete_backup(&self, backup: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, backup);
+        translate_args("unable-to-delete-backup", &args)
+    }
+
+    pub fn deletion_problem(&self, problem: &DeletionProblem) -> String {
+        let mut args = FluentArgs::new();
+        match problem {
+            DeletionProblem::NotFound => translate("deletion-not-found"),
+            DeletionProblem::HasDifferentials { total } => {
+                args.set(TOTAL, *total);
+                translate_args("deletion-has-differentials", &args)
+            }
+            DeletionProblem::Locked => translate("deletion-locked"),
+        }
+    }
+
+    pub fn unsafe_zip_entry(&self, problem: &UnsafeZipEntry) -> String {
+        let mut args = FluentArgs::new();
+        match problem {
+            UnsafeZipEntry::AbsolutePath { entry } => {
+                args.set(PATH, entry.as_str());
+                translate_args("unsafe-zip-entry-absolute-path", &args)
+            }
+            UnsafeZipEntry::EscapesRoot { entry } => {
+                args.set(PATH, entry.as_str());
+                translate_args("unsafe-zip-entry-escapes-root", &args)
+            }
+            UnsafeZipEntry::Symlink { entry, target } => {
+                args.set(PATH, entry.as_str());
+                args.set("target", target.as_str());
+                translate_args("unsafe-zip-entry-symlink", &args)
+            }
+            UnsafeZipEntry::CompressionRatio { entry, ratio, limit } => {
+                args.set(PATH, entry.as_str());
+                args.set("ratio", ratio);
+                args.set("limit", limit);
+                translate_args("unsafe-zip-entry-compression-ratio", &args)
+            }
+            UnsafeZipEntry::TotalCompressionRatio { entry, ratio, limit } => {
+                args.set(PATH, entry.as_str());
+                args.set("ratio", ratio);
+                args.set("limit", limit);
+                translate_args("unsafe-zip-entry-total-compression-ratio", &args)
+            }
+            UnsafeZipEntry::TooLarge { entry, size, limit } => {
+                args.set(PATH, entry.as_str());
+                args.set("size", self.adjusted_size(*size));
+                args.set("limit", self.adjusted_size(*limit));
+                translate_args("unsafe-zip-entry-too-large", &args)
+            }
+            UnsafeZipEntry::Oversized { entry, declared } => {
+                args.set(PATH, entry.as_str());
+                args.set("size", self.adjusted_size(*declared));
+                translate_args("unsafe-zip-entry-oversized", &args)
+            }
+        }
+    }
+
     pub fn registry_issue(&self) -> String {
         translate("registry-issue")
     }
 
+    pub fn registry_hive_unsupported(&self) -> String {
+        translate("registry-hive-unsupported")
+    }
+
+    pub fn registry_hive_needs_elevation(&self) -> String {
+        translate("registry-hive-needs-elevation")
+    }
+
+    pub fn unable_to_load_registry_hive(&self, path: &StrictPath, why: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, path.render());
+        args.set("why", why);
+        translate_args("unable-to-load-registry-hive", &args)
+    }
+
     pub fn unable_to_browse_file_system(&self) -> String {
         translate("unable-to-browse-file-system")
     }
@@ -760,10 +1480,89 @@ impl Translator {
         translate("unable-to-synchronize-with-cloud")
     }
 
+    pub fn unresolved_base_dirs(&self, games: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL_GAMES, games);
+        translate_args("unresolved-base-dirs", &args)
+    }
+
+    pub fn io_timeout(&self) -> String {
+        translate("io-timeout")
+    }
+
+    pub fn manifest_fallback(&self, why: &str, age_hours: Option<i64>) -> String {
+        let mut out = translate("manifest-fallback");
+        if let Some(hours) = age_hours {
+            let mut args = FluentArgs::new();
+            args.set("hours", hours);
+            out.push_str(&format!(" {}", translate_args("manifest-fallback-age", &args)));
+        }
+        format!("{out}\n{why}")
+    }
+
+    pub fn game_running(&self, game: &str, process: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(GAME, game);
+        args.set("process", process);
+        translate_args("game-running", &args)
+    }
+
+    pub fn game_running_for_backup(&self, game: &str, process: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set(GAME, game);
+        args.set("process", process);
+        translate_args("game-running-for-backup", &args)
+    }
+
+    pub fn running_check_unavailable(&self) -> String {
+        translate("running-check-unavailable")
+    }
+
+    pub fn backup_inconsistent(&self, game: &str, repaired: bool) -> String {
+        let mut args = FluentArgs::new();
+        args.set(GAME, game);
+        if repaired {
+            translate_args("backup-inconsistent-repaired", &args)
+        } else {
+            translate_args("backup-inconsistent", &args)
+        }
+    }
+
+    pub fn backup_discrepancy(&self, discrepancy: &Discrepancy) -> String {
+        let mut args = FluentArgs::new();
+        match discrepancy {
+            Discrepancy::MissingBackup { backup } => {
+                args.set(PATH, backup.as_str());
+                translate_args("backup-missing-backup", &args)
+            }
+            Discrepancy::MissingFile { backup, file } => {
+                args.set("name", backup.as_str());
+                args.set(PATH, file.as_str());
+                translate_args("backup-missing-file", &args)
+            }
+            Discrepancy::UnknownFile { path } => {
+                args.set(PATH, path.as_str());
+                translate_args("backup-unknown-file", &args)
+            }
+        }
+    }
+
     pub fn cloud_synchronize_conflict(&self) -> String {
         translate("cloud-synchronize-conflict")
     }
 
+    pub fn cli_cloud_conflict_paths(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        translate_args("cli-cloud-conflict-paths", &args)
+    }
+
+    pub fn cli_cloud_conflict_more(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        translate_args("cli-cloud-conflict-more", &args)
+    }
+
     pub fn adjusted_size(&self, bytes: u64) -> String {
         let byte = Byte::from_bytes(bytes.into());
         let adjusted_byte = byte.get_appropriate_unit(true);
@@ -959,6 +1758,18 @@ impl Translator {
         translate("show-unscanned-games")
     }
 
+    pub fn registry_enabled(&self) -> String {
+        translate("registry-enabled")
+    }
+
+    pub fn enable_registry_button(&self) -> String {
+        translate("button-enable-registry")
+    }
+
+    pub fn disable_registry_button(&self) -> String {
+        translate("button-disable-registry")
+    }
+
     pub fn override_max_threads(&self) -> String {
         format!(
             "{} ({})",
@@ -1003,6 +1814,32 @@ impl Translator {
         translate("field-backup-compression-level")
     }
 
+    pub fn backup_mode_field(&self) -> String {
+        translate("field-backup-mode")
+    }
+
+    pub fn backup_mode_name(&self, mode: &BackupMode) -> String {
+        translate(match mode {
+            BackupMode::AllGamesExceptDisabled => "backup-mode-all-games-except-disabled",
+            BackupMode::OnlyEnabledList => "backup-mode-only-enabled-list",
+        })
+    }
+
+    pub fn backup_mode_only_enabled_list_tooltip(&self) -> String {
+        translate("backup-mode-only-enabled-list-tooltip")
+    }
+
+    pub fn cli_summary_backup_mode(&self, mode: &BackupMode, skipped_games: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set("mode", self.backup_mode_name(mode));
+        args.set(TOTAL, skipped_games);
+        translate_args("cli-summary-backup-mode", &args)
+    }
+
+    pub fn enable_for_backup_button(&self) -> String {
+        translate("button-enable-for-backup")
+    }
+
     pub fn manifest_label(&self) -> String {
         self.field(&translate("label-manifest"))
     }
@@ -1019,6 +1856,30 @@ impl Translator {
         translate("label-comment")
     }
 
+    pub fn backup_newer_version(&self, version: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("version", version);
+        translate_args("backup-newer-version", &args)
+    }
+
+    pub fn backup_provenance(&self, version: &str, manifest_hash: Option<&str>) -> String {
+        let mut args = FluentArgs::new();
+        args.set("version", version);
+        let created_by = if is_newer_version(version) {
+            self.backup_newer_version(version)
+        } else {
+            translate_args("backup-created-by-version", &args)
+        };
+
+        match manifest_hash {
+            Some(hash) => {
+                args.set("hash", hash);
+                format!("{created_by}\n{}", translate_args("backup-manifest-hash", &args))
+            }
+            None => created_by,
+        }
+    }
+
     pub fn scan_label(&self) -> String {
         translate("label-scan")
     }
@@ -1099,6 +1960,14 @@ impl Translator {
         self.field(&translate("label-provider"))
     }
 
+    pub fn comment_field(&self) -> String {
+        self.field(&translate("label-comment"))
+    }
+
+    pub fn lock_new_backups_label(&self) -> String {
+        translate("label-lock-new-backups")
+    }
+
     pub fn none_label(&self) -> String {
         translate("label-none")
     }
@@ -1117,6 +1986,30 @@ impl Translator {
         translate("synchronize-automatically")
     }
 
+    pub fn on_metered_label(&self) -> String {
+        translate("label-on-metered-connection")
+    }
+
+    pub fn on_metered_name(&self, policy: &OnMetered) -> String {
+        translate(match policy {
+            OnMetered::Proceed => "on-metered-proceed",
+            OnMetered::Skip => "on-metered-skip",
+            OnMetered::Ask => "on-metered-ask",
+        })
+    }
+
+    pub fn cloud_sync_deferred_on_metered(&self) -> String {
+        translate("cloud-sync-deferred-on-metered")
+    }
+
+    pub fn cli_cloud_sync_deferred_on_metered(&self) -> String {
+        translate("cli-cloud-sync-deferred-on-metered")
+    }
+
+    pub fn confirm_metered_cloud_sync(&self) -> String {
+        translate("confirm-metered-cloud-sync")
+    }
+
     pub fn total_games(&self) -> String {
         translate("total-games")
     }
@@ -1169,6 +2062,26 @@ impl Translator {
         }
     }
 
+    pub fn restore_many_games_summary(&self, games: usize, bytes: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL_GAMES, games);
+        args.set("size", self.adjusted_size(bytes));
+        translate_args("restore-many-games-summary", &args)
+    }
+
+    pub fn confirm_remap_user_profile(&self, remaps: &BTreeSet<(String, String)>) -> String {
+        let mut out = translate("confirm-remap-user-profile");
+        out.push('\n');
+        for (source, target) in remaps {
+            out.push_str(&format!("\n{source} -> {target}"));
+        }
+        out
+    }
+
+    pub fn choose_account_for_restore(&self) -> String {
+        translate("choose-account-for-restore")
+    }
+
     pub fn confirm_cloud_upload(&self, local: &str, cloud: &str) -> String {
         let mut args = FluentArgs::new();
         args.set(LOCAL_PATH, local);
@@ -1183,10 +2096,302 @@ impl Translator {
         translate_args("confirm-cloud-download", &args)
     }
 
+    pub fn no_config_changes(&self) -> String {
+        translate("no-config-changes")
+    }
+
+    pub fn no_redundant_roots(&self) -> String {
+        translate("no-redundant-roots")
+    }
+
+    pub fn redundant_root(&self, entry: &RedundantRoot) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PATH, entry.kept.path.raw());
+        match entry.redundancy {
+            RootRedundancy::Same => translate_args("redundant-root-same", &args),
+            RootRedundancy::Nested => translate_args("redundant-root-nested", &args),
+        }
+    }
+
+    pub fn redundant_roots_skipped(&self) -> String {
+        translate("redundant-roots-skipped")
+    }
+
+    pub fn no_dangling_titles(&self) -> String {
+        translate("no-dangling-titles")
+    }
+
+    pub fn dangling_title(&self, entry: &DanglingTitle) -> String {
+        let mut args = FluentArgs::new();
+        args.set("title", entry.title.clone());
+        match &entry.reference {
+            TitleReference::CustomAlias { name } => {
+                args.set("name", name.clone());
+                translate_args("dangling-custom-alias", &args)
+            }
+            TitleReference::MergedTitle { name } => {
+                args.set("name", name.clone());
+                translate_args("dangling-merged-title", &args)
+            }
+        }
+    }
+
+    pub fn no_custom_games(&self) -> String {
+        translate("no-custom-games")
+    }
+
+    pub fn custom_game_alias(&self, name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("name", name);
+        translate_args("custom-game-alias", &args)
+    }
+
+    pub fn custom_game_overrides_manifest(&self) -> String {
+        translate("custom-game-overrides-manifest")
+    }
+
+    pub fn no_imported_entries(&self) -> String {
+        translate("no-imported-entries")
+    }
+
+    pub fn import_section(&self, section: ImportSection) -> String {
+        let mut args = FluentArgs::new();
+        args.set(
+            "section",
+            match section {
+                ImportSection::CustomGame => "custom-game",
+                ImportSection::BackupPath => "backup-path",
+                ImportSection::BackupRegistry => "backup-registry",
+                ImportSection::RestorePath => "restore-path",
+                ImportSection::RestoreRegistry => "restore-registry",
+                ImportSection::Redirect => "redirect",
+            },
+        );
+        translate_args("import-section", &args)
+    }
+
+    pub fn import_skipped(&self) -> String {
+        translate("import-skipped")
+    }
+
+    pub fn import_summary(&self, added: usize, updated: usize, unchanged: usize, skipped: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set("added", added);
+        args.set("updated", updated);
+        args.set("unchanged", unchanged);
+        args.set("skipped", skipped);
+        translate_args("import-summary", &args)
+    }
+
+    pub fn dangling_titles_found(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        translate_args("dangling-titles-found", &args)
+    }
+
+    pub fn cloud_upload_resumed_at(&self, percent: u8) -> String {
+        let mut args = FluentArgs::new();
+        args.set(PERCENT, percent);
+        translate_args("cloud-upload-resumed-at", &args)
+    }
+
     pub fn no_cloud_changes(&self) -> String {
         translate("no-cloud-changes")
     }
 
+    pub fn cli_cloud_header(&self, preview: bool) -> String {
+        if preview {
+            format!("{}:", translate("cli-cloud-header-preview"))
+        } else {
+            format!("{}:", translate("cli-cloud-header"))
+        }
+    }
+
+    pub fn no_duplicate_content(&self) -> String {
+        translate("no-duplicate-content")
+    }
+
+    pub fn duplicate_content_group(&self, hash: &str, size: u64, savings: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set("hash", hash);
+        args.set("size", self.adjusted_size(size));
+        args.set("savings", self.adjusted_size(savings));
+        translate_args("duplicate-content-group", &args)
+    }
+
+    pub fn duplicate_content_total(&self, savings: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set("savings", self.adjusted_size(savings));
+        translate_args("duplicate-content-total", &args)
+    }
+
+    pub fn cli_deleted_backups(&self, preview: bool, total: usize, bytes: u64) -> String {
+        let mut args = FluentArgs::new();
+        args.set("preview", preview.to_string());
+        args.set(TOTAL, total);
+        args.set("size", self.adjusted_size(bytes));
+        translate_args("cli-deleted-backups", &args)
+    }
+
+    pub fn no_backup_differences(&self) -> String {
+        translate("no-backup-differences")
+    }
+
+    pub fn backup_diff_header(&self, game: &str, old: &str, new: Option<&str>) -> String {
+        let mut args = FluentArgs::new();
+        args.set("old", old);
+        args.set(
+            "new",
+            new.map(|x| x.to_string())
+                .unwrap_or_else(|| translate("backup-diff-current")),
+        );
+        format!("{}: {}", game, translate_args("backup-diff-header", &args))
+    }
+
+    pub fn backup_diff_registry(&self) -> String {
+        translate("backup-diff-registry")
+    }
+
+    pub fn fuzzy_match(&self, score: f64, source: FuzzySource, matched_name: &str) -> String {
+        let mut args = FluentArgs::new();
+        args.set("score", format!("{score:.2}"));
+        args.set(
+            "source",
+            match source {
+                FuzzySource::Title => "title",
+                FuzzySource::Alias => "alias",
+                FuzzySource::CustomGame => "custom-game",
+                FuzzySource::InstallDir => "install-dir",
+            },
+        );
+        args.set("name", matched_name);
+        translate_args("fuzzy-match", &args)
+    }
+
+    pub fn no_size_history(&self) -> String {
+        translate("no-size-history")
+    }
+
+    pub fn size_history_line(&self, entry: &SizeHistoryEntry) -> String {
+        let mut args = FluentArgs::new();
+        args.set(
+            "when",
+            entry
+                .when
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+        );
+        args.set(
+            "kind",
+            match entry.kind {
+                BackupKind::Full => "full",
+                BackupKind::Differential => "differential",
+            },
+        );
+        args.set("size", self.adjusted_size(entry.bytes));
+        args.set("files", entry.files);
+        let line = translate_args("size-history-line", &args);
+
+        match entry.delta {
+            Some(delta) => {
+                let sign = if delta < 0 { "-" } else { "+" };
+                format!("{line} [{sign}{}]", self.adjusted_size(delta.unsigned_abs()))
+            }
+            None => line,
+        }
+    }
+
+    pub fn storage_line(&self, storage: &GameStorage) -> String {
+        let mut args = FluentArgs::new();
+        args.set("size", self.adjusted_size(storage.bytes));
+        args.set("full", storage.full_backups);
+        args.set("differential", storage.differential_backups);
+        args.set(TOTAL, storage.full_backups + storage.differential_backups);
+        args.set("latest-size", self.adjusted_size(storage.latest_bytes));
+        args.set(
+            "when",
+            storage
+                .last_backup
+                .map(|x| x.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S").to_string())
+                .unwrap_or_default(),
+        );
+        translate_args("storage-line", &args)
+    }
+
+    pub fn storage_missing_files(&self, total: usize) -> String {
+        let mut args = FluentArgs::new();
+        args.set(TOTAL, total);
+        translate_args("storage-missing-files", &args)
+    }
+
+    pub fn storage_total(&self, totals: &StorageTotals) -> String {
+        let mut args = FluentArgs::new();
+        args.set("size", self.adjusted_size(totals.bytes));
+        args.set(TOTAL, totals.full_backups + totals.differential_backups);
+        args.set("games", totals.games);
+        translate_args("storage-total", &args)
+    }
+
+    pub fn no_cloud_history(&self) -> String {
+        translate("no-cloud-history")
+    }
+
+    pub fn cloud_history_total(&self) -> String {
+        translate("cloud-history-total")
+    }
+
+    pub fn cloud_history_line(&self, period: &str, totals: &crate::cloud::history::Totals) -> String {
+        let mut args = FluentArgs::new();
+        args.set("period", period);
+        args.set("uploaded", self.adjusted_size(totals.uploaded_bytes));
+        args.set("downloaded", self.adjusted_size(totals.downloaded_bytes));
+        args.set("runs", totals.runs);
+        args.set("failed", totals.failed_runs);
+        translate_args("cloud-history-line", &args)
+    }
+
+    pub fn no_run_history(&self) -> String {
+        translate("no-run-history")
+    }
+
+    pub fn run_history_line(&self, record: &crate::history::Record) -> String {
+        use crate::history::Operation;
+
+        let mut args = FluentArgs::new();
+        args.set(
+            "when",
+            record
+                .when
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+        );
+        args.set(
+            "operation",
+            match record.operation {
+                Operation::Backup => "backup",
+                Operation::Restore => "restore",
+                Operation::CloudUpload => "cloudUpload",
+                Operation::CloudDownload => "cloudDownload",
+            },
+        );
+        match (record.games, record.files) {
+            (None, Some(files)) => {
+                args.set("unit", "files");
+                args.set("total", files);
+            }
+            (games, _) => {
+                args.set("unit", "games");
+                args.set("total", games.unwrap_or_default());
+            }
+        }
+        args.set("size", self.adjusted_size(record.bytes));
+        args.set("failures", record.failures);
+        args.set("seconds", format!("{:.1}", record.duration_ms as f64 / 1000.0));
+        translate_args("run-history-line", &args)
+    }
+
     pub fn notify_single_game_status(&self, found: bool) -> String {
         if found {
             translate("saves-found")
@@ -1269,3 +2474,41 @@ impl Translator {
         translate_args("restore-specific-game.failed", &args)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn language_override_takes_precedence_over_config() {
+        assert_eq!(Language::German, choose_language(Language::German, None));
+        assert_eq!(
+            Language::English,
+            choose_language(Language::German, Some(Language::English))
+        );
+    }
+
+    #[test]
+    fn can_translate_into_chosen_language() {
+        let args = FluentArgs::new();
+        assert_eq!(
+            "Back up",
+            format_message(&load_bundle(Language::English), "button-backup", &args)
+        );
+        assert_eq!(
+            "Sichern",
+            format_message(&load_bundle(Language::German), "button-backup", &args)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_english_for_untranslated_messages() {
+        let args = FluentArgs::new();
+        assert_eq!(
+            "DEFERRED",
+            format_message(&load_bundle(Language::German), "badge-deferred", &args)
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Ludusavi's scan/backup engine.
+//!
+//! The supported interface for other programs is the [`api`] module.
+//! Everything else is internal to the bundled CLI and GUI.
+
+#![allow(clippy::too_many_arguments)]
+
+pub mod api;
+
+pub(crate) mod cli;
+pub(crate) mod cloud;
+pub(crate) mod daemon;
+pub(crate) mod gui;
+pub(crate) mod history;
+pub(crate) mod lang;
+pub(crate) mod path;
+pub(crate) mod prelude;
+pub(crate) mod resource;
+pub(crate) mod scan;
+pub(crate) mod serialization;
+pub(crate) mod serve;
+pub(crate) mod wrap;
+
+mod launch;
+
+#[cfg(test)]
+mod testing;
+
+/// Runs the bundled `ludusavi` program with the process's arguments.
+/// This is only meant for the binary target and is not covered by semantic versioning.
+#[doc(hidden)]
+pub use launch::main;
@@ -357,6 +357,16 @@ impl StrictPath {
         }
     }
 
+    /// Like `rendered`, but without the basis,
+    /// so that paths to the same file are equal regardless of how they were found.
+    pub fn canonical(&self) -> Self {
+        Self {
+            raw: self.render(),
+            basis: None,
+            interpreted: Arc::new(Mutex::new(Some(self.interpret()))),
+        }
+    }
+
     pub fn resolve(&self) -> String {
         if let Ok(resolved) = resolve(&self.raw) {
             resolved
@@ -426,14 +436,8 @@ impl StrictPath {
 
     /// Zips don't store time zones, so we normalize to/from UTC.
     pub fn set_mtime_zip(&self, mtime: zip::DateTime) -> Result<(), SetFileTimeError> {
-        let naive_mtime = chrono::NaiveDateTime::new(
-            chrono::NaiveDate::from_ymd_opt(mtime.year() as i32, mtime.month() as u32, mtime.day() as u32)
-                .ok_or(SetFileTimeError::InvalidTimestamp)?,
-            chrono::NaiveTime::from_hms_opt(mtime.hour() as u32, mtime.minute() as u32, mtime.second() as u32)
-                .ok_or(SetFileTimeError::InvalidTimestamp)?,
-        );
-        self.set_mtime(chrono::DateTime::<chrono::Utc>::from_utc(naive_mtime, chrono::Utc).into())
-            .map_err(SetFileTimeError::Write)
+        let mtime = system_time_from_zip(mtime).ok_or(SetFileTimeError::InvalidTimestamp)?;
+        self.set_mtime(mtime).map_err(SetFileTimeError::Write)
     }
 
     pub fn remove(&self) -> Result<(), Box<dyn std::error::Error>> {
@@ -634,6 +638,13 @@ impl StrictPath {
         Ok(())
     }
 
+    /// Whether both paths lead to the same place, once interpreted.
+    /// Since interpretation canonicalizes existing paths,
+    /// this also accounts for symlinks (and on Windows, junctions and `subst` drives).
+    pub fn same_location(&self, other: &StrictPath) -> bool {
+        splittable(self) == splittable(other)
+    }
+
     pub fn is_prefix_of(&self, other: &StrictPath) -> bool {
         let us_rendered = splittable(self);
         let them_rendered = splittable(other);
@@ -804,6 +815,54 @@ impl From<&StrictPath> for StrictPath {
     }
 }
 
+/// Unlike the normal serialization, this keeps the basis of a relative path
+/// (e.g., a file in a backup), for data that's only exchanged between Ludusavi processes.
+pub mod with_basis {
+    use super::StrictPath;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub(super) struct Helper {
+        raw: String,
+        basis: Option<String>,
+    }
+
+    impl From<&StrictPath> for Helper {
+        fn from(path: &StrictPath) -> Self {
+            Self {
+                raw: path.raw.clone(),
+                basis: path.basis.clone(),
+            }
+        }
+    }
+
+    impl From<Helper> for StrictPath {
+        fn from(helper: Helper) -> Self {
+            StrictPath::relative(helper.raw, helper.basis)
+        }
+    }
+
+    pub fn serialize<S: serde::Serializer>(path: &StrictPath, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&Helper::from(path), serializer)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<StrictPath, D::Error> {
+        <Helper as serde::Deserialize>::deserialize(deserializer).map(StrictPath::from)
+    }
+}
+
+/// Like `with_basis`, but for an optional path.
+pub mod option_with_basis {
+    use super::{with_basis::Helper, StrictPath};
+
+    pub fn serialize<S: serde::Serializer>(path: &Option<StrictPath>, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&path.as_ref().map(Helper::from), serializer)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<StrictPath>, D::Error> {
+        <Option<Helper> as serde::Deserialize>::deserialize(deserializer).map(|x| x.map(StrictPath::from))
+    }
+}
+
 // Based on:
 // https://github.com/serde-rs/serde/issues/751#issuecomment-277580700
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -827,6 +886,29 @@ impl<'de> serde::Deserialize<'de> for StrictPath {
     }
 }
 
+impl schemars::JsonSchema for StrictPath {
+    fn schema_name() -> String {
+        "StrictPath".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// Zips don't store time zones, so we normalize to/from UTC.
+pub fn system_time_from_zip(mtime: zip::DateTime) -> Option<std::time::SystemTime> {
+    let naive_mtime = chrono::NaiveDateTime::new(
+        chrono::NaiveDate::from_ymd_opt(mtime.year() as i32, mtime.month() as u32, mtime.day() as u32)?,
+        chrono::NaiveTime::from_hms_opt(mtime.hour() as u32, mtime.minute() as u32, mtime.second() as u32)?,
+    );
+    Some(chrono::DateTime::<chrono::Utc>::from_utc(naive_mtime, chrono::Utc).into())
+}
+
 #[allow(dead_code)]
 pub fn is_raw_path_relative(path: &str) -> bool {
     let path = path.replace('\\', "/");
@@ -1182,6 +1264,13 @@ mod tests {
             assert!(!StrictPath::new(s("")).is_prefix_of(&StrictPath::new(s("/foo"))));
         }
 
+        #[test]
+        fn same_location() {
+            assert!(StrictPath::new(s("/foo/bar")).same_location(&StrictPath::new(s("/foo/bar/"))));
+            assert!(StrictPath::new(s("/foo/bar")).same_location(&StrictPath::new(s("/foo/./baz/../bar"))));
+            assert!(!StrictPath::new(s("/foo/bar")).same_location(&StrictPath::new(s("/foo"))));
+        }
+
         #[test]
         #[cfg(target_os = "windows")]
         fn is_prefix_of_with_windows_drive_letters() {
@@ -7,7 +7,13 @@ use std::{
 use once_cell::sync::Lazy;
 
 pub use crate::path::StrictPath;
-use crate::resource::manifest::Os;
+use crate::{
+    resource::manifest::{Os, StoreId},
+    scan::{
+        layout::{AdoptionProblem, DeletionProblem},
+        UnknownGame,
+    },
+};
 
 pub static VERSION: Lazy<&'static str> =
     Lazy::new(|| option_env!("LUDUSAVI_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")));
@@ -24,6 +30,23 @@ pub static CANONICAL_VERSION: Lazy<(u32, u32, u32)> = Lazy::new(|| {
     }
 });
 
+/// Check if a version string (e.g., from a backup made by another installation)
+/// is newer than the running version. Suffixes like `-dev` are ignored.
+pub fn is_newer_version(raw: &str) -> bool {
+    let parts: Vec<u32> = raw
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map_while(|x| x.parse().ok())
+        .collect();
+    match parts.as_slice() {
+        [major, minor, patch] => (*major, *minor, *patch) > *CANONICAL_VERSION,
+        _ => false,
+    }
+}
+
 pub type AnyError = Box<dyn std::error::Error>;
 
 pub const SKIP: &str = "<skip>";
@@ -69,7 +92,8 @@ impl Privacy {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum SyncDirection {
     Upload,
     Download,
@@ -87,21 +111,103 @@ pub enum Error {
     ConfigInvalid {
         why: String,
     },
+    ConfigCannotBeSaved {
+        why: String,
+    },
     CliUnrecognizedGames {
+        games: Vec<UnknownGame>,
+    },
+    CliAmbiguousStoreId {
+        id: StoreId,
         games: Vec<String>,
     },
     CliUnableToRequestConfirmation,
     CliBackupIdWithMultipleGames,
-    CliInvalidBackupId,
+    CliInvalidBackupId {
+        available: Vec<String>,
+    },
+    CliRestoreNeedsConfirmation {
+        games: usize,
+    },
+    CliUnableToServe {
+        address: String,
+        why: String,
+    },
+    CliUnableToStartDaemon {
+        why: String,
+    },
+    CliUnableToWatch {
+        why: String,
+    },
+    CliUnableToReadGameList {
+        path: StrictPath,
+        why: String,
+    },
+    CliInvalidGlob {
+        why: String,
+    },
+    CliRootAlreadyConfigured {
+        path: StrictPath,
+    },
+    CliRootDoesNotExist {
+        path: StrictPath,
+    },
+    CliUnrecognizedRoot {
+        root: String,
+    },
+    CliCustomGameAlreadyExists {
+        name: String,
+    },
+    CliCustomGameInManifest {
+        name: String,
+    },
+    CliUnknownPlaceholders {
+        path: String,
+        placeholders: Vec<String>,
+    },
+    CliUnableToImportConfig {
+        path: StrictPath,
+        why: String,
+    },
+    DaemonUnavailable {
+        why: String,
+    },
+    AppUpdateUnavailable {
+        why: String,
+    },
+    /// The daemon couldn't run the operation, and this explains why (already translated).
+    DaemonReported {
+        message: String,
+    },
     SomeEntriesFailed,
+    /// A preview found changes, and `--fail-on-change` was set.
+    ChangesPending,
+    NothingChanged,
     CannotPrepareBackupTarget {
         path: StrictPath,
     },
     RestorationSourceInvalid {
         path: StrictPath,
     },
+    UnableToAdoptBackup {
+        path: StrictPath,
+        problem: AdoptionProblem,
+    },
+    UnableToDeleteBackup {
+        backup: String,
+        problem: DeletionProblem,
+    },
     #[allow(dead_code)]
     RegistryIssue,
+    #[allow(dead_code)]
+    RegistryHiveUnsupported,
+    #[allow(dead_code)]
+    RegistryHiveNeedsElevation,
+    #[allow(dead_code)]
+    UnableToLoadRegistryHive {
+        path: StrictPath,
+        why: String,
+    },
     UnableToBrowseFileSystem,
     UnableToOpenDir(StrictPath),
     UnableToOpenUrl(String),
@@ -111,10 +217,36 @@ pub enum Error {
     UnableToConfigureCloud(CommandError),
     UnableToSynchronizeCloud(CommandError),
     CloudConflict,
+    CloudSyncDeferred,
+    CloudChunkedUploadFailed {
+        path: String,
+        why: String,
+    },
     GameDidNotLaunch {
         why: String,
     },
+    UnableToWriteReport {
+        path: StrictPath,
+        why: String,
+    },
 }
+impl Error {
+    /// Pending changes and runs with nothing to do get their own codes,
+    /// so that scripts can tell them apart from actual errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::ChangesPending => 2,
+            Self::NothingChanged => 3,
+            _ => 1,
+        }
+    }
+
+    /// These only describe the outcome (see `exit_code`), so they aren't shown as errors.
+    pub fn is_status(&self) -> bool {
+        matches!(self, Self::ChangesPending | Self::NothingChanged)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CommandError {
     Launched {
@@ -171,7 +303,6 @@ pub fn filter_map_walkdir(e: Result<walkdir::DirEntry, walkdir::Error>) -> Optio
     e.ok()
 }
 
-#[cfg(target_os = "windows")]
 pub fn sha1(content: String) -> String {
     use sha1::Digest;
     let mut hasher = sha1::Sha1::new();
@@ -206,6 +337,7 @@ pub fn initialize_rayon(threads: NonZeroUsize) {
 pub struct CommandOutput {
     pub code: i32,
     pub stdout: String,
+    #[allow(dead_code)]
     pub stderr: String,
 }
 
@@ -323,3 +455,41 @@ pub fn unregister_sigint() {
         }
     }
 }
+
+/// If a lock is older than this, then its owner probably crashed.
+const STALE_LOCK: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Exclusive access to a file in the app folder,
+/// since the GUI and CLI may both be running and writing to it.
+/// The lock is released when this is dropped.
+pub struct FileLock(PathBuf);
+
+impl FileLock {
+    pub fn acquire(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        let mut attempts = 0;
+        loop {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(Self(path.to_path_buf())),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempts < 100 => {
+                    let stale = std::fs::metadata(path)
+                        .and_then(|x| x.modified())
+                        .is_ok_and(|x| x.elapsed().is_ok_and(|age| age > STALE_LOCK));
+                    if stale {
+                        log::warn!("Removing stale lock: {path:?}");
+                        _ = std::fs::remove_file(path);
+                    } else {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                    }
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        _ = std::fs::remove_file(&self.0);
+    }
+}
@@ -57,16 +57,36 @@ where
     Self: ResourceFile + serde::Serialize,
 {
     fn save(&self) {
-        let new_content = serde_yaml::to_string(&self).unwrap();
+        if let Err(e) = self.try_save() {
+            log::error!("Unable to save {}: {e:?}", Self::FILE_NAME);
+        }
+    }
+
+    fn try_save(&self) -> Result<(), AnyError> {
+        let new_content = serde_yaml::to_string(&self)?;
 
         if let Ok(old_content) = Self::load_raw(&Self::path()) {
             if old_content == new_content {
-                return;
+                return Ok(());
             }
-        }
 
-        if std::fs::create_dir_all(app_dir()).is_ok() {
-            let _ = std::fs::write(Self::path(), new_content.as_bytes());
+            // Don't rewrite the file if it only differs in formatting or key order.
+            if let (Ok(old_value), Ok(new_value)) = (
+                serde_yaml::from_str::<serde_yaml::Value>(&old_content),
+                serde_yaml::to_value(self),
+            ) {
+                if old_value == new_value {
+                    return Ok(());
+                }
+            }
         }
+
+        std::fs::create_dir_all(app_dir())?;
+
+        // Write to a temporary file first so that an interruption can't leave a partial file.
+        let temp = app_dir().join(format!("{}.tmp", Self::FILE_NAME));
+        std::fs::write(&temp, new_content.as_bytes())?;
+        std::fs::rename(&temp, Self::path())?;
+        Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::{
     lang::Language,
@@ -24,6 +24,8 @@ pub struct Cache {
     pub backup: Backup,
     #[serde(default)]
     pub restore: Restore,
+    #[serde(default)]
+    pub cloud: Cloud,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -58,6 +60,17 @@ pub struct Restore {
     pub recent_games: BTreeSet<String>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Cloud {
+    /// Set when an automatic sync was skipped because of a metered connection,
+    /// so that the next one covers every game rather than just the ones in that run.
+    #[serde(default)]
+    pub deferred: bool,
+    /// Chunked uploads that haven't finished yet, by the SHA-1 hash of the file.
+    #[serde(default)]
+    pub uploads: BTreeMap<String, crate::cloud::chunked::PartialUpload>,
+}
+
 impl ResourceFile for Cache {
     const FILE_NAME: &'static str = "cache.yaml";
 }
@@ -1,3 +1,8 @@
+mod migration;
+mod roots;
+mod sharing;
+mod titles;
+
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     num::NonZeroUsize,
@@ -7,14 +12,21 @@ use std::{
 use crate::{
     cloud::Remote,
     lang::{Language, TRANSLATOR},
-    prelude::{app_dir, Error, StrictPath, AVAILABLE_PARALELLISM},
+    prelude::{app_dir, AnyError, Error, StrictPath, AVAILABLE_PARALELLISM},
     resource::{
-        manifest::{Manifest, Store},
+        manifest::{Manifest, ManifestFallback, Store},
         ResourceFile, SaveableResourceFile,
     },
     scan::registry_compat::RegistryItem,
 };
 
+pub use self::{
+    migration::ConfigChange,
+    roots::{find_redundant_roots, RedundantRoot, RootRedundancy},
+    sharing::{ImportChange, ImportMode, ImportOutcome, ImportSection, SharedConfig},
+    titles::{DanglingTitle, TitleReference},
+};
+
 const MANIFEST_URL: &str = "https://raw.githubusercontent.com/mtkennerly/ludusavi-manifest/master/data/manifest.yaml";
 
 fn default_backup_dir() -> StrictPath {
@@ -23,7 +35,7 @@ fn default_backup_dir() -> StrictPath {
     StrictPath::from_std_path_buf(&path)
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Config {
     #[serde(default)]
     pub runtime: Runtime,
@@ -43,21 +55,38 @@ pub struct Config {
     pub cloud: Cloud,
     #[serde(default)]
     pub apps: Apps,
+    #[serde(default)]
+    pub history: History,
     #[serde(default, rename = "customGames")]
     pub custom_games: Vec<CustomGame>,
+    #[serde(default, rename = "mergedGames")]
+    pub merged_games: Vec<MergedGame>,
+    #[serde(default, rename = "pathMigrations", skip_serializing_if = "Vec::is_empty")]
+    pub path_migrations: Vec<PathMigrationConfig>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Runtime {
     #[serde(default)]
     pub threads: Option<NonZeroUsize>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ManifestConfig {
     pub url: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub secondary: Vec<SecondaryManifestConfig>,
+    /// Hash of the primary manifest as of when it was loaded (see `Manifest::load_with_hash`).
+    /// This is only kept in memory, for new backups to record.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub loaded_hash: Option<String>,
+    /// Set if the primary manifest was unreadable when it was loaded,
+    /// so that an older copy was used instead (see `Manifest::load_with_hash`).
+    /// This is only kept in memory, for reports to mention.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub loaded_fallback: Option<ManifestFallback>,
 }
 
 impl ManifestConfig {
@@ -95,7 +124,7 @@ impl ManifestConfig {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum SecondaryManifestConfigKind {
     Local,
     #[default]
@@ -115,7 +144,7 @@ impl ToString for SecondaryManifestConfigKind {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum SecondaryManifestConfig {
     Local { path: StrictPath },
@@ -179,7 +208,7 @@ impl Default for SecondaryManifestConfig {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum Theme {
     #[default]
     #[serde(rename = "light")]
@@ -198,7 +227,19 @@ impl ToString for Theme {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct RootsConfig {
     pub path: StrictPath,
     pub store: Store,
@@ -240,7 +281,7 @@ impl RootsConfig {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct RedirectConfig {
     #[serde(default)]
     pub kind: RedirectKind,
@@ -248,7 +289,7 @@ pub struct RedirectConfig {
     pub target: StrictPath,
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum RedirectKind {
     #[serde(rename = "backup")]
     Backup,
@@ -269,7 +310,7 @@ impl ToString for RedirectKind {
     }
 }
 
-#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct BackupFilter {
     #[serde(default, rename = "excludeStoreScreenshots")]
     pub exclude_store_screenshots: bool,
@@ -349,13 +390,13 @@ impl BackupFilter {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ToggledPaths(BTreeMap<String, BTreeMap<StrictPath, bool>>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ToggledRegistry(BTreeMap<String, BTreeMap<RegistryItem, ToggledRegistryEntry>>);
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(untagged)]
 pub enum ToggledRegistryEntry {
     Unset,
@@ -462,7 +503,7 @@ impl ToggledRegistryEntry {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum SortKey {
     #[serde(rename = "name")]
     Name,
@@ -483,18 +524,27 @@ impl ToString for SortKey {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Sort {
     pub key: SortKey,
     pub reversed: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Retention {
     pub full: u8,
     pub differential: u8,
     #[serde(default, skip)]
     pub force_new_full: bool,
+    /// Make a new full backup instead of a differential one
+    /// once the differential backups would add up to more than this.
+    /// A percentage is relative to the size of the full backup.
+    #[serde(
+        default,
+        rename = "promoteFullWhenDiffBytesExceed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub promote_full_when_diff_bytes_exceed: Option<SizeThreshold>,
 }
 
 impl Default for Retention {
@@ -503,11 +553,86 @@ impl Default for Retention {
             full: 1,
             differential: 0,
             force_new_full: false,
+            promote_full_when_diff_bytes_exceed: None,
+        }
+    }
+}
+
+/// A size limit, either relative to some other size (`50%`)
+/// or absolute (`500 MB`, or a plain number of bytes).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SizeThreshold {
+    Percent(u32),
+    Bytes(u64),
+}
+
+impl SizeThreshold {
+    pub fn resolve(&self, relative_to: u64) -> u64 {
+        match self {
+            Self::Percent(percent) => relative_to.saturating_mul(*percent as u64) / 100,
+            Self::Bytes(bytes) => *bytes,
+        }
+    }
+}
+
+impl std::str::FromStr for SizeThreshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            return percent
+                .trim()
+                .parse()
+                .map(Self::Percent)
+                .map_err(|_| format!("invalid percentage: {s}"));
+        }
+
+        byte_unit::Byte::from_str(s)
+            .ok()
+            .and_then(|x| u64::try_from(x.get_bytes()).ok())
+            .map(Self::Bytes)
+            .ok_or_else(|| format!("expected a percentage like `50%` or a size like `500 MB`: {s}"))
+    }
+}
+
+impl serde::Serialize for SizeThreshold {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Percent(percent) => serializer.serialize_str(&format!("{percent}%")),
+            Self::Bytes(bytes) => serializer.serialize_u64(*bytes),
+        }
+    }
+}
+
+/// Either a number of bytes or a string like `50%` or `500 MB`.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+enum SizeThresholdSerdeHelper {
+    Bytes(u64),
+    Text(String),
+}
+
+impl<'de> serde::Deserialize<'de> for SizeThreshold {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match SizeThresholdSerdeHelper::deserialize(deserializer)? {
+            SizeThresholdSerdeHelper::Bytes(bytes) => Ok(Self::Bytes(bytes)),
+            SizeThresholdSerdeHelper::Text(text) => text.parse().map_err(serde::de::Error::custom),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+impl schemars::JsonSchema for SizeThreshold {
+    fn schema_name() -> String {
+        "SizeThreshold".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        SizeThresholdSerdeHelper::json_schema(gen)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum BackupFormat {
     #[default]
     #[serde(rename = "simple")]
@@ -539,7 +664,7 @@ impl ToString for BackupFormat {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct BackupFormats {
     pub chosen: BackupFormat,
     pub zip: ZipConfig,
@@ -591,12 +716,12 @@ impl BackupFormats {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ZipConfig {
     pub compression: ZipCompression,
 }
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum ZipCompression {
     #[serde(rename = "none")]
     None,
@@ -634,7 +759,7 @@ impl ToString for ZipCompression {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Compression {
     deflate: DeflateCompression,
     bzip2: Bzip2Compression,
@@ -658,7 +783,7 @@ impl Compression {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct DeflateCompression {
     level: i32,
 }
@@ -673,7 +798,7 @@ impl DeflateCompression {
     pub const RANGE: std::ops::RangeInclusive<i32> = 1..=9;
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Bzip2Compression {
     level: i32,
 }
@@ -688,7 +813,7 @@ impl Bzip2Compression {
     pub const RANGE: std::ops::RangeInclusive<i32> = 1..=9;
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ZstdCompression {
     level: i32,
 }
@@ -703,7 +828,7 @@ impl ZstdCompression {
     pub const RANGE: std::ops::RangeInclusive<i32> = -7..=22;
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct BackupConfig {
     pub path: StrictPath,
     #[serde(
@@ -713,6 +838,19 @@ pub struct BackupConfig {
     )]
     pub ignored_games: HashSet<String>,
     #[serde(default)]
+    pub mode: BackupMode,
+    /// Games to back up with `BackupMode::OnlyEnabledList`.
+    #[serde(
+        default,
+        rename = "enabledGames",
+        serialize_with = "crate::serialization::ordered_set"
+    )]
+    pub enabled_games: HashSet<String>,
+    /// Named lists of games to back up with `BackupMode::OnlyEnabledList`,
+    /// in addition to `enabled_games`.
+    #[serde(default, rename = "gameLists", serialize_with = "crate::serialization::ordered_map")]
+    pub game_lists: HashMap<String, Vec<String>>,
+    #[serde(default)]
     pub filter: BackupFilter,
     #[serde(default, rename = "toggledPaths")]
     pub toggled_paths: ToggledPaths,
@@ -724,9 +862,53 @@ pub struct BackupConfig {
     pub retention: Retention,
     #[serde(default)]
     pub format: BackupFormats,
+    /// Copy a file one more time if it changes while being backed up.
+    /// Zip entries can't be rewritten, so this only applies to the simple format.
+    #[serde(default, rename = "retryChangedFiles")]
+    pub retry_changed_files: bool,
+    /// Try this many more times to back up a file that's locked by another process
+    /// (e.g., a running game). Locked files are tried again together after the game's other files,
+    /// waiting a few seconds before each attempt.
+    /// This only applies on Windows, where locked files can't be read.
+    #[serde(default, rename = "retryLocked")]
+    pub retry_locked: u32,
+    /// Skip games with a running process under their install folder,
+    /// so that they can be backed up later once they've exited.
+    #[serde(default, rename = "skipIfRunning")]
+    pub skip_if_running: bool,
+}
+
+impl BackupConfig {
+    /// Whether the game was explicitly selected for `BackupMode::OnlyEnabledList`.
+    pub fn is_listed(&self, name: &str) -> bool {
+        self.enabled_games.contains(name) || self.game_lists.values().any(|games| games.iter().any(|x| x == name))
+    }
+}
+
+/// Which games to back up when no specific games are requested.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub enum BackupMode {
+    /// Back up every game that hasn't been disabled.
+    #[default]
+    #[serde(rename = "allGamesExceptDisabled")]
+    AllGamesExceptDisabled,
+    /// Only back up games in `enabledGames` or `gameLists`.
+    /// Other games are skipped without being scanned.
+    #[serde(rename = "onlyEnabledList")]
+    OnlyEnabledList,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+impl BackupMode {
+    pub const ALL: &'static [Self] = &[Self::AllGamesExceptDisabled, Self::OnlyEnabledList];
+}
+
+impl std::fmt::Display for BackupMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&TRANSLATOR.backup_mode_name(self))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct RestoreConfig {
     pub path: StrictPath,
     #[serde(
@@ -741,9 +923,76 @@ pub struct RestoreConfig {
     pub toggled_registry: ToggledRegistry,
     #[serde(default)]
     pub sort: Sort,
+    #[serde(default, rename = "remapUserProfile")]
+    pub remap_user_profile: RemapUserProfile,
+    /// Skip games that seem to be running, since they would likely overwrite the restored saves.
+    #[serde(default, rename = "blockIfRunning")]
+    pub block_if_running: bool,
+    /// Steam account ID to restore into when a backup's account folder doesn't exist
+    /// and there are several accounts on this system.
+    #[serde(default, rename = "preferAccount")]
+    pub prefer_account: Option<String>,
+    /// When restoring into a Wine prefix, which user folder to use if there are several.
+    #[serde(default, rename = "preferWineUser")]
+    pub prefer_wine_user: PreferWineUser,
+    /// Refuse to read zip entries that would expand more than this many times their compressed size.
+    /// Zero means there is no limit.
+    #[serde(
+        default = "crate::serialization::default_max_compression_ratio",
+        rename = "maxCompressionRatio"
+    )]
+    pub max_compression_ratio: u64,
+    /// Refuse to read zip files that would expand to more than this many bytes.
+    /// Zero means there is no limit.
+    #[serde(
+        default = "crate::serialization::default_max_uncompressed_size",
+        rename = "maxUncompressedSize"
+    )]
+    pub max_uncompressed_size: u64,
+    /// In the CLI, when restoring without naming any games,
+    /// show a summary and ask for confirmation if more than this many games would be restored.
+    #[serde(
+        default = "crate::serialization::default_confirm_when_games_exceed",
+        rename = "confirmWhenGamesExceed"
+    )]
+    pub confirm_when_games_exceed: usize,
+    /// Back up each game's current files before restoring over them,
+    /// as a locked backup with a comment so that retention never removes it.
+    #[serde(default, rename = "backupBeforeRestore")]
+    pub backup_before_restore: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// What to do when a backup's files are in another user's profile folder.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub enum RemapUserProfile {
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "off")]
+    Off,
+    #[serde(rename = "ask")]
+    Ask,
+}
+
+impl RemapUserProfile {
+    /// Outside of the CLI, we can't prompt, so `ask` behaves like `auto`.
+    pub fn enabled(&self) -> bool {
+        *self != Self::Off
+    }
+}
+
+/// Which user folder to restore into when a Wine prefix has several,
+/// like `steamuser` from current versions of Proton and the real user name from older ones.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub enum PreferWineUser {
+    #[default]
+    #[serde(rename = "steamuser")]
+    Steamuser,
+    #[serde(rename = "username")]
+    Username,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Scan {
     #[serde(default = "crate::serialization::default_true")]
@@ -752,6 +1001,44 @@ pub struct Scan {
     pub show_unchanged_games: bool,
     #[serde(default = "crate::serialization::default_true")]
     pub show_unscanned_games: bool,
+    /// Whether to read and write the Windows registry at all.
+    #[serde(default = "crate::serialization::default_true")]
+    pub registry: bool,
+    /// Per-game exceptions to the `registry` setting.
+    #[serde(default, serialize_with = "crate::serialization::ordered_map")]
+    pub registry_games: HashMap<String, bool>,
+    /// Ignore crash reports, logs, and shader caches by default (see `scan::junk`).
+    /// Users can still re-enable specific paths per game.
+    #[serde(default)]
+    pub exclude_common_junk: bool,
+    /// How to normalize file names before comparing them between scans and backups.
+    #[serde(default)]
+    pub unicode_normalization: UnicodeNormalization,
+    /// Seconds to wait for a file system operation before skipping that location,
+    /// such as a network mount that stopped responding. Disabled by default.
+    #[serde(default)]
+    pub io_timeout: Option<u64>,
+    /// For games with more files than this, the CLI summarizes them by folder
+    /// instead of listing each one. Disabled when unset.
+    #[serde(default = "crate::serialization::default_file_list_limit")]
+    pub file_list_limit: Option<usize>,
+    /// Whether the CLI's `--api` output includes each file's hash.
+    #[serde(default = "crate::serialization::default_true")]
+    pub report_file_hashes: bool,
+}
+
+/// Some systems store accented characters precomposed (NFC), while others (notably Mac)
+/// decompose them (NFD), so the same file name may come back with different bytes.
+/// This only affects comparisons; the original names are still stored for restoring.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub enum UnicodeNormalization {
+    #[default]
+    #[serde(rename = "nfc")]
+    Nfc,
+    #[serde(rename = "nfd")]
+    Nfd,
+    #[serde(rename = "none")]
+    None,
 }
 
 impl Default for Scan {
@@ -760,11 +1047,18 @@ impl Default for Scan {
             show_deselected_games: true,
             show_unchanged_games: true,
             show_unscanned_games: true,
+            registry: true,
+            registry_games: HashMap::new(),
+            exclude_common_junk: false,
+            unicode_normalization: UnicodeNormalization::default(),
+            io_timeout: None,
+            file_list_limit: crate::serialization::default_file_list_limit(),
+            report_file_hashes: true,
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Cloud {
     #[serde(default)]
@@ -773,6 +1067,11 @@ pub struct Cloud {
     pub path: String,
     #[serde(default = "crate::serialization::default_true")]
     pub synchronize: bool,
+    /// What to do about automatic synchronization when the network connection is metered.
+    #[serde(default)]
+    pub on_metered: OnMetered,
+    #[serde(default)]
+    pub chunked_upload: ChunkedUpload,
 }
 
 impl Default for Cloud {
@@ -781,18 +1080,106 @@ impl Default for Cloud {
             remote: Default::default(),
             path: "ludusavi-backup".to_string(),
             synchronize: true,
+            on_metered: Default::default(),
+            chunked_upload: Default::default(),
         }
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// Large files can be stored in chunks (using Rclone's chunker backend),
+/// so that the CLI can resume an interrupted upload instead of starting over.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkedUpload {
+    /// Files of at least this many MiB are uploaded in chunks.
+    /// When unset, chunking is disabled.
+    #[serde(default)]
+    pub threshold: Option<u64>,
+    /// Size of each chunk, in MiB.
+    #[serde(default = "crate::serialization::default_chunk_size")]
+    pub chunk_size: u64,
+}
+
+impl Default for ChunkedUpload {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            chunk_size: crate::serialization::default_chunk_size(),
+        }
+    }
+}
+
+impl ChunkedUpload {
+    const MIB: u64 = 1024 * 1024;
+
+    pub fn enabled(&self) -> bool {
+        self.threshold.is_some()
+    }
+
+    pub fn threshold_bytes(&self) -> Option<u64> {
+        self.threshold.map(|x| x.max(1) * Self::MIB)
+    }
+
+    pub fn chunk_size_bytes(&self) -> u64 {
+        self.chunk_size.max(1) * Self::MIB
+    }
+}
+
+/// Metered connections are only detected where the OS reports them,
+/// and any other connection is treated as unmetered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub enum OnMetered {
+    /// Synchronize anyway.
+    #[serde(rename = "proceed")]
+    Proceed,
+    /// Wait for an unmetered connection.
+    #[serde(rename = "skip")]
+    Skip,
+    /// Let the user decide in the GUI. The CLI waits unless `--allow-metered` is set.
+    #[default]
+    #[serde(rename = "ask")]
+    Ask,
+}
+
+impl OnMetered {
+    pub const ALL: &'static [Self] = &[Self::Proceed, Self::Skip, Self::Ask];
+}
+
+impl std::fmt::Display for OnMetered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&TRANSLATOR.on_metered_name(self))
+    }
+}
+
+/// A local log of recent operations (see `ludusavi history`).
+/// This is opt-in and is never sent anywhere.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct History {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many of the most recent operations to keep.
+    #[serde(default = "crate::serialization::default_history_limit")]
+    pub limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            limit: crate::history::DEFAULT_LIMIT,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Apps {
     #[serde(default)]
     pub rclone: App,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct App {
     #[serde(default)]
@@ -807,7 +1194,7 @@ impl App {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct CustomGame {
     pub name: String,
     #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
@@ -841,6 +1228,24 @@ impl CustomGame {
     }
 }
 
+/// Several manifest titles that should be treated as one game.
+/// Their save locations are combined, and the backups are stored under `name`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct MergedGame {
+    pub name: String,
+    #[serde(default)]
+    pub titles: Vec<String>,
+}
+
+/// A save location that moved between versions of a game,
+/// in addition to any from the manifest (see `PathMigration`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct PathMigrationConfig {
+    pub game: String,
+    pub from: String,
+    pub to: String,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CustomGameKind {
     Game,
@@ -862,6 +1267,8 @@ impl Default for ManifestConfig {
         Self {
             url: MANIFEST_URL.to_string(),
             secondary: vec![],
+            loaded_hash: None,
+            loaded_fallback: None,
         }
     }
 }
@@ -871,12 +1278,18 @@ impl Default for BackupConfig {
         Self {
             path: default_backup_dir(),
             ignored_games: HashSet::new(),
+            mode: Default::default(),
+            enabled_games: HashSet::new(),
+            game_lists: HashMap::new(),
             filter: BackupFilter::default(),
             toggled_paths: Default::default(),
             toggled_registry: Default::default(),
             sort: Default::default(),
             retention: Retention::default(),
             format: Default::default(),
+            retry_changed_files: false,
+            retry_locked: 0,
+            skip_if_running: false,
         }
     }
 }
@@ -889,6 +1302,14 @@ impl Default for RestoreConfig {
             toggled_paths: Default::default(),
             toggled_registry: Default::default(),
             sort: Default::default(),
+            remap_user_profile: Default::default(),
+            block_if_running: false,
+            prefer_account: None,
+            prefer_wine_user: Default::default(),
+            max_compression_ratio: crate::scan::zip_safety::DEFAULT_MAX_COMPRESSION_RATIO,
+            max_uncompressed_size: crate::scan::zip_safety::DEFAULT_MAX_UNCOMPRESSED_SIZE,
+            confirm_when_games_exceed: crate::serialization::default_confirm_when_games_exceed(),
+            backup_before_restore: false,
         }
     }
 }
@@ -934,6 +1355,10 @@ impl ResourceFile for Config {
 
         self
     }
+
+    fn load_from_string(content: &str) -> Result<Self, AnyError> {
+        Self::migrate_from_string(content).map(|(config, _)| config)
+    }
 }
 
 impl SaveableResourceFile for Config {}
@@ -946,7 +1371,41 @@ impl Config {
     }
 
     pub fn load() -> Result<Self, Error> {
-        ResourceFile::load().map_err(|e| Error::ConfigInvalid { why: format!("{}", e) })
+        let (config, changes) = Self::load_with_changes()?;
+        for change in changes {
+            log::info!("Config file will be updated on next save: {change:?}");
+        }
+        Ok(config)
+    }
+
+    /// Load the config and list how the file would change if Ludusavi saved it again.
+    pub fn load_with_changes() -> Result<(Self, Vec<ConfigChange>), Error> {
+        let path = Self::path();
+        let loaded = if path.exists() {
+            Self::load_raw(&path).and_then(|content| Self::migrate_from_string(&content))
+        } else {
+            Ok((Self::default().initialize(), vec![]))
+        };
+        let (config, changes) = loaded.map_err(|e| Error::ConfigInvalid { why: format!("{}", e) })?;
+        for redundant in config.redundant_roots() {
+            log::warn!(
+                "Root is redundant ({:?}): {} | covered by: {}",
+                redundant.redundancy,
+                redundant.root.path.raw(),
+                redundant.kept.path.raw()
+            );
+        }
+        Ok((config, changes))
+    }
+
+    /// Parse a config file and list how it would change if Ludusavi saved it again,
+    /// such as new settings being added with their default values.
+    pub fn migrate_from_string(content: &str) -> Result<(Self, Vec<ConfigChange>), AnyError> {
+        let mut raw: serde_yaml::Value = serde_yaml::from_str(content)?;
+        let mut changes = migration::apply_renames(&mut raw);
+        let config: Self = ResourceFile::migrate(serde_yaml::from_value(raw.clone())?);
+        changes.extend(migration::diff(&raw, &serde_yaml::to_value(&config)?));
+        Ok((config, changes))
     }
 
     pub fn archive_invalid() -> Result<(), Box<dyn std::error::Error>> {
@@ -1100,15 +1559,39 @@ impl Config {
     }
 
     pub fn is_game_enabled_for_backup(&self, name: &str) -> bool {
-        !self.backup.ignored_games.contains(name)
+        match self.backup.mode {
+            BackupMode::AllGamesExceptDisabled => !self.backup.ignored_games.contains(name),
+            BackupMode::OnlyEnabledList => self.backup.is_listed(name) && !self.backup.ignored_games.contains(name),
+        }
     }
 
     pub fn enable_game_for_backup(&mut self, name: &str) {
         self.backup.ignored_games.remove(name);
+        if self.backup.mode == BackupMode::OnlyEnabledList && !self.backup.is_listed(name) {
+            self.backup.enabled_games.insert(name.to_owned());
+        }
+    }
+
+    /// With `BackupMode::OnlyEnabledList`, games that aren't enabled are left out of full backups entirely,
+    /// instead of being scanned and then ignored.
+    pub fn is_game_skipped_for_backup(&self, name: &str) -> bool {
+        self.backup.mode == BackupMode::OnlyEnabledList && !self.is_game_enabled_for_backup(name)
     }
 
+    /// With `BackupMode::OnlyEnabledList`, this only adds the game to `ignoredGames`
+    /// if it's still in one of the `gameLists`.
     pub fn disable_game_for_backup(&mut self, name: &str) {
-        self.backup.ignored_games.insert(name.to_owned());
+        match self.backup.mode {
+            BackupMode::AllGamesExceptDisabled => {
+                self.backup.ignored_games.insert(name.to_owned());
+            }
+            BackupMode::OnlyEnabledList => {
+                self.backup.enabled_games.remove(name);
+                if self.backup.is_listed(name) {
+                    self.backup.ignored_games.insert(name.to_owned());
+                }
+            }
+        }
     }
 
     pub fn is_game_enabled_for_restore(&self, name: &str) -> bool {
@@ -1123,6 +1606,22 @@ impl Config {
         self.restore.ignored_games.insert(name.to_owned());
     }
 
+    pub fn is_registry_enabled(&self, name: &str) -> bool {
+        self.scan
+            .registry_games
+            .get(name)
+            .copied()
+            .unwrap_or(self.scan.registry)
+    }
+
+    pub fn set_registry_enabled(&mut self, name: &str, enabled: bool) {
+        if enabled == self.scan.registry {
+            self.scan.registry_games.remove(name);
+        } else {
+            self.scan.registry_games.insert(name.to_owned(), enabled);
+        }
+    }
+
     pub fn any_saves_ignored(&self, name: &str, restoring: bool) -> bool {
         if restoring {
             self.restore
@@ -1178,6 +1677,19 @@ impl Config {
         });
     }
 
+    /// For each merged game, the titles that were combined into it.
+    pub fn merged_titles(&self) -> HashMap<String, Vec<String>> {
+        self.merged_games
+            .iter()
+            .map(|x| {
+                (
+                    x.name.clone(),
+                    x.titles.iter().filter(|title| **title != x.name).cloned().collect(),
+                )
+            })
+            .collect()
+    }
+
     pub fn is_game_customized(&self, name: &str) -> bool {
         self.custom_games.iter().any(|x| x.name == name)
     }
@@ -1214,7 +1726,20 @@ impl Config {
             );
         }
 
-        let expanded: Vec<RootsConfig> = self.roots.iter().flat_map(|x| x.glob()).collect();
+        let mut expanded: Vec<RootsConfig> = self.roots.iter().flat_map(|x| x.glob()).collect();
+
+        // Scanning the same place twice would make every save look like its own duplicate.
+        for redundant in find_redundant_roots(&expanded) {
+            log::info!(
+                "Skipping redundant root ({:?}): {} | covered by: {}",
+                &redundant.root.store,
+                redundant.root.path.raw(),
+                redundant.kept.path.raw()
+            );
+            if let Some(index) = expanded.iter().position(|x| *x == redundant.root) {
+                expanded.remove(index);
+            }
+        }
 
         for root in &expanded {
             log::trace!(
@@ -1255,6 +1780,10 @@ impl ToggledPaths {
         Self(data)
     }
 
+    pub fn for_game(&self, game: &str) -> Option<&BTreeMap<StrictPath, bool>> {
+        self.0.get(game)
+    }
+
     pub fn invalidate_path_caches(&self) {
         for inner in self.0.values() {
             for key in inner.keys() {
@@ -1264,12 +1793,18 @@ impl ToggledPaths {
     }
 
     pub fn is_ignored(&self, game: &str, path: &StrictPath) -> bool {
+        self.is_ignored_with_default(game, path, false)
+    }
+
+    /// Like `is_ignored`, but for paths that are ignored unless the user says otherwise
+    /// (e.g., because of the built-in junk filter).
+    pub fn is_ignored_with_default(&self, game: &str, path: &StrictPath, ignored_by_default: bool) -> bool {
         let transitive = self.is_enabled_transitively(game, path);
         let specific = self.is_enabled_specifically(game, path);
         match (transitive, specific) {
             (_, Some(x)) => !x,
             (Some(x), _) => !x,
-            _ => false,
+            _ => ignored_by_default,
         }
     }
 
@@ -1320,7 +1855,13 @@ impl ToggledPaths {
     }
 
     pub fn toggle(&mut self, game: &str, path: &StrictPath) {
-        let transitive = self.is_enabled_transitively(game, path);
+        self.toggle_with_default(game, path, false);
+    }
+
+    pub fn toggle_with_default(&mut self, game: &str, path: &StrictPath, ignored_by_default: bool) {
+        let transitive = self
+            .is_enabled_transitively(game, path)
+            .or(ignored_by_default.then_some(false));
         let specific = self.is_enabled_specifically(game, path);
         match (transitive, specific) {
             (None, None | Some(true)) => {
@@ -1349,6 +1890,10 @@ impl ToggledRegistry {
         Self(data)
     }
 
+    pub fn for_game(&self, game: &str) -> Option<&BTreeMap<RegistryItem, ToggledRegistryEntry>> {
+        self.0.get(game)
+    }
+
     fn prune(&mut self, game: &str, path: &RegistryItem) {
         if !self.0.contains_key(game) {
             return;
@@ -1509,7 +2054,7 @@ impl ToggledRegistry {
 
 #[cfg(test)]
 mod tests {
-    use maplit::hashset;
+    use maplit::{hashmap, hashset};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -1539,7 +2084,9 @@ mod tests {
                 runtime: Default::default(),
                 manifest: ManifestConfig {
                     url: s("example.com"),
-                    secondary: vec![]
+                    secondary: vec![],
+                    loaded_hash: None,
+                    loaded_fallback: None,
                 },
                 language: Language::English,
                 theme: Theme::Light,
@@ -1548,6 +2095,9 @@ mod tests {
                 backup: BackupConfig {
                     path: StrictPath::new(s("~/backup")),
                     ignored_games: HashSet::new(),
+                    mode: Default::default(),
+                    enabled_games: HashSet::new(),
+                    game_lists: HashMap::new(),
                     filter: BackupFilter {
                         exclude_store_screenshots: false,
                         ..Default::default()
@@ -1557,6 +2107,9 @@ mod tests {
                     sort: Default::default(),
                     retention: Retention::default(),
                     format: Default::default(),
+                    retry_changed_files: false,
+                    retry_locked: 0,
+                    skip_if_running: false,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -1564,6 +2117,14 @@ mod tests {
                     toggled_paths: Default::default(),
                     toggled_registry: Default::default(),
                     sort: Default::default(),
+                    remap_user_profile: Default::default(),
+                    block_if_running: false,
+                    prefer_account: None,
+                    prefer_wine_user: Default::default(),
+                    max_compression_ratio: 200,
+                    max_uncompressed_size: 68_719_476_736,
+                    confirm_when_games_exceed: 1,
+                    backup_before_restore: false,
                 },
                 scan: Default::default(),
                 apps: Apps {
@@ -1572,6 +2133,7 @@ mod tests {
                         ..Default::default()
                     }
                 },
+                history: Default::default(),
                 custom_games: vec![],
                 ..Default::default()
             },
@@ -1603,6 +2165,12 @@ mod tests {
                 - Backup Game 1
                 - Backup Game 2
                 - Backup Game 2
+              mode: onlyEnabledList
+              enabledGames:
+                - Backup Game 4
+              gameLists:
+                kids:
+                  - Backup Game 5
               filter:
                 excludeStoreScreenshots: true
             restore:
@@ -1611,16 +2179,32 @@ mod tests {
                 - Restore Game 1
                 - Restore Game 2
                 - Restore Game 2
+              blockIfRunning: true
+              preferAccount: "12345678"
+              backupBeforeRestore: true
+              preferWineUser: username
             scan:
               showDeselectedGames: false
               showUnchangedGames: false
               showUnscannedGames: false
+              registry: false
+              registryGames:
+                Registry Game: true
+              excludeCommonJunk: true
+              unicodeNormalization: nfd
+              ioTimeout: 30
+              fileListLimit: 50
+              reportFileHashes: false
             cloud:
               remote:
                 GoogleDrive:
                   id: remote-id
               path: ludusavi-backup
               synchronize: false
+              onMetered: skip
+              chunkedUpload:
+                threshold: 1024
+                chunkSize: 32
             apps:
               rclone:
                 path: rclone.exe
@@ -1647,7 +2231,9 @@ mod tests {
                     url: s("example.com"),
                     secondary: vec![SecondaryManifestConfig::Remote {
                         url: s("example.com/2")
-                    }]
+                    }],
+                    loaded_hash: None,
+                    loaded_fallback: None,
                 },
                 language: Language::English,
                 theme: Theme::Light,
@@ -1672,6 +2258,11 @@ mod tests {
                         s("Backup Game 1"),
                         s("Backup Game 2"),
                     },
+                    mode: BackupMode::OnlyEnabledList,
+                    enabled_games: hashset! { s("Backup Game 4") },
+                    game_lists: hashmap! {
+                        s("kids") => vec![s("Backup Game 5")],
+                    },
                     filter: BackupFilter {
                         exclude_store_screenshots: true,
                         ..Default::default()
@@ -1681,6 +2272,9 @@ mod tests {
                     sort: Default::default(),
                     retention: Retention::default(),
                     format: Default::default(),
+                    retry_changed_files: false,
+                    retry_locked: 0,
+                    skip_if_running: false,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -1691,11 +2285,28 @@ mod tests {
                     toggled_paths: Default::default(),
                     toggled_registry: Default::default(),
                     sort: Default::default(),
+                    remap_user_profile: Default::default(),
+                    block_if_running: true,
+                    prefer_account: Some(s("12345678")),
+                    prefer_wine_user: PreferWineUser::Username,
+                    max_compression_ratio: 200,
+                    max_uncompressed_size: 68_719_476_736,
+                    confirm_when_games_exceed: 1,
+                    backup_before_restore: true,
                 },
                 scan: Scan {
                     show_deselected_games: false,
                     show_unchanged_games: false,
                     show_unscanned_games: false,
+                    registry: false,
+                    registry_games: hashmap! {
+                        s("Registry Game") => true,
+                    },
+                    exclude_common_junk: true,
+                    unicode_normalization: UnicodeNormalization::Nfd,
+                    io_timeout: Some(30),
+                    file_list_limit: Some(50),
+                    report_file_hashes: false,
                 },
                 cloud: Cloud {
                     remote: Some(Remote::GoogleDrive {
@@ -1703,6 +2314,11 @@ mod tests {
                     }),
                     path: "ludusavi-backup".to_string(),
                     synchronize: false,
+                    on_metered: OnMetered::Skip,
+                    chunked_upload: ChunkedUpload {
+                        threshold: Some(1024),
+                        chunk_size: 32,
+                    },
                 },
                 apps: Apps {
                     rclone: App {
@@ -1710,6 +2326,7 @@ mod tests {
                         arguments: "".to_string(),
                     },
                 },
+                history: Default::default(),
                 custom_games: vec![
                     CustomGame {
                         name: s("Custom Game 1"),
@@ -1726,11 +2343,79 @@ mod tests {
                         registry: vec![s("Custom Registry 1"), s("Custom Registry 2"), s("Custom Registry 2"),],
                     },
                 ],
+                merged_games: vec![],
+                path_migrations: vec![],
             },
             config,
         );
     }
 
+    #[test]
+    fn config_matches_its_schema() {
+        let config = Config::load_from_string(
+            r#"
+            manifest:
+              url: example.com
+              secondary:
+                - url: example.com/2
+                - path: ~/manifest.yaml
+            roots:
+              - path: ~/steam
+                store: steam
+            redirects:
+              - kind: restore
+                source: ~/old
+                target: ~/new
+            backup:
+              path: ~/backup
+              ignoredGames:
+                - Backup Game 1
+              toggledPaths:
+                Backup Game 2:
+                  ~/save.dat: false
+              toggledRegistry:
+                Backup Game 3:
+                  HKEY_CURRENT_USER/Software/Foo:
+                    key: false
+                    values:
+                      Bar: true
+              retention:
+                full: 2
+                differential: 3
+                promoteFullWhenDiffBytesExceed: 50%
+            restore:
+              path: ~/restore
+              preferWineUser: username
+            scan:
+              unicodeNormalization: nfd
+              ioTimeout: 30
+            cloud:
+              remote:
+                Ftp:
+                  id: remote-id
+                  host: example.com
+                  port: 21
+                  username: user
+            customGames:
+              - name: Custom Game
+                files:
+                  - Custom File
+            "#,
+        )
+        .unwrap();
+
+        let schema = serde_json::to_value(schemars::schema_for!(Config)).unwrap();
+        let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+        let serialized = serde_json::to_value(&config).unwrap();
+        if let Err(errors) = schema.validate(&serialized) {
+            let errors: Vec<_> = errors.map(|e| format!("{} at {}", e, e.instance_path)).collect();
+            panic!("{errors:#?}");
+        }
+
+        assert_eq!(config, serde_json::from_value(serialized).unwrap());
+    }
+
     /// There was a defect previously where `Store::Other` would be serialized
     /// as `store: Other` (capitalized). This test ensures that old config files
     /// with that issue will still be accepted.
@@ -1760,7 +2445,9 @@ mod tests {
                 runtime: Default::default(),
                 manifest: ManifestConfig {
                     url: s("example.com"),
-                    secondary: vec![]
+                    secondary: vec![],
+                    loaded_hash: None,
+                    loaded_fallback: None,
                 },
                 language: Language::English,
                 theme: Theme::Light,
@@ -1772,6 +2459,9 @@ mod tests {
                 backup: BackupConfig {
                     path: StrictPath::new(s("~/backup")),
                     ignored_games: HashSet::new(),
+                    mode: Default::default(),
+                    enabled_games: HashSet::new(),
+                    game_lists: HashMap::new(),
                     filter: BackupFilter {
                         exclude_store_screenshots: false,
                         ..Default::default()
@@ -1781,6 +2471,9 @@ mod tests {
                     sort: Default::default(),
                     retention: Retention::default(),
                     format: Default::default(),
+                    retry_changed_files: false,
+                    retry_locked: 0,
+                    skip_if_running: false,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -1788,6 +2481,14 @@ mod tests {
                     toggled_paths: Default::default(),
                     toggled_registry: Default::default(),
                     sort: Default::default(),
+                    remap_user_profile: Default::default(),
+                    block_if_running: false,
+                    prefer_account: None,
+                    prefer_wine_user: Default::default(),
+                    max_compression_ratio: 200,
+                    max_uncompressed_size: 68_719_476_736,
+                    confirm_when_games_exceed: 1,
+                    backup_before_restore: false,
                 },
                 scan: Default::default(),
                 apps: Apps {
@@ -1796,6 +2497,7 @@ mod tests {
                         ..Default::default()
                     },
                 },
+                history: Default::default(),
                 custom_games: vec![],
                 ..Default::default()
             },
@@ -1829,6 +2531,9 @@ backup:
     - Backup Game 1
     - Backup Game 2
     - Backup Game 3
+  mode: allGamesExceptDisabled
+  enabledGames: []
+  gameLists: {}
   filter:
     excludeStoreScreenshots: true
     ignoredPaths: []
@@ -1852,6 +2557,9 @@ backup:
         level: 6
       zstd:
         level: 10
+  retryChangedFiles: false
+  retryLocked: 0
+  skipIfRunning: false
 restore:
   path: ~/restore
   ignoredGames:
@@ -1863,20 +2571,43 @@ restore:
   sort:
     key: status
     reversed: false
+  remapUserProfile: auto
+  blockIfRunning: false
+  preferAccount: ~
+  preferWineUser: steamuser
+  maxCompressionRatio: 200
+  maxUncompressedSize: 68719476736
+  confirmWhenGamesExceed: 1
+  backupBeforeRestore: false
 scan:
   showDeselectedGames: false
   showUnchangedGames: false
   showUnscannedGames: false
+  registry: false
+  registryGames:
+    Registry Game: true
+  excludeCommonJunk: false
+  unicodeNormalization: nfc
+  ioTimeout: ~
+  fileListLimit: 1000
+  reportFileHashes: true
 cloud:
   remote:
     GoogleDrive:
       id: remote-id
   path: ludusavi-backup
   synchronize: true
+  onMetered: ask
+  chunkedUpload:
+    threshold: ~
+    chunkSize: 64
 apps:
   rclone:
     path: rclone.exe
     arguments: ""
+history:
+  enabled: false
+  limit: 100
 customGames:
   - name: Custom Game 1
     files: []
@@ -1894,13 +2625,24 @@ customGames:
     alias: Other
     files: []
     registry: []
+mergedGames:
+  - name: Game
+    titles:
+      - Game (Disc 1)
+      - Game (Disc 2)
+pathMigrations:
+  - game: Game
+    from: "<winDocuments>/Game"
+    to: "<winLocalAppData>Low/Studio/Game"
 "#
             .trim(),
             serde_yaml::to_string(&Config {
                 runtime: Default::default(),
                 manifest: ManifestConfig {
                     url: s("example.com"),
-                    secondary: vec![]
+                    secondary: vec![],
+                    loaded_hash: None,
+                    loaded_fallback: None,
                 },
                 language: Language::English,
                 theme: Theme::Light,
@@ -1926,6 +2668,9 @@ customGames:
                         s("Backup Game 1"),
                         s("Backup Game 2"),
                     },
+                    mode: Default::default(),
+                    enabled_games: HashSet::new(),
+                    game_lists: HashMap::new(),
                     filter: BackupFilter {
                         exclude_store_screenshots: true,
                         ..Default::default()
@@ -1935,6 +2680,9 @@ customGames:
                     sort: Default::default(),
                     retention: Retention::default(),
                     format: Default::default(),
+                    retry_changed_files: false,
+                    retry_locked: 0,
+                    skip_if_running: false,
                 },
                 restore: RestoreConfig {
                     path: StrictPath::new(s("~/restore")),
@@ -1946,11 +2694,28 @@ customGames:
                     toggled_paths: Default::default(),
                     toggled_registry: Default::default(),
                     sort: Default::default(),
+                    remap_user_profile: Default::default(),
+                    block_if_running: false,
+                    prefer_account: None,
+                    prefer_wine_user: Default::default(),
+                    max_compression_ratio: 200,
+                    max_uncompressed_size: 68_719_476_736,
+                    confirm_when_games_exceed: 1,
+                    backup_before_restore: false,
                 },
                 scan: Scan {
                     show_deselected_games: false,
                     show_unchanged_games: false,
                     show_unscanned_games: false,
+                    registry: false,
+                    registry_games: hashmap! {
+                        s("Registry Game") => true,
+                    },
+                    exclude_common_junk: false,
+                    unicode_normalization: UnicodeNormalization::Nfc,
+                    io_timeout: None,
+                    file_list_limit: Some(1000),
+                    report_file_hashes: true,
                 },
                 cloud: Cloud {
                     remote: Some(Remote::GoogleDrive {
@@ -1958,6 +2723,8 @@ customGames:
                     }),
                     path: "ludusavi-backup".to_string(),
                     synchronize: true,
+                    on_metered: OnMetered::Ask,
+                    chunked_upload: ChunkedUpload::default(),
                 },
                 apps: Apps {
                     rclone: App {
@@ -1965,6 +2732,7 @@ customGames:
                         arguments: "".to_string(),
                     }
                 },
+                history: Default::default(),
                 custom_games: vec![
                     CustomGame {
                         name: s("Custom Game 1"),
@@ -1988,12 +2756,110 @@ customGames:
                         registry: vec![],
                     },
                 ],
+                merged_games: vec![MergedGame {
+                    name: s("Game"),
+                    titles: vec![s("Game (Disc 1)"), s("Game (Disc 2)")],
+                }],
+                path_migrations: vec![PathMigrationConfig {
+                    game: s("Game"),
+                    from: s("<winDocuments>/Game"),
+                    to: s("<winLocalAppData>Low/Studio/Game"),
+                }],
             })
             .unwrap()
             .trim(),
         );
     }
 
+    #[test]
+    fn registry_overrides_fall_back_to_global_setting() {
+        let mut config = Config::default();
+        assert!(config.is_registry_enabled("game"));
+
+        config.set_registry_enabled("game", false);
+        assert!(!config.is_registry_enabled("game"));
+        assert_eq!(hashmap! { s("game") => false }, config.scan.registry_games);
+
+        config.scan.registry = false;
+        assert!(!config.is_registry_enabled("other"));
+
+        config.set_registry_enabled("game", false);
+        assert!(config.scan.registry_games.is_empty());
+
+        config.set_registry_enabled("game", true);
+        assert!(config.is_registry_enabled("game"));
+        assert!(!config.is_registry_enabled("other"));
+    }
+
+    #[test]
+    fn only_listed_games_are_enabled_for_backup_in_allowlist_mode() {
+        let mut config = Config::default();
+        config.backup.mode = BackupMode::OnlyEnabledList;
+        config.backup.game_lists = hashmap! { s("kids") => vec![s("listed")] };
+        assert!(config.is_game_enabled_for_backup("listed"));
+        assert!(!config.is_game_enabled_for_backup("other"));
+
+        config.enable_game_for_backup("other");
+        assert!(config.is_game_enabled_for_backup("other"));
+        assert_eq!(hashset! { s("other") }, config.backup.enabled_games);
+
+        config.disable_game_for_backup("other");
+        config.disable_game_for_backup("listed");
+        assert!(!config.is_game_enabled_for_backup("other"));
+        assert!(!config.is_game_enabled_for_backup("listed"));
+        assert!(config.backup.enabled_games.is_empty());
+        assert_eq!(hashset! { s("listed") }, config.backup.ignored_games);
+
+        config.enable_game_for_backup("listed");
+        assert!(config.is_game_enabled_for_backup("listed"));
+        assert!(config.backup.enabled_games.is_empty());
+        assert!(config.backup.ignored_games.is_empty());
+
+        config.backup.mode = BackupMode::AllGamesExceptDisabled;
+        assert!(config.is_game_enabled_for_backup("other"));
+    }
+
+    #[test]
+    fn can_parse_retention_promotion_threshold() {
+        let parse = |raw: &str| {
+            serde_yaml::from_str::<Retention>(&format!(
+                "full: 1\ndifferential: 2\npromoteFullWhenDiffBytesExceed: {raw}"
+            ))
+            .map(|x| x.promote_full_when_diff_bytes_exceed)
+            .map_err(|_| ())
+        };
+
+        assert_eq!(Ok(Some(SizeThreshold::Percent(50))), parse("50%"));
+        assert_eq!(Ok(Some(SizeThreshold::Bytes(1024))), parse("1024"));
+        assert_eq!(Ok(Some(SizeThreshold::Bytes(500_000_000))), parse("500 MB"));
+        assert_eq!(Err(()), parse("lots"));
+        assert_eq!(Err(()), parse("x%"));
+
+        assert_eq!(
+            None,
+            serde_yaml::from_str::<Retention>("full: 1\ndifferential: 2")
+                .unwrap()
+                .promote_full_when_diff_bytes_exceed
+        );
+    }
+
+    #[test]
+    fn can_serialize_retention_promotion_threshold() {
+        let retention = |threshold| Retention {
+            promote_full_when_diff_bytes_exceed: Some(threshold),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            "---\nfull: 1\ndifferential: 0\npromoteFullWhenDiffBytesExceed: 50%\n",
+            serde_yaml::to_string(&retention(SizeThreshold::Percent(50))).unwrap()
+        );
+        assert_eq!(
+            "---\nfull: 1\ndifferential: 0\npromoteFullWhenDiffBytesExceed: 1024\n",
+            serde_yaml::to_string(&retention(SizeThreshold::Bytes(1024))).unwrap()
+        );
+    }
+
     mod ignored_paths {
         use maplit::*;
         use pretty_assertions::assert_eq;
@@ -2118,6 +2984,28 @@ customGames:
                 ],
             );
         }
+
+        #[test]
+        fn ignored_by_default_can_be_reenabled_and_reset() {
+            let mut toggled = ToggledPaths::default();
+            let path = StrictPath::new(repo_path("tests/root1/game1/subdir/file2.txt"));
+            assert!(toggled.is_ignored_with_default("game", &path, true));
+
+            toggled.toggle_with_default("game", &path, true);
+            assert!(!toggled.is_ignored_with_default("game", &path, true));
+            assert_eq!(
+                ToggledPaths(btreemap! {
+                    s("game") => btreemap! {
+                        path.clone() => true,
+                    }
+                }),
+                toggled
+            );
+
+            toggled.toggle_with_default("game", &path, true);
+            assert!(toggled.is_ignored_with_default("game", &path, true));
+            assert_eq!(ToggledPaths::default(), toggled);
+        }
     }
 
     mod ignored_registry {
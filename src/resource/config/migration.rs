@@ -0,0 +1,323 @@
+use serde_yaml::Value;
+
+/// Settings that were moved in older versions, from their old location to their current one.
+const RENAMED_KEYS: &[(&str, &str)] = &[("restore.redirects", "redirects")];
+
+/// A difference between the config file as it was written and how Ludusavi would write it now.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ConfigChange {
+    /// A new setting, which will be written with its default value.
+    Added { key: String, value: Value },
+    /// A setting that was moved from an older location.
+    Renamed { from: String, to: String },
+    /// An obsolete or unrecognized setting, which will be dropped.
+    Removed { key: String, value: Value },
+    /// A setting whose value will be normalized (e.g., blank entries removed).
+    Changed { key: String, old: Value, new: Value },
+}
+
+fn split_key(key: &str) -> Vec<Value> {
+    key.split('.').map(|x| Value::String(x.to_string())).collect()
+}
+
+fn take(raw: &mut Value, path: &[Value]) -> Option<Value> {
+    match path {
+        [] => None,
+        [last] => raw.as_mapping_mut()?.remove(last),
+        [first, rest @ ..] => take(raw.as_mapping_mut()?.get_mut(first)?, rest),
+    }
+}
+
+fn contains(raw: &Value, path: &[Value]) -> bool {
+    match path {
+        [] => true,
+        [first, rest @ ..] => raw
+            .as_mapping()
+            .and_then(|x| x.get(first))
+            .is_some_and(|x| contains(x, rest)),
+    }
+}
+
+fn insert(raw: &mut Value, path: &[Value], value: Value) {
+    let Some(mapping) = raw.as_mapping_mut() else {
+        return;
+    };
+    match path {
+        [] => {}
+        [last] => {
+            mapping.insert(last.clone(), value);
+        }
+        [first, rest @ ..] => {
+            if !mapping.contains_key(first) {
+                mapping.insert(first.clone(), Value::Mapping(Default::default()));
+            }
+            if let Some(child) = mapping.get_mut(first) {
+                insert(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Move any renamed settings to their current location in the raw config data,
+/// so that they're picked up when the config is deserialized.
+/// If a setting already exists in its current location, then the old one is left alone
+/// and will be reported as removed.
+pub fn apply_renames(raw: &mut Value) -> Vec<ConfigChange> {
+    let mut changes = vec![];
+
+    for (from, to) in RENAMED_KEYS {
+        let from_path = split_key(from);
+        let to_path = split_key(to);
+        if !contains(raw, &from_path) || contains(raw, &to_path) {
+            continue;
+        }
+        if let Some(value) = take(raw, &from_path) {
+            insert(raw, &to_path, value);
+            changes.push(ConfigChange::Renamed {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+    }
+
+    changes
+}
+
+fn render_key(prefix: &str, key: &Value) -> String {
+    let key = match key {
+        Value::String(x) => x.clone(),
+        other => serde_yaml::to_string(other)
+            .map(|x| x.trim().trim_start_matches("---").trim().to_string())
+            .unwrap_or_default(),
+    };
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn diff_into(prefix: &str, old: &Value, new: &Value, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Mapping(old), Value::Mapping(new)) => {
+            for (key, old_value) in old {
+                match new.get(key) {
+                    Some(new_value) => diff_into(&render_key(prefix, key), old_value, new_value, changes),
+                    None => changes.push(ConfigChange::Removed {
+                        key: render_key(prefix, key),
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    changes.push(ConfigChange::Added {
+                        key: render_key(prefix, key),
+                        value: new_value.clone(),
+                    });
+                }
+            }
+        }
+        _ => {
+            if old != new {
+                changes.push(ConfigChange::Changed {
+                    key: prefix.to_string(),
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Compare the raw config data (after renames) with how it would be serialized now.
+pub fn diff(old: &Value, new: &Value) -> Vec<ConfigChange> {
+    let mut changes = vec![];
+    diff_into("", old, new, &mut changes);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        prelude::StrictPath,
+        resource::config::{Config, RedirectConfig, RedirectKind},
+        testing::s,
+    };
+
+    fn yaml(raw: &str) -> Value {
+        serde_yaml::from_str(raw).unwrap()
+    }
+
+    fn added<'a>(changes: &'a [ConfigChange], key: &str) -> Option<&'a Value> {
+        changes.iter().find_map(|x| match x {
+            ConfigChange::Added { key: k, value } if k == key => Some(value),
+            _ => None,
+        })
+    }
+
+    fn not_added(changes: Vec<ConfigChange>) -> Vec<ConfigChange> {
+        changes
+            .into_iter()
+            .filter(|x| !matches!(x, ConfigChange::Added { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn can_migrate_config_from_v0_13() {
+        let (config, changes) = Config::migrate_from_string(
+            r#"
+manifest:
+  url: example.com
+  etag: "foo"
+roots: []
+backup:
+  path: ~/backup
+  recentGames:
+    - Game 1
+restore:
+  path: ~/restore
+  recentGames: []
+  redirects:
+    - source: ~/old
+      target: ~/new
+apps:
+  rclone:
+    path: rclone
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![RedirectConfig {
+                kind: RedirectKind::Restore,
+                source: StrictPath::new(s("~/old")),
+                target: StrictPath::new(s("~/new")),
+            }],
+            config.redirects
+        );
+        assert_eq!(
+            vec![
+                ConfigChange::Renamed {
+                    from: s("restore.redirects"),
+                    to: s("redirects"),
+                },
+                ConfigChange::Removed {
+                    key: s("manifest.etag"),
+                    value: yaml("foo"),
+                },
+                ConfigChange::Removed {
+                    key: s("backup.recentGames"),
+                    value: yaml("[Game 1]"),
+                },
+                ConfigChange::Removed {
+                    key: s("restore.recentGames"),
+                    value: yaml("[]"),
+                },
+                ConfigChange::Changed {
+                    key: s("redirects"),
+                    old: yaml("[{source: ~/old, target: ~/new}]"),
+                    new: yaml("[{kind: restore, source: ~/old, target: ~/new}]"),
+                },
+            ],
+            not_added(changes.clone())
+        );
+        assert_eq!(
+            Some(&yaml("{full: 1, differential: 0}")),
+            added(&changes, "backup.retention")
+        );
+        assert_eq!(Some(&yaml("false")), added(&changes, "restore.blockIfRunning"));
+    }
+
+    #[test]
+    fn can_migrate_config_from_v0_22() {
+        let (_, changes) = Config::migrate_from_string(
+            r#"
+runtime:
+  threads: ~
+manifest:
+  url: example.com
+language: en-US
+theme: light
+roots:
+  - path: ~/steam
+    store: steam
+redirects: []
+backup:
+  path: ~/backup
+  ignoredGames: []
+  filter:
+    excludeStoreScreenshots: false
+    ignoredPaths: []
+    ignoredRegistry: []
+  toggledPaths: {}
+  toggledRegistry: {}
+  sort:
+    key: status
+    reversed: false
+  retention:
+    full: 1
+    differential: 0
+  format:
+    chosen: simple
+    zip:
+      compression: deflate
+    compression:
+      deflate:
+        level: 6
+      bzip2:
+        level: 6
+      zstd:
+        level: 10
+restore:
+  path: ~/restore
+  ignoredGames: []
+  toggledPaths: {}
+  toggledRegistry: {}
+  sort:
+    key: status
+    reversed: false
+scan:
+  showDeselectedGames: true
+  showUnchangedGames: true
+  showUnscannedGames: true
+cloud:
+  remote: ~
+  path: ludusavi-backup
+  synchronize: true
+apps:
+  rclone:
+    path: rclone
+    arguments: "--fast-list --ignore-checksum"
+customGames: []
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(Vec::<ConfigChange>::new(), not_added(changes.clone()));
+        assert_eq!(Some(&yaml("nfc")), added(&changes, "scan.unicodeNormalization"));
+        assert_eq!(Some(&yaml("false")), added(&changes, "scan.excludeCommonJunk"));
+        assert_eq!(Some(&yaml("auto")), added(&changes, "restore.remapUserProfile"));
+    }
+
+    #[test]
+    fn finds_no_changes_for_current_config() {
+        let mut config = Config::default();
+        config.apps.rclone.path = StrictPath::new(s("rclone"));
+        let content = serde_yaml::to_string(&config).unwrap();
+
+        let (_, changes) = Config::migrate_from_string(&content).unwrap();
+        assert_eq!(Vec::<ConfigChange>::new(), changes);
+    }
+
+    #[test]
+    fn does_not_rename_over_existing_key() {
+        let mut raw = yaml("{restore: {redirects: [a]}, redirects: [b]}");
+        assert_eq!(Vec::<ConfigChange>::new(), apply_renames(&mut raw));
+        assert_eq!(yaml("{restore: {redirects: [a]}, redirects: [b]}"), raw);
+    }
+}
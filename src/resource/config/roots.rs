@@ -0,0 +1,242 @@
+use crate::{
+    prelude::StrictPath,
+    resource::{
+        config::{Config, RootsConfig},
+        manifest::Store,
+    },
+};
+
+/// Why a root is redundant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RootRedundancy {
+    /// It leads to the same place as another root of the same store.
+    Same,
+    /// It's inside of another root for the same launcher.
+    Nested,
+}
+
+/// A configured root that doesn't need to be scanned because another root already covers it.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedundantRoot {
+    pub root: RootsConfig,
+    /// The root that covers this one and is kept.
+    pub kept: RootsConfig,
+    pub redundancy: RootRedundancy,
+}
+
+/// Roots for a specific launcher have a fixed layout, so a nested root of the same launcher is never useful.
+/// However, "other" roots are just folders of games, and it's normal to have one inside another.
+fn nesting_is_redundant(store: Store) -> bool {
+    !matches!(
+        store,
+        Store::Other | Store::OtherHome | Store::OtherWine | Store::OtherWindows | Store::OtherLinux | Store::OtherMac
+    )
+}
+
+/// Find roots that are covered by another root of the same store.
+///
+/// * When several roots lead to the same place, the first one listed is kept.
+///   Roots that Ludusavi finds automatically are added after existing ones,
+///   so this prefers a root that was added by hand.
+/// * When a launcher root is inside of another root for the same launcher,
+///   the broadest one is kept.
+pub fn find_redundant_roots(roots: &[RootsConfig]) -> Vec<RedundantRoot> {
+    let same = |a: &RootsConfig, b: &RootsConfig| a.store == b.store && a.path.same_location(&b.path);
+    let first_same = |root: &RootsConfig| roots.iter().find(|x| same(x, root)).unwrap_or(root).clone();
+
+    let mut redundant = vec![];
+
+    for (i, root) in roots.iter().enumerate() {
+        if let Some(kept) = roots[..i].iter().find(|x| same(x, root)) {
+            redundant.push(RedundantRoot {
+                root: root.clone(),
+                kept: kept.clone(),
+                redundancy: RootRedundancy::Same,
+            });
+            continue;
+        }
+
+        if !nesting_is_redundant(root.store) {
+            continue;
+        }
+
+        let broadest = roots
+            .iter()
+            .filter(|x| x.store == root.store && x.path.is_prefix_of(&root.path) && !same(x, root))
+            .min_by_key(|x| x.path.render().len());
+        if let Some(kept) = broadest {
+            redundant.push(RedundantRoot {
+                root: root.clone(),
+                kept: first_same(kept),
+                redundancy: RootRedundancy::Nested,
+            });
+        }
+    }
+
+    redundant
+}
+
+impl Config {
+    pub fn redundant_roots(&self) -> Vec<RedundantRoot> {
+        find_redundant_roots(&self.roots)
+    }
+
+    pub fn remove_redundant_roots(&mut self, redundant: &[RedundantRoot]) {
+        for entry in redundant {
+            if let Some(index) = self.roots.iter().position(|x| *x == entry.root) {
+                self.roots.remove(index);
+            }
+        }
+    }
+
+    /// An existing root of the same store that leads to the same place.
+    pub fn find_duplicate_root(&self, root: &RootsConfig) -> Option<&RootsConfig> {
+        self.roots
+            .iter()
+            .find(|x| x.store == root.store && x.path.same_location(&root.path))
+    }
+
+    /// Remove the roots at a path (of any store) or else at a position in the list (starting from 1),
+    /// returning the ones that were removed.
+    pub fn remove_roots(&mut self, selector: &str) -> Vec<RootsConfig> {
+        let path = StrictPath::new(selector.to_string());
+        let by_path = |root: &RootsConfig| root.path.raw() == selector || root.path.same_location(&path);
+
+        if self.roots.iter().any(by_path) {
+            let (removed, kept) = self.roots.drain(..).partition(by_path);
+            self.roots = kept;
+            return removed;
+        }
+
+        match selector.parse::<usize>() {
+            Ok(index) if (1..=self.roots.len()).contains(&index) => vec![self.roots.remove(index - 1)],
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn root(path: &str, store: Store) -> RootsConfig {
+        RootsConfig {
+            path: StrictPath::new(s(path)),
+            store,
+        }
+    }
+
+    #[test]
+    fn keeps_first_of_identical_roots() {
+        let roots = vec![
+            root("/games/steam", Store::Steam),
+            root("/games/steam/", Store::Steam),
+            root("/games/other/../steam", Store::Steam),
+            root("/games/steam", Store::Other),
+        ];
+
+        assert_eq!(
+            vec![
+                RedundantRoot {
+                    root: root("/games/steam/", Store::Steam),
+                    kept: root("/games/steam", Store::Steam),
+                    redundancy: RootRedundancy::Same,
+                },
+                RedundantRoot {
+                    root: root("/games/other/../steam", Store::Steam),
+                    kept: root("/games/steam", Store::Steam),
+                    redundancy: RootRedundancy::Same,
+                },
+            ],
+            find_redundant_roots(&roots)
+        );
+    }
+
+    #[test]
+    fn keeps_broadest_of_nested_launcher_roots() {
+        let roots = vec![
+            root("/games/steam/library", Store::Steam),
+            root("/games/steam", Store::Steam),
+            root("/games/steam/library/nested", Store::Steam),
+            root("/games", Store::Other),
+            root("/games/indie", Store::Other),
+        ];
+
+        assert_eq!(
+            vec![
+                RedundantRoot {
+                    root: root("/games/steam/library", Store::Steam),
+                    kept: root("/games/steam", Store::Steam),
+                    redundancy: RootRedundancy::Nested,
+                },
+                RedundantRoot {
+                    root: root("/games/steam/library/nested", Store::Steam),
+                    kept: root("/games/steam", Store::Steam),
+                    redundancy: RootRedundancy::Nested,
+                },
+            ],
+            find_redundant_roots(&roots)
+        );
+    }
+
+    #[test]
+    fn can_remove_redundant_roots() {
+        let mut config = Config {
+            roots: vec![
+                root("/games/steam", Store::Steam),
+                root("/games/steam/", Store::Steam),
+                root("/games/epic", Store::Epic),
+            ],
+            ..Default::default()
+        };
+
+        config.remove_redundant_roots(&config.redundant_roots());
+        assert_eq!(
+            vec![root("/games/steam", Store::Steam), root("/games/epic", Store::Epic)],
+            config.roots
+        );
+    }
+
+    #[test]
+    fn can_find_duplicate_root() {
+        let config = Config {
+            roots: vec![root("/games/steam", Store::Steam)],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Some(&root("/games/steam", Store::Steam)),
+            config.find_duplicate_root(&root("/games/steam/", Store::Steam))
+        );
+        assert_eq!(None, config.find_duplicate_root(&root("/games/steam", Store::Other)));
+        assert_eq!(None, config.find_duplicate_root(&root("/games/epic", Store::Steam)));
+    }
+
+    #[test]
+    fn can_remove_roots_by_path_or_position() {
+        let mut config = Config {
+            roots: vec![
+                root("/games/steam", Store::Steam),
+                root("/games/epic", Store::Epic),
+                root("/games/steam/", Store::Other),
+                root("/games/gog", Store::Gog),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![root("/games/steam", Store::Steam), root("/games/steam/", Store::Other)],
+            config.remove_roots("/games/steam")
+        );
+        assert_eq!(vec![root("/games/gog", Store::Gog)], config.remove_roots("2"));
+        assert_eq!(Vec::<RootsConfig>::new(), config.remove_roots("2"));
+        assert_eq!(Vec::<RootsConfig>::new(), config.remove_roots("0"));
+        assert_eq!(Vec::<RootsConfig>::new(), config.remove_roots("/games/gog"));
+        assert_eq!(vec![root("/games/epic", Store::Epic)], config.roots);
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+
+use crate::resource::config::{Config, CustomGame, RedirectConfig, ToggledPaths, ToggledRegistry};
+
+/// The format version written by `config export-custom`.
+pub const SHARED_CONFIG_VERSION: u32 = 1;
+
+/// Custom games, per-game ignore rules, and redirects,
+/// in a file that can be shared between machines
+/// (`config export-custom` and `config import-custom`).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedConfig {
+    /// Files from a newer version of Ludusavi are rejected,
+    /// since they may contain settings that would be silently dropped.
+    pub version: u32,
+    #[serde(default)]
+    pub custom_games: Vec<CustomGame>,
+    #[serde(default)]
+    pub backup: SharedToggles,
+    #[serde(default)]
+    pub restore: SharedToggles,
+    #[serde(default)]
+    pub redirects: Vec<RedirectConfig>,
+}
+
+/// Paths and registry entries that were ignored (or re-enabled) for specific games.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedToggles {
+    #[serde(default)]
+    pub toggled_paths: ToggledPaths,
+    #[serde(default)]
+    pub toggled_registry: ToggledRegistry,
+}
+
+impl SharedConfig {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let shared: Self = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+        if shared.version > SHARED_CONFIG_VERSION {
+            return Err(format!(
+                "unsupported version {} (this version of Ludusavi supports up to {})",
+                shared.version, SHARED_CONFIG_VERSION
+            ));
+        }
+        Ok(shared)
+    }
+}
+
+/// How to handle an imported entry that conflicts with an existing one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep the existing entry.
+    #[default]
+    Merge,
+    /// Overwrite the existing entry.
+    Replace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSection {
+    CustomGame,
+    BackupPath,
+    BackupRegistry,
+    RestorePath,
+    RestoreRegistry,
+    Redirect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportOutcome {
+    /// There was no existing entry.
+    Added,
+    /// The existing entry was different, and it was overwritten.
+    Updated,
+    /// The existing entry was already the same.
+    Unchanged,
+    /// The existing entry was different, and it was kept.
+    Skipped,
+}
+
+impl ImportOutcome {
+    fn resolve<T: PartialEq>(existing: Option<&T>, incoming: &T, mode: ImportMode) -> Self {
+        match existing {
+            None => Self::Added,
+            Some(existing) if existing == incoming => Self::Unchanged,
+            Some(_) => match mode {
+                ImportMode::Merge => Self::Skipped,
+                ImportMode::Replace => Self::Updated,
+            },
+        }
+    }
+}
+
+/// What happened to one entry from an imported file.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChange {
+    pub section: ImportSection,
+    /// For ignore rules, the game that they apply to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game: Option<String>,
+    /// The custom game's name, the path or registry key, or the redirect's source.
+    pub entry: String,
+    pub outcome: ImportOutcome,
+}
+
+fn import_toggles<K: Ord + Clone, V: PartialEq>(
+    target: &mut BTreeMap<String, BTreeMap<K, V>>,
+    source: BTreeMap<String, BTreeMap<K, V>>,
+    section: ImportSection,
+    mode: ImportMode,
+    render: impl Fn(&K) -> String,
+    changes: &mut Vec<ImportChange>,
+) {
+    for (game, entries) in source {
+        for (key, value) in entries {
+            let outcome = ImportOutcome::resolve(target.get(&game).and_then(|x| x.get(&key)), &value, mode);
+            changes.push(ImportChange {
+                section,
+                game: Some(game.clone()),
+                entry: render(&key),
+                outcome,
+            });
+            if matches!(outcome, ImportOutcome::Added | ImportOutcome::Updated) {
+                target.entry(game.clone()).or_default().insert(key, value);
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn export_shared(&self) -> SharedConfig {
+        SharedConfig {
+            version: SHARED_CONFIG_VERSION,
+            custom_games: self.custom_games.clone(),
+            backup: SharedToggles {
+                toggled_paths: self.backup.toggled_paths.clone(),
+                toggled_registry: self.backup.toggled_registry.clone(),
+            },
+            restore: SharedToggles {
+                toggled_paths: self.restore.toggled_paths.clone(),
+                toggled_registry: self.restore.toggled_registry.clone(),
+            },
+            redirects: self.redirects.clone(),
+        }
+    }
+
+    /// Custom games are matched by name, and redirects are matched by kind and source.
+    /// Ignore rules are matched by game and path (or registry key).
+    pub fn import_shared(&mut self, shared: SharedConfig, mode: ImportMode) -> Vec<ImportChange> {
+        let mut changes = vec![];
+
+        for game in shared.custom_games {
+            let index = self.custom_games.iter().position(|x| x.name == game.name);
+            let outcome = ImportOutcome::resolve(index.map(|i| &self.custom_games[i]), &game, mode);
+            changes.push(ImportChange {
+                section: ImportSection::CustomGame,
+                game: None,
+                entry: game.name.clone(),
+                outcome,
+            });
+            match (outcome, index) {
+                (ImportOutcome::Added, _) => self.custom_games.push(game),
+                (ImportOutcome::Updated, Some(i)) => self.custom_games[i] = game,
+                _ => {}
+            }
+        }
+
+        import_toggles(
+            &mut self.backup.toggled_paths.0,
+            shared.backup.toggled_paths.0,
+            ImportSection::BackupPath,
+            mode,
+            |x| x.raw(),
+            &mut changes,
+        );
+        import_toggles(
+            &mut self.backup.toggled_registry.0,
+            shared.backup.toggled_registry.0,
+            ImportSection::BackupRegistry,
+            mode,
+            |x| x.raw(),
+            &mut changes,
+        );
+        import_toggles(
+            &mut self.restore.toggled_paths.0,
+            shared.restore.toggled_paths.0,
+            ImportSection::RestorePath,
+            mode,
+            |x| x.raw(),
+            &mut changes,
+        );
+        import_toggles(
+            &mut self.restore.toggled_registry.0,
+            shared.restore.toggled_registry.0,
+            ImportSection::RestoreRegistry,
+            mode,
+            |x| x.raw(),
+            &mut changes,
+        );
+
+        for redirect in shared.redirects {
+            let index = self
+                .redirects
+                .iter()
+                .position(|x| x.kind == redirect.kind && x.source == redirect.source);
+            let outcome = ImportOutcome::resolve(index.map(|i| &self.redirects[i]), &redirect, mode);
+            changes.push(ImportChange {
+                section: ImportSection::Redirect,
+                game: None,
+                entry: redirect.source.raw(),
+                outcome,
+            });
+            match (outcome, index) {
+                (ImportOutcome::Added, _) => self.redirects.push(redirect),
+                (ImportOutcome::Updated, Some(i)) => self.redirects[i] = redirect,
+                _ => {}
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        prelude::StrictPath,
+        resource::config::{RedirectKind, ToggledRegistryEntry},
+        scan::registry_compat::RegistryItem,
+        testing::s,
+    };
+
+    fn custom_game(name: &str, files: &[&str]) -> CustomGame {
+        CustomGame {
+            name: s(name),
+            ignore: false,
+            alias: None,
+            files: files.iter().map(|x| x.to_string()).collect(),
+            registry: vec![],
+        }
+    }
+
+    fn redirect(source: &str, target: &str) -> RedirectConfig {
+        RedirectConfig {
+            kind: RedirectKind::Bidirectional,
+            source: StrictPath::new(s(source)),
+            target: StrictPath::new(s(target)),
+        }
+    }
+
+    fn config() -> Config {
+        let mut config = Config {
+            custom_games: vec![
+                custom_game("foo", &["<base>/saves"]),
+                CustomGame {
+                    name: s("bar"),
+                    ignore: true,
+                    alias: Some(s("foo")),
+                    files: vec![],
+                    registry: vec![s("HKEY_CURRENT_USER/Software/Bar")],
+                },
+            ],
+            redirects: vec![redirect("/old", "/new")],
+            ..Default::default()
+        };
+        config.backup.toggled_paths = ToggledPaths(btreemap! {
+            s("foo") => btreemap! {
+                StrictPath::new(s("/saves/foo/cache")) => false,
+            },
+        });
+        config.backup.toggled_registry = ToggledRegistry(btreemap! {
+            s("foo") => btreemap! {
+                RegistryItem::new(s("HKEY_CURRENT_USER/Software/Foo")) => ToggledRegistryEntry::Complex {
+                    key: None,
+                    values: btreemap! {
+                        s("temp") => false,
+                    },
+                },
+            },
+        });
+        config.restore.toggled_paths = ToggledPaths(btreemap! {
+            s("bar") => btreemap! {
+                StrictPath::new(s("/saves/bar/settings.ini")) => false,
+            },
+        });
+        config.restore.toggled_registry = ToggledRegistry(btreemap! {
+            s("bar") => btreemap! {
+                RegistryItem::new(s("HKEY_CURRENT_USER/Software/Bar")) => ToggledRegistryEntry::Key(false),
+            },
+        });
+        config
+    }
+
+    #[test]
+    fn can_round_trip_export_and_import() {
+        let original = config();
+        let serialized = serde_yaml::to_string(&original.export_shared()).unwrap();
+
+        let mut imported = Config::default();
+        let changes = imported.import_shared(SharedConfig::parse(&serialized).unwrap(), ImportMode::Merge);
+
+        assert_eq!(original.export_shared(), imported.export_shared());
+        assert!(changes.iter().all(|x| x.outcome == ImportOutcome::Added));
+        assert_eq!(7, changes.len());
+
+        let changes = imported.import_shared(SharedConfig::parse(&serialized).unwrap(), ImportMode::Merge);
+        assert_eq!(original.export_shared(), imported.export_shared());
+        assert!(changes.iter().all(|x| x.outcome == ImportOutcome::Unchanged));
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        assert!(SharedConfig::parse("version: 1").is_ok());
+        assert!(SharedConfig::parse("version: 2").is_err());
+        assert!(SharedConfig::parse("customGames: []").is_err());
+    }
+
+    fn incoming() -> SharedConfig {
+        SharedConfig {
+            version: SHARED_CONFIG_VERSION,
+            custom_games: vec![custom_game("foo", &["<home>/foo"]), custom_game("new", &[])],
+            backup: SharedToggles {
+                toggled_paths: ToggledPaths(btreemap! {
+                    s("foo") => btreemap! {
+                        StrictPath::new(s("/saves/foo/cache")) => true,
+                    },
+                }),
+                toggled_registry: ToggledRegistry::default(),
+            },
+            restore: SharedToggles::default(),
+            redirects: vec![redirect("/old", "/elsewhere"), redirect("/other", "/new")],
+        }
+    }
+
+    #[test]
+    fn can_import_with_merge() {
+        let mut config = config();
+        let changes = config.import_shared(incoming(), ImportMode::Merge);
+
+        assert_eq!(
+            vec![
+                (ImportSection::CustomGame, s("foo"), ImportOutcome::Skipped),
+                (ImportSection::CustomGame, s("new"), ImportOutcome::Added),
+                (ImportSection::BackupPath, s("/saves/foo/cache"), ImportOutcome::Skipped),
+                (ImportSection::Redirect, s("/old"), ImportOutcome::Skipped),
+                (ImportSection::Redirect, s("/other"), ImportOutcome::Added),
+            ],
+            changes
+                .into_iter()
+                .map(|x| (x.section, x.entry, x.outcome))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![s("<base>/saves")], config.custom_games[0].files);
+        assert_eq!(3, config.custom_games.len());
+        assert_eq!(
+            Some(&false),
+            config.backup.toggled_paths.0["foo"].get(&StrictPath::new(s("/saves/foo/cache")))
+        );
+        assert_eq!(
+            vec![redirect("/old", "/new"), redirect("/other", "/new")],
+            config.redirects
+        );
+    }
+
+    #[test]
+    fn can_import_with_replace() {
+        let mut config = config();
+        let changes = config.import_shared(incoming(), ImportMode::Replace);
+
+        assert_eq!(
+            vec![
+                (ImportSection::CustomGame, s("foo"), ImportOutcome::Updated),
+                (ImportSection::CustomGame, s("new"), ImportOutcome::Added),
+                (ImportSection::BackupPath, s("/saves/foo/cache"), ImportOutcome::Updated),
+                (ImportSection::Redirect, s("/old"), ImportOutcome::Updated),
+                (ImportSection::Redirect, s("/other"), ImportOutcome::Added),
+            ],
+            changes
+                .into_iter()
+                .map(|x| (x.section, x.entry, x.outcome))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![s("<home>/foo")], config.custom_games[0].files);
+        assert_eq!(s("bar"), config.custom_games[1].name);
+        assert_eq!(
+            Some(&true),
+            config.backup.toggled_paths.0["foo"].get(&StrictPath::new(s("/saves/foo/cache")))
+        );
+        assert_eq!(
+            vec![redirect("/old", "/elsewhere"), redirect("/other", "/new")],
+            config.redirects
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    resource::{config::Config, manifest::Manifest},
+    scan::normalize_title,
+};
+
+/// Where a stored title reference lives in the config.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum TitleReference {
+    /// The `alias` of a custom game.
+    CustomAlias { name: String },
+    /// One of the `titles` of a merged game.
+    MergedTitle { name: String },
+}
+
+/// A stored title that no longer refers to a current game.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DanglingTitle {
+    #[serde(flatten)]
+    pub reference: TitleReference,
+    pub title: String,
+    /// If the manifest seems to have renamed the title, this is its new name.
+    /// Such titles should be updated rather than pruned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl DanglingTitle {
+    /// Whether the title has no likely replacement and can be removed.
+    pub fn prunable(&self) -> bool {
+        self.suggestion.is_none()
+    }
+}
+
+struct Titles<'a> {
+    current: HashSet<&'a str>,
+    renamed: HashMap<String, String>,
+    normalized: HashMap<String, &'a str>,
+}
+
+impl<'a> Titles<'a> {
+    fn new(config: &'a Config, manifest: &'a Manifest, backups: &'a HashSet<String>) -> Self {
+        let renamed = manifest.aliases();
+        let current: HashSet<_> = manifest
+            .0
+            .keys()
+            .filter(|x| !renamed.contains_key(*x))
+            .chain(
+                config
+                    .custom_games
+                    .iter()
+                    .filter(|x| x.alias.is_none())
+                    .map(|x| &x.name),
+            )
+            .chain(backups.iter())
+            .map(|x| x.as_str())
+            .collect();
+        let normalized = current.iter().map(|x| (normalize_title(x), *x)).collect();
+
+        Self {
+            current,
+            renamed,
+            normalized,
+        }
+    }
+
+    /// Returns `None` if the title is still current,
+    /// otherwise whatever it was most likely renamed to.
+    fn check(&self, title: &str) -> Option<Option<String>> {
+        if self.current.contains(title) {
+            return None;
+        }
+        if let Some(renamed) = self.renamed.get(title) {
+            return Some(Some(renamed.clone()));
+        }
+        Some(
+            self.normalized
+                .get(&normalize_title(title))
+                .map(|x| x.to_string())
+                .filter(|x| x != title),
+        )
+    }
+}
+
+impl Config {
+    /// Find custom game aliases and merged game titles that don't refer to any current game.
+    /// Titles are current if they're in the manifest (but not just as a manifest alias),
+    /// if they're a non-alias custom game, or if they're in `backups`.
+    pub fn dangling_titles(&self, manifest: &Manifest, backups: &HashSet<String>) -> Vec<DanglingTitle> {
+        let titles = Titles::new(self, manifest, backups);
+        let mut dangling = vec![];
+
+        for game in &self.custom_games {
+            let Some(alias) = game.alias.as_ref().filter(|x| !x.trim().is_empty()) else {
+                continue;
+            };
+            if let Some(suggestion) = titles.check(alias) {
+                dangling.push(DanglingTitle {
+                    reference: TitleReference::CustomAlias {
+                        name: game.name.clone(),
+                    },
+                    title: alias.clone(),
+                    suggestion,
+                });
+            }
+        }
+
+        for merged in &self.merged_games {
+            for title in &merged.titles {
+                if *title == merged.name {
+                    continue;
+                }
+                if let Some(suggestion) = titles.check(title) {
+                    dangling.push(DanglingTitle {
+                        reference: TitleReference::MergedTitle {
+                            name: merged.name.clone(),
+                        },
+                        title: title.clone(),
+                        suggestion,
+                    });
+                }
+            }
+        }
+
+        dangling
+    }
+
+    /// Remove the prunable entries from `dangling`.
+    /// Alias custom games are removed entirely, and merged games with no titles left are removed.
+    pub fn prune_dangling_titles(&mut self, dangling: &[DanglingTitle]) {
+        for entry in dangling.iter().filter(|x| x.prunable()) {
+            match &entry.reference {
+                TitleReference::CustomAlias { name } => {
+                    self.custom_games
+                        .retain(|x| !(x.name == *name && x.alias.as_ref() == Some(&entry.title)));
+                }
+                TitleReference::MergedTitle { name } => {
+                    for merged in self.merged_games.iter_mut().filter(|x| x.name == *name) {
+                        merged.titles.retain(|x| *x != entry.title);
+                    }
+                    self.merged_games
+                        .retain(|x| x.name != *name || x.titles.iter().any(|title| *title != x.name));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        resource::{
+            config::{CustomGame, MergedGame},
+            ResourceFile,
+        },
+        testing::s,
+    };
+
+    fn manifest() -> Manifest {
+        Manifest::load_from_string(
+            r#"
+            Current Game: {}
+            Old Name:
+              alias: New Name
+            New Name: {}
+            Sequel: {}
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn alias(name: &str, target: &str) -> CustomGame {
+        CustomGame {
+            name: s(name),
+            ignore: false,
+            alias: Some(s(target)),
+            files: vec![],
+            registry: vec![],
+        }
+    }
+
+    #[test]
+    fn suggests_renamed_alias_targets_instead_of_pruning() {
+        let mut config = Config {
+            custom_games: vec![
+                alias("current", "Current Game"),
+                alias("renamed in manifest", "Old Name"),
+                alias("renamed with year", "Sequel (2019)"),
+                alias("gone", "Removed Game"),
+            ],
+            merged_games: vec![MergedGame {
+                name: s("Current Game"),
+                titles: vec![s("Current Game"), s("Removed Game"), s("Old Name")],
+            }],
+            ..Default::default()
+        };
+
+        let dangling = config.dangling_titles(&manifest(), &HashSet::new());
+        assert_eq!(
+            vec![
+                DanglingTitle {
+                    reference: TitleReference::CustomAlias {
+                        name: s("renamed in manifest")
+                    },
+                    title: s("Old Name"),
+                    suggestion: Some(s("New Name")),
+                },
+                DanglingTitle {
+                    reference: TitleReference::CustomAlias {
+                        name: s("renamed with year")
+                    },
+                    title: s("Sequel (2019)"),
+                    suggestion: Some(s("Sequel")),
+                },
+                DanglingTitle {
+                    reference: TitleReference::CustomAlias { name: s("gone") },
+                    title: s("Removed Game"),
+                    suggestion: None,
+                },
+                DanglingTitle {
+                    reference: TitleReference::MergedTitle {
+                        name: s("Current Game")
+                    },
+                    title: s("Removed Game"),
+                    suggestion: None,
+                },
+                DanglingTitle {
+                    reference: TitleReference::MergedTitle {
+                        name: s("Current Game")
+                    },
+                    title: s("Old Name"),
+                    suggestion: Some(s("New Name")),
+                },
+            ],
+            dangling
+        );
+
+        config.prune_dangling_titles(&dangling);
+        assert_eq!(
+            vec![
+                alias("current", "Current Game"),
+                alias("renamed in manifest", "Old Name"),
+                alias("renamed with year", "Sequel (2019)"),
+            ],
+            config.custom_games
+        );
+        assert_eq!(
+            vec![MergedGame {
+                name: s("Current Game"),
+                titles: vec![s("Current Game"), s("Old Name")],
+            }],
+            config.merged_games
+        );
+    }
+
+    #[test]
+    fn treats_backed_up_titles_as_current() {
+        let config = Config {
+            merged_games: vec![MergedGame {
+                name: s("Current Game"),
+                titles: vec![s("Only Backed Up")],
+            }],
+            ..Default::default()
+        };
+
+        let backups = HashSet::from([s("Only Backed Up")]);
+        assert_eq!(
+            Vec::<DanglingTitle>::new(),
+            config.dangling_titles(&manifest(), &backups)
+        );
+    }
+}
@@ -2,10 +2,10 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::{
     lang::TRANSLATOR,
-    prelude::{app_dir, Error, StrictPath},
+    prelude::{app_dir, sha1, Error, StrictPath},
     resource::{
         cache::{self, Cache},
-        config::{Config, CustomGame, ManifestConfig},
+        config::{Config, CustomGame, ManifestConfig, MergedGame},
         ResourceFile, SaveableResourceFile,
     },
     scan::layout::escape_folder_name,
@@ -26,9 +26,39 @@ pub mod placeholder {
     pub const WIN_DIR: &str = "<winDir>";
     pub const XDG_DATA: &str = "<xdgData>";
     pub const XDG_CONFIG: &str = "<xdgConfig>";
+
+    pub const ALL: &[&str] = &[
+        ROOT,
+        GAME,
+        BASE,
+        HOME,
+        STORE_USER_ID,
+        OS_USER_NAME,
+        WIN_APP_DATA,
+        WIN_LOCAL_APP_DATA,
+        WIN_DOCUMENTS,
+        WIN_PUBLIC,
+        WIN_PROGRAM_DATA,
+        WIN_DIR,
+        XDG_DATA,
+        XDG_CONFIG,
+    ];
+
+    static RE_PLACEHOLDER: once_cell::sync::Lazy<regex::Regex> =
+        once_cell::sync::Lazy::new(|| regex::Regex::new(r"<[^<>/\\]*>").unwrap());
+
+    /// Anything in the path that looks like a placeholder (e.g., `<winDocs>`), but isn't one.
+    pub fn find_unknown(path: &str) -> Vec<String> {
+        RE_PLACEHOLDER
+            .find_iter(path)
+            .map(|x| x.as_str())
+            .filter(|x| !ALL.contains(x))
+            .map(str::to_string)
+            .collect()
+    }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum Os {
     #[serde(rename = "windows")]
     Windows,
@@ -74,7 +104,20 @@ impl From<&str> for Os {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 pub enum Store {
     #[serde(rename = "ea")]
     Ea,
@@ -136,6 +179,63 @@ impl Store {
         Store::OtherMac,
         Store::Other,
     ];
+
+    /// The same names as in the config file.
+    pub const ALL_CLI: &'static [&'static str] = &[
+        "ea",
+        "epic",
+        "gog",
+        "gogGalaxy",
+        "heroic",
+        "legendary",
+        "lutris",
+        "microsoft",
+        "origin",
+        "prime",
+        "steam",
+        "uplay",
+        "otherHome",
+        "otherWine",
+        "otherWindows",
+        "otherLinux",
+        "otherMac",
+        "other",
+    ];
+
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Ea => "ea",
+            Self::Epic => "epic",
+            Self::Gog => "gog",
+            Self::GogGalaxy => "gogGalaxy",
+            Self::Heroic => "heroic",
+            Self::Legendary => "legendary",
+            Self::Lutris => "lutris",
+            Self::Microsoft => "microsoft",
+            Self::Origin => "origin",
+            Self::Prime => "prime",
+            Self::Steam => "steam",
+            Self::Uplay => "uplay",
+            Self::OtherHome => "otherHome",
+            Self::OtherWine => "otherWine",
+            Self::OtherWindows => "otherWindows",
+            Self::OtherLinux => "otherLinux",
+            Self::OtherMac => "otherMac",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for Store {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|x| x.slug() == s)
+            .copied()
+            .ok_or_else(|| format!("invalid store: {}", s))
+    }
 }
 
 impl ToString for Store {
@@ -144,7 +244,7 @@ impl ToString for Store {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum Tag {
     #[serde(rename = "save")]
     Save,
@@ -155,10 +255,20 @@ pub enum Tag {
     Other,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// The primary manifest couldn't be parsed,
+/// so the last known-good copy was loaded instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestFallback {
+    /// Why the primary manifest couldn't be parsed.
+    pub why: String,
+    /// When the fallback copy was last modified.
+    pub updated: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Manifest(#[serde(serialize_with = "crate::serialization::ordered_map")] pub HashMap<String, Game>);
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Game {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alias: Option<String>,
@@ -174,6 +284,25 @@ pub struct Game {
     pub gog: Option<GogMetadata>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<IdMetadata>,
+    #[serde(rename = "pathMigrations", skip_serializing_if = "Option::is_none")]
+    pub path_migrations: Option<Vec<PathMigration>>,
+    /// Which manifest provided the entry.
+    /// This is tracked while loading, rather than read from the manifest itself.
+    #[serde(skip)]
+    pub source: GameSource,
+}
+
+/// Where a game's manifest entry came from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum GameSource {
+    /// The primary manifest.
+    #[default]
+    Primary,
+    /// A secondary manifest, which may also have added to an entry from the primary manifest.
+    Secondary,
+    /// A custom game from the config, which replaces any manifest entry.
+    Custom,
 }
 
 impl Game {
@@ -202,7 +331,7 @@ impl Game {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GameFileEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<Tag>>,
@@ -210,10 +339,10 @@ pub struct GameFileEntry {
     pub when: Option<Vec<GameFileConstraint>>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GameInstallDirEntry {}
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GameRegistryEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<Tag>>,
@@ -221,7 +350,7 @@ pub struct GameRegistryEntry {
     pub when: Option<Vec<GameRegistryConstraint>>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GameFileConstraint {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub os: Option<Os>,
@@ -229,35 +358,73 @@ pub struct GameFileConstraint {
     pub store: Option<Store>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GameRegistryConstraint {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub store: Option<Store>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// A save location that moved between versions of a game.
+/// Both paths may use the same placeholders as `files`,
+/// except for the ones that depend on a root (e.g., `<root>` and `<base>`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct PathMigration {
+    /// Where the game used to keep its saves.
+    pub from: String,
+    /// Where the game keeps its saves now.
+    pub to: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct SteamMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<u32>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct GogMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<u64>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IdMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub flatpak: Option<String>,
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub gog_extra: BTreeSet<u64>,
+    /// Lutris game slug.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lutris: Option<String>,
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub steam_extra: BTreeSet<u32>,
 }
 
+/// A game's identifier in a particular store or launcher.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum StoreId {
+    Steam(u32),
+    Gog(u64),
+    Lutris(String),
+}
+
+impl StoreId {
+    /// The raw identifier, without the store.
+    pub fn value(&self) -> String {
+        match self {
+            Self::Steam(id) => id.to_string(),
+            Self::Gog(id) => id.to_string(),
+            Self::Lutris(id) => id.clone(),
+        }
+    }
+
+    pub fn same_store(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ManifestUpdate {
     pub url: String,
@@ -290,11 +457,67 @@ impl Manifest {
         }
     }
 
+    /// The last known-good copy of a downloaded manifest,
+    /// which is kept around in case the current copy becomes unreadable.
+    fn fallback_path_for(path: &std::path::Path) -> std::path::PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        path.with_file_name(name)
+    }
+
     pub fn load() -> Result<Self, Error> {
-        ResourceFile::load().map_err(|e| Error::ManifestInvalid {
-            why: format!("{}", e),
+        Self::load_with_hash().map(|(manifest, _, _)| manifest)
+    }
+
+    /// Like `load`, but also returns the hash of the primary manifest's content,
+    /// which new backups record so that you can tell which manifest revision produced them
+    /// (see `ManifestConfig::loaded_hash`),
+    /// and whether the primary manifest was unreadable so that an older copy had to be loaded
+    /// (see `ManifestConfig::loaded_fallback`).
+    pub fn load_with_hash() -> Result<(Self, Option<String>, Option<ManifestFallback>), Error> {
+        let path = Self::path();
+        if !path.exists() {
+            let manifest = ResourceFile::load().map_err(|e| Error::ManifestInvalid {
+                why: format!("{}", e),
+                identifier: None,
+            })?;
+            return Ok((manifest, None, None));
+        }
+
+        let (manifest, raw, fallback) = Self::load_with_fallback(&path, &Self::fallback_path_for(&path))?;
+        Ok((manifest, Some(sha1(raw)), fallback))
+    }
+
+    /// If the manifest at `path` can't be parsed (e.g., because of a truncated download),
+    /// then try the copy at `fallback` instead.
+    /// The error is only returned if neither one is usable.
+    fn load_with_fallback(
+        path: &std::path::PathBuf,
+        fallback: &std::path::PathBuf,
+    ) -> Result<(Self, String, Option<ManifestFallback>), Error> {
+        let why = match Self::load_raw(path).and_then(|raw| Ok((Self::load_from_string(&raw)?, raw))) {
+            Ok((manifest, raw)) => return Ok((manifest, raw, None)),
+            Err(e) => e.to_string(),
+        };
+
+        let invalid = || Error::ManifestInvalid {
+            why: why.clone(),
             identifier: None,
-        })
+        };
+
+        let raw = Self::load_raw(fallback).map_err(|_| invalid())?;
+        let manifest = Self::load_from_string(&raw).map_err(|_| invalid())?;
+        log::warn!(
+            "Unable to parse manifest, so falling back to the previous copy: {} | {why}",
+            fallback.display()
+        );
+
+        let updated = std::fs::metadata(fallback)
+            .and_then(|x| x.modified())
+            .ok()
+            .map(chrono::DateTime::<chrono::Utc>::from);
+
+        Ok((manifest, raw, Some(ManifestFallback { why, updated })))
     }
 
     pub fn should_update(url: &str, cache: &cache::Manifests, force: bool, primary: bool) -> bool {
@@ -371,7 +594,14 @@ impl Manifest {
                     });
                 }
 
-                std::fs::write(path.as_std_path_buf(), manifest_string).map_err(|_| cannot_update())?;
+                // Keep the previous copy until the new one is fully written,
+                // so that there's still something to fall back to if the new one gets corrupted.
+                let path = path.as_std_path_buf();
+                let fallback = Self::fallback_path_for(&path);
+                if path.exists() && Self::load_from_existing(&path).is_ok() {
+                    std::fs::copy(&path, &fallback).map_err(|_| cannot_update())?;
+                }
+                std::fs::write(&path, manifest_string).map_err(|_| cannot_update())?;
 
                 let new_etag = res
                     .headers()
@@ -440,6 +670,41 @@ impl Manifest {
             .collect()
     }
 
+    /// Every store ID in the manifest, along with the games that list it.
+    /// Each game is flagged as to whether the ID is its primary one for that store,
+    /// since several games may share an extra ID.
+    pub fn map_store_ids_to_names(&self) -> HashMap<StoreId, BTreeMap<String, bool>> {
+        let mut output = HashMap::<StoreId, BTreeMap<String, bool>>::new();
+
+        for (name, game) in &self.0 {
+            if game.alias.is_some() {
+                continue;
+            }
+
+            let primary = [
+                game.steam.as_ref().and_then(|x| x.id).map(StoreId::Steam),
+                game.gog.as_ref().and_then(|x| x.id).map(StoreId::Gog),
+                game.id.as_ref().and_then(|x| x.lutris.clone()).map(StoreId::Lutris),
+            ];
+            for id in primary.into_iter().flatten() {
+                output.entry(id).or_default().insert(name.clone(), true);
+            }
+
+            if let Some(ids) = &game.id {
+                let extra = ids
+                    .steam_extra
+                    .iter()
+                    .map(|x| StoreId::Steam(*x))
+                    .chain(ids.gog_extra.iter().map(|x| StoreId::Gog(*x)));
+                for id in extra {
+                    output.entry(id).or_default().entry(name.clone()).or_insert(false);
+                }
+            }
+        }
+
+        output
+    }
+
     pub fn incorporate_extensions(&mut self, config: &Config) {
         for (path, secondary) in config.manifest.load_secondary_manifests() {
             self.incorporate_secondary_manifest(path, secondary);
@@ -457,6 +722,103 @@ impl Manifest {
             }
             self.add_custom_game(custom_game.clone());
         }
+
+        for merged in &config.merged_games {
+            self.merge_games(merged);
+        }
+
+        for migration in &config.path_migrations {
+            match self.0.get_mut(&migration.game) {
+                Some(game) => game
+                    .path_migrations
+                    .get_or_insert_with(Default::default)
+                    .push(PathMigration {
+                        from: migration.from.clone(),
+                        to: migration.to.clone(),
+                    }),
+                None => log::warn!("Ignoring path migration for unknown game: {}", &migration.game),
+            }
+        }
+    }
+
+    /// The merged titles become aliases of the combined game,
+    /// so they can still be requested by name.
+    fn merge_games(&mut self, merged: &MergedGame) {
+        if merged.name.trim().is_empty() {
+            return;
+        }
+
+        let mut combined = self.0.get(&merged.name).cloned().unwrap_or_default();
+        combined.alias = None;
+
+        for title in &merged.titles {
+            if *title == merged.name {
+                continue;
+            }
+            let Some(game) = self.0.get(title).cloned() else {
+                continue;
+            };
+            log::debug!("merging game: {title} -> {}", merged.name);
+
+            if let Some(files) = game.files {
+                combined.files.get_or_insert_with(Default::default).extend(files);
+            }
+            if let Some(registry) = game.registry {
+                combined.registry.get_or_insert_with(Default::default).extend(registry);
+            }
+            if let Some(install_dir) = game.install_dir {
+                combined
+                    .install_dir
+                    .get_or_insert_with(Default::default)
+                    .extend(install_dir);
+            }
+            if let Some(path_migrations) = game.path_migrations {
+                combined
+                    .path_migrations
+                    .get_or_insert_with(Default::default)
+                    .extend(path_migrations);
+            }
+
+            if let Some(id) = game.steam.and_then(|x| x.id) {
+                match combined.steam.as_ref().and_then(|x| x.id) {
+                    None => combined.steam = Some(SteamMetadata { id: Some(id) }),
+                    Some(existing) if existing != id => {
+                        combined.id.get_or_insert_with(Default::default).steam_extra.insert(id);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let Some(id) = game.gog.and_then(|x| x.id) {
+                match combined.gog.as_ref().and_then(|x| x.id) {
+                    None => combined.gog = Some(GogMetadata { id: Some(id) }),
+                    Some(existing) if existing != id => {
+                        combined.id.get_or_insert_with(Default::default).gog_extra.insert(id);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let Some(id) = game.id {
+                let combined_id = combined.id.get_or_insert_with(Default::default);
+                if combined_id.flatpak.is_none() {
+                    combined_id.flatpak = id.flatpak;
+                }
+                if combined_id.lutris.is_none() {
+                    combined_id.lutris = id.lutris;
+                }
+                combined_id.gog_extra.extend(id.gog_extra);
+                combined_id.steam_extra.extend(id.steam_extra);
+            }
+
+            self.0.insert(
+                title.clone(),
+                Game {
+                    alias: Some(merged.name.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.0.insert(merged.name.clone(), combined);
     }
 
     fn add_custom_game(&mut self, custom: CustomGame) {
@@ -483,6 +845,8 @@ impl Manifest {
             steam: existing.and_then(|x| x.steam.clone()),
             gog: existing.and_then(|x| x.gog.clone()),
             id: existing.and_then(|x| x.id.clone()),
+            path_migrations: existing.and_then(|x| x.path_migrations.clone()),
+            source: GameSource::Custom,
         };
 
         self.0.insert(name, game);
@@ -495,6 +859,7 @@ impl Manifest {
 
             if let Some(standard) = self.0.get_mut(&name) {
                 log::debug!("overriding game from secondary manifest: {name}");
+                standard.source = GameSource::Secondary;
 
                 if let Some(secondary) = game.files {
                     if let Some(standard) = &mut standard.files {
@@ -532,11 +897,21 @@ impl Manifest {
                     standard.gog = Some(secondary);
                 }
 
+                if let Some(secondary) = game.path_migrations {
+                    standard
+                        .path_migrations
+                        .get_or_insert_with(Default::default)
+                        .extend(secondary);
+                }
+
                 if let Some(secondary_id) = game.id {
                     if let Some(standard_id) = &mut standard.id {
                         if standard_id.flatpak.is_none() {
                             standard_id.flatpak = secondary_id.flatpak;
                         }
+                        if standard_id.lutris.is_none() {
+                            standard_id.lutris = secondary_id.lutris;
+                        }
                         standard_id.gog_extra.extend(secondary_id.gog_extra);
                         standard_id.steam_extra.extend(secondary_id.steam_extra);
                     } else {
@@ -545,6 +920,7 @@ impl Manifest {
                 }
             } else {
                 log::debug!("adding game from secondary manifest: {name}");
+                game.source = GameSource::Secondary;
 
                 if let Some(folder) = path.parent().and_then(|x| x.leaf()) {
                     if let Some(secondary) = &mut game.install_dir {
@@ -573,12 +949,28 @@ impl Manifest {
                 steam,
                 gog,
                 id,
+                path_migrations: _,
+                source: _,
             } = &v;
             alias.is_none()
                 && (files.is_some() || registry.is_some() || steam.is_some() || gog.is_some() || id.is_some())
         })
     }
 
+    /// Titles (including aliases) that start with the prefix, ignoring case, for shell completion.
+    pub fn titles_starting_with(&self, prefix: &str) -> Vec<String> {
+        let prefix = prefix.to_lowercase();
+        let aliases = self.aliases();
+        let mut titles: Vec<_> = self
+            .processable_titles()
+            .chain(aliases.keys())
+            .filter(|title| title.to_lowercase().starts_with(&prefix))
+            .cloned()
+            .collect();
+        titles.sort();
+        titles
+    }
+
     pub fn aliases(&self) -> HashMap<String, String> {
         self.0
             .keys()
@@ -613,11 +1005,79 @@ impl Manifest {
 
 #[cfg(test)]
 mod tests {
-    use maplit::{btreemap, hashmap};
+    use maplit::{btreemap, btreeset, hashmap};
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::testing::s;
+    use crate::{
+        resource::config::PathMigrationConfig,
+        testing::{repo, s},
+    };
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        std::path::PathBuf::from(format!("{}/tests/manifest/{name}", repo()))
+    }
+
+    #[test]
+    fn can_find_unknown_placeholders() {
+        assert_eq!(Vec::<String>::new(), placeholder::find_unknown("<home>/<game>/*.sav"));
+        assert_eq!(
+            vec![s("<winDocs>"), s("<Home>")],
+            placeholder::find_unknown("<winDocs>/foo/<Home>/<winAppData>")
+        );
+        assert_eq!(Vec::<String>::new(), placeholder::find_unknown("C:/Games/a<b/c>d"));
+    }
+
+    #[test]
+    fn store_slugs_match_config_names() {
+        assert_eq!(
+            Store::ALL_CLI.to_vec(),
+            Store::ALL.iter().map(|x| x.slug()).collect::<Vec<_>>()
+        );
+        for store in Store::ALL {
+            assert_eq!(serde_json::json!(store.slug()), serde_json::to_value(store).unwrap());
+            assert_eq!(Ok(*store), store.slug().parse());
+        }
+    }
+
+    #[test]
+    fn can_load_manifest_without_fallback() {
+        let (manifest, _, fallback) =
+            Manifest::load_with_fallback(&fixture("valid.yaml"), &fixture("truncated.yaml")).unwrap();
+        assert!(manifest.0.contains_key("game"));
+        assert_eq!(None, fallback);
+    }
+
+    #[test]
+    fn can_fall_back_when_manifest_is_corrupted() {
+        let (manifest, _, fallback) =
+            Manifest::load_with_fallback(&fixture("truncated.yaml"), &fixture("valid.yaml")).unwrap();
+        assert!(manifest.0.contains_key("game"));
+
+        let fallback = fallback.unwrap();
+        assert!(!fallback.why.is_empty());
+        assert!(fallback.updated.is_some());
+    }
+
+    #[test]
+    fn fails_when_manifest_and_fallback_are_unusable() {
+        assert!(matches!(
+            Manifest::load_with_fallback(&fixture("truncated.yaml"), &fixture("missing.yaml")),
+            Err(Error::ManifestInvalid { identifier: None, .. })
+        ));
+        assert!(matches!(
+            Manifest::load_with_fallback(&fixture("truncated.yaml"), &fixture("truncated.yaml")),
+            Err(Error::ManifestInvalid { identifier: None, .. })
+        ));
+    }
+
+    #[test]
+    fn fallback_path_is_next_to_manifest() {
+        assert_eq!(
+            std::path::PathBuf::from("/app/manifest.yaml.bak"),
+            Manifest::fallback_path_for(std::path::Path::new("/app/manifest.yaml")),
+        );
+    }
 
     #[test]
     fn can_parse_game_with_no_fields() {
@@ -637,6 +1097,8 @@ mod tests {
                 steam: None,
                 gog: None,
                 id: None,
+                path_migrations: None,
+                source: GameSource::Primary,
             },
             manifest.0["game"],
         );
@@ -670,7 +1132,11 @@ mod tests {
               id:
                 flatpak: com.example.Game
                 gogExtra: [10, 11]
+                lutris: example-game
                 steamExtra: [1, 2]
+              pathMigrations:
+                - from: <winDocuments>/ExampleGame
+                  to: <winLocalAppData>Low/Studio/ExampleGame
             "#,
         )
         .unwrap();
@@ -707,8 +1173,14 @@ mod tests {
                 id: Some(IdMetadata {
                     flatpak: Some("com.example.Game".to_string()),
                     gog_extra: vec![10, 11].into_iter().collect(),
+                    lutris: Some("example-game".to_string()),
                     steam_extra: vec![1, 2].into_iter().collect(),
                 }),
+                path_migrations: Some(vec![PathMigration {
+                    from: s("<winDocuments>/ExampleGame"),
+                    to: s("<winLocalAppData>Low/Studio/ExampleGame"),
+                }]),
+                source: GameSource::Primary,
             },
             manifest.0["game"],
         );
@@ -917,4 +1389,173 @@ mod tests {
             manifest.aliases(),
         );
     }
+
+    #[test]
+    fn can_find_titles_for_completion() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            Foo Game: {}
+            foo game 2:
+              files:
+                foo: {}
+            Foo Alias:
+              alias: foo game 2
+            bar:
+              files:
+                bar: {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![s("Foo Alias"), s("foo game 2")],
+            manifest.titles_starting_with("FOO")
+        );
+        assert_eq!(vec![s("bar")], manifest.titles_starting_with("b"));
+        assert_eq!(3, manifest.titles_starting_with("").len());
+    }
+
+    #[test]
+    fn can_merge_games() {
+        let mut manifest = Manifest::load_from_string(
+            r#"
+            game (disc 1):
+              files:
+                foo: {}
+              steam:
+                id: 1
+            game (disc 2):
+              files:
+                bar: {}
+              registry:
+                baz: {}
+              steam:
+                id: 2
+            other:
+              files:
+                other: {}
+            "#,
+        )
+        .unwrap();
+
+        manifest.incorporate_extensions(&Config {
+            merged_games: vec![MergedGame {
+                name: s("game"),
+                titles: vec![s("game (disc 1)"), s("game (disc 2)"), s("missing")],
+            }],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            Game {
+                files: Some(btreemap! {
+                    s("foo") => GameFileEntry::default(),
+                    s("bar") => GameFileEntry::default(),
+                }),
+                registry: Some(btreemap! {
+                    s("baz") => GameRegistryEntry::default(),
+                }),
+                steam: Some(SteamMetadata { id: Some(1) }),
+                id: Some(IdMetadata {
+                    steam_extra: vec![2].into_iter().collect(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            manifest.0["game"],
+        );
+        assert_eq!(
+            btreeset! {s("game"), s("other")},
+            manifest.processable_titles().cloned().collect::<BTreeSet<_>>(),
+        );
+        assert_eq!(
+            hashmap! {
+                s("game (disc 1)") => s("game"),
+                s("game (disc 2)") => s("game"),
+            },
+            manifest.aliases(),
+        );
+        assert!(!manifest.0.contains_key("missing"));
+    }
+
+    #[test]
+    fn can_add_path_migrations_from_config() {
+        let mut manifest = Manifest::load_from_string(
+            r#"
+            game:
+              pathMigrations:
+                - from: <home>/old
+                  to: <home>/new
+            "#,
+        )
+        .unwrap();
+
+        manifest.incorporate_extensions(&Config {
+            path_migrations: vec![
+                PathMigrationConfig {
+                    game: s("game"),
+                    from: s("<home>/older"),
+                    to: s("<home>/old"),
+                },
+                PathMigrationConfig {
+                    game: s("missing"),
+                    from: s("<home>/a"),
+                    to: s("<home>/b"),
+                },
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            Some(vec![
+                PathMigration {
+                    from: s("<home>/old"),
+                    to: s("<home>/new"),
+                },
+                PathMigration {
+                    from: s("<home>/older"),
+                    to: s("<home>/old"),
+                },
+            ]),
+            manifest.0["game"].path_migrations,
+        );
+        assert!(!manifest.0.contains_key("missing"));
+    }
+
+    #[test]
+    fn can_map_store_ids_to_names() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game1:
+              steam:
+                id: 1
+              id:
+                lutris: game-one
+                steamExtra: [2]
+            game2:
+              steam:
+                id: 2
+              gog:
+                id: 3
+            alias:
+              alias: game1
+              steam:
+                id: 1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            HashMap::from([
+                (StoreId::Steam(1), BTreeMap::from([(s("game1"), true)])),
+                (
+                    StoreId::Steam(2),
+                    BTreeMap::from([(s("game1"), false), (s("game2"), true)])
+                ),
+                (StoreId::Gog(3), BTreeMap::from([(s("game2"), true)])),
+                (StoreId::Lutris(s("game-one")), BTreeMap::from([(s("game1"), true)])),
+            ]),
+            manifest.map_store_ids_to_names(),
+        );
+    }
 }
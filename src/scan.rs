@@ -1,27 +1,52 @@
+mod activity;
 mod backup;
 mod change;
+mod dedup;
+mod diff;
 mod duplicate;
+mod explain;
 pub mod game_filter;
+pub mod junk;
 pub mod launchers;
 pub mod layout;
+mod locked;
+mod os_constraint;
+mod path_filter;
+mod path_migration;
 mod preview;
 pub mod registry_compat;
+mod remap;
+pub mod running;
 mod saves;
+mod size_history;
+mod sqlite;
 mod steam;
+mod storage;
 mod title;
+mod torn;
+mod watchdog;
+mod wine_user;
+pub mod zip_safety;
 
 #[cfg(target_os = "windows")]
 pub mod registry;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-pub use self::{backup::*, change::*, duplicate::*, launchers::*, preview::*, saves::*, steam::*, title::*};
+pub use self::{
+    backup::*, change::*, dedup::*, diff::*, duplicate::*, explain::*, launchers::*, locked::*, os_constraint::*,
+    path_filter::*, path_migration::*, preview::*, remap::*, saves::*, size_history::*, steam::*, storage::*, title::*,
+    torn::*, watchdog::*, wine_user::*,
+};
 
 use crate::{
     path::StrictPath,
     prelude::{filter_map_walkdir, Error, SKIP},
     resource::{
-        config::{BackupFilter, RedirectConfig, RedirectKind, RootsConfig, SortKey, ToggledPaths, ToggledRegistry},
+        config::{
+            BackupFilter, RedirectConfig, RedirectKind, RootsConfig, SortKey, ToggledPaths, ToggledRegistry,
+            UnicodeNormalization,
+        },
         manifest::{Game, GameFileEntry, IdMetadata, Os, Store},
     },
     scan::layout::LatestBackup,
@@ -30,18 +55,20 @@ use crate::{
 #[cfg(target_os = "windows")]
 use crate::scan::registry_compat::RegistryItem;
 
-/// Returns the effective target, if different from the original
+/// Returns the effective target, if different from the original,
+/// along with the first redirect rule that changed it.
 pub fn game_file_target(
     original_target: &StrictPath,
     redirects: &[RedirectConfig],
     restoring: bool,
-) -> Option<StrictPath> {
+) -> Option<(StrictPath, RedirectRule)> {
     if redirects.is_empty() {
         return None;
     }
 
     let mut redirected_target = original_target.render();
-    for redirect in redirects {
+    let mut rule = None;
+    for (index, redirect) in redirects.iter().enumerate() {
         if redirect.source.raw().trim().is_empty() || redirect.target.raw().trim().is_empty() {
             continue;
         }
@@ -61,12 +88,17 @@ pub fn game_file_target(
         };
         if !source.is_empty() && !target.is_empty() && redirected_target.starts_with(&source) {
             redirected_target = redirected_target.replacen(&source, &target, 1);
+            rule.get_or_insert_with(|| RedirectRule {
+                index,
+                source: redirect.source.clone(),
+                target: redirect.target.clone(),
+            });
         }
     }
 
     let redirected_target = StrictPath::new(redirected_target);
     if original_target.render() != redirected_target.render() {
-        Some(redirected_target)
+        rule.map(|rule| (redirected_target, rule))
     } else {
         None
     }
@@ -120,6 +152,32 @@ pub fn steam_ids(game: &Game, shortcut: Option<&SteamShortcut>) -> Vec<u32> {
     ids
 }
 
+/// Returns what `<base>` means for a root, given the game's install folder.
+fn resolve_base(root: &RootsConfig, install_dir: &str, full_install_dir: &Option<&StrictPath>) -> String {
+    let root_interpreted = root.path.interpret();
+
+    match root.store {
+        Store::Steam => format!("{}/steamapps/common/{}", &root_interpreted, install_dir),
+        Store::Heroic | Store::Legendary | Store::Lutris => full_install_dir
+            .map(|x| x.interpret())
+            .unwrap_or_else(|| SKIP.to_string()),
+        Store::Ea
+        | Store::Epic
+        | Store::Gog
+        | Store::GogGalaxy
+        | Store::Microsoft
+        | Store::Origin
+        | Store::Prime
+        | Store::Uplay
+        | Store::OtherHome
+        | Store::OtherWine
+        | Store::OtherWindows
+        | Store::OtherLinux
+        | Store::OtherMac
+        | Store::Other => format!("{}/{}", &root_interpreted, install_dir),
+    }
+}
+
 /// Returns paths to check and whether they require case-sensitive matching.
 pub fn parse_paths(
     path: &str,
@@ -162,29 +220,7 @@ pub fn parse_paths(
     paths.insert((
         path.replace(ROOT, &root_interpreted)
             .replace(GAME, install_dir)
-            .replace(
-                BASE,
-                &match root.store {
-                    Store::Steam => format!("{}/steamapps/common/{}", &root_interpreted, install_dir),
-                    Store::Heroic | Store::Legendary | Store::Lutris => full_install_dir
-                        .map(|x| x.interpret())
-                        .unwrap_or_else(|| SKIP.to_string()),
-                    Store::Ea
-                    | Store::Epic
-                    | Store::Gog
-                    | Store::GogGalaxy
-                    | Store::Microsoft
-                    | Store::Origin
-                    | Store::Prime
-                    | Store::Uplay
-                    | Store::OtherHome
-                    | Store::OtherWine
-                    | Store::OtherWindows
-                    | Store::OtherLinux
-                    | Store::OtherMac
-                    | Store::Other => format!("{}/{}", &root_interpreted, install_dir),
-                },
-            )
+            .replace(BASE, &resolve_base(root, install_dir, full_install_dir))
             .replace(HOME, &home)
             .replace(STORE_USER_ID, "*")
             .replace(OS_USER_NAME, &whoami::username())
@@ -297,13 +333,14 @@ pub fn parse_paths(
     if root.store == Store::Steam && Os::HOST == Os::Linux {
         for id in steam_ids {
             let prefix = format!("{}/steamapps/compatdata/{}/pfx/drive_c", &root_interpreted, id);
+            // Proton uses `steamuser`, but older versions used the real user name (see `WineUsers`).
             let path2 = path
                 .replace(ROOT, &root_interpreted)
                 .replace(GAME, install_dir)
                 .replace(BASE, &format!("{}/steamapps/common/{}", &root_interpreted, install_dir))
-                .replace(HOME, &format!("{}/users/steamuser", prefix))
+                .replace(HOME, &format!("{}/users/*", prefix))
                 .replace(STORE_USER_ID, "*")
-                .replace(OS_USER_NAME, "steamuser")
+                .replace(OS_USER_NAME, "*")
                 .replace(WIN_PUBLIC, &format!("{}/users/Public", prefix))
                 .replace(WIN_PROGRAM_DATA, &format!("{}/ProgramData", prefix))
                 .replace(WIN_DIR, &format!("{}/windows", prefix))
@@ -311,18 +348,18 @@ pub fn parse_paths(
                 .replace(XDG_CONFIG, &check_nonwindows_path(dirs::config_dir()));
             paths.insert((
                 path2
-                    .replace(WIN_DOCUMENTS, &format!("{}/users/steamuser/Documents", prefix))
-                    .replace(WIN_APP_DATA, &format!("{}/users/steamuser/AppData/Roaming", prefix))
-                    .replace(WIN_LOCAL_APP_DATA, &format!("{}/users/steamuser/AppData/Local", prefix)),
+                    .replace(WIN_DOCUMENTS, &format!("{}/users/*/Documents", prefix))
+                    .replace(WIN_APP_DATA, &format!("{}/users/*/AppData/Roaming", prefix))
+                    .replace(WIN_LOCAL_APP_DATA, &format!("{}/users/*/AppData/Local", prefix)),
                 false,
             ));
             paths.insert((
                 path2
-                    .replace(WIN_DOCUMENTS, &format!("{}/users/steamuser/My Documents", prefix))
-                    .replace(WIN_APP_DATA, &format!("{}/users/steamuser/Application Data", prefix))
+                    .replace(WIN_DOCUMENTS, &format!("{}/users/*/My Documents", prefix))
+                    .replace(WIN_APP_DATA, &format!("{}/users/*/Application Data", prefix))
                     .replace(
                         WIN_LOCAL_APP_DATA,
-                        &format!("{}/users/steamuser/Local Settings/Application Data", prefix),
+                        &format!("{}/users/*/Local Settings/Application Data", prefix),
                     ),
                 false,
             ));
@@ -463,142 +500,69 @@ pub fn scan_game_for_backup(
     previous: Option<LatestBackup>,
     redirects: &[RedirectConfig],
     steam_shortcuts: &SteamShortcuts,
+    normalization: UnicodeNormalization,
+    watchdog: &IoWatchdog,
 ) -> ScanInfo {
     log::trace!("[{name}] beginning scan for backup");
 
     let mut found_files = HashSet::new();
     #[allow(unused_mut)]
     let mut found_registry_keys = HashSet::new();
-
-    let mut paths_to_check = HashSet::<(StrictPath, Option<bool>)>::new();
-
-    // Add a dummy root for checking paths without `<root>`.
-    let mut roots_to_check: Vec<RootsConfig> = vec![RootsConfig {
-        path: StrictPath::new(SKIP.to_string()),
-        store: Store::Other,
-    }];
-    roots_to_check.extend(roots.iter().cloned());
-
-    let manifest_dir_interpreted = manifest_dir.interpret();
-    let steam_ids = steam_ids(game, steam_shortcuts.get(name));
-
-    // We can add this for Wine prefixes from the CLI because they're
-    // typically going to be used for only one or a few games at a time.
-    // For other Wine roots, it would trigger for every game.
-    if let Some(wp) = wine_prefix {
-        log::trace!("[{name}] adding extra Wine prefix: {}", wp.raw());
-        scan_game_for_backup_add_prefix(
-            &mut roots_to_check,
-            &mut paths_to_check,
-            wp,
-            &manifest_dir_interpreted,
-            game.registry.is_some(),
-        );
-    }
-
-    // handle what was found for heroic
-    for root in roots {
-        if let Some(wp) = launchers.get_prefix(root, name) {
-            let with_pfx = wp.joined("pfx");
-            scan_game_for_backup_add_prefix(
-                &mut roots_to_check,
-                &mut paths_to_check,
-                if with_pfx.exists() { &with_pfx } else { wp },
-                &manifest_dir_interpreted,
-                game.registry.is_some(),
-            );
-        }
-    }
-
-    for root in roots_to_check {
-        log::trace!(
-            "[{name}] adding candidates from {:?} root: {}",
-            root.store,
-            root.path.raw()
-        );
-        if root.path.raw().trim().is_empty() {
-            continue;
-        }
-        let root_interpreted = root.path.interpret();
-
-        let platform = launchers.get_platform(&root, name).unwrap_or(Os::HOST);
-
-        if let Some(files) = &game.files {
-            let install_dir = launchers.get_install_dir_leaf(&root, name);
-            let full_install_dir = launchers.get_install_dir(&root, name);
-
-            for (raw_path, path_data) in files {
-                log::trace!("[{name}] parsing candidates from: {}", raw_path);
-                if raw_path.trim().is_empty() {
-                    continue;
-                }
-                let candidates = parse_paths(
-                    raw_path,
-                    path_data,
-                    &root,
-                    &install_dir,
-                    &full_install_dir,
-                    &steam_ids,
-                    game.id.as_ref(),
-                    manifest_dir,
-                    steam_shortcuts.get(name),
-                    platform,
-                );
-                for (candidate, case_sensitive) in candidates {
-                    log::trace!("[{name}] parsed candidate: {}", candidate.raw());
-                    if candidate.raw().contains('<') {
-                        // This covers `SKIP` and any other unmatched placeholders.
-                        continue;
-                    }
-                    paths_to_check.insert((candidate, Some(case_sensitive)));
-                }
-            }
-        }
-        if root.store == Store::Steam {
-            for id in &steam_ids {
-                // Cloud saves:
-                paths_to_check.insert((
-                    StrictPath::relative(
-                        format!("{}/userdata/*/{}/remote/", root_interpreted.clone(), id),
-                        Some(manifest_dir_interpreted.clone()),
-                    ),
-                    None,
-                ));
-
-                // Screenshots:
-                if !filter.exclude_store_screenshots {
-                    paths_to_check.insert((
-                        StrictPath::relative(
-                            format!("{}/userdata/*/760/remote/{}/screenshots/*.*", &root_interpreted, id),
-                            Some(manifest_dir_interpreted.clone()),
-                        ),
-                        None,
-                    ));
-                }
-
-                // Registry:
-                if game.registry.is_some() {
-                    let prefix = format!("{}/steamapps/compatdata/{}/pfx", &root_interpreted, id);
-                    paths_to_check.insert((
-                        StrictPath::relative(format!("{}/*.reg", prefix), Some(manifest_dir_interpreted.clone())),
-                        None,
-                    ));
-                }
-            }
-        }
-    }
-
-    let previous_files: HashMap<&StrictPath, &String> = previous
+    let (paths_to_check, mut unresolved_base_dirs) = backup_candidates(
+        game,
+        name,
+        roots,
+        manifest_dir,
+        launchers,
+        filter,
+        wine_prefix,
+        steam_shortcuts,
+    );
+
+    let previous_files = previous
         .as_ref()
         .map(|previous| {
-            previous
-                .scan
-                .found_files
-                .iter()
-                .map(|x| (x.original_path(), &x.hash))
-                .collect()
+            PreviousFiles::new(
+                previous.scan.found_files.iter().map(|x| (x.original_path(), &x.hash)),
+                Os::HOST.is_case_sensitive(),
+                normalization,
+            )
+        })
+        .unwrap_or_default();
+    let stored_files = previous
+        .as_ref()
+        .map(|previous| {
+            PreviousFiles::new(
+                previous.stored_files.iter().map(|(path, hash)| (path, hash)),
+                Os::HOST.is_case_sensitive(),
+                normalization,
+            )
         })
         .unwrap_or_default();
+    let migrations = PathMigrations::from_game(game);
+    let wine_users = previous
+        .as_ref()
+        .map(|previous| WineUsers::new(previous.scan.found_files.iter().map(|x| x.original_path())))
+        .unwrap_or_default();
+    let evaluate_change = |hash: &str, path: &StrictPath, ignored: bool| {
+        let (mut previous_hash, mut migrated_from) =
+            migrations.previous_hash(&previous_files, path, Os::HOST.is_case_sensitive());
+        if previous_hash.is_none() {
+            if let Some((hash, old)) = wine_users.previous_hash(&previous_files, path) {
+                previous_hash = Some(hash);
+                migrated_from = Some(old);
+            }
+        }
+        if previous_hash.is_none() && !ignored {
+            // The latest backup may have left this file out (e.g., because it was ignored at the time),
+            // but it could still be unchanged since an earlier backup in the same chain.
+            previous_hash = stored_files.hash(path).copied();
+        }
+        (ScanChange::evaluate_backup(hash, previous_hash.as_ref()), migrated_from)
+    };
+
+    let mut io_timeouts = BTreeSet::new();
+    let root_paths: Vec<_> = roots.iter().map(|x| x.path.clone()).collect();
 
     for (path, case_sensitive) in paths_to_check {
         log::trace!("[{name}] checking: {}", path.raw());
@@ -606,12 +570,21 @@ pub fn scan_game_for_backup(
             log::debug!("[{name}] excluded: {}", path.raw());
             continue;
         }
+        let location = StrictPath::new(activity::glob_free_prefix(&path));
+        let abandon = location
+            .nearest_prefix(root_paths.clone())
+            .unwrap_or_else(|| location.clone());
+        if !watchdog.responds(&location, &abandon) {
+            log::warn!("[{name}] skipping unresponsive location: {}", location.raw());
+            io_timeouts.insert(location);
+            continue;
+        }
         let paths = match case_sensitive {
             None => path.glob(),
             Some(cs) => path.glob_case_sensitive(cs),
         };
         for p in paths {
-            let p = p.rendered();
+            let p = p.canonical();
             if p.is_file() {
                 if filter.is_path_ignored(&p) {
                     log::debug!("[{name}] excluded: {}", p.raw());
@@ -620,16 +593,21 @@ pub fn scan_game_for_backup(
                 let ignored = ignored_paths.is_ignored(name, &p);
                 log::debug!("[{name}] found: {}", p.raw());
                 let hash = p.sha1();
-                let redirected = game_file_target(&p, redirects, false);
+                let (redirected, redirect_rule) = game_file_target(&p, redirects, false).unzip();
+                let (change, migrated_from) = evaluate_change(&hash, redirected.as_ref().unwrap_or(&p), ignored);
                 found_files.insert(ScannedFile {
-                    change: ScanChange::evaluate_backup(&hash, previous_files.get(redirected.as_ref().unwrap_or(&p))),
+                    change,
                     size: p.size(),
                     hash,
                     redirected,
+                    redirect_rule,
                     path: p,
                     original_path: None,
                     ignored,
                     container: None,
+                    sqlite_safe_copy: false,
+                    junk: false,
+                    migrated_from,
                 });
             } else if p.is_dir() {
                 log::trace!("[{name}] looking for files in: {}", p.raw());
@@ -646,7 +624,7 @@ pub fn scan_game_for_backup(
                     }
 
                     if child.file_type().is_file() {
-                        let child = StrictPath::from(&child).rendered();
+                        let child = StrictPath::from(&child).canonical();
                         if filter.is_path_ignored(&child) {
                             log::debug!("[{name}] excluded: {}", child.raw());
                             continue;
@@ -654,19 +632,22 @@ pub fn scan_game_for_backup(
                         let ignored = ignored_paths.is_ignored(name, &child);
                         log::debug!("[{name}] found: {}", child.raw());
                         let hash = child.sha1();
-                        let redirected = game_file_target(&child, redirects, false);
+                        let (redirected, redirect_rule) = game_file_target(&child, redirects, false).unzip();
+                        let (change, migrated_from) =
+                            evaluate_change(&hash, redirected.as_ref().unwrap_or(&child), ignored);
                         found_files.insert(ScannedFile {
-                            change: ScanChange::evaluate_backup(
-                                &hash,
-                                previous_files.get(redirected.as_ref().unwrap_or(&child)),
-                            ),
+                            change,
                             size: child.size(),
                             hash,
                             redirected,
+                            redirect_rule,
                             path: child,
                             original_path: None,
                             ignored,
                             container: None,
+                            sqlite_safe_copy: false,
+                            junk: false,
+                            migrated_from,
                         });
                     }
                 }
@@ -674,35 +655,89 @@ pub fn scan_game_for_backup(
         }
     }
 
+    let mut found_files = dedupe_found_files(found_files);
+
+    // SQLite databases in WAL mode are only consistent together with their journal files,
+    // so make sure that we back up the whole group.
+    for database in sqlite::find_databases_with_journal(&found_files) {
+        for journal in sqlite::journal_files(&database.path) {
+            let existing = found_files.iter().find(|x| x.path == journal).cloned();
+            let journal = match existing {
+                Some(existing) => {
+                    found_files.remove(&existing);
+                    existing
+                }
+                None => {
+                    log::debug!("[{name}] found SQLite journal: {}", journal.raw());
+                    let hash = journal.sha1();
+                    let (redirected, redirect_rule) = game_file_target(&journal, redirects, false).unzip();
+                    let (change, migrated_from) =
+                        evaluate_change(&hash, redirected.as_ref().unwrap_or(&journal), database.ignored);
+                    ScannedFile {
+                        change,
+                        size: journal.size(),
+                        hash,
+                        redirected,
+                        redirect_rule,
+                        path: journal,
+                        original_path: None,
+                        ignored: database.ignored,
+                        container: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from,
+                    }
+                }
+            };
+            found_files.insert(ScannedFile {
+                sqlite_safe_copy: true,
+                junk: false,
+                ..journal
+            });
+        }
+        found_files.remove(&database);
+        found_files.insert(ScannedFile {
+            sqlite_safe_copy: true,
+            junk: false,
+            ..database
+        });
+    }
+
     // Mark removed files.
-    let current_files: Vec<_> = found_files
-        .iter()
-        .map(|x| x.redirected.as_ref().unwrap_or(&x.path).interpret())
-        .collect();
     // But if a file is only "removed" because now it has a redirect,
     // then the removal isn't very interesting
     // and would lead to duplicate hash keys during reporting.
-    let current_files_with_redirects: Vec<_> = found_files
-        .iter()
-        .filter(|&x| x.redirected.is_some())
-        .map(|x| x.path.interpret())
-        .collect();
-    for (previous_file, _) in previous_files {
-        let previous_file_interpreted = previous_file.interpret();
-        if !current_files.contains(&previous_file_interpreted)
-            && !current_files_with_redirects.contains(&previous_file_interpreted)
-        {
-            found_files.insert(ScannedFile {
-                change: ScanChange::Removed,
-                size: 0,
-                hash: "".to_string(),
-                redirected: None,
-                path: previous_file.to_owned(),
-                original_path: None,
-                ignored: ignored_paths.is_ignored(name, previous_file),
-                container: None,
-            });
+    // Likewise, a file that was matched to its location before a path migration
+    // hasn't really been removed.
+    // We also can't tell if anything was removed from a location that timed out.
+    let current_files = found_files.iter().flat_map(|x| {
+        let mut paths = vec![x.redirected.as_ref().unwrap_or(&x.path)];
+        if x.redirected.is_some() {
+            paths.push(&x.path);
         }
+        if let Some(migrated_from) = &x.migrated_from {
+            paths.push(migrated_from);
+        }
+        paths
+    });
+    for previous_file in previous_files.removed(current_files) {
+        if io_timeouts.iter().any(|x: &StrictPath| x.is_prefix_of(previous_file)) {
+            continue;
+        }
+        found_files.insert(ScannedFile {
+            change: ScanChange::Removed,
+            size: 0,
+            hash: "".to_string(),
+            redirected: None,
+            redirect_rule: None,
+            path: previous_file.to_owned(),
+            original_path: None,
+            ignored: ignored_paths.is_ignored(name, previous_file),
+            container: None,
+            sqlite_safe_copy: false,
+            junk: false,
+            migrated_from: None,
+        });
     }
 
     #[cfg(target_os = "windows")]
@@ -792,14 +827,265 @@ pub fn scan_game_for_backup(
 
     log::trace!("[{name}] completed scan for backup");
 
+    // This is only interesting when it explains why nothing was found.
+    if !found_files.is_empty() {
+        unresolved_base_dirs.clear();
+    }
+
+    let roots = found_files
+        .iter()
+        .filter_map(|file| {
+            file.path
+                .nearest_prefix(root_paths.clone())
+                .map(|root| (file.path.clone(), root))
+        })
+        .collect();
+
     ScanInfo {
         game_name: name.to_string(),
         found_files,
         found_registry_keys,
+        unresolved_base_dirs,
+        io_timeouts,
+        source: Some(game.source),
+        roots,
         ..Default::default()
     }
 }
 
+/// Cheaply check whether any of a game's save locations were modified after `since`,
+/// without reading or hashing any files.
+pub fn game_has_recent_activity(
+    game: &Game,
+    name: &str,
+    roots: &[RootsConfig],
+    manifest_dir: &StrictPath,
+    launchers: &Launchers,
+    filter: &BackupFilter,
+    wine_prefix: &Option<StrictPath>,
+    steam_shortcuts: &SteamShortcuts,
+    since: std::time::SystemTime,
+    depth: usize,
+) -> bool {
+    let (candidates, _) = backup_candidates(
+        game,
+        name,
+        roots,
+        manifest_dir,
+        launchers,
+        filter,
+        wine_prefix,
+        steam_shortcuts,
+    );
+    activity::has_recent_activity(candidates.iter().map(|(path, _)| path), since, depth)
+}
+
+/// The folders (or individual files) that may contain a game's saves, without any glob patterns,
+/// for watching them for changes. Some of them may not exist yet.
+pub fn game_save_locations(
+    game: &Game,
+    name: &str,
+    roots: &[RootsConfig],
+    manifest_dir: &StrictPath,
+    launchers: &Launchers,
+    filter: &BackupFilter,
+    wine_prefix: &Option<StrictPath>,
+    steam_shortcuts: &SteamShortcuts,
+) -> BTreeSet<StrictPath> {
+    let (candidates, _) = backup_candidates(
+        game,
+        name,
+        roots,
+        manifest_dir,
+        launchers,
+        filter,
+        wine_prefix,
+        steam_shortcuts,
+    );
+    candidates
+        .iter()
+        .map(|(path, _)| activity::glob_free_prefix(path))
+        .filter(|x| !x.is_empty() && !x.contains(SKIP))
+        .map(StrictPath::new)
+        .collect()
+}
+
+/// Find the paths that may contain a game's saves, possibly with glob patterns,
+/// along with any `<base>` folders that should exist but don't.
+fn backup_candidates(
+    game: &Game,
+    name: &str,
+    roots: &[RootsConfig],
+    manifest_dir: &StrictPath,
+    launchers: &Launchers,
+    filter: &BackupFilter,
+    wine_prefix: &Option<StrictPath>,
+    steam_shortcuts: &SteamShortcuts,
+) -> (HashSet<(StrictPath, Option<bool>)>, BTreeSet<StrictPath>) {
+    let mut unresolved_base_dirs = BTreeSet::new();
+
+    let mut paths_to_check = HashSet::<(StrictPath, Option<bool>)>::new();
+
+    // Add a dummy root for checking paths without `<root>`.
+    let mut roots_to_check: Vec<RootsConfig> = vec![RootsConfig {
+        path: StrictPath::new(SKIP.to_string()),
+        store: Store::Other,
+    }];
+    roots_to_check.extend(roots.iter().cloned());
+
+    let manifest_dir_interpreted = manifest_dir.interpret();
+    let steam_ids = steam_ids(game, steam_shortcuts.get(name));
+
+    // We can add this for Wine prefixes from the CLI because they're
+    // typically going to be used for only one or a few games at a time.
+    // For other Wine roots, it would trigger for every game.
+    if let Some(wp) = wine_prefix {
+        log::trace!("[{name}] adding extra Wine prefix: {}", wp.raw());
+        scan_game_for_backup_add_prefix(
+            &mut roots_to_check,
+            &mut paths_to_check,
+            wp,
+            &manifest_dir_interpreted,
+            game.registry.is_some(),
+        );
+    }
+
+    // handle what was found for heroic
+    for root in roots {
+        if let Some(wp) = launchers.get_prefix(root, name) {
+            let with_pfx = wp.joined("pfx");
+            scan_game_for_backup_add_prefix(
+                &mut roots_to_check,
+                &mut paths_to_check,
+                if with_pfx.exists() { &with_pfx } else { wp },
+                &manifest_dir_interpreted,
+                game.registry.is_some(),
+            );
+        }
+    }
+
+    for root in roots_to_check {
+        log::trace!(
+            "[{name}] adding candidates from {:?} root: {}",
+            root.store,
+            root.path.raw()
+        );
+        if root.path.raw().trim().is_empty() {
+            continue;
+        }
+        let root_interpreted = root.path.interpret();
+
+        let platform = launchers.get_platform(&root, name).unwrap_or(Os::HOST);
+
+        if let Some(files) = &game.files {
+            let install_dir = launchers.get_install_dir_leaf(&root, name);
+            let full_install_dir = launchers.get_install_dir(&root, name);
+
+            // If a launcher told us where the game is installed, but that folder is gone,
+            // then any `<base>` paths will silently come up empty.
+            if let (Some(leaf), Some(_)) = (&install_dir, full_install_dir) {
+                if files
+                    .keys()
+                    .any(|x| x.contains(crate::resource::manifest::placeholder::BASE))
+                {
+                    let base = StrictPath::new(resolve_base(&root, leaf, &full_install_dir));
+                    if !base.is_dir() {
+                        log::debug!("[{name}] base directory does not exist: {}", base.raw());
+                        unresolved_base_dirs.insert(base);
+                    }
+                }
+            }
+
+            for (raw_path, path_data) in files {
+                log::trace!("[{name}] parsing candidates from: {}", raw_path);
+                if raw_path.trim().is_empty() {
+                    continue;
+                }
+                let candidates = parse_paths(
+                    raw_path,
+                    path_data,
+                    &root,
+                    &install_dir,
+                    &full_install_dir,
+                    &steam_ids,
+                    game.id.as_ref(),
+                    manifest_dir,
+                    steam_shortcuts.get(name),
+                    platform,
+                );
+                for (candidate, case_sensitive) in candidates {
+                    log::trace!("[{name}] parsed candidate: {}", candidate.raw());
+                    if candidate.raw().contains('<') {
+                        // This covers `SKIP` and any other unmatched placeholders.
+                        continue;
+                    }
+                    paths_to_check.insert((candidate, Some(case_sensitive)));
+                }
+            }
+        }
+        if root.store == Store::Steam {
+            for id in &steam_ids {
+                // Cloud saves:
+                paths_to_check.insert((
+                    StrictPath::relative(
+                        format!("{}/userdata/*/{}/remote/", root_interpreted.clone(), id),
+                        Some(manifest_dir_interpreted.clone()),
+                    ),
+                    None,
+                ));
+
+                // Screenshots:
+                if !filter.exclude_store_screenshots {
+                    paths_to_check.insert((
+                        StrictPath::relative(
+                            format!("{}/userdata/*/760/remote/{}/screenshots/*.*", &root_interpreted, id),
+                            Some(manifest_dir_interpreted.clone()),
+                        ),
+                        None,
+                    ));
+                }
+
+                // Registry:
+                if game.registry.is_some() {
+                    let prefix = format!("{}/steamapps/compatdata/{}/pfx", &root_interpreted, id);
+                    paths_to_check.insert((
+                        StrictPath::relative(format!("{}/*.reg", prefix), Some(manifest_dir_interpreted.clone())),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    (paths_to_check, unresolved_base_dirs)
+}
+
+/// Several manifest paths for a game can match the same file
+/// (e.g., `SaveGames/*` and `SaveGames/*.sav`),
+/// so merge any entries that refer to the same canonical path.
+fn dedupe_found_files(found_files: HashSet<ScannedFile>) -> HashSet<ScannedFile> {
+    let mut by_path = BTreeMap::<StrictPath, ScannedFile>::new();
+
+    for file in found_files {
+        let path = file.path.canonical();
+        match by_path.get_mut(&path) {
+            Some(existing) => {
+                existing.ignored |= file.ignored;
+                existing.junk |= file.junk;
+                existing.change = existing.change.worst(file.change);
+                if existing.migrated_from.is_none() {
+                    existing.migrated_from = file.migrated_from;
+                }
+            }
+            None => {
+                by_path.insert(path.clone(), ScannedFile { path, ..file });
+            }
+        }
+    }
+
+    by_path.into_values().collect()
+}
+
 fn scan_game_for_backup_add_prefix(
     roots_to_check: &mut Vec<RootsConfig>,
     paths_to_check: &mut HashSet<(StrictPath, Option<bool>)>,
@@ -883,7 +1169,11 @@ mod tests {
     #[cfg(target_os = "windows")]
     use crate::resource::config::ToggledRegistryEntry;
     use crate::{
-        resource::{config::Config, manifest::Manifest, ResourceFile},
+        resource::{
+            config::Config,
+            manifest::{GameSource, Manifest},
+            ResourceFile,
+        },
         testing::{repo, s, EMPTY_HASH},
     };
 
@@ -956,6 +1246,11 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/root1/game1/subdir/file2.txt", repo())) => StrictPath::new(format!("{}/tests/root1", repo())),
+                    StrictPath::new(format!("{}/tests/root2/game1/file1.txt", repo())) => StrictPath::new(format!("{}/tests/root2", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -971,6 +1266,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
 
@@ -981,6 +1278,10 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/root2/game2/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/root2/game2/file1.txt", repo())) => StrictPath::new(format!("{}/tests/root2", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -996,6 +1297,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1013,6 +1316,10 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/root3/game5/data/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/root3/game5/data/file1.txt", repo())) => StrictPath::new(format!("{}/tests/root3", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1028,6 +1335,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1045,6 +1354,10 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/root3/game_2/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/root3/game_2/file1.txt", repo())) => StrictPath::new(format!("{}/tests/root3", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1060,6 +1373,53 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
+            ),
+        );
+    }
+
+    #[test]
+    fn can_scan_game_for_backup_with_launcher_install_dir_that_no_longer_exists() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            windows-game:
+              files:
+                <base>/file1.txt: {}
+            "#,
+        )
+        .unwrap();
+        let roots = &[RootsConfig {
+            path: StrictPath::new(format!("{}/tests/launchers/lutris", repo())),
+            store: Store::Lutris,
+        }];
+        let subjects = &["windows-game".to_string()];
+        let title_finder = TitleFinder::new(&manifest, &Default::default());
+
+        assert_eq!(
+            ScanInfo {
+                game_name: s("windows-game"),
+                unresolved_base_dirs: btreeset! {
+                    StrictPath::new(s("/home/deck/Games/service/windows-game/drive_c/game")),
+                },
+                source: Some(GameSource::Primary),
+                ..Default::default()
+            },
+            scan_game_for_backup(
+                &manifest.0["windows-game"],
+                "windows-game",
+                roots,
+                &StrictPath::new(repo()),
+                &Launchers::scan(roots, &manifest, subjects, &title_finder, None),
+                &BackupFilter::default(),
+                &None,
+                &ToggledPaths::default(),
+                &ToggledRegistry::default(),
+                None,
+                &[],
+                &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1081,6 +1441,13 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/home/Documents/winDocuments.txt", repo()), 0, EMPTY_HASH).change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/home/data.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                    StrictPath::new(format!("{}/tests/home/AppData/Roaming/winAppData.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                    StrictPath::new(format!("{}/tests/home/AppData/Local/winLocalAppData.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                    StrictPath::new(format!("{}/tests/home/Documents/winDocuments.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1096,6 +1463,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1116,6 +1485,12 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/home/.local/share/xdgData.txt", repo()), 0, EMPTY_HASH).change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
+                roots: btreemap! {
+                    StrictPath::new(format!("{}/tests/home/data.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                    StrictPath::new(format!("{}/tests/home/.config/xdgConfig.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                    StrictPath::new(format!("{}/tests/home/.local/share/xdgData.txt", repo())) => StrictPath::new(format!("{}/tests/home", repo())),
+                },
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1131,6 +1506,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1144,6 +1521,7 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/wine-prefix/drive_c/users/anyone/data.txt", repo()), 0, EMPTY_HASH).change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1159,6 +1537,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1172,6 +1552,7 @@ mod tests {
                     ScannedFile::new(format!("{}/tests/wine-prefix/user.reg", repo()), 37, "4a5b7e9de7d84ffb4bb3e9f38667f85741d5fbc0",).change_new(),
                 },
                 found_registry_keys: hashset! {},
+                source: Some(GameSource::Primary),
                 ..Default::default()
             },
             scan_game_for_backup(
@@ -1187,6 +1568,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1232,11 +1615,24 @@ mod tests {
 
         for (mut filter, ignored, found) in cases {
             filter.build_globs();
+            let roots = found
+                .iter()
+                .map(|file| {
+                    let root = if file.path.raw().contains("root1") {
+                        "root1"
+                    } else {
+                        "root2"
+                    };
+                    (file.path.clone(), StrictPath::new(format!("{}/tests/{root}", repo())))
+                })
+                .collect();
             assert_eq!(
                 ScanInfo {
                     game_name: s("game1"),
                     found_files: found,
                     found_registry_keys: hashset! {},
+                    source: Some(GameSource::Primary),
+                    roots,
                     ..Default::default()
                 },
                 scan_game_for_backup(
@@ -1252,11 +1648,196 @@ mod tests {
                     None,
                     &[],
                     &Default::default(),
+                    Default::default(),
+                    &Default::default(),
                 ),
             );
         }
     }
 
+    #[test]
+    fn can_scan_game_for_backup_with_overlapping_file_matches() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game1:
+              files:
+                <base>: {}
+                <base>/file1.txt: {}
+                <base>/./file1.txt: {}
+                <base>/subdir/: {}
+                <base>/subdir/*.txt: {}
+            "#,
+        )
+        .unwrap();
+
+        let scan = scan_game_for_backup(
+            &manifest.0["game1"],
+            "game1",
+            &config().roots,
+            &StrictPath::new(repo()),
+            &Launchers::scan_dirs(&config().roots, &manifest, &["game1".to_string()]),
+            &BackupFilter::default(),
+            &None,
+            &ToggledPaths::default(),
+            &ToggledRegistry::default(),
+            None,
+            &[],
+            &Default::default(),
+            Default::default(),
+            &Default::default(),
+        );
+
+        assert_eq!(
+            hashset! {
+                ScannedFile::new(format!("{}/tests/root1/game1/ignored.txt", repo()), 0, EMPTY_HASH).change_new(),
+                ScannedFile::new(format!("{}/tests/root1/game1/subdir/file2.txt", repo()), 2, "9d891e731f75deae56884d79e9816736b7488080").change_new(),
+                ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
+            },
+            scan.found_files,
+        );
+
+        let mut status = OperationStatus::default();
+        status.add_game(&scan, &None, true);
+        assert_eq!(3, status.total_bytes);
+        assert_eq!(3, status.processed_bytes);
+    }
+
+    #[test]
+    fn can_scan_game_for_backup_with_path_migration() {
+        let manifest = Manifest::load_from_string(&format!(
+            r#"
+            game1:
+              files:
+                {0}/tests/root1/game1/subdir: {{}}
+                {0}/tests/root2/game1: {{}}
+              pathMigrations:
+                - from: {0}/tests/old-location
+                  to: {0}/tests/root1/game1/subdir
+            "#,
+            repo()
+        ))
+        .unwrap();
+
+        let previous = LatestBackup {
+            scan: ScanInfo {
+                found_files: hashset! {
+                    ScannedFile::new(format!("{}/tests/old-location/file2.txt", repo()), 2, "9d891e731f75deae56884d79e9816736b7488080"),
+                    ScannedFile::new(format!("{}/tests/old-location/gone.txt", repo()), 1, "1"),
+                },
+                ..Default::default()
+            },
+            registry_content: None,
+            stored_files: vec![],
+        };
+
+        let scan = scan_game_for_backup(
+            &manifest.0["game1"],
+            "game1",
+            &config().roots,
+            &StrictPath::new(repo()),
+            &Launchers::default(),
+            &BackupFilter::default(),
+            &None,
+            &ToggledPaths::default(),
+            &ToggledRegistry::default(),
+            Some(previous),
+            &[],
+            &Default::default(),
+            Default::default(),
+            &Default::default(),
+        );
+
+        assert_eq!(
+            hashset! {
+                ScannedFile {
+                    migrated_from: Some(StrictPath::new(format!("{}/tests/old-location/file2.txt", repo()))),
+                    ..ScannedFile::new(format!("{}/tests/root1/game1/subdir/file2.txt", repo()), 2, "9d891e731f75deae56884d79e9816736b7488080").change_as(ScanChange::Same)
+                },
+                ScannedFile::new(format!("{}/tests/root2/game1/file1.txt", repo()), 1, "3a52ce780950d4d969792a2559cd519d7ee8c727").change_new(),
+                ScannedFile::new(format!("{}/tests/old-location/gone.txt", repo()), 0, "").change_as(ScanChange::Removed),
+            },
+            scan.found_files,
+        );
+    }
+
+    #[test]
+    fn can_scan_game_for_backup_across_wine_user_folders() {
+        let manifest = Manifest::load_from_string(
+            r#"
+            game1:
+              files:
+                <winDocuments>/Game: {}
+            "#,
+        )
+        .unwrap();
+        let prefix = format!("{}/tests/compatdata/1234/pfx", repo());
+
+        let previous = LatestBackup {
+            scan: ScanInfo {
+                found_files: hashset! {
+                    ScannedFile::new(format!("{prefix}/drive_c/users/alex/Documents/Game/slot1.sav"), 1, "86f7e437faa5a7fce15d1ddcb9eaeaea377667b8"),
+                    ScannedFile::new(format!("{prefix}/drive_c/users/alex/Documents/Game/slot2.sav"), 1, "e9d71f5ee7c92d6dc9e92ffdad17b8bd49418f98"),
+                },
+                ..Default::default()
+            },
+            registry_content: None,
+            stored_files: vec![],
+        };
+
+        let scan = scan_game_for_backup(
+            &manifest.0["game1"],
+            "game1",
+            &[],
+            &StrictPath::new(repo()),
+            &Launchers::default(),
+            &BackupFilter::default(),
+            &Some(StrictPath::new(prefix.clone())),
+            &ToggledPaths::default(),
+            &ToggledRegistry::default(),
+            Some(previous),
+            &[],
+            &Default::default(),
+            Default::default(),
+            &Default::default(),
+        );
+
+        assert_eq!(
+            hashset! {
+                ScannedFile {
+                    migrated_from: Some(StrictPath::new(format!("{prefix}/drive_c/users/alex/Documents/Game/slot1.sav"))),
+                    ..ScannedFile::new(format!("{prefix}/drive_c/users/steamuser/Documents/Game/slot1.sav"), 1, "86f7e437faa5a7fce15d1ddcb9eaeaea377667b8").change_as(ScanChange::Same)
+                },
+                ScannedFile::new(format!("{prefix}/drive_c/users/alex/Documents/Game/slot2.sav"), 1, "e9d71f5ee7c92d6dc9e92ffdad17b8bd49418f98").change_as(ScanChange::Same),
+            },
+            scan.found_files,
+        );
+    }
+
+    #[test]
+    fn can_dedupe_found_files_with_merged_flags() {
+        let path = format!("{}/tests/root2/game1/file1.txt", repo());
+        let found = hashset! {
+            ScannedFile {
+                path: StrictPath::relative(path.clone(), Some(repo())),
+                ..ScannedFile::new(&path, 1, "1").change_as(ScanChange::Same)
+            },
+            ScannedFile {
+                ignored: true,
+                ..ScannedFile::new(&path, 1, "1").change_as(ScanChange::Different)
+            },
+        };
+
+        assert_eq!(
+            hashset! {
+                ScannedFile {
+                    ignored: true,
+                    ..ScannedFile::new(&path, 1, "1").change_as(ScanChange::Different)
+                },
+            },
+            dedupe_found_files(found),
+        );
+    }
+
     #[test]
     #[cfg(target_os = "windows")]
     fn can_scan_game_for_backup_with_registry_matches_on_leaf_key_with_values() {
@@ -1288,6 +1869,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1327,6 +1910,8 @@ mod tests {
                 None,
                 &[],
                 &Default::default(),
+                Default::default(),
+                &Default::default(),
             ),
         );
     }
@@ -1426,8 +2011,76 @@ mod tests {
                     None,
                     &[],
                     &Default::default(),
+                    Default::default(),
+                    &Default::default(),
                 ),
             );
         }
     }
+
+    fn redirect(kind: RedirectKind, source: &str, target: &str) -> RedirectConfig {
+        RedirectConfig {
+            kind,
+            source: StrictPath::new(s(source)),
+            target: StrictPath::new(s(target)),
+        }
+    }
+
+    #[test]
+    fn game_file_target_reports_first_matching_rule() {
+        let redirects = vec![
+            redirect(RedirectKind::Restore, "/old", "/ignored"),
+            redirect(RedirectKind::Backup, "/saves", "/a"),
+            redirect(RedirectKind::Backup, "/saves/game", "/b"),
+        ];
+
+        assert_eq!(
+            Some((
+                StrictPath::new(s("/a/game/file.sav")),
+                RedirectRule {
+                    index: 1,
+                    source: StrictPath::new(s("/saves")),
+                    target: StrictPath::new(s("/a")),
+                },
+            )),
+            game_file_target(&StrictPath::new(s("/saves/game/file.sav")), &redirects, false),
+        );
+    }
+
+    #[test]
+    fn game_file_target_reports_first_rule_when_rules_chain() {
+        let redirects = vec![
+            redirect(RedirectKind::Bidirectional, "/x", "/saves"),
+            redirect(RedirectKind::Restore, "/x/game", "/y"),
+        ];
+
+        assert_eq!(
+            Some((
+                StrictPath::new(s("/y/file.sav")),
+                RedirectRule {
+                    index: 0,
+                    source: StrictPath::new(s("/x")),
+                    target: StrictPath::new(s("/saves")),
+                },
+            )),
+            game_file_target(&StrictPath::new(s("/saves/game/file.sav")), &redirects, true),
+        );
+    }
+
+    #[test]
+    fn game_file_target_ignores_rules_that_do_not_change_the_path() {
+        let redirects = vec![
+            redirect(RedirectKind::Backup, "/saves", "/a"),
+            redirect(RedirectKind::Backup, "/a", "/saves"),
+        ];
+
+        assert_eq!(
+            None,
+            game_file_target(&StrictPath::new(s("/saves/file.sav")), &redirects, false),
+        );
+        assert_eq!(
+            None,
+            game_file_target(&StrictPath::new(s("/other/file.sav")), &redirects, false)
+        );
+    }
 }
@@ -0,0 +1,120 @@
+use std::time::SystemTime;
+
+use crate::path::StrictPath;
+
+const GLOB_CHARS: &[char] = &['*', '?', '['];
+
+/// The deepest part of a candidate save path that doesn't contain any glob patterns.
+pub(super) fn glob_free_prefix(candidate: &StrictPath) -> String {
+    candidate
+        .render()
+        .split('/')
+        .take_while(|component| !component.contains(GLOB_CHARS))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn modified_after(path: &std::path::Path, since: SystemTime) -> bool {
+    match path.metadata().and_then(|x| x.modified()) {
+        Ok(modified) => modified > since,
+        Err(_) => true,
+    }
+}
+
+fn has_children(path: &std::path::Path) -> bool {
+    std::fs::read_dir(path).map(|mut x| x.next().is_some()).unwrap_or(true)
+}
+
+/// Whether anything in a game's candidate save paths was modified after `since`,
+/// looking at most `depth` levels into each folder.
+///
+/// This is only a cheap estimate, so it errs on the side of reporting activity:
+/// if a folder goes deeper than `depth`, or if we can't read something,
+/// then we assume that it has changed.
+pub fn has_recent_activity<'a>(
+    candidates: impl IntoIterator<Item = &'a StrictPath>,
+    since: SystemTime,
+    depth: usize,
+) -> bool {
+    candidates
+        .into_iter()
+        .any(|candidate| location_has_recent_activity(&glob_free_prefix(candidate), since, depth))
+}
+
+fn location_has_recent_activity(location: &str, since: SystemTime, depth: usize) -> bool {
+    if location.is_empty() {
+        return true;
+    }
+
+    let location = StrictPath::new(location.to_string()).as_std_path_buf();
+    if !location.exists() {
+        // If the folder was deleted, then its parent will have been modified.
+        return location
+            .ancestors()
+            .skip(1)
+            .find(|x| x.exists())
+            .map(|x| modified_after(x, since))
+            .unwrap_or(true);
+    }
+
+    for entry in walkdir::WalkDir::new(&location).max_depth(depth).follow_links(true) {
+        let Ok(entry) = entry else {
+            return true;
+        };
+        if modified_after(entry.path(), since) {
+            return true;
+        }
+        if entry.depth() == depth && entry.file_type().is_dir() && has_children(entry.path()) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::repo;
+
+    fn future() -> SystemTime {
+        SystemTime::now() + Duration::from_secs(3600)
+    }
+
+    #[test]
+    fn can_find_glob_free_prefix() {
+        assert_eq!(
+            format!("{}/tests/root1", repo()),
+            glob_free_prefix(&StrictPath::new(format!("{}/tests/root1/*/subdir/*.txt", repo())))
+        );
+        assert_eq!(
+            format!("{}/tests/root1/game1", repo()),
+            glob_free_prefix(&StrictPath::new(format!("{}/tests/root1/game1", repo())))
+        );
+    }
+
+    #[test]
+    fn detects_activity_when_anything_is_newer() {
+        let candidates = [StrictPath::new(format!("{}/tests/root1/game1", repo()))];
+        assert!(has_recent_activity(&candidates, SystemTime::UNIX_EPOCH, 5));
+    }
+
+    #[test]
+    fn detects_no_activity_when_everything_is_older() {
+        let candidates = [
+            StrictPath::new(format!("{}/tests/root1/game1", repo())),
+            StrictPath::new(format!("{}/tests/root2/game1/*.txt", repo())),
+        ];
+        assert!(!has_recent_activity(&candidates, future(), 5));
+    }
+
+    #[test]
+    fn assumes_activity_when_folder_is_deeper_than_depth() {
+        let candidates = [StrictPath::new(format!("{}/tests/root1/game1", repo()))];
+        assert!(has_recent_activity(&candidates, future(), 1));
+    }
+}
@@ -1,11 +1,191 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::scan::{registry_compat::RegistryItem, ScanChangeCount, ScanInfo, ScannedFile};
+use crate::{
+    prelude::StrictPath,
+    scan::{
+        describe_failure, is_locked,
+        layout::{BackupKind, IndividualMappingFile, PromotionReason},
+        registry_compat::RegistryItem,
+        zip_safety::UnsafeZipEntry,
+        ScanChange, ScanChangeCount, ScanInfo, ScannedFile,
+    },
+};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct BackupInfo {
-    pub failed_files: HashSet<ScannedFile>,
-    pub failed_registry: HashSet<RegistryItem>,
+    /// Files that failed to process, along with why.
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub failed_files: HashMap<ScannedFile, String>,
+    /// Registry keys that failed to process, along with why.
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub failed_registry: HashMap<RegistryItem, String>,
+    /// Files that were backed up, but which the backup location stored under a different name.
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub altered_names: HashMap<ScannedFile, StrictPath>,
+    /// The kind of backup that was created, if any.
+    pub kind: Option<BackupKind>,
+    /// Set when a differential backup was made as a full backup instead.
+    pub promoted_because: Option<PromotionReason>,
+    /// The label that was applied to the new backup, if any.
+    pub label: BackupLabel,
+    /// Files whose content changed after the scan (e.g., because the game wrote to them during the backup),
+    /// along with what was actually stored for them.
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub changed_during_backup: HashMap<ScannedFile, IndividualMappingFile>,
+    /// Files that weren't restored because their zip entries looked malicious.
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub unsafe_zip_entries: HashMap<ScannedFile, UnsafeZipEntry>,
+    /// Files that failed because another process had them locked, even after retrying.
+    /// These are also included in `failed_files`.
+    pub locked_files: HashSet<ScannedFile>,
+    /// Set when nothing could be backed up because every file was locked,
+    /// so the game should be tried again later (e.g., after it's closed).
+    pub deferred: bool,
+}
+
+/// What kind of invocation created a backup.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerSource {
+    Cli,
+    Gui,
+    Wrap,
+    Scheduler,
+    Api,
+    Watch,
+    /// The backup was made by a version of Ludusavi that didn't record this.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl TriggerSource {
+    pub const CLI: &'static str = "cli";
+    pub const GUI: &'static str = "gui";
+    pub const WRAP: &'static str = "wrap";
+    pub const SCHEDULER: &'static str = "scheduler";
+    pub const API: &'static str = "api";
+    pub const WATCH: &'static str = "watch";
+    pub const UNKNOWN: &'static str = "unknown";
+    pub const ALL_NAMES: &'static [&'static str] = &[
+        Self::CLI,
+        Self::GUI,
+        Self::WRAP,
+        Self::SCHEDULER,
+        Self::API,
+        Self::WATCH,
+        Self::UNKNOWN,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cli => Self::CLI,
+            Self::Gui => Self::GUI,
+            Self::Wrap => Self::WRAP,
+            Self::Scheduler => Self::SCHEDULER,
+            Self::Api => Self::API,
+            Self::Watch => Self::WATCH,
+            Self::Unknown => Self::UNKNOWN,
+        }
+    }
+}
+
+impl std::str::FromStr for TriggerSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::CLI => Ok(Self::Cli),
+            Self::GUI => Ok(Self::Gui),
+            Self::WRAP => Ok(Self::Wrap),
+            Self::SCHEDULER => Ok(Self::Scheduler),
+            Self::API => Ok(Self::Api),
+            Self::WATCH => Ok(Self::Watch),
+            Self::UNKNOWN => Ok(Self::Unknown),
+            _ => Err(format!("invalid trigger: {}", s)),
+        }
+    }
+}
+
+/// Where a backup came from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Trigger {
+    #[serde(default)]
+    pub source: TriggerSource,
+    /// Name of the machine that made the backup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// For `wrap`, the name of the game executable that was launched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+}
+
+impl Trigger {
+    pub fn new(source: TriggerSource) -> Self {
+        Self {
+            source,
+            host: Some(whoami::hostname()).filter(|x| !x.is_empty()),
+            executable: None,
+        }
+    }
+
+    pub fn with_executable(mut self, executable: Option<String>) -> Self {
+        self.executable = executable;
+        self
+    }
+
+    /// A short description like `wrap@host (game.exe)`.
+    pub fn describe(&self) -> String {
+        let mut out = self.source.name().to_string();
+        if let Some(host) = &self.host {
+            out.push('@');
+            out.push_str(host);
+        }
+        if let Some(executable) = &self.executable {
+            out.push_str(&format!(" ({})", executable));
+        }
+        out
+    }
+}
+
+/// Details to attach to a new backup when it's created.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BackupLabel {
+    pub comment: Option<String>,
+    /// Locked backups are exempt from retention.
+    pub locked: bool,
+    /// What made this backup. This is always recorded, so it doesn't affect `is_empty`.
+    pub trigger: Trigger,
+    /// Which manifest revision was used (see `ManifestConfig::loaded_hash`).
+    /// This is always recorded, so it doesn't affect `is_empty`.
+    pub manifest_hash: Option<String>,
+}
+
+impl BackupLabel {
+    pub fn is_empty(&self) -> bool {
+        self.comment.is_none() && !self.locked
+    }
+}
+
+/// The outcome of backing up a game's current files right before restoring over them
+/// (see `restore --backup-before-restore`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SafetySnapshot {
+    /// There were no local files to back up.
+    Unneeded,
+    /// The name of the new backup.
+    Created(String),
+    /// Why the snapshot couldn't be made.
+    Failed(String),
+}
+
+impl SafetySnapshot {
+    pub fn failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
 }
 
 impl BackupInfo {
@@ -13,27 +193,47 @@ impl BackupInfo {
         self.failed_files.is_empty() && self.failed_registry.is_empty()
     }
 
-    pub fn total_failure(scan: &ScanInfo) -> Self {
+    /// Record a file that couldn't be read, noting whether it was locked.
+    pub fn fail_file(&mut self, file: &ScannedFile, error: &std::io::Error) {
+        let locked = is_locked(error);
+        if locked {
+            self.locked_files.insert(file.clone());
+        }
+        self.failed_files.insert(file.clone(), describe_failure(error, locked));
+    }
+
+    /// Once all files have been processed, mark the game as deferred
+    /// if none of them were stored and the only failures were locked files.
+    pub fn check_deferred(&mut self, stored: usize) {
+        self.deferred = stored == 0
+            && !self.locked_files.is_empty()
+            && self.failed_files.keys().all(|x| self.locked_files.contains(x))
+            && self.failed_registry.is_empty();
+    }
+
+    pub fn total_failure(scan: &ScanInfo, reason: &str) -> Self {
         let mut backup_info = Self::default();
 
         for file in &scan.found_files {
             if file.ignored {
                 continue;
             }
-            backup_info.failed_files.insert(file.clone());
+            backup_info.failed_files.insert(file.clone(), reason.to_string());
         }
         for reg_path in &scan.found_registry_keys {
             if reg_path.ignored {
                 continue;
             }
-            backup_info.failed_registry.insert(reg_path.path.clone());
+            backup_info
+                .failed_registry
+                .insert(reg_path.path.clone(), reason.to_string());
         }
 
         backup_info
     }
 }
 
-#[derive(Clone, Debug, Default, serde::Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct OperationStatus {
     #[serde(rename = "totalGames")]
     pub total_games: usize,
@@ -45,6 +245,16 @@ pub struct OperationStatus {
     pub processed_bytes: u64,
     #[serde(rename = "changedGames")]
     pub changed_games: ScanChangeCount,
+    /// Files that were excluded by ignore rules, across all games.
+    #[serde(rename = "ignoredFiles", default)]
+    pub ignored_files: usize,
+    #[serde(rename = "ignoredBytes", default)]
+    pub ignored_bytes: u64,
+    #[serde(rename = "ignoredRegistryKeys", default)]
+    pub ignored_registry_keys: usize,
+    /// How long the whole operation took, when measured by the CLI.
+    #[serde(rename = "totalDurationMs", default, skip_serializing_if = "Option::is_none")]
+    pub total_duration_ms: Option<u64>,
 }
 
 impl OperationStatus {
@@ -56,14 +266,22 @@ impl OperationStatus {
             self.processed_bytes += scan_info.sum_bytes(backup_info.as_ref());
         }
 
-        let changes = scan_info.count_changes();
-        if changes.brand_new() {
-            self.changed_games.new += 1;
-        } else if changes.updated() {
-            self.changed_games.different += 1;
-        } else {
-            self.changed_games.same += 1;
+        match scan_info.count_changes().overall() {
+            ScanChange::New => self.changed_games.new += 1,
+            ScanChange::Different => self.changed_games.different += 1,
+            ScanChange::Removed => self.changed_games.removed += 1,
+            ScanChange::Same | ScanChange::Unknown => self.changed_games.same += 1,
+        }
+
+        for file in scan_info.found_files.iter().filter(|x| x.ignored) {
+            self.ignored_files += 1;
+            self.ignored_bytes += file.size;
         }
+        self.ignored_registry_keys += scan_info.found_registry_keys.iter().filter(|x| x.ignored).count();
+    }
+
+    pub fn ignored_anything(&self) -> bool {
+        self.ignored_files > 0 || self.ignored_registry_keys > 0
     }
 
     pub fn processed_all_games(&self) -> bool {
@@ -75,15 +293,18 @@ impl OperationStatus {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum OperationStepDecision {
     #[default]
     Processed,
     Cancelled,
     Ignored,
+    /// The game's files were all locked by another process, even after retrying,
+    /// so it should be backed up again later.
+    Deferred,
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum BackupId {
     #[default]
     Latest,
@@ -1,9 +1,42 @@
+use std::collections::{HashMap, HashSet};
+
+use unicode_normalization::UnicodeNormalization as _;
+
 use crate::{
     lang::{ADD_SYMBOL, CHANGE_SYMBOL, REMOVAL_SYMBOL},
     prelude::StrictPath,
+    resource::config::UnicodeNormalization,
 };
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize)]
+/// Normalize a file name so that it compares equal regardless of
+/// whether the file system gave it to us in composed or decomposed form
+/// (see `scan.unicodeNormalization`).
+pub fn normalize_unicode(path: &str, normalization: UnicodeNormalization) -> String {
+    if path.is_ascii() {
+        return path.to_string();
+    }
+
+    match normalization {
+        UnicodeNormalization::Nfc => path.nfc().collect(),
+        UnicodeNormalization::Nfd => path.nfd().collect(),
+        UnicodeNormalization::None => path.to_string(),
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 pub enum ScanChange {
     New,
     Different,
@@ -38,6 +71,12 @@ impl ScanChange {
         }
     }
 
+    /// Pick the more significant of two changes reported for the same item.
+    pub fn worst(self, other: Self) -> Self {
+        // The variants are declared from most to least significant.
+        self.min(other)
+    }
+
     pub fn is_changed(&self) -> bool {
         match self {
             Self::New => true,
@@ -70,11 +109,23 @@ impl ScanChange {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
 pub struct ScanChangeCount {
     pub new: usize,
     pub different: usize,
-    #[serde(skip)]
+    #[serde(default)]
     pub removed: usize,
     pub same: usize,
 }
@@ -161,3 +212,204 @@ impl ScanChange {
         }
     }
 }
+
+/// How a file from a backup compares to the local file that restoring it would overwrite.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum LocalState {
+    /// There is no local file yet.
+    Missing,
+    /// The local file has the same content as the backup.
+    Same,
+    /// The local file is different and was modified before the backed up copy.
+    OlderLocal,
+    /// The local file is different and was modified after the backed up copy,
+    /// so restoring would discard newer changes.
+    NewerLocal,
+}
+
+impl LocalState {
+    /// Zip archives only store modification times to the nearest 2 seconds.
+    const MTIME_TOLERANCE: std::time::Duration = std::time::Duration::from_secs(2);
+
+    /// This builds on the result of `ScanChange::evaluate_restore`.
+    /// The modification times only matter when the content is different.
+    pub fn evaluate(
+        change: ScanChange,
+        local_mtime: Option<std::time::SystemTime>,
+        backup_mtime: Option<std::time::SystemTime>,
+    ) -> Option<Self> {
+        match change {
+            ScanChange::New => Some(Self::Missing),
+            ScanChange::Same => Some(Self::Same),
+            ScanChange::Different => {
+                let (local, backup) = local_mtime.zip(backup_mtime)?;
+                if local > backup + Self::MTIME_TOLERANCE {
+                    Some(Self::NewerLocal)
+                } else {
+                    Some(Self::OlderLocal)
+                }
+            }
+            ScanChange::Removed | ScanChange::Unknown => None,
+        }
+    }
+}
+
+/// How to compare file paths between backups.
+/// On case-insensitive systems, a folder that was only renamed by capitalization
+/// (e.g., `SaveGames` to `savegames`) still contains the same files.
+/// Names are also compared after Unicode normalization (see `normalize_unicode`).
+pub fn path_key(path: &str, case_sensitive: bool, normalization: UnicodeNormalization) -> String {
+    let path = normalize_unicode(path, normalization);
+    if case_sensitive {
+        path
+    } else {
+        path.to_lowercase()
+    }
+}
+
+/// The files from the previous backup, used to evaluate changes in a new scan.
+#[derive(Clone, Debug, Default)]
+pub struct PreviousFiles<'a> {
+    case_sensitive: bool,
+    normalization: UnicodeNormalization,
+    files: HashMap<String, (&'a StrictPath, &'a String)>,
+}
+
+impl<'a> PreviousFiles<'a> {
+    pub fn new(
+        files: impl Iterator<Item = (&'a StrictPath, &'a String)>,
+        case_sensitive: bool,
+        normalization: UnicodeNormalization,
+    ) -> Self {
+        Self {
+            case_sensitive,
+            normalization,
+            files: files
+                .map(|(path, hash)| (path_key(&path.interpret(), case_sensitive, normalization), (path, hash)))
+                .collect(),
+        }
+    }
+
+    pub fn normalization(&self) -> UnicodeNormalization {
+        self.normalization
+    }
+
+    fn key(&self, path: &StrictPath) -> String {
+        path_key(&path.interpret(), self.case_sensitive, self.normalization)
+    }
+
+    pub fn hash(&self, path: &StrictPath) -> Option<&&'a String> {
+        self.files.get(&self.key(path)).map(|(_, hash)| hash)
+    }
+
+    /// Previous files that don't correspond to any of the current files.
+    pub fn removed<'b>(&self, current: impl Iterator<Item = &'b StrictPath>) -> Vec<&'a StrictPath> {
+        let current: HashSet<_> = current.map(|x| self.key(x)).collect();
+        self.files
+            .iter()
+            .filter(|(key, _)| !current.contains(*key))
+            .map(|(_, (path, _))| *path)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    #[test]
+    fn previous_files_can_match_casing_changes_when_case_insensitive() {
+        let old = StrictPath::new(s("/game/SaveGames/file.sav"));
+        let hash = s("abc");
+        let new = StrictPath::new(s("/game/savegames/file.sav"));
+
+        let previous = PreviousFiles::new([(&old, &hash)].into_iter(), false, UnicodeNormalization::Nfc);
+        assert_eq!(Some(&&hash), previous.hash(&new));
+        assert_eq!(
+            ScanChange::Same,
+            ScanChange::evaluate_backup("abc", previous.hash(&new))
+        );
+        assert_eq!(Vec::<&StrictPath>::new(), previous.removed([&new].into_iter()));
+    }
+
+    #[test]
+    fn previous_files_keep_casing_changes_separate_when_case_sensitive() {
+        let old = StrictPath::new(s("/game/SaveGames/file.sav"));
+        let hash = s("abc");
+        let new = StrictPath::new(s("/game/savegames/file.sav"));
+
+        let previous = PreviousFiles::new([(&old, &hash)].into_iter(), true, UnicodeNormalization::Nfc);
+        assert_eq!(None, previous.hash(&new));
+        assert_eq!(ScanChange::New, ScanChange::evaluate_backup("abc", previous.hash(&new)));
+        assert_eq!(vec![&old], previous.removed([&new].into_iter()));
+    }
+
+    #[test]
+    fn previous_files_can_match_name_stored_as_nfd_with_scan_as_nfc() {
+        let old = StrictPath::new(s("/game/Sauvegarde/partie-e\u{301}te\u{301}.sav"));
+        let hash = s("abc");
+        let new = StrictPath::new(s("/game/Sauvegarde/partie-\u{e9}t\u{e9}.sav"));
+        assert_ne!(old.raw(), new.raw());
+
+        let previous = PreviousFiles::new([(&old, &hash)].into_iter(), true, UnicodeNormalization::Nfc);
+        assert_eq!(Some(&&hash), previous.hash(&new));
+        assert_eq!(
+            ScanChange::Same,
+            ScanChange::evaluate_backup("abc", previous.hash(&new))
+        );
+        assert_eq!(Vec::<&StrictPath>::new(), previous.removed([&new].into_iter()));
+    }
+
+    #[test]
+    fn can_evaluate_local_state() {
+        let backup = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+        let at = |secs| Some(std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs));
+
+        assert_eq!(
+            Some(LocalState::Missing),
+            LocalState::evaluate(ScanChange::New, None, Some(backup))
+        );
+        assert_eq!(
+            Some(LocalState::Same),
+            LocalState::evaluate(ScanChange::Same, at(5_000), Some(backup))
+        );
+        assert_eq!(
+            Some(LocalState::NewerLocal),
+            LocalState::evaluate(ScanChange::Different, at(5_000), Some(backup))
+        );
+        assert_eq!(
+            Some(LocalState::OlderLocal),
+            LocalState::evaluate(ScanChange::Different, at(500), Some(backup))
+        );
+        assert_eq!(
+            Some(LocalState::OlderLocal),
+            LocalState::evaluate(ScanChange::Different, at(1_001), Some(backup))
+        );
+        assert_eq!(None, LocalState::evaluate(ScanChange::Different, None, Some(backup)));
+        assert_eq!(None, LocalState::evaluate(ScanChange::Unknown, at(5_000), Some(backup)));
+    }
+
+    #[test]
+    fn path_key_normalizes_unicode() {
+        let nfc = UnicodeNormalization::Nfc;
+        assert_eq!(path_key("\u{e9}", true, nfc), path_key("e\u{301}", true, nfc));
+        assert_eq!(path_key("\u{c9}", false, nfc), path_key("e\u{301}", false, nfc));
+        assert_eq!("plain/ascii.sav", path_key("plain/ascii.sav", true, nfc));
+    }
+
+    #[test]
+    fn path_key_can_skip_unicode_normalization() {
+        let none = UnicodeNormalization::None;
+        assert_ne!(path_key("\u{e9}", true, none), path_key("e\u{301}", true, none));
+        assert_eq!(
+            path_key("\u{e9}", true, UnicodeNormalization::Nfd),
+            path_key("e\u{301}", true, UnicodeNormalization::Nfd)
+        );
+    }
+}
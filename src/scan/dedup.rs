@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use crate::scan::layout::{BackupLayout, IndividualMappingFile};
+
+/// A backed up file whose content also appears elsewhere.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupEntry {
+    pub game: String,
+    pub path: String,
+}
+
+/// Identical content found in the latest backups of more than one game.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupGroup {
+    pub hash: String,
+    /// Size of each copy.
+    pub size: u64,
+    /// Space that would be freed by keeping only one copy.
+    pub savings: u64,
+    pub files: Vec<DedupEntry>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupReport {
+    pub groups: Vec<DedupGroup>,
+    pub total_savings: u64,
+}
+
+/// Every recorded hash, mapped to where it appears.
+/// Game names are stored once and referenced by index,
+/// since there may be hundreds of thousands of entries.
+#[derive(Debug, Default)]
+struct HashIndex {
+    games: Vec<String>,
+    hashes: HashMap<String, Vec<(usize, String, u64)>>,
+}
+
+impl HashIndex {
+    fn add<'a>(&mut self, game: &str, files: impl IntoIterator<Item = (&'a str, &'a IndividualMappingFile)>) {
+        let game_index = self.games.len();
+        self.games.push(game.to_string());
+
+        for (path, file) in files {
+            if file.size == 0 {
+                // There is nothing to save.
+                continue;
+            }
+            self.hashes
+                .entry(file.hash.clone())
+                .or_default()
+                .push((game_index, path.to_string(), file.size));
+        }
+    }
+
+    fn into_report(self) -> DedupReport {
+        let mut report = DedupReport::default();
+
+        for (hash, mut entries) in self.hashes {
+            let first_game = entries[0].0;
+            if entries.iter().all(|(game, _, _)| *game == first_game) {
+                continue;
+            }
+
+            entries.sort_by(|(game1, path1, _), (game2, path2, _)| {
+                self.games[*game1]
+                    .cmp(&self.games[*game2])
+                    .then_with(|| path1.cmp(path2))
+            });
+
+            let size = entries.iter().map(|(_, _, size)| *size).max().unwrap_or_default();
+            let savings = size * (entries.len() as u64 - 1);
+            report.total_savings += savings;
+            report.groups.push(DedupGroup {
+                hash,
+                size,
+                savings,
+                files: entries
+                    .into_iter()
+                    .map(|(game, path, _)| DedupEntry {
+                        game: self.games[game].clone(),
+                        path,
+                    })
+                    .collect(),
+            });
+        }
+
+        report
+            .groups
+            .sort_by(|x, y| y.savings.cmp(&x.savings).then_with(|| x.hash.cmp(&y.hash)));
+
+        report
+    }
+}
+
+impl DedupReport {
+    /// Find identical content across the latest backups of all games,
+    /// based on the hashes recorded in each game's mapping file.
+    /// This does not read or modify any of the backed up files.
+    pub fn analyze(layout: &BackupLayout) -> Self {
+        let mut index = HashIndex::default();
+
+        let mut games = layout.restorable_games();
+        games.sort();
+        for name in games {
+            if let Some(game_layout) = layout.try_game_layout(&name) {
+                index.add(&name, game_layout.latest_files());
+            }
+        }
+
+        index.into_report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn file(hash: &str, size: u64) -> IndividualMappingFile {
+        IndividualMappingFile { hash: s(hash), size }
+    }
+
+    #[test]
+    fn can_group_identical_content_across_games() {
+        let mut index = HashIndex::default();
+        index.add(
+            "game2",
+            [
+                ("/saves/b/common.dll", &file("aaa", 100)),
+                ("/saves/b/unique.sav", &file("bbb", 5)),
+            ],
+        );
+        index.add(
+            "game1",
+            [
+                ("/saves/a/common.dll", &file("aaa", 100)),
+                ("/saves/a/shared.cfg", &file("ccc", 10)),
+            ],
+        );
+        index.add(
+            "game3",
+            [
+                ("/saves/c/common.dll", &file("aaa", 100)),
+                ("/saves/c/shared.cfg", &file("ccc", 10)),
+                ("/saves/c/empty.txt", &file("ddd", 0)),
+            ],
+        );
+        index.add("game4", [("/saves/d/empty.txt", &file("ddd", 0))]);
+
+        assert_eq!(
+            DedupReport {
+                groups: vec![
+                    DedupGroup {
+                        hash: s("aaa"),
+                        size: 100,
+                        savings: 200,
+                        files: vec![
+                            DedupEntry {
+                                game: s("game1"),
+                                path: s("/saves/a/common.dll"),
+                            },
+                            DedupEntry {
+                                game: s("game2"),
+                                path: s("/saves/b/common.dll"),
+                            },
+                            DedupEntry {
+                                game: s("game3"),
+                                path: s("/saves/c/common.dll"),
+                            },
+                        ],
+                    },
+                    DedupGroup {
+                        hash: s("ccc"),
+                        size: 10,
+                        savings: 10,
+                        files: vec![
+                            DedupEntry {
+                                game: s("game1"),
+                                path: s("/saves/a/shared.cfg"),
+                            },
+                            DedupEntry {
+                                game: s("game3"),
+                                path: s("/saves/c/shared.cfg"),
+                            },
+                        ],
+                    },
+                ],
+                total_savings: 210,
+            },
+            index.into_report()
+        );
+    }
+
+    #[test]
+    fn ignores_identical_content_within_one_game() {
+        let mut index = HashIndex::default();
+        index.add(
+            "game1",
+            [("/saves/a.sav", &file("aaa", 100)), ("/saves/b.sav", &file("aaa", 100))],
+        );
+        index.add("game2", [("/saves/c.sav", &file("bbb", 100))]);
+
+        assert_eq!(DedupReport::default(), index.into_report());
+    }
+}
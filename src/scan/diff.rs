@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    resource::config::ToggledPaths,
+    scan::{
+        layout::{GameLayout, IndividualMappingFile},
+        registry_compat::RegistryItem,
+        BackupId, ScanChange, ScanInfo,
+    },
+};
+
+/// Registry key -> value name -> data (e.g., `{"dword": 1}`).
+type RegistrySnapshot = BTreeMap<String, BTreeMap<String, serde_json::Value>>;
+
+/// Everything in one backup (or on disk) that can be compared with `BackupDiff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffSide {
+    /// Keyed by the file's original path, in rendered form.
+    pub files: BTreeMap<String, IndividualMappingFile>,
+    /// This is `None` when the registry can't be checked,
+    /// in which case it is left out of the comparison.
+    pub registry: Option<RegistrySnapshot>,
+}
+
+impl DiffSide {
+    /// Differential backups are resolved against their full backup.
+    /// Returns `None` if there is no such backup.
+    pub fn from_backup(layout: &GameLayout, id: &str) -> Option<Self> {
+        let id = BackupId::Named(id.to_string());
+        layout.find_by_id(&id)?;
+
+        let files = layout
+            .restorable_files(&id, false, &[], &ToggledPaths::default())
+            .into_iter()
+            .map(|file| {
+                (
+                    file.original_path().render(),
+                    IndividualMappingFile {
+                        hash: file.hash,
+                        size: file.size,
+                    },
+                )
+            })
+            .collect();
+
+        let registry = match layout.registry_content(&id) {
+            None => RegistrySnapshot::new(),
+            Some(content) => Self::parse_registry(&content).unwrap_or_default(),
+        };
+
+        Some(Self {
+            files,
+            registry: Some(registry),
+        })
+    }
+
+    /// Uses what a new backup would contain.
+    /// The registry is only available on Windows.
+    pub fn from_scan(scan_info: &ScanInfo) -> Self {
+        let files = scan_info
+            .found_files
+            .iter()
+            // The scan also lists files from the previous backup that are gone now.
+            .filter(|file| !file.ignored && file.change != ScanChange::Removed)
+            .map(|file| {
+                (
+                    file.effective().render(),
+                    IndividualMappingFile {
+                        hash: file.hash.clone(),
+                        size: file.size,
+                    },
+                )
+            })
+            .collect();
+
+        #[cfg(target_os = "windows")]
+        let registry = Some(
+            Self::parse_registry(
+                &crate::scan::registry::Hives::incorporated(&scan_info.found_registry_keys).serialize(),
+            )
+            .unwrap_or_default(),
+        );
+        #[cfg(not(target_os = "windows"))]
+        let registry = None;
+
+        Self { files, registry }
+    }
+
+    fn parse_registry(content: &str) -> Option<RegistrySnapshot> {
+        let hives: BTreeMap<String, BTreeMap<String, BTreeMap<String, serde_json::Value>>> =
+            serde_yaml::from_str(content).ok()?;
+
+        let mut snapshot = RegistrySnapshot::new();
+        for (hive, keys) in hives {
+            for (key, values) in keys {
+                snapshot.insert(RegistryItem::from_hive_and_key(&hive, &key).render(), values);
+            }
+        }
+        Some(snapshot)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub path: String,
+    pub change: ScanChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_size: Option<u64>,
+}
+
+/// A registry key that was added or removed (when `value` is unset),
+/// or a value that was added, removed, or changed.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryDiff {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub change: ScanChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_data: Option<serde_json::Value>,
+}
+
+/// What changed between two backups of the same game (or between a backup and the files on disk).
+/// Unchanged entries are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDiff {
+    pub game: String,
+    pub old: String,
+    /// This is unset when comparing against the files on disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+    pub files: Vec<FileDiff>,
+    /// This is omitted when the registry could not be compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<Vec<RegistryDiff>>,
+}
+
+fn change<T: PartialEq>(old: Option<&T>, new: Option<&T>) -> ScanChange {
+    match (old, new) {
+        (None, None) => ScanChange::Unknown,
+        (None, Some(_)) => ScanChange::New,
+        (Some(_), None) => ScanChange::Removed,
+        (Some(old), Some(new)) if old == new => ScanChange::Same,
+        (Some(_), Some(_)) => ScanChange::Different,
+    }
+}
+
+impl BackupDiff {
+    pub fn compare(old: &DiffSide, new: &DiffSide) -> Self {
+        let mut files = vec![];
+        let paths: BTreeSet<_> = old.files.keys().chain(new.files.keys()).collect();
+        for path in paths {
+            let old_file = old.files.get(path);
+            let new_file = new.files.get(path);
+            let change = change(old_file.map(|x| &x.hash), new_file.map(|x| &x.hash));
+            if change != ScanChange::Same {
+                files.push(FileDiff {
+                    path: path.clone(),
+                    change,
+                    old_size: old_file.map(|x| x.size),
+                    new_size: new_file.map(|x| x.size),
+                });
+            }
+        }
+
+        let registry = match (&old.registry, &new.registry) {
+            (Some(old), Some(new)) => Some(Self::compare_registry(old, new)),
+            _ => None,
+        };
+
+        Self {
+            files,
+            registry,
+            ..Default::default()
+        }
+    }
+
+    fn compare_registry(old: &RegistrySnapshot, new: &RegistrySnapshot) -> Vec<RegistryDiff> {
+        let mut diffs = vec![];
+        let empty = BTreeMap::new();
+
+        let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+        for key in keys {
+            let old_values = old.get(key);
+            let new_values = new.get(key);
+            let key_change = match (old_values, new_values) {
+                (None, Some(_)) => ScanChange::New,
+                (Some(_), None) => ScanChange::Removed,
+                _ => ScanChange::Same,
+            };
+            if key_change != ScanChange::Same {
+                diffs.push(RegistryDiff {
+                    key: key.clone(),
+                    value: None,
+                    change: key_change,
+                    old_data: None,
+                    new_data: None,
+                });
+            }
+
+            let old_values = old_values.unwrap_or(&empty);
+            let new_values = new_values.unwrap_or(&empty);
+            let names: BTreeSet<_> = old_values.keys().chain(new_values.keys()).collect();
+            for name in names {
+                let old_data = old_values.get(name);
+                let new_data = new_values.get(name);
+                let change = change(old_data, new_data);
+                if change != ScanChange::Same {
+                    diffs.push(RegistryDiff {
+                        key: key.clone(),
+                        value: Some(name.clone()),
+                        change,
+                        old_data: old_data.cloned(),
+                        new_data: new_data.cloned(),
+                    });
+                }
+            }
+        }
+
+        diffs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::{
+        prelude::StrictPath,
+        resource::config::Retention,
+        scan::layout::{DifferentialBackup, FullBackup, IndividualMapping},
+        testing::s,
+    };
+
+    fn file(hash: &str, size: u64) -> IndividualMappingFile {
+        IndividualMappingFile { hash: s(hash), size }
+    }
+
+    #[test]
+    fn can_compare_files() {
+        let old = DiffSide {
+            files: BTreeMap::from([
+                (s("/saves/changed.sav"), file("aaa", 1)),
+                (s("/saves/removed.sav"), file("bbb", 2)),
+                (s("/saves/same.sav"), file("ccc", 3)),
+            ]),
+            registry: None,
+        };
+        let new = DiffSide {
+            files: BTreeMap::from([
+                (s("/saves/added.sav"), file("ddd", 4)),
+                (s("/saves/changed.sav"), file("eee", 5)),
+                (s("/saves/same.sav"), file("ccc", 3)),
+            ]),
+            registry: Some(RegistrySnapshot::new()),
+        };
+
+        assert_eq!(
+            BackupDiff {
+                files: vec![
+                    FileDiff {
+                        path: s("/saves/added.sav"),
+                        change: ScanChange::New,
+                        old_size: None,
+                        new_size: Some(4),
+                    },
+                    FileDiff {
+                        path: s("/saves/changed.sav"),
+                        change: ScanChange::Different,
+                        old_size: Some(1),
+                        new_size: Some(5),
+                    },
+                    FileDiff {
+                        path: s("/saves/removed.sav"),
+                        change: ScanChange::Removed,
+                        old_size: Some(2),
+                        new_size: None,
+                    },
+                ],
+                registry: None,
+                ..Default::default()
+            },
+            BackupDiff::compare(&old, &new)
+        );
+    }
+
+    #[test]
+    fn can_compare_registry() {
+        let old = DiffSide::parse_registry(
+            r#"
+HKEY_CURRENT_USER:
+  "Software\\Ludusavi\\game":
+    changed:
+      dword: 1
+    removed:
+      sz: foo
+    same:
+      sz: bar
+  "Software\\Ludusavi\\old": {}
+"#,
+        );
+        let new = DiffSide::parse_registry(
+            r#"
+HKEY_CURRENT_USER:
+  "Software\\Ludusavi\\game":
+    added:
+      binary: [65]
+    changed:
+      dword: 2
+    same:
+      sz: bar
+"#,
+        );
+
+        let diff = BackupDiff::compare(
+            &DiffSide {
+                registry: old,
+                ..Default::default()
+            },
+            &DiffSide {
+                registry: new,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            Some(vec![
+                RegistryDiff {
+                    key: s("HKEY_CURRENT_USER/Software/Ludusavi/game"),
+                    value: Some(s("added")),
+                    change: ScanChange::New,
+                    old_data: None,
+                    new_data: Some(json!({"binary": [65]})),
+                },
+                RegistryDiff {
+                    key: s("HKEY_CURRENT_USER/Software/Ludusavi/game"),
+                    value: Some(s("changed")),
+                    change: ScanChange::Different,
+                    old_data: Some(json!({"dword": 1})),
+                    new_data: Some(json!({"dword": 2})),
+                },
+                RegistryDiff {
+                    key: s("HKEY_CURRENT_USER/Software/Ludusavi/game"),
+                    value: Some(s("removed")),
+                    change: ScanChange::Removed,
+                    old_data: Some(json!({"sz": "foo"})),
+                    new_data: None,
+                },
+                RegistryDiff {
+                    key: s("HKEY_CURRENT_USER/Software/Ludusavi/old"),
+                    value: None,
+                    change: ScanChange::Removed,
+                    old_data: None,
+                    new_data: None,
+                },
+            ]),
+            diff.registry
+        );
+    }
+
+    #[test]
+    fn can_resolve_differential_backup_against_its_base() {
+        let layout = GameLayout::new(
+            StrictPath::new(s("/backups/game")),
+            IndividualMapping {
+                name: s("game"),
+                backups: VecDeque::from([FullBackup {
+                    name: s("backup-1"),
+                    files: BTreeMap::from([
+                        (s("/saves/changed.sav"), file("aaa", 1)),
+                        (s("/saves/removed.sav"), file("bbb", 2)),
+                        (s("/saves/same.sav"), file("ccc", 3)),
+                    ]),
+                    children: VecDeque::from([DifferentialBackup {
+                        name: s("backup-2"),
+                        files: BTreeMap::from([
+                            (s("/saves/added.sav"), Some(file("ddd", 4))),
+                            (s("/saves/changed.sav"), Some(file("eee", 5))),
+                            (s("/saves/removed.sav"), None),
+                        ]),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            Retention::default(),
+        );
+
+        assert!(DiffSide::from_backup(&layout, "nope").is_none());
+        assert_eq!(
+            Some(DiffSide {
+                files: BTreeMap::from([
+                    (s("/saves/added.sav"), file("ddd", 4)),
+                    (s("/saves/changed.sav"), file("eee", 5)),
+                    (s("/saves/same.sav"), file("ccc", 3)),
+                ]),
+                registry: Some(RegistrySnapshot::new()),
+            }),
+            DiffSide::from_backup(&layout, "backup-2")
+        );
+    }
+}
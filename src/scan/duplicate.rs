@@ -24,22 +24,18 @@ impl Duplication {
 
     pub fn evaluate<'a>(items: impl Iterator<Item = &'a DuplicateDetectorEntry> + Clone) -> Duplication {
         let mut total = 0;
-        let mut enabled = 0;
-        let mut removed = 0;
+        let mut contending = 0;
 
         for item in items {
             total += 1;
-            if item.enabled {
-                enabled += 1;
-            }
-            if item.change == ScanChange::Removed {
-                removed += 1;
+            if item.enabled && item.change != ScanChange::Removed {
+                contending += 1;
             }
         }
 
         if total < 2 {
             Duplication::Unique
-        } else if enabled <= 1 || removed >= total - 1 {
+        } else if contending <= 1 {
             Duplication::Resolved
         } else {
             Duplication::Duplicate
@@ -53,6 +49,14 @@ pub struct DuplicateDetectorEntry {
     change: ScanChange,
 }
 
+impl DuplicateDetectorEntry {
+    /// Whether this game would actually back up or restore the item.
+    /// Items that are ignored, removed, or not yet scanned don't conflict with anything.
+    fn is_active(&self) -> bool {
+        self.enabled && !self.change.is_inert()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 struct DuplicateDetectorCount {
     non_unique: u32,
@@ -124,7 +128,8 @@ impl DuplicateDetector {
             self.registry.entry(path.clone()).or_default().insert(
                 scan_info.game_name.clone(),
                 DuplicateDetectorEntry {
-                    enabled: game_enabled && !item.ignored,
+                    // A key is still included if any of its values are.
+                    enabled: game_enabled && !(item.ignored && item.values.values().all(|x| x.ignored)),
                     change: item.change(scan_info.restoring()),
                 },
             );
@@ -287,7 +292,7 @@ impl DuplicateDetector {
         for item in self.files.values() {
             if item.contains_key(game) && item.len() > 1 {
                 tally.non_unique += 1;
-                if item.values().filter(|x| !x.change.is_inert()).count() <= 1 {
+                if item.values().filter(|x| x.is_active()).count() <= 1 {
                     tally.resolved += 1;
                 }
             }
@@ -295,7 +300,7 @@ impl DuplicateDetector {
         for item in self.registry.values() {
             if item.contains_key(game) && item.len() > 1 {
                 tally.non_unique += 1;
-                if item.values().filter(|x| !x.change.is_inert()).count() <= 1 {
+                if item.values().filter(|x| x.is_active()).count() <= 1 {
                     tally.resolved += 1;
                 }
             }
@@ -304,7 +309,7 @@ impl DuplicateDetector {
             for item in item.values() {
                 if item.contains_key(game) && item.len() > 1 {
                     tally.non_unique += 1;
-                    if item.values().filter(|x| !x.change.is_inert()).count() <= 1 {
+                    if item.values().filter(|x| x.is_active()).count() <= 1 {
                         tally.resolved += 1;
                     }
                 }
@@ -462,6 +467,10 @@ mod tests {
             change: Default::default(),
             container: None,
             redirected: None,
+            redirect_rule: None,
+            sqlite_safe_copy: false,
+            junk: false,
+            migrated_from: None,
         };
         let file1b = ScannedFile {
             path: StrictPath::new(s("file1b.txt")),
@@ -472,6 +481,10 @@ mod tests {
             change: Default::default(),
             container: None,
             redirected: None,
+            redirect_rule: None,
+            sqlite_safe_copy: false,
+            junk: false,
+            migrated_from: None,
         };
 
         detector.add_game(
@@ -510,6 +523,10 @@ mod tests {
                 change: Default::default(),
                 container: None,
                 redirected: None,
+                redirect_rule: None,
+                sqlite_safe_copy: false,
+                junk: false,
+                migrated_from: None,
             })
         );
 
@@ -532,6 +549,10 @@ mod tests {
                 change: Default::default(),
                 container: None,
                 redirected: None,
+                redirect_rule: None,
+                sqlite_safe_copy: false,
+                junk: false,
+                migrated_from: None,
             })
         );
     }
@@ -603,4 +624,181 @@ mod tests {
             detector.is_file_duplicated(&ScannedFile::with_name("file1"))
         );
     }
+
+    #[test]
+    fn new_file_ignored_on_one_side_is_resolved() {
+        let mut detector = DuplicateDetector::default();
+
+        detector.add_game(
+            &ScanInfo {
+                game_name: "base".into(),
+                found_files: hashset! {
+                    ScannedFile::with_name("file1").change_new(),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+        detector.add_game(
+            &ScanInfo {
+                game_name: "conflict".into(),
+                found_files: hashset! {
+                    ScannedFile::with_name("file1").change_new().ignored(),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("base"));
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("conflict"));
+        assert_eq!(
+            Duplication::Resolved,
+            detector.is_file_duplicated(&ScannedFile::with_name("file1"))
+        );
+        assert_eq!(Duplication::Resolved, detector.overall());
+    }
+
+    #[test]
+    fn file_ignored_on_both_sides_is_resolved() {
+        let mut detector = DuplicateDetector::default();
+
+        for game in ["base", "conflict"] {
+            detector.add_game(
+                &ScanInfo {
+                    game_name: game.into(),
+                    found_files: hashset! {
+                        ScannedFile::with_name("file1").change_as(ScanChange::Same).ignored(),
+                    },
+                    ..Default::default()
+                },
+                true,
+            );
+        }
+
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("base"));
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("conflict"));
+        assert_eq!(
+            Duplication::Resolved,
+            detector.is_file_duplicated(&ScannedFile::with_name("file1"))
+        );
+    }
+
+    #[test]
+    fn file_in_disabled_game_is_resolved() {
+        let mut detector = DuplicateDetector::default();
+
+        detector.add_game(
+            &ScanInfo {
+                game_name: "base".into(),
+                found_files: hashset! {
+                    ScannedFile::with_name("file1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+        detector.add_game(
+            &ScanInfo {
+                game_name: "conflict".into(),
+                found_files: hashset! {
+                    ScannedFile::with_name("file1").change_as(ScanChange::Same),
+                },
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("base"));
+        assert_eq!(
+            Duplication::Resolved,
+            detector.is_file_duplicated(&ScannedFile::with_name("file1"))
+        );
+    }
+
+    #[test]
+    fn ignored_registry_value_is_resolved() {
+        let mut detector = DuplicateDetector::default();
+        let key = RegistryItem::new("HKEY_CURRENT_USER/Software/Ludusavi".to_string());
+
+        detector.add_game(
+            &ScanInfo {
+                game_name: "base".into(),
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new(key.raw())
+                        .change_as(ScanChange::Same)
+                        .with_value("shared", ScanChange::Same, false)
+                        .with_value("other", ScanChange::Same, false),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+        detector.add_game(
+            &ScanInfo {
+                game_name: "conflict".into(),
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new(key.raw())
+                        .change_as(ScanChange::Same)
+                        .ignored()
+                        .with_value("shared", ScanChange::Same, true)
+                        .with_value("other", ScanChange::Same, true),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("base"));
+        assert_eq!(Duplication::Resolved, detector.is_game_duplicated("conflict"));
+        assert_eq!(Duplication::Resolved, detector.is_registry_duplicated(&key));
+        assert_eq!(
+            Duplication::Resolved,
+            detector.is_registry_value_duplicated(&key, "shared")
+        );
+    }
+
+    #[test]
+    fn registry_value_ignored_on_one_side_leaves_others_duplicated() {
+        let mut detector = DuplicateDetector::default();
+        let key = RegistryItem::new("HKEY_CURRENT_USER/Software/Ludusavi".to_string());
+
+        detector.add_game(
+            &ScanInfo {
+                game_name: "base".into(),
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new(key.raw())
+                        .change_as(ScanChange::Same)
+                        .with_value("shared", ScanChange::Same, false)
+                        .with_value("other", ScanChange::Same, false),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+        detector.add_game(
+            &ScanInfo {
+                game_name: "conflict".into(),
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new(key.raw())
+                        .change_as(ScanChange::Same)
+                        .with_value("shared", ScanChange::Same, true)
+                        .with_value("other", ScanChange::Same, false),
+                },
+                ..Default::default()
+            },
+            true,
+        );
+
+        assert_eq!(
+            Duplication::Resolved,
+            detector.is_registry_value_duplicated(&key, "shared")
+        );
+        assert_eq!(
+            Duplication::Duplicate,
+            detector.is_registry_value_duplicated(&key, "other")
+        );
+        assert_eq!(Duplication::Duplicate, detector.is_registry_duplicated(&key));
+        assert_eq!(Duplication::Duplicate, detector.is_game_duplicated("conflict"));
+    }
 }
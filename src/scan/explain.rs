@@ -0,0 +1,427 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    path::StrictPath,
+    resource::{
+        config::{BackupFormats, Config, RedirectKind, Retention, RootsConfig, ToggledRegistryEntry},
+        manifest::{Game, Store},
+    },
+    scan::{backup_candidates, launchers::Launchers, registry_compat::RegistryItem, PathMigrations, SteamShortcuts},
+};
+
+/// Everything that would go into scanning one game, without actually looking for its saves.
+/// This is meant for troubleshooting (see `ludusavi explain`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Explanation {
+    pub game: String,
+    /// Other titles whose entries were combined into this one (see `mergedGames`).
+    pub merged_titles: Vec<String>,
+    /// The entry after incorporating secondary manifests, custom games, and merged titles.
+    pub manifest_entry: Game,
+    pub roots: Vec<ExplainedRoot>,
+    /// Paths and globs that would be checked, with placeholders resolved.
+    pub candidates: Vec<ExplainedCandidate>,
+    /// Install folders reported by a launcher, but which no longer exist.
+    pub unresolved_base_dirs: Vec<String>,
+    pub ignores: ExplainedIgnores,
+    pub redirects: Vec<ExplainedRedirect>,
+    pub backup: ExplainedBackup,
+    pub cloud: ExplainedCloud,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedRoot {
+    pub path: String,
+    pub store: Store,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedCandidate {
+    pub path: String,
+    /// Only set when the manifest entry implies it (e.g., based on the game's platform).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case_sensitive: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedIgnores {
+    pub enabled_for_backup: bool,
+    pub enabled_for_restore: bool,
+    pub registry_enabled: bool,
+    pub exclude_store_screenshots: bool,
+    pub exclude_common_junk: bool,
+    /// From `backup.filter.ignoredPaths`, which apply to every game.
+    pub global_paths: Vec<String>,
+    /// From `backup.filter.ignoredRegistry`, which apply to every game.
+    pub global_registry: Vec<String>,
+    /// This game's entries in `backup.toggledPaths`, where `false` means ignored.
+    pub backup_toggled_paths: BTreeMap<String, bool>,
+    /// This game's entries in `restore.toggledPaths`, where `false` means ignored.
+    pub restore_toggled_paths: BTreeMap<String, bool>,
+    pub backup_toggled_registry: BTreeMap<String, ToggledRegistryEntry>,
+    pub restore_toggled_registry: BTreeMap<String, ToggledRegistryEntry>,
+}
+
+/// Where a redirect comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RedirectOrigin {
+    /// The `redirects` config field.
+    #[default]
+    Config,
+    /// A path migration rule for this game, which only applies with `restore --migrate-paths`.
+    PathMigration,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedRedirect {
+    pub kind: RedirectKind,
+    pub source: String,
+    pub target: String,
+    pub origin: RedirectOrigin,
+}
+
+/// There are no per-game overrides for these yet, so they match the config.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedBackup {
+    pub path: String,
+    pub restore_path: String,
+    pub format: BackupFormats,
+    pub retention: Retention,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainedCloud {
+    pub configured: bool,
+    pub synchronize: bool,
+    pub path: String,
+    /// Cloud sync covers the whole backup folder, so this is true for every game
+    /// as long as a remote is configured and synchronization is enabled.
+    pub included: bool,
+}
+
+impl Explanation {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        game: &Game,
+        config: &Config,
+        roots: &[RootsConfig],
+        manifest_dir: &StrictPath,
+        launchers: &Launchers,
+        wine_prefix: &Option<StrictPath>,
+        steam_shortcuts: &SteamShortcuts,
+    ) -> Self {
+        let (candidates, unresolved_base_dirs) = backup_candidates(
+            game,
+            name,
+            roots,
+            manifest_dir,
+            launchers,
+            &config.backup.filter,
+            wine_prefix,
+            steam_shortcuts,
+        );
+        let candidates: BTreeSet<_> = candidates
+            .into_iter()
+            .map(|(path, case_sensitive)| ExplainedCandidate {
+                path: path.render(),
+                case_sensitive,
+            })
+            .collect();
+
+        let migration_redirects = PathMigrations::from_game(game).with_redirects(&[]);
+        let redirects = config
+            .redirects
+            .iter()
+            .map(|x| (x, RedirectOrigin::Config))
+            .chain(migration_redirects.iter().map(|x| (x, RedirectOrigin::PathMigration)))
+            .map(|(x, origin)| ExplainedRedirect {
+                kind: x.kind,
+                source: x.source.raw(),
+                target: x.target.raw(),
+                origin,
+            })
+            .collect();
+
+        let render_paths = |paths: Option<&BTreeMap<StrictPath, bool>>| {
+            paths
+                .map(|x| x.iter().map(|(k, v)| (k.raw(), *v)).collect())
+                .unwrap_or_default()
+        };
+        let render_registry = |registry: Option<&BTreeMap<RegistryItem, ToggledRegistryEntry>>| {
+            registry
+                .map(|x| x.iter().map(|(k, v)| (k.raw(), v.clone())).collect())
+                .unwrap_or_default()
+        };
+
+        Self {
+            game: name.to_string(),
+            merged_titles: config.merged_titles().remove(name).unwrap_or_default(),
+            manifest_entry: game.clone(),
+            roots: roots
+                .iter()
+                .map(|x| ExplainedRoot {
+                    path: x.path.raw(),
+                    store: x.store,
+                })
+                .collect(),
+            candidates: candidates.into_iter().collect(),
+            unresolved_base_dirs: unresolved_base_dirs.iter().map(|x| x.raw()).collect(),
+            ignores: ExplainedIgnores {
+                enabled_for_backup: config.is_game_enabled_for_backup(name),
+                enabled_for_restore: config.is_game_enabled_for_restore(name),
+                registry_enabled: config.is_registry_enabled(name),
+                exclude_store_screenshots: config.backup.filter.exclude_store_screenshots,
+                exclude_common_junk: config.scan.exclude_common_junk,
+                global_paths: config.backup.filter.ignored_paths.iter().map(|x| x.raw()).collect(),
+                global_registry: config.backup.filter.ignored_registry.iter().map(|x| x.raw()).collect(),
+                backup_toggled_paths: render_paths(config.backup.toggled_paths.for_game(name)),
+                restore_toggled_paths: render_paths(config.restore.toggled_paths.for_game(name)),
+                backup_toggled_registry: render_registry(config.backup.toggled_registry.for_game(name)),
+                restore_toggled_registry: render_registry(config.restore.toggled_registry.for_game(name)),
+            },
+            redirects,
+            backup: ExplainedBackup {
+                path: config.backup.path.raw(),
+                restore_path: config.restore.path.raw(),
+                format: config.backup.format.clone(),
+                retention: config.backup.retention.clone(),
+            },
+            cloud: ExplainedCloud {
+                configured: config.cloud.remote.is_some(),
+                synchronize: config.cloud.synchronize,
+                path: config.cloud.path.clone(),
+                included: config.cloud.remote.is_some() && config.cloud.synchronize,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        resource::{
+            manifest::{Manifest, Os},
+            ResourceFile,
+        },
+        testing::{repo, s},
+    };
+
+    fn config() -> Config {
+        Config::load_from_string(&format!(
+            r#"
+            manifest:
+              url: example.com
+              etag: null
+            roots:
+              - path: {0}/tests/root1
+                store: other
+            backup:
+              path: /backups
+              ignoredGames:
+                - game1
+              filter:
+                ignoredPaths:
+                  - /global/ignored
+              toggledPaths:
+                game1:
+                  {0}/tests/root1/game1/subdir: false
+            restore:
+              path: /restores
+            redirects:
+              - kind: restore
+                source: /old
+                target: /new
+            mergedGames:
+              - name: game1
+                titles:
+                  - game1-dlc
+            "#,
+            repo()
+        ))
+        .unwrap()
+    }
+
+    fn manifest() -> Manifest {
+        Manifest::load_from_string(&format!(
+            r#"
+            game1:
+              files:
+                <base>/*.sav: {{}}
+                <root>/<game>/subdir: {{}}
+              pathMigrations:
+                - from: /legacy
+                  to: {0}/tests/root1/game1/subdir
+            "#,
+            repo()
+        ))
+        .unwrap()
+    }
+
+    fn explain(config: &Config, manifest: &Manifest) -> Explanation {
+        Explanation::new(
+            "game1",
+            &manifest.0["game1"],
+            config,
+            &config.roots,
+            &StrictPath::new(repo()),
+            &Launchers::scan_dirs(&config.roots, manifest, &[s("game1")]),
+            &None,
+            &SteamShortcuts::default(),
+        )
+    }
+
+    #[test]
+    fn can_explain_game() {
+        let config = config();
+        let manifest = manifest();
+
+        assert_eq!(
+            Explanation {
+                game: s("game1"),
+                merged_titles: vec![s("game1-dlc")],
+                manifest_entry: manifest.0["game1"].clone(),
+                roots: vec![ExplainedRoot {
+                    path: format!("{}/tests/root1", repo()),
+                    store: Store::Other,
+                }],
+                candidates: vec![
+                    ExplainedCandidate {
+                        path: StrictPath::new(format!("{}/tests/root1/game1/*.sav", repo())).render(),
+                        case_sensitive: Some(Os::HOST.is_case_sensitive()),
+                    },
+                    ExplainedCandidate {
+                        path: StrictPath::new(format!("{}/tests/root1/game1/subdir", repo())).render(),
+                        case_sensitive: Some(Os::HOST.is_case_sensitive()),
+                    },
+                ],
+                unresolved_base_dirs: vec![],
+                ignores: ExplainedIgnores {
+                    enabled_for_backup: false,
+                    enabled_for_restore: true,
+                    registry_enabled: true,
+                    exclude_store_screenshots: false,
+                    exclude_common_junk: false,
+                    global_paths: vec![s("/global/ignored")],
+                    global_registry: vec![],
+                    backup_toggled_paths: btreemap! {
+                        format!("{}/tests/root1/game1/subdir", repo()) => false,
+                    },
+                    restore_toggled_paths: btreemap! {},
+                    backup_toggled_registry: btreemap! {},
+                    restore_toggled_registry: btreemap! {},
+                },
+                redirects: vec![
+                    ExplainedRedirect {
+                        kind: RedirectKind::Restore,
+                        source: s("/old"),
+                        target: s("/new"),
+                        origin: RedirectOrigin::Config,
+                    },
+                    ExplainedRedirect {
+                        kind: RedirectKind::Restore,
+                        source: s("/legacy"),
+                        target: format!("{}/tests/root1/game1/subdir", repo()),
+                        origin: RedirectOrigin::PathMigration,
+                    },
+                ],
+                backup: ExplainedBackup {
+                    path: s("/backups"),
+                    restore_path: s("/restores"),
+                    format: BackupFormats::default(),
+                    retention: Retention::default(),
+                },
+                cloud: ExplainedCloud {
+                    configured: false,
+                    synchronize: true,
+                    path: s("ludusavi-backup"),
+                    included: false,
+                },
+            },
+            explain(&config, &manifest),
+        );
+    }
+
+    #[test]
+    fn can_serialize_explanation() {
+        let mut config = config();
+        config.roots.clear();
+        config.redirects.clear();
+        config.merged_games.clear();
+        config.backup.toggled_paths = Default::default();
+        config.backup.filter.ignored_paths.clear();
+        let manifest = Manifest::load_from_string("game1: {}").unwrap();
+
+        assert_eq!(
+            r#"
+{
+  "game": "game1",
+  "mergedTitles": [],
+  "manifestEntry": {},
+  "roots": [],
+  "candidates": [],
+  "unresolvedBaseDirs": [],
+  "ignores": {
+    "enabledForBackup": false,
+    "enabledForRestore": true,
+    "registryEnabled": true,
+    "excludeStoreScreenshots": false,
+    "excludeCommonJunk": false,
+    "globalPaths": [],
+    "globalRegistry": [],
+    "backupToggledPaths": {},
+    "restoreToggledPaths": {},
+    "backupToggledRegistry": {},
+    "restoreToggledRegistry": {}
+  },
+  "redirects": [],
+  "backup": {
+    "path": "/backups",
+    "restorePath": "/restores",
+    "format": {
+      "chosen": "simple",
+      "zip": {
+        "compression": "deflate"
+      },
+      "compression": {
+        "deflate": {
+          "level": 6
+        },
+        "bzip2": {
+          "level": 6
+        },
+        "zstd": {
+          "level": 10
+        }
+      }
+    },
+    "retention": {
+      "full": 1,
+      "differential": 0
+    }
+  },
+  "cloud": {
+    "configured": false,
+    "synchronize": true,
+    "path": "ludusavi-backup",
+    "included": false
+  }
+}
+"#
+            .trim(),
+            serde_json::to_string_pretty(&explain(&config, &manifest)).unwrap(),
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Built-in patterns for files that games leave next to their saves,
+//! but which are never worth backing up (crash reports, logs, shader caches).
+//! When `scan.excludeCommonJunk` is enabled, matching files are found as usual,
+//! but ignored unless the user re-enables them for a specific game.
+
+use once_cell::sync::Lazy;
+
+use crate::prelude::StrictPath;
+
+/// Bump this whenever `PATTERNS` changes, so that logs show which list was applied.
+pub const VERSION: u32 = 1;
+
+/// Each pattern matches a file or folder, along with anything inside of it.
+/// Matching is case-insensitive and uses forward slashes.
+const PATTERNS: &[&str] = &[
+    // Unreal Engine
+    "**/CrashReportClient",
+    "**/Saved/Crashes",
+    "**/Saved/Logs",
+    "**/DerivedDataCache",
+    "**/*.upipelinecache",
+    // Crash dumps
+    "**/*.dmp",
+    "**/*.mdmp",
+    // Logs
+    "**/*.log",
+    // Shader caches
+    "**/ShaderCache",
+    "**/ShaderCaches",
+    "**/D3DSCache",
+    "**/DXCache",
+    "**/GLCache",
+    "**/GPUCache",
+];
+
+static GLOBS: Lazy<globset::GlobSet> = Lazy::new(|| {
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in PATTERNS {
+        for variant in [pattern.to_string(), format!("{pattern}/**")] {
+            match globset::GlobBuilder::new(&variant)
+                .literal_separator(true)
+                .backslash_escape(false)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => {
+                    log::error!("Invalid junk pattern: {variant} | {e:?}");
+                }
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|_| globset::GlobSet::empty())
+});
+
+pub fn is_junk(path: &StrictPath) -> bool {
+    GLOBS.is_match(path.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(path: &str) -> bool {
+        is_junk(&StrictPath::new(path.to_string()))
+    }
+
+    #[test]
+    fn matches_well_known_junk() {
+        assert!(check("/home/user/.config/Epic/Game/Saved/Logs/Game.log"));
+        assert!(check(
+            "/home/user/.config/Epic/Game/Saved/Logs/Game-backup-2024.01.02.txt"
+        ));
+        assert!(check(
+            "C:/Users/user/AppData/Local/Game/Saved/Crashes/UE-Game-1234/CrashContext.runtime-xml"
+        ));
+        assert!(check(
+            "C:/Users/user/AppData/Local/Game/Saved/Config/CrashReportClient/UECC-Windows-1234/CrashReportClient.ini"
+        ));
+        assert!(check(
+            "C:/Users/user/AppData/Local/Game/Saved/Game_PCD3D_SM5.upipelinecache"
+        ));
+        assert!(check("C:/Users/user/AppData/LocalLow/Company/Game/Player.log"));
+        assert!(check("C:/Users/user/AppData/LocalLow/Company/Game/Player-prev.log"));
+        assert!(check("C:/Users/user/AppData/Local/Game/crash.dmp"));
+        assert!(check("C:/Users/user/AppData/Local/Game/shadercache/0001.bin"));
+        assert!(check("C:/Users/user/AppData/Local/Game/GPUCache/data_0"));
+    }
+
+    #[test]
+    fn matches_junk_folders_themselves() {
+        assert!(check("C:/Users/user/AppData/Local/Game/Saved/Logs"));
+        assert!(check("C:/Users/user/AppData/Local/Game/ShaderCache"));
+    }
+
+    #[test]
+    fn does_not_match_saves() {
+        assert!(!check("C:/Users/user/AppData/Local/Game/Saved/SaveGames/Slot1.sav"));
+        assert!(!check(
+            "C:/Users/user/AppData/Local/Game/Saved/Config/WindowsNoEditor/GameUserSettings.ini"
+        ));
+        assert!(!check("C:/Users/user/AppData/LocalLow/Company/Game/save.json"));
+        assert!(!check("C:/Users/user/AppData/LocalLow/Company/Game/saves/profile.json"));
+        assert!(!check("C:/Users/user/AppData/Local/Game/Logs.sav"));
+        assert!(!check("C:/Users/user/AppData/Local/Game/catalog.logbook"));
+        assert!(!check("C:/Users/user/Documents/My Games/Game/ShaderCacheSettings.ini"));
+    }
+}
@@ -3,7 +3,7 @@ pub mod heroic;
 mod legendary;
 mod lutris;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::{
     prelude::StrictPath,
@@ -43,6 +43,20 @@ impl Launchers {
         self.get_game(root, game).map(|x| &x.install_dir)
     }
 
+    /// Games that were found in at least one root.
+    pub fn installed_games(&self) -> BTreeSet<String> {
+        self.games.values().flat_map(|games| games.keys().cloned()).collect()
+    }
+
+    /// Every install folder reported for the game, across all roots.
+    pub fn install_dirs(&self, game: &str) -> Vec<StrictPath> {
+        self.games
+            .values()
+            .filter_map(|games| games.get(game))
+            .map(|x| x.install_dir.clone())
+            .collect()
+    }
+
     pub fn get_platform(&self, root: &RootsConfig, game: &str) -> Option<Os> {
         self.get_game(root, game).and_then(|x| x.platform)
     }
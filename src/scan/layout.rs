@@ -1,26 +1,35 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     io::Write,
 };
 
 use chrono::{Datelike, Timelike};
 
 use crate::{
-    path::StrictPath,
-    prelude::{AnyError, INVALID_FILE_CHARS},
+    lang::TRANSLATOR,
+    path::{system_time_from_zip, StrictPath},
+    prelude::{is_newer_version, AnyError, INVALID_FILE_CHARS, VERSION},
     resource::{
         config::{
-            BackupFormat, BackupFormats, RedirectConfig, Retention, ToggledPaths, ToggledRegistry, ZipCompression,
+            BackupFormat, BackupFormats, Config, PreferWineUser, RedirectConfig, Retention, ToggledPaths,
+            ToggledRegistry, UnicodeNormalization, ZipCompression,
         },
         manifest::Os,
     },
     scan::{
-        game_file_target, prepare_backup_target, BackupId, BackupInfo, ScanChange, ScanInfo, ScannedFile,
-        ScannedRegistry,
+        game_file_target, is_locked, may_have_changed, normalize_unicode, path_key, prepare_backup_target,
+        retry_locked, sqlite, stamp, steam_account_targets, user_profile_redirect, wine_user_redirects,
+        zip_safety::{self, UnsafeZipEntry},
+        BackupId, BackupInfo, BackupLabel, LocalState, OsConstraints, ScanChange, ScanInfo, ScannedFile,
+        ScannedRegistry, SizeHistoryEntry, Trigger, TriggerSource,
     },
 };
 
 const SAFE: &str = "_";
+const ZIP_WRITE_BUFFER: usize = 1024 * 1024;
+const ZIP_READ_BUFFER: usize = 64 * 1024;
+/// Exported registry data is small, so a larger `registry.yaml` in a zip backup is not read into memory.
+const MAX_REGISTRY_SIZE: u64 = 16 * 1024 * 1024;
 
 macro_rules! some_or_continue {
     ($maybe:expr) => {
@@ -59,9 +68,13 @@ pub fn escape_folder_name(name: &str) -> String {
 pub struct LatestBackup {
     pub scan: ScanInfo,
     pub registry_content: Option<String>,
+    /// The most recently stored hash of each file in the latest chain of backups,
+    /// including files that the latest backup itself leaves out.
+    /// This is only populated when preparing for a backup.
+    pub stored_files: Vec<(StrictPath, String)>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Backup {
     Full(FullBackup),
     Differential(DifferentialBackup),
@@ -100,6 +113,68 @@ impl Backup {
         }
     }
 
+    pub fn app_version(&self) -> Option<&str> {
+        match self {
+            Self::Full(x) => x.app_version.as_deref(),
+            Self::Differential(x) => x.app_version.as_deref(),
+        }
+    }
+
+    pub fn manifest_hash(&self) -> Option<&str> {
+        match self {
+            Self::Full(x) => x.manifest_hash.as_deref(),
+            Self::Differential(x) => x.manifest_hash.as_deref(),
+        }
+    }
+
+    pub fn format(&self) -> BackupFormat {
+        match self {
+            Self::Full(x) => x.format(),
+            Self::Differential(x) => x.format(),
+        }
+    }
+
+    /// Size of the files stored in this backup, according to its mapping.
+    /// Differential backups only count their own changes, not what they inherit.
+    pub fn bytes(&self) -> u64 {
+        match self {
+            Self::Full(x) => x.bytes(),
+            Self::Differential(x) => x.changed_bytes(),
+        }
+    }
+
+    /// Number of files stored in this backup, according to its mapping.
+    /// Differential backups only count their own changes, not what they inherit.
+    pub fn file_count(&self) -> usize {
+        match self {
+            Self::Full(x) => x.files.len(),
+            Self::Differential(x) => x.files.values().flatten().count(),
+        }
+    }
+
+    /// What made this backup. Backups from before this was recorded have an unknown source.
+    pub fn trigger(&self) -> Trigger {
+        match self {
+            Self::Full(x) => x.trigger.clone(),
+            Self::Differential(x) => x.trigger.clone(),
+        }
+        .unwrap_or_default()
+    }
+
+    pub fn set_trigger(&mut self, trigger: Trigger) {
+        match self {
+            Self::Full(x) => x.trigger = Some(trigger),
+            Self::Differential(x) => x.trigger = Some(trigger),
+        }
+    }
+
+    pub fn set_manifest_hash(&mut self, hash: Option<String>) {
+        match self {
+            Self::Full(x) => x.manifest_hash = hash,
+            Self::Differential(x) => x.manifest_hash = hash,
+        }
+    }
+
     pub fn set_comment(&mut self, comment: String) {
         let comment = if comment.is_empty() { None } else { Some(comment) };
 
@@ -137,6 +212,21 @@ impl Backup {
         }
     }
 
+    pub fn short_id(&self) -> Option<u64> {
+        match self {
+            Self::Full(x) => x.id,
+            Self::Differential(x) => x.id,
+        }
+    }
+
+    /// Whether this backup is the one selected by a name or short ID.
+    pub fn matches(&self, id: &str) -> bool {
+        match self {
+            Self::Full(x) => x.matches(id),
+            Self::Differential(x) => x.matches(id),
+        }
+    }
+
     pub fn kind(&self) -> BackupKind {
         match self {
             Self::Full(_) => BackupKind::Full,
@@ -178,7 +268,7 @@ impl Backup {
             Self::Full(backup) => {
                 let mut failed = vec![];
                 for file in backup.files.keys() {
-                    if backup_info.failed_files.iter().any(|x| &x.path.raw() == file) {
+                    if backup_info.failed_files.keys().any(|x| &x.path.raw() == file) {
                         failed.push(file.to_string());
                     }
                 }
@@ -195,7 +285,7 @@ impl Backup {
             Self::Differential(backup) => {
                 let mut failed = vec![];
                 for file in backup.files.keys() {
-                    if backup_info.failed_files.iter().any(|x| &x.path.raw() == file) {
+                    if backup_info.failed_files.keys().any(|x| &x.path.raw() == file) {
                         failed.push(file.to_string());
                     }
                 }
@@ -210,6 +300,25 @@ impl Backup {
         }
     }
 
+    /// Record what was actually stored for files that changed after the scan.
+    pub fn update_changed_files(&mut self, backup_info: &BackupInfo) {
+        for (file, stored) in &backup_info.changed_during_backup {
+            let key = file.effective().render();
+            match self {
+                Self::Full(backup) => {
+                    if let Some(entry) = backup.files.get_mut(&key) {
+                        *entry = stored.clone();
+                    }
+                }
+                Self::Differential(backup) => {
+                    if let Some(Some(entry)) = backup.files.get_mut(&key) {
+                        *entry = stored.clone();
+                    }
+                }
+            }
+        }
+    }
+
     /// Use this after pruning failures to check if the backup is still useful.
     pub fn needed(&self) -> bool {
         match self {
@@ -219,6 +328,71 @@ impl Backup {
     }
 }
 
+/// Restricts which backups are considered, based on when and how they were made.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFilter {
+    /// Only include backups made at or after this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newer_than: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only include backups made before this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only include backups made by this kind of invocation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<TriggerSource>,
+}
+
+impl BackupFilter {
+    pub fn is_empty(&self) -> bool {
+        self.newer_than.is_none() && self.older_than.is_none() && self.trigger.is_none()
+    }
+
+    pub fn includes(&self, backup: &Backup) -> bool {
+        let (when, trigger) = match backup {
+            Backup::Full(x) => (&x.when, &x.trigger),
+            Backup::Differential(x) => (&x.when, &x.trigger),
+        };
+        self.includes_parts(when, trigger)
+    }
+
+    fn includes_parts(&self, when: &chrono::DateTime<chrono::Utc>, trigger: &Option<Trigger>) -> bool {
+        self.newer_than.map(|bound| *when >= bound).unwrap_or(true)
+            && self.older_than.map(|bound| *when < bound).unwrap_or(true)
+            && self
+                .trigger
+                .map(|source| trigger.clone().unwrap_or_default().source == source)
+                .unwrap_or(true)
+    }
+}
+
+/// Decides which backups the `prune` command deletes.
+/// Locked backups are never deleted, and they don't count toward the limits.
+/// Each game's newest backup is also kept unless `allow_latest` is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruneRules {
+    /// Keep this many of the newest full backups.
+    pub keep_full: Option<usize>,
+    /// Keep this many of the newest differential backups for each full backup.
+    pub keep_differential: Option<usize>,
+    /// Only delete backups that match this filter.
+    pub filter: BackupFilter,
+    /// Allow deleting the newest backup of a game.
+    pub allow_latest: bool,
+}
+
+impl PruneRules {
+    /// How many of the oldest unlocked backups are candidates for deletion.
+    /// Without a limit, every backup is a candidate if there's a filter.
+    fn excess(&self, keep: Option<usize>, unlocked: usize) -> usize {
+        match keep {
+            Some(keep) => unlocked.saturating_sub(keep),
+            None if !self.filter.is_empty() => unlocked,
+            None => 0,
+        }
+    }
+}
+
 impl ToString for Backup {
     fn to_string(&self) -> String {
         self.label()
@@ -228,6 +402,9 @@ impl ToString for Backup {
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct FullBackup {
     pub name: String,
+    /// A short number that identifies this backup within its game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
     pub when: chrono::DateTime<chrono::Utc>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub os: Option<Os>,
@@ -236,6 +413,15 @@ pub struct FullBackup {
     /// Locked backups do not count toward retention limits and are never deleted.
     #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
     pub locked: bool,
+    /// The version of Ludusavi that created this backup.
+    #[serde(default, rename = "appVersion", skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    /// The hash of the primary manifest that was loaded when this backup was created.
+    #[serde(default, rename = "manifestHash", skip_serializing_if = "Option::is_none")]
+    pub manifest_hash: Option<String>,
+    /// What made this backup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
     #[serde(default)]
     pub files: BTreeMap<String, IndividualMappingFile>,
     #[serde(default)]
@@ -244,6 +430,11 @@ pub struct FullBackup {
 }
 
 impl FullBackup {
+    /// Whether this backup is the one selected by a name or short ID.
+    pub fn matches(&self, id: &str) -> bool {
+        self.name == id || self.id.is_some_and(|x| x.to_string() == id)
+    }
+
     pub fn label(&self) -> String {
         chrono::DateTime::<chrono::Local>::from(self.when)
             .format("%Y-%m-%dT%H:%M:%S")
@@ -257,6 +448,16 @@ impl FullBackup {
             BackupFormat::Simple
         }
     }
+
+    /// Total size of the files stored in this backup.
+    pub fn bytes(&self) -> u64 {
+        self.files.values().map(|x| x.size).sum()
+    }
+
+    /// Total size of the files stored in this backup's differential children.
+    pub fn children_bytes(&self) -> u64 {
+        self.children.iter().map(|x| x.changed_bytes()).sum()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -269,6 +470,9 @@ pub enum BackupInclusion {
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct DifferentialBackup {
     pub name: String,
+    /// A short number that identifies this backup within its game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
     pub when: chrono::DateTime<chrono::Utc>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub os: Option<Os>,
@@ -277,6 +481,15 @@ pub struct DifferentialBackup {
     /// Locked backups do not count toward retention limits and are never deleted.
     #[serde(default, skip_serializing_if = "crate::serialization::is_false")]
     pub locked: bool,
+    /// The version of Ludusavi that created this backup.
+    #[serde(default, rename = "appVersion", skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    /// The hash of the primary manifest that was loaded when this backup was created.
+    #[serde(default, rename = "manifestHash", skip_serializing_if = "Option::is_none")]
+    pub manifest_hash: Option<String>,
+    /// What made this backup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
     #[serde(default)]
     pub files: BTreeMap<String, Option<IndividualMappingFile>>,
     #[serde(default)]
@@ -284,6 +497,16 @@ pub struct DifferentialBackup {
 }
 
 impl DifferentialBackup {
+    /// Whether this backup is the one selected by a name or short ID.
+    pub fn matches(&self, id: &str) -> bool {
+        self.name == id || self.id.is_some_and(|x| x.to_string() == id)
+    }
+
+    /// Total size of the changed files stored in this backup.
+    pub fn changed_bytes(&self) -> u64 {
+        self.files.values().flatten().map(|x| x.size).sum()
+    }
+
     /// File path must be in rendered form.
     pub fn file(&self, file: String) -> BackupInclusion {
         match self.files.get(&file) {
@@ -295,6 +518,39 @@ impl DifferentialBackup {
         }
     }
 
+    /// Like `file`, but a path that only differs by capitalization also counts
+    /// when the system is case-insensitive,
+    /// as does a path that only differs by Unicode normalization.
+    /// When checking many files, use `file_index` instead.
+    pub fn file_matching(
+        &self,
+        file: &str,
+        case_sensitive: bool,
+        normalization: UnicodeNormalization,
+    ) -> BackupInclusion {
+        self.file_index(case_sensitive, normalization).get(file)
+    }
+
+    /// Look up files in the same way as `file_matching`,
+    /// but only normalize this backup's paths once.
+    pub fn file_index(&self, case_sensitive: bool, normalization: UnicodeNormalization) -> DiffFileIndex {
+        DiffFileIndex {
+            case_sensitive,
+            normalization,
+            files: self
+                .files
+                .iter()
+                .map(|(k, v)| {
+                    let inclusion = match v {
+                        None => BackupInclusion::Excluded,
+                        Some(_) => BackupInclusion::Included,
+                    };
+                    (path_key(k, case_sensitive, normalization), inclusion)
+                })
+                .collect(),
+        }
+    }
+
     pub fn omits_registry(&self) -> bool {
         self.registry.as_ref().map(|x| x.hash.is_none()).unwrap_or_default()
     }
@@ -314,6 +570,22 @@ impl DifferentialBackup {
     }
 }
 
+/// The files of a `DifferentialBackup`, keyed by `path_key`.
+pub struct DiffFileIndex {
+    case_sensitive: bool,
+    normalization: UnicodeNormalization,
+    files: HashMap<String, BackupInclusion>,
+}
+
+impl DiffFileIndex {
+    pub fn get(&self, file: &str) -> BackupInclusion {
+        self.files
+            .get(&path_key(file, self.case_sensitive, self.normalization))
+            .copied()
+            .unwrap_or(BackupInclusion::Inherited)
+    }
+}
+
 fn default_backup_list() -> VecDeque<FullBackup> {
     VecDeque::from(vec![FullBackup {
         name: ".".to_string(),
@@ -339,6 +611,14 @@ pub struct IndividualMapping {
     pub drives: HashMap<String, String>,
     #[serde(default = "default_backup_list")]
     pub backups: VecDeque<FullBackup>,
+    /// The highest short ID ever assigned to a backup of this game,
+    /// including backups that have since been deleted or pruned.
+    #[serde(
+        default,
+        rename = "lastBackupId",
+        skip_serializing_if = "crate::serialization::is_zero"
+    )]
+    pub last_backup_id: u64,
 }
 
 impl IndividualMapping {
@@ -453,9 +733,52 @@ impl IndividualMapping {
             }
         }
 
+        parsed.assign_missing_ids();
+
         Ok(parsed)
     }
 
+    /// The next unused short ID for a new backup.
+    /// IDs only ever increase, so no two backups of the same game share one,
+    /// even after some backups are deleted.
+    pub fn next_backup_id(&self) -> u64 {
+        self.highest_backup_id() + 1
+    }
+
+    fn highest_backup_id(&self) -> u64 {
+        self.backups
+            .iter()
+            .flat_map(|full| std::iter::once(full.id).chain(full.children.iter().map(|diff| diff.id)))
+            .flatten()
+            .max()
+            .unwrap_or_default()
+            .max(self.last_backup_id)
+    }
+
+    /// Remember the highest ID in use, so that it isn't assigned again once that backup is gone.
+    fn update_last_backup_id(&mut self) {
+        self.last_backup_id = self.highest_backup_id();
+    }
+
+    /// Backups from older versions don't have short IDs yet,
+    /// so number them in order after any existing IDs.
+    fn assign_missing_ids(&mut self) {
+        let mut next = self.next_backup_id();
+        for full in self.backups.iter_mut() {
+            if full.id.is_none() {
+                full.id = Some(next);
+                next += 1;
+            }
+            for diff in full.children.iter_mut() {
+                if diff.id.is_none() {
+                    diff.id = Some(next);
+                    next += 1;
+                }
+            }
+        }
+        self.update_last_backup_id();
+    }
+
     fn load_raw(file: &StrictPath) -> Result<String, AnyError> {
         Ok(std::fs::read_to_string(file.interpret())?)
     }
@@ -505,6 +828,63 @@ impl IndividualMapping {
     }
 }
 
+/// Some file systems silently normalize or replace characters in names,
+/// so after writing a file with a non-ASCII name, check that it can be found again
+/// under the exact same name. If not, return the name that the file system actually used.
+fn find_altered_name(written: &StrictPath, normalization: UnicodeNormalization) -> Option<StrictPath> {
+    let interpreted = written.interpret();
+    let written_path = std::path::Path::new(&interpreted);
+    let written_name = written_path.file_name()?.to_string_lossy().to_string();
+    if written_name.is_ascii() {
+        return None;
+    }
+
+    let parent = written_path.parent()?;
+    let mut entries = std::fs::read_dir(parent).ok()?.filter_map(|x| x.ok());
+    let expected = normalize_unicode(&written_name, normalization);
+    let mut candidate = None;
+    for entry in entries.by_ref() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == written_name {
+            return None;
+        }
+        if candidate.is_none() && normalize_unicode(&name, normalization) == expected {
+            candidate = Some(entry.path());
+        }
+    }
+
+    candidate.map(|x| StrictPath::from(x.as_path()))
+}
+
+/// Drop files from a planned differential backup if they're unchanged since the full backup,
+/// and exclude any files from the full backup that no longer exist.
+/// On case-insensitive systems, a file whose folder was only renamed by capitalization
+/// is still inherited from the full backup, so restoring it uses the casing stored there.
+fn inherit_from_full_backup(
+    files: &mut BTreeMap<String, Option<IndividualMappingFile>>,
+    full: &FullBackup,
+    case_sensitive: bool,
+    normalization: UnicodeNormalization,
+) {
+    let current_keys: HashMap<_, _> = files
+        .keys()
+        .map(|x| (path_key(x, case_sensitive, normalization), x.clone()))
+        .collect();
+
+    for (file, prior) in &full.files {
+        match current_keys.get(&path_key(file, case_sensitive, normalization)) {
+            Some(current_key) => {
+                if Some(&prior.hash) == files[current_key].as_ref().map(|x| &x.hash) {
+                    files.remove(current_key);
+                }
+            }
+            None => {
+                files.insert(file.clone(), None);
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct BackupPlan {
     backup: Backup,
@@ -512,12 +892,43 @@ struct BackupPlan {
     registry: HashSet<ScannedRegistry>,
 }
 
+/// Settings from the config that affect how backups are read and written,
+/// other than the retention policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutSettings {
+    pub unicode_normalization: UnicodeNormalization,
+    pub retry_changed_files: bool,
+    pub retry_locked: u32,
+    pub zip_limits: zip_safety::ZipLimits,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self::new(&Config::default())
+    }
+}
+
+impl LayoutSettings {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            unicode_normalization: config.scan.unicode_normalization,
+            retry_changed_files: config.backup.retry_changed_files,
+            retry_locked: config.backup.retry_locked,
+            zip_limits: zip_safety::ZipLimits::new(
+                config.restore.max_compression_ratio,
+                config.restore.max_uncompressed_size,
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GameLayout {
     pub path: StrictPath,
     mapping: IndividualMapping,
     #[allow(dead_code)]
     retention: Retention,
+    settings: LayoutSettings,
 }
 
 impl GameLayout {
@@ -527,10 +938,11 @@ impl GameLayout {
             path,
             mapping,
             retention,
+            settings: LayoutSettings::default(),
         }
     }
 
-    pub fn load(path: StrictPath, retention: Retention) -> Result<Self, AnyError> {
+    pub fn load(path: StrictPath, retention: Retention, settings: LayoutSettings) -> Result<Self, AnyError> {
         let mapping = Self::mapping_file(&path);
         Ok(Self {
             path,
@@ -539,6 +951,7 @@ impl GameLayout {
                 e
             })?,
             retention,
+            settings,
         })
     }
 
@@ -552,11 +965,11 @@ impl GameLayout {
             BackupId::Named(name) => {
                 for full in &self.mapping.backups {
                     for diff in &full.children {
-                        if diff.name == *name {
+                        if diff.matches(name) {
                             return id.clone();
                         }
                     }
-                    if full.name == *name {
+                    if full.matches(name) {
                         return id.clone();
                     }
                 }
@@ -573,12 +986,12 @@ impl GameLayout {
                 let mut diff = None;
 
                 'outer: for full_candidate in &self.mapping.backups {
-                    if full_candidate.name == *id {
+                    if full_candidate.matches(id) {
                         full = Some(full_candidate);
                         break 'outer;
                     }
                     for diff_candidate in &full_candidate.children {
-                        if diff_candidate.name == *id {
+                        if diff_candidate.matches(id) {
                             full = Some(full_candidate);
                             diff = Some(diff_candidate);
                             break 'outer;
@@ -603,6 +1016,156 @@ impl GameLayout {
         }
     }
 
+    /// When the latest backup (full or differential) was made.
+    pub fn latest_backup_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.mapping
+            .latest_backup()
+            .map(|(full, diff)| diff.map(|x| x.when).unwrap_or(full.when))
+    }
+
+    /// The recorded hash and size of each file in the latest backup,
+    /// keyed by the file's original path.
+    pub fn latest_files(&self) -> Vec<(&str, &IndividualMappingFile)> {
+        match self.mapping.latest_backup() {
+            None => vec![],
+            Some((full, None)) => full.files.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+            Some((full, Some(diff))) => {
+                let index = diff.file_index(Os::HOST.is_case_sensitive(), self.settings.unicode_normalization);
+                diff.files
+                    .iter()
+                    .filter_map(|(k, v)| v.as_ref().map(|v| (k.as_str(), v)))
+                    .chain(
+                        full.files
+                            .iter()
+                            .filter(|(k, _)| index.get(k) == BackupInclusion::Inherited)
+                            .map(|(k, v)| (k.as_str(), v)),
+                    )
+                    .collect()
+            }
+        }
+    }
+
+    /// The most recently stored hash and size of each file in the latest chain
+    /// (the latest full backup and its differential backups), keyed by the file's original path.
+    /// Unlike `latest_files`, this also includes files that the latest backup leaves out
+    /// (e.g., because they were ignored for a while), as long as an earlier backup in the chain has them.
+    pub fn latest_stored_files(&self) -> Vec<(&str, &IndividualMappingFile)> {
+        let Some((full, _)) = self.mapping.latest_backup() else {
+            return vec![];
+        };
+
+        let case_sensitive = Os::HOST.is_case_sensitive();
+        let normalization = self.settings.unicode_normalization;
+        let mut files: HashMap<_, _> = full
+            .files
+            .iter()
+            .map(|(k, v)| (path_key(k, case_sensitive, normalization), (k.as_str(), v)))
+            .collect();
+        for diff in &full.children {
+            for (k, v) in &diff.files {
+                if let Some(v) = v {
+                    files.insert(path_key(k, case_sensitive, normalization), (k.as_str(), v));
+                }
+            }
+        }
+
+        files.into_values().collect()
+    }
+
+    /// The total size and file count as of each backup, oldest first,
+    /// based on the sizes recorded in the mapping file.
+    /// Differential backups include the files that they inherit from their full backup.
+    pub fn size_history(&self) -> Vec<SizeHistoryEntry> {
+        let case_sensitive = Os::HOST.is_case_sensitive();
+        let normalization = self.settings.unicode_normalization;
+        let mut entries = vec![];
+
+        let mut push = |name: &str, when, kind, files: Vec<&IndividualMappingFile>| {
+            let bytes: u64 = files.iter().map(|x| x.size).sum();
+            let delta = entries
+                .last()
+                .map(|previous: &SizeHistoryEntry| bytes as i64 - previous.bytes as i64);
+            entries.push(SizeHistoryEntry {
+                game: self.mapping.name.clone(),
+                backup: name.to_string(),
+                when,
+                kind,
+                bytes,
+                files: files.len(),
+                delta,
+            });
+        };
+
+        for full in &self.mapping.backups {
+            push(&full.name, full.when, BackupKind::Full, full.files.values().collect());
+
+            for diff in &full.children {
+                let index = diff.file_index(case_sensitive, normalization);
+                let files = diff
+                    .files
+                    .values()
+                    .flatten()
+                    .chain(
+                        full.files
+                            .iter()
+                            .filter(|(k, _)| index.get(k) == BackupInclusion::Inherited)
+                            .map(|(_, v)| v),
+                    )
+                    .collect();
+                push(&diff.name, diff.when, BackupKind::Differential, files);
+            }
+        }
+
+        entries
+    }
+
+    /// The space that each backup takes up on disk, oldest first,
+    /// along with how many of the files in its mapping are missing.
+    /// Zip archives are measured directly, while simple backups add up the actual size of each file,
+    /// with missing files counting as zero.
+    pub fn disk_usage(&self) -> Vec<(Backup, u64, usize)> {
+        self.restorable_backups_flattened()
+            .into_iter()
+            .map(|backup| {
+                let (bytes, missing) = {
+                    let files: Vec<_> = match &backup {
+                        Backup::Full(full) => full.files.keys().collect(),
+                        Backup::Differential(diff) => diff
+                            .files
+                            .iter()
+                            .filter(|(_, file)| file.is_some())
+                            .map(|(path, _)| path)
+                            .collect(),
+                    };
+
+                    match backup.format() {
+                        BackupFormat::Zip => match self.path.joined(backup.name()).metadata() {
+                            Ok(metadata) => (metadata.len(), 0),
+                            Err(_) => (0, files.len()),
+                        },
+                        BackupFormat::Simple => {
+                            let mut bytes = 0;
+                            let mut missing = 0;
+                            for file in files {
+                                let stored = self.mapping.game_file_immutable(
+                                    &self.path,
+                                    &StrictPath::new(file.clone()),
+                                    backup.name(),
+                                );
+                                match stored.metadata() {
+                                    Ok(metadata) => bytes += metadata.len(),
+                                    Err(_) => missing += 1,
+                                }
+                            }
+                            (bytes, missing)
+                        }
+                    }
+                };
+                (backup, bytes, missing)
+            })
+            .collect()
+    }
+
     /// When `restoring` is false, we don't check for entries' ScanChange,
     /// because the backup scan will do that separately.
     pub fn latest_backup(
@@ -621,6 +1184,15 @@ impl GameLayout {
                 found_registry_keys: Default::default(),
                 available_backups: vec![],
                 backup: None,
+                registry_skipped: false,
+                unresolved_base_dirs: Default::default(),
+                io_timeouts: Default::default(),
+                ambiguous_targets: Default::default(),
+                os_constrained: Default::default(),
+                local_states: Default::default(),
+                kept_newer_local: Default::default(),
+                source: None,
+                roots: Default::default(),
             })
         }
     }
@@ -655,9 +1227,10 @@ impl GameLayout {
             Some((full, Some(diff))) => {
                 files.extend(self.restorable_files_from_diff_backup(diff, restoring, redirects, toggled_paths));
 
+                let index = diff.file_index(Os::HOST.is_case_sensitive(), self.settings.unicode_normalization);
                 for full_file in self.restorable_files_from_full_backup(full, restoring, redirects, toggled_paths) {
                     let original_path = full_file.original_path.as_ref().unwrap().render();
-                    if diff.file(original_path) == BackupInclusion::Inherited {
+                    if index.get(&original_path) == BackupInclusion::Inherited {
                         files.insert(full_file);
                     }
                 }
@@ -678,7 +1251,7 @@ impl GameLayout {
 
         for (k, v) in &backup.files {
             let original_path = StrictPath::new(k.to_string());
-            let redirected = game_file_target(&original_path, redirects, true);
+            let (redirected, redirect_rule) = game_file_target(&original_path, redirects, true).unzip();
             let ignorable_path = redirected.as_ref().unwrap_or(&original_path);
             match backup.format() {
                 BackupFormat::Simple => {
@@ -695,8 +1268,12 @@ impl GameLayout {
                         hash: v.hash.clone(),
                         ignored: toggled_paths.is_ignored(&self.mapping.name, ignorable_path),
                         redirected,
+                        redirect_rule,
                         original_path: Some(original_path),
                         container: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     });
                 }
                 BackupFormat::Zip => {
@@ -711,8 +1288,12 @@ impl GameLayout {
                         hash: v.hash.clone(),
                         ignored: toggled_paths.is_ignored(&self.mapping.name, ignorable_path),
                         redirected,
+                        redirect_rule,
                         original_path: Some(original_path),
                         container: Some(self.path.joined(&backup.name)),
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     });
                 }
             }
@@ -733,7 +1314,7 @@ impl GameLayout {
         for (k, v) in &backup.files {
             let v = some_or_continue!(v);
             let original_path = StrictPath::new(k.to_string());
-            let redirected = game_file_target(&original_path, redirects, true);
+            let (redirected, redirect_rule) = game_file_target(&original_path, redirects, true).unzip();
             let ignorable_path = redirected.as_ref().unwrap_or(&original_path);
             match backup.format() {
                 BackupFormat::Simple => {
@@ -750,8 +1331,12 @@ impl GameLayout {
                         hash: v.hash.clone(),
                         ignored: toggled_paths.is_ignored(&self.mapping.name, ignorable_path),
                         redirected,
+                        redirect_rule,
                         original_path: Some(original_path),
                         container: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     });
                 }
                 BackupFormat::Zip => {
@@ -766,8 +1351,12 @@ impl GameLayout {
                         hash: v.hash.clone(),
                         ignored: toggled_paths.is_ignored(&self.mapping.name, ignorable_path),
                         redirected,
+                        redirect_rule,
                         original_path: Some(original_path),
                         container: Some(self.path.joined(&backup.name)),
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     });
                 }
             }
@@ -809,6 +1398,10 @@ impl GameLayout {
                     ignored: false,
                     container: None,
                     redirected: None,
+                    redirect_rule: None,
+                    sqlite_safe_copy: false,
+                    junk: false,
+                    migrated_from: None,
                 });
             }
         }
@@ -839,10 +1432,18 @@ impl GameLayout {
             BackupFormat::Zip => {
                 let handle = std::fs::File::open(self.path.joined(backup).interpret()).ok()?;
                 let mut archive = zip::ZipArchive::new(handle).ok()?;
-                let mut file = archive.by_name("registry.yaml").ok()?;
+                let mut file =
+                    zip_safety::open_by_name(&mut archive, "registry.yaml", self.settings.zip_limits).ok()?;
+                if file.size() > MAX_REGISTRY_SIZE {
+                    log::warn!(
+                        "Ignoring registry.yaml in {backup} because it expands to {} bytes",
+                        file.size()
+                    );
+                    return None;
+                }
 
                 let mut buffer = vec![];
-                std::io::copy(&mut file, &mut buffer).ok()?;
+                zip_safety::copy_entry(&mut file, &mut buffer).ok()?;
 
                 String::from_utf8(buffer).ok()
             }
@@ -908,19 +1509,79 @@ impl GameLayout {
         scan: &ScanInfo,
         now: &chrono::DateTime<chrono::Utc>,
         format: &BackupFormats,
-    ) -> Option<Backup> {
-        if !scan.found_anything_processable() && !self.retention.force_new_full {
+    ) -> Option<(Backup, Option<PromotionReason>)> {
+        if !scan.found_anything_processable() && !self.retention.force_new_full && !self.reincludes_files(scan) {
             return None;
         }
 
         let kind = self.plan_backup_kind();
 
+        let mut promoted_because = None;
         let backup = match kind {
             BackupKind::Full => Backup::Full(self.plan_full_backup(scan, now, format)),
-            BackupKind::Differential => Backup::Differential(self.plan_differential_backup(scan, now, format)),
+            BackupKind::Differential => {
+                let backup = Backup::Differential(self.plan_differential_backup(scan, now, format));
+                match &backup {
+                    Backup::Differential(diff) if backup.needed() => match self.plan_promotion(diff) {
+                        Some(reason) => {
+                            promoted_because = Some(reason);
+                            Backup::Full(self.plan_full_backup(scan, now, format))
+                        }
+                        None => backup,
+                    },
+                    _ => backup,
+                }
+            }
+        };
+
+        (backup.needed() || self.reinstates_inherited_files(&backup)).then_some((backup, promoted_because))
+    }
+
+    /// Whether the scan found unchanged files that the latest backup leaves out,
+    /// such as files that were ignored for a while and then included again.
+    fn reincludes_files(&self, scan: &ScanInfo) -> bool {
+        let case_sensitive = Os::HOST.is_case_sensitive();
+        let normalization = self.settings.unicode_normalization;
+        let latest: HashSet<_> = self
+            .latest_files()
+            .into_iter()
+            .map(|(k, _)| path_key(k, case_sensitive, normalization))
+            .collect();
+
+        scan.found_files.iter().any(|file| {
+            !file.ignored
+                && file.change() == ScanChange::Same
+                && !latest.contains(&path_key(&file.effective().render(), case_sensitive, normalization))
+        })
+    }
+
+    /// A differential backup without any files of its own normally isn't worth keeping,
+    /// but it still matters if the latest differential backup excluded some files
+    /// that would now be inherited from the full backup again.
+    /// That way, those files become restorable without storing their content another time.
+    fn reinstates_inherited_files(&self, backup: &Backup) -> bool {
+        let Backup::Differential(diff) = backup else {
+            return false;
         };
+        match self.mapping.latest_backup() {
+            Some((_, Some(latest))) => latest.files != diff.files,
+            _ => false,
+        }
+    }
+
+    /// Decide whether a planned differential backup should become a full one instead,
+    /// based on how much data the differential backups would hold in total.
+    fn plan_promotion(&self, diff: &DifferentialBackup) -> Option<PromotionReason> {
+        let threshold = self.retention.promote_full_when_diff_bytes_exceed?;
+        let full = self.mapping.backups.back()?;
 
-        backup.needed().then_some(backup)
+        let diff_bytes = full.children_bytes() + diff.changed_bytes();
+        let limit_bytes = threshold.resolve(full.bytes());
+
+        (diff_bytes > limit_bytes).then_some(PromotionReason::DiffBytesExceeded {
+            diff_bytes,
+            limit_bytes,
+        })
     }
 
     fn plan_backup_kind(&self) -> BackupKind {
@@ -971,7 +1632,11 @@ impl GameLayout {
         }
 
         #[cfg(target_os = "windows")]
-        {
+        if scan.registry_skipped {
+            // Keep the previous registry data so that it's still available,
+            // and so that change detection can resume if the registry is re-enabled.
+            registry.hash = self.latest_registry_hash();
+        } else {
             use crate::scan::registry::Hives;
             let hives = Hives::incorporated(&scan.found_registry_keys);
             if !hives.is_empty() {
@@ -981,10 +1646,14 @@ impl GameLayout {
 
         FullBackup {
             name: self.generate_backup_name(&BackupKind::Full, now, format),
+            id: Some(self.mapping.next_backup_id()),
             when: *now,
             os: Some(Os::HOST),
             comment: None,
             locked: false,
+            app_version: Some(VERSION.to_string()),
+            manifest_hash: None,
+            trigger: None,
             files,
             registry,
             children: VecDeque::new(),
@@ -1030,19 +1699,21 @@ impl GameLayout {
             }
         }
 
+        if scan.registry_skipped {
+            // Inherit the registry data from the full backup.
+            registry = None;
+        }
+
         // Individual saves' ScanChange are relative to the latest full + differential composite.
         // If the latest full backup has file 1 version 1, the latest diff has file 1 version 2,
         // and our new scan is back to version 1, then we don't want to duplicate the file content.
         if let Some((full, _)) = self.mapping.latest_backup() {
-            for (file, prior) in &full.files {
-                if let Some(current) = files.get(file) {
-                    if Some(&prior.hash) == current.as_ref().map(|x| &x.hash) {
-                        files.remove(file);
-                    }
-                } else {
-                    files.insert(file.clone(), None);
-                }
-            }
+            inherit_from_full_backup(
+                &mut files,
+                full,
+                Os::HOST.is_case_sensitive(),
+                self.settings.unicode_normalization,
+            );
             if let Some(current_registry) = &registry {
                 if &full.registry == current_registry {
                     registry = None;
@@ -1052,27 +1723,63 @@ impl GameLayout {
 
         DifferentialBackup {
             name: self.generate_backup_name(&BackupKind::Differential, now, format),
+            id: Some(self.mapping.next_backup_id()),
             when: *now,
             os: Some(Os::HOST),
             comment: None,
             locked: false,
+            app_version: Some(VERSION.to_string()),
+            manifest_hash: None,
+            trigger: None,
             files,
             registry,
         }
     }
 
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn latest_registry_hash(&self) -> Option<String> {
+        let (full, diff) = self.mapping.latest_backup()?;
+        match diff.and_then(|x| x.registry.as_ref()) {
+            Some(registry) => registry.hash.clone(),
+            None => full.registry.hash.clone(),
+        }
+    }
+
+    /// When registry access is disabled, this is the content from the latest backup.
+    #[cfg(target_os = "windows")]
+    fn registry_hives_for_backup(&self, scan: &ScanInfo) -> Option<crate::scan::registry::Hives> {
+        use crate::scan::registry::Hives;
+
+        if scan.registry_skipped {
+            self.registry_content(&BackupId::Latest)
+                .and_then(|content| Hives::deserialize(&content))
+        } else {
+            Some(Hives::incorporated(&scan.found_registry_keys))
+        }
+    }
+
     fn execute_backup_as_simple(&mut self, backup: &Backup, scan: &ScanInfo) -> BackupInfo {
         let mut backup_info = BackupInfo::default();
 
+        let snapshot = sqlite::Snapshot::take(&self.mapping.name, &scan.found_files);
+
         let mut relevant_files = vec![];
+        let mut stored = 0;
+        let mut locked = vec![];
         for file in &scan.found_files {
             if !backup.includes_file(file.effective().render()) {
                 log::debug!("[{}] skipped: {}", self.mapping.name, file.path.raw());
                 continue;
             }
+            if snapshot.unstable.contains(&file.path) {
+                backup_info
+                    .failed_files
+                    .insert(file.clone(), TRANSLATOR.failure_changed_while_copying());
+                continue;
+            }
 
             let target_file = self.mapping.game_file(&self.path, file.effective(), backup.name());
-            if file.path.same_content(&target_file) {
+            if snapshot.source(&file.path).same_content(&target_file) {
                 log::info!(
                     "[{}] already matches: {} -> {}",
                     self.mapping.name,
@@ -1080,29 +1787,40 @@ impl GameLayout {
                     target_file.raw()
                 );
                 relevant_files.push(target_file);
+                stored += 1;
                 continue;
             }
-            if let Err(_e) = file.path.copy_to_path(&self.mapping.name, &target_file) {
-                backup_info.failed_files.insert(file.clone());
-                continue;
+            let source = snapshot.source(&file.path);
+            match self.back_up_file_as_simple(file, source, &target_file, &mut backup_info, &mut relevant_files) {
+                Ok(()) => stored += 1,
+                Err(e) if is_locked(&e) => locked.push(((file, target_file), e)),
+                Err(e) => backup_info.fail_file(file, &e),
             }
-            log::info!(
-                "[{}] backed up: {} -> {}",
-                self.mapping.name,
-                file.path.raw(),
-                target_file.raw()
-            );
-            relevant_files.push(target_file);
+        }
+
+        let still_failed = retry_locked(
+            &self.mapping.name,
+            self.settings.retry_locked,
+            locked,
+            |(file, target_file)| {
+                let source = snapshot.source(&file.path);
+                self.back_up_file_as_simple(file, source, target_file, &mut backup_info, &mut relevant_files)?;
+                stored += 1;
+                Ok(())
+            },
+        );
+        for ((file, _), e) in still_failed {
+            backup_info.fail_file(file, &e);
         }
 
         #[cfg(target_os = "windows")]
         {
-            use crate::scan::registry::Hives;
             let target_registry_file = self.registry_file_in(backup.name());
 
             if backup.includes_registry() {
-                let hives = Hives::incorporated(&scan.found_registry_keys);
-                hives.save(&target_registry_file);
+                if let Some(hives) = self.registry_hives_for_backup(scan) {
+                    hives.save(&target_registry_file);
+                }
             } else {
                 let _ = target_registry_file.remove();
             }
@@ -1112,17 +1830,78 @@ impl GameLayout {
             self.remove_irrelevant_backup_files(backup.name(), &relevant_files);
         }
 
+        backup_info.check_deferred(stored);
         backup_info
     }
 
+    /// Copy one file for a simple backup.
+    /// An error is only returned if the file couldn't be copied at all,
+    /// so that the caller can try again later if it was locked.
+    fn back_up_file_as_simple(
+        &self,
+        file: &ScannedFile,
+        source: &StrictPath,
+        target_file: &StrictPath,
+        backup_info: &mut BackupInfo,
+        relevant_files: &mut Vec<StrictPath>,
+    ) -> std::io::Result<()> {
+        let mut before = stamp(source);
+        source.copy_to_path(&self.mapping.name, target_file)?;
+        let mut after = stamp(source);
+        if before != after && self.settings.retry_changed_files {
+            log::warn!(
+                "[{}] file changed while copying, so trying again: {}",
+                self.mapping.name,
+                file.path.raw()
+            );
+            before = after;
+            if let Err(e) = source.copy_to_path(&self.mapping.name, target_file) {
+                backup_info.failed_files.insert(file.clone(), e.to_string());
+                return Ok(());
+            }
+            after = stamp(source);
+        }
+        log::info!(
+            "[{}] backed up: {} -> {}",
+            self.mapping.name,
+            file.path.raw(),
+            target_file.raw()
+        );
+        let altered = find_altered_name(target_file, self.settings.unicode_normalization);
+        if may_have_changed(file.size, before, after) {
+            let stored = altered.as_ref().unwrap_or(target_file);
+            self.check_stored_content(
+                file,
+                IndividualMappingFile {
+                    hash: stored.sha1(),
+                    size: stored.size(),
+                },
+                backup_info,
+            );
+        }
+        if let Some(stored) = altered {
+            log::warn!(
+                "[{}] backup location altered file name: {} -> {}",
+                self.mapping.name,
+                target_file.raw(),
+                stored.raw()
+            );
+            backup_info.altered_names.insert(file.clone(), stored.clone());
+            relevant_files.push(stored);
+        }
+        relevant_files.push(target_file.clone());
+        Ok(())
+    }
+
     fn execute_backup_as_zip(&mut self, backup: &Backup, scan: &ScanInfo, format: &BackupFormats) -> BackupInfo {
         let mut backup_info = BackupInfo::default();
 
-        let fail_file =
-            |file: &ScannedFile, backup_info: &mut BackupInfo| backup_info.failed_files.insert(file.clone());
-        let fail_all = |backup_info: &mut BackupInfo| {
+        let fail_file = |file: &ScannedFile, reason: String, backup_info: &mut BackupInfo| {
+            backup_info.failed_files.insert(file.clone(), reason)
+        };
+        let fail_all = |reason: String, backup_info: &mut BackupInfo| {
             for file in &scan.found_files {
-                backup_info.failed_files.insert(file.clone());
+                backup_info.failed_files.insert(file.clone(), reason.clone());
             }
         };
 
@@ -1135,11 +1914,12 @@ impl GameLayout {
                     self.mapping.name,
                     archive_path.raw()
                 );
-                fail_all(&mut backup_info);
+                fail_all(e.to_string(), &mut backup_info);
                 return backup_info;
             }
         };
-        let mut zip = zip::ZipWriter::new(archive_file);
+        // Games with many small files would otherwise make a syscall for every little write.
+        let mut zip = zip::ZipWriter::new(std::io::BufWriter::with_capacity(ZIP_WRITE_BUFFER, archive_file));
         let options = zip::write::FileOptions::default()
             .compression_method(match format.zip.compression {
                 ZipCompression::None => zip::CompressionMethod::Stored,
@@ -1150,11 +1930,20 @@ impl GameLayout {
             .compression_level(format.level())
             .large_file(true);
 
-        'item: for file in &scan.found_files {
+        let snapshot = sqlite::Snapshot::take(&self.mapping.name, &scan.found_files);
+        let mut buffer = vec![0; ZIP_READ_BUFFER];
+        let mut stored = 0;
+        let mut locked = vec![];
+
+        for file in &scan.found_files {
             if !backup.includes_file(file.effective().render()) {
                 log::debug!("[{}] skipped: {}", self.mapping.name, file.path.raw());
                 continue;
             }
+            if snapshot.unstable.contains(&file.path) {
+                fail_file(file, TRANSLATOR.failure_changed_while_copying(), &mut backup_info);
+                continue;
+            }
 
             let target_file_id = self.mapping.game_file_for_zip(file.effective());
 
@@ -1167,7 +1956,7 @@ impl GameLayout {
                         file.path.raw(),
                         &target_file_id
                     );
-                    fail_file(file, &mut backup_info);
+                    fail_file(file, e.to_string(), &mut backup_info);
                     continue;
                 }
             };
@@ -1185,85 +1974,199 @@ impl GameLayout {
                 None => options.last_modified_time(mtime),
             };
 
-            if let Err(e) = zip.start_file(&target_file_id, local_options) {
-                log::error!(
-                    "[{}] unable to start zip file record: {} -> {} | {e}",
-                    self.mapping.name,
-                    file.path.raw(),
-                    &target_file_id
-                );
-                fail_file(file, &mut backup_info);
-                continue;
-            }
-
-            use std::io::Read;
-            let handle = match std::fs::File::open(file.path.interpret()) {
+            let source = snapshot.source(&file.path);
+            let before = stamp(source);
+            let handle = match std::fs::File::open(source.interpret()) {
                 Ok(x) => x,
+                Err(e) if is_locked(&e) => {
+                    locked.push(((file, target_file_id, local_options), e));
+                    continue;
+                }
                 Err(e) => {
                     log::error!(
                         "[{}] unable to open source: {} | {e}",
                         self.mapping.name,
                         file.path.raw()
                     );
-                    fail_file(file, &mut backup_info);
+                    backup_info.fail_file(file, &e);
                     continue;
                 }
             };
-            let mut reader = std::io::BufReader::new(handle);
-            let mut buffer = [0; 1024];
+            if self.write_zip_entry(
+                &mut zip,
+                &mut buffer,
+                file,
+                &target_file_id,
+                local_options,
+                source,
+                handle,
+                before,
+                &mut backup_info,
+            ) {
+                stored += 1;
+            }
+        }
 
-            loop {
-                let read = match reader.read(&mut buffer[..]) {
-                    Ok(x) => x,
-                    Err(e) => {
-                        log::error!(
-                            "[{}] unable to read source: {} | {e}",
-                            self.mapping.name,
-                            file.path.raw()
-                        );
-                        fail_file(file, &mut backup_info);
-                        continue 'item;
+        let still_failed = retry_locked(
+            &self.mapping.name,
+            self.settings.retry_locked,
+            locked,
+            |(file, target_file_id, local_options)| {
+                let source = snapshot.source(&file.path);
+                let before = stamp(source);
+                let handle = std::fs::File::open(source.interpret())?;
+                if self.write_zip_entry(
+                    &mut zip,
+                    &mut buffer,
+                    file,
+                    target_file_id,
+                    *local_options,
+                    source,
+                    handle,
+                    before,
+                    &mut backup_info,
+                ) {
+                    stored += 1;
+                }
+                Ok(())
+            },
+        );
+        for ((file, _, _), e) in still_failed {
+            log::error!(
+                "[{}] unable to open source: {} | {e}",
+                self.mapping.name,
+                file.path.raw()
+            );
+            backup_info.fail_file(file, &e);
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            if backup.includes_registry() {
+                if let Some(hives) = self.registry_hives_for_backup(scan) {
+                    if zip.start_file("registry.yaml", options).is_ok() {
+                        let _ = zip.write_all(hives.serialize().as_bytes());
                     }
-                };
-                if read == 0 {
-                    log::info!(
-                        "[{}] backed up: {} -> {}",
-                        self.mapping.name,
-                        file.path.raw(),
-                        &target_file_id
-                    );
-                    break;
                 }
-                if let Err(e) = zip.write_all(&buffer[0..read]) {
+            }
+        }
+
+        match zip.finish() {
+            Ok(mut writer) => {
+                if let Err(e) = writer.flush() {
                     log::error!(
-                        "[{}] unable to write target: {} -> {} | {e}",
+                        "[{}] unable to finish zip file: {} | {e}",
                         self.mapping.name,
-                        file.path.raw(),
-                        &target_file_id
+                        archive_path.raw()
                     );
-                    fail_file(file, &mut backup_info);
-                    continue 'item;
+                    fail_all(e.to_string(), &mut backup_info);
                 }
             }
+            Err(e) => fail_all(e.to_string(), &mut backup_info),
         }
 
-        #[cfg(target_os = "windows")]
-        {
-            use crate::scan::registry::Hives;
+        backup_info.check_deferred(stored);
+        backup_info
+    }
 
-            if backup.includes_registry() {
-                let hives = Hives::incorporated(&scan.found_registry_keys);
-                if zip.start_file("registry.yaml", options).is_ok() {
-                    let _ = zip.write_all(hives.serialize().as_bytes());
+    /// Add one file to a zip backup, returning whether it was stored.
+    /// The source is opened by the caller, so that it can try again later if the file was locked.
+    fn write_zip_entry(
+        &self,
+        zip: &mut zip::ZipWriter<std::io::BufWriter<std::fs::File>>,
+        buffer: &mut [u8],
+        file: &ScannedFile,
+        target_file_id: &str,
+        options: zip::write::FileOptions,
+        source: &StrictPath,
+        handle: std::fs::File,
+        before: Option<(u64, std::time::SystemTime)>,
+        backup_info: &mut BackupInfo,
+    ) -> bool {
+        use sha1::Digest;
+        use std::io::Read;
+
+        let fail_file = |reason: String, backup_info: &mut BackupInfo| {
+            backup_info.failed_files.insert(file.clone(), reason);
+        };
+
+        if let Err(e) = zip.start_file(target_file_id, options) {
+            log::error!(
+                "[{}] unable to start zip file record: {} -> {} | {e}",
+                self.mapping.name,
+                file.path.raw(),
+                target_file_id
+            );
+            fail_file(e.to_string(), backup_info);
+            return false;
+        }
+
+        let mut reader = std::io::BufReader::new(handle);
+        let mut hasher = sha1::Sha1::new();
+        let mut written = 0;
+
+        loop {
+            let read = match reader.read(buffer) {
+                Ok(x) => x,
+                Err(e) => {
+                    log::error!(
+                        "[{}] unable to read source: {} | {e}",
+                        self.mapping.name,
+                        file.path.raw()
+                    );
+                    fail_file(e.to_string(), backup_info);
+                    return false;
                 }
+            };
+            if read == 0 {
+                log::info!(
+                    "[{}] backed up: {} -> {}",
+                    self.mapping.name,
+                    file.path.raw(),
+                    target_file_id
+                );
+                break;
+            }
+            if let Err(e) = zip.write_all(&buffer[0..read]) {
+                log::error!(
+                    "[{}] unable to write target: {} -> {} | {e}",
+                    self.mapping.name,
+                    file.path.raw(),
+                    target_file_id
+                );
+                fail_file(e.to_string(), backup_info);
+                return false;
             }
+            hasher.update(&buffer[0..read]);
+            written += read as u64;
         }
 
-        if zip.finish().is_err() {
-            fail_all(&mut backup_info);
+        // Zip entries can't be rewritten, so there's no retry here,
+        // but we already hashed exactly what we stored.
+        if may_have_changed(file.size, before, stamp(source)) {
+            self.check_stored_content(
+                file,
+                IndividualMappingFile {
+                    hash: format!("{:x}", hasher.finalize()),
+                    size: written,
+                },
+                backup_info,
+            );
         }
+        true
+    }
 
-        backup_info
+    /// Flag a file if what we stored doesn't match what we found during the scan.
+    fn check_stored_content(&self, file: &ScannedFile, stored: IndividualMappingFile, backup_info: &mut BackupInfo) {
+        if stored.hash == file.hash {
+            return;
+        }
+        log::warn!(
+            "[{}] file changed during backup, so the backup may be inconsistent: {}",
+            self.mapping.name,
+            file.path.raw()
+        );
+        backup_info.changed_during_backup.insert(file.clone(), stored);
     }
 
     fn insert_backup(&mut self, backup: Backup) {
@@ -1277,6 +2180,7 @@ impl GameLayout {
                 }
             }
         }
+        self.mapping.update_last_backup_id();
     }
 
     fn forget_excess_backups(&mut self) {
@@ -1400,11 +2304,21 @@ impl GameLayout {
         scan: &ScanInfo,
         now: &chrono::DateTime<chrono::Utc>,
         format: &BackupFormats,
+        label: &BackupLabel,
     ) -> BackupInfo {
         if !scan.found_anything() {
             log::trace!("[{}] nothing to back up", &scan.game_name);
             return BackupInfo::default();
         }
+        if !scan.io_timeouts.is_empty() {
+            // A backup without the unresponsive locations would look like those saves were deleted.
+            log::warn!(
+                "[{}] skipping backup because some locations timed out: {:?}",
+                &scan.game_name,
+                &scan.io_timeouts
+            );
+            return BackupInfo::default();
+        }
 
         log::trace!("[{}] preparing for backup", &scan.game_name);
         if let Err(e) = prepare_backup_target(&self.path) {
@@ -1413,7 +2327,7 @@ impl GameLayout {
                 scan.game_name,
                 self.path.raw()
             );
-            return BackupInfo::total_failure(scan);
+            return BackupInfo::total_failure(scan, &TRANSLATOR.handle_error(&e));
         }
 
         self.migrate_legacy_backup();
@@ -1422,16 +2336,33 @@ impl GameLayout {
                 log::info!("[{}] no need for new backup", &scan.game_name);
                 BackupInfo::default()
             }
-            Some(mut backup) => {
+            Some((mut backup, promoted_because)) => {
+                let reinstating = !backup.needed();
                 log::info!(
                     "[{}] creating a {:?} backup: {}",
                     &scan.game_name,
                     backup.kind(),
                     backup.name()
                 );
-                let backup_info = self.execute_backup(&backup, scan, format);
+                if let Some(reason) = &promoted_because {
+                    log::info!("[{}] promoted to a full backup: {:?}", &scan.game_name, reason);
+                }
+                let mut backup_info = self.execute_backup(&backup, scan, format);
+                backup_info.kind = Some(backup.kind());
+                backup_info.promoted_because = promoted_because;
                 backup.prune_failures(&backup_info);
-                if backup.needed() {
+                backup.update_changed_files(&backup_info);
+                if backup.needed() || reinstating {
+                    // Apply this before pruning so that a locked backup is exempt from retention right away.
+                    if let Some(comment) = &label.comment {
+                        backup.set_comment(comment.clone());
+                    }
+                    if label.locked {
+                        backup.set_locked(true);
+                    }
+                    backup.set_trigger(label.trigger.clone());
+                    backup.set_manifest_hash(label.manifest_hash.clone());
+                    backup_info.label = label.clone();
                     self.insert_backup(backup.clone());
                     self.forget_excess_backups();
                     self.save();
@@ -1453,6 +2384,29 @@ impl GameLayout {
         available_backups
     }
 
+    /// One line per backup, for pointing out the valid choices when a backup ID isn't found.
+    pub fn describe_backups(&self) -> Vec<String> {
+        self.restorable_backups_flattened()
+            .iter()
+            .map(|backup| match backup.short_id() {
+                Some(id) => format!("[{id}] \"{}\" ({})", backup.name(), backup.label()),
+                None => format!("\"{}\" ({})", backup.name(), backup.label()),
+            })
+            .collect()
+    }
+
+    /// Space that a backup takes up on disk.
+    /// Zip archives are compressed, so we check their actual size,
+    /// but otherwise we rely on the mapping rather than walking the backup's folder.
+    pub fn backup_bytes(&self, backup: &Backup) -> u64 {
+        if backup.format() == BackupFormat::Zip {
+            if let Ok(metadata) = self.path.joined(backup.name()).metadata() {
+                return metadata.len();
+            }
+        }
+        backup.bytes()
+    }
+
     pub fn has_backups(&self) -> bool {
         !self.mapping.backups.is_empty()
     }
@@ -1464,6 +2418,11 @@ impl GameLayout {
         redirects: &[RedirectConfig],
         toggled_paths: &ToggledPaths,
         #[allow(unused)] toggled_registry: &ToggledRegistry,
+        remap_user_profile: bool,
+        prefer_account: Option<&str>,
+        prefer_wine_user: PreferWineUser,
+        scan_registry: bool,
+        os_constraints: &OsConstraints,
     ) -> ScanInfo {
         log::trace!("[{name}] beginning scan for restore");
 
@@ -1473,18 +2432,75 @@ impl GameLayout {
         #[allow(unused_mut)]
         let mut available_backups = vec![];
         let mut backup = None;
+        let mut ambiguous_targets = BTreeMap::new();
+        let mut os_constrained = BTreeSet::new();
+        let mut local_states = BTreeMap::new();
 
         let id = self.verify_id(id);
 
         if self.path.is_dir() {
             self.migrate_legacy_backup();
-            found_files = self.restorable_files(&id, true, redirects, toggled_paths);
+
+            let mut redirects = redirects.to_vec();
+            if remap_user_profile {
+                if let Some(redirect) = self.user_profile_redirect(&id) {
+                    log::info!(
+                        "[{name}] remapping user profile: {} -> {}",
+                        redirect.source.raw(),
+                        redirect.target.raw()
+                    );
+                    redirects.push(redirect);
+                }
+            }
+
+            found_files = self.restorable_files(&id, true, &redirects, toggled_paths);
+
+            let accounts = steam_account_targets(found_files.iter().map(|x| x.effective()), prefer_account);
+            if !accounts.redirects.is_empty() {
+                redirects.extend(accounts.redirects);
+                found_files = self.restorable_files(&id, true, &redirects, toggled_paths);
+            }
+            ambiguous_targets = accounts.ambiguous;
+
+            let wine_users = wine_user_redirects(found_files.iter().map(|x| x.effective()), prefer_wine_user);
+            if !wine_users.is_empty() {
+                redirects.extend(wine_users);
+                found_files = self.restorable_files(&id, true, &redirects, toggled_paths);
+            }
+
+            os_constrained = found_files
+                .iter()
+                .filter(|x| x.redirected.is_none() && os_constraints.is_foreign(x.original_path()))
+                .map(|x| x.original_path().clone())
+                .collect();
+            if !os_constrained.is_empty() {
+                log::info!(
+                    "[{name}] skipping {} files that belong to a different OS",
+                    os_constrained.len()
+                );
+            }
+
+            local_states = self.local_states(&found_files);
+
             available_backups = self.restorable_backups_flattened();
             backup = self.find_by_id_flattened(&id);
         }
 
+        if let Some(version) = backup.as_ref().and_then(|x| x.app_version()) {
+            if is_newer_version(version) {
+                log::warn!(
+                    "[{name}] backup was made by a newer version of Ludusavi ({version}), so it may not restore correctly"
+                );
+            }
+        }
+
+        let registry_skipped = !scan_registry && cfg!(target_os = "windows") && self.registry_content(&id).is_some();
+        if registry_skipped {
+            log::info!("[{name}] skipping registry because it is disabled");
+        }
+
         #[cfg(target_os = "windows")]
-        {
+        if scan_registry {
             use crate::scan::{registry, RegistryItem, ScannedRegistryValue, ScannedRegistryValues};
 
             if let Some(registry_content) = self.registry_content(&id) {
@@ -1543,21 +2559,114 @@ impl GameLayout {
             found_registry_keys,
             available_backups,
             backup,
+            registry_skipped,
+            unresolved_base_dirs: Default::default(),
+            io_timeouts: Default::default(),
+            ambiguous_targets,
+            os_constrained,
+            local_states,
+            kept_newer_local: Default::default(),
+            source: None,
+            roots: Default::default(),
+        }
+    }
+
+    /// Compare each file with the local file that restoring it would overwrite.
+    fn local_states(&self, files: &HashSet<ScannedFile>) -> BTreeMap<StrictPath, LocalState> {
+        let mut archives = HashMap::new();
+        let mut states = BTreeMap::new();
+
+        for file in files {
+            let target = file.effective();
+            let (local_mtime, backup_mtime) = if file.change == ScanChange::Different {
+                (target.get_mtime().ok(), self.backed_up_mtime(file, &mut archives))
+            } else {
+                (None, None)
+            };
+            if let Some(state) = LocalState::evaluate(file.change, local_mtime, backup_mtime) {
+                states.insert(target.clone(), state);
+            }
+        }
+
+        states
+    }
+
+    /// Simple backups keep the original modification time,
+    /// and zip backups store it for each entry.
+    fn backed_up_mtime(
+        &self,
+        file: &ScannedFile,
+        archives: &mut HashMap<StrictPath, Option<zip::ZipArchive<std::fs::File>>>,
+    ) -> Option<std::time::SystemTime> {
+        match &file.container {
+            None => file.path.get_mtime().ok(),
+            Some(container) => {
+                let archive = archives
+                    .entry(container.clone())
+                    .or_insert_with(|| {
+                        std::fs::File::open(container.interpret())
+                            .ok()
+                            .and_then(|handle| zip::ZipArchive::new(handle).ok())
+                    })
+                    .as_mut()?;
+                let entry = zip_safety::open_by_name(archive, &file.path.raw(), self.settings.zip_limits).ok()?;
+                system_time_from_zip(entry.last_modified())
+            }
         }
     }
 
-    pub fn restore(&self, scan: &ScanInfo, #[allow(unused)] toggled: &ToggledRegistry) -> BackupInfo {
+    /// If the backup was made under another user's profile folder,
+    /// this redirects it to the current user's profile folder.
+    pub fn user_profile_redirect(&self, id: &BackupId) -> Option<RedirectConfig> {
+        let home = StrictPath::from_std_path_buf(&dirs::home_dir()?);
+        let files = self.restorable_files(id, false, &[], &Default::default());
+        user_profile_redirect(files.iter().filter_map(|x| x.original_path.as_ref()), &home)
+    }
+
+    pub fn restore(&self, scan: &ScanInfo, toggled: &ToggledRegistry) -> BackupInfo {
+        self.restore_into_hive(scan, toggled, None)
+    }
+
+    /// Like `restore`, but `HKEY_CURRENT_USER` registry data is written into the hive file
+    /// loaded under this subkey of `HKEY_USERS` (see `registry::OfflineHive`).
+    pub fn restore_into_hive(
+        &self,
+        scan: &ScanInfo,
+        #[allow(unused)] toggled: &ToggledRegistry,
+        #[allow(unused)] offline_hive: Option<&str>,
+    ) -> BackupInfo {
         log::trace!("[{}] beginning restore", &scan.game_name);
 
-        let mut failed_files = HashSet::new();
-        let failed_registry = HashSet::new();
+        let mut failed_files = HashMap::new();
+        let failed_registry = HashMap::new();
 
         let mut containers: HashMap<StrictPath, zip::ZipArchive<std::fs::File>> = HashMap::new();
-        let mut failed_containers: HashSet<StrictPath> = HashSet::new();
+        let mut failed_containers: HashMap<StrictPath, String> = HashMap::new();
+        let mut unsafe_containers: HashMap<StrictPath, UnsafeZipEntry> = HashMap::new();
+        let mut unsafe_zip_entries = HashMap::new();
 
         for file in &scan.found_files {
             let target = file.effective();
 
+            if let Some(candidates) = scan.ambiguous_target(file) {
+                log::warn!(
+                    "[{}] skipping file with ambiguous target: {} -> {:?}",
+                    self.mapping.name,
+                    target.raw(),
+                    candidates
+                );
+                continue;
+            }
+
+            if scan.os_constrained(file) {
+                log::warn!(
+                    "[{}] skipping file that belongs to a different OS: {}",
+                    self.mapping.name,
+                    target.raw()
+                );
+                continue;
+            }
+
             if !file.change().is_changed() || file.ignored {
                 log::info!(
                     "[{}] skipping file; change={:?}, ignored={}: {} -> {}",
@@ -1571,7 +2680,7 @@ impl GameLayout {
             }
 
             if let Some(container) = file.container.as_ref() {
-                if failed_containers.contains(container) {
+                if let Some(reason) = failed_containers.get(container) {
                     log::warn!(
                         "[{}] skipping file because container had failed to load: {} -> {} -> {}",
                         self.mapping.name,
@@ -1579,7 +2688,10 @@ impl GameLayout {
                         file.path.raw(),
                         target.raw(),
                     );
-                    failed_files.insert(file.clone());
+                    failed_files.insert(file.clone(), reason.clone());
+                    if let Some(problem) = unsafe_containers.get(container) {
+                        unsafe_zip_entries.insert(file.clone(), problem.clone());
+                    }
                     continue;
                 }
 
@@ -1593,12 +2705,12 @@ impl GameLayout {
                                 &self.mapping.name,
                                 container.raw()
                             );
-                            failed_containers.insert(container.clone());
-                            failed_files.insert(file.clone());
+                            failed_containers.insert(container.clone(), e.to_string());
+                            failed_files.insert(file.clone(), e.to_string());
                             continue;
                         }
                     };
-                    let archive = match zip::ZipArchive::new(handle) {
+                    let mut archive = match zip::ZipArchive::new(handle) {
                         Ok(archive) => archive,
                         Err(e) => {
                             log::error!(
@@ -1606,11 +2718,23 @@ impl GameLayout {
                                 &self.mapping.name,
                                 container.raw()
                             );
-                            failed_containers.insert(container.clone());
-                            failed_files.insert(file.clone());
+                            failed_containers.insert(container.clone(), e.to_string());
+                            failed_files.insert(file.clone(), e.to_string());
                             continue;
                         }
                     };
+                    if let Err(problem) = zip_safety::check_archive(&mut archive, self.settings.zip_limits) {
+                        log::error!(
+                            "[{}] rejecting zip archive because of an unsafe entry: {} | {problem}",
+                            &self.mapping.name,
+                            container.raw()
+                        );
+                        failed_containers.insert(container.clone(), problem.to_string());
+                        failed_files.insert(file.clone(), problem.to_string());
+                        unsafe_zip_entries.insert(file.clone(), problem.clone());
+                        unsafe_containers.insert(container.clone(), problem);
+                        continue;
+                    }
                     log::debug!("[{}] loaded zip archive: {:?}", &self.mapping.name, container.raw());
                     containers.insert(container.clone(), archive);
                 }
@@ -1626,6 +2750,21 @@ impl GameLayout {
                 }
             };
 
+            // Some problems only show up while reading (e.g., an entry that's larger than declared).
+            // The archive was probably tampered with, so don't trust anything else in it.
+            if let Some(problem) = outcome.as_ref().err().and_then(|e| e.downcast_ref::<UnsafeZipEntry>()) {
+                if let Some(container) = &file.container {
+                    log::error!(
+                        "[{}] rejecting zip archive because of an unsafe entry: {} | {problem}",
+                        self.mapping.name,
+                        container.raw()
+                    );
+                    failed_containers.insert(container.clone(), problem.to_string());
+                    unsafe_containers.insert(container.clone(), problem.clone());
+                }
+                unsafe_zip_entries.insert(file.clone(), problem.clone());
+            }
+
             match outcome {
                 Ok(_) => {
                     log::info!(
@@ -1642,7 +2781,7 @@ impl GameLayout {
                         file.path.raw(),
                         target.raw()
                     );
-                    failed_files.insert(file.clone());
+                    failed_files.insert(file.clone(), e.to_string());
                 }
             }
         }
@@ -1651,11 +2790,18 @@ impl GameLayout {
         {
             use crate::scan::registry::Hives;
 
-            if let Some(backup) = scan.backup.as_ref() {
+            // When only some files were selected, the registry keys are all ignored.
+            let registry_ignored =
+                !scan.found_registry_keys.is_empty() && scan.found_registry_keys.iter().all(|x| x.ignored);
+            if let Some(backup) = scan
+                .backup
+                .as_ref()
+                .filter(|_| !scan.registry_skipped && !registry_ignored)
+            {
                 if let Some(registry_content) = self.registry_content(&backup.id()) {
                     if let Some(hives) = Hives::deserialize(&registry_content) {
                         // TODO: Track failed keys.
-                        let _ = hives.restore(&scan.game_name, toggled);
+                        let _ = hives.restore(&scan.game_name, toggled, offline_hive);
                     }
                 }
             }
@@ -1666,6 +2812,8 @@ impl GameLayout {
         BackupInfo {
             failed_files,
             failed_registry,
+            unsafe_zip_entries,
+            ..Default::default()
         }
     }
 
@@ -1693,6 +2841,9 @@ impl GameLayout {
             target.raw()
         );
 
+        // Check the entry before touching the target, in case the archive is malicious.
+        let mut source_file = zip_safety::open_by_name(archive, &file.path.raw(), self.settings.zip_limits)?;
+
         if let Err(e) = target.create_parent_dir() {
             log::error!(
                 "[{}] unable to create parent directories: {} | {e}",
@@ -1721,15 +2872,14 @@ impl GameLayout {
                 return Err(Box::new(e));
             }
         };
-        let mut source_file = archive.by_name(&file.path.raw())?;
-        if let Err(e) = std::io::copy(&mut source_file, &mut target_handle) {
+        if let Err(e) = zip_safety::copy_entry(&mut source_file, &mut target_handle) {
             log::warn!(
                 "[{}] failed to copy to target: {} -> {} | {e}",
                 self.mapping.name,
                 file.path.raw(),
                 target.raw()
             );
-            return Err(Box::new(e));
+            return Err(e);
         }
 
         let mtime = source_file.last_modified();
@@ -1833,146 +2983,696 @@ impl GameLayout {
         }
     }
 
-    /// Checks the latest backup (full + diff) only.
-    /// Returns whether backup is valid.
-    pub fn validate(&self, backup_id: BackupId) -> bool {
-        if let Some((backup, diff)) = self.find_by_id(&backup_id) {
-            match backup.format() {
-                BackupFormat::Simple => {
-                    for file in backup.files.keys() {
-                        let original_path = StrictPath::new(file.to_string());
-                        let stored = self
-                            .mapping
-                            .game_file_immutable(&self.path, &original_path, &backup.name);
-                        if !stored.is_file() {
-                            #[cfg(test)]
-                            eprintln!("can't find {}", stored.render());
-                            return false;
-                        }
-                    }
+    /// Files from the mapping that can't be found in the backup.
+    /// Returns `None` if the backup's folder or archive is missing altogether.
+    fn find_missing_backup_files<'a>(
+        &self,
+        backup: &str,
+        format: BackupFormat,
+        files: impl Iterator<Item = &'a String>,
+    ) -> Option<Vec<String>> {
+        let mut missing = vec![];
+
+        match format {
+            BackupFormat::Simple => {
+                let files: Vec<_> = files.collect();
+                if !files.is_empty() && !self.path.joined(backup).is_dir() {
+                    return None;
                 }
-                BackupFormat::Zip => {
-                    let Ok(handle) = std::fs::File::open(self.path.joined(&backup.name).interpret()) else {
-                        return false;
-                    };
-                    let Ok(mut archive) = zip::ZipArchive::new(handle) else {
-                        return false;
-                    };
 
-                    for file in backup.files.keys() {
-                        let original_path = StrictPath::new(file.to_string());
-                        let stored = self.mapping.game_file_for_zip_immutable(&original_path);
-                        if archive.by_name(&stored).is_err() {
-                            #[cfg(test)]
-                            eprintln!("can't find {}", stored);
-                            return false;
-                        }
+                for file in files {
+                    let original_path = StrictPath::new(file.to_string());
+                    let stored = self.mapping.game_file_immutable(&self.path, &original_path, backup);
+                    if !stored.is_file() {
+                        missing.push(file.clone());
                     }
                 }
             }
+            BackupFormat::Zip => {
+                let handle = std::fs::File::open(self.path.joined(backup).interpret()).ok()?;
+                let mut archive = zip::ZipArchive::new(handle).ok()?;
 
-            if let Some(backup) = diff {
-                match backup.format() {
-                    BackupFormat::Simple => {
-                        for (file, data) in &backup.files {
-                            if data.is_none() {
-                                // File is deliberately omitted.
-                                continue;
-                            }
-
-                            let original_path = StrictPath::new(file.to_string());
-                            let stored = self
-                                .mapping
-                                .game_file_immutable(&self.path, &original_path, &backup.name);
-                            if !stored.is_file() {
-                                #[cfg(test)]
-                                eprintln!("can't find {}", stored.render());
-                                return false;
-                            }
-                        }
-                    }
-                    BackupFormat::Zip => {
-                        let Ok(handle) = std::fs::File::open(self.path.joined(&backup.name).interpret()) else {
-                            return false;
-                        };
-                        let Ok(mut archive) = zip::ZipArchive::new(handle) else {
-                            return false;
-                        };
-
-                        for (file, data) in &backup.files {
-                            if data.is_none() {
-                                // File is deliberately omitted.
-                                continue;
-                            }
-
-                            let original_path = StrictPath::new(file.to_string());
-                            let stored = self.mapping.game_file_for_zip_immutable(&original_path);
-                            if archive.by_name(&stored).is_err() {
-                                #[cfg(test)]
-                                eprintln!("can't find {}", stored);
-                                return false;
-                            }
+                for file in files {
+                    let original_path = StrictPath::new(file.to_string());
+                    let stored = self.mapping.game_file_for_zip_immutable(&original_path);
+                    if let Err(e) = zip_safety::open_by_name(&mut archive, &stored, self.settings.zip_limits) {
+                        if let Some(problem) = e.downcast_ref::<UnsafeZipEntry>() {
+                            log::error!("[{}] unsafe entry in backup {backup} | {problem}", self.mapping.name);
                         }
+                        missing.push(file.clone());
                     }
                 }
             }
         }
 
-        true
+        Some(missing)
     }
-}
-
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub enum BackupKind {
-    #[default]
-    Full,
-    Differential,
-}
 
-#[derive(Clone, Debug, Default)]
-pub struct BackupLayout {
-    pub base: StrictPath,
-    games: HashMap<String, StrictPath>,
-    games_lowercase: HashMap<String, StrictPath>,
-    retention: Retention,
-}
+    /// Checks the latest backup (full + diff) only.
+    /// Returns whether backup is valid.
+    pub fn validate(&self, backup_id: BackupId) -> bool {
+        if let Some((backup, diff)) = self.find_by_id(&backup_id) {
+            let full_missing = self.find_missing_backup_files(&backup.name, backup.format(), backup.files.keys());
+            if full_missing.map(|x| !x.is_empty()).unwrap_or(true) {
+                return false;
+            }
 
-impl BackupLayout {
-    pub fn new(base: StrictPath, retention: Retention) -> Self {
-        let games = Self::load(&base);
-        let games_lowercase = games.iter().map(|(k, v)| (k.to_lowercase(), v.clone())).collect();
-        Self {
-            base,
-            games,
-            games_lowercase,
-            retention,
+            if let Some(backup) = diff {
+                // Files that are deliberately omitted have no data.
+                let diff_missing = self.find_missing_backup_files(
+                    &backup.name,
+                    backup.format(),
+                    backup
+                        .files
+                        .iter()
+                        .filter(|(_, data)| data.is_some())
+                        .map(|(file, _)| file),
+                );
+                if diff_missing.map(|x| !x.is_empty()).unwrap_or(true) {
+                    return false;
+                }
+            }
         }
+
+        true
     }
 
-    pub fn load(base: &StrictPath) -> HashMap<String, StrictPath> {
-        let mut overall = HashMap::new();
+    /// Cross-checks every backup in the mapping against what's on disk.
+    pub fn check_consistency(&self) -> Vec<Discrepancy> {
+        let mut discrepancies = vec![];
+        let mut known_names = vec!["mapping.yaml".to_string()];
 
-        for game_dir in walkdir::WalkDir::new(base.interpret())
-            .max_depth(1)
-            .follow_links(false)
-            .into_iter()
-            .skip(1) // the base path itself
-            .filter_map(crate::scan::filter_map_walkdir)
-            .filter(|x| x.file_type().is_dir())
-        {
-            let game_dir = StrictPath::from(&game_dir);
-            let mapping_file = game_dir.joined("mapping.yaml");
-            if mapping_file.is_file() {
-                match IndividualMapping::load(&mapping_file) {
-                    Ok(mapping) => {
-                        overall.insert(mapping.name.clone(), game_dir);
+        let mut check = |name: &str, format: BackupFormat, files: Vec<&String>| {
+            known_names.push(name.to_string());
+
+            match self.find_missing_backup_files(name, format, files.iter().copied()) {
+                None => {
+                    discrepancies.push(Discrepancy::MissingBackup {
+                        backup: name.to_string(),
+                    });
+                }
+                Some(missing) => {
+                    for file in missing {
+                        discrepancies.push(Discrepancy::MissingFile {
+                            backup: name.to_string(),
+                            file,
+                        });
                     }
-                    Err(e) => {
-                        log::warn!("Ignoring unloadable mapping: {} | {:?}", mapping_file.render(), e);
+
+                    if format == BackupFormat::Simple {
+                        let relevant: Vec<_> = files
+                            .iter()
+                            .map(|file| {
+                                self.mapping
+                                    .game_file_immutable(&self.path, &StrictPath::new(file.to_string()), name)
+                            })
+                            .collect();
+                        for path in self.find_irrelevant_backup_files(name, &relevant) {
+                            discrepancies.push(Discrepancy::UnknownFile { path: path.render() });
+                        }
                     }
                 }
             }
-        }
+        };
+
+        for full in &self.mapping.backups {
+            check(&full.name, full.format(), full.files.keys().collect());
+            for diff in &full.children {
+                check(
+                    &diff.name,
+                    diff.format(),
+                    diff.files
+                        .iter()
+                        .filter(|(_, data)| data.is_some())
+                        .map(|(file, _)| file)
+                        .collect(),
+                );
+            }
+        }
+
+        // Legacy backups are stored directly in the game folder.
+        let has_legacy = known_names.iter().any(|x| x == ".");
+        for entry in std::fs::read_dir(self.path.interpret()).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if known_names.contains(&name) || (has_legacy && (name.starts_with("drive-") || name == "registry.yaml")) {
+                continue;
+            }
+            discrepancies.push(Discrepancy::UnknownFile {
+                path: StrictPath::from(entry.path()).render(),
+            });
+        }
+
+        discrepancies.sort();
+        discrepancies.dedup();
+        discrepancies
+    }
+
+    /// Recomputes the hashes of the files in each backup (or only the one matching `backup`)
+    /// and compares them with the hashes in the mapping.
+    /// Every file is listed in the scan info, and any problems are recorded as failures.
+    /// Missing backups and files are found the same way as in `check_consistency`,
+    /// so only the files that are actually present get hashed.
+    pub fn verify(&self, backup: Option<&str>) -> (ScanInfo, BackupInfo) {
+        let mut found_files = HashSet::new();
+        let mut backup_info = BackupInfo::default();
+
+        for full in &self.mapping.backups {
+            let mut candidates = vec![(
+                &full.name,
+                full.format(),
+                full.files.iter().collect::<Vec<_>>(),
+                backup.is_none_or(|id| full.matches(id)),
+            )];
+            for diff in &full.children {
+                candidates.push((
+                    &diff.name,
+                    diff.format(),
+                    // Files that are deliberately omitted have no data.
+                    diff.files
+                        .iter()
+                        .filter_map(|(file, data)| data.as_ref().map(|data| (file, data)))
+                        .collect(),
+                    backup.is_none_or(|id| diff.matches(id)),
+                ));
+            }
+
+            for (name, format, files, selected) in candidates {
+                if !selected {
+                    continue;
+                }
+                log::trace!("[{}] verifying backup: {}", self.mapping.name, name);
+
+                let Some(missing) = self.find_missing_backup_files(name, format, files.iter().map(|(file, _)| *file))
+                else {
+                    let container = self.path.joined(name);
+                    log::warn!(
+                        "[{}] unable to find or open backup: {}",
+                        self.mapping.name,
+                        container.raw()
+                    );
+                    let scanned = ScannedFile {
+                        path: container,
+                        size: files.iter().map(|(_, data)| data.size).sum(),
+                        change: ScanChange::Same,
+                        ..Default::default()
+                    };
+                    backup_info
+                        .failed_files
+                        .insert(scanned.clone(), TRANSLATOR.failure_missing_backup());
+                    found_files.insert(scanned);
+                    continue;
+                };
+
+                let mut present = vec![];
+                for (file, data) in files {
+                    if missing.contains(file) {
+                        let scanned = self.verified_file(name, format, file, data);
+                        log::warn!(
+                            "[{}] failed to verify: {} | missing",
+                            self.mapping.name,
+                            scanned.path.raw()
+                        );
+                        backup_info
+                            .failed_files
+                            .insert(scanned.clone(), TRANSLATOR.failure_missing_from_backup());
+                        found_files.insert(scanned);
+                    } else {
+                        present.push((file, data));
+                    }
+                }
+
+                match format {
+                    BackupFormat::Simple => self.verify_simple(name, &present, &mut found_files, &mut backup_info),
+                    BackupFormat::Zip => self.verify_zip(name, &present, &mut found_files, &mut backup_info),
+                }
+            }
+        }
+
+        let scan_info = ScanInfo {
+            game_name: self.mapping.name.clone(),
+            found_files,
+            ..Default::default()
+        };
+        (scan_info, backup_info)
+    }
+
+    /// How a file from the mapping is reported by `verify`.
+    fn verified_file(
+        &self,
+        backup: &str,
+        format: BackupFormat,
+        file: &str,
+        data: &IndividualMappingFile,
+    ) -> ScannedFile {
+        let original_path = StrictPath::new(file.to_string());
+        let path = match format {
+            BackupFormat::Simple => self.mapping.game_file_immutable(&self.path, &original_path, backup),
+            BackupFormat::Zip => self
+                .path
+                .joined(backup)
+                .joined(&self.mapping.game_file_for_zip_immutable(&original_path)),
+        };
+        ScannedFile {
+            path,
+            size: data.size,
+            hash: data.hash.clone(),
+            change: ScanChange::Same,
+            ..Default::default()
+        }
+    }
+
+    /// Hashes files that `find_missing_backup_files` found in a simple backup.
+    fn verify_simple(
+        &self,
+        backup: &str,
+        files: &[(&String, &IndividualMappingFile)],
+        found_files: &mut HashSet<ScannedFile>,
+        backup_info: &mut BackupInfo,
+    ) {
+        for (file, data) in files {
+            let scanned = self.verified_file(backup, BackupFormat::Simple, file, data);
+
+            let problem = match scanned.path.try_sha1() {
+                Ok(hash) if hash == data.hash => None,
+                Ok(_) => Some(TRANSLATOR.failure_hash_mismatch()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(problem) = problem {
+                log::warn!(
+                    "[{}] failed to verify: {} | {problem}",
+                    self.mapping.name,
+                    scanned.path.raw()
+                );
+                backup_info.failed_files.insert(scanned.clone(), problem);
+            }
+            found_files.insert(scanned);
+        }
+    }
+
+    /// Hashes files that `find_missing_backup_files` found in a zip backup.
+    fn verify_zip(
+        &self,
+        backup: &str,
+        files: &[(&String, &IndividualMappingFile)],
+        found_files: &mut HashSet<ScannedFile>,
+        backup_info: &mut BackupInfo,
+    ) {
+        use sha1::Digest;
+
+        if files.is_empty() {
+            return;
+        }
+
+        let container = self.path.joined(backup);
+        let archive = std::fs::File::open(container.interpret())
+            .map_err(|e| e.to_string())
+            .and_then(|handle| zip::ZipArchive::new(handle).map_err(|e| e.to_string()));
+        let mut archive = match archive {
+            Ok(x) => x,
+            Err(e) => {
+                log::warn!(
+                    "[{}] unable to open archive: {} | {e}",
+                    self.mapping.name,
+                    container.raw()
+                );
+                let scanned = ScannedFile {
+                    path: container,
+                    size: files.iter().map(|(_, data)| data.size).sum(),
+                    change: ScanChange::Same,
+                    ..Default::default()
+                };
+                backup_info
+                    .failed_files
+                    .insert(scanned.clone(), TRANSLATOR.failure_unreadable_archive(&e));
+                found_files.insert(scanned);
+                return;
+            }
+        };
+
+        for (file, data) in files {
+            let scanned = self.verified_file(backup, BackupFormat::Zip, file, data);
+            let entry = self
+                .mapping
+                .game_file_for_zip_immutable(&StrictPath::new(file.to_string()));
+
+            let problem = match zip_safety::open_by_name(&mut archive, &entry, self.settings.zip_limits) {
+                Err(e) => Some(e.to_string()),
+                Ok(mut source) => {
+                    let mut hasher = sha1::Sha1::new();
+                    match zip_safety::copy_entry(&mut source, &mut hasher) {
+                        Ok(_) if format!("{:x}", hasher.finalize()) == data.hash => None,
+                        Ok(_) => Some(TRANSLATOR.failure_hash_mismatch()),
+                        Err(e) => Some(e.to_string()),
+                    }
+                }
+            };
+            if let Some(problem) = problem {
+                log::warn!(
+                    "[{}] failed to verify: {} | {problem}",
+                    self.mapping.name,
+                    scanned.path.raw()
+                );
+                backup_info.failed_files.insert(scanned.clone(), problem);
+            }
+            found_files.insert(scanned);
+        }
+    }
+
+    /// Updates the mapping so that it only refers to data that still exists.
+    /// Unknown files are left alone, since we can't tell what they are.
+    pub fn repair(&mut self, discrepancies: &[Discrepancy]) {
+        for discrepancy in discrepancies {
+            match discrepancy {
+                Discrepancy::MissingBackup { backup } => {
+                    log::warn!("[{}] forgetting missing backup: {}", self.mapping.name, backup);
+                    // Differential backups can't be used without their full backup.
+                    self.mapping.backups.retain(|full| full.name != *backup);
+                    for full in &mut self.mapping.backups {
+                        full.children.retain(|diff| diff.name != *backup);
+                    }
+                }
+                Discrepancy::MissingFile { backup, file } => {
+                    log::warn!(
+                        "[{}] forgetting missing file in backup {}: {}",
+                        self.mapping.name,
+                        backup,
+                        file
+                    );
+                    for full in &mut self.mapping.backups {
+                        if full.name == *backup {
+                            full.files.remove(file);
+                        }
+                        for diff in &mut full.children {
+                            if diff.name == *backup {
+                                diff.files.remove(file);
+                            }
+                        }
+                    }
+                }
+                Discrepancy::UnknownFile { path } => {
+                    log::warn!("[{}] leaving unknown file in place: {}", self.mapping.name, path);
+                }
+            }
+        }
+
+        self.save();
+    }
+
+    /// Deletes the backups selected by the rules, unless previewing,
+    /// and returns them along with the space that they took up.
+    /// A full backup is only deleted if all of its differential backups can be deleted too,
+    /// since they can't be restored without it.
+    pub fn prune(&mut self, rules: &PruneRules, preview: bool) -> Vec<(Backup, u64)> {
+        let mut excess = vec![];
+
+        let latest_full = self.mapping.backups.len().checked_sub(1);
+        let is_latest = |i: usize, j: Option<usize>| {
+            !rules.allow_latest && Some(i) == latest_full && j == self.mapping.backups[i].children.len().checked_sub(1)
+        };
+        let deletable =
+            |i: usize, j: Option<usize>, when, trigger| !is_latest(i, j) && rules.filter.includes_parts(when, trigger);
+
+        let unlocked_fulls = self
+            .mapping
+            .backups
+            .iter()
+            .filter(|full| !full.locked && full.children.iter().all(|diff| !diff.locked))
+            .count();
+        let mut excess_fulls = rules.excess(rules.keep_full, unlocked_fulls);
+
+        for (i, full) in self.mapping.backups.iter().enumerate() {
+            let locked = full.locked || full.children.iter().any(|diff| diff.locked);
+            if !locked && excess_fulls > 0 {
+                excess_fulls -= 1;
+                if deletable(i, None, &full.when, &full.trigger)
+                    && full
+                        .children
+                        .iter()
+                        .enumerate()
+                        .all(|(j, diff)| deletable(i, Some(j), &diff.when, &diff.trigger))
+                {
+                    excess.push((i, None));
+                    continue;
+                }
+            }
+
+            let unlocked_diffs = full.children.iter().filter(|diff| !diff.locked).count();
+            let mut excess_diffs = rules.excess(rules.keep_differential, unlocked_diffs);
+
+            for (j, diff) in full.children.iter().enumerate() {
+                if !diff.locked && excess_diffs > 0 {
+                    excess_diffs -= 1;
+                    if deletable(i, Some(j), &diff.when, &diff.trigger) {
+                        excess.push((i, Some(j)));
+                    }
+                }
+            }
+        }
+
+        let mut pruned = vec![];
+        for (full, diff) in &excess {
+            let full = &self.mapping.backups[*full];
+            match diff {
+                Some(diff) => {
+                    let diff = Backup::Differential(full.children[*diff].clone());
+                    let bytes = self.backup_bytes(&diff);
+                    pruned.push((diff, bytes));
+                }
+                None => {
+                    let backup = Backup::Full(full.clone());
+                    let bytes = self.backup_bytes(&backup);
+                    pruned.push((backup, bytes));
+                    for diff in &full.children {
+                        let diff = Backup::Differential(diff.clone());
+                        let bytes = self.backup_bytes(&diff);
+                        pruned.push((diff, bytes));
+                    }
+                }
+            }
+        }
+
+        log::debug!(
+            "[{}] Pruning backups (preview: {}): {:?}",
+            &self.mapping.name,
+            preview,
+            excess
+        );
+
+        if !preview && !excess.is_empty() {
+            // Remove indices from biggest to smallest so that the order is stable.
+            for (full, diff) in excess.into_iter().rev() {
+                if let Some(diff) = diff {
+                    self.mapping.backups[full].children.remove(diff);
+                } else {
+                    self.mapping.backups.remove(full);
+                }
+            }
+            self.save();
+            self.prune_irrelevant_parents();
+        }
+
+        pruned
+    }
+
+    /// Deletes the backup with the given name or ID
+    /// and returns it (plus any deleted differential backups) along with the space that they took up.
+    /// A full backup with differential backups can only be deleted together with them (`cascade`),
+    /// and locked backups can only be deleted when forced.
+    pub fn delete_backup(
+        &mut self,
+        id: &str,
+        cascade: bool,
+        force: bool,
+    ) -> Result<Vec<(Backup, u64)>, DeletionProblem> {
+        let mut target = None;
+        for (i, full) in self.mapping.backups.iter().enumerate() {
+            if full.matches(id) {
+                target = Some((i, None));
+                break;
+            }
+            if let Some(j) = full.children.iter().position(|diff| diff.matches(id)) {
+                target = Some((i, Some(j)));
+                break;
+            }
+        }
+        let Some((full, diff)) = target else {
+            return Err(DeletionProblem::NotFound);
+        };
+
+        let mut deleted = vec![];
+        match diff {
+            Some(diff) => {
+                let diff = &self.mapping.backups[full].children[diff];
+                if diff.locked && !force {
+                    return Err(DeletionProblem::Locked);
+                }
+                let diff = Backup::Differential(diff.clone());
+                let bytes = self.backup_bytes(&diff);
+                deleted.push((diff, bytes));
+            }
+            None => {
+                let full = &self.mapping.backups[full];
+                if !full.children.is_empty() && !cascade {
+                    return Err(DeletionProblem::HasDifferentials {
+                        total: full.children.len(),
+                    });
+                }
+                if (full.locked || full.children.iter().any(|diff| diff.locked)) && !force {
+                    return Err(DeletionProblem::Locked);
+                }
+                let backup = Backup::Full(full.clone());
+                let bytes = self.backup_bytes(&backup);
+                deleted.push((backup, bytes));
+                for diff in &full.children {
+                    let diff = Backup::Differential(diff.clone());
+                    let bytes = self.backup_bytes(&diff);
+                    deleted.push((diff, bytes));
+                }
+            }
+        }
+
+        log::debug!("[{}] Deleting backup: {} ({:?})", &self.mapping.name, id, (full, diff));
+
+        match diff {
+            Some(diff) => {
+                self.mapping.backups[full].children.remove(diff);
+            }
+            None => {
+                self.mapping.backups.remove(full);
+            }
+        }
+        self.save();
+        self.prune_irrelevant_parents();
+
+        Ok(deleted)
+    }
+}
+
+/// A mismatch between a game's mapping file and what is actually in its backup folder.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Discrepancy {
+    /// The mapping lists a backup whose folder or archive is gone.
+    MissingBackup { backup: String },
+    /// The mapping lists a file that is not in the backup.
+    MissingFile { backup: String, file: String },
+    /// The backup folder contains something that the mapping doesn't know about.
+    UnknownFile { path: String },
+}
+
+/// Why a folder or archive could not be adopted as a game's backup.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdoptionProblem {
+    /// The source is neither a folder nor a zip file.
+    SourceMissing,
+    /// The game already has a backup folder.
+    DestinationOccupied { path: StrictPath },
+    /// The source has a `mapping.yaml`, but it could not be parsed.
+    UnreadableMapping { why: String },
+    /// The mapping does not list any backups.
+    NoBackups,
+    /// The mapping lists a backup whose folder or archive is not in the source.
+    MissingBackup { backup: String },
+    /// There are no `drive-*` folders with the actual files.
+    NoDriveFolders,
+    /// A `drive-*` folder does not correspond to a known drive,
+    /// so we can't tell where its files originally came from.
+    UnknownDriveFolder { folder: String },
+    /// The source looks like a zip file, but could not be opened.
+    UnreadableZip { why: String },
+    /// The zip file has an entry that could write outside of the backup or expand excessively.
+    UnsafeZipEntry { problem: UnsafeZipEntry },
+    /// The source could not be moved into the backup folder.
+    UnableToMove { why: String },
+}
+
+/// Why a backup could not be deleted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DeletionProblem {
+    /// No backup has the requested name or ID.
+    NotFound,
+    /// The backup is a full backup with differential backups that depend on it.
+    HasDifferentials { total: usize },
+    /// The backup (or one of its differential backups) is locked.
+    Locked,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupKind {
+    #[default]
+    Full,
+    Differential,
+}
+
+/// Why a differential backup was made as a full backup instead.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PromotionReason {
+    /// The differential backups would have held more data than the configured limit.
+    #[serde(rename_all = "camelCase")]
+    DiffBytesExceeded { diff_bytes: u64, limit_bytes: u64 },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BackupLayout {
+    pub base: StrictPath,
+    games: HashMap<String, StrictPath>,
+    games_lowercase: HashMap<String, StrictPath>,
+    retention: Retention,
+    settings: LayoutSettings,
+}
+
+impl BackupLayout {
+    /// The retention policy and other settings are taken from `config`.
+    pub fn new(base: StrictPath, config: &Config) -> Self {
+        let games = Self::load(&base);
+        let games_lowercase = games.iter().map(|(k, v)| (k.to_lowercase(), v.clone())).collect();
+        Self {
+            base,
+            games,
+            games_lowercase,
+            retention: config.backup.retention.clone(),
+            settings: LayoutSettings::new(config),
+        }
+    }
+
+    /// Reuse the same backup folders with a different retention policy.
+    pub fn with_retention(&self, retention: Retention) -> Self {
+        Self {
+            retention,
+            ..self.clone()
+        }
+    }
+
+    pub fn retention(&self) -> &Retention {
+        &self.retention
+    }
+
+    pub fn load(base: &StrictPath) -> HashMap<String, StrictPath> {
+        let mut overall = HashMap::new();
+
+        for game_dir in walkdir::WalkDir::new(base.interpret())
+            .max_depth(1)
+            .follow_links(false)
+            .into_iter()
+            .skip(1) // the base path itself
+            .filter_map(crate::scan::filter_map_walkdir)
+            .filter(|x| x.file_type().is_dir())
+        {
+            let game_dir = StrictPath::from(&game_dir);
+            let mapping_file = game_dir.joined("mapping.yaml");
+            if mapping_file.is_file() {
+                match IndividualMapping::load(&mapping_file) {
+                    Ok(mapping) => {
+                        overall.insert(mapping.name.clone(), game_dir);
+                    }
+                    Err(e) => {
+                        log::warn!("Ignoring unloadable mapping: {} | {:?}", mapping_file.render(), e);
+                    }
+                }
+            }
+        }
 
         overall
     }
@@ -1980,7 +3680,7 @@ impl BackupLayout {
     pub fn game_layout(&self, name: &str) -> GameLayout {
         let path = self.game_folder(name);
 
-        match GameLayout::load(path.clone(), self.retention.clone()) {
+        match GameLayout::load(path.clone(), self.retention.clone(), self.settings.clone()) {
             Ok(mut x) => {
                 if x.mapping.name != name {
                     // This can happen if the game name changed in the manifest,
@@ -1995,6 +3695,7 @@ impl BackupLayout {
                 path,
                 mapping: IndividualMapping::new(name.to_string()),
                 retention: self.retention.clone(),
+                settings: self.settings.clone(),
             },
         }
     }
@@ -2002,16 +3703,18 @@ impl BackupLayout {
     pub fn try_game_layout(&self, name: &str) -> Option<GameLayout> {
         let path = self.game_folder(name);
 
-        GameLayout::load(path, self.retention.clone()).ok().map(|mut x| {
-            if x.mapping.name != name {
-                // This can happen if the game name changed in the manifest,
-                // but differs only by capitalization when we're on a case-insensitive OS.
-                // If we don't adjust it, it'll always show up as a new game.
-                log::info!("Updating renamed game: {} -> {}", &x.mapping.name, name);
-                x.mapping.name = name.to_string();
-            }
-            x
-        })
+        GameLayout::load(path, self.retention.clone(), self.settings.clone())
+            .ok()
+            .map(|mut x| {
+                if x.mapping.name != name {
+                    // This can happen if the game name changed in the manifest,
+                    // but differs only by capitalization when we're on a case-insensitive OS.
+                    // If we don't adjust it, it'll always show up as a new game.
+                    log::info!("Updating renamed game: {} -> {}", &x.mapping.name, name);
+                    x.mapping.name = name.to_string();
+                }
+                x
+            })
     }
 
     fn contains_game(&self, name: &str) -> bool {
@@ -2036,6 +3739,15 @@ impl BackupLayout {
                 } else {
                     None
                 },
+                stored_files: if restoring {
+                    vec![]
+                } else {
+                    game_layout
+                        .latest_stored_files()
+                        .into_iter()
+                        .map(|(k, v)| (StrictPath::new(k.to_string()), v.hash.clone()))
+                        .collect()
+                },
             })
         } else {
             None
@@ -2062,9 +3774,289 @@ impl BackupLayout {
         }
     }
 
-    pub fn restorable_games(&self) -> Vec<String> {
+    /// Whether any backups are stored for the game.
+    pub fn has_backups(&self, name: &str) -> bool {
+        self.contains_game(name)
+            && self
+                .try_game_layout(name)
+                .is_some_and(|game_layout| !game_layout.mapping.backups.is_empty())
+    }
+
+    pub fn restorable_games(&self) -> Vec<String> {
         self.games.keys().cloned().collect()
     }
+
+    /// Move a backup that was renamed or migrated by hand into the canonical folder for `name`.
+    /// The source may be a game's backup folder (with or without `mapping.yaml`)
+    /// or a single zip backup.
+    pub fn adopt(
+        &self,
+        source: &StrictPath,
+        name: &str,
+        now: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<StrictPath, AdoptionProblem> {
+        let target = self.game_folder(name);
+        if self.contains_game(name) || target.exists() {
+            return Err(AdoptionProblem::DestinationOccupied { path: target });
+        }
+
+        if source.is_dir() {
+            self.adopt_folder(source, name, &target, now)?;
+        } else if source.is_file() {
+            self.adopt_zip(source, name, &target, now)?;
+        } else {
+            return Err(AdoptionProblem::SourceMissing);
+        }
+
+        log::info!("[{name}] adopted backup: {} -> {}", source.raw(), target.raw());
+        Ok(target)
+    }
+
+    fn adopt_folder(
+        &self,
+        source: &StrictPath,
+        name: &str,
+        target: &StrictPath,
+        now: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AdoptionProblem> {
+        let mapping_file = GameLayout::mapping_file(source);
+
+        let mapping = if mapping_file.is_file() {
+            let mut mapping = IndividualMapping::load(&mapping_file)
+                .map_err(|e| AdoptionProblem::UnreadableMapping { why: e.to_string() })?;
+            if mapping.backups.is_empty() {
+                return Err(AdoptionProblem::NoBackups);
+            }
+            for full in &mapping.backups {
+                let names = std::iter::once(&full.name).chain(full.children.iter().map(|diff| &diff.name));
+                for backup in names {
+                    if backup != "." && !source.joined(backup).exists() {
+                        return Err(AdoptionProblem::MissingBackup { backup: backup.clone() });
+                    }
+                }
+            }
+            mapping.name = name.to_string();
+            mapping
+        } else {
+            let folders: Vec<_> = walkdir::WalkDir::new(source.interpret())
+                .max_depth(1)
+                .follow_links(false)
+                .into_iter()
+                .skip(1) // the source itself
+                .filter_map(crate::scan::filter_map_walkdir)
+                .filter(|x| x.file_type().is_dir())
+                .map(|x| x.file_name().to_string_lossy().to_string())
+                .filter(|x| x.starts_with("drive-"))
+                .collect();
+            if folders.is_empty() {
+                return Err(AdoptionProblem::NoDriveFolders);
+            }
+            Self::ensure_known_drive_folders(folders.iter().map(|x| x.as_str()))?;
+
+            let mut mapping = IndividualMapping::new(name.to_string());
+            let mut files = BTreeMap::new();
+            for folder in &folders {
+                let drive = Self::drive_from_folder_name(folder).unwrap_or_default();
+                mapping.drive_folder_name(&drive);
+
+                let drive_dir = source.joined(folder);
+                for file in walkdir::WalkDir::new(drive_dir.interpret())
+                    .max_depth(100)
+                    .follow_links(false)
+                    .into_iter()
+                    .filter_map(crate::scan::filter_map_walkdir)
+                    .filter(|x| x.file_type().is_file())
+                {
+                    let Ok(plain_path) = file.path().strip_prefix(drive_dir.interpret()) else {
+                        continue;
+                    };
+                    let path = StrictPath::from(&file);
+                    files.insert(
+                        format!("{drive}/{}", plain_path.display()).replace('\\', "/"),
+                        IndividualMappingFile {
+                            hash: path.sha1(),
+                            size: path.size(),
+                        },
+                    );
+                }
+            }
+            mapping.backups = VecDeque::from(vec![FullBackup {
+                name: ".".to_string(),
+                id: Some(1),
+                when: *now,
+                files,
+                ..Default::default()
+            }]);
+            mapping
+        };
+
+        if let Err(e) = target.create_parent_dir() {
+            return Err(AdoptionProblem::UnableToMove { why: e.to_string() });
+        }
+        if let Err(e) = Self::move_path(name, source, target) {
+            return Err(AdoptionProblem::UnableToMove { why: e.to_string() });
+        }
+
+        mapping.save(&GameLayout::mapping_file(target));
+
+        Ok(())
+    }
+
+    /// Renaming doesn't work across file systems (e.g., from a removable drive),
+    /// so fall back to copying everything and then deleting the source.
+    fn move_path(context: &str, source: &StrictPath, target: &StrictPath) -> Result<(), std::io::Error> {
+        match std::fs::rename(source.interpret(), target.interpret()) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::info!(
+                    "[{context}] unable to rename, so copying instead: {} -> {} | {e}",
+                    source.raw(),
+                    target.raw()
+                );
+                Self::copy_then_remove(context, source, target)
+            }
+        }
+    }
+
+    fn copy_then_remove(context: &str, source: &StrictPath, target: &StrictPath) -> Result<(), std::io::Error> {
+        let copied = if source.is_file() {
+            source.copy_to_path(context, target)
+        } else {
+            let source_dir = source.as_std_path_buf();
+            walkdir::WalkDir::new(&source_dir)
+                .follow_links(false)
+                .into_iter()
+                .filter_map(crate::scan::filter_map_walkdir)
+                .filter(|x| x.file_type().is_file())
+                .try_for_each(|file| {
+                    let relative = file.path().strip_prefix(&source_dir).unwrap_or(file.path());
+                    StrictPath::from(&file).copy_to_path(context, &target.joined(&relative.to_string_lossy()))
+                })
+        };
+
+        if let Err(e) = copied {
+            // Don't leave a partial copy that would look like a real backup.
+            _ = target.remove();
+            return Err(e);
+        }
+
+        source.remove().map_err(|e| std::io::Error::other(e.to_string()))
+    }
+
+    fn adopt_zip(
+        &self,
+        source: &StrictPath,
+        name: &str,
+        target: &StrictPath,
+        now: &chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AdoptionProblem> {
+        use sha1::Digest;
+
+        let handle = std::fs::File::open(source.interpret())
+            .map_err(|e| AdoptionProblem::UnreadableZip { why: e.to_string() })?;
+        let mut archive =
+            zip::ZipArchive::new(handle).map_err(|e| AdoptionProblem::UnreadableZip { why: e.to_string() })?;
+
+        let mut mapping = IndividualMapping::new(name.to_string());
+        let mut files = BTreeMap::new();
+        for i in 0..archive.len() {
+            let mut entry =
+                zip_safety::open_by_index(&mut archive, i, self.settings.zip_limits).map_err(|e| match e
+                    .downcast_ref::<UnsafeZipEntry>()
+                {
+                    Some(problem) => AdoptionProblem::UnsafeZipEntry {
+                        problem: problem.clone(),
+                    },
+                    None => AdoptionProblem::UnreadableZip { why: e.to_string() },
+                })?;
+            if entry.is_dir() {
+                continue;
+            }
+            let entry_name = entry.name().to_string();
+            let Some((folder, plain_path)) = entry_name.split_once('/') else {
+                continue;
+            };
+            if !folder.starts_with("drive-") {
+                continue;
+            }
+            // The path becomes a restore target, so it must not leave its drive either.
+            if zip_safety::check_entry_name(plain_path).is_err() {
+                return Err(AdoptionProblem::UnsafeZipEntry {
+                    problem: UnsafeZipEntry::EscapesRoot { entry: entry_name },
+                });
+            }
+            Self::ensure_known_drive_folders(std::iter::once(folder))?;
+            let drive = Self::drive_from_folder_name(folder).unwrap_or_default();
+            mapping.drive_folder_name(&drive);
+
+            // Hash the entry as it's read, without holding its content in memory.
+            let mut hasher = sha1::Sha1::new();
+            let size = zip_safety::copy_entry(&mut entry, &mut hasher).map_err(|e| {
+                match e.downcast_ref::<UnsafeZipEntry>() {
+                    Some(problem) => AdoptionProblem::UnsafeZipEntry {
+                        problem: problem.clone(),
+                    },
+                    None => AdoptionProblem::UnreadableZip { why: e.to_string() },
+                }
+            })?;
+
+            files.insert(
+                format!("{drive}/{plain_path}"),
+                IndividualMappingFile {
+                    hash: format!("{:x}", hasher.finalize()),
+                    size,
+                },
+            );
+        }
+        if files.is_empty() {
+            return Err(AdoptionProblem::NoDriveFolders);
+        }
+
+        let backup_name = format!("backup-{}.zip", GameLayout::generate_file_friendly_timestamp(now));
+        let target_file = target.joined(&backup_name);
+        if let Err(e) = target_file.create_parent_dir() {
+            return Err(AdoptionProblem::UnableToMove { why: e.to_string() });
+        }
+        if let Err(e) = Self::move_path(name, source, &target_file) {
+            _ = target.remove();
+            return Err(AdoptionProblem::UnableToMove { why: e.to_string() });
+        }
+
+        mapping.backups = VecDeque::from(vec![FullBackup {
+            name: backup_name,
+            id: Some(1),
+            when: *now,
+            files,
+            ..Default::default()
+        }]);
+        mapping.save(&GameLayout::mapping_file(target));
+
+        Ok(())
+    }
+
+    /// Without a mapping, we can only infer the plain drive folders that Ludusavi generates,
+    /// like `drive-C` for `C:` and `drive-0` for paths without a drive letter.
+    fn drive_from_folder_name(folder: &str) -> Option<String> {
+        let suffix = folder.strip_prefix("drive-")?;
+        if suffix == "0" {
+            Some(String::new())
+        } else if suffix.len() == 1 && suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+            Some(format!("{suffix}:"))
+        } else {
+            None
+        }
+    }
+
+    fn ensure_known_drive_folders<'a>(folders: impl Iterator<Item = &'a str>) -> Result<(), AdoptionProblem> {
+        for folder in folders {
+            if Self::drive_from_folder_name(folder).is_none() {
+                return Err(AdoptionProblem::UnknownDriveFolder {
+                    folder: folder.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -2088,17 +4080,148 @@ mod tests {
             assert_eq!("drive-____C", mapping.drive_folder_name(r#"\\?\C:"#));
             assert_eq!("drive-__remote", mapping.drive_folder_name(r#"\\remote"#));
         }
+
+        #[test]
+        fn assigns_missing_backup_ids_after_existing_ones() {
+            let mut mapping = IndividualMapping {
+                backups: VecDeque::from(vec![
+                    FullBackup {
+                        name: s("backup-1"),
+                        children: VecDeque::from(vec![DifferentialBackup {
+                            name: s("backup-1-diff"),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    },
+                    FullBackup {
+                        name: s("backup-2"),
+                        id: Some(5),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            };
+
+            mapping.assign_missing_ids();
+
+            assert_eq!(Some(6), mapping.backups[0].id);
+            assert_eq!(Some(7), mapping.backups[0].children[0].id);
+            assert_eq!(Some(5), mapping.backups[1].id);
+            assert_eq!(7, mapping.last_backup_id);
+            assert_eq!(8, mapping.next_backup_id());
+        }
+
+        #[test]
+        fn backups_match_by_name_or_id() {
+            let backup = FullBackup {
+                name: s("backup-1"),
+                id: Some(3),
+                ..Default::default()
+            };
+            assert!(backup.matches("backup-1"));
+            assert!(backup.matches("3"));
+            assert!(!backup.matches("4"));
+            assert!(!FullBackup::default().matches("1"));
+        }
+    }
+
+    #[test]
+    fn backup_filter_uses_backup_time() {
+        let at = |time: &str| {
+            chrono::DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let backup = |time: &str| {
+            Backup::Full(FullBackup {
+                when: at(time),
+                ..Default::default()
+            })
+        };
+        let filter = BackupFilter {
+            newer_than: Some(at("2024-01-01T00:00:00Z")),
+            older_than: Some(at("2024-02-01T00:00:00Z")),
+            trigger: None,
+        };
+
+        assert!(BackupFilter::default().includes(&backup("2000-01-01T00:00:00Z")));
+        assert!(!filter.includes(&backup("2023-12-31T23:59:59Z")));
+        assert!(filter.includes(&backup("2024-01-01T00:00:00Z")));
+        assert!(filter.includes(&backup("2024-01-15T00:00:00Z")));
+        assert!(!filter.includes(&backup("2024-02-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn backup_filter_uses_trigger() {
+        let backup = |trigger: Option<TriggerSource>| {
+            Backup::Full(FullBackup {
+                trigger: trigger.map(|source| Trigger {
+                    source,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        };
+        let filter = |source: TriggerSource| BackupFilter {
+            trigger: Some(source),
+            ..Default::default()
+        };
+
+        assert!(filter(TriggerSource::Wrap).includes(&backup(Some(TriggerSource::Wrap))));
+        assert!(!filter(TriggerSource::Wrap).includes(&backup(Some(TriggerSource::Gui))));
+        assert!(!filter(TriggerSource::Wrap).includes(&backup(None)));
+        assert!(filter(TriggerSource::Unknown).includes(&backup(None)));
+    }
+
+    #[test]
+    fn can_load_trigger_from_mapping() {
+        let backup: FullBackup = serde_yaml::from_str(
+            r#"
+name: "."
+when: "2024-01-01T00:00:00Z"
+trigger:
+  source: somethingNew
+  host: desktop
+children: []
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Trigger {
+                source: TriggerSource::Unknown,
+                host: Some("desktop".to_string()),
+                executable: None,
+            },
+            Backup::Full(backup).trigger()
+        );
+    }
+
+    #[test]
+    fn can_round_trip_multi_line_comment_in_mapping() {
+        let mut mapping = IndividualMapping::new(s("game"));
+        mapping.backups.push_back(FullBackup {
+            name: s("."),
+            comment: Some(s("before 1.5 patch\n  slot 2 is broken\n")),
+            ..Default::default()
+        });
+
+        let loaded = IndividualMapping::load_from_string(&mapping.serialize()).unwrap();
+        assert_eq!(
+            Some(s("before 1.5 patch\n  slot 2 is broken\n")),
+            loaded.backups[0].comment
+        );
     }
 
     mod backup_layout {
         use pretty_assertions::assert_eq;
 
         use super::*;
+        use crate::resource::config::SizeThreshold;
 
         fn layout() -> BackupLayout {
             BackupLayout::new(
                 StrictPath::new(format!("{}/tests/backup", repo_raw())),
-                Retention::default(),
+                &Config::default(),
             )
         }
 
@@ -2107,6 +4230,7 @@ mod tests {
                 path: StrictPath::new(path.to_string()),
                 mapping: IndividualMapping::new(name.to_string()),
                 retention: Retention::default(),
+                settings: LayoutSettings::default(),
             }
         }
 
@@ -2128,6 +4252,13 @@ mod tests {
             );
         }
 
+        #[test]
+        fn can_check_whether_game_has_backups() {
+            assert!(layout().has_backups("game1"));
+            assert!(layout().has_backups("game3"));
+            assert!(!layout().has_backups("nonexistent"));
+        }
+
         #[test]
         fn can_find_existing_game_folder_with_rename() {
             assert_eq!(
@@ -2261,6 +4392,7 @@ mod tests {
                 path: StrictPath::new(format!("{}/tests/backup/game1", repo_raw())),
                 mapping: IndividualMapping::new("game1".to_string()),
                 retention: Retention::default(),
+                settings: LayoutSettings::default(),
             };
             assert_eq!(None, layout.plan_backup(&scan, &now(), &BackupFormats::default()));
         }
@@ -2449,6 +4581,102 @@ mod tests {
             assert_eq!(BackupKind::Differential, layout.plan_backup_kind());
         }
 
+        fn layout_for_promotion(threshold: Option<SizeThreshold>) -> GameLayout {
+            GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![FullBackup {
+                        files: btreemap! {
+                            s("/file1") => IndividualMappingFile { hash: s("1"), size: 100 },
+                        },
+                        children: VecDeque::from(vec![DifferentialBackup {
+                            files: btreemap! {
+                                s("/file1") => Some(IndividualMappingFile { hash: s("2"), size: 30 }),
+                            },
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                retention: Retention {
+                    full: 1,
+                    differential: 5,
+                    promote_full_when_diff_bytes_exceed: threshold,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        fn diff_for_promotion() -> DifferentialBackup {
+            DifferentialBackup {
+                files: btreemap! {
+                    s("/file1") => Some(IndividualMappingFile { hash: s("3"), size: 30 }),
+                    s("/file2") => None,
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn does_not_plan_promotion_without_threshold() {
+            let layout = layout_for_promotion(None);
+            assert_eq!(None, layout.plan_promotion(&diff_for_promotion()));
+        }
+
+        #[test]
+        fn can_plan_promotion_when_percentage_exceeded() {
+            let layout = layout_for_promotion(Some(SizeThreshold::Percent(50)));
+            assert_eq!(
+                Some(PromotionReason::DiffBytesExceeded {
+                    diff_bytes: 60,
+                    limit_bytes: 50,
+                }),
+                layout.plan_promotion(&diff_for_promotion())
+            );
+        }
+
+        #[test]
+        fn does_not_plan_promotion_when_within_absolute_limit() {
+            let layout = layout_for_promotion(Some(SizeThreshold::Bytes(60)));
+            assert_eq!(None, layout.plan_promotion(&diff_for_promotion()));
+        }
+
+        #[test]
+        fn can_list_latest_files_through_differential_backup() {
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![FullBackup {
+                        files: btreemap! {
+                            s("/changed") => IndividualMappingFile { hash: s("1"), size: 1 },
+                            s("/removed") => IndividualMappingFile { hash: s("2"), size: 2 },
+                            s("/same") => IndividualMappingFile { hash: s("3"), size: 3 },
+                        },
+                        children: VecDeque::from(vec![DifferentialBackup {
+                            files: btreemap! {
+                                s("/changed") => Some(IndividualMappingFile { hash: s("4"), size: 4 }),
+                                s("/removed") => None,
+                            },
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let mut files = layout.latest_files();
+            files.sort();
+            assert_eq!(
+                vec![
+                    ("/changed", &IndividualMappingFile { hash: s("4"), size: 4 }),
+                    ("/same", &IndividualMappingFile { hash: s("3"), size: 3 }),
+                ],
+                files
+            );
+        }
+
         #[test]
         fn can_plan_full_backup_with_files() {
             let scan = ScanInfo {
@@ -2465,8 +4693,10 @@ mod tests {
             assert_eq!(
                 FullBackup {
                     name: ".".to_string(),
+                    id: Some(1),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     files: btreemap! {
                         StrictPath::new(repo_file("new")).render() => IndividualMappingFile { hash: "n".into(), size: 1 },
                         StrictPath::new(repo_file("different")).render() => IndividualMappingFile { hash: "d".into(), size: 2 },
@@ -2517,6 +4747,7 @@ mod tests {
                     name: ".".to_string(),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     registry: IndividualMappingRegistry {
                         hash: Some(crate::prelude::sha1(hives.serialize()))
                     },
@@ -2543,6 +4774,7 @@ mod tests {
                     drives: drives(),
                     backups: VecDeque::from_iter(vec![FullBackup {
                         name: ".".to_string(),
+                        id: Some(1),
                         when: past(),
                         files: btreemap! {
                             StrictPath::new(repo_file("different")).render() => IndividualMappingFile { hash: "d".into(), size: 2 },
@@ -2558,8 +4790,10 @@ mod tests {
             assert_eq!(
                 DifferentialBackup {
                     name: format!("backup-{}", now_str()),
+                    id: Some(2),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     files: btreemap! {
                         StrictPath::new(repo_file("new")).render() => Some(IndividualMappingFile { hash: "n".into(), size: 1 }),
                         StrictPath::new(repo_file("different")).render() => Some(IndividualMappingFile { hash: "d+".into(), size: 2 }),
@@ -2573,30 +4807,158 @@ mod tests {
         }
 
         #[test]
-        #[cfg(target_os = "windows")]
-        fn can_plan_differential_backup_with_registry_new() {
-            use crate::scan::registry::{Entries, Hives, Keys};
-
-            let scan = ScanInfo {
-                found_registry_keys: hashset! {
-                    ScannedRegistry::new("HKEY_CURRENT_USER/Software/Ludusavi/other").change_as(ScanChange::New)
+        fn can_plan_differential_backup_after_casing_change() {
+            let full = FullBackup {
+                name: ".".to_string(),
+                when: past(),
+                files: btreemap! {
+                    s("C:/Game/SaveGames/a.sav") => IndividualMappingFile { hash: "a".into(), size: 1 },
+                    s("C:/Game/SaveGames/b.sav") => IndividualMappingFile { hash: "b".into(), size: 2 },
                 },
                 ..Default::default()
             };
-            let layout = GameLayout {
-                mapping: IndividualMapping {
-                    backups: VecDeque::from_iter(vec![FullBackup {
-                        name: ".".to_string(),
-                        when: past(),
-                        registry: IndividualMappingRegistry { hash: None },
-                        ..Default::default()
-                    }]),
-                    ..Default::default()
+            let scanned = btreemap! {
+                s("C:/Game/savegames/a.sav") => Some(IndividualMappingFile { hash: "a".into(), size: 1 }),
+                s("C:/Game/savegames/b.sav") => Some(IndividualMappingFile { hash: "b+".into(), size: 3 }),
+            };
+
+            let mut files = scanned.clone();
+            inherit_from_full_backup(&mut files, &full, false, UnicodeNormalization::Nfc);
+            assert_eq!(
+                btreemap! {
+                    s("C:/Game/savegames/b.sav") => Some(IndividualMappingFile { hash: "b+".into(), size: 3 }),
                 },
+                files,
+            );
+
+            let diff = DifferentialBackup {
+                files: files.clone(),
                 ..Default::default()
             };
-            let hives = Hives(hashmap! {
-                s("HKEY_CURRENT_USER") => Keys(hashmap! {
+            assert_eq!(
+                BackupInclusion::Inherited,
+                diff.file_matching("C:/Game/SaveGames/a.sav", false, UnicodeNormalization::Nfc)
+            );
+            assert_eq!(
+                BackupInclusion::Included,
+                diff.file_matching("C:/Game/SaveGames/b.sav", false, UnicodeNormalization::Nfc)
+            );
+            assert_eq!(
+                BackupInclusion::Inherited,
+                diff.file_matching("C:/Game/SaveGames/b.sav", true, UnicodeNormalization::Nfc)
+            );
+
+            let mut files = scanned.clone();
+            inherit_from_full_backup(&mut files, &full, true, UnicodeNormalization::Nfc);
+            assert_eq!(
+                btreemap! {
+                    s("C:/Game/SaveGames/a.sav") => None,
+                    s("C:/Game/SaveGames/b.sav") => None,
+                    s("C:/Game/savegames/a.sav") => Some(IndividualMappingFile { hash: "a".into(), size: 1 }),
+                    s("C:/Game/savegames/b.sav") => Some(IndividualMappingFile { hash: "b+".into(), size: 3 }),
+                },
+                files,
+            );
+        }
+
+        #[test]
+        fn can_plan_differential_backup_when_name_stored_as_nfd_is_scanned_as_nfc() {
+            let full = FullBackup {
+                name: ".".to_string(),
+                when: past(),
+                files: btreemap! {
+                    s("/Game/Partie e\u{301}te\u{301}/a.sav") => IndividualMappingFile { hash: "a".into(), size: 1 },
+                    s("/Game/Partie e\u{301}te\u{301}/b.sav") => IndividualMappingFile { hash: "b".into(), size: 2 },
+                },
+                ..Default::default()
+            };
+            let mut files = btreemap! {
+                s("/Game/Partie \u{e9}t\u{e9}/a.sav") => Some(IndividualMappingFile { hash: "a".into(), size: 1 }),
+                s("/Game/Partie \u{e9}t\u{e9}/b.sav") => Some(IndividualMappingFile { hash: "b+".into(), size: 3 }),
+            };
+
+            inherit_from_full_backup(&mut files, &full, true, UnicodeNormalization::Nfc);
+            assert_eq!(
+                btreemap! {
+                    s("/Game/Partie \u{e9}t\u{e9}/b.sav") => Some(IndividualMappingFile { hash: "b+".into(), size: 3 }),
+                },
+                files,
+            );
+
+            let diff = DifferentialBackup {
+                files,
+                ..Default::default()
+            };
+            assert_eq!(
+                BackupInclusion::Inherited,
+                diff.file_matching("/Game/Partie e\u{301}te\u{301}/a.sav", true, UnicodeNormalization::Nfc)
+            );
+            assert_eq!(
+                BackupInclusion::Included,
+                diff.file_matching("/Game/Partie e\u{301}te\u{301}/b.sav", true, UnicodeNormalization::Nfc)
+            );
+        }
+
+        #[test]
+        fn can_detect_file_name_altered_by_backup_location() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-altered-name-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+
+            // Simulate a file system that decomposes names after we write a composed one.
+            std::fs::write(dir.join("cafe\u{301}.sav"), "1").unwrap();
+            std::fs::write(dir.join("plain.sav"), "1").unwrap();
+
+            assert_eq!(
+                Some(StrictPath::from(dir.join("cafe\u{301}.sav").as_path())),
+                find_altered_name(
+                    &StrictPath::from(dir.join("caf\u{e9}.sav").as_path()),
+                    UnicodeNormalization::Nfc
+                )
+            );
+            assert_eq!(
+                None,
+                find_altered_name(
+                    &StrictPath::from(dir.join("cafe\u{301}.sav").as_path()),
+                    UnicodeNormalization::Nfc
+                )
+            );
+            assert_eq!(
+                None,
+                find_altered_name(
+                    &StrictPath::from(dir.join("plain.sav").as_path()),
+                    UnicodeNormalization::Nfc
+                )
+            );
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        #[cfg(target_os = "windows")]
+        fn can_plan_differential_backup_with_registry_new() {
+            use crate::scan::registry::{Entries, Hives, Keys};
+
+            let scan = ScanInfo {
+                found_registry_keys: hashset! {
+                    ScannedRegistry::new("HKEY_CURRENT_USER/Software/Ludusavi/other").change_as(ScanChange::New)
+                },
+                ..Default::default()
+            };
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![FullBackup {
+                        name: ".".to_string(),
+                        when: past(),
+                        registry: IndividualMappingRegistry { hash: None },
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let hives = Hives(hashmap! {
+                s("HKEY_CURRENT_USER") => Keys(hashmap! {
                     s("Software\\Ludusavi\\other") => Entries::default()
                 })
             });
@@ -2605,6 +4967,7 @@ mod tests {
                     name: format!("backup-{}", now_str()),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     registry: Some(IndividualMappingRegistry {
                         hash: Some(crate::prelude::sha1(hives.serialize()))
                     }),
@@ -2652,6 +5015,7 @@ mod tests {
                     name: format!("backup-{}", now_str()),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     registry: Some(IndividualMappingRegistry {
                         hash: Some(crate::prelude::sha1(hives.serialize()))
                     }),
@@ -2696,6 +5060,7 @@ mod tests {
                     name: format!("backup-{}", now_str()),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     registry: None,
                     ..Default::default()
                 },
@@ -2731,6 +5096,7 @@ mod tests {
                     name: format!("backup-{}", now_str()),
                     when: now(),
                     os: Some(Os::HOST),
+                    app_version: Some(VERSION.to_string()),
                     registry: Some(IndividualMappingRegistry { hash: None }),
                     ..Default::default()
                 },
@@ -2790,6 +5156,280 @@ mod tests {
             );
         }
 
+        #[test]
+        fn can_preview_pruning_by_count() {
+            let file = |size| IndividualMappingFile { hash: s("x"), size };
+            let backups = VecDeque::from_iter(vec![
+                FullBackup {
+                    name: "1".to_string(),
+                    files: btreemap! { s("a") => file(10) },
+                    children: VecDeque::from_iter(vec![DifferentialBackup {
+                        name: "1-a".to_string(),
+                        files: btreemap! { s("a") => Some(file(5)) },
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                FullBackup {
+                    name: "2".to_string(),
+                    children: VecDeque::from_iter(vec![
+                        DifferentialBackup {
+                            name: "2-a".to_string(),
+                            ..Default::default()
+                        },
+                        DifferentialBackup {
+                            name: "2-b".to_string(),
+                            locked: true,
+                            ..Default::default()
+                        },
+                        DifferentialBackup {
+                            name: "2-c".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+                FullBackup {
+                    name: "3".to_string(),
+                    children: VecDeque::from_iter(vec![
+                        DifferentialBackup {
+                            name: "3-a".to_string(),
+                            ..Default::default()
+                        },
+                        DifferentialBackup {
+                            name: "3-b".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            ]);
+            let mut layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: backups.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let rules = PruneRules {
+                keep_full: Some(1),
+                keep_differential: Some(1),
+                ..Default::default()
+            };
+
+            let pruned: Vec<_> = layout
+                .prune(&rules, true)
+                .into_iter()
+                .map(|(backup, bytes)| (backup.name().to_string(), bytes))
+                .collect();
+            // Full backup 2 is kept because one of its differential backups is locked.
+            assert_eq!(vec![(s("1"), 10), (s("1-a"), 5), (s("2-a"), 0), (s("3-a"), 0)], pruned);
+            assert_eq!(backups, layout.mapping.backups);
+        }
+
+        #[test]
+        fn can_prune_by_age() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-prune-{}", std::process::id()));
+            std::fs::create_dir_all(dir.join("backup-1")).unwrap();
+            std::fs::create_dir_all(dir.join("backup-2")).unwrap();
+            std::fs::create_dir_all(dir.join("backup-3")).unwrap();
+
+            let mut layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![
+                        FullBackup {
+                            name: "backup-1".to_string(),
+                            when: past(),
+                            children: VecDeque::from_iter(vec![DifferentialBackup {
+                                name: "backup-2".to_string(),
+                                when: past2(),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        FullBackup {
+                            name: "backup-3".to_string(),
+                            when: past2(),
+                            children: VecDeque::from_iter(vec![DifferentialBackup {
+                                name: "backup-4".to_string(),
+                                when: now(),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+                path: StrictPath::from(dir.as_path()),
+                ..Default::default()
+            };
+            let rules = PruneRules {
+                filter: BackupFilter {
+                    older_than: Some(now()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let pruned: Vec<_> = layout
+                .prune(&rules, false)
+                .into_iter()
+                .map(|(backup, _)| backup.name().to_string())
+                .collect();
+            // Full backup 3 is kept because its differential backup is newer.
+            assert_eq!(vec![s("backup-1"), s("backup-2")], pruned);
+            assert_eq!(
+                vec![s("backup-3")],
+                layout
+                    .mapping
+                    .backups
+                    .iter()
+                    .map(|x| x.name.clone())
+                    .collect::<Vec<_>>()
+            );
+            assert!(!dir.join("backup-1").exists());
+            assert!(!dir.join("backup-2").exists());
+            assert!(dir.join("backup-3").exists());
+            assert!(dir.join("mapping.yaml").is_file());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn keeps_latest_backup_when_pruning_by_age() {
+            let mut layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![FullBackup {
+                        name: "backup-1".to_string(),
+                        when: past(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut rules = PruneRules {
+                filter: BackupFilter {
+                    older_than: Some(now()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let mut prune = |rules: &PruneRules| {
+                layout
+                    .prune(rules, true)
+                    .into_iter()
+                    .map(|(backup, _)| backup.name().to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(Vec::<String>::new(), prune(&rules));
+
+            rules.allow_latest = true;
+            assert_eq!(vec![s("backup-1")], prune(&rules));
+        }
+
+        #[test]
+        fn can_delete_individual_backups() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-delete-{}", std::process::id()));
+            std::fs::create_dir_all(dir.join("backup-1")).unwrap();
+            std::fs::create_dir_all(dir.join("backup-2")).unwrap();
+            std::fs::create_dir_all(dir.join("backup-3")).unwrap();
+
+            let mut layout = GameLayout {
+                mapping: IndividualMapping {
+                    backups: VecDeque::from_iter(vec![
+                        FullBackup {
+                            name: "backup-1".to_string(),
+                            when: past(),
+                            children: VecDeque::from_iter(vec![DifferentialBackup {
+                                name: "backup-2".to_string(),
+                                when: past2(),
+                                locked: true,
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        FullBackup {
+                            name: "backup-3".to_string(),
+                            when: now(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+                path: StrictPath::from(dir.as_path()),
+                ..Default::default()
+            };
+            let mut delete = |id: &str, cascade: bool, force: bool| {
+                layout.delete_backup(id, cascade, force).map(|deleted| {
+                    deleted
+                        .into_iter()
+                        .map(|(backup, _)| backup.name().to_string())
+                        .collect::<Vec<_>>()
+                })
+            };
+
+            assert_eq!(Err(DeletionProblem::NotFound), delete("backup-4", true, true));
+            assert_eq!(
+                Err(DeletionProblem::HasDifferentials { total: 1 }),
+                delete("backup-1", false, true)
+            );
+            assert_eq!(Err(DeletionProblem::Locked), delete("backup-1", true, false));
+            assert_eq!(Err(DeletionProblem::Locked), delete("backup-2", false, false));
+            assert_eq!(Ok(vec![s("backup-3")]), delete("backup-3", false, false));
+            assert_eq!(Ok(vec![s("backup-1"), s("backup-2")]), delete("backup-1", true, true));
+
+            assert!(layout.mapping.backups.is_empty());
+            assert!(!dir.join("backup-1").exists());
+            assert!(!dir.join("backup-2").exists());
+            assert!(!dir.join("backup-3").exists());
+            assert!(dir.join("mapping.yaml").is_file());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn does_not_reuse_the_id_of_a_deleted_backup() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-reused-id-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups")).unwrap();
+            std::fs::create_dir_all(dir.join("game")).unwrap();
+            let source = dir.join("game/slot1.sav");
+            std::fs::write(&source, "1").unwrap();
+
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default());
+            let scan = ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! {
+                    ScannedFile {
+                        path: StrictPath::from(source.as_path()),
+                        size: 1,
+                        hash: s("356a192b7913b04c54574d18c28d46e6395428ab"),
+                        change: ScanChange::New,
+                        ..Default::default()
+                    },
+                },
+                ..Default::default()
+            };
+            let back_up = || {
+                layout.game_layout("game1").back_up(
+                    &scan,
+                    &chrono::Utc::now(),
+                    &BackupFormats::default(),
+                    &BackupLabel::default(),
+                );
+                layout.game_layout("game1").mapping.backups.back().unwrap().id
+            };
+
+            assert_eq!(Some(1), back_up());
+            assert!(layout.game_layout("game1").delete_backup("1", true, true).is_ok());
+            assert!(layout.game_layout("game1").mapping.backups.is_empty());
+            assert_eq!(Some(2), back_up());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
         #[test]
         fn can_forget_excess_backups_without_locks_using_duplicate_name() {
             let mut layout = GameLayout {
@@ -2970,12 +5610,14 @@ mod tests {
                         },
                         ..Default::default()
                     }]),
+                    last_backup_id: 0,
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                     ..Default::default()
                 },
+                settings: LayoutSettings::default(),
             };
             assert_eq!(
                 hashset! {
@@ -2988,6 +5630,10 @@ mod tests {
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path("backup-1", "file2.txt"),
@@ -2998,6 +5644,10 @@ mod tests {
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                 },
                 layout.restorable_files(&BackupId::Latest, false, &[], &Default::default()),
@@ -3020,12 +5670,14 @@ mod tests {
                         },
                         ..Default::default()
                     }]),
+                    last_backup_id: 0,
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                     ..Default::default()
                 },
+                settings: LayoutSettings::default(),
             };
             assert_eq!(
                 hashset! {
@@ -3038,6 +5690,10 @@ mod tests {
                         change: Default::default(),
                         container: Some(make_path("backup-1.zip")),
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path_zip("file2.txt"),
@@ -3048,6 +5704,10 @@ mod tests {
                         change: Default::default(),
                         container: Some(make_path("backup-1.zip")),
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                 },
                 layout.restorable_files(&BackupId::Latest, false, &[], &Default::default()),
@@ -3081,12 +5741,14 @@ mod tests {
                         }]),
                         ..Default::default()
                     }]),
+                    last_backup_id: 0,
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                     ..Default::default()
                 },
+                settings: LayoutSettings::default(),
             };
             assert_eq!(
                 hashset! {
@@ -3099,6 +5761,10 @@ mod tests {
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path("backup-2", "changed.txt"),
@@ -3109,6 +5775,10 @@ mod tests {
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path("backup-2", "added.txt"),
@@ -3119,6 +5789,10 @@ mod tests {
                         change: Default::default(),
                         container: None,
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                 },
                 layout.restorable_files(&BackupId::Latest, false, &[], &Default::default()),
@@ -3152,12 +5826,14 @@ mod tests {
                         }]),
                         ..Default::default()
                     }]),
+                    last_backup_id: 0,
                 },
                 retention: Retention {
                     full: 1,
                     differential: 1,
                     ..Default::default()
                 },
+                settings: LayoutSettings::default(),
             };
             assert_eq!(
                 hashset! {
@@ -3170,6 +5846,10 @@ mod tests {
                         change: Default::default(),
                         container: Some(make_path("backup-1.zip")),
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path_zip("changed.txt"),
@@ -3180,6 +5860,10 @@ mod tests {
                         change: Default::default(),
                         container: Some(make_path("backup-2.zip")),
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                     ScannedFile {
                         path: make_restorable_path_zip("added.txt"),
@@ -3190,6 +5874,10 @@ mod tests {
                         change: Default::default(),
                         container: Some(make_path("backup-2.zip")),
                         redirected: None,
+                        redirect_rule: None,
+                        sqlite_safe_copy: false,
+                        junk: false,
+                        migrated_from: None,
                     },
                 },
                 layout.restorable_files(&BackupId::Latest, false, &[], &Default::default()),
@@ -3243,6 +5931,7 @@ mod tests {
                         },
                         ..Default::default()
                     }]),
+                    last_backup_id: 0,
                 },
                 Retention {
                     full: 1,
@@ -3279,6 +5968,10 @@ mod tests {
                             change: ScanChange::New,
                             container: None,
                             redirected: None,
+                            redirect_rule: None,
+                            sqlite_safe_copy: false,
+                            junk: false,
+                            migrated_from: None,
                         },
                         ScannedFile {
                             path: restorable_file_simple(".", "file2.txt"),
@@ -3289,10 +5982,18 @@ mod tests {
                             change: ScanChange::New,
                             container: None,
                             redirected: None,
+                            redirect_rule: None,
+                            sqlite_safe_copy: false,
+                            junk: false,
+                            migrated_from: None,
                         },
                     },
                     available_backups: backups.clone(),
                     backup: Some(backups[0].clone()),
+                    local_states: btreemap! {
+                        make_original_path("/file1.txt") => LocalState::Missing,
+                        make_original_path("/file2.txt") => LocalState::Missing,
+                    },
                     ..Default::default()
                 },
                 layout.scan_for_restoration(
@@ -3300,18 +6001,20 @@ mod tests {
                     &BackupId::Latest,
                     &[],
                     &Default::default(),
-                    &Default::default()
+                    &Default::default(),
+                    false,
+                    None,
+                    Default::default(),
+                    true,
+                    &Default::default(),
                 ),
             );
         }
 
         #[test]
         fn can_scan_game_for_restoration_with_registry() {
-            let mut layout = BackupLayout::new(
-                StrictPath::new(format!("{}/tests/backup", repo())),
-                Retention::default(),
-            )
-            .game_layout("game3");
+            let mut layout = BackupLayout::new(StrictPath::new(format!("{}/tests/backup", repo())), &Config::default())
+                .game_layout("game3");
             if cfg!(target_os = "windows") {
                 assert_eq!(
                     ScanInfo {
@@ -3327,6 +6030,7 @@ mod tests {
                         },
                         available_backups: vec![Backup::Full(FullBackup {
                             name: ".".to_string(),
+                            id: Some(1),
                             when: now(),
                             registry: IndividualMappingRegistry {
                                 hash: Some("4e2cab4b4e3ab853e5767fae35f317c26c655c52".into()),
@@ -3335,6 +6039,7 @@ mod tests {
                         })],
                         backup: Some(Backup::Full(FullBackup {
                             name: ".".to_string(),
+                            id: Some(1),
                             when: now(),
                             registry: IndividualMappingRegistry {
                                 hash: Some("4e2cab4b4e3ab853e5767fae35f317c26c655c52".into()),
@@ -3348,7 +6053,12 @@ mod tests {
                         &BackupId::Latest,
                         &[],
                         &Default::default(),
-                        &Default::default()
+                        &Default::default(),
+                        false,
+                        None,
+                        Default::default(),
+                        true,
+                        &Default::default(),
                     ),
                 );
             } else {
@@ -3357,6 +6067,7 @@ mod tests {
                         game_name: s("game3"),
                         available_backups: vec![Backup::Full(FullBackup {
                             name: ".".to_string(),
+                            id: Some(1),
                             when: now(),
                             registry: IndividualMappingRegistry {
                                 hash: Some("4e2cab4b4e3ab853e5767fae35f317c26c655c52".into()),
@@ -3365,6 +6076,7 @@ mod tests {
                         })],
                         backup: Some(Backup::Full(FullBackup {
                             name: ".".to_string(),
+                            id: Some(1),
                             when: now(),
                             registry: IndividualMappingRegistry {
                                 hash: Some("4e2cab4b4e3ab853e5767fae35f317c26c655c52".into())
@@ -3378,7 +6090,12 @@ mod tests {
                         &BackupId::Latest,
                         &[],
                         &Default::default(),
-                        &Default::default()
+                        &Default::default(),
+                        false,
+                        None,
+                        Default::default(),
+                        true,
+                        &Default::default(),
                     ),
                 );
             }
@@ -3579,5 +6296,1158 @@ mod tests {
             };
             assert!(!layout.validate(BackupId::Latest));
         }
+
+        #[test]
+        fn can_measure_backup_sizes() {
+            let layout = GameLayout {
+                path: StrictPath::new(format!("{}/tests/backup/game1-zipped", repo_raw())),
+                ..Default::default()
+            };
+            let files = btreemap! {
+                mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                mapping_file_key("/file2.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+            };
+
+            let zipped = Backup::Full(FullBackup {
+                name: "test.zip".into(),
+                files: files.clone(),
+                ..Default::default()
+            });
+            assert_eq!(245, layout.backup_bytes(&zipped));
+            assert_eq!(2, zipped.file_count());
+
+            let simple = Backup::Full(FullBackup {
+                name: ".".into(),
+                files,
+                ..Default::default()
+            });
+            assert_eq!(3, layout.backup_bytes(&simple));
+
+            let differential = Backup::Differential(DifferentialBackup {
+                name: "backup-diff".into(),
+                files: btreemap! {
+                    mapping_file_key("/file1.txt") => None,
+                    mapping_file_key("/file2.txt") => Some(IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 }),
+                },
+                ..Default::default()
+            });
+            assert_eq!(2, layout.backup_bytes(&differential));
+            assert_eq!(1, differential.file_count());
+        }
+
+        #[test]
+        fn can_check_consistency_when_consistent() {
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![FullBackup {
+                        name: ".".into(),
+                        files: btreemap! {
+                            mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                            mapping_file_key("/file2.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+                        },
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                path: StrictPath::new(format!("{}/tests/backup/game1", repo_raw())),
+                ..Default::default()
+            };
+            assert_eq!(Vec::<Discrepancy>::new(), layout.check_consistency());
+        }
+
+        #[test]
+        fn can_check_consistency_when_inconsistent() {
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![FullBackup {
+                        name: ".".into(),
+                        files: btreemap! {
+                            mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                            mapping_file_key("/fake.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+                        },
+                        children: VecDeque::from(vec![DifferentialBackup {
+                            name: "backup-20240101T000000Z-diff.zip".into(),
+                            files: btreemap! {
+                                mapping_file_key("/file1.txt") => None,
+                            },
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+                path: StrictPath::new(format!("{}/tests/backup/game1", repo_raw())),
+                ..Default::default()
+            };
+            assert_eq!(
+                vec![
+                    Discrepancy::MissingBackup {
+                        backup: s("backup-20240101T000000Z-diff.zip")
+                    },
+                    Discrepancy::MissingFile {
+                        backup: s("."),
+                        file: mapping_file_key("/fake.txt")
+                    },
+                    Discrepancy::UnknownFile {
+                        path: StrictPath::new(format!("{}/tests/backup/game1/./drive-X/file2.txt", repo_raw()))
+                            .render()
+                    },
+                ],
+                layout.check_consistency()
+            );
+        }
+
+        #[test]
+        fn can_verify_simple_backup() {
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    name: s("game1"),
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![FullBackup {
+                        name: ".".into(),
+                        files: btreemap! {
+                            mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                            mapping_file_key("/file2.txt") => IndividualMappingFile { hash: "wrong".into(), size: 2 },
+                            mapping_file_key("/fake.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+                        },
+                        ..Default::default()
+                    }]),
+                    last_backup_id: 0,
+                },
+                path: StrictPath::new(format!("{}/tests/backup/game1", repo_raw())),
+                ..Default::default()
+            };
+            let stored = |name: &str, hash: &str, size: u64| ScannedFile {
+                path: layout.mapping.game_file_immutable(
+                    &layout.path,
+                    &StrictPath::new(mapping_file_key(&format!("/{name}"))),
+                    ".",
+                ),
+                size,
+                hash: hash.to_string(),
+                change: ScanChange::Same,
+                ..Default::default()
+            };
+
+            let (scan_info, backup_info) = layout.verify(None);
+            assert_eq!(
+                hashset! {
+                    stored("file1.txt", "3a52ce780950d4d969792a2559cd519d7ee8c727", 1),
+                    stored("file2.txt", "wrong", 2),
+                    stored("fake.txt", "9d891e731f75deae56884d79e9816736b7488080", 2),
+                },
+                scan_info.found_files
+            );
+            assert_eq!(
+                hashmap! {
+                    stored("file2.txt", "wrong", 2) => TRANSLATOR.failure_hash_mismatch(),
+                    stored("fake.txt", "9d891e731f75deae56884d79e9816736b7488080", 2) => TRANSLATOR.failure_missing_from_backup(),
+                },
+                backup_info.failed_files
+            );
+        }
+
+        #[test]
+        fn can_verify_zip_backup() {
+            let layout = GameLayout {
+                mapping: IndividualMapping {
+                    name: s("game1-zipped"),
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![FullBackup {
+                        name: "test.zip".into(),
+                        files: btreemap! {
+                            mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                            mapping_file_key("/file2.txt") => IndividualMappingFile { hash: "wrong".into(), size: 2 },
+                            mapping_file_key("/fake.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+                        },
+                        children: VecDeque::from(vec![DifferentialBackup {
+                            name: "missing.zip".into(),
+                            files: btreemap! {
+                                mapping_file_key("/file1.txt") => Some(IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 }),
+                            },
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }]),
+                    last_backup_id: 0,
+                },
+                path: StrictPath::new(format!("{}/tests/backup/game1-zipped", repo_raw())),
+                ..Default::default()
+            };
+            let stored = |name: &str, hash: &str, size: u64| ScannedFile {
+                path: StrictPath::new(format!(
+                    "{}/tests/backup/game1-zipped/test.zip/drive-X/{name}",
+                    repo_raw()
+                )),
+                size,
+                hash: hash.to_string(),
+                change: ScanChange::Same,
+                ..Default::default()
+            };
+
+            let (scan_info, backup_info) = layout.verify(Some("test.zip"));
+            assert_eq!(
+                hashset! {
+                    stored("file1.txt", "3a52ce780950d4d969792a2559cd519d7ee8c727", 1),
+                    stored("file2.txt", "wrong", 2),
+                    stored("fake.txt", "9d891e731f75deae56884d79e9816736b7488080", 2),
+                },
+                scan_info.found_files
+            );
+            assert_eq!(
+                hashmap! {
+                    stored("file2.txt", "wrong", 2) => TRANSLATOR.failure_hash_mismatch(),
+                    stored("fake.txt", "9d891e731f75deae56884d79e9816736b7488080", 2) => TRANSLATOR.failure_missing_from_backup(),
+                },
+                backup_info.failed_files
+            );
+
+            let (scan_info, backup_info) = layout.verify(Some("missing.zip"));
+            let archive = ScannedFile {
+                path: StrictPath::new(format!("{}/tests/backup/game1-zipped/missing.zip", repo_raw())),
+                size: 1,
+                change: ScanChange::Same,
+                ..Default::default()
+            };
+            assert_eq!(hashset! { archive.clone() }, scan_info.found_files);
+            assert_eq!(
+                hashmap! { archive => TRANSLATOR.failure_missing_backup() },
+                backup_info.failed_files
+            );
+        }
+
+        #[test]
+        fn can_repair_inconsistent_mapping() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-repair-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let mut layout = GameLayout {
+                mapping: IndividualMapping {
+                    name: s("game1"),
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![
+                        FullBackup {
+                            name: "backup-1".into(),
+                            files: btreemap! {
+                                mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                            },
+                            children: VecDeque::from(vec![DifferentialBackup {
+                                name: "backup-2".into(),
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        FullBackup {
+                            name: "backup-3".into(),
+                            files: btreemap! {
+                                mapping_file_key("/file1.txt") => IndividualMappingFile { hash: "3a52ce780950d4d969792a2559cd519d7ee8c727".into(), size: 1 },
+                                mapping_file_key("/file2.txt") => IndividualMappingFile { hash: "9d891e731f75deae56884d79e9816736b7488080".into(), size: 2 },
+                            },
+                            ..Default::default()
+                        },
+                    ]),
+                    last_backup_id: 0,
+                },
+                path: StrictPath::from(dir.as_path()),
+                ..Default::default()
+            };
+            let stored = layout.mapping.game_file_immutable(
+                &layout.path,
+                &StrictPath::new(mapping_file_key("/file1.txt")),
+                "backup-3",
+            );
+            std::fs::create_dir_all(stored.interpret()).unwrap();
+            std::fs::remove_dir(stored.interpret()).unwrap();
+            std::fs::write(stored.interpret(), "1").unwrap();
+
+            let discrepancies = layout.check_consistency();
+            assert_eq!(
+                vec![
+                    Discrepancy::MissingBackup { backup: s("backup-1") },
+                    Discrepancy::MissingFile {
+                        backup: s("backup-3"),
+                        file: mapping_file_key("/file2.txt")
+                    },
+                ],
+                discrepancies
+            );
+
+            layout.repair(&discrepancies);
+            assert_eq!(Vec::<Discrepancy>::new(), layout.check_consistency());
+            assert_eq!(
+                vec![s("backup-3")],
+                layout
+                    .mapping
+                    .backups
+                    .iter()
+                    .map(|x| x.name.clone())
+                    .collect::<Vec<_>>()
+            );
+            assert!(StrictPath::from(dir.join("mapping.yaml").as_path()).is_file());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn can_summarize_size_history() {
+            let file = |size| IndividualMappingFile { hash: s("x"), size };
+            let layout = GameLayout {
+                path: StrictPath::new(s("/backups/game1")),
+                mapping: IndividualMapping {
+                    name: s("game1"),
+                    drives: drives_x_always(),
+                    backups: VecDeque::from(vec![
+                        FullBackup {
+                            name: s("backup-1"),
+                            when: now(),
+                            files: btreemap! {
+                                s("X:/a") => file(100),
+                                s("X:/b") => file(50),
+                            },
+                            children: VecDeque::from(vec![DifferentialBackup {
+                                name: s("backup-2"),
+                                when: now(),
+                                files: btreemap! {
+                                    s("X:/a") => Some(file(120)),
+                                    s("X:/b") => None,
+                                    s("X:/c") => Some(file(10)),
+                                },
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                        FullBackup {
+                            name: s("backup-3"),
+                            when: now(),
+                            files: btreemap! { s("X:/a") => file(200) },
+                            ..Default::default()
+                        },
+                    ]),
+                    last_backup_id: 0,
+                },
+                retention: Retention::default(),
+                settings: LayoutSettings::default(),
+            };
+
+            let entry = |backup: &str, kind, bytes, files, delta| SizeHistoryEntry {
+                game: s("game1"),
+                backup: s(backup),
+                when: now(),
+                kind,
+                bytes,
+                files,
+                delta,
+            };
+            assert_eq!(
+                vec![
+                    entry("backup-1", BackupKind::Full, 150, 2, None),
+                    entry("backup-2", BackupKind::Differential, 130, 2, Some(-20)),
+                    entry("backup-3", BackupKind::Full, 200, 1, Some(70)),
+                ],
+                layout.size_history()
+            );
+        }
+
+        #[test]
+        fn can_measure_disk_usage() {
+            let mut layout = GameLayout::load(
+                StrictPath::new(format!("{}/tests/backup/game1", repo_raw())),
+                Retention::default(),
+                LayoutSettings::default(),
+            )
+            .unwrap();
+            layout.mapping.backups[0].files.insert(
+                s("X:/missing.txt"),
+                IndividualMappingFile {
+                    hash: s("abc"),
+                    size: 10,
+                },
+            );
+            let zipped = GameLayout::load(
+                StrictPath::new(format!("{}/tests/backup/game1-zipped", repo_raw())),
+                Retention::default(),
+                LayoutSettings::default(),
+            )
+            .unwrap();
+
+            let usage = |layout: &GameLayout| {
+                layout
+                    .disk_usage()
+                    .into_iter()
+                    .map(|(backup, bytes, missing)| (backup.name().to_string(), bytes, missing))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(vec![(s("."), 3, 1)], usage(&layout));
+            assert_eq!(vec![(s("test.zip"), 245, 0)], usage(&zipped));
+        }
+
+        #[test]
+        fn can_compare_restorable_files_with_local_files() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-local-state-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let at = |secs| std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+            let path = |name: &str| StrictPath::from(dir.join(name).as_path());
+            let write = |name: &str, secs| {
+                std::fs::write(dir.join(name), name).unwrap();
+                path(name).set_mtime(at(secs)).unwrap();
+            };
+            write("backup.sav", 1_000);
+            write("newer.sav", 5_000);
+            write("older.sav", 500);
+
+            let file = |target: &str, change| ScannedFile {
+                path: path("backup.sav"),
+                original_path: Some(path(target)),
+                change,
+                ..Default::default()
+            };
+            let files = hashset! {
+                file("newer.sav", ScanChange::Different),
+                file("older.sav", ScanChange::Different),
+                file("same.sav", ScanChange::Same),
+                file("missing.sav", ScanChange::New),
+            };
+
+            assert_eq!(
+                btreemap! {
+                    path("missing.sav") => LocalState::Missing,
+                    path("newer.sav") => LocalState::NewerLocal,
+                    path("older.sav") => LocalState::OlderLocal,
+                    path("same.sav") => LocalState::Same,
+                },
+                GameLayout::default().local_states(&files),
+            );
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod adoption {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        fn sandbox(label: &str) -> (std::path::PathBuf, BackupLayout) {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-adopt-{label}-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups")).unwrap();
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default());
+            (dir, layout)
+        }
+
+        fn now() -> chrono::DateTime<chrono::Utc> {
+            chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        }
+
+        fn key(file: &str) -> String {
+            format!("X:{file}")
+        }
+
+        #[test]
+        fn can_adopt_folder_without_mapping() {
+            let (dir, layout) = sandbox("legacy");
+            let source = dir.join("old-tool/Game One");
+            std::fs::create_dir_all(source.join("drive-X/saves")).unwrap();
+            std::fs::write(source.join("drive-X/saves/slot1.sav"), "1").unwrap();
+
+            let target = layout
+                .adopt(&StrictPath::from(source.as_path()), "game1", &now())
+                .unwrap();
+            assert!(!source.exists());
+
+            let layout = BackupLayout::new(layout.base.clone(), &Config::default());
+            assert_eq!(target, layout.game_folder("game1"));
+            let mapping = layout.game_layout("game1").mapping;
+            assert_eq!(s("game1"), mapping.name);
+            assert_eq!(hashmap! { s("drive-X") => s("X:") }, mapping.drives);
+            assert_eq!(1, mapping.backups.len());
+            assert_eq!(".", mapping.backups[0].name);
+            assert_eq!(now(), mapping.backups[0].when);
+            assert_eq!(
+                btreemap! {
+                    key("/saves/slot1.sav") => IndividualMappingFile {
+                        hash: s("356a192b7913b04c54574d18c28d46e6395428ab"),
+                        size: 1,
+                    },
+                },
+                mapping.backups[0].files
+            );
+            assert!(layout.game_layout("game1").validate(BackupId::Latest));
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn can_adopt_folder_with_mapping_for_another_name() {
+            let (dir, layout) = sandbox("renamed");
+            let source = dir.join("renamed");
+            std::fs::create_dir_all(source.join("backup-1/drive-X")).unwrap();
+            IndividualMapping {
+                name: s("Old Name"),
+                drives: hashmap! { s("drive-X") => s("X:") },
+                backups: VecDeque::from(vec![FullBackup {
+                    name: s("backup-1"),
+                    ..Default::default()
+                }]),
+                last_backup_id: 0,
+            }
+            .save(&StrictPath::from(source.join("mapping.yaml").as_path()));
+
+            layout
+                .adopt(&StrictPath::from(source.as_path()), "game1", &now())
+                .unwrap();
+
+            let layout = BackupLayout::new(layout.base.clone(), &Config::default());
+            assert_eq!(vec![s("game1")], layout.restorable_games());
+            assert_eq!(s("backup-1"), layout.game_layout("game1").mapping.backups[0].name);
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn can_adopt_zip() {
+            let (dir, layout) = sandbox("zip");
+            let source = dir.join("saves.zip");
+            {
+                let mut zip = zip::ZipWriter::new(std::fs::File::create(&source).unwrap());
+                zip.start_file("drive-X/saves/slot1.sav", zip::write::FileOptions::default())
+                    .unwrap();
+                zip.write_all(b"1").unwrap();
+                zip.finish().unwrap();
+            }
+
+            layout
+                .adopt(&StrictPath::from(source.as_path()), "game1", &now())
+                .unwrap();
+
+            let layout = BackupLayout::new(layout.base.clone(), &Config::default());
+            let game_layout = layout.game_layout("game1");
+            let backup = &game_layout.mapping.backups[0];
+            assert_eq!("backup-20240102T030405Z.zip", backup.name);
+            assert_eq!(
+                btreemap! {
+                    key("/saves/slot1.sav") => IndividualMappingFile {
+                        hash: s("356a192b7913b04c54574d18c28d46e6395428ab"),
+                        size: 1,
+                    },
+                },
+                backup.files
+            );
+            assert!(game_layout.path.joined(&backup.name).is_file());
+            assert!(game_layout.validate(BackupId::Latest));
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn can_move_by_copying_when_renaming_is_not_possible() {
+            let (dir, _) = sandbox("copy");
+            let source = dir.join("source");
+            std::fs::create_dir_all(source.join("drive-X/saves")).unwrap();
+            std::fs::write(source.join("drive-X/saves/slot1.sav"), "1").unwrap();
+            std::fs::write(source.join("mapping.yaml"), "name: game1").unwrap();
+            let target = dir.join("backups/game1");
+
+            BackupLayout::copy_then_remove(
+                "game1",
+                &StrictPath::from(source.as_path()),
+                &StrictPath::from(target.as_path()),
+            )
+            .unwrap();
+
+            assert!(!source.exists());
+            assert_eq!(
+                "1",
+                std::fs::read_to_string(target.join("drive-X/saves/slot1.sav")).unwrap()
+            );
+            assert_eq!(
+                "name: game1",
+                std::fs::read_to_string(target.join("mapping.yaml")).unwrap()
+            );
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn refuses_to_adopt_zip_with_unsafe_entries() {
+            let (dir, layout) = sandbox("unsafe-zip");
+            let make_zip = |name: &str, entry: &str| {
+                let source = dir.join(name);
+                let mut zip = zip::ZipWriter::new(std::fs::File::create(&source).unwrap());
+                zip.start_file("drive-X/saves/slot1.sav", zip::write::FileOptions::default())
+                    .unwrap();
+                zip.write_all(b"1").unwrap();
+                zip.start_file(entry, zip::write::FileOptions::default()).unwrap();
+                zip.write_all(b"2").unwrap();
+                zip.finish().unwrap();
+                source
+            };
+            let adopt = |source: &std::path::Path| layout.adopt(&StrictPath::from(source), "game1", &now());
+
+            let source = make_zip("escape.zip", "drive-X/../../evil.sav");
+            assert_eq!(
+                Err(AdoptionProblem::UnsafeZipEntry {
+                    problem: UnsafeZipEntry::EscapesRoot {
+                        entry: s("drive-X/../../evil.sav")
+                    }
+                }),
+                adopt(&source)
+            );
+            assert!(source.exists());
+
+            let source = make_zip("other-drive.zip", "drive-X/../drive-Y/evil.sav");
+            assert_eq!(
+                Err(AdoptionProblem::UnsafeZipEntry {
+                    problem: UnsafeZipEntry::EscapesRoot {
+                        entry: s("drive-X/../drive-Y/evil.sav")
+                    }
+                }),
+                adopt(&source)
+            );
+
+            let source = make_zip("absolute.zip", "/etc/evil.sav");
+            assert_eq!(
+                Err(AdoptionProblem::UnsafeZipEntry {
+                    problem: UnsafeZipEntry::AbsolutePath {
+                        entry: s("/etc/evil.sav")
+                    }
+                }),
+                adopt(&source)
+            );
+            assert!(!layout.game_folder("game1").exists());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn explains_why_source_cannot_be_adopted() {
+            let (dir, layout) = sandbox("invalid");
+            let adopt = |source: &std::path::Path| layout.adopt(&StrictPath::from(source), "game1", &now());
+
+            assert_eq!(Err(AdoptionProblem::SourceMissing), adopt(&dir.join("nope")));
+
+            std::fs::create_dir_all(dir.join("empty/saves")).unwrap();
+            assert_eq!(Err(AdoptionProblem::NoDriveFolders), adopt(&dir.join("empty")));
+
+            std::fs::create_dir_all(dir.join("unc/drive-__remote")).unwrap();
+            assert_eq!(
+                Err(AdoptionProblem::UnknownDriveFolder {
+                    folder: s("drive-__remote")
+                }),
+                adopt(&dir.join("unc"))
+            );
+
+            std::fs::create_dir_all(dir.join("partial/backup-1")).unwrap();
+            IndividualMapping {
+                name: s("game1"),
+                drives: HashMap::new(),
+                backups: VecDeque::from(vec![FullBackup {
+                    name: s("backup-1"),
+                    children: VecDeque::from(vec![DifferentialBackup {
+                        name: s("backup-2"),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }]),
+                last_backup_id: 0,
+            }
+            .save(&StrictPath::from(dir.join("partial/mapping.yaml").as_path()));
+            assert_eq!(
+                Err(AdoptionProblem::MissingBackup { backup: s("backup-2") }),
+                adopt(&dir.join("partial"))
+            );
+
+            std::fs::write(dir.join("broken.zip"), "not a zip").unwrap();
+            assert!(matches!(
+                adopt(&dir.join("broken.zip")),
+                Err(AdoptionProblem::UnreadableZip { .. })
+            ));
+
+            std::fs::create_dir_all(dir.join("backups/game1")).unwrap();
+            std::fs::create_dir_all(dir.join("valid/drive-X")).unwrap();
+            assert_eq!(
+                Err(AdoptionProblem::DestinationOccupied {
+                    path: layout.game_folder("game1")
+                }),
+                adopt(&dir.join("valid"))
+            );
+            assert!(dir.join("valid").exists());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod unsafe_zip {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn fails_every_file_from_a_zip_with_an_unsafe_entry() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-unsafe-zip-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups/game1")).unwrap();
+            std::fs::create_dir_all(dir.join("restored")).unwrap();
+
+            let container = dir.join("backups/game1/backup-1.zip");
+            {
+                let mut zip = zip::ZipWriter::new(std::fs::File::create(&container).unwrap());
+                zip.start_file("drive-0/../../evil.sav", zip::write::FileOptions::default())
+                    .unwrap();
+                zip.write_all(b"1").unwrap();
+                zip.start_file("drive-0/slot1.sav", zip::write::FileOptions::default())
+                    .unwrap();
+                zip.write_all(b"2").unwrap();
+                zip.finish().unwrap();
+            }
+
+            let restorable = |entry: &str, target: &str| ScannedFile {
+                path: StrictPath::new(entry.to_string()),
+                size: 1,
+                original_path: Some(StrictPath::from(dir.join("restored").join(target).as_path())),
+                change: ScanChange::New,
+                container: Some(StrictPath::from(container.as_path())),
+                ..Default::default()
+            };
+            let evil = restorable("drive-0/../../evil.sav", "evil.sav");
+            let normal = restorable("drive-0/slot1.sav", "slot1.sav");
+
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default())
+                .game_layout("game1");
+            let scan = ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! { evil.clone(), normal.clone() },
+                ..Default::default()
+            };
+            let info = layout.restore(&scan, &Default::default());
+
+            assert!(!dir.join("restored/evil.sav").exists());
+            assert!(!dir.join("restored/slot1.sav").exists());
+
+            let problem = UnsafeZipEntry::EscapesRoot {
+                entry: s("drive-0/../../evil.sav"),
+            };
+            assert_eq!(
+                hashmap! { evil.clone() => problem.to_string(), normal.clone() => problem.to_string() },
+                info.failed_files
+            );
+            assert_eq!(
+                hashmap! { evil => problem.clone(), normal => problem },
+                info.unsafe_zip_entries
+            );
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn ignores_registry_content_beyond_the_size_limit() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-zip-registry-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups/game1")).unwrap();
+
+            let write = |name: &str, content: &[u8]| {
+                let mut zip = zip::ZipWriter::new(std::fs::File::create(dir.join("backups/game1").join(name)).unwrap());
+                zip.start_file("registry.yaml", zip::write::FileOptions::default())
+                    .unwrap();
+                zip.write_all(content).unwrap();
+                zip.finish().unwrap();
+            };
+            write("backup-1.zip", b"HKEY_CURRENT_USER: {}");
+            write("backup-2.zip", &vec![b'a'; MAX_REGISTRY_SIZE as usize + 1]);
+
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default())
+                .game_layout("game1");
+            assert_eq!(
+                Some(s("HKEY_CURRENT_USER: {}")),
+                layout.registry_content_in("backup-1.zip", &BackupFormat::Zip)
+            );
+            assert_eq!(None, layout.registry_content_in("backup-2.zip", &BackupFormat::Zip));
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod zero_filled_zip {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn can_restore_a_zero_filled_file_from_a_zip() {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-zero-filled-zip-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups/game1")).unwrap();
+            std::fs::create_dir_all(dir.join("restored")).unwrap();
+
+            let content = vec![0; 8 * 1024 * 1024];
+            let container = dir.join("backups/game1/backup-1.zip");
+            {
+                let mut zip = zip::ZipWriter::new(std::fs::File::create(&container).unwrap());
+                zip.start_file(
+                    "drive-0/sparse.sav",
+                    zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Bzip2),
+                )
+                .unwrap();
+                zip.write_all(&content).unwrap();
+                zip.finish().unwrap();
+            }
+
+            let file = ScannedFile {
+                path: StrictPath::new(s("drive-0/sparse.sav")),
+                size: content.len() as u64,
+                original_path: Some(StrictPath::from(dir.join("restored/sparse.sav").as_path())),
+                change: ScanChange::New,
+                container: Some(StrictPath::from(container.as_path())),
+                ..Default::default()
+            };
+
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default())
+                .game_layout("game1");
+            let scan = ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! { file },
+                ..Default::default()
+            };
+            let info = layout.restore(&scan, &Default::default());
+
+            assert_eq!(HashMap::new(), info.failed_files);
+            assert_eq!(content, std::fs::read(dir.join("restored/sparse.sav")).unwrap());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod changed_during_backup {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        fn sandbox(label: &str) -> (std::path::PathBuf, BackupLayout, ScannedFile) {
+            let dir = std::env::temp_dir().join(format!("ludusavi-test-changed-{label}-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(dir.join("backups")).unwrap();
+            std::fs::create_dir_all(dir.join("game")).unwrap();
+            let source = dir.join("game/slot1.sav");
+            std::fs::write(&source, "1").unwrap();
+
+            let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default());
+            let file = ScannedFile {
+                path: StrictPath::from(source.as_path()),
+                size: 1,
+                hash: s("356a192b7913b04c54574d18c28d46e6395428ab"),
+                change: ScanChange::New,
+                ..Default::default()
+            };
+            (dir, layout, file)
+        }
+
+        fn back_up(layout: &BackupLayout, file: &ScannedFile, format: BackupFormat) -> (BackupInfo, FullBackup) {
+            let mut game_layout = layout.game_layout("game1");
+            let scan = ScanInfo {
+                game_name: s("game1"),
+                found_files: hashset! { file.clone() },
+                ..Default::default()
+            };
+            let format = BackupFormats {
+                chosen: format,
+                ..Default::default()
+            };
+            let info = game_layout.back_up(&scan, &chrono::Utc::now(), &format, &BackupLabel::default());
+            let backup = layout.game_layout("game1").mapping.backups[0].clone();
+            (info, backup)
+        }
+
+        #[test]
+        fn records_scanned_hash_when_file_is_unchanged() {
+            for (label, format) in [("same-simple", BackupFormat::Simple), ("same-zip", BackupFormat::Zip)] {
+                let (dir, layout, file) = sandbox(label);
+
+                let (info, backup) = back_up(&layout, &file, format);
+                assert!(info.changed_during_backup.is_empty());
+                assert_eq!(
+                    btreemap! {
+                        file.path.render() => IndividualMappingFile {
+                            hash: s("356a192b7913b04c54574d18c28d46e6395428ab"),
+                            size: 1,
+                        },
+                    },
+                    backup.files
+                );
+
+                std::fs::remove_dir_all(&dir).unwrap();
+            }
+        }
+
+        #[test]
+        fn records_stored_hash_when_file_changes_after_scan() {
+            for (label, format) in [("diff-simple", BackupFormat::Simple), ("diff-zip", BackupFormat::Zip)] {
+                let (dir, layout, file) = sandbox(label);
+                std::fs::write(file.path.as_std_path_buf(), "22").unwrap();
+
+                let stored = IndividualMappingFile {
+                    hash: s("12c6fc06c99a462375eeb3f43dfd832b08ca9e17"),
+                    size: 2,
+                };
+                let (info, backup) = back_up(&layout, &file, format);
+                assert_eq!(hashmap! { file.clone() => stored.clone() }, info.changed_during_backup);
+                assert_eq!(btreemap! { file.path.render() => stored }, backup.files);
+                assert!(layout.game_layout("game1").validate(BackupId::Latest));
+
+                std::fs::remove_dir_all(&dir).unwrap();
+            }
+        }
+    }
+
+    mod reincluded_files {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+        use crate::{
+            resource::{
+                config::{BackupFilter, ToggledPaths, ToggledRegistry},
+                manifest::Manifest,
+                ResourceFile,
+            },
+            scan::{launchers::Launchers, scan_game_for_backup},
+        };
+
+        const HASH_A1: &str = "356a192b7913b04c54574d18c28d46e6395428ab";
+        const HASH_A2: &str = "da4b9237bacccdf19c0760cab7aec4a8359010b0";
+        const HASH_B: &str = "77de68daecd823babbb58edb1c8e14d7106e83bb";
+
+        struct Sandbox {
+            dir: std::path::PathBuf,
+            manifest: Manifest,
+        }
+
+        impl Sandbox {
+            fn new(label: &str) -> Self {
+                let dir = std::env::temp_dir().join(format!("ludusavi-test-reincluded-{label}-{}", std::process::id()));
+                _ = std::fs::remove_dir_all(&dir);
+                std::fs::create_dir_all(dir.join("backups")).unwrap();
+                std::fs::create_dir_all(dir.join("game")).unwrap();
+                std::fs::write(dir.join("game/a.sav"), "1").unwrap();
+                std::fs::write(dir.join("game/b.sav"), "3").unwrap();
+
+                let manifest = Manifest::load_from_string(&format!(
+                    "game1:\n  files:\n    '{}/game/*.sav': {{}}\n",
+                    StrictPath::from(dir.as_path()).render()
+                ))
+                .unwrap();
+
+                Self { dir, manifest }
+            }
+
+            fn file(&self, name: &str) -> StrictPath {
+                StrictPath::from(self.dir.join("game").join(name).as_path()).canonical()
+            }
+
+            fn layout(&self) -> BackupLayout {
+                BackupLayout::new(StrictPath::from(self.dir.join("backups").as_path()), &Config::default())
+                    .with_retention(Retention {
+                        full: 1,
+                        differential: 5,
+                        ..Default::default()
+                    })
+            }
+
+            fn ignoring(&self, name: &str) -> ToggledPaths {
+                ToggledPaths::new(btreemap! {
+                    s("game1") => btreemap! { self.file(name) => false },
+                })
+            }
+
+            fn back_up(&self, ignored: &ToggledPaths) -> ScanInfo {
+                let layout = self.layout();
+                let scan = scan_game_for_backup(
+                    &self.manifest.0["game1"],
+                    "game1",
+                    &[],
+                    &StrictPath::new(repo()),
+                    &Launchers::default(),
+                    &BackupFilter::default(),
+                    &None,
+                    ignored,
+                    &ToggledRegistry::default(),
+                    layout.latest_backup("game1", false, &[], ignored),
+                    &[],
+                    &Default::default(),
+                    Default::default(),
+                    &Default::default(),
+                );
+                let format = BackupFormats {
+                    chosen: BackupFormat::Simple,
+                    ..Default::default()
+                };
+                layout
+                    .game_layout("game1")
+                    .back_up(&scan, &chrono::Utc::now(), &format, &BackupLabel::default());
+                scan
+            }
+
+            fn change(scan: &ScanInfo, path: &StrictPath) -> ScanChange {
+                scan.found_files.iter().find(|x| &x.path == path).unwrap().change()
+            }
+
+            fn latest_files(&self) -> BTreeMap<String, String> {
+                self.layout()
+                    .game_layout("game1")
+                    .latest_files()
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.hash.clone()))
+                    .collect()
+            }
+
+            fn stored_copies(&self, name: &str) -> usize {
+                walkdir::WalkDir::new(self.dir.join("backups"))
+                    .into_iter()
+                    .filter_map(|x| x.ok())
+                    .filter(|x| x.file_type().is_file() && x.file_name() == name)
+                    .count()
+            }
+        }
+
+        impl Drop for Sandbox {
+            fn drop(&mut self) {
+                _ = std::fs::remove_dir_all(&self.dir);
+            }
+        }
+
+        #[test]
+        fn treats_unignored_file_as_unchanged_since_earlier_backup_in_chain() {
+            let sandbox = Sandbox::new("same");
+            let a = sandbox.file("a.sav");
+            let b = sandbox.file("b.sav");
+
+            sandbox.back_up(&ToggledPaths::default());
+
+            std::fs::write(a.as_std_path_buf(), "2").unwrap();
+            let scan = sandbox.back_up(&sandbox.ignoring("b.sav"));
+            assert_eq!(ScanChange::Removed, Sandbox::change(&scan, &b));
+            assert_eq!(btreemap! { a.render() => s(HASH_A2) }, sandbox.latest_files());
+
+            let scan = sandbox.back_up(&ToggledPaths::default());
+            assert_eq!(ScanChange::Same, Sandbox::change(&scan, &a));
+            assert_eq!(ScanChange::Same, Sandbox::change(&scan, &b));
+
+            let mapping = sandbox.layout().game_layout("game1").mapping;
+            assert_eq!(1, mapping.backups.len());
+            assert_eq!(2, mapping.backups[0].children.len());
+            assert_eq!(
+                btreemap! {
+                    a.render() => Some(IndividualMappingFile { hash: s(HASH_A2), size: 1 }),
+                },
+                mapping.backups[0].children[1].files
+            );
+            assert_eq!(
+                btreemap! { a.render() => s(HASH_A2), b.render() => s(HASH_B) },
+                sandbox.latest_files()
+            );
+            assert_eq!(1, sandbox.stored_copies("b.sav"));
+        }
+
+        #[test]
+        fn reinstates_files_inherited_from_full_backup() {
+            let sandbox = Sandbox::new("reinstate");
+            let a = sandbox.file("a.sav");
+            let b = sandbox.file("b.sav");
+
+            sandbox.back_up(&ToggledPaths::default());
+
+            std::fs::write(a.as_std_path_buf(), "2").unwrap();
+            sandbox.back_up(&sandbox.ignoring("b.sav"));
+
+            std::fs::write(a.as_std_path_buf(), "1").unwrap();
+            let scan = sandbox.back_up(&ToggledPaths::default());
+            assert_eq!(ScanChange::Different, Sandbox::change(&scan, &a));
+            assert_eq!(ScanChange::Same, Sandbox::change(&scan, &b));
+
+            let mapping = sandbox.layout().game_layout("game1").mapping;
+            assert_eq!(2, mapping.backups[0].children.len());
+            assert_eq!(BTreeMap::new(), mapping.backups[0].children[1].files);
+            assert_eq!(
+                btreemap! { a.render() => s(HASH_A1), b.render() => s(HASH_B) },
+                sandbox.latest_files()
+            );
+            assert_eq!(1, sandbox.stored_copies("b.sav"));
+        }
+    }
+
+    mod many_files {
+        use super::*;
+
+        const FILES: usize = 20_000;
+
+        #[test]
+        fn can_back_up_and_restore_many_small_files() {
+            for (label, format) in [("simple", BackupFormat::Simple), ("zip", BackupFormat::Zip)] {
+                let dir = std::env::temp_dir().join(format!("ludusavi-test-many-{label}-{}", std::process::id()));
+                _ = std::fs::remove_dir_all(&dir);
+
+                let mut found_files = HashSet::new();
+                for i in 0..FILES {
+                    let folder = dir.join(format!("game/{}/{}", i % 10, i % 100));
+                    std::fs::create_dir_all(&folder).unwrap();
+                    let source = folder.join(format!("{i}.dat"));
+                    std::fs::write(&source, i.to_string()).unwrap();
+
+                    let path = StrictPath::from(source.as_path());
+                    found_files.insert(ScannedFile {
+                        size: i.to_string().len() as u64,
+                        hash: path.sha1(),
+                        change: ScanChange::New,
+                        path,
+                        ..Default::default()
+                    });
+                }
+                let scan = ScanInfo {
+                    game_name: s("game1"),
+                    found_files,
+                    ..Default::default()
+                };
+
+                let layout = BackupLayout::new(StrictPath::from(dir.join("backups").as_path()), &Config::default());
+                let format = BackupFormats {
+                    chosen: format,
+                    ..Default::default()
+                };
+                let info =
+                    layout
+                        .game_layout("game1")
+                        .back_up(&scan, &chrono::Utc::now(), &format, &BackupLabel::default());
+                assert!(info.successful());
+
+                std::fs::remove_dir_all(dir.join("game")).unwrap();
+
+                let mut game_layout = layout.game_layout("game1");
+                let restorable = game_layout.scan_for_restoration(
+                    "game1",
+                    &BackupId::Latest,
+                    &[],
+                    &Default::default(),
+                    &Default::default(),
+                    false,
+                    None,
+                    Default::default(),
+                    true,
+                    &Default::default(),
+                );
+                assert_eq!(FILES, restorable.found_files.len());
+                assert!(game_layout.restore(&restorable, &Default::default()).successful());
+
+                for i in 0..FILES {
+                    let source = dir.join(format!("game/{}/{}/{i}.dat", i % 10, i % 100));
+                    assert_eq!(i.to_string(), std::fs::read_to_string(source).unwrap());
+                }
+
+                std::fs::remove_dir_all(&dir).unwrap();
+            }
+        }
     }
 }
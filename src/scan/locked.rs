@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use crate::lang::TRANSLATOR;
+
+/// How long to wait before trying a locked file again.
+const RETRY_LOCKED_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether a file couldn't be read because another process (usually the running game) has it open.
+/// Only Windows prevents reading files this way.
+pub fn is_locked(error: &std::io::Error) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        const ERROR_SHARING_VIOLATION: i32 = 32;
+        const ERROR_LOCK_VIOLATION: i32 = 33;
+        matches!(
+            error.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = error;
+        false
+    }
+}
+
+/// The error to report for a file, marking the ones that were locked.
+pub fn describe_failure(error: &std::io::Error, locked: bool) -> String {
+    if locked {
+        TRANSLATOR.failure_locked(&error.to_string())
+    } else {
+        error.to_string()
+    }
+}
+
+/// Try the files that were locked during a backup pass again, once the pass is done,
+/// up to `retries` more times (see `backup.retryLocked`).
+/// Each round waits once for all of the files, rather than once per file,
+/// so that other files aren't held up in the meantime.
+/// Returns the files that still couldn't be backed up, along with the last error for each.
+pub fn retry_locked<T>(
+    context: &str,
+    retries: u32,
+    locked: Vec<(T, std::io::Error)>,
+    attempt: impl FnMut(&T) -> std::io::Result<()>,
+) -> Vec<(T, std::io::Error)> {
+    retry_rounds(
+        retries,
+        RETRY_LOCKED_DELAY,
+        is_locked,
+        locked,
+        attempt,
+        |round, count| {
+            log::warn!(
+                "[{context}] {count} file(s) locked, so trying again in {:?} ({round}/{retries})",
+                RETRY_LOCKED_DELAY,
+            );
+        },
+    )
+}
+
+fn retry_rounds<T>(
+    retries: u32,
+    delay: Duration,
+    retryable: impl Fn(&std::io::Error) -> bool,
+    mut failed: Vec<(T, std::io::Error)>,
+    mut attempt: impl FnMut(&T) -> std::io::Result<()>,
+    mut on_round: impl FnMut(u32, usize),
+) -> Vec<(T, std::io::Error)> {
+    for round in 1..=retries {
+        let (pending, done): (Vec<_>, Vec<_>) = failed.into_iter().partition(|(_, e)| retryable(e));
+        failed = done;
+        if pending.is_empty() {
+            break;
+        }
+
+        on_round(round, pending.len());
+        std::thread::sleep(delay);
+        for (item, _) in pending {
+            if let Err(e) = attempt(&item) {
+                failed.push((item, e));
+            }
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn retryable(e: &Error) -> bool {
+        e.kind() == ErrorKind::WouldBlock
+    }
+
+    fn locked(items: &[u32]) -> Vec<(u32, Error)> {
+        items.iter().map(|x| (*x, Error::from(ErrorKind::WouldBlock))).collect()
+    }
+
+    fn ids(failed: &[(u32, Error)]) -> Vec<u32> {
+        failed.iter().map(|(x, _)| *x).collect()
+    }
+
+    #[test]
+    fn retries_all_locked_files_in_one_round() {
+        let mut attempts = vec![];
+        let mut rounds = vec![];
+        let failed = retry_rounds(
+            3,
+            Duration::ZERO,
+            retryable,
+            locked(&[1, 2, 3]),
+            |x| {
+                attempts.push(*x);
+                Ok(())
+            },
+            |round, count| rounds.push((round, count)),
+        );
+        assert_eq!(Vec::<u32>::new(), ids(&failed));
+        assert_eq!(vec![1, 2, 3], attempts);
+        assert_eq!(vec![(1, 3)], rounds);
+    }
+
+    #[test]
+    fn only_retries_files_that_are_still_locked() {
+        let mut attempts = vec![];
+        let mut rounds = vec![];
+        let failed = retry_rounds(
+            3,
+            Duration::ZERO,
+            retryable,
+            locked(&[1, 2]),
+            |x| {
+                attempts.push(*x);
+                if *x == 1 || attempts.len() > 3 {
+                    Ok(())
+                } else {
+                    Err(Error::from(ErrorKind::WouldBlock))
+                }
+            },
+            |round, count| rounds.push((round, count)),
+        );
+        assert_eq!(Vec::<u32>::new(), ids(&failed));
+        assert_eq!(vec![1, 2, 2, 2], attempts);
+        assert_eq!(vec![(1, 2), (2, 1), (3, 1)], rounds);
+    }
+
+    #[test]
+    fn gives_up_after_retries() {
+        let mut attempts = 0;
+        let mut rounds = vec![];
+        let failed = retry_rounds(
+            2,
+            Duration::ZERO,
+            retryable,
+            locked(&[1]),
+            |_| {
+                attempts += 1;
+                Err(Error::from(ErrorKind::WouldBlock))
+            },
+            |round, _| rounds.push(round),
+        );
+        assert_eq!(vec![1], ids(&failed));
+        assert_eq!(2, attempts);
+        assert_eq!(vec![1, 2], rounds);
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let mut attempts = 0;
+        let failed = retry_rounds(
+            5,
+            Duration::ZERO,
+            retryable,
+            locked(&[1]),
+            |_| {
+                attempts += 1;
+                Err(Error::from(ErrorKind::NotFound))
+            },
+            |_, _| {},
+        );
+        assert_eq!(vec![1], ids(&failed));
+        assert_eq!(ErrorKind::NotFound, failed[0].1.kind());
+        assert_eq!(1, attempts);
+    }
+
+    #[test]
+    fn does_nothing_without_retries() {
+        let failed = retry_rounds(
+            0,
+            Duration::ZERO,
+            retryable,
+            locked(&[1]),
+            |_| panic!("should not be retried"),
+            |_, _| panic!("should not wait"),
+        );
+        assert_eq!(vec![1], ids(&failed));
+    }
+}
@@ -0,0 +1,209 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{
+    path::StrictPath,
+    resource::manifest::{Game, Os},
+};
+
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[A-Za-z]+>").unwrap());
+
+/// A manifest path that only applies on certain operating systems.
+#[derive(Clone, Debug)]
+struct ConstrainedPath {
+    pattern: Regex,
+    /// Empty if the path applies on any OS.
+    oses: Vec<Os>,
+}
+
+/// The OSes that each of a game's save locations belong to, according to the manifest,
+/// so that a restore can skip files that were backed up on a different OS.
+#[derive(Clone, Debug, Default)]
+pub struct OsConstraints {
+    paths: Vec<ConstrainedPath>,
+}
+
+/// Convert a manifest path into a regular expression that matches it and anything inside of it.
+/// Placeholders may resolve to several folders, so they match anything.
+fn to_pattern(path: &str) -> Option<Regex> {
+    let mut pattern = String::from("(?i)^");
+    let mut rest = path.replace('\\', "/");
+
+    while !rest.is_empty() {
+        let (literal, remainder) = match PLACEHOLDER.find(&rest) {
+            Some(found) => (&rest[..found.start()], Some(&rest[found.end()..])),
+            None => (rest.as_str(), None),
+        };
+
+        let mut chars = literal.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    pattern.push_str(".*");
+                }
+                '*' => pattern.push_str("[^/]*"),
+                '?' => pattern.push_str("[^/]"),
+                c => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+
+        match remainder {
+            Some(remainder) => {
+                pattern.push_str(".*");
+                rest = remainder.to_string();
+            }
+            None => break,
+        }
+    }
+
+    pattern.push_str("(?:/.*)?$");
+    Regex::new(&pattern).ok()
+}
+
+fn is_windows_path(path: &str) -> bool {
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), Some(':')) => letter.is_ascii_alphabetic(),
+        _ => path.starts_with("//"),
+    }
+}
+
+impl OsConstraints {
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            paths: game
+                .files
+                .iter()
+                .flatten()
+                .filter_map(|(path, entry)| {
+                    let constraints = entry.when.as_deref().unwrap_or_default();
+                    let oses = if constraints.is_empty() || constraints.iter().any(|x| x.os.is_none()) {
+                        vec![]
+                    } else {
+                        constraints.iter().filter_map(|x| x.os).collect()
+                    };
+
+                    match to_pattern(path) {
+                        Some(pattern) => Some(ConstrainedPath { pattern, oses }),
+                        None => {
+                            log::debug!("Ignoring manifest path for OS constraints: {path}");
+                            None
+                        }
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether a backed up file belongs to a different OS than the current one.
+    ///
+    /// A Windows path can only be restored on Windows, and vice versa.
+    /// Between Linux and Mac, we rely on the manifest:
+    /// the file is foreign if every save location that it matches is only for other OSes.
+    /// A location for Windows is allowed if the file is inside of a Wine prefix.
+    pub fn is_foreign(&self, original: &StrictPath) -> bool {
+        self.is_foreign_on(original, Os::HOST)
+    }
+
+    fn is_foreign_on(&self, original: &StrictPath, host: Os) -> bool {
+        let path = original.raw().replace('\\', "/");
+
+        if is_windows_path(&path) {
+            return host != Os::Windows;
+        } else if !path.starts_with('/') {
+            return false;
+        } else if host == Os::Windows {
+            return true;
+        }
+
+        let in_wine_prefix = path.to_lowercase().contains("/drive_");
+        let mut matched = false;
+        for constrained in &self.paths {
+            if !constrained.pattern.is_match(&path) {
+                continue;
+            }
+            if constrained.oses.is_empty()
+                || constrained.oses.contains(&host)
+                || (in_wine_prefix && constrained.oses.contains(&Os::Windows))
+            {
+                return false;
+            }
+            if constrained.oses.iter().any(|x| *x != Os::Windows) {
+                matched = true;
+            }
+        }
+
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+
+    use super::*;
+    use crate::resource::manifest::{GameFileConstraint, GameFileEntry};
+
+    fn constraints() -> OsConstraints {
+        OsConstraints::from_game(&Game {
+            files: Some(btreemap! {
+                "<home>/.local/share/game/save".to_string() => GameFileEntry {
+                    when: Some(vec![GameFileConstraint { os: Some(Os::Linux), store: None }]),
+                    ..Default::default()
+                },
+                "<home>/Library/Application Support/game/*.sav".to_string() => GameFileEntry {
+                    when: Some(vec![GameFileConstraint { os: Some(Os::Mac), store: None }]),
+                    ..Default::default()
+                },
+                "<winAppData>/game".to_string() => GameFileEntry {
+                    when: Some(vec![GameFileConstraint { os: Some(Os::Windows), store: None }]),
+                    ..Default::default()
+                },
+                "<base>/shared".to_string() => GameFileEntry::default(),
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn foreign(path: &str, host: Os) -> bool {
+        constraints().is_foreign_on(&StrictPath::new(path.to_string()), host)
+    }
+
+    #[test]
+    fn windows_paths_only_belong_on_windows() {
+        assert!(!foreign("C:/Users/me/AppData/Roaming/game/save.dat", Os::Windows));
+        assert!(!foreign(r"C:\Users\me\AppData\Roaming\game\save.dat", Os::Windows));
+        assert!(foreign("C:/Users/me/AppData/Roaming/game/save.dat", Os::Linux));
+        assert!(foreign("C:/Users/me/AppData/Roaming/game/save.dat", Os::Mac));
+    }
+
+    #[test]
+    fn unix_paths_do_not_belong_on_windows() {
+        assert!(foreign("/home/me/.local/share/game/save/1.dat", Os::Windows));
+        assert!(foreign("/home/me/unknown.dat", Os::Windows));
+    }
+
+    #[test]
+    fn unix_paths_are_checked_against_the_manifest() {
+        assert!(!foreign("/home/me/.local/share/game/save/1.dat", Os::Linux));
+        assert!(foreign("/home/me/.local/share/game/save/1.dat", Os::Mac));
+        assert!(!foreign("/Users/me/Library/Application Support/game/1.sav", Os::Mac));
+        assert!(foreign("/Users/me/Library/Application Support/game/1.sav", Os::Linux));
+    }
+
+    #[test]
+    fn unix_paths_are_allowed_when_unconstrained_or_unknown() {
+        assert!(!foreign("/games/game/shared/1.dat", Os::Mac));
+        assert!(!foreign("/home/me/unknown.dat", Os::Linux));
+        assert!(!foreign("relative/file.dat", Os::Windows));
+    }
+
+    #[test]
+    fn windows_locations_in_a_wine_prefix_are_allowed() {
+        assert!(!foreign(
+            "/home/me/.wine/drive_c/users/me/AppData/Roaming/game/save.dat",
+            Os::Linux
+        ));
+    }
+}
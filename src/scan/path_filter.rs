@@ -0,0 +1,297 @@
+use crate::{path::StrictPath, resource::manifest::Os, scan::ScanInfo};
+
+/// Glob patterns that select which files to restore (see `restore --only-path`).
+/// `*` stays within one folder, `**` spans any number of folders,
+/// and a pattern for a folder also selects everything inside of it.
+#[derive(Clone, Debug, Default)]
+pub struct PathSelection {
+    globs: Option<globset::GlobSet>,
+    /// How many folder levels each glob spells out, not counting `**`.
+    specificity: Vec<usize>,
+}
+
+impl PathSelection {
+    pub fn new(patterns: &[String]) -> Result<Self, globset::Error> {
+        if patterns.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut builder = globset::GlobSetBuilder::new();
+        let mut specificity = vec![];
+        for pattern in patterns {
+            let normalized = crate::path::parse_home(pattern).replace('\\', "/");
+            let normalized = normalized.trim_end_matches('/');
+            let levels = normalized
+                .split('/')
+                .filter(|level| !level.is_empty() && *level != "**")
+                .count();
+
+            for variant in [normalized.to_string(), format!("{normalized}/**")] {
+                builder.add(
+                    globset::GlobBuilder::new(&variant)
+                        .literal_separator(true)
+                        .backslash_escape(false)
+                        .case_insensitive(!Os::HOST.is_case_sensitive())
+                        .build()?,
+                );
+                specificity.push(levels);
+            }
+        }
+
+        Ok(Self {
+            globs: Some(builder.build()?),
+            specificity,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_none()
+    }
+
+    /// Without any patterns, everything is selected.
+    pub fn includes(&self, path: &StrictPath) -> bool {
+        self.globs.as_ref().is_none_or(|globs| globs.is_match(path.render()))
+    }
+
+    /// How specific the most specific matching pattern is, if any match.
+    fn best_match(&self, path: &str) -> Option<usize> {
+        let globs = self.globs.as_ref()?;
+        globs
+            .matches(path)
+            .into_iter()
+            .map(|index| self.specificity[index])
+            .max()
+    }
+
+    /// Ignore any files whose restore target (after redirects) doesn't match.
+    /// The registry isn't a file, so it's ignored whenever there are patterns.
+    pub fn apply(&self, scan_info: &mut ScanInfo) {
+        if self.is_empty() {
+            return;
+        }
+
+        scan_info.found_files = std::mem::take(&mut scan_info.found_files)
+            .into_iter()
+            .map(|mut file| {
+                if !self.includes(file.effective()) {
+                    file.ignored = true;
+                }
+                file
+            })
+            .collect();
+
+        scan_info.found_registry_keys = std::mem::take(&mut scan_info.found_registry_keys)
+            .into_iter()
+            .map(|mut key| {
+                key.ignored = true;
+                for value in key.values.values_mut() {
+                    value.ignored = true;
+                }
+                key
+            })
+            .collect();
+    }
+}
+
+/// One-off `--exclude` and `--include` globs for a backup or restore,
+/// applied on top of the config's filters.
+/// These are checked against the path as shown in the report
+/// (the original location when backing up, the target after redirects when restoring).
+///
+/// Precedence:
+/// * Anything that the config already ignores stays ignored.
+/// * A file matching `--exclude` is ignored,
+///   unless it also matches an `--include` pattern that is at least as specific.
+///   Specificity is the number of folder levels in the pattern, not counting `**`.
+/// * `--include` only overrides `--exclude`, so it doesn't select anything by itself.
+/// * Registry data is not affected.
+#[derive(Clone, Debug, Default)]
+pub struct PathOverrides {
+    exclude: PathSelection,
+    include: PathSelection,
+}
+
+impl PathOverrides {
+    pub fn new(exclude: &[String], include: &[String]) -> Result<Self, globset::Error> {
+        Ok(Self {
+            exclude: PathSelection::new(exclude)?,
+            include: PathSelection::new(include)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclude.is_empty()
+    }
+
+    pub fn excludes(&self, path: &str) -> bool {
+        let Some(excluded) = self.exclude.best_match(path) else {
+            return false;
+        };
+        self.include.best_match(path).is_none_or(|included| included < excluded)
+    }
+
+    pub fn apply(&self, scan_info: &mut ScanInfo) {
+        if self.is_empty() {
+            return;
+        }
+
+        scan_info.found_files = std::mem::take(&mut scan_info.found_files)
+            .into_iter()
+            .map(|mut file| {
+                if !file.ignored && self.excludes(&file.readable(file.restoring())) {
+                    log::debug!("[{}] excluded by --exclude: {}", scan_info.game_name, file.path.raw());
+                    file.ignored = true;
+                }
+                file
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashset;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::scan::ScannedFile;
+
+    fn selection(patterns: &[&str]) -> PathSelection {
+        PathSelection::new(&patterns.iter().map(|x| x.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    fn check(selection: &PathSelection, path: &str) -> bool {
+        selection.includes(&StrictPath::new(path.to_string()))
+    }
+
+    #[test]
+    fn selects_everything_without_patterns() {
+        assert!(check(&selection(&[]), "/saves/slot1.sav"));
+    }
+
+    #[test]
+    fn can_match_with_wildcards() {
+        let selection = selection(&["/saves/*.sav", "/other/**/config.ini", "/folder"]);
+
+        assert!(check(&selection, "/saves/slot1.sav"));
+        assert!(!check(&selection, "/saves/nested/slot1.sav"));
+        assert!(!check(&selection, "/saves/slot1.txt"));
+        assert!(check(&selection, "/other/config.ini"));
+        assert!(check(&selection, "/other/a/b/config.ini"));
+        assert!(check(&selection, "/folder/a/b.txt"));
+        assert!(!check(&selection, "/folder2/b.txt"));
+    }
+
+    #[test]
+    fn matches_case_insensitively_where_paths_are() {
+        assert_eq!(
+            !Os::HOST.is_case_sensitive(),
+            check(&selection(&["/Saves/*.SAV"]), "/saves/slot1.sav")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(PathSelection::new(&["/saves/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn can_apply_to_redirected_targets() {
+        let mut scan_info = ScanInfo {
+            found_files: hashset! {
+                ScannedFile {
+                    original_path: Some(StrictPath::new("/original/slot1.sav".to_string())),
+                    redirected: Some(StrictPath::new("/redirected/slot1.sav".to_string())),
+                    ..ScannedFile::new("/backup/slot1.sav", 1, "1")
+                },
+                ScannedFile {
+                    original_path: Some(StrictPath::new("/original/slot2.sav".to_string())),
+                    ..ScannedFile::new("/backup/slot2.sav", 1, "2")
+                },
+            },
+            ..Default::default()
+        };
+
+        selection(&["/redirected/*.sav"]).apply(&mut scan_info);
+
+        let mut ignored: Vec<_> = scan_info
+            .found_files
+            .iter()
+            .map(|file| (file.effective().raw(), file.ignored))
+            .collect();
+        ignored.sort();
+        assert_eq!(
+            vec![
+                ("/original/slot2.sav".to_string(), true),
+                ("/redirected/slot1.sav".to_string(), false),
+            ],
+            ignored
+        );
+    }
+
+    fn overrides(exclude: &[&str], include: &[&str]) -> PathOverrides {
+        let strings = |patterns: &[&str]| patterns.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        PathOverrides::new(&strings(exclude), &strings(include)).unwrap()
+    }
+
+    #[test]
+    fn excludes_nothing_without_patterns() {
+        assert!(!overrides(&[], &["/saves"]).excludes("/saves/slot1.sav"));
+    }
+
+    #[test]
+    fn can_include_more_specific_paths_within_an_exclusion() {
+        let overrides = overrides(&["/saves/autosave"], &["/saves/autosave/keep.sav", "/saves"]);
+
+        assert!(overrides.excludes("/saves/autosave/1.sav"));
+        assert!(!overrides.excludes("/saves/autosave/keep.sav"));
+        assert!(!overrides.excludes("/saves/slot1.sav"));
+    }
+
+    #[test]
+    fn includes_win_ties_and_ignore_double_wildcards_for_specificity() {
+        let overrides = overrides(&["**/autosave/*.sav"], &["/saves/autosave"]);
+
+        assert!(!overrides.excludes("/saves/autosave/1.sav"));
+        assert!(overrides.excludes("/other/autosave/1.sav"));
+    }
+
+    #[test]
+    fn can_apply_overrides_on_top_of_existing_filters() {
+        let mut scan_info = ScanInfo {
+            game_name: "game".to_string(),
+            found_files: hashset! {
+                ScannedFile::new("/saves/autosave/1.sav", 1, "1"),
+                ScannedFile::new("/saves/autosave/keep.sav", 1, "2"),
+                ScannedFile {
+                    ignored: true,
+                    ..ScannedFile::new("/saves/config.ini", 1, "3")
+                },
+                ScannedFile {
+                    original_path: Some(StrictPath::new("/original/autosave/2.sav".to_string())),
+                    redirected: Some(StrictPath::new("/redirected/2.sav".to_string())),
+                    ..ScannedFile::new("/backup/2.sav", 1, "4")
+                },
+            },
+            ..Default::default()
+        };
+
+        overrides(&["**/autosave", "/saves/config.ini"], &["/saves/autosave/keep.sav"]).apply(&mut scan_info);
+
+        let mut ignored: Vec<_> = scan_info
+            .found_files
+            .iter()
+            .map(|file| (file.readable(file.restoring()), file.ignored))
+            .collect();
+        ignored.sort();
+        assert_eq!(
+            vec![
+                ("/redirected/2.sav".to_string(), false),
+                ("/saves/autosave/1.sav".to_string(), true),
+                ("/saves/autosave/keep.sav".to_string(), false),
+                ("/saves/config.ini".to_string(), true),
+            ],
+            ignored
+        );
+    }
+}
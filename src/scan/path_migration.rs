@@ -0,0 +1,263 @@
+use crate::{
+    path::StrictPath,
+    resource::{
+        config::{RedirectConfig, RedirectKind, UnicodeNormalization},
+        manifest::{placeholder::*, Game, PathMigration},
+    },
+    scan::{
+        check_nonwindows_path_str, check_path, check_windows_path, check_windows_path_str, path_key, PreviousFiles,
+    },
+};
+
+/// A `PathMigration` with its placeholders resolved for the current system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ResolvedMigration {
+    from: StrictPath,
+    to: StrictPath,
+}
+
+/// The save locations that moved between versions of a game,
+/// so that files in the new location can be compared with backups from the old location.
+#[derive(Clone, Debug, Default)]
+pub struct PathMigrations {
+    rules: Vec<ResolvedMigration>,
+}
+
+fn resolve(path: &str) -> Option<StrictPath> {
+    let data_dir = check_path(dirs::data_dir());
+    let data_local_dir = check_path(dirs::data_local_dir());
+    let config_dir = check_path(dirs::config_dir());
+
+    let resolved = path
+        .replace(HOME, &check_path(dirs::home_dir()))
+        .replace(OS_USER_NAME, &whoami::username())
+        .replace(WIN_APP_DATA, check_windows_path_str(&data_dir))
+        .replace(WIN_LOCAL_APP_DATA, check_windows_path_str(&data_local_dir))
+        .replace(WIN_DOCUMENTS, &check_windows_path(dirs::document_dir()))
+        .replace(WIN_PUBLIC, &check_windows_path(dirs::public_dir()))
+        .replace(WIN_PROGRAM_DATA, check_windows_path_str("C:/ProgramData"))
+        .replace(WIN_DIR, check_windows_path_str("C:/Windows"))
+        .replace(XDG_DATA, check_nonwindows_path_str(&data_dir))
+        .replace(XDG_CONFIG, check_nonwindows_path_str(&config_dir));
+
+    // Anything left over is either specific to a root, not applicable to this OS, or a glob.
+    if resolved.trim().is_empty() || resolved.contains(['<', '>', '*', '?', '[']) {
+        return None;
+    }
+
+    Some(StrictPath::new(resolved))
+}
+
+/// If `path` is inside of `prefix`, then move it into `replacement` instead.
+fn replace_prefix(
+    path: &StrictPath,
+    prefix: &StrictPath,
+    replacement: &StrictPath,
+    case_sensitive: bool,
+    normalization: UnicodeNormalization,
+) -> Option<StrictPath> {
+    let path = path.render();
+    let prefix = prefix.render();
+    let path_parts: Vec<_> = path.split('/').collect();
+    let prefix_parts: Vec<_> = prefix.trim_end_matches('/').split('/').collect();
+
+    if path_parts.len() <= prefix_parts.len() {
+        return None;
+    }
+    let matched = path_parts
+        .iter()
+        .zip(&prefix_parts)
+        .all(|(x, y)| path_key(x, case_sensitive, normalization) == path_key(y, case_sensitive, normalization));
+    if !matched {
+        return None;
+    }
+
+    Some(StrictPath::new(format!(
+        "{}/{}",
+        replacement.render().trim_end_matches('/'),
+        path_parts[prefix_parts.len()..].join("/")
+    )))
+}
+
+impl PathMigrations {
+    pub fn new(rules: &[PathMigration]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .filter_map(|rule| match (resolve(&rule.from), resolve(&rule.to)) {
+                    (Some(from), Some(to)) => Some(ResolvedMigration { from, to }),
+                    _ => {
+                        log::debug!("Ignoring unresolvable path migration: {} -> {}", &rule.from, &rule.to);
+                        None
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_game(game: &Game) -> Self {
+        Self::new(game.path_migrations.as_deref().unwrap_or_default())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Look up the hash of a file in the previous backup.
+    /// If it isn't there, but it's in the new location of a migration rule,
+    /// then we also check where it would have been before the migration.
+    /// In that case, the old location is returned as well.
+    pub fn previous_hash<'a>(
+        &self,
+        previous: &PreviousFiles<'a>,
+        path: &StrictPath,
+        case_sensitive: bool,
+    ) -> (Option<&'a String>, Option<StrictPath>) {
+        if let Some(hash) = previous.hash(path) {
+            return (Some(*hash), None);
+        }
+
+        for rule in &self.rules {
+            if let Some(old) = replace_prefix(path, &rule.to, &rule.from, case_sensitive, previous.normalization()) {
+                if let Some(hash) = previous.hash(&old) {
+                    return (Some(*hash), Some(old));
+                }
+            }
+        }
+
+        (None, None)
+    }
+
+    /// Restore redirects that send files from the old locations to the new ones.
+    /// These come before `redirects`, which still apply to the migrated paths.
+    pub fn with_redirects(&self, redirects: &[RedirectConfig]) -> Vec<RedirectConfig> {
+        self.rules
+            .iter()
+            .map(|rule| RedirectConfig {
+                kind: RedirectKind::Restore,
+                source: rule.from.clone(),
+                target: rule.to.clone(),
+            })
+            .chain(redirects.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn migrations() -> PathMigrations {
+        PathMigrations::new(&[PathMigration {
+            from: s("/home/user/Documents/MyGame"),
+            to: s("/home/user/AppData/LocalLow/Studio/MyGame"),
+        }])
+    }
+
+    #[test]
+    fn can_replace_prefix() {
+        assert_eq!(
+            Some(StrictPath::new(s("/new/sub/file.sav"))),
+            replace_prefix(
+                &StrictPath::new(s("/old/sub/file.sav")),
+                &StrictPath::new(s("/old")),
+                &StrictPath::new(s("/new")),
+                true,
+                UnicodeNormalization::Nfc,
+            )
+        );
+        assert_eq!(
+            None,
+            replace_prefix(
+                &StrictPath::new(s("/older/file.sav")),
+                &StrictPath::new(s("/old")),
+                &StrictPath::new(s("/new")),
+                true,
+                UnicodeNormalization::Nfc,
+            )
+        );
+        assert_eq!(
+            None,
+            replace_prefix(
+                &StrictPath::new(s("/OLD/file.sav")),
+                &StrictPath::new(s("/old")),
+                &StrictPath::new(s("/new")),
+                true,
+                UnicodeNormalization::Nfc,
+            )
+        );
+        assert_eq!(
+            Some(StrictPath::new(s("/new/file.sav"))),
+            replace_prefix(
+                &StrictPath::new(s("/OLD/file.sav")),
+                &StrictPath::new(s("/old")),
+                &StrictPath::new(s("/new")),
+                false,
+                UnicodeNormalization::Nfc,
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_rules_that_depend_on_a_root() {
+        let migrations = PathMigrations::new(&[
+            PathMigration {
+                from: s("<base>/saves"),
+                to: s("<home>/saves"),
+            },
+            PathMigration {
+                from: s("<home>/*/saves"),
+                to: s("<home>/saves"),
+            },
+        ]);
+        assert!(migrations.is_empty());
+    }
+
+    #[test]
+    fn can_find_previous_hash_in_old_location() {
+        let old = StrictPath::new(s("/home/user/Documents/MyGame/slot1.sav"));
+        let hash = s("abc");
+        let previous = PreviousFiles::new([(&old, &hash)].into_iter(), true, UnicodeNormalization::Nfc);
+
+        assert_eq!(
+            (Some(&hash), Some(old.clone())),
+            migrations().previous_hash(
+                &previous,
+                &StrictPath::new(s("/home/user/AppData/LocalLow/Studio/MyGame/slot1.sav")),
+                true
+            )
+        );
+        assert_eq!((Some(&hash), None), migrations().previous_hash(&previous, &old, true));
+        assert_eq!(
+            (None, None),
+            migrations().previous_hash(
+                &previous,
+                &StrictPath::new(s("/home/user/AppData/LocalLow/Studio/MyGame/slot2.sav")),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn can_redirect_restores_to_new_location() {
+        let user = RedirectConfig {
+            kind: RedirectKind::Restore,
+            source: StrictPath::new(s("/home/user")),
+            target: StrictPath::new(s("/home/other")),
+        };
+        assert_eq!(
+            vec![
+                RedirectConfig {
+                    kind: RedirectKind::Restore,
+                    source: StrictPath::new(s("/home/user/Documents/MyGame")),
+                    target: StrictPath::new(s("/home/user/AppData/LocalLow/Studio/MyGame")),
+                },
+                user.clone(),
+            ],
+            migrations().with_redirects(&[user])
+        );
+    }
+}
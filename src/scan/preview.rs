@@ -1,11 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use crate::{
-    resource::config::{ToggledPaths, ToggledRegistry},
-    scan::{layout::Backup, BackupInfo, ScanChange, ScanChangeCount, ScannedFile, ScannedRegistry},
+    prelude::StrictPath,
+    resource::{
+        config::{ToggledPaths, ToggledRegistry},
+        manifest::GameSource,
+    },
+    scan::{junk, layout::Backup, BackupInfo, LocalState, ScanChange, ScanChangeCount, ScannedFile, ScannedRegistry},
 };
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ScanInfo {
     pub game_name: String,
     pub found_files: HashSet<ScannedFile>,
@@ -14,6 +18,35 @@ pub struct ScanInfo {
     pub available_backups: Vec<Backup>,
     /// Only populated by a restoration scan.
     pub backup: Option<Backup>,
+    /// Registry access was disabled for this game, but it may have registry data.
+    /// In that case, `found_registry_keys` is empty on purpose.
+    pub registry_skipped: bool,
+    /// Install folders reported by a launcher for `<base>`, but which don't exist.
+    /// Only populated by a backup scan, and only when no files were found.
+    pub unresolved_base_dirs: BTreeSet<StrictPath>,
+    /// Save locations that were skipped because they didn't respond within `scan.ioTimeout`.
+    /// Only populated by a backup scan.
+    pub io_timeouts: BTreeSet<StrictPath>,
+    /// Steam account folders from the backup that could be restored into several current accounts,
+    /// along with those candidates. Files in these folders are skipped.
+    /// Only populated by a restoration scan.
+    pub ambiguous_targets: BTreeMap<StrictPath, Vec<StrictPath>>,
+    /// Original paths of files that were backed up on a different OS and weren't redirected.
+    /// These files are skipped.
+    /// Only populated by a restoration scan.
+    pub os_constrained: BTreeSet<StrictPath>,
+    /// How the local file at each restoration target compares to the backup.
+    /// Only populated by a restoration scan.
+    pub local_states: BTreeMap<StrictPath, LocalState>,
+    /// Restoration targets that were ignored because the local file is newer
+    /// (see `restore --no-clobber-newer`).
+    pub kept_newer_local: BTreeSet<StrictPath>,
+    /// Which manifest provided the game's entry.
+    /// Only populated by a backup scan.
+    pub source: Option<GameSource>,
+    /// The configured root that each found file was under, if any.
+    /// Only populated by a backup scan.
+    pub roots: BTreeMap<StrictPath, StrictPath>,
 }
 
 impl ScanInfo {
@@ -25,7 +58,7 @@ impl ScanInfo {
             .map(|x| x.size)
             .sum::<u64>();
         let failed_bytes = if let Some(backup_info) = &backup_info {
-            backup_info.failed_files.iter().map(|x| x.size).sum::<u64>()
+            backup_info.failed_files.keys().map(|x| x.size).sum::<u64>()
         } else {
             0
         };
@@ -47,6 +80,59 @@ impl ScanInfo {
             }
     }
 
+    /// Like `can_report_game`, but games whose saves were all removed are included,
+    /// so that the report shows what happened to them since the last backup.
+    pub fn can_report_game_or_removal(&self) -> bool {
+        self.found_anything()
+    }
+
+    /// If the file would be restored into an ambiguous account folder, then this lists the alternatives.
+    pub fn ambiguous_target(&self, file: &ScannedFile) -> Option<&Vec<StrictPath>> {
+        let target = file.effective();
+        self.ambiguous_targets
+            .iter()
+            .find(|(folder, _)| folder.is_prefix_of(target))
+            .map(|(_, candidates)| candidates)
+    }
+
+    /// Whether the file belongs to a different OS, so it won't be restored.
+    pub fn os_constrained(&self, file: &ScannedFile) -> bool {
+        file.redirected.is_none() && self.os_constrained.contains(file.original_path())
+    }
+
+    pub fn local_state(&self, file: &ScannedFile) -> Option<LocalState> {
+        self.local_states.get(file.effective()).copied()
+    }
+
+    /// Whether the file was ignored by `keep_newer_local_files`.
+    pub fn kept_newer_local(&self, file: &ScannedFile) -> bool {
+        self.kept_newer_local.contains(file.effective())
+    }
+
+    /// Ignore files whose local copy is newer than the backup,
+    /// so that restoring won't overwrite them.
+    pub fn keep_newer_local_files(&mut self) {
+        let newer: BTreeSet<_> = self
+            .local_states
+            .iter()
+            .filter(|(_, state)| **state == LocalState::NewerLocal)
+            .map(|(target, _)| target.clone())
+            .collect();
+
+        self.found_files = self
+            .found_files
+            .drain()
+            .map(|mut x| {
+                if !x.ignored && newer.contains(x.effective()) {
+                    log::info!("[{}] keeping newer local file: {}", self.game_name, x.effective().raw());
+                    x.ignored = true;
+                    self.kept_newer_local.insert(x.effective().clone());
+                }
+                x
+            })
+            .collect();
+    }
+
     pub fn found_anything(&self) -> bool {
         !self.found_files.is_empty() || !self.found_registry_keys.is_empty()
     }
@@ -61,13 +147,30 @@ impl ScanInfo {
         }
     }
 
+    /// Flag files that match the built-in junk filter,
+    /// which are then ignored unless the user has re-enabled them.
+    pub fn apply_junk_filter(&mut self, toggled_paths: &ToggledPaths) {
+        self.found_files = self
+            .found_files
+            .drain()
+            .map(|mut x| {
+                if junk::is_junk(&x.path) {
+                    log::debug!("[{}] junk (v{}): {}", self.game_name, junk::VERSION, x.path.raw());
+                    x.junk = true;
+                    x.ignored = toggled_paths.is_ignored_with_default(&self.game_name, &x.path, true);
+                }
+                x
+            })
+            .collect();
+    }
+
     pub fn update_ignored(&mut self, toggled_paths: &ToggledPaths, toggled_registry: &ToggledRegistry) {
         self.found_files = self
             .found_files
             .iter()
             .map(|x| {
                 let mut y = x.clone();
-                y.ignored = toggled_paths.is_ignored(&self.game_name, x.effective());
+                y.ignored = toggled_paths.is_ignored_with_default(&self.game_name, x.effective(), x.junk);
                 y
             })
             .collect();
@@ -250,6 +353,35 @@ mod tests {
         assert_eq!(ScanChange::New, scan.overall_change());
     }
 
+    #[test]
+    fn can_keep_newer_local_files() {
+        let file = |name: &str| ScannedFile {
+            original_path: Some(StrictPath::new(name.into())),
+            change: ScanChange::Different,
+            ..ScannedFile::with_name(format!("backup/{name}"))
+        };
+        let mut scan = ScanInfo {
+            found_files: hashset! { file("newer"), file("older") },
+            local_states: btreemap! {
+                StrictPath::new("newer".into()) => LocalState::NewerLocal,
+                StrictPath::new("older".into()) => LocalState::OlderLocal,
+            },
+            ..Default::default()
+        };
+
+        scan.keep_newer_local_files();
+
+        assert_eq!(
+            hashset! {
+                ScannedFile { ignored: true, ..file("newer") },
+                file("older"),
+            },
+            scan.found_files
+        );
+        assert!(scan.kept_newer_local(&file("newer")));
+        assert!(!scan.kept_newer_local(&file("older")));
+    }
+
     #[test]
     fn game_is_total_removal() {
         let scan = ScanInfo {
@@ -518,6 +650,23 @@ mod tests {
         assert!(!scan.can_report_game());
     }
 
+    #[test]
+    fn can_report_game_or_removal_when_total_removal() {
+        let scan = ScanInfo {
+            found_files: hashset! {
+                ScannedFile {
+                    path: StrictPath::new("a".into()),
+                    ignored: false,
+                    change: ScanChange::Removed,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        };
+
+        assert!(scan.can_report_game_or_removal());
+    }
+
     #[test]
     fn can_report_game_when_inert_but_not_total_removal() {
         let scan = ScanInfo {
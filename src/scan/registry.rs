@@ -328,13 +328,34 @@ impl Hives {
         Ok(())
     }
 
-    pub fn restore(&self, game_name: &str, toggled: &ToggledRegistry) -> Result<(), Error> {
+    /// Write the entries back into the registry.
+    /// If `offline_hive` is set, then `HKEY_CURRENT_USER` entries are written
+    /// into the hive loaded under that subkey of `HKEY_USERS` (see `OfflineHive`),
+    /// and entries for other hives are skipped.
+    pub fn restore(&self, game_name: &str, toggled: &ToggledRegistry, offline_hive: Option<&str>) -> Result<(), Error> {
         let mut failed = false;
 
         for (hive_name, keys) in self.0.iter() {
-            let hive = match get_hkey_from_name(hive_name) {
-                Some(x) => winreg::RegKey::predef(x),
-                None => {
+            let hive = match (offline_hive, get_hkey_from_name(hive_name)) {
+                (None, Some(x)) => winreg::RegKey::predef(x),
+                (Some(subkey), Some(_)) if hive_name == "HKEY_CURRENT_USER" => {
+                    match winreg::RegKey::predef(winreg::enums::HKEY_USERS)
+                        .open_subkey_with_flags(subkey, winreg::enums::KEY_ALL_ACCESS)
+                    {
+                        Ok(x) => x,
+                        Err(e) => {
+                            log::error!("[{game_name}] unable to open offline hive under HKEY_USERS/{subkey}: {e:?}");
+                            failed = true;
+                            continue;
+                        }
+                    }
+                }
+                (Some(_), Some(_)) => {
+                    log::warn!("[{game_name}] skipping {hive_name} because only HKEY_CURRENT_USER can be restored into a hive file");
+                    failed = true;
+                    continue;
+                }
+                (_, None) => {
                     failed = true;
                     continue;
                 }
@@ -397,6 +418,170 @@ impl Hives {
     }
 }
 
+/// A user's registry hive file (e.g., `NTUSER.DAT`),
+/// loaded under a temporary subkey of `HKEY_USERS` so that it can be edited offline.
+/// The hive is flushed and unloaded when this is dropped.
+pub struct OfflineHive {
+    subkey: String,
+}
+
+impl OfflineHive {
+    /// This requires an elevated process, since loading a hive needs the backup and restore privileges.
+    pub fn load(file: &StrictPath) -> Result<Self, Error> {
+        use winapi::{shared::winerror::ERROR_SUCCESS, um::winreg::RegLoadKeyW};
+
+        if !is_elevated() {
+            return Err(Error::RegistryHiveNeedsElevation);
+        }
+
+        let fail = |why: String| Error::UnableToLoadRegistryHive {
+            path: file.clone(),
+            why,
+        };
+
+        for privilege in ["SeBackupPrivilege", "SeRestorePrivilege"] {
+            enable_privilege(privilege).map_err(|e| fail(format!("{privilege}: {e}")))?;
+        }
+
+        let subkey = format!("ludusavi-offline-{}", std::process::id());
+        let status = unsafe {
+            RegLoadKeyW(
+                winreg::enums::HKEY_USERS,
+                wide(&subkey).as_ptr(),
+                wide(&file.interpret()).as_ptr(),
+            )
+        };
+        if status != ERROR_SUCCESS as i32 {
+            return Err(fail(std::io::Error::from_raw_os_error(status).to_string()));
+        }
+
+        log::info!("loaded registry hive {} under HKEY_USERS/{subkey}", file.raw());
+        Ok(Self { subkey })
+    }
+
+    /// The subkey of `HKEY_USERS` where the hive is loaded.
+    pub fn subkey(&self) -> &str {
+        &self.subkey
+    }
+}
+
+impl Drop for OfflineHive {
+    fn drop(&mut self) {
+        use winapi::{
+            shared::winerror::ERROR_SUCCESS,
+            um::winreg::{RegFlushKey, RegUnLoadKeyW},
+        };
+
+        let users = winreg::RegKey::predef(winreg::enums::HKEY_USERS);
+        if let Ok(key) = users.open_subkey_with_flags(&self.subkey, winreg::enums::KEY_ALL_ACCESS) {
+            let status = unsafe { RegFlushKey(key.raw_handle()) };
+            if status != ERROR_SUCCESS as i32 {
+                log::error!(
+                    "unable to flush registry hive under HKEY_USERS/{}: {}",
+                    self.subkey,
+                    std::io::Error::from_raw_os_error(status)
+                );
+            }
+        }
+
+        let status = unsafe { RegUnLoadKeyW(winreg::enums::HKEY_USERS, wide(&self.subkey).as_ptr()) };
+        if status == ERROR_SUCCESS as i32 {
+            log::info!("unloaded registry hive from HKEY_USERS/{}", self.subkey);
+        } else {
+            log::error!(
+                "unable to unload registry hive from HKEY_USERS/{}: {}",
+                self.subkey,
+                std::io::Error::from_raw_os_error(status)
+            );
+        }
+    }
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    std::ffi::OsStr::new(text).encode_wide().chain(Some(0)).collect()
+}
+
+fn is_elevated() -> bool {
+    use winapi::um::{
+        handleapi::CloseHandle,
+        processthreadsapi::{GetCurrentProcess, OpenProcessToken},
+        securitybaseapi::GetTokenInformation,
+        winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY},
+    };
+
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut size = 0;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut _ as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        CloseHandle(token);
+
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+fn enable_privilege(name: &str) -> Result<(), std::io::Error> {
+    use winapi::{
+        shared::winerror::ERROR_NOT_ALL_ASSIGNED,
+        um::{
+            errhandlingapi::GetLastError,
+            handleapi::CloseHandle,
+            processthreadsapi::{GetCurrentProcess, OpenProcessToken},
+            securitybaseapi::AdjustTokenPrivileges,
+            winbase::LookupPrivilegeValueW,
+            winnt::{
+                HANDLE, LUID, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+            },
+        },
+    };
+
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &mut token) == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut luid = LUID {
+            LowPart: 0,
+            HighPart: 0,
+        };
+        if LookupPrivilegeValueW(std::ptr::null(), wide(name).as_ptr(), &mut luid) == 0 {
+            let error = std::io::Error::last_os_error();
+            CloseHandle(token);
+            return Err(error);
+        }
+
+        let mut privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES {
+                Luid: luid,
+                Attributes: SE_PRIVILEGE_ENABLED,
+            }],
+        };
+        let ok = AdjustTokenPrivileges(token, 0, &mut privileges, 0, std::ptr::null_mut(), std::ptr::null_mut());
+        // This can succeed without actually granting the privilege.
+        let error = (ok == 0 || GetLastError() == ERROR_NOT_ALL_ASSIGNED).then(std::io::Error::last_os_error);
+        CloseHandle(token);
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
 impl Entry {
     fn is_set(&self) -> bool {
         *self != Self::Unknown
@@ -116,6 +116,20 @@ impl<'de> serde::Deserialize<'de> for RegistryItem {
     }
 }
 
+impl schemars::JsonSchema for RegistryItem {
+    fn schema_name() -> String {
+        "RegistryItem".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{
+    path::StrictPath,
+    resource::config::{RedirectConfig, RedirectKind},
+};
+
+static WINDOWS_PROFILE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([A-Za-z]:/Users/)([^/]+)(?:/|$)").unwrap());
+static LINUX_PROFILE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(/home/)([^/]+)(?:/|$)").unwrap());
+static MAC_PROFILE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(/Users/)([^/]+)(?:/|$)").unwrap());
+static STEAM_USERDATA: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+/userdata)/([0-9]+)(?:/|$)").unwrap());
+
+/// Folders under the profile root that don't belong to a real user.
+const SHARED_PROFILES: &[&str] = &["Public", "Default", "Default User", "All Users", "steamuser"];
+
+/// When restoring a backup made under a different user name,
+/// this finds the old profile folder so that it can be redirected to the current one.
+/// If the backup contains files from several profiles (or from the current one),
+/// we can't tell which one to use, so nothing is remapped.
+pub fn user_profile_redirect<'a>(
+    original_paths: impl Iterator<Item = &'a StrictPath>,
+    home: &StrictPath,
+) -> Option<RedirectConfig> {
+    let home = home.raw().replace('\\', "/");
+    let pattern = [&*WINDOWS_PROFILE, &*LINUX_PROFILE, &*MAC_PROFILE]
+        .into_iter()
+        .find(|pattern| pattern.is_match(&home))?;
+    let current_user = pattern.captures(&home)?.get(2)?.as_str();
+
+    let mut profiles = BTreeSet::new();
+    for path in original_paths {
+        let path = path.raw().replace('\\', "/");
+        let Some(captures) = pattern.captures(&path) else {
+            continue;
+        };
+        let user = &captures[2];
+        if SHARED_PROFILES.iter().any(|x| x.eq_ignore_ascii_case(user)) {
+            continue;
+        }
+        if user == current_user {
+            return None;
+        }
+        profiles.insert(format!("{}{}", &captures[1], user));
+    }
+
+    if profiles.len() > 1 {
+        log::warn!("Not remapping user profile because the backup contains several: {profiles:?}");
+        return None;
+    }
+
+    let source = profiles.pop_first()?;
+    Some(RedirectConfig {
+        kind: RedirectKind::Restore,
+        source: StrictPath::new(source),
+        target: StrictPath::new(home.trim_end_matches('/').to_string()),
+    })
+}
+
+/// Where to restore files that were backed up from a Steam account folder (`<storeUserId>`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountTargets {
+    /// Redirects into the preferred account, when it was one of the candidates.
+    pub redirects: Vec<RedirectConfig>,
+    /// Account folders from the backup that don't exist on this system,
+    /// mapped to the current account folders that could replace them.
+    pub ambiguous: BTreeMap<StrictPath, Vec<StrictPath>>,
+}
+
+/// Current Steam account folders next to `userdata`, like `userdata/12345678`.
+/// The `0` folder is not a real account.
+fn steam_accounts(userdata: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(StrictPath::new(userdata.to_string()).as_std_path_buf()) else {
+        return vec![];
+    };
+
+    let mut accounts: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(|x| x.to_string()))
+        .filter(|name| name != "0" && !name.is_empty() && name.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    accounts.sort();
+    accounts
+}
+
+/// When restoring files from a Steam account folder that doesn't exist on this system,
+/// there may be several other accounts that the files could belong to.
+/// If `prefer` is one of them, then we redirect the files there;
+/// otherwise, we report the candidates instead of picking one.
+/// If there is at most one candidate, then there's nothing to decide.
+pub fn steam_account_targets<'a>(
+    target_paths: impl Iterator<Item = &'a StrictPath>,
+    prefer: Option<&str>,
+) -> AccountTargets {
+    let mut out = AccountTargets::default();
+
+    let mut folders = BTreeSet::new();
+    for path in target_paths {
+        let path = path.render();
+        if let Some(captures) = STEAM_USERDATA.captures(&path) {
+            folders.insert((captures[1].to_string(), captures[2].to_string()));
+        }
+    }
+
+    for (userdata, account) in folders {
+        let original = StrictPath::new(format!("{userdata}/{account}"));
+        if original.is_dir() {
+            continue;
+        }
+
+        let candidates = steam_accounts(&userdata);
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        match prefer.filter(|x| candidates.iter().any(|candidate| candidate == x)) {
+            Some(preferred) => {
+                log::info!(
+                    "Redirecting Steam account {account} to preferred account {preferred} in {}",
+                    &userdata
+                );
+                out.redirects.push(RedirectConfig {
+                    kind: RedirectKind::Restore,
+                    source: original,
+                    target: StrictPath::new(format!("{userdata}/{preferred}")),
+                });
+            }
+            None => {
+                log::warn!("Steam account {account} could be restored into several accounts: {candidates:?}");
+                out.ambiguous.insert(
+                    original,
+                    candidates
+                        .into_iter()
+                        .map(|x| StrictPath::new(format!("{userdata}/{x}")))
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::*;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::{repo, s};
+
+    fn remap(paths: &[&str], home: &str) -> Option<(String, String)> {
+        let paths: Vec<_> = paths.iter().map(|x| StrictPath::new(x.to_string())).collect();
+        user_profile_redirect(paths.iter(), &StrictPath::new(home.to_string()))
+            .map(|x| (x.source.raw(), x.target.raw()))
+    }
+
+    #[test]
+    fn remaps_windows_profile_for_roaming_and_local_app_data() {
+        assert_eq!(
+            Some((s("C:/Users/Alex"), s("C:/Users/alexander"))),
+            remap(
+                &[
+                    "C:/Users/Alex/AppData/Roaming/game/save.dat",
+                    "C:/Users/Alex/AppData/Local/game/settings.ini",
+                ],
+                "C:/Users/alexander",
+            ),
+        );
+    }
+
+    #[test]
+    fn remaps_linux_profile() {
+        assert_eq!(
+            Some((s("/home/alex"), s("/home/alexander"))),
+            remap(&["/home/alex/.local/share/game/save.dat"], "/home/alexander"),
+        );
+    }
+
+    #[test]
+    fn does_not_remap_current_profile() {
+        assert_eq!(
+            None,
+            remap(&["/home/alexander/.config/game/save.dat"], "/home/alexander")
+        );
+    }
+
+    #[test]
+    fn does_not_remap_when_several_profiles_are_present() {
+        assert_eq!(
+            None,
+            remap(
+                &[
+                    "C:/Users/Alex/Documents/game/save.dat",
+                    "C:/Users/Sam/Documents/game/save.dat"
+                ],
+                "C:/Users/alexander",
+            ),
+        );
+    }
+
+    #[test]
+    fn does_not_remap_when_current_profile_is_also_present() {
+        assert_eq!(
+            None,
+            remap(
+                &[
+                    "/home/alex/.config/game/save.dat",
+                    "/home/alexander/.config/game/save.dat"
+                ],
+                "/home/alexander",
+            ),
+        );
+    }
+
+    #[test]
+    fn ignores_shared_profiles_and_other_locations() {
+        assert_eq!(
+            Some((s("C:/Users/Alex"), s("C:/Users/alexander"))),
+            remap(
+                &[
+                    "C:/Users/Public/Documents/game/save.dat",
+                    "C:/Games/game/save.dat",
+                    "C:/Users/Alex/Saved Games/game/save.dat",
+                ],
+                "C:/Users/alexander",
+            ),
+        );
+        assert_eq!(
+            None,
+            remap(&["C:/Users/Public/Documents/game/save.dat"], "C:/Users/alexander")
+        );
+    }
+
+    #[test]
+    fn does_not_mix_path_styles() {
+        assert_eq!(
+            None,
+            remap(&["C:/Users/Alex/Documents/game/save.dat"], "/home/alexander")
+        );
+    }
+
+    fn account_targets(accounts: &[&str], prefer: Option<&str>) -> AccountTargets {
+        let paths: Vec<_> = accounts
+            .iter()
+            .map(|x| StrictPath::new(format!("{}/tests/steam/userdata/{x}/123/remote/save.dat", repo())))
+            .collect();
+        steam_account_targets(paths.iter(), prefer)
+    }
+
+    fn account(id: &str) -> StrictPath {
+        StrictPath::new(format!("{}/tests/steam/userdata/{id}", repo()))
+    }
+
+    #[test]
+    fn reports_ambiguous_steam_account() {
+        assert_eq!(
+            AccountTargets {
+                redirects: vec![],
+                ambiguous: btreemap! {
+                    account("99999999") => vec![account("11111111"), account("22222222")],
+                },
+            },
+            account_targets(&["99999999"], None),
+        );
+    }
+
+    #[test]
+    fn redirects_to_preferred_steam_account() {
+        assert_eq!(
+            AccountTargets {
+                redirects: vec![RedirectConfig {
+                    kind: RedirectKind::Restore,
+                    source: account("99999999"),
+                    target: account("22222222"),
+                }],
+                ambiguous: BTreeMap::new(),
+            },
+            account_targets(&["99999999"], Some("22222222")),
+        );
+    }
+
+    #[test]
+    fn ignores_preferred_steam_account_that_does_not_exist() {
+        assert_eq!(
+            vec![account("11111111"), account("22222222")],
+            account_targets(&["99999999"], Some("33333333")).ambiguous[&account("99999999")],
+        );
+    }
+
+    #[test]
+    fn does_not_redirect_existing_steam_account() {
+        assert_eq!(AccountTargets::default(), account_targets(&["11111111"], None));
+    }
+}
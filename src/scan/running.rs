@@ -0,0 +1,312 @@
+//! Best-effort detection of games that are currently running.
+//! None of this is authoritative: processes that we aren't allowed to inspect can't be matched.
+//! If the platform doesn't let us list processes at all, then the snapshot says so,
+//! so that callers can warn about it instead of treating every game as not running.
+
+use crate::prelude::StrictPath;
+
+/// Don't spend too long looking for executables in large install folders.
+#[cfg(target_os = "windows")]
+const MAX_EXECUTABLES: usize = 200;
+
+/// How far below an install folder to look for executables and locked files.
+#[cfg(target_os = "windows")]
+const MAX_DEPTH: usize = 3;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningProcess {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The file or executable that made us think the game is running.
+    pub path: String,
+}
+
+impl RunningProcess {
+    pub fn label(&self) -> String {
+        match (&self.name, self.pid) {
+            (Some(name), Some(pid)) => format!("{name}, PID {pid}"),
+            (Some(name), None) => name.clone(),
+            (None, Some(pid)) => format!("PID {pid}"),
+            (None, None) => self.path.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct ProcessInfo {
+    pid: Option<u32>,
+    name: Option<String>,
+    /// The executable and any open files, where available.
+    files: Vec<StrictPath>,
+}
+
+/// The processes that were running at one point in time.
+/// Taking this is relatively expensive, so reuse it across games.
+#[derive(Clone, Debug, Default)]
+pub struct ProcessSnapshot {
+    processes: Vec<ProcessInfo>,
+    unsupported: bool,
+}
+
+impl ProcessSnapshot {
+    pub fn take() -> Self {
+        if !sysinfo::IS_SUPPORTED_SYSTEM {
+            log::warn!("Unable to list processes on this platform, so running games can't be detected");
+            return Self {
+                processes: vec![],
+                unsupported: true,
+            };
+        }
+
+        // Our own log and config files are often open in the same folders as saves.
+        let own_pid = std::process::id();
+        let mut processes = list_processes();
+        processes.retain(|x| x.pid != Some(own_pid));
+        log::debug!("Found {} processes for running game detection", processes.len());
+        Self {
+            processes,
+            unsupported: false,
+        }
+    }
+
+    /// Whether the platform doesn't let us list processes,
+    /// in which case `find` can't tell if a game is running.
+    pub fn is_unsupported(&self) -> bool {
+        self.unsupported
+    }
+
+    /// Check whether some process is using the game,
+    /// either because it has a file open in one of the install folders or was launched from there,
+    /// or because it has one of the save files open.
+    ///
+    /// Save files are only matched exactly, since their parent folders
+    /// are often shared with other programs (e.g., `~` or `~/.config`).
+    pub fn find(&self, install_dirs: &[StrictPath], save_files: &[StrictPath]) -> Option<RunningProcess> {
+        let dirs: Vec<_> = install_dirs.iter().filter(|x| x.is_dir()).collect();
+        let files: Vec<_> = save_files.iter().filter(|x| x.is_file()).collect();
+        if dirs.is_empty() && files.is_empty() {
+            return None;
+        }
+
+        for process in &self.processes {
+            for file in &process.files {
+                if dirs.iter().any(|dir| dir.is_prefix_of(file)) || files.iter().any(|x| x.same_location(file)) {
+                    return Some(RunningProcess {
+                        pid: process.pid,
+                        name: process.name.clone(),
+                        path: file.render(),
+                    });
+                }
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        if let Some(found) = self.find_by_executable_name(&dirs) {
+            return Some(found);
+        }
+
+        #[cfg(target_os = "windows")]
+        if let Some(found) = find_locked_file(&dirs, &files) {
+            return Some(found);
+        }
+
+        None
+    }
+
+    /// Windows doesn't always tell us where other processes live without extra privileges,
+    /// so we match executables from the game's folders by name instead.
+    #[cfg(target_os = "windows")]
+    fn find_by_executable_name(&self, dirs: &[&StrictPath]) -> Option<RunningProcess> {
+        let names: std::collections::HashMap<_, _> = self
+            .processes
+            .iter()
+            .filter_map(|x| Some((x.name.as_ref()?.to_lowercase(), x)))
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+
+        for dir in dirs {
+            for entry in walkdir::WalkDir::new(dir.as_std_path_buf())
+                .max_depth(MAX_DEPTH)
+                .into_iter()
+                .filter_map(crate::prelude::filter_map_walkdir)
+                .filter(|x| {
+                    x.file_type().is_file() && x.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+                })
+                .take(MAX_EXECUTABLES)
+            {
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                if let Some(process) = names.get(&name) {
+                    return Some(RunningProcess {
+                        pid: process.pid,
+                        name: process.name.clone(),
+                        path: StrictPath::from(&entry).render(),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn list_processes() -> Vec<ProcessInfo> {
+    use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessRefreshKind::new().with_exe(UpdateKind::Always));
+
+    system
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            let pid = pid.as_u32();
+            let mut files: Vec<_> = process.exe().map(StrictPath::from).into_iter().collect();
+            files.extend(open_files(pid));
+            ProcessInfo {
+                pid: Some(pid),
+                name: Some(process.name().to_string()).filter(|x| !x.is_empty()),
+                files,
+            }
+        })
+        .collect()
+}
+
+/// We can only see the open files of our own user's processes, which is fine for games.
+#[cfg(target_os = "linux")]
+fn open_files(pid: u32) -> Vec<StrictPath> {
+    let Ok(fds) = std::fs::read_dir(format!("/proc/{pid}/fd")) else {
+        return vec![];
+    };
+
+    fds.flatten()
+        .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+        .filter(|target| target.is_absolute())
+        .map(StrictPath::from)
+        .collect()
+}
+
+/// Other platforms don't expose open files without extra privileges,
+/// so we rely on the executable path (and on Windows, locked files).
+#[cfg(not(target_os = "linux"))]
+fn open_files(_pid: u32) -> Vec<StrictPath> {
+    vec![]
+}
+
+/// A game that's running will often hold its save files open without sharing them.
+#[cfg(target_os = "windows")]
+fn find_locked_file(dirs: &[&StrictPath], files: &[&StrictPath]) -> Option<RunningProcess> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+
+    let is_locked = |path: &std::path::Path| {
+        let result = std::fs::OpenOptions::new().read(true).share_mode(0).open(path);
+        result.is_err_and(|e| e.raw_os_error() == Some(ERROR_SHARING_VIOLATION))
+    };
+
+    for file in files {
+        if is_locked(&file.as_std_path_buf()) {
+            return Some(RunningProcess {
+                pid: None,
+                name: None,
+                path: file.render(),
+            });
+        }
+    }
+
+    for dir in dirs {
+        for entry in walkdir::WalkDir::new(dir.as_std_path_buf())
+            .max_depth(MAX_DEPTH)
+            .into_iter()
+            .filter_map(crate::prelude::filter_map_walkdir)
+            .filter(|x| x.file_type().is_file())
+            .take(MAX_EXECUTABLES)
+        {
+            if is_locked(entry.path()) {
+                return Some(RunningProcess {
+                    pid: None,
+                    name: None,
+                    path: StrictPath::from(&entry).render(),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_missing_folders() {
+        let snapshot = ProcessSnapshot {
+            processes: vec![ProcessInfo {
+                pid: Some(1),
+                name: Some("game".to_string()),
+                files: vec![StrictPath::new("/does/not/exist/save.dat".to_string())],
+            }],
+            unsupported: false,
+        };
+        assert_eq!(
+            None,
+            snapshot.find(
+                &[StrictPath::new("/does/not/exist".to_string())],
+                &[StrictPath::new("/does/not/exist/save.dat".to_string())],
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_other_files_next_to_save_files() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-running-neighbor-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("save.dat"), "").unwrap();
+
+        let snapshot = ProcessSnapshot {
+            processes: vec![ProcessInfo {
+                pid: Some(1),
+                name: Some("other".to_string()),
+                files: vec![StrictPath::from(dir.join("other.log"))],
+            }],
+            unsupported: false,
+        };
+        assert_eq!(None, snapshot.find(&[], &[StrictPath::from(dir.join("save.dat"))]));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_find_process_with_open_file() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-running-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("save.dat");
+        let handle = std::fs::File::create(&file).unwrap();
+        let save_files = [StrictPath::from(file.clone())];
+
+        // Our own process doesn't count.
+        let found = ProcessSnapshot::take().find(&[], &save_files);
+        assert_eq!(None, found.and_then(|x| x.pid.filter(|pid| *pid == std::process::id())));
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .stdin(std::process::Stdio::from(handle))
+            .spawn()
+            .unwrap();
+        let found = ProcessSnapshot::take().find(&[], &save_files);
+        _ = child.kill();
+        _ = child.wait();
+        assert_eq!(Some(child.id()), found.and_then(|x| x.pid));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -5,21 +5,49 @@ use crate::{
     scan::{registry_compat::RegistryItem, ScanChange},
 };
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// This is serialized only for the daemon, so relative paths keep their basis.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
 pub struct ScannedFile {
     /// The actual location on disk.
     /// When `container` is set, this is the path inside of the container
     /// and should be used in its raw form.
+    #[serde(with = "crate::path::with_basis")]
     pub path: StrictPath,
     pub size: u64,
     pub hash: String,
     /// This is the restoration target path, without redirects applied.
+    #[serde(with = "crate::path::option_with_basis")]
     pub original_path: Option<StrictPath>,
     pub ignored: bool,
     pub change: ScanChange,
     /// An enclosing archive file, if any, depending on the `BackupFormat`.
+    #[serde(with = "crate::path::option_with_basis")]
     pub container: Option<StrictPath>,
+    #[serde(with = "crate::path::option_with_basis")]
     pub redirected: Option<StrictPath>,
+    /// The redirect that produced `redirected`, if any.
+    pub redirect_rule: Option<RedirectRule>,
+    /// This is an SQLite database or one of its journal files,
+    /// which are copied together as a group.
+    pub sqlite_safe_copy: bool,
+    /// This matches the built-in junk filter (see `scan.excludeCommonJunk`),
+    /// so it's ignored unless the user has re-enabled it.
+    pub junk: bool,
+    /// The file was compared with the previous backup at this location instead,
+    /// because of a path migration rule (see `PathMigrations`).
+    #[serde(with = "crate::path::option_with_basis")]
+    pub migrated_from: Option<StrictPath>,
+}
+
+/// A redirect that applied to a file.
+/// When several redirects apply in sequence, this is the first one.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RedirectRule {
+    /// Position in the list of redirects that were in effect,
+    /// which may include automatic ones (e.g., for path migrations or remapped user profiles).
+    pub index: usize,
+    pub source: StrictPath,
+    pub target: StrictPath,
 }
 
 impl ScannedFile {
@@ -34,6 +62,10 @@ impl ScannedFile {
             change: Default::default(),
             container: None,
             redirected: None,
+            redirect_rule: None,
+            sqlite_safe_copy: false,
+            junk: false,
+            migrated_from: None,
         }
     }
 
@@ -56,6 +88,10 @@ impl ScannedFile {
             change,
             container: None,
             redirected: None,
+            redirect_rule: None,
+            sqlite_safe_copy: false,
+            junk: false,
+            migrated_from: None,
         }
     }
 
@@ -132,7 +168,7 @@ impl ScannedFile {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
 pub struct ScannedRegistry {
     pub path: RegistryItem,
     pub ignored: bool,
@@ -140,7 +176,7 @@ pub struct ScannedRegistry {
     pub values: ScannedRegistryValues,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
 pub struct ScannedRegistryValue {
     pub ignored: bool,
     pub change: ScanChange,
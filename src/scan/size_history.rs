@@ -0,0 +1,103 @@
+use crate::scan::layout::{BackupKind, BackupLayout};
+
+/// How much data a game had as of one of its backups.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeHistoryEntry {
+    pub game: String,
+    pub backup: String,
+    pub when: chrono::DateTime<chrono::Utc>,
+    pub kind: BackupKind,
+    /// Total size of the game's files, including any that a differential backup inherits.
+    pub bytes: u64,
+    pub files: usize,
+    /// Change in `bytes` since the game's previous backup, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<i64>,
+}
+
+impl SizeHistoryEntry {
+    /// Every backup of the given games, grouped by game and oldest first.
+    pub fn analyze(layout: &BackupLayout, games: &[String]) -> Vec<Self> {
+        let mut games = games.to_vec();
+        games.sort();
+
+        games
+            .iter()
+            .filter_map(|name| layout.try_game_layout(name))
+            .flat_map(|game_layout| game_layout.size_history())
+            .collect()
+    }
+
+    /// A header row for `to_csv_row`.
+    pub fn csv_header() -> &'static str {
+        "game,backup,when,kind,bytes,files,delta"
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        let kind = match self.kind {
+            BackupKind::Full => "full",
+            BackupKind::Differential => "differential",
+        };
+        format!(
+            "{},{},{},{kind},{},{},{}",
+            csv_field(&self.game),
+            csv_field(&self.backup),
+            self.when.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            self.bytes,
+            self.files,
+            self.delta.map(|x| x.to_string()).unwrap_or_default(),
+        )
+    }
+}
+
+/// Quote a field if it contains a delimiter, quote, or line break.
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn entry(game: &str, kind: BackupKind, delta: Option<i64>) -> SizeHistoryEntry {
+        SizeHistoryEntry {
+            game: s(game),
+            backup: s("backup-1"),
+            when: chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+            kind,
+            bytes: 150,
+            files: 2,
+            delta,
+        }
+    }
+
+    #[test]
+    fn can_render_csv_rows() {
+        assert_eq!(
+            "game1,backup-1,2024-01-02T03:04:05Z,full,150,2,",
+            entry("game1", BackupKind::Full, None).to_csv_row()
+        );
+        assert_eq!(
+            "game1,backup-1,2024-01-02T03:04:05Z,differential,150,2,-50",
+            entry("game1", BackupKind::Differential, Some(-50)).to_csv_row()
+        );
+    }
+
+    #[test]
+    fn quotes_csv_fields_when_needed() {
+        assert_eq!(
+            "\"Game, \"\"Deluxe\"\"\",backup-1,2024-01-02T03:04:05Z,full,150,2,",
+            entry("Game, \"Deluxe\"", BackupKind::Full, None).to_csv_row()
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    path::StrictPath,
+    scan::{stamp, ScannedFile},
+};
+
+const HEADER: &[u8; 16] = b"SQLite format 3\0";
+const JOURNAL_SUFFIXES: &[&str] = &["-wal", "-shm"];
+
+static SNAPSHOT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Checks for the SQLite header magic.
+pub fn is_database(path: &StrictPath) -> bool {
+    let Ok(mut handle) = std::fs::File::open(path.interpret()) else {
+        return false;
+    };
+    let mut header = [0; 16];
+    handle.read_exact(&mut header).is_ok() && &header == HEADER
+}
+
+pub fn is_journal(path: &StrictPath) -> bool {
+    let raw = path.raw();
+    JOURNAL_SUFFIXES.iter().any(|suffix| raw.ends_with(suffix))
+}
+
+/// The write-ahead log and shared memory files that currently exist next to a database.
+pub fn journal_files(database: &StrictPath) -> Vec<StrictPath> {
+    JOURNAL_SUFFIXES
+        .iter()
+        .map(|suffix| StrictPath::new(format!("{}{suffix}", database.raw())))
+        .filter(|x| x.is_file())
+        .collect()
+}
+
+/// A database is only consistent together with its journal files,
+/// so this looks for databases with a write-ahead log.
+pub fn find_databases_with_journal(files: &HashSet<ScannedFile>) -> Vec<ScannedFile> {
+    files
+        .iter()
+        .filter(|x| !x.ignored && !is_journal(&x.path) && is_database(&x.path) && !journal_files(&x.path).is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Copies of SQLite databases and their journal files that were taken together,
+/// so that a game writing to the database during the backup can't leave us
+/// with a database that doesn't match its journal.
+/// The copies are deleted when this is dropped.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    dir: Option<StrictPath>,
+    sources: HashMap<StrictPath, StrictPath>,
+    /// Files that kept changing while we were copying them.
+    pub unstable: HashSet<StrictPath>,
+}
+
+impl Snapshot {
+    pub fn take(game: &str, files: &HashSet<ScannedFile>) -> Self {
+        let mut snapshot = Self::default();
+
+        let databases: Vec<_> = files
+            .iter()
+            .filter(|x| x.sqlite_safe_copy && !x.ignored && !is_journal(&x.path))
+            .collect();
+        if databases.is_empty() {
+            return snapshot;
+        }
+
+        let dir = StrictPath::from_std_path_buf(&std::env::temp_dir()).joined(&format!(
+            "ludusavi-sqlite-{}-{}",
+            std::process::id(),
+            SNAPSHOT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        snapshot.dir = Some(dir.clone());
+
+        for (i, database) in databases.into_iter().enumerate() {
+            let mut members = vec![database.path.clone()];
+            members.extend(
+                files
+                    .iter()
+                    .filter(|x| x.sqlite_safe_copy && is_journal(&x.path))
+                    .filter(|x| journal_files(&database.path).contains(&x.path))
+                    .map(|x| x.path.clone()),
+            );
+            let group_dir = dir.joined(&i.to_string());
+
+            let mut copied = None;
+            for attempt in 1..=2 {
+                let before: Vec<_> = members.iter().map(stamp).collect();
+                let mut copies = HashMap::new();
+                for member in &members {
+                    let Some(leaf) = member.leaf() else {
+                        continue;
+                    };
+                    let copy = group_dir.joined(&leaf);
+                    if member.copy_to_path(game, &copy).is_ok() {
+                        copies.insert(member.clone(), copy);
+                    }
+                }
+                let after: Vec<_> = members.iter().map(stamp).collect();
+
+                if before == after && copies.len() == members.len() {
+                    copied = Some(copies);
+                    break;
+                }
+                log::warn!(
+                    "[{game}] SQLite database changed while copying (attempt {attempt}): {}",
+                    database.path.raw()
+                );
+            }
+
+            match copied {
+                Some(copies) => snapshot.sources.extend(copies),
+                None => snapshot.unstable.extend(members),
+            }
+        }
+
+        snapshot
+    }
+
+    /// Where to read a file's content from for the backup.
+    pub fn source<'a>(&'a self, path: &'a StrictPath) -> &'a StrictPath {
+        self.sources.get(path).unwrap_or(path)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = std::fs::remove_dir_all(dir.interpret());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::repo;
+
+    #[test]
+    fn can_recognize_journal_files() {
+        assert!(is_journal(&StrictPath::new("/save.db-wal".to_string())));
+        assert!(is_journal(&StrictPath::new("/save.db-shm".to_string())));
+        assert!(!is_journal(&StrictPath::new("/save.db".to_string())));
+    }
+
+    #[test]
+    fn plain_files_are_not_databases() {
+        assert!(!is_database(&StrictPath::new(format!(
+            "{}/tests/root1/game1/subdir/file2.txt",
+            repo()
+        ))));
+        assert!(!is_database(&StrictPath::new(format!(
+            "{}/tests/nonexistent.db",
+            repo()
+        ))));
+    }
+
+    #[test]
+    fn snapshot_copies_database_with_journal() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-sqlite-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = StrictPath::from_std_path_buf(&dir.join("save.db"));
+        let wal = StrictPath::from_std_path_buf(&dir.join("save.db-wal"));
+        std::fs::write(database.interpret(), [HEADER.as_slice(), b"data"].concat()).unwrap();
+        std::fs::write(wal.interpret(), b"journal").unwrap();
+
+        assert!(is_database(&database));
+        assert_eq!(vec![wal.clone()], journal_files(&database));
+
+        let files = HashSet::from([
+            ScannedFile {
+                path: database.clone(),
+                sqlite_safe_copy: true,
+                junk: false,
+                ..Default::default()
+            },
+            ScannedFile {
+                path: wal.clone(),
+                sqlite_safe_copy: true,
+                junk: false,
+                ..Default::default()
+            },
+        ]);
+        let snapshot = Snapshot::take("game", &files);
+        assert!(snapshot.unstable.is_empty());
+        assert_ne!(&database, snapshot.source(&database));
+        assert_eq!(Some("journal".to_string()), snapshot.source(&wal).read());
+
+        let snapshot_dir = snapshot.dir.clone().unwrap();
+        drop(snapshot);
+        assert!(!snapshot_dir.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+use crate::scan::layout::{Backup, BackupLayout};
+
+/// How much space one game's backups take up on disk.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameStorage {
+    pub game: String,
+    pub full_backups: usize,
+    pub differential_backups: usize,
+    /// Total size of all of the game's backups.
+    pub bytes: u64,
+    /// Size of the latest backup on its own,
+    /// not including the full backup that a differential backup builds on.
+    pub latest_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_backup: Option<chrono::DateTime<chrono::Utc>>,
+    /// Files listed in the game's mapping that are missing on disk.
+    /// These count as zero bytes.
+    pub missing_files: usize,
+}
+
+impl GameStorage {
+    fn new(game: &str, usage: Vec<(Backup, u64, usize)>) -> Self {
+        let mut storage = Self {
+            game: game.to_string(),
+            ..Default::default()
+        };
+
+        for (backup, bytes, missing) in usage {
+            if backup.full() {
+                storage.full_backups += 1;
+            } else {
+                storage.differential_backups += 1;
+            }
+            storage.bytes += bytes;
+            storage.missing_files += missing;
+
+            if storage.last_backup.is_none_or(|last| *backup.when() >= last) {
+                storage.last_backup = Some(*backup.when());
+                storage.latest_bytes = bytes;
+            }
+        }
+
+        storage
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageTotals {
+    pub games: usize,
+    pub full_backups: usize,
+    pub differential_backups: usize,
+    pub bytes: u64,
+    pub latest_bytes: u64,
+    pub missing_files: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub games: Vec<GameStorage>,
+    pub total: StorageTotals,
+}
+
+impl StorageReport {
+    /// Measure every game's backups, sorted by name.
+    pub fn analyze(layout: &BackupLayout) -> Self {
+        let mut games = layout.restorable_games();
+        games.sort();
+
+        Self::from_games(
+            games
+                .iter()
+                .filter_map(|name| Some(GameStorage::new(name, layout.try_game_layout(name)?.disk_usage())))
+                .collect(),
+        )
+    }
+
+    fn from_games(games: Vec<GameStorage>) -> Self {
+        let mut total = StorageTotals {
+            games: games.len(),
+            ..Default::default()
+        };
+        for game in &games {
+            total.full_backups += game.full_backups;
+            total.differential_backups += game.differential_backups;
+            total.bytes += game.bytes;
+            total.latest_bytes += game.latest_bytes;
+            total.missing_files += game.missing_files;
+        }
+
+        Self { games, total }
+    }
+
+    /// Biggest first, then by name.
+    pub fn sort_by_size(&mut self) {
+        self.games
+            .sort_by(|x, y| y.bytes.cmp(&x.bytes).then_with(|| x.game.cmp(&y.game)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        scan::layout::{DifferentialBackup, FullBackup},
+        testing::s,
+    };
+
+    fn when(day: u32) -> chrono::DateTime<chrono::Utc> {
+        use chrono::TimeZone;
+        chrono::Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()
+    }
+
+    fn full(day: u32) -> Backup {
+        Backup::Full(FullBackup {
+            name: format!("backup-{day}"),
+            when: when(day),
+            ..Default::default()
+        })
+    }
+
+    fn diff(day: u32) -> Backup {
+        Backup::Differential(DifferentialBackup {
+            name: format!("backup-{day}"),
+            when: when(day),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn can_summarize_one_game() {
+        assert_eq!(
+            GameStorage {
+                game: s("game1"),
+                full_backups: 2,
+                differential_backups: 1,
+                bytes: 175,
+                latest_bytes: 25,
+                last_backup: Some(when(3)),
+                missing_files: 1,
+            },
+            GameStorage::new("game1", vec![(full(1), 100, 0), (diff(2), 50, 1), (full(3), 25, 0)])
+        );
+    }
+
+    #[test]
+    fn can_total_and_sort_by_size() {
+        let game = |name: &str, bytes| GameStorage {
+            game: s(name),
+            full_backups: 1,
+            bytes,
+            latest_bytes: bytes,
+            missing_files: 1,
+            ..Default::default()
+        };
+
+        let mut report = StorageReport::from_games(vec![game("a", 10), game("b", 30), game("c", 10)]);
+        report.sort_by_size();
+
+        assert_eq!(
+            StorageReport {
+                games: vec![game("b", 30), game("a", 10), game("c", 10)],
+                total: StorageTotals {
+                    games: 3,
+                    full_backups: 3,
+                    differential_backups: 0,
+                    bytes: 50,
+                    latest_bytes: 50,
+                    missing_files: 3,
+                },
+            },
+            report
+        );
+    }
+}
@@ -1,10 +1,13 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use regex::Regex;
 
 use crate::{
-    resource::{config::Config, manifest::Manifest},
+    resource::{
+        config::Config,
+        manifest::{GameSource, Manifest, StoreId},
+    },
     scan::layout::BackupLayout,
 };
 
@@ -19,6 +22,13 @@ static RE_YEAR_SUFFIX: Lazy<Regex> = Lazy::new(|| Regex::new(r" \(\d+\)$").unwra
 static RE_SYMBOLS: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[™®©:-]"#).unwrap());
 static RE_SPACES: Lazy<Regex> = Lazy::new(|| Regex::new(r#" {2,}"#).unwrap());
 
+/// How many similar titles to suggest for a game that wasn't recognized.
+const MAX_SUGGESTIONS: usize = 3;
+/// Default cutoff for `find --fuzzy`, as a similarity from 0 to 1.
+pub const DEFAULT_FUZZY_THRESHOLD: f64 = 0.6;
+/// Default number of results for `find --fuzzy`.
+pub const DEFAULT_FUZZY_LIMIT: usize = 10;
+
 pub fn normalize_title(title: &str) -> String {
     let normalized = title.to_lowercase();
     let normalized = RE_YEAR_SUFFIX.replace_all(&normalized, "");
@@ -30,6 +40,94 @@ pub fn normalize_title(title: &str) -> String {
     normalized.trim().to_string()
 }
 
+/// A looser normalization for fuzzy searches,
+/// which only keeps letters, digits, and single spaces between words.
+/// Trademark symbols and apostrophes are dropped, while other punctuation separates words.
+fn normalize_fuzzy(title: &str) -> String {
+    let mut normalized = String::new();
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            normalized.push(c);
+        } else if c.is_whitespace() || !matches!(c, '™' | '®' | '©' | '\'' | '’') {
+            normalized.push(' ');
+        }
+    }
+    normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// How many single-character edits it takes to turn one string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous: Vec<_> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How similar two strings are, from 0 (nothing in common) to 1 (identical),
+/// rounded to three decimal places.
+fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+    let score = 1.0 - edit_distance(a, b) as f64 / longest as f64;
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Which name of a game matched a fuzzy search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FuzzySource {
+    Title,
+    Alias,
+    CustomGame,
+    /// The manifest does not include store names,
+    /// but install folders usually match the name used by Steam.
+    InstallDir,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzyMatch {
+    /// The primary title, even if the match was for an alias.
+    pub title: String,
+    pub score: f64,
+    pub matched_via: FuzzySource,
+    /// The name that was compared against the search.
+    pub matched_name: String,
+}
+
+/// A requested title that didn't match any game, along with similar titles that may have been intended.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+pub struct UnknownGame {
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+impl UnknownGame {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            suggestions: vec![],
+        }
+    }
+}
+
+/// Why a store ID couldn't be resolved to a single game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoreIdProblem {
+    /// No game has the ID, but these games have similar IDs.
+    Unknown { suggestions: Vec<String> },
+    /// Several games have the ID, and none of them are more relevant than the others.
+    Ambiguous { games: Vec<String> },
+}
+
 #[derive(Default)]
 pub struct TitleFinder {
     all_games: HashSet<String>,
@@ -38,7 +136,11 @@ pub struct TitleFinder {
     can_do_both: HashSet<String>,
     steam_ids: HashMap<u32, String>,
     gog_ids: HashMap<u64, String>,
-    normalized: HashMap<String, String>,
+    /// Unlike `steam_ids` and `gog_ids`, this includes extra IDs and any duplicates.
+    store_ids: HashMap<StoreId, BTreeMap<String, bool>>,
+    /// Only built when a normalized lookup is first needed,
+    /// since most runs look up games by their exact title.
+    normalized: OnceCell<HashMap<String, String>>,
     aliases: HashMap<String, String>,
 }
 
@@ -50,10 +152,7 @@ impl TitleFinder {
         let can_do_both: HashSet<_> = can_backup.intersection(&can_restore).cloned().collect();
         let steam_ids = manifest.map_steam_ids_to_names();
         let gog_ids = manifest.map_gog_ids_to_names();
-        let normalized: HashMap<_, _> = all_games
-            .iter()
-            .map(|title| (normalize_title(title), title.to_owned()))
-            .collect();
+        let store_ids = manifest.map_store_ids_to_names();
         let aliases = manifest.aliases();
 
         Self {
@@ -63,11 +162,21 @@ impl TitleFinder {
             can_do_both,
             steam_ids,
             gog_ids,
-            normalized,
+            store_ids,
+            normalized: OnceCell::new(),
             aliases,
         }
     }
 
+    fn normalized(&self) -> &HashMap<String, String> {
+        self.normalized.get_or_init(|| {
+            self.all_games
+                .iter()
+                .map(|title| (normalize_title(title), title.to_owned()))
+                .collect()
+        })
+    }
+
     fn eligible(&self, game: &str, backup: bool, restore: bool) -> bool {
         let can_backup = self.can_backup.contains(game);
         let can_restore = self.can_restore.contains(game);
@@ -83,6 +192,181 @@ impl TitleFinder {
         }
     }
 
+    /// Find the titles that are closest to one that wasn't recognized,
+    /// allowing roughly one typo for every few characters.
+    /// Aliases are resolved to their primary title.
+    pub fn suggest(&self, name: &str, backup: bool, restore: bool) -> Vec<String> {
+        let wanted = normalize_title(name);
+        let max_distance = (wanted.chars().count() / 4).max(1);
+
+        let candidates: Vec<_> = self
+            .normalized()
+            .iter()
+            .filter(|(_, title)| self.eligible(title, backup, restore))
+            .filter_map(|(normalized, title)| {
+                let distance = edit_distance(&wanted, normalized);
+                (distance <= max_distance).then_some((distance, title))
+            })
+            .collect();
+
+        self.closest(candidates)
+    }
+
+    /// Pick the titles with the lowest distances, resolving aliases to their primary title.
+    fn closest(&self, mut candidates: Vec<(usize, &String)>) -> Vec<String> {
+        candidates.sort();
+
+        let mut suggestions = vec![];
+        for (_, title) in candidates {
+            let title = self.aliases.get(title).unwrap_or(title);
+            if !suggestions.contains(title) {
+                suggestions.push(title.clone());
+            }
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        suggestions
+    }
+
+    /// Resolve a store ID to a single game.
+    /// When several games have the ID, a game whose primary ID it is
+    /// takes precedence over games that only list it as an extra ID.
+    pub fn find_by_store_id(&self, id: &StoreId, backup: bool, restore: bool) -> Result<String, StoreIdProblem> {
+        let owners: Vec<_> = self
+            .store_ids
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter(|(title, _)| self.eligible(title, backup, restore))
+            .collect();
+
+        let primary: Vec<_> = owners.iter().filter(|(_, primary)| **primary).collect();
+        let candidates: Vec<_> = if primary.is_empty() {
+            owners.iter().map(|(title, _)| (*title).clone()).collect()
+        } else {
+            primary.iter().map(|(title, _)| (*title).clone()).collect()
+        };
+
+        match candidates.len() {
+            0 => Err(StoreIdProblem::Unknown {
+                suggestions: self.suggest_by_store_id(id, backup, restore),
+            }),
+            1 => Ok(candidates[0].clone()),
+            _ => Err(StoreIdProblem::Ambiguous { games: candidates }),
+        }
+    }
+
+    /// Find the games whose IDs for the same store are closest to one that wasn't recognized,
+    /// using the same typo allowance as for titles.
+    fn suggest_by_store_id(&self, id: &StoreId, backup: bool, restore: bool) -> Vec<String> {
+        let wanted = id.value();
+        let max_distance = (wanted.chars().count() / 4).max(1);
+
+        let candidates: Vec<_> = self
+            .store_ids
+            .iter()
+            .filter(|(other, _)| other.same_store(id))
+            .filter_map(|(other, titles)| {
+                let distance = edit_distance(&wanted, &other.value());
+                (distance <= max_distance).then_some((distance, titles))
+            })
+            .flat_map(|(distance, titles)| {
+                titles
+                    .keys()
+                    .filter(|title| self.eligible(title, backup, restore))
+                    .map(move |title| (distance, title))
+            })
+            .collect();
+
+        self.closest(candidates)
+    }
+
+    /// Rank games by how closely any of their names resemble any of the searches,
+    /// best first, keeping only each game's best name.
+    /// The manifest provides the install folder names and which games are custom.
+    pub fn fuzzy_find(
+        &self,
+        manifest: &Manifest,
+        names: &[String],
+        backup: bool,
+        restore: bool,
+        threshold: f64,
+        limit: usize,
+    ) -> Vec<FuzzyMatch> {
+        let wanted: Vec<_> = names.iter().map(|x| normalize_fuzzy(x)).collect();
+
+        let mut candidates = vec![];
+        for title in &self.all_games {
+            match self.aliases.get(title) {
+                Some(target) => candidates.push((target, FuzzySource::Alias, title)),
+                None => {
+                    let custom = manifest.0.get(title).is_some_and(|x| x.source == GameSource::Custom);
+                    let source = if custom {
+                        FuzzySource::CustomGame
+                    } else {
+                        FuzzySource::Title
+                    };
+                    candidates.push((title, source, title));
+                }
+            }
+        }
+        for (title, game) in &manifest.0 {
+            if game.alias.is_some() {
+                continue;
+            }
+            for folder in game.install_dir.iter().flat_map(|x| x.keys()) {
+                candidates.push((title, FuzzySource::InstallDir, folder));
+            }
+        }
+
+        let mut best = HashMap::<&String, FuzzyMatch>::new();
+        for (title, source, candidate) in candidates {
+            if !self.eligible(title, backup, restore) {
+                continue;
+            }
+
+            let candidate_normalized = normalize_fuzzy(candidate);
+            let score = wanted
+                .iter()
+                .map(|x| similarity(x, &candidate_normalized))
+                .fold(0.0, f64::max);
+            if score < threshold {
+                continue;
+            }
+
+            let better = best.get(title).is_none_or(|existing| {
+                (score, std::cmp::Reverse(source)) > (existing.score, std::cmp::Reverse(existing.matched_via))
+            });
+            if better {
+                best.insert(
+                    title,
+                    FuzzyMatch {
+                        title: title.clone(),
+                        score,
+                        matched_via: source,
+                        matched_name: candidate.clone(),
+                    },
+                );
+            }
+        }
+
+        let mut matches: Vec<_> = best.into_values().collect();
+        matches.sort_by(|x, y| y.score.total_cmp(&x.score).then_with(|| x.title.cmp(&y.title)));
+        matches.truncate(limit);
+        matches
+    }
+
+    pub fn unknown_games(&self, names: Vec<String>, backup: bool, restore: bool) -> Vec<UnknownGame> {
+        names
+            .into_iter()
+            .map(|name| UnknownGame {
+                suggestions: self.suggest(&name, backup, restore),
+                name,
+            })
+            .collect()
+    }
+
     pub fn find_one(
         &self,
         names: &[String],
@@ -133,7 +417,7 @@ impl TitleFinder {
     /// from `self.all_games` whose name is equal to any of the given `names`
     /// and which is eligible according to the `backup` and `restore`
     /// parameters.  If `normalized` is set, it additionally tries to look up
-    /// the game in `self.normalized.get(&normalize_title(name))` (also filters
+    /// the game in `self.normalized().get(&normalize_title(name))` (also filters
     /// for eligible).
     ///
     /// * _multi mode_: if none of the parameters `names`, `steam_id` or
@@ -186,7 +470,7 @@ impl TitleFinder {
 
                 if normalized {
                     for name in names {
-                        if let Some(found) = self.normalized.get(&normalize_title(name)) {
+                        if let Some(found) = self.normalized().get(&normalize_title(name)) {
                             if self.eligible(found, backup, restore) {
                                 output.insert((*found).to_owned());
                                 break 'outer;
@@ -255,6 +539,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::testing::s;
 
     #[test]
     fn can_normalize_title() {
@@ -284,4 +569,153 @@ mod tests {
         // spaces
         assert_eq!("foo bar", normalize_title("  Foo  Bar  "));
     }
+
+    #[test]
+    fn can_measure_edit_distance() {
+        assert_eq!(0, edit_distance("knight", "knight"));
+        assert_eq!(1, edit_distance("knigt", "knight"));
+        assert_eq!(2, edit_distance("kinght", "knight"));
+        assert_eq!(6, edit_distance("", "knight"));
+    }
+
+    #[test]
+    fn can_suggest_similar_titles() {
+        let finder = TitleFinder {
+            all_games: ["Hollow Knight", "Hollow Knight: Silksong", "Knights", "HK", "Celeste"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            aliases: HashMap::from([("HK".to_string(), "Hollow Knight".to_string())]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec!["Hollow Knight".to_string()],
+            finder.suggest("Hollow Knigt", false, false)
+        );
+        assert_eq!(vec!["Hollow Knight".to_string()], finder.suggest("HJ", false, false));
+        assert_eq!(Vec::<String>::new(), finder.suggest("Terraria", false, false));
+    }
+
+    #[test]
+    fn can_find_by_store_id() {
+        let finder = TitleFinder {
+            all_games: ["game1", "game2", "game3"].into_iter().map(String::from).collect(),
+            store_ids: HashMap::from([
+                (
+                    StoreId::Steam(100),
+                    BTreeMap::from([(s("game1"), true), (s("game2"), false)]),
+                ),
+                (
+                    StoreId::Steam(200),
+                    BTreeMap::from([(s("game2"), false), (s("game3"), false)]),
+                ),
+                (StoreId::Lutris(s("game-three")), BTreeMap::from([(s("game3"), true)])),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Ok(s("game1")),
+            finder.find_by_store_id(&StoreId::Steam(100), false, false)
+        );
+        assert_eq!(
+            Ok(s("game3")),
+            finder.find_by_store_id(&StoreId::Lutris(s("game-three")), false, false)
+        );
+        assert_eq!(
+            Err(StoreIdProblem::Ambiguous {
+                games: vec![s("game2"), s("game3")]
+            }),
+            finder.find_by_store_id(&StoreId::Steam(200), false, false)
+        );
+        assert_eq!(
+            Err(StoreIdProblem::Unknown {
+                suggestions: vec![s("game1"), s("game2")]
+            }),
+            finder.find_by_store_id(&StoreId::Steam(101), false, false)
+        );
+        assert_eq!(
+            Err(StoreIdProblem::Unknown { suggestions: vec![] }),
+            finder.find_by_store_id(&StoreId::Gog(100), false, false)
+        );
+    }
+
+    #[test]
+    fn can_normalize_title_for_fuzzy_search() {
+        assert_eq!("foo bar", normalize_fuzzy("Foo™ Bar®"));
+        assert_eq!("foo bar 2", normalize_fuzzy("FOO: Bar - 2"));
+        assert_eq!("foos bar", normalize_fuzzy("Foo's  Bar!"));
+    }
+
+    #[test]
+    fn can_fuzzy_find_titles() {
+        use std::collections::BTreeMap;
+
+        use crate::resource::manifest::{Game, GameInstallDirEntry};
+
+        let mut manifest = Manifest::default();
+        manifest.0.insert(
+            "Hollow Knight".to_string(),
+            Game {
+                install_dir: Some(BTreeMap::from([("HollowKnight".to_string(), GameInstallDirEntry {})])),
+                ..Default::default()
+            },
+        );
+        manifest
+            .0
+            .insert("Hollow Knight: Silksong".to_string(), Game::default());
+        manifest.0.insert(
+            "HK".to_string(),
+            Game {
+                alias: Some("Hollow Knight".to_string()),
+                ..Default::default()
+            },
+        );
+        manifest.0.insert(
+            "Hollow Night".to_string(),
+            Game {
+                source: GameSource::Custom,
+                ..Default::default()
+            },
+        );
+        manifest.0.insert("Celeste".to_string(), Game::default());
+        let finder = TitleFinder {
+            all_games: manifest.0.keys().cloned().collect(),
+            aliases: manifest.aliases(),
+            ..Default::default()
+        };
+
+        let found = finder.fuzzy_find(&manifest, &[s("hollow nigt™")], false, false, 0.6, 10);
+        assert_eq!(
+            vec![
+                ("Hollow Night", 0.917, FuzzySource::CustomGame),
+                ("Hollow Knight", 0.846, FuzzySource::Title),
+            ],
+            found
+                .iter()
+                .map(|x| (x.title.as_str(), x.score, x.matched_via))
+                .collect::<Vec<_>>()
+        );
+
+        let found = finder.fuzzy_find(&manifest, &[s("hk")], false, false, 0.6, 10);
+        assert_eq!(
+            vec![FuzzyMatch {
+                title: "Hollow Knight".to_string(),
+                score: 1.0,
+                matched_via: FuzzySource::Alias,
+                matched_name: "HK".to_string(),
+            }],
+            found
+        );
+
+        let found = finder.fuzzy_find(&manifest, &[s("hollowknight")], false, false, 0.6, 1);
+        assert_eq!(1, found.len());
+        assert_eq!(FuzzySource::InstallDir, found[0].matched_via);
+        assert_eq!(1.0, found[0].score);
+
+        assert!(finder
+            .fuzzy_find(&manifest, &[s("hollow nigt")], false, false, 0.95, 10)
+            .is_empty());
+    }
 }
@@ -0,0 +1,42 @@
+use crate::path::StrictPath;
+
+/// The size and modification time of a file,
+/// which we compare before and after copying it to tell if something wrote to it in the meantime.
+pub fn stamp(path: &StrictPath) -> Option<(u64, std::time::SystemTime)> {
+    let metadata = path.metadata().ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Whether a file may no longer have the content that we hashed during the scan,
+/// either because it changed before we copied it or while we were copying it.
+/// When this is true, the copy should be hashed again to find out what we actually stored.
+pub fn may_have_changed(
+    scanned_size: u64,
+    before: Option<(u64, std::time::SystemTime)>,
+    after: Option<(u64, std::time::SystemTime)>,
+) -> bool {
+    match (before, after) {
+        (Some(before), Some(after)) => before != after || after.0 != scanned_size,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    #[test]
+    fn detects_changes_during_and_before_copy() {
+        let early = SystemTime::UNIX_EPOCH;
+        let late = early + Duration::from_secs(1);
+
+        assert!(!may_have_changed(1, Some((1, early)), Some((1, early))));
+        assert!(may_have_changed(1, Some((1, early)), Some((1, late))));
+        assert!(may_have_changed(1, Some((1, early)), Some((2, early))));
+        assert!(may_have_changed(1, Some((2, early)), Some((2, early))));
+        assert!(may_have_changed(1, None, Some((1, early))));
+        assert!(may_have_changed(1, Some((1, early)), None));
+    }
+}
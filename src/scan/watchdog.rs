@@ -0,0 +1,140 @@
+use std::{
+    collections::BTreeSet,
+    sync::{mpsc, Mutex},
+    time::Duration,
+};
+
+use crate::path::StrictPath;
+
+/// Guards against file system operations that never return, like on a dead network mount.
+/// The blocked operation is left running on its own thread, since there's no way to cancel it,
+/// but we stop waiting for it and avoid touching that location again for the rest of the run.
+/// See `scan.ioTimeout`.
+#[derive(Debug, Default)]
+pub struct IoWatchdog {
+    timeout: Option<Duration>,
+    /// Rendered paths that timed out. Anything inside of them is skipped without checking.
+    timed_out: Mutex<BTreeSet<String>>,
+}
+
+impl IoWatchdog {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            timed_out: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Check that `path` responds within the timeout.
+    /// If it doesn't, then `abandon` (usually the root containing `path`) is skipped from now on.
+    pub fn responds(&self, path: &StrictPath, abandon: &StrictPath) -> bool {
+        let probed = path.as_std_path_buf();
+        self.check(path, abandon, move || {
+            let _ = probed.metadata();
+        })
+    }
+
+    fn is_abandoned(&self, path: &StrictPath) -> bool {
+        let rendered = path.render();
+        self.timed_out.lock().unwrap().iter().any(|x| {
+            let x = StrictPath::new(x.clone());
+            x.render() == rendered || x.is_prefix_of(path)
+        })
+    }
+
+    fn check(&self, path: &StrictPath, abandon: &StrictPath, operation: impl FnOnce() + Send + 'static) -> bool {
+        let Some(timeout) = self.timeout else {
+            return true;
+        };
+
+        if self.is_abandoned(path) {
+            return false;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            operation();
+            let _ = sender.send(());
+        });
+
+        match receiver.recv_timeout(timeout) {
+            Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => true,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::warn!(
+                    "Timed out after {:?} while accessing {}; skipping {}",
+                    timeout,
+                    path.raw(),
+                    abandon.raw()
+                );
+                self.timed_out.lock().unwrap().insert(abandon.render());
+                false
+            }
+        }
+    }
+
+    /// Every location that has timed out so far.
+    pub fn timed_out_paths(&self) -> Vec<StrictPath> {
+        self.timed_out
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|x| StrictPath::new(x.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::testing::s;
+
+    fn watchdog() -> IoWatchdog {
+        IoWatchdog::new(Some(Duration::from_millis(50)))
+    }
+
+    #[test]
+    fn allows_everything_without_timeout() {
+        let watchdog = IoWatchdog::default();
+        let path = StrictPath::new(s("/mnt/dead/game"));
+        assert!(watchdog.check(&path, &path, || std::thread::sleep(Duration::from_millis(100))));
+        assert_eq!(Vec::<StrictPath>::new(), watchdog.timed_out_paths());
+    }
+
+    #[test]
+    fn allows_operations_that_finish_in_time() {
+        let watchdog = watchdog();
+        let path = StrictPath::new(s("/mnt/live/game"));
+        assert!(watchdog.check(&path, &path, || {}));
+        assert_eq!(Vec::<StrictPath>::new(), watchdog.timed_out_paths());
+    }
+
+    #[test]
+    fn abandons_root_after_first_timeout() {
+        let watchdog = watchdog();
+        let root = StrictPath::new(s("/mnt/dead"));
+        let attempts = Arc::new(AtomicUsize::new(0));
+
+        for game in ["/mnt/dead/game1", "/mnt/dead/game2"] {
+            let attempts = attempts.clone();
+            assert!(!watchdog.check(&StrictPath::new(s(game)), &root, move || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(500));
+            }));
+        }
+
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+        assert_eq!(vec![root], watchdog.timed_out_paths());
+        assert!(watchdog.check(
+            &StrictPath::new(s("/mnt/deadline/game")),
+            &StrictPath::new(s("/mnt")),
+            || {}
+        ));
+    }
+}
@@ -0,0 +1,323 @@
+//! Each Wine prefix has a profile folder per user under `drive_c/users`.
+//! Current versions of Proton always use `steamuser`, but older versions (and plain Wine) use the real user name,
+//! so the same save may move from one of these folders to another when the game's compatibility tool changes.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{
+    path::StrictPath,
+    resource::config::{PreferWineUser, RedirectConfig, RedirectKind},
+    scan::PreviousFiles,
+};
+
+static WINE_USER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+/(?i:drive_c/users))/([^/]+)(/.+)?$").unwrap());
+
+/// The user folder that Proton creates in every prefix.
+const STEAM_USER: &str = "steamuser";
+
+/// Folders under `drive_c/users` that don't belong to a user.
+const SHARED_PROFILES: &[&str] = &["Public"];
+
+/// A path inside of a Wine user folder, like `<prefix>/drive_c/users/<user><rest>`.
+struct WineUserPath {
+    users: String,
+    user: String,
+    rest: String,
+}
+
+impl WineUserPath {
+    fn parse(path: &StrictPath) -> Option<Self> {
+        let path = path.render();
+        let captures = WINE_USER.captures(&path)?;
+        let user = captures[2].to_string();
+        if SHARED_PROFILES.iter().any(|x| x.eq_ignore_ascii_case(&user)) {
+            return None;
+        }
+        Some(Self {
+            users: captures[1].to_string(),
+            user,
+            rest: captures.get(3).map(|x| x.as_str().to_string()).unwrap_or_default(),
+        })
+    }
+
+    /// Wine treats paths case-insensitively.
+    fn users_key(&self) -> String {
+        self.users.to_lowercase()
+    }
+
+    fn folder(&self) -> String {
+        format!("{}/{}", self.users, self.user)
+    }
+
+    fn with_user(&self, user: &str) -> StrictPath {
+        StrictPath::new(format!("{}/{}{}", self.users, user, self.rest))
+    }
+}
+
+/// Whether the two paths are the same file in different user folders of one Wine prefix.
+pub fn is_wine_user_variant(a: &StrictPath, b: &StrictPath) -> bool {
+    match (WineUserPath::parse(a), WineUserPath::parse(b)) {
+        (Some(a), Some(b)) => {
+            a.users_key() == b.users_key()
+                && !a.user.eq_ignore_ascii_case(&b.user)
+                && a.rest.to_lowercase() == b.rest.to_lowercase()
+        }
+        _ => false,
+    }
+}
+
+/// The Wine user folders that appear in the previous backup, grouped by prefix,
+/// so that files in one user folder can be compared with backups from another.
+#[derive(Clone, Debug, Default)]
+pub struct WineUsers {
+    users: HashMap<String, BTreeSet<String>>,
+}
+
+impl WineUsers {
+    pub fn new<'a>(paths: impl Iterator<Item = &'a StrictPath>) -> Self {
+        let mut users = HashMap::<String, BTreeSet<String>>::new();
+        for path in paths.filter_map(WineUserPath::parse) {
+            users.entry(path.users_key()).or_default().insert(path.user);
+        }
+        Self { users }
+    }
+
+    /// If the file isn't in the previous backup under its own user folder,
+    /// then look for it under the prefix's other user folders.
+    /// When found, this returns its hash and the path that it was backed up from.
+    pub fn previous_hash<'a>(
+        &self,
+        previous: &PreviousFiles<'a>,
+        path: &StrictPath,
+    ) -> Option<(&'a String, StrictPath)> {
+        let parsed = WineUserPath::parse(path)?;
+        let users = self.users.get(&parsed.users_key())?;
+
+        users
+            .iter()
+            .filter(|user| !user.eq_ignore_ascii_case(&parsed.user))
+            .find_map(|user| {
+                let old = parsed.with_user(user);
+                previous.hash(&old).map(|hash| (*hash, old))
+            })
+    }
+}
+
+/// User folders that currently exist in a prefix.
+fn existing_users(users: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(StrictPath::new(users.to_string()).as_std_path_buf()) else {
+        return vec![];
+    };
+
+    let mut found: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(|x| x.to_string()))
+        .filter(|name| !SHARED_PROFILES.iter().any(|x| x.eq_ignore_ascii_case(name)))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Pick the user folder to restore into, out of the ones that currently exist.
+/// When the preference doesn't settle it, we keep the original folder if it still exists.
+fn choose_user<'a>(existing: &'a [String], original: &str, prefer: PreferWineUser) -> Option<&'a str> {
+    if let [only] = existing {
+        return Some(only.as_str());
+    }
+
+    let is_original = |x: &&String| x.eq_ignore_ascii_case(original);
+    let preferred: Vec<_> = existing
+        .iter()
+        .filter(|x| match prefer {
+            PreferWineUser::Steamuser => x.eq_ignore_ascii_case(STEAM_USER),
+            PreferWineUser::Username => !x.eq_ignore_ascii_case(STEAM_USER),
+        })
+        .collect();
+
+    if let Some(user) = preferred.iter().copied().find(is_original) {
+        return Some(user.as_str());
+    }
+    if let [only] = preferred.as_slice() {
+        return Some(only.as_str());
+    }
+    existing.iter().find(is_original).map(|x| x.as_str())
+}
+
+/// When restoring files from a Wine user folder,
+/// this redirects them into whichever user folder the prefix currently has.
+/// If the prefix has no user folders yet, then the files are restored as-is.
+pub fn wine_user_redirects<'a>(
+    target_paths: impl Iterator<Item = &'a StrictPath>,
+    prefer: PreferWineUser,
+) -> Vec<RedirectConfig> {
+    let mut folders = BTreeMap::new();
+    for path in target_paths.filter_map(WineUserPath::parse) {
+        folders.insert(path.folder(), path);
+    }
+
+    let mut redirects = vec![];
+    for (folder, path) in folders {
+        let existing = existing_users(&path.users);
+        let Some(user) = choose_user(&existing, &path.user, prefer) else {
+            continue;
+        };
+        if user == path.user {
+            continue;
+        }
+
+        let target = StrictPath::new(format!("{}/{}", path.users, user));
+        log::info!("Redirecting Wine user folder {} to {}", &folder, target.render());
+        redirects.push(RedirectConfig {
+            kind: RedirectKind::Restore,
+            source: StrictPath::new(folder),
+            target,
+        });
+    }
+
+    redirects
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        resource::config::UnicodeNormalization,
+        testing::{repo, s},
+    };
+
+    fn prefix_path(user: &str, rest: &str) -> StrictPath {
+        StrictPath::new(format!(
+            "{}/tests/compatdata/1234/pfx/drive_c/users/{user}{rest}",
+            repo()
+        ))
+    }
+
+    #[test]
+    fn can_detect_wine_user_variants() {
+        let steamuser = StrictPath::new(s("/pfx/drive_c/users/steamuser/Documents/game/save.dat"));
+        let alex = StrictPath::new(s("/pfx/drive_c/users/alex/Documents/game/save.dat"));
+
+        assert!(is_wine_user_variant(&steamuser, &alex));
+        assert!(!is_wine_user_variant(&steamuser, &steamuser));
+        assert!(!is_wine_user_variant(
+            &steamuser,
+            &StrictPath::new(s("/other-pfx/drive_c/users/alex/Documents/game/save.dat"))
+        ));
+        assert!(!is_wine_user_variant(
+            &steamuser,
+            &StrictPath::new(s("/pfx/drive_c/users/alex/Documents/game/other.dat"))
+        ));
+        assert!(!is_wine_user_variant(
+            &steamuser,
+            &StrictPath::new(s("/pfx/drive_c/users/Public/Documents/game/save.dat"))
+        ));
+        assert!(!is_wine_user_variant(
+            &StrictPath::new(s("/home/steamuser/save.dat")),
+            &StrictPath::new(s("/home/alex/save.dat"))
+        ));
+    }
+
+    #[test]
+    fn can_find_previous_hash_in_other_wine_user_folder() {
+        let old = StrictPath::new(s("/pfx/drive_c/users/alex/Documents/game/save.dat"));
+        let unrelated = StrictPath::new(s("/other-pfx/drive_c/users/sam/Documents/game/save.dat"));
+        let hash = s("abc");
+        let previous = PreviousFiles::new(
+            [(&old, &hash), (&unrelated, &hash)].into_iter(),
+            true,
+            UnicodeNormalization::Nfc,
+        );
+        let users = WineUsers::new([&old, &unrelated].into_iter());
+
+        assert_eq!(
+            Some((&hash, old.clone())),
+            users.previous_hash(
+                &previous,
+                &StrictPath::new(s("/pfx/drive_c/users/steamuser/Documents/game/save.dat"))
+            )
+        );
+        assert_eq!(
+            None,
+            users.previous_hash(
+                &previous,
+                &StrictPath::new(s("/pfx/drive_c/users/steamuser/Documents/game/other.dat"))
+            )
+        );
+        assert_eq!(
+            None,
+            users.previous_hash(
+                &previous,
+                &StrictPath::new(s("/third-pfx/drive_c/users/steamuser/Documents/game/save.dat"))
+            )
+        );
+    }
+
+    fn redirects(user: &str, prefer: PreferWineUser) -> Vec<(StrictPath, StrictPath)> {
+        let paths = [prefix_path(user, "/Documents/game/save.dat")];
+        wine_user_redirects(paths.iter(), prefer)
+            .into_iter()
+            .map(|x| (x.source, x.target))
+            .collect()
+    }
+
+    #[test]
+    fn redirects_missing_wine_user_folder_by_preference() {
+        assert_eq!(
+            vec![(prefix_path("sam", ""), prefix_path("steamuser", ""))],
+            redirects("sam", PreferWineUser::Steamuser),
+        );
+        assert_eq!(
+            vec![(prefix_path("sam", ""), prefix_path("alex", ""))],
+            redirects("sam", PreferWineUser::Username),
+        );
+    }
+
+    #[test]
+    fn redirects_existing_wine_user_folder_by_preference() {
+        assert_eq!(
+            vec![(prefix_path("alex", ""), prefix_path("steamuser", ""))],
+            redirects("alex", PreferWineUser::Steamuser),
+        );
+        assert_eq!(
+            Vec::<(StrictPath, StrictPath)>::new(),
+            redirects("alex", PreferWineUser::Username)
+        );
+        assert_eq!(
+            Vec::<(StrictPath, StrictPath)>::new(),
+            redirects("steamuser", PreferWineUser::Steamuser)
+        );
+    }
+
+    #[test]
+    fn redirects_to_only_wine_user_folder() {
+        let paths = [StrictPath::new(format!(
+            "{}/tests/wine-prefix/drive_c/users/steamuser/Documents/game/save.dat",
+            repo()
+        ))];
+        assert_eq!(
+            vec![RedirectConfig {
+                kind: RedirectKind::Restore,
+                source: StrictPath::new(format!("{}/tests/wine-prefix/drive_c/users/steamuser", repo())),
+                target: StrictPath::new(format!("{}/tests/wine-prefix/drive_c/users/anyone", repo())),
+            }],
+            wine_user_redirects(paths.iter(), PreferWineUser::Steamuser),
+        );
+    }
+
+    #[test]
+    fn does_not_redirect_without_wine_user_folders() {
+        let paths = [StrictPath::new(s(
+            "/missing/drive_c/users/steamuser/Documents/game/save.dat",
+        ))];
+        assert_eq!(
+            Vec::<RedirectConfig>::new(),
+            wine_user_redirects(paths.iter(), PreferWineUser::Steamuser)
+        );
+    }
+}
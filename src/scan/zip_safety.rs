@@ -0,0 +1,569 @@
+use std::io::{Read, Seek, Write};
+
+use crate::prelude::AnyError;
+
+/// Deflate can't compress better than about 1032:1, so the limit must be well below that
+/// for it to catch anything. Ordinary save data rarely reaches 100:1.
+pub const DEFAULT_MAX_COMPRESSION_RATIO: u64 = 200;
+
+/// No matter how well it compresses, an archive won't be read if it would expand to more than this.
+pub const DEFAULT_MAX_UNCOMPRESSED_SIZE: u64 = 64 * 1024 * 1024 * 1024;
+
+/// Saves that are mostly zeros (e.g., preallocated or sparse files) compress extremely well,
+/// so the ratio only matters once an entry would expand to something this large.
+pub const MIN_SIZE_FOR_COMPRESSION_RATIO: u64 = 512 * 1024 * 1024;
+
+/// Symbolic links don't hold any content of their own, so their targets are short.
+const MAX_SYMLINK_TARGET: u64 = 4096;
+
+/// How much a zip file may expand when it's read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZipLimits {
+    /// From `restore.maxCompressionRatio`. Zero means there is no limit.
+    pub max_ratio: u64,
+    /// From `restore.maxUncompressedSize`, in bytes. Zero means there is no limit.
+    pub max_size: u64,
+    min_size_for_ratio: u64,
+}
+
+impl Default for ZipLimits {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_COMPRESSION_RATIO, DEFAULT_MAX_UNCOMPRESSED_SIZE)
+    }
+}
+
+impl ZipLimits {
+    pub fn new(max_ratio: u64, max_size: u64) -> Self {
+        Self {
+            max_ratio,
+            max_size,
+            min_size_for_ratio: MIN_SIZE_FOR_COMPRESSION_RATIO,
+        }
+    }
+}
+
+/// An entry in a zip file that we refuse to read,
+/// because the archive was likely crafted to write outside of where it's supposed to
+/// or to exhaust disk space.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum UnsafeZipEntry {
+    /// The entry's name starts at the root of a drive instead of inside of the archive.
+    #[serde(rename_all = "camelCase")]
+    AbsolutePath { entry: String },
+    /// The entry's name uses `..` to leave the archive.
+    #[serde(rename_all = "camelCase")]
+    EscapesRoot { entry: String },
+    /// The entry is a symbolic link to somewhere outside of the archive.
+    #[serde(rename_all = "camelCase")]
+    Symlink { entry: String, target: String },
+    /// The entry would expand far more than normal data does.
+    #[serde(rename_all = "camelCase")]
+    CompressionRatio { entry: String, ratio: u64, limit: u64 },
+    /// Together with the entries before it, the entry would expand far more than normal data does,
+    /// even though each entry is small enough to be allowed on its own.
+    #[serde(rename_all = "camelCase")]
+    TotalCompressionRatio { entry: String, ratio: u64, limit: u64 },
+    /// The entry, or the archive up to and including the entry, would expand beyond the allowed size.
+    #[serde(rename_all = "camelCase")]
+    TooLarge { entry: String, size: u64, limit: u64 },
+    /// The entry expands to more data than its header declares.
+    #[serde(rename_all = "camelCase")]
+    Oversized { entry: String, declared: u64 },
+}
+
+impl std::fmt::Display for UnsafeZipEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AbsolutePath { entry } => write!(f, "absolute path: {entry}"),
+            Self::EscapesRoot { entry } => write!(f, "path leads outside of the archive: {entry}"),
+            Self::Symlink { entry, target } => write!(f, "link leads outside of the archive: {entry} -> {target}"),
+            Self::CompressionRatio { entry, ratio, limit } => {
+                write!(f, "compression ratio of {ratio} exceeds the limit of {limit}: {entry}")
+            }
+            Self::TotalCompressionRatio { entry, ratio, limit } => {
+                write!(
+                    f,
+                    "total compression ratio of {ratio} exceeds the limit of {limit}: {entry}"
+                )
+            }
+            Self::TooLarge { entry, size, limit } => {
+                write!(f, "expanded size of {size} bytes exceeds the limit of {limit}: {entry}")
+            }
+            Self::Oversized { entry, declared } => write!(f, "expands beyond its declared {declared} bytes: {entry}"),
+        }
+    }
+}
+
+impl std::error::Error for UnsafeZipEntry {}
+
+fn is_absolute(path: &str) -> bool {
+    let mut chars = path.chars();
+    let has_drive = match (chars.next(), chars.next()) {
+        (Some(letter), Some(':')) => letter.is_ascii_alphabetic(),
+        _ => false,
+    };
+    has_drive || path.starts_with(['/', '\\'])
+}
+
+/// Whether a relative path stays inside of its root once `.` and `..` are resolved,
+/// starting from `depth` folders below the root.
+fn stays_inside(path: &str, mut depth: usize) -> bool {
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => return false,
+            },
+            _ => depth += 1,
+        }
+    }
+    true
+}
+
+/// Check the name of an entry without reading it.
+pub fn check_entry_name(entry: &str) -> Result<(), UnsafeZipEntry> {
+    if is_absolute(entry) {
+        Err(UnsafeZipEntry::AbsolutePath {
+            entry: entry.to_string(),
+        })
+    } else if !stays_inside(entry, 0) {
+        Err(UnsafeZipEntry::EscapesRoot {
+            entry: entry.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check where a symbolic link entry points, relative to the folder that contains it.
+pub fn check_symlink_target(entry: &str, target: &str) -> Result<(), UnsafeZipEntry> {
+    let parents = entry
+        .split(['/', '\\'])
+        .filter(|x| !x.is_empty())
+        .count()
+        .saturating_sub(1);
+    if is_absolute(target) || !stays_inside(target, parents) {
+        Err(UnsafeZipEntry::Symlink {
+            entry: entry.to_string(),
+            target: target.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check how much an entry would expand, according to its header.
+pub fn check_expansion(entry: &str, size: u64, compressed_size: u64, limits: ZipLimits) -> Result<(), UnsafeZipEntry> {
+    let ratio = size / compressed_size.max(1);
+    if limits.max_size > 0 && size > limits.max_size {
+        Err(UnsafeZipEntry::TooLarge {
+            entry: entry.to_string(),
+            size,
+            limit: limits.max_size,
+        })
+    } else if limits.max_ratio > 0 && size > limits.min_size_for_ratio && ratio > limits.max_ratio {
+        Err(UnsafeZipEntry::CompressionRatio {
+            entry: entry.to_string(),
+            ratio,
+            limit: limits.max_ratio,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Running totals across the entries of an archive,
+/// so that it can't get around `check_expansion` by spreading its content
+/// over many entries that are each just under `MIN_SIZE_FOR_COMPRESSION_RATIO`.
+#[derive(Debug, Default)]
+struct ExpansionTotals {
+    size: u64,
+    compressed_size: u64,
+}
+
+impl ExpansionTotals {
+    fn add(&mut self, entry: &str, size: u64, compressed_size: u64, limits: ZipLimits) -> Result<(), UnsafeZipEntry> {
+        self.size = self.size.saturating_add(size);
+        self.compressed_size = self.compressed_size.saturating_add(compressed_size);
+
+        check_expansion(entry, self.size, self.compressed_size, limits).map_err(|e| match e {
+            UnsafeZipEntry::CompressionRatio { entry, ratio, limit } => {
+                UnsafeZipEntry::TotalCompressionRatio { entry, ratio, limit }
+            }
+            e => e,
+        })
+    }
+}
+
+fn is_symlink(file: &zip::read::ZipFile) -> bool {
+    file.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000)
+}
+
+/// Check an entry before reading it.
+/// This reads the target of a symbolic link, so the entry must be opened again afterward.
+fn check_entry(file: &mut zip::read::ZipFile, limits: ZipLimits) -> Result<(), UnsafeZipEntry> {
+    let entry = file.name().to_string();
+    check_entry_name(&entry)?;
+    check_expansion(&entry, file.size(), file.compressed_size(), limits)?;
+
+    if is_symlink(file) {
+        let mut target = String::new();
+        // If the target can't be read, then it's not a usable link anyway.
+        let _ = file.by_ref().take(MAX_SYMLINK_TARGET).read_to_string(&mut target);
+        check_symlink_target(&entry, &target)?;
+    }
+
+    Ok(())
+}
+
+/// Open an entry by name after making sure that it's safe to read.
+pub fn open_by_name<'a, R: Read + Seek>(
+    archive: &'a mut zip::ZipArchive<R>,
+    name: &str,
+    limits: ZipLimits,
+) -> Result<zip::read::ZipFile<'a>, AnyError> {
+    check_entry(&mut archive.by_name(name)?, limits)?;
+    Ok(archive.by_name(name)?)
+}
+
+/// Open an entry by index after making sure that it's safe to read.
+pub fn open_by_index<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    index: usize,
+    limits: ZipLimits,
+) -> Result<zip::read::ZipFile<'_>, AnyError> {
+    check_entry(&mut archive.by_index(index)?, limits)?;
+    Ok(archive.by_index(index)?)
+}
+
+/// Check every entry of an archive before reading any of them,
+/// so that nothing is restored from an archive that was tampered with.
+/// Entries that can't be opened are left for the caller to handle when it tries to read them.
+pub fn check_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    limits: ZipLimits,
+) -> Result<(), UnsafeZipEntry> {
+    let mut totals = ExpansionTotals::default();
+    for index in 0..archive.len() {
+        let Ok(mut file) = archive.by_index(index) else {
+            continue;
+        };
+        totals.add(file.name(), file.size(), file.compressed_size(), limits)?;
+        check_entry(&mut file, limits)?;
+    }
+    Ok(())
+}
+
+/// Copy an entry's content, but stop if it's larger than its header declares,
+/// since the header is what `check_expansion` relied on.
+pub fn copy_entry(file: &mut zip::read::ZipFile, target: &mut impl Write) -> Result<u64, AnyError> {
+    let declared = file.size();
+    let copied = std::io::copy(&mut file.by_ref().take(declared.saturating_add(1)), target)?;
+    if copied > declared {
+        return Err(Box::new(UnsafeZipEntry::Oversized {
+            entry: file.name().to_string(),
+            declared,
+        }));
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use pretty_assertions::assert_eq;
+    use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+    use super::*;
+    use crate::testing::s;
+
+    fn archive(build: impl FnOnce(&mut ZipWriter<Cursor<Vec<u8>>>)) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        build(&mut writer);
+        let buffer = writer.finish().unwrap();
+        ZipArchive::new(Cursor::new(buffer.into_inner())).unwrap()
+    }
+
+    fn add(writer: &mut ZipWriter<Cursor<Vec<u8>>>, name: &str, content: &[u8], method: CompressionMethod) {
+        writer
+            .start_file(name, FileOptions::default().compression_method(method))
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+
+    fn problem(result: Result<zip::read::ZipFile, AnyError>) -> Option<UnsafeZipEntry> {
+        match result {
+            Ok(_) => None,
+            Err(e) => e.downcast_ref::<UnsafeZipEntry>().cloned(),
+        }
+    }
+
+    #[test]
+    fn accepts_normal_entries() {
+        let mut archive = archive(|writer| {
+            add(writer, "drive-C/Users/me/save.dat", b"foo", CompressionMethod::Deflated);
+            add(
+                writer,
+                "drive-0/home/me/./a/../save.dat",
+                b"bar",
+                CompressionMethod::Stored,
+            );
+        });
+
+        let mut content = vec![];
+        let mut file = open_by_name(&mut archive, "drive-C/Users/me/save.dat", ZipLimits::default()).unwrap();
+        assert_eq!(3, copy_entry(&mut file, &mut content).unwrap());
+        assert_eq!(b"foo".to_vec(), content);
+        drop(file);
+
+        assert_eq!(None, problem(open_by_index(&mut archive, 1, ZipLimits::default())));
+    }
+
+    #[test]
+    fn rejects_entries_that_escape_the_root() {
+        let mut archive = archive(|writer| {
+            add(writer, "drive-C/../../evil.dat", b"foo", CompressionMethod::Stored);
+            add(writer, "../evil.dat", b"foo", CompressionMethod::Stored);
+        });
+
+        assert_eq!(
+            Some(UnsafeZipEntry::EscapesRoot {
+                entry: s("drive-C/../../evil.dat")
+            }),
+            problem(open_by_index(&mut archive, 0, ZipLimits::default()))
+        );
+        assert_eq!(
+            Some(UnsafeZipEntry::EscapesRoot {
+                entry: s("../evil.dat")
+            }),
+            problem(open_by_name(&mut archive, "../evil.dat", ZipLimits::default()))
+        );
+    }
+
+    #[test]
+    fn rejects_absolute_entries() {
+        let mut archive = archive(|writer| {
+            add(writer, "/etc/evil.dat", b"foo", CompressionMethod::Stored);
+            add(writer, "C:/Windows/evil.dat", b"foo", CompressionMethod::Stored);
+            add(writer, r"\\server\share\evil.dat", b"foo", CompressionMethod::Stored);
+        });
+
+        for (index, entry) in ["/etc/evil.dat", "C:/Windows/evil.dat", r"\\server\share\evil.dat"]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(
+                Some(UnsafeZipEntry::AbsolutePath { entry: s(entry) }),
+                problem(open_by_index(&mut archive, index, ZipLimits::default()))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_symlinks_that_escape_the_root() {
+        let mut archive = archive(|writer| {
+            writer
+                .add_symlink("drive-0/home/link-out", "../../../etc/passwd", FileOptions::default())
+                .unwrap();
+            writer
+                .add_symlink("drive-0/home/link-abs", "/etc/passwd", FileOptions::default())
+                .unwrap();
+            writer
+                .add_symlink("drive-0/home/link-in", "../other/save.dat", FileOptions::default())
+                .unwrap();
+        });
+
+        assert_eq!(
+            Some(UnsafeZipEntry::Symlink {
+                entry: s("drive-0/home/link-out"),
+                target: s("../../../etc/passwd"),
+            }),
+            problem(open_by_index(&mut archive, 0, ZipLimits::default()))
+        );
+        assert_eq!(
+            Some(UnsafeZipEntry::Symlink {
+                entry: s("drive-0/home/link-abs"),
+                target: s("/etc/passwd"),
+            }),
+            problem(open_by_index(&mut archive, 1, ZipLimits::default()))
+        );
+        assert_eq!(None, problem(open_by_index(&mut archive, 2, ZipLimits::default())));
+    }
+
+    /// Limits that check the ratio of much smaller entries than usual,
+    /// so that the tests don't need to compress hundreds of megabytes.
+    fn small_limits() -> ZipLimits {
+        ZipLimits {
+            min_size_for_ratio: 1024 * 1024,
+            ..ZipLimits::default()
+        }
+    }
+
+    fn zeros(writer: &mut ZipWriter<Cursor<Vec<u8>>>, name: &str, mebibytes: usize) {
+        writer
+            .start_file(
+                name,
+                FileOptions::default().compression_method(CompressionMethod::Deflated),
+            )
+            .unwrap();
+        for _ in 0..mebibytes {
+            writer.write_all(&[0; 1024 * 1024]).unwrap();
+        }
+    }
+
+    fn ratio(archive: &mut ZipArchive<Cursor<Vec<u8>>>, indices: std::ops::Range<usize>) -> u64 {
+        let (size, compressed_size) = indices.fold((0, 0), |(size, compressed_size), i| {
+            let file = archive.by_index(i).unwrap();
+            (size + file.size(), compressed_size + file.compressed_size())
+        });
+        size / compressed_size
+    }
+
+    #[test]
+    fn rejects_entries_with_an_extreme_compression_ratio() {
+        let mut archive = archive(|writer| {
+            zeros(writer, "bomb.dat", 8);
+        });
+        let ratio = ratio(&mut archive, 0..1);
+        assert!(ratio > DEFAULT_MAX_COMPRESSION_RATIO);
+
+        assert_eq!(
+            Some(UnsafeZipEntry::CompressionRatio {
+                entry: s("bomb.dat"),
+                ratio,
+                limit: DEFAULT_MAX_COMPRESSION_RATIO,
+            }),
+            problem(open_by_index(&mut archive, 0, small_limits()))
+        );
+        assert_eq!(
+            None,
+            problem(open_by_index(
+                &mut archive,
+                0,
+                ZipLimits {
+                    max_ratio: 0,
+                    ..small_limits()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_many_small_entries_with_an_extreme_compression_ratio() {
+        let mut archive = archive(|writer| {
+            for i in 0..4 {
+                zeros(writer, &format!("bomb-{i}.dat"), 1);
+            }
+        });
+        let limits = ZipLimits {
+            min_size_for_ratio: 2 * 1024 * 1024,
+            ..ZipLimits::default()
+        };
+
+        for i in 0..4 {
+            assert_eq!(None, problem(open_by_index(&mut archive, i, limits)));
+        }
+        assert_eq!(
+            Err(UnsafeZipEntry::TotalCompressionRatio {
+                entry: s("bomb-2.dat"),
+                ratio: ratio(&mut archive, 0..3),
+                limit: DEFAULT_MAX_COMPRESSION_RATIO,
+            }),
+            check_archive(&mut archive, limits)
+        );
+    }
+
+    #[test]
+    fn rejects_archives_that_expand_beyond_the_size_limit() {
+        let mut archive = archive(|writer| {
+            for i in 0..3 {
+                add(
+                    writer,
+                    &format!("save-{i}.dat"),
+                    &[b'a'; 1000],
+                    CompressionMethod::Deflated,
+                );
+            }
+        });
+
+        assert_eq!(
+            Some(UnsafeZipEntry::TooLarge {
+                entry: s("save-0.dat"),
+                size: 1000,
+                limit: 999,
+            }),
+            problem(open_by_index(&mut archive, 0, ZipLimits::new(0, 999)))
+        );
+
+        let limits = ZipLimits::new(0, 2500);
+        assert_eq!(None, problem(open_by_index(&mut archive, 2, limits)));
+        assert_eq!(
+            Err(UnsafeZipEntry::TooLarge {
+                entry: s("save-2.dat"),
+                size: 3000,
+                limit: 2500,
+            }),
+            check_archive(&mut archive, limits)
+        );
+        assert_eq!(Ok(()), check_archive(&mut archive, ZipLimits::new(0, 0)));
+    }
+
+    #[test]
+    fn accepts_many_small_entries_with_a_normal_compression_ratio() {
+        let size = MIN_SIZE_FOR_COMPRESSION_RATIO - 1;
+        let mut totals = ExpansionTotals::default();
+        for i in 0..10 {
+            assert_eq!(
+                Ok(()),
+                totals.add(&format!("save-{i}.dat"), size, size / 2, ZipLimits::default())
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_small_entries_with_an_extreme_compression_ratio() {
+        let mut archive = archive(|writer| {
+            add(
+                writer,
+                "sparse.dat",
+                &vec![0; 8 * 1024 * 1024],
+                CompressionMethod::Bzip2,
+            );
+        });
+
+        let mut content = vec![];
+        let mut file = open_by_index(&mut archive, 0, ZipLimits::default()).unwrap();
+        assert!(file.size() / file.compressed_size() > DEFAULT_MAX_COMPRESSION_RATIO);
+        assert_eq!(8 * 1024 * 1024, copy_entry(&mut file, &mut content).unwrap());
+    }
+
+    #[test]
+    fn stops_copying_entries_that_exceed_their_declared_size() {
+        let archive = archive(|writer| {
+            add(writer, "liar.dat", &[b'a'; 1000], CompressionMethod::Deflated);
+        });
+        let mut buffer = archive.into_inner().into_inner();
+
+        // Declare 100 bytes instead of 1,000 in the local and central headers.
+        let central = buffer.windows(4).position(|x| x == b"PK\x01\x02").unwrap();
+        for offset in [22, central + 24] {
+            buffer[offset..offset + 4].copy_from_slice(&100u32.to_le_bytes());
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buffer)).unwrap();
+        let mut file = open_by_index(&mut archive, 0, ZipLimits::default()).unwrap();
+        let mut content = vec![];
+        let e = copy_entry(&mut file, &mut content).unwrap_err();
+        assert_eq!(
+            Some(&UnsafeZipEntry::Oversized {
+                entry: s("liar.dat"),
+                declared: 100,
+            }),
+            e.downcast_ref::<UnsafeZipEntry>()
+        );
+        assert_eq!(101, content.len());
+    }
+}
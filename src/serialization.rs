@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 
+use indexmap::IndexMap;
 use serde::{Serialize, Serializer};
 
 #[allow(dead_code)]
@@ -12,6 +13,37 @@ where
     ordered.serialize(serializer)
 }
 
+/// For a map that keeps insertion order internally, but should be presented sorted by key.
+pub fn ordered_index_map<S, V>(value: &IndexMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    let ordered: BTreeMap<_, _> = value.iter().collect();
+    ordered.serialize(serializer)
+}
+
+/// For a list of pairs that should be presented like a map, sorted by key.
+pub fn ordered_pairs<S, V>(value: &[(String, V)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    let ordered: BTreeMap<_, _> = value.iter().map(|(k, v)| (k, v)).collect();
+    ordered.serialize(serializer)
+}
+
+pub fn ordered_optional_pairs<S, V>(value: &Option<Vec<(String, V)>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    match value {
+        Some(value) => ordered_pairs(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub fn ordered_set<S>(value: &HashSet<String>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -21,10 +53,39 @@ where
     ordered.serialize(serializer)
 }
 
+/// For a map whose keys can't be object keys (e.g., structs), as a list of pairs.
+pub mod map_as_pairs {
+    use std::{collections::HashMap, hash::Hash};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, K, V>(value: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(value)
+    }
+
+    pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Vec::<(K, V)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
 pub fn is_false(v: &bool) -> bool {
     !v
 }
 
+pub fn is_zero(v: &u64) -> bool {
+    *v == 0
+}
+
 pub fn is_empty_set<T>(v: &HashSet<T>) -> bool {
     v.is_empty()
 }
@@ -32,3 +93,27 @@ pub fn is_empty_set<T>(v: &HashSet<T>) -> bool {
 pub const fn default_true() -> bool {
     true
 }
+
+pub const fn default_history_limit() -> usize {
+    crate::history::DEFAULT_LIMIT
+}
+
+pub const fn default_file_list_limit() -> Option<usize> {
+    Some(1000)
+}
+
+pub const fn default_chunk_size() -> u64 {
+    64
+}
+
+pub const fn default_max_compression_ratio() -> u64 {
+    crate::scan::zip_safety::DEFAULT_MAX_COMPRESSION_RATIO
+}
+
+pub const fn default_max_uncompressed_size() -> u64 {
+    crate::scan::zip_safety::DEFAULT_MAX_UNCOMPRESSED_SIZE
+}
+
+pub const fn default_confirm_when_games_exceed() -> usize {
+    1
+}
@@ -0,0 +1,458 @@
+//! Read-only reporting of recent operations, for dashboards and monitoring.
+//! Each CLI or GUI backup/restore records its outcome in `last-operations.json` next to the config file,
+//! and `ludusavi serve` publishes that file over HTTP, so the server can run as a separate process.
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    prelude::{app_dir, Error, FileLock},
+    scan::OperationStatus,
+};
+
+const FILE_NAME: &str = "last-operations.json";
+
+/// Requests only need a short request line and a few headers,
+/// and the server handles one connection at a time, so anything longer is rejected.
+const MAX_LINE: u64 = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+
+/// Distinguishes temporary files from several saves in the same process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    Backup,
+    Restore,
+}
+
+impl OperationKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Backup => "backup",
+            Self::Restore => "restore",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<DateTime<Utc>>,
+    pub finished: DateTime<Utc>,
+    pub failed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<OperationStatus>,
+    /// This is the same as the `errors` field in the CLI's `--api` output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<serde_json::Value>,
+    /// When this kind of operation last finished without failing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+impl OperationRecord {
+    pub fn new(started: Option<DateTime<Utc>>, failed: bool, status: Option<OperationStatus>) -> Self {
+        Self {
+            started,
+            finished: Utc::now(),
+            failed,
+            status,
+            errors: None,
+            last_success: None,
+        }
+    }
+
+    pub fn with_errors(mut self, errors: Option<serde_json::Value>) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LastOperations(pub BTreeMap<OperationKind, OperationRecord>);
+
+impl LastOperations {
+    /// Render the data in the Prometheus text exposition format.
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let mut metric = |name: &str, help: &str, value: &dyn Fn(&OperationRecord) -> Option<f64>| {
+            out += &format!("# HELP ludusavi_{name} {help}\n# TYPE ludusavi_{name} gauge\n");
+            for (kind, record) in &self.0 {
+                if let Some(value) = value(record) {
+                    out += &format!("ludusavi_{name}{{operation=\"{}\"}} {value}\n", kind.label());
+                }
+            }
+        };
+
+        metric("games_total", "Games found in the last operation.", &|x| {
+            x.status.as_ref().map(|x| x.total_games as f64)
+        });
+        metric("games_processed", "Games processed in the last operation.", &|x| {
+            x.status.as_ref().map(|x| x.processed_games as f64)
+        });
+        metric("bytes_total", "Bytes found in the last operation.", &|x| {
+            x.status.as_ref().map(|x| x.total_bytes as f64)
+        });
+        metric("bytes_processed", "Bytes processed in the last operation.", &|x| {
+            x.status.as_ref().map(|x| x.processed_bytes as f64)
+        });
+        metric("failed", "Whether the last operation failed.", &|x| {
+            Some(if x.failed { 1.0 } else { 0.0 })
+        });
+        metric(
+            "last_finished_timestamp_seconds",
+            "When the last operation finished.",
+            &|x| Some(x.finished.timestamp() as f64),
+        );
+        metric(
+            "last_success_timestamp_seconds",
+            "When the last successful operation finished.",
+            &|x| x.last_success.map(|x| x.timestamp() as f64),
+        );
+        metric("last_duration_seconds", "How long the last operation took.", &|x| {
+            x.started
+                .map(|started| (x.finished - started).num_milliseconds() as f64 / 1000.0)
+        });
+
+        out
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LastOperationsFile {
+    dir: PathBuf,
+}
+
+impl Default for LastOperationsFile {
+    fn default() -> Self {
+        Self::new(app_dir())
+    }
+}
+
+impl LastOperationsFile {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn file(&self) -> PathBuf {
+        self.dir.join(FILE_NAME)
+    }
+
+    fn lock_file(&self) -> PathBuf {
+        self.dir.join(format!("{FILE_NAME}.lock"))
+    }
+
+    pub fn load(&self) -> LastOperations {
+        let Ok(content) = std::fs::read_to_string(self.file()) else {
+            return LastOperations::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("Ignoring invalid {FILE_NAME}: {e:?}");
+                LastOperations::default()
+            }
+        }
+    }
+
+    /// Failures are only logged, since this shouldn't affect the operation itself.
+    pub fn record(&self, kind: OperationKind, record: OperationRecord) {
+        if let Err(e) = self.try_record(kind, record) {
+            log::warn!("Unable to record last operation: {e:?}");
+        }
+    }
+
+    fn try_record(&self, kind: OperationKind, mut record: OperationRecord) -> Result<(), std::io::Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        // The GUI and several CLI processes may finish at the same time,
+        // so only one of them can update the file at a time.
+        let _lock = FileLock::acquire(&self.lock_file())?;
+
+        let mut data = self.load();
+        record.last_success = if record.failed {
+            data.0.get(&kind).and_then(|x| x.last_success)
+        } else {
+            Some(record.finished)
+        };
+        data.0.insert(kind, record);
+
+        self.save(&data)
+    }
+
+    fn save(&self, data: &LastOperations) -> Result<(), std::io::Error> {
+        let content = serde_json::to_string_pretty(data).map_err(std::io::Error::other)?;
+
+        // Write to a temporary file first so that the server never sees a partial file.
+        let temp = self.dir.join(format!(
+            "{FILE_NAME}.{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, self.file()).inspect_err(|_| {
+            _ = std::fs::remove_file(&temp);
+        })
+    }
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: &'static str, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::new("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string())
+    }
+}
+
+fn respond(method: &str, path: &str, file: &LastOperationsFile) -> Response {
+    if method != "GET" && method != "HEAD" {
+        return Response::new(
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Only GET is supported\n".to_string(),
+        );
+    }
+
+    match path.split('?').next().unwrap_or_default() {
+        "/status" => Response::new(
+            "200 OK",
+            "application/json",
+            serde_json::to_string_pretty(&file.load()).unwrap_or_default(),
+        ),
+        "/metrics" => Response::new("200 OK", "text/plain; version=0.0.4", file.load().prometheus()),
+        _ => Response::not_found(),
+    }
+}
+
+/// Read one line, but give up once it reaches `MAX_LINE` bytes.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> Result<usize, std::io::Error> {
+    let read = reader.take(MAX_LINE).read_line(line)?;
+    if read as u64 == MAX_LINE && !line.ends_with('\n') {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "request line is too long",
+        ));
+    }
+    Ok(read)
+}
+
+fn handle(stream: TcpStream, file: &LastOperationsFile) -> Result<(), std::io::Error> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    read_line(&mut reader, &mut request_line)?;
+    // We don't need any headers, but we read them so the client sees a clean response.
+    for _ in 0..MAX_HEADERS {
+        let mut header = String::new();
+        if read_line(&mut reader, &mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    log::debug!("Serving request: {method} {path}");
+
+    let response = respond(method, path, file);
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    if method != "HEAD" {
+        stream.write_all(response.body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+/// Serve the recorded data until the process is stopped.
+pub fn serve(bind: &str, file: LastOperationsFile) -> Result<(), Error> {
+    let listener = TcpListener::bind(bind).map_err(|e| Error::CliUnableToServe {
+        address: bind.to_string(),
+        why: e.to_string(),
+    })?;
+    log::info!("Serving on {bind}");
+    eprintln!("http://{bind}/status\nhttp://{bind}/metrics");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle(stream, &file) {
+                    log::warn!("Unable to handle request: {e:?}");
+                }
+            }
+            Err(e) => log::warn!("Unable to accept connection: {e:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn status() -> OperationStatus {
+        OperationStatus {
+            total_games: 3,
+            total_bytes: 300,
+            processed_games: 2,
+            processed_bytes: 200,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_render_prometheus_metrics() {
+        let started = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let data = LastOperations(BTreeMap::from([(
+            OperationKind::Backup,
+            OperationRecord {
+                started: Some(started),
+                finished: started + chrono::Duration::milliseconds(1500),
+                failed: false,
+                status: Some(status()),
+                errors: None,
+                last_success: Some(started + chrono::Duration::milliseconds(1500)),
+            },
+        )]));
+
+        assert_eq!(
+            r#"
+# HELP ludusavi_games_total Games found in the last operation.
+# TYPE ludusavi_games_total gauge
+ludusavi_games_total{operation="backup"} 3
+# HELP ludusavi_games_processed Games processed in the last operation.
+# TYPE ludusavi_games_processed gauge
+ludusavi_games_processed{operation="backup"} 2
+# HELP ludusavi_bytes_total Bytes found in the last operation.
+# TYPE ludusavi_bytes_total gauge
+ludusavi_bytes_total{operation="backup"} 300
+# HELP ludusavi_bytes_processed Bytes processed in the last operation.
+# TYPE ludusavi_bytes_processed gauge
+ludusavi_bytes_processed{operation="backup"} 200
+# HELP ludusavi_failed Whether the last operation failed.
+# TYPE ludusavi_failed gauge
+ludusavi_failed{operation="backup"} 0
+# HELP ludusavi_last_finished_timestamp_seconds When the last operation finished.
+# TYPE ludusavi_last_finished_timestamp_seconds gauge
+ludusavi_last_finished_timestamp_seconds{operation="backup"} 1704164646
+# HELP ludusavi_last_success_timestamp_seconds When the last successful operation finished.
+# TYPE ludusavi_last_success_timestamp_seconds gauge
+ludusavi_last_success_timestamp_seconds{operation="backup"} 1704164646
+# HELP ludusavi_last_duration_seconds How long the last operation took.
+# TYPE ludusavi_last_duration_seconds gauge
+ludusavi_last_duration_seconds{operation="backup"} 1.5
+            "#
+            .trim(),
+            data.prometheus().trim_end(),
+        );
+    }
+
+    #[test]
+    fn keeps_last_success_after_failure() {
+        let dir = std::env::temp_dir().join(format!("ludusavi-test-last-operations-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        let file = LastOperationsFile::new(dir.clone());
+
+        file.record(OperationKind::Backup, OperationRecord::new(None, false, Some(status())));
+        let succeeded = file.load().0[&OperationKind::Backup].finished;
+
+        file.record(OperationKind::Backup, OperationRecord::new(None, true, None));
+        let data = file.load();
+        assert!(data.0[&OperationKind::Backup].failed);
+        assert_eq!(Some(succeeded), data.0[&OperationKind::Backup].last_success);
+        assert!(!data.0.contains_key(&OperationKind::Restore));
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keeps_every_record_when_saving_concurrently() {
+        let dir = std::env::temp_dir().join(format!(
+            "ludusavi-test-last-operations-concurrent-{}",
+            std::process::id()
+        ));
+        _ = std::fs::remove_dir_all(&dir);
+        let file = LastOperationsFile::new(dir.clone());
+
+        std::thread::scope(|scope| {
+            for kind in [OperationKind::Backup, OperationKind::Restore] {
+                let file = &file;
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        file.record(kind, OperationRecord::new(None, false, Some(status())));
+                    }
+                });
+            }
+        });
+
+        let data = file.load();
+        assert!(data.0.contains_key(&OperationKind::Backup));
+        assert!(data.0.contains_key(&OperationKind::Restore));
+        assert_eq!(
+            vec![std::ffi::OsString::from(FILE_NAME)],
+            std::fs::read_dir(&dir)
+                .unwrap()
+                .map(|x| x.unwrap().file_name())
+                .collect::<Vec<_>>()
+        );
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stops_reading_an_endless_line() {
+        let mut line = String::new();
+        let mut reader = BufReader::new(std::io::repeat(b'a'));
+        assert_eq!(
+            std::io::ErrorKind::InvalidData,
+            read_line(&mut reader, &mut line).unwrap_err().kind()
+        );
+        assert_eq!(MAX_LINE as usize, line.len());
+
+        let mut line = String::new();
+        let mut reader = BufReader::new(&b"GET /status HTTP/1.1\r\n"[..]);
+        assert_eq!(22, read_line(&mut reader, &mut line).unwrap());
+    }
+
+    #[test]
+    fn only_allows_reading() {
+        let file = LastOperationsFile::new(std::env::temp_dir().join("ludusavi-test-serve-nonexistent"));
+        assert_eq!("200 OK", respond("GET", "/status", &file).status);
+        assert_eq!("200 OK", respond("GET", "/metrics?x=1", &file).status);
+        assert_eq!("404 Not Found", respond("GET", "/", &file).status);
+        assert_eq!("405 Method Not Allowed", respond("POST", "/status", &file).status);
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use ludusavi::api::{
+    BackupLayout, BackupOptions, Cancellation, Config, Finality, Game, GameFileEntry, Manifest, OperationStepDecision,
+    Progress, RestoreOptions, Roots, ScannedFile, StrictPath,
+};
+
+fn work_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A manifest with one game, whose only save file is `save`.
+fn manifest_with_save(save: &Path) -> Manifest {
+    let mut files = BTreeMap::new();
+    files.insert(save.display().to_string(), GameFileEntry::default());
+
+    let mut manifest = Manifest::default();
+    manifest.0.insert(
+        "Test Game".to_string(),
+        Game {
+            files: Some(files),
+            ..Default::default()
+        },
+    );
+    manifest
+}
+
+#[test]
+fn backs_up_and_restores_a_game_from_outside_the_crate() {
+    let dir = work_dir("api-restore");
+    let save = dir.join("save.txt");
+    std::fs::write(&save, "data").unwrap();
+
+    let manifest = manifest_with_save(&save);
+    let config = Config::default();
+    let layout = BackupLayout::new(StrictPath::from_std_path_buf(&dir.join("backup")), &config);
+    let roots = Roots::new(&manifest, &config, &layout, &[]);
+    let cancel = Cancellation::default();
+
+    let outcomes = ludusavi::api::back_up_games(
+        &manifest,
+        &config,
+        &roots,
+        &layout,
+        &[],
+        Finality::Final,
+        &BackupOptions::default(),
+        &|_| {},
+        &cancel,
+    );
+    assert_eq!(1, outcomes.len());
+    assert!(outcomes[0].backup_info.as_ref().is_some_and(|x| x.successful()));
+
+    std::fs::remove_file(&save).unwrap();
+
+    // Reload the backups, since the layout only knows about the games that had a backup when it was created.
+    let layout = BackupLayout::new(StrictPath::from_std_path_buf(&dir.join("backup")), &config);
+    let events = Mutex::new(vec![]);
+    let outcomes = ludusavi::api::restore_games(
+        &manifest,
+        &config,
+        &layout,
+        &[],
+        Finality::Final,
+        &RestoreOptions::default(),
+        &|progress| {
+            let event = match progress {
+                Progress::Started { game, index, max } => format!("started {game} {index}/{max}"),
+                Progress::Finished { outcome, current, max } => format!("finished {} {current}/{max}", outcome.game),
+            };
+            events.lock().unwrap().push(event);
+        },
+        &cancel,
+    );
+
+    assert_eq!(
+        vec!["started Test Game 1/1", "finished Test Game 1/1"],
+        events.into_inner().unwrap()
+    );
+    assert_eq!(1, outcomes.len());
+    assert_eq!(OperationStepDecision::Processed, outcomes[0].decision);
+    assert!(outcomes[0].backup_info.as_ref().is_some_and(|x| x.successful()));
+
+    let restored: Vec<&ScannedFile> = outcomes[0].scan_info.found_files.iter().collect();
+    assert_eq!(1, restored.len());
+    assert_eq!(4, restored[0].size);
+    assert_eq!("data", std::fs::read_to_string(&save).unwrap());
+}
+
+#[test]
+fn restores_nothing_once_cancelled() {
+    let dir = work_dir("api-restore-cancelled");
+    let config = Config::default();
+    let layout = BackupLayout::new(StrictPath::from_std_path_buf(&dir.join("backup")), &config);
+    let cancel = Cancellation::default();
+    cancel.cancel();
+
+    let outcomes = ludusavi::api::restore_games(
+        &Manifest::default(),
+        &config,
+        &layout,
+        &["Test Game".to_string()],
+        Finality::Preview,
+        &RestoreOptions::default(),
+        &|_| panic!("no progress expected"),
+        &cancel,
+    );
+    assert!(outcomes.is_empty());
+}
@@ -0,0 +1,73 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn config_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(config_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ludusavi"))
+        .arg("--config")
+        .arg(config_dir)
+        .arg("--no-manifest-update")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn backup_preview(config_dir: &Path, flag: &str) -> Output {
+    let target = config_dir.join("backup");
+    run(
+        config_dir,
+        &[
+            "backup",
+            "--preview",
+            "--force",
+            flag,
+            "--path",
+            target.to_str().unwrap(),
+        ],
+    )
+}
+
+/// The first run writes the default config, which is then given a custom game with one save file.
+fn add_custom_game(config_dir: &Path) {
+    assert!(backup_preview(config_dir, "--api").status.success());
+
+    let save = config_dir.join("save.txt");
+    std::fs::write(&save, "data").unwrap();
+
+    let config_file = config_dir.join("config.yaml");
+    let config = std::fs::read_to_string(&config_file).unwrap().replace(
+        "customGames: []",
+        &format!(
+            "customGames:\n  - name: Test Game\n    files:\n      - {}",
+            save.display()
+        ),
+    );
+    std::fs::write(&config_file, config).unwrap();
+}
+
+#[test]
+fn changes_pending_is_not_reported_as_an_error() {
+    let dir = config_dir("changes-pending");
+    add_custom_game(&dir);
+
+    let output = backup_preview(&dir, "--fail-on-change");
+    assert_eq!(Some(2), output.status.code());
+    assert_eq!("", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn nothing_changed_is_not_reported_as_an_error() {
+    let dir = config_dir("nothing-changed");
+
+    let output = backup_preview(&dir, "--if-changed");
+    assert_eq!(Some(3), output.status.code());
+    assert_eq!("", String::from_utf8_lossy(&output.stderr));
+}